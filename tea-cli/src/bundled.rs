@@ -22,14 +22,20 @@ pub fn toolchain_label() -> Option<&'static str> {
     enabled().then_some("bundled-linkkit")
 }
 
-pub fn materialize_runtime_archive(root: &Path) -> Result<PathBuf> {
+pub fn runtime_archive_digest() -> Option<String> {
     if !enabled() {
-        anyhow::bail!("bundled runtime archive is not available in this build");
+        return None;
     }
 
     let mut hasher = Sha256::new();
     hasher.update(RUNTIME_ARCHIVE);
-    let digest = format!("{:x}", hasher.finalize());
+    Some(format!("{:x}", hasher.finalize()))
+}
+
+pub fn materialize_runtime_archive(root: &Path) -> Result<PathBuf> {
+    let Some(digest) = runtime_archive_digest() else {
+        anyhow::bail!("bundled runtime archive is not available in this build");
+    };
     let digest_prefix = &digest[..16];
     let target = target().unwrap_or("host");
 
@@ -1,20 +1,22 @@
 mod bundled;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::env;
 use std::ffi::OsString;
 use std::fs::{self, File};
 use std::io::{self, Cursor, Read};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::SystemTime;
 
 use anyhow::{anyhow, bail, Context, Result};
-use clap::{ArgAction, CommandFactory, Parser, ValueEnum};
+use clap::{ArgAction, CommandFactory, Parser, Subcommand, ValueEnum};
 use dirs_next::home_dir;
 use pathdiff::diff_paths;
 use tea_compiler::{
-    build_reference_manifest, format_source, CompileOptions, Compiler, Diagnostic, DiagnosticLevel,
-    SourceFile, SourceId,
+    build_reference_manifest, format_source, validate_package_name, CompilationUnit,
+    CompileOptions, Compiler, DependencySpec, Diagnostic, DiagnosticLevel, PackageManifest,
+    Project, SourceFile, SourceId, MANIFEST_FILE, VENDOR_DIR,
 };
 
 use tea_compiler::aot::{self, ObjectCompileOptions};
@@ -29,11 +31,19 @@ use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// Size the build cache may grow to before the least recently used binaries are
+/// evicted. Override with `TEA_CACHE_MAX_BYTES`.
+const DEFAULT_CACHE_MAX_BYTES: u64 = 1024 * 1024 * 1024;
+
 const RUN_AFTER_HELP: &str = "\
 Subcommands:
+  tea add <NAME>           Add a path or git dependency to tea.toml.
   tea build <INPUT>        Compile a tea-lang file to a native executable.
+  tea cache <info|clean>   Inspect or clear the build cache.
   tea docs-manifest        Generate the docs reference manifest for the website.
   tea fmt [PATH]...        Format tea-lang sources in place (defaults to current directory).
+  tea init [PATH]          Create a tea.toml project in PATH (defaults to current directory).
+  tea lock                 Rewrite tea.lock from the packages on disk.
   tea test [PATH]...       Discover and run tea-lang test blocks.
 
 See `tea <subcommand> --help` for command-specific options.";
@@ -133,6 +143,14 @@ struct BuildCli {
 
     #[arg(long = "linker-arg", value_name = "ARG")]
     linker_args: Vec<String>,
+
+    /// Link a native library (`-l<NAME>`) for `extern def` bindings; may be repeated.
+    #[arg(long = "link-lib", value_name = "NAME")]
+    link_libs: Vec<String>,
+
+    /// Compile every module into a single object instead of one object per module.
+    #[arg(long, action = ArgAction::SetTrue)]
+    whole_program: bool,
 }
 
 #[derive(Parser)]
@@ -179,6 +197,83 @@ struct TestCli {
     update_snapshots: bool,
 }
 
+#[derive(Parser)]
+#[command(
+    name = "tea cache",
+    version,
+    about = "Inspect or clear the build cache."
+)]
+struct CacheCli {
+    #[command(subcommand)]
+    command: CacheCommand,
+}
+
+#[derive(Subcommand)]
+enum CacheCommand {
+    /// Show the cache location, entry count, and total size.
+    Info,
+    /// Remove every cached binary.
+    Clean,
+}
+
+#[derive(Parser)]
+#[command(
+    name = "tea init",
+    version,
+    about = "Create a tea.toml project manifest."
+)]
+struct InitCli {
+    /// Project directory (defaults to the current directory).
+    #[arg(value_name = "PATH")]
+    path: Option<PathBuf>,
+
+    /// Package name (defaults to the directory name).
+    #[arg(long)]
+    name: Option<String>,
+}
+
+#[derive(Parser)]
+#[command(
+    name = "tea lock",
+    version,
+    about = "Rewrite tea.lock from the packages on disk.",
+    long_about = "Rewrite tea.lock from the packages on disk. Builds fail when a dependency's \
+version, source, vendored commit or sources no longer match tea.lock; run this after \
+updating a dependency on purpose."
+)]
+struct LockCli {}
+
+#[derive(Parser)]
+#[command(
+    name = "tea add",
+    version,
+    about = "Add a dependency to the nearest tea.toml.",
+    long_about = "Add a dependency to the nearest tea.toml. Path dependencies are read in place; \
+git dependencies are cloned into vendor/<NAME> unless a checkout is already there, so \
+a pre-populated vendor directory works offline."
+)]
+struct AddCli {
+    /// Dependency name, used as the first segment of import paths.
+    name: String,
+
+    /// Local directory containing the dependency's tea.toml.
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with = "git",
+        required_unless_present = "git"
+    )]
+    path: Option<String>,
+
+    /// Git repository to vendor the dependency from.
+    #[arg(long, value_name = "URL")]
+    git: Option<String>,
+
+    /// Commit, tag, or branch to pin a git dependency to.
+    #[arg(long, value_name = "REV", requires = "git")]
+    rev: Option<String>,
+}
+
 #[derive(Parser)]
 #[command(
     name = "tea docs-manifest",
@@ -206,6 +301,15 @@ fn main() -> Result<()> {
     if raw.get(1).map(|arg| arg == "build").unwrap_or(false) {
         return handle_build(raw);
     }
+    if raw.get(1).map(|arg| arg == "init").unwrap_or(false) {
+        return handle_init(raw);
+    }
+    if raw.get(1).map(|arg| arg == "add").unwrap_or(false) {
+        return handle_add(raw);
+    }
+    if raw.get(1).map(|arg| arg == "lock").unwrap_or(false) {
+        return handle_lock(raw);
+    }
     if raw
         .get(1)
         .map(|arg| arg == "docs-manifest")
@@ -213,6 +317,9 @@ fn main() -> Result<()> {
     {
         return handle_docs_manifest(raw);
     }
+    if raw.get(1).map(|arg| arg == "cache").unwrap_or(false) {
+        return handle_cache(raw);
+    }
     if raw.get(1).map(|arg| arg == "fmt").unwrap_or(false) {
         return handle_fmt(raw);
     }
@@ -246,6 +353,42 @@ fn handle_fmt(raw: Vec<OsString>) -> Result<()> {
     run_fmt(&cli)
 }
 
+fn handle_cache(raw: Vec<OsString>) -> Result<()> {
+    let mut args = raw.clone();
+    if !args.is_empty() {
+        args.remove(1); // drop the literal "cache"
+    }
+    let cli = CacheCli::parse_from(args);
+    run_cache(&cli)
+}
+
+fn handle_init(raw: Vec<OsString>) -> Result<()> {
+    let mut args = raw.clone();
+    if !args.is_empty() {
+        args.remove(1); // drop the literal "init"
+    }
+    let cli = InitCli::parse_from(args);
+    run_init(&cli)
+}
+
+fn handle_add(raw: Vec<OsString>) -> Result<()> {
+    let mut args = raw.clone();
+    if !args.is_empty() {
+        args.remove(1); // drop the literal "add"
+    }
+    let cli = AddCli::parse_from(args);
+    run_add(&cli)
+}
+
+fn handle_lock(raw: Vec<OsString>) -> Result<()> {
+    let mut args = raw.clone();
+    if !args.is_empty() {
+        args.remove(1); // drop the literal "lock"
+    }
+    LockCli::parse_from(args);
+    run_lock()
+}
+
 fn handle_docs_manifest(raw: Vec<OsString>) -> Result<()> {
     let mut args = raw.clone();
     if !args.is_empty() {
@@ -295,6 +438,204 @@ fn run_docs_manifest(cli: &DocsManifestCli) -> Result<()> {
     Ok(())
 }
 
+fn run_init(cli: &InitCli) -> Result<()> {
+    let root = match &cli.path {
+        Some(path) => path.clone(),
+        None => env::current_dir().context("failed to read current directory")?,
+    };
+    fs::create_dir_all(&root).with_context(|| format!("failed to create {}", root.display()))?;
+    let root = root
+        .canonicalize()
+        .with_context(|| format!("failed to resolve {}", root.display()))?;
+    if root.join(MANIFEST_FILE).exists() {
+        bail!("{} already exists in {}", MANIFEST_FILE, root.display());
+    }
+
+    let name = match &cli.name {
+        Some(name) => name.clone(),
+        None => root
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.to_string())
+            .ok_or_else(|| anyhow!("cannot infer a package name; pass --name"))?,
+    };
+    validate_package_name(&name)?;
+
+    let project = Project {
+        root: root.clone(),
+        manifest: PackageManifest::new(name),
+    };
+    project.save()?;
+    for entry in &project.manifest.package.entry {
+        let entry_path = root.join(entry);
+        if !entry_path.exists() {
+            fs::write(&entry_path, "@println(\"Hello, Tea!\")\n")
+                .with_context(|| format!("failed to write {}", entry_path.display()))?;
+        }
+    }
+    project.write_lockfile()?;
+    println!("Created package '{}' in {}", project.name(), root.display());
+    Ok(())
+}
+
+fn run_add(cli: &AddCli) -> Result<()> {
+    validate_package_name(&cli.name)?;
+    let cwd = env::current_dir().context("failed to read current directory")?;
+    let mut project = Project::discover(&cwd)?.ok_or_else(|| {
+        anyhow!(
+            "no {} found in {} or any parent directory; run `tea init` first",
+            MANIFEST_FILE,
+            cwd.display()
+        )
+    })?;
+    if cli.name == project.name() {
+        bail!("package '{}' cannot depend on itself", cli.name);
+    }
+
+    let mut spec = DependencySpec {
+        path: cli.path.clone(),
+        git: cli.git.clone(),
+        rev: cli.rev.clone(),
+    };
+    if let Some(url) = &cli.git {
+        let checkout = project.root.join(VENDOR_DIR).join(&cli.name);
+        if !checkout.exists() {
+            vendor_git_dependency(url, cli.rev.as_deref(), &checkout)?;
+        }
+        if spec.rev.is_none() {
+            spec.rev = git_head_revision(&checkout);
+        }
+    }
+
+    project.manifest.dependencies.insert(cli.name.clone(), spec);
+    let dependency_root = project.dependency_root(&cli.name)?;
+    if !dependency_root.join(MANIFEST_FILE).is_file() {
+        bail!(
+            "dependency '{}' has no {} in {}",
+            cli.name,
+            MANIFEST_FILE,
+            dependency_root.display()
+        );
+    }
+    let lockfile = project.resolve_lockfile()?;
+    project.save()?;
+    project.write_lockfile()?;
+
+    let version = lockfile
+        .packages
+        .iter()
+        .find(|package| package.name == cli.name)
+        .map(|package| package.version.as_str())
+        .unwrap_or("?");
+    println!("Added {} {}", cli.name, version);
+    Ok(())
+}
+
+fn run_lock() -> Result<()> {
+    let cwd = env::current_dir().context("failed to read current directory")?;
+    let project = Project::discover(&cwd)?.ok_or_else(|| {
+        anyhow!(
+            "no {} found in {} or any parent directory; run `tea init` first",
+            MANIFEST_FILE,
+            cwd.display()
+        )
+    })?;
+    let lockfile = project.write_lockfile()?;
+    println!(
+        "Locked {} package{}",
+        lockfile.packages.len(),
+        if lockfile.packages.len() == 1 {
+            ""
+        } else {
+            "s"
+        }
+    );
+    Ok(())
+}
+
+fn vendor_git_dependency(url: &str, rev: Option<&str>, checkout: &Path) -> Result<()> {
+    if url.starts_with('-') {
+        bail!("git url '{}' must not start with '-'", url);
+    }
+    if let Some(rev) = rev.filter(|rev| rev.starts_with('-')) {
+        bail!("git rev '{}' must not start with '-'", rev);
+    }
+    if let Some(parent) = checkout.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let status = Command::new("git")
+        .args(["clone", "--quiet", "--"])
+        .arg(url)
+        .arg(checkout)
+        .status()
+        .context("failed to run git clone")?;
+    if !status.success() {
+        bail!("git clone {} failed with status {}", url, status);
+    }
+    if let Some(rev) = rev {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(checkout)
+            .args(["checkout", "--quiet", "--end-of-options", rev])
+            .status()
+            .context("failed to run git checkout")?;
+        if !status.success() {
+            bail!("git checkout {} failed with status {}", rev, status);
+        }
+    }
+    Ok(())
+}
+
+fn git_head_revision(checkout: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(checkout)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let revision = String::from_utf8(output.stdout).ok()?;
+    let revision = revision.trim();
+    (!revision.is_empty()).then(|| revision.to_string())
+}
+
+fn run_cache(cli: &CacheCli) -> Result<()> {
+    let cache_root =
+        cache_root_dir().ok_or_else(|| anyhow!("unable to locate the build cache directory"))?;
+    let mut entries = Vec::new();
+    collect_cache_entries(&cache_root, &mut entries)?;
+    let total: u64 = entries.iter().map(|entry| entry.size).sum();
+
+    match cli.command {
+        CacheCommand::Info => {
+            println!("Cache directory: {}", cache_root.display());
+            println!("Entries: {}", entries.len());
+            println!(
+                "Size: {} (limit {})",
+                format_size(total),
+                format_size(cache_max_bytes())
+            );
+        }
+        CacheCommand::Clean => {
+            for entry in &entries {
+                fs::remove_file(&entry.path)
+                    .with_context(|| format!("failed to remove {}", entry.path.display()))?;
+            }
+            remove_empty_dirs(&cache_root)?;
+            println!(
+                "Removed {} cached binaries ({})",
+                entries.len(),
+                format_size(total)
+            );
+        }
+    }
+
+    Ok(())
+}
+
 fn run_fmt(cli: &FmtCli) -> Result<()> {
     let inputs = if cli.inputs.is_empty() {
         vec![env::current_dir().context("failed to determine current directory")?]
@@ -614,8 +955,28 @@ fn run_build(cli: BuildCli) -> Result<()> {
 
     let object_options = object_options_from_cli(&cli)?;
 
+    let source = SourceFile::new(SourceId(0), cli.input.clone(), contents);
+    let line_cache: Vec<&str> = source.contents.lines().collect();
+    let mut compiler = Compiler::new(CompileOptions::default());
+
+    // Expand imports before consulting the cache so the key covers every module
+    // the program pulls in, not just the entry file.
+    let expanded = match compiler
+        .parse_source(&source)
+        .and_then(|parsed| compiler.expand_modules(&source, parsed))
+    {
+        Ok(expanded) => expanded,
+        Err(err) => return Err(compilation_failed(&compiler, &source, &line_cache, err)),
+    };
+
     let cache_entry = if cli.emit.is_empty() {
-        build_cache_entry(&cli, &contents, &rustc_info, &object_options)?
+        build_cache_entry(
+            &cli,
+            &source.contents,
+            expanded.loaded_sources(),
+            &rustc_info,
+            &object_options,
+        )?
     } else {
         None
     };
@@ -629,21 +990,12 @@ fn run_build(cli: BuildCli) -> Result<()> {
         }
     }
 
-    let source = SourceFile::new(SourceId(0), cli.input.clone(), contents);
-    let line_cache: Vec<&str> = source.contents.lines().collect();
-    let mut compiler = Compiler::new(CompileOptions::default());
-
-    let compilation = match compiler.compile(&source) {
+    let compilation = match compiler
+        .resolve_module(expanded)
+        .and_then(|resolved| compiler.typecheck_module(resolved))
+    {
         Ok(comp) => comp,
-        Err(err) => {
-            if !compiler.diagnostics().is_empty() {
-                eprintln!("Diagnostics:");
-                for diagnostic in compiler.diagnostics().entries() {
-                    print_diagnostic(&source, &line_cache, diagnostic);
-                }
-            }
-            return Err(err.context("Compilation failed"));
-        }
+        Err(err) => return Err(compilation_failed(&compiler, &source, &line_cache, err)),
     };
 
     if cli.emit.contains(&Emit::Ast) {
@@ -670,11 +1022,29 @@ fn run_build(cli: BuildCli) -> Result<()> {
         if let Err(err) = store_binary_in_cache(&entry.path, &output) {
             eprintln!("warning: failed to write cache entry: {err}");
         }
+        if let Err(err) = evict_cache_entries(&entry.root, cache_max_bytes()) {
+            eprintln!("warning: failed to prune build cache: {err}");
+        }
     }
 
     Ok(())
 }
 
+fn compilation_failed(
+    compiler: &Compiler,
+    source: &SourceFile,
+    line_cache: &[&str],
+    err: anyhow::Error,
+) -> anyhow::Error {
+    if !compiler.diagnostics().is_empty() {
+        eprintln!("Diagnostics:");
+        for diagnostic in compiler.diagnostics().entries() {
+            print_diagnostic(source, line_cache, diagnostic);
+        }
+    }
+    err.context("Compilation failed")
+}
+
 fn fallback_native_cpu() -> Option<String> {
     #[cfg(target_arch = "aarch64")]
     {
@@ -720,24 +1090,47 @@ fn build_with_llvm(
     object_options: &ObjectCompileOptions,
 ) -> Result<()> {
     let object_path = object_path_for_output(output);
-    if let Err(err) = aot::compile_compilation_to_object(compilation, &object_path, object_options)
-    {
-        if err
-            .to_string()
-            .contains("No available targets are compatible")
-        {
-            bail!(
-                "{}
+    // Each imported module becomes its own object so unchanged modules can be
+    // reused from the cache. `--emit obj` keeps producing a single object file.
+    let separate =
+        compilation.units.len() > 1 && !cli.whole_program && !cli.emit.contains(&Emit::Obj);
+    let compiled = if separate {
+        compile_units_to_objects(compilation, output, object_options)
+    } else {
+        aot::compile_compilation_to_object(compilation, &object_path, object_options).map(|()| {
+            vec![UnitObject {
+                path: object_path.clone(),
+                temporary: !cli.emit.contains(&Emit::Obj),
+            }]
+        })
+    };
+    let objects = match compiled {
+        Ok(objects) => objects,
+        Err(err) => {
+            if err
+                .to_string()
+                .contains("No available targets are compatible")
+            {
+                bail!(
+                    "{}
 
 Install an LLVM toolchain with support for {} or re-run with `--target <triple>`.",
-                err,
-                object_options
-                    .triple
-                    .as_deref()
-                    .unwrap_or("the host target")
-            );
-        } else {
-            return Err(err);
+                    err,
+                    object_options
+                        .triple
+                        .as_deref()
+                        .unwrap_or("the host target")
+                );
+            } else {
+                return Err(err);
+            }
+        }
+    };
+    let object_paths: Vec<PathBuf> = objects.iter().map(|object| object.path.clone()).collect();
+    let mut link_libs = compilation.extern_libraries();
+    for library in &cli.link_libs {
+        if !link_libs.contains(library) {
+            link_libs.push(library.clone());
         }
     }
 
@@ -753,11 +1146,12 @@ Install an LLVM toolchain with support for {} or re-run with `--target <triple>`
     if using_bundled_linkkit() {
         let runtime_archive = bundled_runtime_archive()?;
         link_with_cc(
-            &object_path,
+            &object_paths,
             &runtime_archive,
             output,
             cli.linker.as_deref(),
             &cli.linker_args,
+            &link_libs,
             cli.lto,
         )?;
     } else {
@@ -771,20 +1165,23 @@ Install an LLVM toolchain with support for {} or re-run with `--target <triple>`
         link_with_rustc(
             rustc_path,
             &stub_path,
-            &object_path,
+            &object_paths,
             &runtime_rlib,
             output,
             object_options,
             cli.linker.as_deref(),
             &cli.linker_args,
+            &link_libs,
             cli.lto,
         )?;
 
         let _ = fs::remove_file(&stub_path);
     }
 
-    if !cli.emit.contains(&Emit::Obj) {
-        let _ = fs::remove_file(&object_path);
+    for object in &objects {
+        if object.temporary {
+            let _ = fs::remove_file(&object.path);
+        }
     }
 
     finalize_build_outputs(cli, output, object_options, rustc_info)?;
@@ -807,10 +1204,19 @@ fn build_temporary_executable(
     let mut object_path = output.to_path_buf();
     object_path.set_extension(object_extension());
     aot::compile_compilation_to_object(compilation, &object_path, &object_options)?;
+    let link_libs = compilation.extern_libraries();
 
     if using_bundled_linkkit() {
         let runtime_archive = bundled_runtime_archive()?;
-        link_with_cc(&object_path, &runtime_archive, output, None, &[], false)?;
+        link_with_cc(
+            std::slice::from_ref(&object_path),
+            &runtime_archive,
+            output,
+            None,
+            &[],
+            &link_libs,
+            false,
+        )?;
     } else {
         let rustc_path =
             rustc_path.ok_or_else(|| anyhow!("missing rustc path for external toolchain mode"))?;
@@ -821,12 +1227,13 @@ fn build_temporary_executable(
         link_with_rustc(
             rustc_path,
             &stub_path,
-            &object_path,
+            std::slice::from_ref(&object_path),
             &runtime_rlib,
             output,
             &object_options,
             None,
             &[],
+            &link_libs,
             false,
         )?;
 
@@ -837,6 +1244,134 @@ fn build_temporary_executable(
     Ok(())
 }
 
+/// An object file to link, and whether it should be deleted afterwards.
+struct UnitObject {
+    path: PathBuf,
+    temporary: bool,
+}
+
+/// Compile each unit of the program to its own object file. Objects live in the
+/// build cache keyed by the unit's source and the interfaces it depends on, so
+/// a module is only recompiled when it or something it imports changes shape.
+fn compile_units_to_objects(
+    compilation: &tea_compiler::Compilation,
+    output: &Path,
+    object_options: &ObjectCompileOptions,
+) -> Result<Vec<UnitObject>> {
+    let units = &compilation.units;
+    let interfaces: HashMap<&Path, String> = units
+        .iter()
+        .map(|unit| (unit.path(), unit.interface().to_json()))
+        .collect();
+    let objects_dir = cache_root_dir().map(|root| root.join("objects"));
+
+    let mut objects = Vec::with_capacity(units.len());
+    for (index, unit) in units.iter().enumerate() {
+        let Some(objects_dir) = &objects_dir else {
+            let path = object_path_for_output(output)
+                .with_extension(format!("{index}.{}", object_extension()));
+            aot::compile_unit_to_object(compilation, unit, &path, object_options)?;
+            objects.push(UnitObject {
+                path,
+                temporary: true,
+            });
+            continue;
+        };
+
+        let key = unit_object_key(unit, units, &interfaces, object_options);
+        let cached = objects_dir.join(format!("{key}.{}", object_extension()));
+        if cached.exists() {
+            if let Ok(file) = File::options().write(true).open(&cached) {
+                let _ = file.set_modified(SystemTime::now());
+            }
+        } else {
+            fs::create_dir_all(objects_dir)?;
+            let partial = objects_dir.join(format!(
+                "{key}.{}.partial.{}",
+                std::process::id(),
+                object_extension()
+            ));
+            aot::compile_unit_to_object(compilation, unit, &partial, object_options)?;
+            fs::rename(&partial, &cached)
+                .with_context(|| format!("failed to write {}", cached.display()))?;
+        }
+        objects.push(UnitObject {
+            path: cached,
+            temporary: false,
+        });
+    }
+    Ok(objects)
+}
+
+fn unit_object_key(
+    unit: &CompilationUnit,
+    units: &[CompilationUnit],
+    interfaces: &HashMap<&Path, String>,
+    object_options: &ObjectCompileOptions,
+) -> String {
+    let by_path: HashMap<&Path, &CompilationUnit> =
+        units.iter().map(|unit| (unit.path(), unit)).collect();
+
+    let mut hasher = Sha256::new();
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    hasher.update(unit.path().to_string_lossy().as_bytes());
+    hasher.update([0]);
+    hasher.update(unit.source().as_bytes());
+    hasher.update([0]);
+    for function in unit.functions() {
+        hasher.update(function.as_bytes());
+        hasher.update([0]);
+    }
+
+    // The entry unit runs every module's top-level code, so it depends on that
+    // code and on every interface, but not on other modules' function bodies.
+    // Other units only see their imports through interfaces.
+    let mut seen: BTreeSet<&Path> = BTreeSet::new();
+    if unit.is_entry() {
+        for other in units {
+            hasher.update(other.top_level_source().as_bytes());
+            hasher.update([0]);
+            seen.insert(other.path());
+        }
+    } else {
+        let mut pending = vec![unit.path()];
+        while let Some(path) = pending.pop() {
+            if !seen.insert(path) {
+                continue;
+            }
+            if let Some(current) = by_path.get(path) {
+                pending.extend(current.dependencies().iter().map(PathBuf::as_path));
+            }
+        }
+    }
+    for path in seen {
+        if let Some(interface) = interfaces.get(path) {
+            hasher.update(interface.as_bytes());
+            hasher.update([0]);
+        }
+    }
+
+    hasher.update(
+        object_options
+            .triple
+            .as_deref()
+            .unwrap_or("host")
+            .as_bytes(),
+    );
+    hasher.update(
+        object_options
+            .cpu
+            .as_deref()
+            .unwrap_or("generic")
+            .as_bytes(),
+    );
+    hasher.update(object_options.features.as_deref().unwrap_or("").as_bytes());
+    hasher.update(opt_level_to_string(object_options.opt_level).as_bytes());
+    hasher.update(current_profile().as_bytes());
+    let key_hex = format!("{:x}", hasher.finalize());
+    key_hex[..32].to_string()
+}
+
 fn object_options_from_cli(cli: &BuildCli) -> Result<ObjectCompileOptions> {
     let (default_cpu, default_features) = default_host_codegen_settings();
     let mut options = ObjectCompileOptions::default();
@@ -975,12 +1510,14 @@ fn default_binary_path(input: &Path) -> PathBuf {
 }
 
 struct CacheEntry {
+    root: PathBuf,
     path: PathBuf,
 }
 
 fn build_cache_entry(
     cli: &BuildCli,
     contents: &str,
+    loaded_sources: &[(PathBuf, String)],
     rustc_info: &RustcInfo,
     object_options: &ObjectCompileOptions,
 ) -> Result<Option<CacheEntry>> {
@@ -1017,6 +1554,17 @@ fn build_cache_entry(
     let mut hasher = Sha256::new();
     hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
     hasher.update(contents.as_bytes());
+    // Imported modules (including the stdlib sources) and the runtime library
+    // all feed into the binary, so a change to any of them must miss the cache.
+    for (path, source) in loaded_sources {
+        hasher.update(path.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(source.as_bytes());
+        hasher.update([0]);
+    }
+    if let Some(runtime) = runtime_fingerprint() {
+        hasher.update(runtime.as_bytes());
+    }
     hasher.update(
         object_options
             .triple
@@ -1039,6 +1587,18 @@ fn build_cache_entry(
     hasher.update(opt_level_label.as_bytes());
     let lto_flag = if cli.lto { "lto" } else { "no-lto" };
     hasher.update(lto_flag.as_bytes());
+    for library in &cli.link_libs {
+        hasher.update(library.as_bytes());
+        hasher.update([0]);
+    }
+    if let Some(linker) = &cli.linker {
+        hasher.update(linker.to_string_lossy().as_bytes());
+    }
+    hasher.update([0]);
+    for arg in &cli.linker_args {
+        hasher.update(arg.as_bytes());
+        hasher.update([0]);
+    }
     hasher.update(current_profile().as_bytes());
     if let Some(version) = rustc_info.version.as_deref() {
         hasher.update(version.as_bytes());
@@ -1055,7 +1615,20 @@ fn build_cache_entry(
         .unwrap_or("program");
     let cache_file = cache_dir.join(format!("{stem}-{hash_prefix}.bin"));
 
-    Ok(Some(CacheEntry { path: cache_file }))
+    Ok(Some(CacheEntry {
+        root: cache_root,
+        path: cache_file,
+    }))
+}
+
+fn runtime_fingerprint() -> Option<String> {
+    if using_bundled_linkkit() {
+        return bundled::runtime_archive_digest();
+    }
+
+    let rlib = find_runtime_rlib(current_profile(), &runtime_target_dir()).ok()??;
+    let bytes = fs::read(rlib).ok()?;
+    Some(format!("{:x}", Sha256::digest(&bytes)))
 }
 
 fn reuse_cached_binary(
@@ -1072,6 +1645,10 @@ fn reuse_cached_binary(
     }
     fs::copy(&entry.path, output)
         .with_context(|| format!("failed to copy cached binary from {}", entry.path.display()))?;
+    // Refresh the entry's timestamp so eviction treats it as recently used.
+    if let Ok(file) = File::options().write(true).open(&entry.path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
     finalize_build_outputs(cli, output, object_options, rustc_info)?;
     println!("Built {} (from cache)", output.display());
     Ok(())
@@ -1086,6 +1663,95 @@ fn store_binary_in_cache(cache_path: &Path, output: &Path) -> Result<()> {
     Ok(())
 }
 
+struct CachedBinary {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+fn cache_max_bytes() -> u64 {
+    std::env::var("TEA_CACHE_MAX_BYTES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_CACHE_MAX_BYTES)
+}
+
+fn collect_cache_entries(dir: &Path, entries: &mut Vec<CachedBinary>) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let entry = entry?;
+        let path = entry.path();
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            collect_cache_entries(&path, entries)?;
+        } else if matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some(ext) if ext == "bin" || ext == object_extension()
+        ) {
+            entries.push(CachedBinary {
+                path,
+                size: metadata.len(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove the least recently used cached binaries until the cache fits in `max_bytes`.
+fn evict_cache_entries(cache_root: &Path, max_bytes: u64) -> Result<Vec<PathBuf>> {
+    let mut entries = Vec::new();
+    collect_cache_entries(cache_root, &mut entries)?;
+    let mut total: u64 = entries.iter().map(|entry| entry.size).sum();
+    entries.sort_by_key(|entry| entry.modified);
+
+    let mut removed = Vec::new();
+    for entry in entries {
+        if total <= max_bytes {
+            break;
+        }
+        fs::remove_file(&entry.path)
+            .with_context(|| format!("failed to remove {}", entry.path.display()))?;
+        total -= entry.size;
+        removed.push(entry.path);
+    }
+
+    Ok(removed)
+}
+
+fn remove_empty_dirs(dir: &Path) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            remove_empty_dirs(&path)?;
+            let _ = fs::remove_dir(&path);
+        }
+    }
+    Ok(())
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
 fn state_root_dir() -> Option<PathBuf> {
     if let Ok(xdg_state) = std::env::var("XDG_STATE_HOME") {
         return Some(PathBuf::from(xdg_state).join("tea"));
@@ -1320,11 +1986,12 @@ fn cc_driver_path() -> PathBuf {
 }
 
 fn link_with_cc(
-    object_path: &Path,
+    object_paths: &[PathBuf],
     runtime_archive: &Path,
     output: &Path,
     linker: Option<&Path>,
     linker_args: &[String],
+    link_libs: &[String],
     lto: bool,
 ) -> Result<()> {
     if let Some(parent) = output.parent() {
@@ -1361,7 +2028,7 @@ fn link_with_cc(
 
     let mut cmd = Command::new(&cc);
     cmd.arg(&launcher_object);
-    cmd.arg(object_path);
+    cmd.args(object_paths);
     cmd.arg(runtime_archive);
     cmd.arg("-o").arg(output);
 
@@ -1371,6 +2038,9 @@ fn link_with_cc(
     for arg in linker_args {
         cmd.arg(format!("-Wl,{arg}"));
     }
+    for lib in link_libs {
+        cmd.arg(format!("-l{lib}"));
+    }
     for lib in bundled::native_static_libs() {
         cmd.arg(lib);
     }
@@ -1413,12 +2083,13 @@ fn link_with_cc(
 fn link_with_rustc(
     rustc: &Path,
     stub_path: &Path,
-    object_path: &Path,
+    object_paths: &[PathBuf],
     runtime_rlib: &Path,
     output: &Path,
     object_options: &ObjectCompileOptions,
     linker: Option<&Path>,
     linker_args: &[String],
+    link_libs: &[String],
     lto: bool,
 ) -> Result<()> {
     if let Some(parent) = output.parent() {
@@ -1461,7 +2132,9 @@ fn link_with_rustc(
         "opt-level={}",
         opt_level_to_string(object_options.opt_level)
     ));
-    cmd.arg(format!("-Clink-arg={}", object_path.to_string_lossy()));
+    for object_path in object_paths {
+        cmd.arg(format!("-Clink-arg={}", object_path.to_string_lossy()));
+    }
     cmd.arg("-o").arg(output);
     if let Some(target) = object_options.triple.as_deref() {
         cmd.arg("--target").arg(target);
@@ -1480,6 +2153,10 @@ fn link_with_rustc(
     for arg in linker_args {
         cmd.arg(format!("-Clink-arg={arg}"));
     }
+    // Passed as link args rather than `-l` so they follow the Tea objects.
+    for lib in link_libs {
+        cmd.arg(format!("-Clink-arg=-l{lib}"));
+    }
 
     // LTO flag handling
     // Note: Full LTO requires all object files to have embedded LLVM bitcode.
@@ -1814,6 +2491,8 @@ mod tests {
             rustc: None,
             linker: None,
             linker_args: Vec::new(),
+            link_libs: Vec::new(),
+            whole_program: false,
         };
 
         let options = object_options_from_cli(&cli).expect("resolve object options");
@@ -1823,4 +2502,62 @@ mod tests {
         assert_eq!(options.features, expected_features);
         assert_eq!(options.entry_symbol.as_deref(), Some("tea_main"));
     }
+
+    #[test]
+    fn cache_key_covers_linker_arguments() {
+        let mut cli = BuildCli {
+            input: PathBuf::from("sample.tea"),
+            output: None,
+            emit: Vec::new(),
+            target: None,
+            cpu: None,
+            features: None,
+            opt_level: None,
+            lto: false,
+            bundle: false,
+            bundle_output: None,
+            checksum: false,
+            checksum_output: None,
+            signature_key: None,
+            signature_output: None,
+            rustc: None,
+            linker: None,
+            linker_args: Vec::new(),
+            link_libs: Vec::new(),
+            whole_program: false,
+        };
+        let options = object_options_from_cli(&cli).expect("resolve object options");
+        let rustc_info = RustcInfo::default();
+
+        let plain = build_cache_entry(&cli, "@println(1)\n", &[], &rustc_info, &options)
+            .expect("cache entry");
+        cli.linker_args = vec!["-Wl,--gc-sections".to_string()];
+        let with_args = build_cache_entry(&cli, "@println(1)\n", &[], &rustc_info, &options)
+            .expect("cache entry");
+
+        if let (Some(plain), Some(with_args)) = (plain, with_args) {
+            assert_ne!(plain.path, with_args.path);
+        }
+    }
+
+    #[test]
+    fn cache_eviction_removes_least_recently_used_entries() {
+        let dir = tempdir().expect("tempdir");
+        let nested = dir.path().join("project");
+        fs::create_dir_all(&nested).expect("create cache dir");
+
+        let now = SystemTime::now();
+        for (index, name) in ["oldest", "middle", "newest"].iter().enumerate() {
+            let path = nested.join(format!("{name}.bin"));
+            fs::write(&path, vec![0u8; 100]).expect("write entry");
+            let file = File::options().write(true).open(&path).expect("open entry");
+            file.set_modified(now - std::time::Duration::from_secs(100 - index as u64 * 10))
+                .expect("set mtime");
+        }
+
+        let removed = evict_cache_entries(dir.path(), 200).expect("evict");
+        assert_eq!(removed, vec![nested.join("oldest.bin")]);
+        assert!(nested.join("middle.bin").exists());
+        assert!(nested.join("newest.bin").exists());
+    }
 }
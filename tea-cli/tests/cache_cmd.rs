@@ -0,0 +1,43 @@
+use std::fs;
+use std::process::Command;
+
+use tempfile::tempdir;
+
+fn tea_cli_binary() -> &'static str {
+    env!("CARGO_BIN_EXE_tea")
+}
+
+#[test]
+fn cache_info_and_clean_manage_cached_binaries() {
+    let state = tempdir().expect("tempdir");
+    let entry_dir = state.path().join("tea").join("cache").join("project");
+    fs::create_dir_all(&entry_dir).expect("create cache dir");
+    let entry = entry_dir.join("main-0123456789abcdef.bin");
+    fs::write(&entry, vec![0u8; 2048]).expect("write cache entry");
+
+    let info = Command::new(tea_cli_binary())
+        .args(["cache", "info"])
+        .env("XDG_STATE_HOME", state.path())
+        .output()
+        .expect("run tea cache info");
+    assert!(info.status.success(), "cache info failed: {:?}", info);
+    let stdout = String::from_utf8_lossy(&info.stdout);
+    assert!(stdout.contains("Entries: 1"), "unexpected output: {stdout}");
+    assert!(
+        stdout.contains("Size: 2.0 KiB"),
+        "unexpected output: {stdout}"
+    );
+
+    let clean = Command::new(tea_cli_binary())
+        .args(["cache", "clean"])
+        .env("XDG_STATE_HOME", state.path())
+        .output()
+        .expect("run tea cache clean");
+    assert!(clean.status.success(), "cache clean failed: {:?}", clean);
+    let stdout = String::from_utf8_lossy(&clean.stdout);
+    assert!(
+        stdout.contains("Removed 1 cached binaries"),
+        "unexpected output: {stdout}"
+    );
+    assert!(!entry.exists());
+}
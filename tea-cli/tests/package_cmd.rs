@@ -0,0 +1,162 @@
+use std::fs;
+use std::process::Command;
+
+use tempfile::tempdir;
+
+fn tea_cli_binary() -> &'static str {
+    env!("CARGO_BIN_EXE_tea")
+}
+
+#[test]
+fn init_and_add_write_manifest_and_lockfile() {
+    let dir = tempdir().expect("tempdir");
+    let app = dir.path().join("app");
+
+    let init = Command::new(tea_cli_binary())
+        .args(["init"])
+        .arg(&app)
+        .output()
+        .expect("run tea init");
+    assert!(init.status.success(), "tea init failed: {:?}", init);
+    let manifest = fs::read_to_string(app.join("tea.toml")).expect("read tea.toml");
+    assert!(manifest.contains("name = \"app\""), "manifest: {manifest}");
+    assert!(app.join("main.tea").is_file());
+    assert!(app.join("tea.lock").is_file());
+
+    let again = Command::new(tea_cli_binary())
+        .args(["init"])
+        .arg(&app)
+        .output()
+        .expect("run tea init twice");
+    assert!(!again.status.success(), "second tea init should fail");
+
+    let helpers = dir.path().join("helpers");
+    fs::create_dir_all(&helpers).expect("create helpers");
+    fs::write(
+        helpers.join("tea.toml"),
+        "[package]\nname = \"helpers\"\nversion = \"0.4.0\"\n",
+    )
+    .expect("write helpers manifest");
+
+    let add = Command::new(tea_cli_binary())
+        .args(["add", "helpers", "--path", "../helpers"])
+        .current_dir(&app)
+        .output()
+        .expect("run tea add");
+    assert!(add.status.success(), "tea add failed: {:?}", add);
+    let stdout = String::from_utf8_lossy(&add.stdout);
+    assert!(stdout.contains("Added helpers 0.4.0"), "output: {stdout}");
+
+    let manifest = fs::read_to_string(app.join("tea.toml")).expect("read tea.toml");
+    assert!(
+        manifest.contains("helpers") && manifest.contains("path = \"../helpers\""),
+        "manifest: {manifest}"
+    );
+    let lockfile = fs::read_to_string(app.join("tea.lock")).expect("read tea.lock");
+    assert!(
+        lockfile.contains("source = \"path+../helpers\""),
+        "lockfile: {lockfile}"
+    );
+}
+
+#[test]
+fn add_uses_pre_populated_vendor_checkout() {
+    let dir = tempdir().expect("tempdir");
+    fs::write(
+        dir.path().join("tea.toml"),
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\n",
+    )
+    .expect("write manifest");
+    let vendored = dir.path().join("vendor").join("extras");
+    fs::create_dir_all(&vendored).expect("create vendor dir");
+    fs::write(
+        vendored.join("tea.toml"),
+        "[package]\nname = \"extras\"\nversion = \"1.0.0\"\n",
+    )
+    .expect("write vendored manifest");
+
+    let add = Command::new(tea_cli_binary())
+        .args([
+            "add",
+            "extras",
+            "--git",
+            "https://example.invalid/extras.git",
+            "--rev",
+            "v1.0.0",
+        ])
+        .current_dir(dir.path())
+        .output()
+        .expect("run tea add");
+    assert!(add.status.success(), "tea add failed: {:?}", add);
+
+    let lockfile = fs::read_to_string(dir.path().join("tea.lock")).expect("read tea.lock");
+    assert!(
+        lockfile.contains("source = \"git+https://example.invalid/extras.git#v1.0.0\""),
+        "lockfile: {lockfile}"
+    );
+}
+
+#[test]
+fn add_rejects_git_values_that_look_like_options() {
+    let dir = tempdir().expect("tempdir");
+    fs::write(
+        dir.path().join("tea.toml"),
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\n",
+    )
+    .expect("write manifest");
+
+    let add = Command::new(tea_cli_binary())
+        .args(["add", "extras", "--git=--upload-pack=touch pwned"])
+        .current_dir(dir.path())
+        .output()
+        .expect("run tea add");
+    assert!(!add.status.success(), "tea add should fail: {:?}", add);
+    let stderr = String::from_utf8_lossy(&add.stderr);
+    assert!(
+        stderr.contains("must not start with '-'"),
+        "stderr: {stderr}"
+    );
+    assert!(!dir.path().join("vendor").join("extras").exists());
+    assert!(!dir.path().join("pwned").exists());
+}
+
+#[test]
+fn lock_rewrites_lockfile_after_dependency_changes() {
+    let dir = tempdir().expect("tempdir");
+    let helpers = dir.path().join("helpers");
+    fs::create_dir_all(&helpers).expect("create helpers");
+    fs::write(
+        helpers.join("tea.toml"),
+        "[package]\nname = \"helpers\"\nversion = \"0.4.0\"\n",
+    )
+    .expect("write helpers manifest");
+    fs::write(
+        dir.path().join("tea.toml"),
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nhelpers = { path = \"helpers\" }\n",
+    )
+    .expect("write manifest");
+
+    let lock = Command::new(tea_cli_binary())
+        .args(["lock"])
+        .current_dir(dir.path())
+        .output()
+        .expect("run tea lock");
+    assert!(lock.status.success(), "tea lock failed: {:?}", lock);
+    let stdout = String::from_utf8_lossy(&lock.stdout);
+    assert!(stdout.contains("Locked 1 package"), "output: {stdout}");
+    let before = fs::read_to_string(dir.path().join("tea.lock")).expect("read tea.lock");
+    assert!(
+        before.contains("checksum = \"sha256:"),
+        "lockfile: {before}"
+    );
+
+    fs::write(helpers.join("text.tea"), "pub const GREETING = \"hi\"\n").expect("write module");
+    let relock = Command::new(tea_cli_binary())
+        .args(["lock"])
+        .current_dir(dir.path())
+        .output()
+        .expect("run tea lock again");
+    assert!(relock.status.success(), "tea lock failed: {:?}", relock);
+    let after = fs::read_to_string(dir.path().join("tea.lock")).expect("read tea.lock");
+    assert_ne!(before, after);
+}
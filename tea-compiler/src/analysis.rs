@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use crate::ast::SourceSpan;
+use crate::consteval::ConstValue;
 use crate::resolver::{ModuleAliasBinding, ModuleExportKind};
 use crate::stdlib::{self, StdFunction, StdType};
 use crate::typechecker::{
@@ -15,6 +16,7 @@ fn describe_std_type(ty: StdType) -> String {
         StdType::Int => "Int".into(),
         StdType::Float => "Float".into(),
         StdType::String => "String".into(),
+        StdType::Bytes => "Bytes".into(),
         StdType::List => "List[Unknown]".into(),
         StdType::Dict => "Dict[String, Unknown]".into(),
         StdType::Struct => "Struct".into(),
@@ -168,7 +170,9 @@ pub struct SemanticAnalysis {
     binding_types: HashMap<SourceSpan, Type>,
     type_test_metadata: HashMap<SourceSpan, Type>,
     struct_definitions: HashMap<String, StructDefinition>,
+    enum_definitions: HashMap<String, EnumDefinition>,
     error_definitions: HashMap<String, ErrorDefinition>,
+    constants: HashMap<String, ConstValue>,
 }
 
 impl SemanticAnalysis {
@@ -223,7 +227,9 @@ impl SemanticAnalysis {
             binding_types,
             type_test_metadata: type_checker.type_test_metadata().clone(),
             struct_definitions,
+            enum_definitions,
             error_definitions,
+            constants: type_checker.constants().clone(),
         }
     }
 
@@ -281,7 +287,15 @@ impl SemanticAnalysis {
         &self.struct_definitions
     }
 
+    pub(crate) fn enum_definitions(&self) -> &HashMap<String, EnumDefinition> {
+        &self.enum_definitions
+    }
+
     pub(crate) fn error_definitions(&self) -> &HashMap<String, ErrorDefinition> {
         &self.error_definitions
     }
+
+    pub(crate) fn constants(&self) -> &HashMap<String, ConstValue> {
+        &self.constants
+    }
 }
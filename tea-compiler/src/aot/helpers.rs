@@ -23,6 +23,7 @@ pub enum TeaValueTag {
     Error = 7,
     Closure = 8,
     Nil = 9,
+    Bytes = 10,
 }
 
 impl TeaValueTag {
@@ -3,6 +3,7 @@ use std::iter::Peekable;
 use std::str::Chars;
 
 use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+use inkwell::attributes::{Attribute, AttributeLoc};
 use inkwell::builder::{Builder, BuilderError};
 use inkwell::context::Context;
 use inkwell::module::{Linkage, Module as LlvmModule};
@@ -10,46 +11,56 @@ use inkwell::passes::PassBuilderOptions;
 use inkwell::targets::{
     CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple,
 };
-use inkwell::types::{BasicMetadataTypeEnum, BasicTypeEnum, FloatType, IntType, PointerType};
+use inkwell::types::{
+    BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FloatType, IntType, PointerType,
+};
 use inkwell::values::{
     BasicMetadataValueEnum, BasicValue, BasicValueEnum, CallSiteValue, FloatValue, FunctionValue,
     GlobalValue, IntValue, PointerValue, StructValue,
 };
 use inkwell::{AddressSpace, FloatPredicate, IntPredicate};
+use tea_support::SLICE_OPEN_BOUND;
 
 pub type OptimizationLevel = inkwell::OptimizationLevel;
 
 use crate::analysis::SemanticAnalysis;
 use crate::ast::{
     BinaryExpression, BinaryOperator, CallExpression, CatchHandler, CatchKind,
-    ConditionalExpression, ConditionalStatement, Expression, ExpressionKind, ForPattern,
-    FunctionStatement, InterpolatedStringExpression, InterpolatedStringPart, LambdaBody,
-    LambdaExpression, Literal, LoopHeader, LoopStatement, MatchPattern, Module as AstModule,
-    ReturnStatement, SourceSpan, Statement, ThrowStatement, TryExpression, TypeExpression,
-    UseStatement, VarStatement,
+    ConditionalExpression, ConditionalStatement, Expression, ExpressionKind, ExternFunction,
+    ForPattern, FormattedInterpolation, FunctionStatement, GuardStatement,
+    InterpolatedStringExpression, InterpolatedStringPart, LambdaBody, LambdaExpression, Literal,
+    LoopHeader, LoopStatement, MatchPattern, Module as AstModule, OptionalBinding, ReturnStatement,
+    SourceSpan, Statement, ThrowStatement, TryExpression, TypeExpression, UseStatement,
+    VarStatement, YieldStatement,
 };
 use crate::compiler::{Compilation, CompileOptions, Compiler};
-use crate::stdlib::{self, StdFunctionKind};
+use crate::consteval::ConstValue;
+use crate::generators::is_generator;
+use crate::stdlib::{self, StdFunction, StdFunctionKind, StdType};
+use crate::units::CompilationUnit;
 
 mod helpers;
 mod intrinsics;
 mod types;
 
 use crate::typechecker::{
-    ErrorDefinition, FunctionInstance, StructDefinition, StructInstance, StructType, Type,
+    EnumDefinition, ErrorDefinition, FunctionInstance, StructDefinition, StructInstance,
+    StructType, Type, EXTERN_ERROR, LIST_METHODS,
 };
 use helpers::{add_function_attr, build_tea_value, LoopMetadataBuilder, TeaValueTag};
 use intrinsics::Intrinsic;
 use types::{
-    format_struct_type_name, mangle_function_name, sanitize_symbol_component, type_to_value_type,
-    ErrorHandlingMode, ErrorVariantLowering, ExprValue, FunctionSignature, GlobalBindingSlot,
-    LambdaSignature, LocalVariable, StringBuilderState, StructLowering, ValueType,
+    extern_wrapper_name, format_struct_type_name, mangle_function_name, same_representation,
+    sanitize_symbol_component, type_to_value_type, CAbiType, ErrorHandlingMode,
+    ErrorVariantLowering, ExprValue, FunctionSignature, GlobalBindingSlot, LambdaSignature,
+    LocalVariable, StringBuilderState, StructLowering, ValueType,
 };
 
 struct SemanticMetadata {
     lambda_captures: HashMap<usize, Vec<String>>,
     lambda_signatures: HashMap<usize, LambdaSignature>,
     struct_definitions: HashMap<String, StructDefinition>,
+    enum_definitions: HashMap<String, EnumDefinition>,
     error_definitions: HashMap<String, ErrorDefinition>,
     function_instances: HashMap<String, Vec<FunctionInstance>>,
     struct_instances: HashMap<String, Vec<StructInstance>>,
@@ -57,6 +68,7 @@ struct SemanticMetadata {
     struct_call_metadata: HashMap<SourceSpan, (String, StructInstance)>,
     binding_types: HashMap<SourceSpan, Type>,
     type_test_metadata: HashMap<SourceSpan, Type>,
+    constants: HashMap<String, ConstValue>,
 }
 
 impl SemanticMetadata {
@@ -85,6 +97,7 @@ impl SemanticMetadata {
             lambda_captures: analysis.lambda_captures().clone(),
             lambda_signatures,
             struct_definitions: analysis.struct_definitions().clone(),
+            enum_definitions: analysis.enum_definitions().clone(),
             error_definitions: analysis.error_definitions().clone(),
             function_instances: analysis.function_instances().clone(),
             struct_instances: analysis.struct_instances().clone(),
@@ -92,6 +105,7 @@ impl SemanticMetadata {
             struct_call_metadata: analysis.struct_call_metadata().clone(),
             binding_types: analysis.typed_binding_types().clone(),
             type_test_metadata: analysis.typed_type_test_metadata().clone(),
+            constants: analysis.constants().clone(),
         })
     }
 }
@@ -262,14 +276,42 @@ pub fn compile_compilation_to_object(
     compile_module_to_object_with_analysis(
         &compilation.module,
         &compilation.analysis,
+        None,
+        output_path,
+        options,
+    )
+}
+
+/// Compile a single unit of `compilation` to an object file.
+///
+/// Functions owned by other units are only declared, and only the entry unit
+/// emits `main` and defines the program's globals, so the objects of every
+/// unit must be linked together.
+pub fn compile_unit_to_object(
+    compilation: &Compilation,
+    unit: &CompilationUnit,
+    output_path: &std::path::Path,
+    options: &ObjectCompileOptions,
+) -> Result<()> {
+    compile_module_to_object_with_analysis(
+        &compilation.module,
+        &compilation.analysis,
+        Some(unit),
         output_path,
         options,
     )
 }
 
+/// The part of the program a separately compiled object emits.
+struct UnitScope {
+    functions: HashSet<String>,
+    is_entry: bool,
+}
+
 fn compile_module_to_object_with_analysis(
     module_ast: &AstModule,
     analysis: &SemanticAnalysis,
+    unit: Option<&CompilationUnit>,
     output_path: &std::path::Path,
     options: &ObjectCompileOptions,
 ) -> Result<()> {
@@ -279,6 +321,10 @@ fn compile_module_to_object_with_analysis(
 
     let metadata = SemanticMetadata::from_analysis(analysis)?;
     let mut generator = LlvmCodeGenerator::new(&context, module, builder, metadata);
+    generator.unit_scope = unit.map(|unit| UnitScope {
+        functions: unit.functions().iter().cloned().collect(),
+        is_entry: unit.is_entry(),
+    });
     generator.compile(module_ast)?;
     let module = generator.into_module();
     module
@@ -360,10 +406,12 @@ struct LlvmCodeGenerator<'ctx> {
     struct_call_metadata_tc: HashMap<SourceSpan, (String, StructInstance)>,
     binding_types_tc: HashMap<SourceSpan, Type>,
     type_test_metadata_tc: HashMap<SourceSpan, Type>,
+    constants_tc: HashMap<String, ConstValue>,
     global_slots: HashMap<String, GlobalBindingSlot<'ctx>>,
     struct_field_variants: HashMap<String, Vec<ValueType>>,
     struct_variant_bases: HashMap<String, String>,
     struct_definitions_tc: HashMap<String, StructDefinition>,
+    enum_definitions_tc: HashMap<String, EnumDefinition>,
     error_definitions_tc: HashMap<String, ErrorDefinition>,
     generic_binding_stack: Vec<HashMap<String, (Type, ValueType)>>,
     lambda_functions: HashMap<usize, FunctionValue<'ctx>>,
@@ -455,6 +503,43 @@ struct LlvmCodeGenerator<'ctx> {
     string_replace_fn: Option<FunctionValue<'ctx>>,
     string_to_lower_fn: Option<FunctionValue<'ctx>>,
     string_to_upper_fn: Option<FunctionValue<'ctx>>,
+    string_chars_fn: Option<FunctionValue<'ctx>>,
+    string_graphemes_fn: Option<FunctionValue<'ctx>>,
+    string_code_points_fn: Option<FunctionValue<'ctx>>,
+    string_char_count_fn: Option<FunctionValue<'ctx>>,
+    string_display_width_fn: Option<FunctionValue<'ctx>>,
+    string_fold_case_fn: Option<FunctionValue<'ctx>>,
+    string_normalize_nfc_fn: Option<FunctionValue<'ctx>>,
+    string_normalize_nfd_fn: Option<FunctionValue<'ctx>>,
+    string_is_alpha_fn: Option<FunctionValue<'ctx>>,
+    string_is_digit_fn: Option<FunctionValue<'ctx>>,
+    string_is_whitespace_fn: Option<FunctionValue<'ctx>>,
+    format_value_fn: Option<FunctionValue<'ctx>>,
+    string_to_cstring_fn: Option<FunctionValue<'ctx>>,
+    cstring_free_fn: Option<FunctionValue<'ctx>>,
+    string_from_cstring_fn: Option<FunctionValue<'ctx>>,
+    bytes_len_fn: Option<FunctionValue<'ctx>>,
+    bytes_get_fn: Option<FunctionValue<'ctx>>,
+    bytes_slice_fn: Option<FunctionValue<'ctx>>,
+    bytes_equal_fn: Option<FunctionValue<'ctx>>,
+    bytes_concat_fn: Option<FunctionValue<'ctx>>,
+    crypto_digest_fn: Option<FunctionValue<'ctx>>,
+    crypto_hmac_sha256_fn: Option<FunctionValue<'ctx>>,
+    crypto_constant_time_eq_fn: Option<FunctionValue<'ctx>>,
+    term_is_terminal_fn: Option<FunctionValue<'ctx>>,
+    term_columns_fn: Option<FunctionValue<'ctx>>,
+    term_rows_fn: Option<FunctionValue<'ctx>>,
+    term_set_echo_fn: Option<FunctionValue<'ctx>>,
+    bytes_from_list_fn: Option<FunctionValue<'ctx>>,
+    bytes_to_list_fn: Option<FunctionValue<'ctx>>,
+    bytes_from_string_fn: Option<FunctionValue<'ctx>>,
+    bytes_to_string_fn: Option<FunctionValue<'ctx>>,
+    bytes_to_string_lossy_fn: Option<FunctionValue<'ctx>>,
+    bytes_is_utf8_fn: Option<FunctionValue<'ctx>>,
+    bytes_to_hex_fn: Option<FunctionValue<'ctx>>,
+    bytes_from_hex_fn: Option<FunctionValue<'ctx>>,
+    bytes_to_base64_fn: Option<FunctionValue<'ctx>>,
+    bytes_from_base64_fn: Option<FunctionValue<'ctx>>,
     // Regex functions
     regex_compile_fn: Option<FunctionValue<'ctx>>,
     regex_is_match_fn: Option<FunctionValue<'ctx>>,
@@ -490,6 +575,7 @@ struct LlvmCodeGenerator<'ctx> {
     process_wait_fn: Option<FunctionValue<'ctx>>,
     process_kill_fn: Option<FunctionValue<'ctx>>,
     process_read_stdout_fn: Option<FunctionValue<'ctx>>,
+    process_read_stdout_line_fn: Option<FunctionValue<'ctx>>,
     process_read_stderr_fn: Option<FunctionValue<'ctx>>,
     process_read_stdout_bytes_fn: Option<FunctionValue<'ctx>>,
     process_read_stderr_bytes_fn: Option<FunctionValue<'ctx>>,
@@ -527,6 +613,10 @@ struct LlvmCodeGenerator<'ctx> {
     fs_open_read_fn: Option<FunctionValue<'ctx>>,
     fs_read_chunk_fn: Option<FunctionValue<'ctx>>,
     fs_close_fn: Option<FunctionValue<'ctx>>,
+    fs_read_line_fn: Option<FunctionValue<'ctx>>,
+    random_seeded_fn: Option<FunctionValue<'ctx>>,
+    random_int_fn: Option<FunctionValue<'ctx>>,
+    random_float_fn: Option<FunctionValue<'ctx>>,
     alloc_string_fn: Option<FunctionValue<'ctx>>,
     alloc_list_fn: Option<FunctionValue<'ctx>>,
     alloc_struct_fn: Option<FunctionValue<'ctx>>,
@@ -542,6 +632,24 @@ struct LlvmCodeGenerator<'ctx> {
     string_push_int_fn: Option<FunctionValue<'ctx>>,
     string_slice_fn: Option<FunctionValue<'ctx>>,
     list_slice_fn: Option<FunctionValue<'ctx>>,
+    list_splice_fn: Option<FunctionValue<'ctx>>,
+    list_sort_fn: Option<FunctionValue<'ctx>>,
+    list_sort_by_fn: Option<FunctionValue<'ctx>>,
+    list_comparator_count: usize,
+    list_reverse_fn: Option<FunctionValue<'ctx>>,
+    list_unique_fn: Option<FunctionValue<'ctx>>,
+    list_chunk_fn: Option<FunctionValue<'ctx>>,
+    list_window_fn: Option<FunctionValue<'ctx>>,
+    list_extend_fn: Option<FunctionValue<'ctx>>,
+    list_join_fn: Option<FunctionValue<'ctx>>,
+    list_sum_int_fn: Option<FunctionValue<'ctx>>,
+    list_sum_float_fn: Option<FunctionValue<'ctx>>,
+    list_min_fn: Option<FunctionValue<'ctx>>,
+    list_max_fn: Option<FunctionValue<'ctx>>,
+    list_insert_fn: Option<FunctionValue<'ctx>>,
+    list_remove_at_fn: Option<FunctionValue<'ctx>>,
+    list_pop_fn: Option<FunctionValue<'ctx>>,
+    dict_group_append_fn: Option<FunctionValue<'ctx>>,
     struct_set_fn: Option<FunctionValue<'ctx>>,
     struct_get_fn: Option<FunctionValue<'ctx>>,
     error_alloc_fn: Option<FunctionValue<'ctx>>,
@@ -592,14 +700,27 @@ struct LlvmCodeGenerator<'ctx> {
     io_write_fn: Option<FunctionValue<'ctx>>,
     io_write_err_fn: Option<FunctionValue<'ctx>>,
     io_flush_fn: Option<FunctionValue<'ctx>>,
-    json_encode_fn: Option<FunctionValue<'ctx>>,
-    json_decode_fn: Option<FunctionValue<'ctx>>,
     http_send_fn: Option<FunctionValue<'ctx>>,
+    task_spawn_fn: Option<FunctionValue<'ctx>>,
+    task_join_fn: Option<FunctionValue<'ctx>>,
+    channel_new_fn: Option<FunctionValue<'ctx>>,
+    channel_send_fn: Option<FunctionValue<'ctx>>,
+    channel_recv_fn: Option<FunctionValue<'ctx>>,
+    channel_close_fn: Option<FunctionValue<'ctx>>,
+    generator_new_fn: Option<FunctionValue<'ctx>>,
+    generator_yield_fn: Option<FunctionValue<'ctx>>,
+    iter_next_fn: Option<FunctionValue<'ctx>>,
+    iter_close_fn: Option<FunctionValue<'ctx>>,
+    task_entry_fns: Vec<(ValueType, FunctionValue<'ctx>)>,
     error_mode_stack: Vec<ErrorHandlingMode>,
     function_return_stack: Vec<ValueType>,
+    /// Iterators the function being compiled must close when it returns, one
+    /// list per entry of `function_return_stack`.
+    owned_iterator_stack: Vec<Vec<IntValue<'ctx>>>,
     function_can_throw_stack: Vec<bool>,
     loop_context: Option<LoopContext<'ctx>>,
     simple_pure_functions: HashSet<String>,
+    unit_scope: Option<UnitScope>,
     manual_modulo_states: Vec<LoopModuloState<'ctx>>,
     list_len_ffi_fn: Option<FunctionValue<'ctx>>,
     string_len_ffi_fn: Option<FunctionValue<'ctx>>,
@@ -748,6 +869,84 @@ macro_rules! define_ffi_list_to_string_fn {
     };
 }
 
+/// FFI for in-place list operations: (list) -> void
+macro_rules! define_ffi_list_mutator_fn {
+    ($fn_name:ident, $field:ident, $ffi_name:literal) => {
+        fn $fn_name(&mut self) -> FunctionValue<'ctx> {
+            if let Some(func) = self.$field {
+                return func;
+            }
+            let fn_type = self
+                .context
+                .void_type()
+                .fn_type(&[self.list_ptr_type().into()], false);
+            let func = self
+                .module
+                .add_function($ffi_name, fn_type, Some(Linkage::External));
+            self.$field = Some(func);
+            func
+        }
+    };
+}
+
+/// FFI for list transforms: (list) -> list
+macro_rules! define_ffi_list_transform_fn {
+    ($fn_name:ident, $field:ident, $ffi_name:literal) => {
+        fn $fn_name(&mut self) -> FunctionValue<'ctx> {
+            if let Some(func) = self.$field {
+                return func;
+            }
+            let fn_type = self
+                .list_ptr_type()
+                .fn_type(&[self.list_ptr_type().into()], false);
+            let func = self
+                .module
+                .add_function($ffi_name, fn_type, Some(Linkage::External));
+            self.$field = Some(func);
+            func
+        }
+    };
+}
+
+/// FFI for list groupings: (list, int) -> list
+macro_rules! define_ffi_list_grouping_fn {
+    ($fn_name:ident, $field:ident, $ffi_name:literal) => {
+        fn $fn_name(&mut self) -> FunctionValue<'ctx> {
+            if let Some(func) = self.$field {
+                return func;
+            }
+            let fn_type = self.list_ptr_type().fn_type(
+                &[self.list_ptr_type().into(), self.int_type().into()],
+                false,
+            );
+            let func = self
+                .module
+                .add_function($ffi_name, fn_type, Some(Linkage::External));
+            self.$field = Some(func);
+            func
+        }
+    };
+}
+
+/// FFI for list items that may be missing: (list) -> value
+macro_rules! define_ffi_list_item_fn {
+    ($fn_name:ident, $field:ident, $ffi_name:literal) => {
+        fn $fn_name(&mut self) -> FunctionValue<'ctx> {
+            if let Some(func) = self.$field {
+                return func;
+            }
+            let fn_type = self
+                .value_type()
+                .fn_type(&[self.list_ptr_type().into()], false);
+            let func = self
+                .module
+                .add_function($ffi_name, fn_type, Some(Linkage::External));
+            self.$field = Some(func);
+            func
+        }
+    };
+}
+
 /// FFI for string to list: (string) -> list
 macro_rules! define_ffi_string_to_list_fn {
     ($fn_name:ident, $field:ident, $ffi_name:literal) => {
@@ -767,6 +966,44 @@ macro_rules! define_ffi_string_to_list_fn {
     };
 }
 
+/// FFI for bytes to string: (bytes) -> string
+macro_rules! define_ffi_bytes_to_string_fn {
+    ($fn_name:ident, $field:ident, $ffi_name:literal) => {
+        fn $fn_name(&mut self) -> FunctionValue<'ctx> {
+            if let Some(func) = self.$field {
+                return func;
+            }
+            let fn_type = self
+                .string_ptr_type()
+                .fn_type(&[self.bytes_ptr_type().into()], false);
+            let func = self
+                .module
+                .add_function($ffi_name, fn_type, Some(Linkage::External));
+            self.$field = Some(func);
+            func
+        }
+    };
+}
+
+/// FFI for string to bytes: (string) -> bytes
+macro_rules! define_ffi_string_to_bytes_fn {
+    ($fn_name:ident, $field:ident, $ffi_name:literal) => {
+        fn $fn_name(&mut self) -> FunctionValue<'ctx> {
+            if let Some(func) = self.$field {
+                return func;
+            }
+            let fn_type = self
+                .bytes_ptr_type()
+                .fn_type(&[self.string_ptr_type().into()], false);
+            let func = self
+                .module
+                .add_function($ffi_name, fn_type, Some(Linkage::External));
+            self.$field = Some(func);
+            func
+        }
+    };
+}
+
 /// FFI for string to int functions: (string) -> int
 macro_rules! define_ffi_string_to_int_fn {
     ($fn_name:ident, $field:ident, $ffi_name:literal) => {
@@ -993,6 +1230,140 @@ macro_rules! compile_string_to_string_call {
     };
 }
 
+/// Macro for single-bytes-arg builtin calls that return a string
+macro_rules! compile_bytes_to_string_call {
+    ($fn_name:ident, $ensure_fn:ident, $ffi_name:literal, $builtin_name:literal) => {
+        fn $fn_name(
+            &mut self,
+            arguments: &[crate::ast::CallArgument],
+            function: FunctionValue<'ctx>,
+            locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        ) -> Result<ExprValue<'ctx>> {
+            if arguments.len() != 1 {
+                bail!(concat!($builtin_name, " expects exactly 1 argument"));
+            }
+            if arguments[0].name.is_some() {
+                bail!(concat!(
+                    "named arguments are not supported for ",
+                    $builtin_name
+                ));
+            }
+            let arg_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+            let arg_ptr = self.expect_bytes_pointer(
+                arg_expr,
+                concat!($builtin_name, " expects a Bytes argument"),
+            )?;
+            let func = self.$ensure_fn();
+            let pointer = self
+                .call_function(func, &[arg_ptr.into()], $ffi_name)?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!(concat!($ffi_name, " returned no value")))?
+                .into_pointer_value();
+            Ok(ExprValue::String(pointer))
+        }
+    };
+}
+
+/// Macro for single-string-arg builtin calls that return bytes
+macro_rules! compile_string_to_bytes_call {
+    ($fn_name:ident, $ensure_fn:ident, $ffi_name:literal, $builtin_name:literal) => {
+        fn $fn_name(
+            &mut self,
+            arguments: &[crate::ast::CallArgument],
+            function: FunctionValue<'ctx>,
+            locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        ) -> Result<ExprValue<'ctx>> {
+            if arguments.len() != 1 {
+                bail!(concat!($builtin_name, " expects exactly 1 argument"));
+            }
+            if arguments[0].name.is_some() {
+                bail!(concat!(
+                    "named arguments are not supported for ",
+                    $builtin_name
+                ));
+            }
+            let arg_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+            let arg_ptr = self.expect_string_pointer(
+                arg_expr,
+                concat!($builtin_name, " expects a String argument"),
+            )?;
+            let func = self.$ensure_fn();
+            let pointer = self
+                .call_function(func, &[arg_ptr.into()], $ffi_name)?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!(concat!($ffi_name, " returned no value")))?
+                .into_pointer_value();
+            Ok(ExprValue::Bytes(pointer))
+        }
+    };
+}
+/// Macro for single-string-arg builtin calls that return a list, an int, or a bool
+macro_rules! compile_string_query_call {
+    ($fn_name:ident, $ensure_fn:ident, $ffi_name:literal, $builtin_name:literal, list($element:expr)) => {
+        fn $fn_name(
+            &mut self,
+            arguments: &[crate::ast::CallArgument],
+            function: FunctionValue<'ctx>,
+            locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        ) -> Result<ExprValue<'ctx>> {
+            let arg_ptr =
+                self.compile_single_string_argument(arguments, function, locals, $builtin_name)?;
+            let func = self.$ensure_fn();
+            let pointer = self
+                .call_function(func, &[arg_ptr.into()], $ffi_name)?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!(concat!($ffi_name, " returned no value")))?
+                .into_pointer_value();
+            Ok(ExprValue::List {
+                pointer,
+                element_type: Box::new($element),
+            })
+        }
+    };
+    ($fn_name:ident, $ensure_fn:ident, $ffi_name:literal, $builtin_name:literal, int) => {
+        fn $fn_name(
+            &mut self,
+            arguments: &[crate::ast::CallArgument],
+            function: FunctionValue<'ctx>,
+            locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        ) -> Result<ExprValue<'ctx>> {
+            let arg_ptr =
+                self.compile_single_string_argument(arguments, function, locals, $builtin_name)?;
+            let func = self.$ensure_fn();
+            let value = self
+                .call_function(func, &[arg_ptr.into()], $ffi_name)?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!(concat!($ffi_name, " returned no value")))?
+                .into_int_value();
+            Ok(ExprValue::Int(value))
+        }
+    };
+    ($fn_name:ident, $ensure_fn:ident, $ffi_name:literal, $builtin_name:literal, bool) => {
+        fn $fn_name(
+            &mut self,
+            arguments: &[crate::ast::CallArgument],
+            function: FunctionValue<'ctx>,
+            locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        ) -> Result<ExprValue<'ctx>> {
+            let arg_ptr =
+                self.compile_single_string_argument(arguments, function, locals, $builtin_name)?;
+            let func = self.$ensure_fn();
+            let raw = self
+                .call_function(func, &[arg_ptr.into()], $ffi_name)?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!(concat!($ffi_name, " returned no value")))?
+                .into_int_value();
+            let flag = self.i32_to_bool(raw, concat!($ffi_name, "_bool"))?;
+            Ok(ExprValue::Bool(flag))
+        }
+    };
+}
+
 // Many methods are for removed functionality but kept for potential future use
 #[allow(dead_code)]
 impl<'ctx> LlvmCodeGenerator<'ctx> {
@@ -1006,6 +1377,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             lambda_captures,
             lambda_signatures,
             struct_definitions,
+            enum_definitions,
             error_definitions,
             function_instances,
             struct_instances,
@@ -1013,6 +1385,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             struct_call_metadata,
             binding_types,
             type_test_metadata,
+            constants,
         } = metadata;
 
         let tea_string = context.opaque_struct_type("TeaString");
@@ -1105,9 +1478,11 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             struct_call_metadata_tc: struct_call_metadata,
             binding_types_tc: binding_types,
             type_test_metadata_tc: type_test_metadata,
+            constants_tc: constants,
             struct_field_variants: HashMap::new(),
             struct_variant_bases: HashMap::new(),
             struct_definitions_tc: struct_definitions,
+            enum_definitions_tc: enum_definitions,
             error_definitions_tc: error_definitions,
             generic_binding_stack: Vec::new(),
             lambda_functions: HashMap::new(),
@@ -1199,6 +1574,43 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             string_replace_fn: None,
             string_to_lower_fn: None,
             string_to_upper_fn: None,
+            string_chars_fn: None,
+            string_graphemes_fn: None,
+            string_code_points_fn: None,
+            string_char_count_fn: None,
+            string_display_width_fn: None,
+            string_fold_case_fn: None,
+            string_normalize_nfc_fn: None,
+            string_normalize_nfd_fn: None,
+            string_is_alpha_fn: None,
+            string_is_digit_fn: None,
+            string_is_whitespace_fn: None,
+            format_value_fn: None,
+            string_to_cstring_fn: None,
+            cstring_free_fn: None,
+            string_from_cstring_fn: None,
+            bytes_len_fn: None,
+            bytes_get_fn: None,
+            bytes_slice_fn: None,
+            bytes_equal_fn: None,
+            bytes_concat_fn: None,
+            crypto_digest_fn: None,
+            crypto_hmac_sha256_fn: None,
+            crypto_constant_time_eq_fn: None,
+            term_is_terminal_fn: None,
+            term_columns_fn: None,
+            term_rows_fn: None,
+            term_set_echo_fn: None,
+            bytes_from_list_fn: None,
+            bytes_to_list_fn: None,
+            bytes_from_string_fn: None,
+            bytes_to_string_fn: None,
+            bytes_to_string_lossy_fn: None,
+            bytes_is_utf8_fn: None,
+            bytes_to_hex_fn: None,
+            bytes_from_hex_fn: None,
+            bytes_to_base64_fn: None,
+            bytes_from_base64_fn: None,
             // Regex functions
             regex_compile_fn: None,
             regex_is_match_fn: None,
@@ -1234,6 +1646,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             process_wait_fn: None,
             process_kill_fn: None,
             process_read_stdout_fn: None,
+            process_read_stdout_line_fn: None,
             process_read_stderr_fn: None,
             process_read_stdout_bytes_fn: None,
             process_read_stderr_bytes_fn: None,
@@ -1271,6 +1684,10 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             fs_open_read_fn: None,
             fs_read_chunk_fn: None,
             fs_close_fn: None,
+            fs_read_line_fn: None,
+            random_seeded_fn: None,
+            random_int_fn: None,
+            random_float_fn: None,
             alloc_string_fn: None,
             alloc_list_fn: None,
             alloc_struct_fn: None,
@@ -1286,6 +1703,24 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             string_push_int_fn: None,
             string_slice_fn: None,
             list_slice_fn: None,
+            list_splice_fn: None,
+            list_sort_fn: None,
+            list_sort_by_fn: None,
+            list_comparator_count: 0,
+            list_reverse_fn: None,
+            list_unique_fn: None,
+            list_chunk_fn: None,
+            list_window_fn: None,
+            list_extend_fn: None,
+            list_join_fn: None,
+            list_sum_int_fn: None,
+            list_sum_float_fn: None,
+            list_min_fn: None,
+            list_max_fn: None,
+            list_insert_fn: None,
+            list_remove_at_fn: None,
+            list_pop_fn: None,
+            dict_group_append_fn: None,
             struct_set_fn: None,
             struct_get_fn: None,
             error_alloc_fn: None,
@@ -1332,19 +1767,30 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             io_write_fn: None,
             io_write_err_fn: None,
             io_flush_fn: None,
-            json_encode_fn: None,
-            json_decode_fn: None,
             http_send_fn: None,
+            task_spawn_fn: None,
+            task_join_fn: None,
+            channel_new_fn: None,
+            channel_send_fn: None,
+            channel_recv_fn: None,
+            channel_close_fn: None,
+            generator_new_fn: None,
+            generator_yield_fn: None,
+            iter_next_fn: None,
+            iter_close_fn: None,
+            task_entry_fns: Vec::new(),
             error_current_fn: None,
             error_set_current_fn: None,
             error_clear_current_fn: None,
             error_get_template_fn: None,
             error_mode_stack: vec![ErrorHandlingMode::Propagate],
             function_return_stack: Vec::new(),
+            owned_iterator_stack: Vec::new(),
             function_can_throw_stack: Vec::new(),
             global_slots: HashMap::new(),
             loop_context: None,
             simple_pure_functions: HashSet::new(),
+            unit_scope: None,
             manual_modulo_states: Vec::new(),
             list_len_ffi_fn: None,
             string_len_ffi_fn: None,
@@ -1380,6 +1826,10 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         self.ptr_type
     }
 
+    fn bytes_ptr_type(&self) -> PointerType<'ctx> {
+        self.ptr_type
+    }
+
     fn error_template_ptr_type(&self) -> PointerType<'ctx> {
         self.ptr_type
     }
@@ -1415,12 +1865,44 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
 
     fn push_function_return(&mut self, ty: ValueType) {
         self.function_return_stack.push(ty);
+        self.owned_iterator_stack.push(Vec::new());
     }
 
     fn pop_function_return(&mut self) {
         self.function_return_stack
             .pop()
             .expect("function return stack underflow");
+        self.owned_iterator_stack.pop();
+    }
+
+    fn push_owned_iterator(&mut self, handle: IntValue<'ctx>) {
+        if let Some(owned) = self.owned_iterator_stack.last_mut() {
+            owned.push(handle);
+        }
+    }
+
+    fn pop_owned_iterator(&mut self) {
+        if let Some(owned) = self.owned_iterator_stack.last_mut() {
+            owned.pop();
+        }
+    }
+
+    /// Close the iterators owned by the current function before it returns,
+    /// so abandoned generators do not keep their threads and files.
+    fn close_owned_iterators(&mut self) -> Result<()> {
+        let owned = self
+            .owned_iterator_stack
+            .last()
+            .cloned()
+            .unwrap_or_default();
+        if owned.is_empty() {
+            return Ok(());
+        }
+        let close_fn = self.ensure_iter_close_fn();
+        for handle in owned.into_iter().rev() {
+            self.call_function(close_fn, &[handle.into()], "tea_iter_close")?;
+        }
+        Ok(())
     }
 
     fn current_function_return_type(&self) -> &ValueType {
@@ -1469,6 +1951,9 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 Statement::Loop(loop_stmt) => {
                     count += self.count_statements(&loop_stmt.body.statements);
                 }
+                Statement::Guard(guard_stmt) => {
+                    count += self.count_statements(&guard_stmt.alternative.statements);
+                }
                 Statement::Match(match_stmt) => {
                     for arm in &match_stmt.arms {
                         count += self.count_statements(&arm.block.statements);
@@ -1550,6 +2035,10 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 Statement::Loop(loop_stmt) => {
                     self.find_mutated_in_statements(&loop_stmt.body.statements, mutated);
                 }
+                Statement::Guard(guard_stmt) => {
+                    self.find_mutated_in_expression(&guard_stmt.condition, mutated);
+                    self.find_mutated_in_statements(&guard_stmt.alternative.statements, mutated);
+                }
                 Statement::Match(match_stmt) => {
                     for arm in &match_stmt.arms {
                         self.find_mutated_in_statements(&arm.block.statements, mutated);
@@ -1659,7 +2148,12 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             }
             ExpressionKind::InterpolatedString(interp) => {
                 for part in &interp.parts {
-                    if let InterpolatedStringPart::Expression(expr) = part {
+                    if let InterpolatedStringPart::Expression(expr)
+                    | InterpolatedStringPart::Formatted(FormattedInterpolation {
+                        expression: expr,
+                        ..
+                    }) = part
+                    {
                         self.find_mutated_in_expression(expr, mutated);
                     }
                 }
@@ -1755,13 +2249,34 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         }
         for statement in &module_ast.statements {
             if let Statement::Function(func) = statement {
-                self.compile_function_variants(func)?;
+                if self.emits_function(&func.name) {
+                    self.compile_function_variants(func)?;
+                }
             }
         }
-        self.compile_main(&module_ast.statements)?;
+        if self.unit_scope.as_ref().is_none_or(|unit| unit.is_entry) {
+            self.compile_main(&module_ast.statements)?;
+        }
         Ok(())
     }
 
+    /// Functions owned by another unit are left as declarations for the linker.
+    fn emits_function(&self, name: &str) -> bool {
+        self.unit_scope
+            .as_ref()
+            .is_none_or(|unit| unit.functions.contains(name))
+    }
+
+    /// Linkage for struct and error templates. Catch arms compare template
+    /// addresses, so every unit must resolve a template to the same symbol.
+    fn template_linkage(&self) -> Linkage {
+        if self.unit_scope.is_some() {
+            Linkage::LinkOnceODR
+        } else {
+            Linkage::Private
+        }
+    }
+
     fn register_use(&mut self, use_stmt: &UseStatement) -> Result<()> {
         let module_path = use_stmt.module_path.as_str();
         if let Some(module) = stdlib::find_module(module_path) {
@@ -2036,6 +2551,11 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 if let Some(entry) = self.structs.get_mut(&struct_stmt.name) {
                     entry.field_names = field_names;
                     entry.field_types = field_types;
+                    entry.field_defaults = struct_stmt
+                        .fields
+                        .iter()
+                        .map(|field| field.default_value.clone())
+                        .collect();
                 }
                 if let Some(entry) = self.structs.get(&struct_stmt.name) {
                     if !entry.field_types.is_empty() {
@@ -2165,7 +2685,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 .add_global(self.tea_struct_template, None, &template_name);
         template_global.set_initializer(&template_value);
         template_global.set_constant(true);
-        template_global.set_linkage(Linkage::Private);
+        template_global.set_linkage(self.template_linkage());
         let template_ptr = template_global.as_pointer_value();
 
         if let Some(entry) = self.structs.get_mut(name) {
@@ -2202,9 +2722,17 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         let basic = self.basic_type(ty)?;
         let symbol = format!(".binding.{}", sanitize_symbol_component(name));
         let global = self.module.add_global(basic, None, &symbol);
-        global.set_linkage(Linkage::Private);
-        let initializer = self.zero_value_for_basic(&basic);
-        global.set_initializer(&initializer);
+        // Under separate compilation the entry unit defines every global and
+        // the other units link against it.
+        if self.unit_scope.is_some() {
+            global.set_linkage(Linkage::External);
+        } else {
+            global.set_linkage(Linkage::Private);
+        }
+        if self.unit_scope.as_ref().is_none_or(|unit| unit.is_entry) {
+            let initializer = self.zero_value_for_basic(&basic);
+            global.set_initializer(&initializer);
+        }
         self.global_slots.insert(
             name.to_string(),
             GlobalBindingSlot {
@@ -2346,7 +2874,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             .add_global(self.tea_error_template, None, &template_name);
         template_global.set_initializer(&template_value);
         template_global.set_constant(true);
-        template_global.set_linkage(Linkage::Private);
+        template_global.set_linkage(self.template_linkage());
         let template_ptr = template_global.as_pointer_value();
 
         if let Some(entry) = self
@@ -2395,6 +2923,9 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             if self.functions.contains_key(&function.name) {
                 return Ok(());
             }
+            if function.extern_function.is_some() {
+                return self.declare_extern_function(function);
+            }
 
             let return_type = match &function.return_type {
                 Some(expr) => self.parse_type(expr)?,
@@ -2407,7 +2938,12 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 let type_expr = param.type_annotation.as_ref().ok_or_else(|| {
                     anyhow!("parameter '{}' requires type annotation", param.name)
                 })?;
-                params.push(self.parse_type(type_expr)?);
+                let param_type = self.parse_type(type_expr)?;
+                if param.variadic {
+                    params.push(ValueType::List(Box::new(param_type)));
+                } else {
+                    params.push(param_type);
+                }
             }
 
             let fn_type = self.function_type(&return_type, &params)?;
@@ -2423,6 +2959,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                     value: fn_value,
                     return_type,
                     param_types: params,
+                    parameters: function.parameters.clone(),
                     can_throw,
                 },
             );
@@ -2466,6 +3003,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                     value: fn_value,
                     return_type,
                     param_types: params,
+                    parameters: function.parameters.clone(),
                     can_throw,
                 },
             );
@@ -2474,6 +3012,167 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         Ok(())
     }
 
+    /// Declare the Tea-ABI wrapper of an `extern def`. Calls reach the C function
+    /// through it, so call sites treat extern functions like any other function.
+    fn declare_extern_function(&mut self, function: &FunctionStatement) -> Result<()> {
+        let return_type = CAbiType::from_annotation(function.return_type.as_ref())?
+            .map_or(ValueType::Void, CAbiType::value_type);
+        let params = function
+            .parameters
+            .iter()
+            .map(|param| {
+                CAbiType::from_annotation(param.type_annotation.as_ref())?
+                    .map(CAbiType::value_type)
+                    .ok_or_else(|| anyhow!("parameter '{}' cannot have type Void", param.name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Converting a `CString` argument throws when the string holds a NUL byte.
+        let can_throw = function.parameters.iter().any(|param| {
+            matches!(
+                CAbiType::from_annotation(param.type_annotation.as_ref()),
+                Ok(Some(CAbiType::CString))
+            )
+        });
+
+        let fn_type = self.function_type(&return_type, &params)?;
+        let fn_value =
+            self.module
+                .add_function(&extern_wrapper_name(&function.name), fn_type, None);
+        add_function_attr(self.context, fn_value, "nounwind");
+
+        self.functions.insert(
+            function.name.clone(),
+            FunctionSignature {
+                value: fn_value,
+                return_type,
+                param_types: params,
+                parameters: function.parameters.clone(),
+                can_throw,
+            },
+        );
+        Ok(())
+    }
+
+    /// Compile the arguments of a direct function call in parameter order, binding
+    /// named arguments, filling omitted parameters from their defaults and packing
+    /// extra positional arguments into the variadic parameter's list.
+    fn compile_function_arguments(
+        &mut self,
+        signature: &FunctionSignature<'ctx>,
+        display_name: &str,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<Vec<BasicValueEnum<'ctx>>> {
+        let parameters = &signature.parameters;
+        let variadic = parameters.last().is_some_and(|param| param.variadic);
+        let fixed_count = if variadic {
+            parameters.len() - 1
+        } else {
+            parameters.len()
+        };
+        let positional_count = arguments
+            .iter()
+            .take_while(|argument| argument.name.is_none())
+            .count();
+        if positional_count > fixed_count && !variadic {
+            bail!(
+                "call to '{}' expects at most {} arguments, found {}",
+                display_name,
+                fixed_count,
+                positional_count
+            );
+        }
+
+        let mut values: Vec<Option<ExprValue<'ctx>>> = vec![None; parameters.len()];
+        for (index, argument) in arguments
+            .iter()
+            .take(positional_count.min(fixed_count))
+            .enumerate()
+        {
+            values[index] =
+                Some(self.compile_expression(&argument.expression, function, locals)?);
+        }
+        if variadic {
+            let extras = crate::ast::ListLiteral {
+                elements: arguments
+                    .iter()
+                    .take(positional_count)
+                    .skip(fixed_count)
+                    .map(|argument| argument.expression.clone())
+                    .collect(),
+            };
+            values[fixed_count] = Some(self.compile_list_literal(&extras, function, locals)?);
+        }
+        for argument in &arguments[positional_count..] {
+            let Some(name) = &argument.name else {
+                bail!(
+                    "positional arguments cannot follow named arguments in call to '{}'",
+                    display_name
+                );
+            };
+            let index = parameters[..fixed_count]
+                .iter()
+                .position(|param| &param.name == name)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "function '{}' has no parameter named '{}'",
+                        display_name,
+                        name
+                    )
+                })?;
+            values[index] =
+                Some(self.compile_expression(&argument.expression, function, locals)?);
+        }
+
+        // Defaults may refer to earlier parameters, so bind those as locals while
+        // compiling them.
+        let mut default_locals: HashMap<String, LocalVariable<'ctx>> = HashMap::new();
+        let mut args = Vec::with_capacity(parameters.len());
+        for (index, parameter) in parameters.iter().enumerate() {
+            let value = match values[index].take() {
+                Some(value) => value,
+                None => {
+                    let default = parameter.default_value.as_ref().ok_or_else(|| {
+                        anyhow!(
+                            "missing argument for parameter '{}' in call to '{}'",
+                            parameter.name,
+                            display_name
+                        )
+                    })?;
+                    self.compile_expression(default, function, &mut default_locals)?
+                }
+            };
+            let expected = &signature.param_types[index];
+            let converted = self
+                .convert_expr_to_type(value, expected)
+                .map_err(|error| {
+                    anyhow!(
+                        "argument {} to '{}' has mismatched type: {}",
+                        index + 1,
+                        display_name,
+                        error
+                    )
+                })?;
+            let basic = converted
+                .into_basic_value()
+                .ok_or_else(|| anyhow!("argument must produce a value"))?;
+            default_locals.insert(
+                parameter.name.clone(),
+                LocalVariable {
+                    pointer: None,
+                    value: Some(basic),
+                    ty: expected.clone(),
+                    mutable: false,
+                    string_builder: None,
+                },
+            );
+            args.push(basic);
+        }
+        Ok(args)
+    }
+
     fn compile_function_variants(&mut self, function: &FunctionStatement) -> Result<()> {
         if function.type_parameters.is_empty() {
             return self.compile_function_body(function, &function.name, None);
@@ -2491,6 +3190,193 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         Ok(())
     }
 
+    /// Emit the body of an `extern def` wrapper: convert each argument to its C
+    /// representation, call the C symbol and convert the result back. `CString`
+    /// arguments are copied into temporary buffers that are freed after the call;
+    /// one holding a NUL byte throws `ExternError.NulByte` before C is called.
+    fn compile_extern_wrapper(
+        &mut self,
+        function: &FunctionStatement,
+        extern_function: &ExternFunction,
+        signature: &FunctionSignature<'ctx>,
+    ) -> Result<()> {
+        let return_abi = CAbiType::from_annotation(function.return_type.as_ref())?;
+        let mut param_abis = Vec::with_capacity(function.parameters.len());
+        for param in &function.parameters {
+            let abi = CAbiType::from_annotation(param.type_annotation.as_ref())?
+                .ok_or_else(|| anyhow!("parameter '{}' cannot have type Void", param.name))?;
+            param_abis.push(abi);
+        }
+        let c_function =
+            self.declare_c_function(&extern_function.symbol, return_abi, &param_abis)?;
+
+        let entry = self.context.append_basic_block(signature.value, "entry");
+        self.builder.position_at_end(entry);
+
+        let mut arguments: Vec<BasicMetadataValueEnum<'ctx>> = Vec::with_capacity(param_abis.len());
+        let mut temporaries = Vec::new();
+        for (index, abi) in param_abis.iter().enumerate() {
+            let value = signature.value.get_nth_param(index as u32).expect("param");
+            value.set_name(&function.parameters[index].name);
+            let argument = match abi {
+                CAbiType::CString => {
+                    let to_cstring = self.ensure_string_to_cstring_fn();
+                    let cstring = self
+                        .call_function(to_cstring, &[value.into()], "cstring")?
+                        .try_as_basic_value()
+                        .left()
+                        .ok_or_else(|| anyhow!("tea_string_to_cstring returned no value"))?;
+                    self.throw_if_nul_byte(
+                        signature.value,
+                        cstring.into_pointer_value(),
+                        &temporaries,
+                        &function.name,
+                        &function.parameters[index].name,
+                    )?;
+                    temporaries.push(cstring);
+                    cstring
+                }
+                CAbiType::CPointer => map_builder_error(self.builder.build_int_to_ptr(
+                    value.into_int_value(),
+                    self.ptr_type,
+                    "pointer",
+                ))?
+                .into(),
+                CAbiType::Int | CAbiType::Float | CAbiType::Bool => value,
+            };
+            arguments.push(argument.into());
+        }
+
+        let call = self.call_function(c_function, &arguments, "")?;
+        let free_cstring = self.ensure_cstring_free_fn();
+        for temporary in temporaries {
+            self.call_function(free_cstring, &[temporary.into()], "")?;
+        }
+
+        let Some(return_abi) = return_abi else {
+            map_builder_error(self.builder.build_return(None))?;
+            return Ok(());
+        };
+        let result = call.try_as_basic_value().left().ok_or_else(|| {
+            anyhow!(
+                "extern function '{}' returned no value",
+                extern_function.symbol
+            )
+        })?;
+        let result: BasicValueEnum<'ctx> = match return_abi {
+            CAbiType::CString => {
+                let from_cstring = self.ensure_string_from_cstring_fn();
+                self.call_function(from_cstring, &[result.into()], "string")?
+                    .try_as_basic_value()
+                    .left()
+                    .ok_or_else(|| anyhow!("tea_string_from_cstring returned no value"))?
+            }
+            CAbiType::CPointer => map_builder_error(self.builder.build_ptr_to_int(
+                result.into_pointer_value(),
+                self.int_type(),
+                "address",
+            ))?
+            .into(),
+            CAbiType::Int | CAbiType::Float | CAbiType::Bool => result,
+        };
+        map_builder_error(self.builder.build_return(Some(&result)))?;
+        Ok(())
+    }
+
+    /// Branch to a block that throws `ExternError.NulByte` when `cstring` is null,
+    /// which is how `tea_string_to_cstring` reports a string holding a NUL byte.
+    /// The buffers already copied for earlier arguments are freed first.
+    fn throw_if_nul_byte(
+        &mut self,
+        wrapper: FunctionValue<'ctx>,
+        cstring: PointerValue<'ctx>,
+        temporaries: &[BasicValueEnum<'ctx>],
+        function_name: &str,
+        parameter_name: &str,
+    ) -> Result<()> {
+        let is_null = map_builder_error(self.builder.build_is_null(cstring, "cstring_is_null"))?;
+        let nul_block = self.context.append_basic_block(wrapper, "cstring_nul");
+        let ok_block = self.context.append_basic_block(wrapper, "cstring_ok");
+        map_builder_error(
+            self.builder
+                .build_conditional_branch(is_null, nul_block, ok_block),
+        )?;
+
+        self.builder.position_at_end(nul_block);
+        let free_cstring = self.ensure_cstring_free_fn();
+        for temporary in temporaries {
+            self.call_function(free_cstring, &[(*temporary).into()], "")?;
+        }
+        let message = self.compile_string_literal(&format!(
+            "argument '{}' of extern function '{}' contains a NUL byte",
+            parameter_name, function_name
+        ))?;
+        let error = self.build_error_instance(EXTERN_ERROR, "NulByte", vec![message])?;
+        let set_fn = self.ensure_error_set_current();
+        self.call_function(set_fn, &[error.into()], "error_set_current")?;
+        match wrapper.get_type().get_return_type() {
+            None => map_builder_error(self.builder.build_return(None))?,
+            Some(basic) => {
+                let zero = self.zero_value_for_basic(&basic);
+                map_builder_error(self.builder.build_return(Some(&zero)))?
+            }
+        };
+
+        self.builder.position_at_end(ok_block);
+        Ok(())
+    }
+
+    /// Declare a C function by symbol, reusing an existing declaration so two
+    /// modules binding the same symbol share it.
+    fn declare_c_function(
+        &self,
+        symbol: &str,
+        return_abi: Option<CAbiType>,
+        param_abis: &[CAbiType],
+    ) -> Result<FunctionValue<'ctx>> {
+        let c_type = |abi: CAbiType| -> BasicTypeEnum<'ctx> {
+            match abi {
+                CAbiType::Int => self.int_type().into(),
+                CAbiType::Float => self.float_type().into(),
+                CAbiType::Bool => self.bool_type().into(),
+                CAbiType::CString | CAbiType::CPointer => self.ptr_type.into(),
+            }
+        };
+        let params: Vec<BasicMetadataTypeEnum<'ctx>> =
+            param_abis.iter().map(|abi| c_type(*abi).into()).collect();
+        let fn_type = match return_abi {
+            Some(abi) => c_type(abi).fn_type(&params, false),
+            None => self.context.void_type().fn_type(&params, false),
+        };
+        if let Some(existing) = self.module.get_function(symbol) {
+            if existing.get_type() != fn_type {
+                bail!(
+                    "C symbol '{}' is already declared with a different signature",
+                    symbol
+                );
+            }
+            return Ok(existing);
+        }
+        let function = self
+            .module
+            .add_function(symbol, fn_type, Some(Linkage::External));
+        // C passes `bool` as a zero-extended byte.
+        for (index, abi) in param_abis.iter().enumerate() {
+            if *abi == CAbiType::Bool {
+                function.add_attribute(AttributeLoc::Param(index as u32), self.zeroext_attribute());
+            }
+        }
+        if return_abi == Some(CAbiType::Bool) {
+            function.add_attribute(AttributeLoc::Return, self.zeroext_attribute());
+        }
+        Ok(function)
+    }
+
+    fn zeroext_attribute(&self) -> Attribute {
+        self.context
+            .create_enum_attribute(Attribute::get_named_enum_kind_id("zeroext"), 0)
+    }
+
     fn compile_function_body(
         &mut self,
         function: &FunctionStatement,
@@ -2506,6 +3392,9 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         if signature.value.count_basic_blocks() > 0 {
             return Ok(());
         }
+        if let Some(extern_function) = &function.extern_function {
+            return self.compile_extern_wrapper(function, extern_function, &signature);
+        }
 
         let mut pushed_generics = false;
         if let Some(instance) = instance {
@@ -2578,6 +3467,10 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         }
 
         let result = (|| -> Result<()> {
+            if is_generator(function) {
+                return self.compile_generator_function(function, fn_name, &signature, &mut locals);
+            }
+
             let terminated = self.compile_block(
                 &function.body.statements,
                 signature.value,
@@ -2616,6 +3509,19 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                             function.name
                         )
                     }
+                    ValueType::Enum(name) => {
+                        bail!(
+                            "function '{}' may exit without returning {}",
+                            function.name,
+                            name
+                        )
+                    }
+                    ValueType::Bytes => {
+                        bail!(
+                            "function '{}' may exit without returning Bytes",
+                            function.name
+                        )
+                    }
                     ValueType::List(_) => {
                         bail!(
                             "function '{}' may exit without returning List",
@@ -2658,6 +3564,12 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                             function.name
                         )
                     }
+                    ValueType::Iterator(_) => {
+                        bail!(
+                            "function '{}' may exit without returning Iterator",
+                            function.name
+                        )
+                    }
                 }
             }
 
@@ -3059,6 +3971,9 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
     }
 
     fn function_is_simple_pure(function: &FunctionStatement) -> bool {
+        if function.extern_function.is_some() {
+            return false;
+        }
         let mut locals = function
             .parameters
             .iter()
@@ -3110,7 +4025,8 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 .as_ref()
                 .is_none_or(Self::expression_is_simple_pure),
             Statement::Conditional(cond) => {
-                Self::expression_is_simple_pure(&cond.condition)
+                cond.binding.is_none()
+                    && Self::expression_is_simple_pure(&cond.condition)
                     && Self::block_is_simple_pure(&cond.consequent, locals)
                     && cond
                         .alternative
@@ -3122,7 +4038,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                     Self::expression_is_simple_pure(condition)
                         && Self::block_is_simple_pure(&loop_stmt.body, locals)
                 }
-                LoopHeader::For { .. } => false,
+                LoopHeader::For { .. } | LoopHeader::Binding { .. } => false,
             },
             _ => false,
         }
@@ -3712,7 +4628,12 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             }
             ExpressionKind::InterpolatedString(template) => {
                 for part in &template.parts {
-                    if let InterpolatedStringPart::Expression(expr) = part {
+                    if let InterpolatedStringPart::Expression(expr)
+                    | InterpolatedStringPart::Formatted(FormattedInterpolation {
+                        expression: expr,
+                        ..
+                    }) = part
+                    {
                         Self::collect_loop_modulo_divisors(expr, index_name, divisors);
                     }
                 }
@@ -3796,6 +4717,10 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 matches!(
                     part,
                     InterpolatedStringPart::Expression(expr)
+                        | InterpolatedStringPart::Formatted(FormattedInterpolation {
+                            expression: expr,
+                            ..
+                        })
                         if Self::expression_assigns_identifier(expr, identifier)
                 )
             }),
@@ -3897,6 +4822,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 if is_last && !matches!(return_type, ValueType::Void) && value.ty() == *return_type
                 {
                     if let Some(basic) = value.into_basic_value() {
+                        self.close_owned_iterators()?;
                         self.clear_error_state()?;
                         map_builder_error(self.builder.build_return(Some(&basic)))?;
                         return Ok(true);
@@ -3918,6 +4844,10 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             Statement::Loop(loop_stmt) => {
                 self.compile_loop(loop_stmt, function, locals, return_type)
             }
+            Statement::Guard(guard_stmt) => {
+                self.compile_guard(guard_stmt, function, locals, return_type)?;
+                Ok(false)
+            }
             Statement::Struct(_) => Ok(false),
             Statement::Union(_) => Ok(false),
             Statement::Enum(_) => Ok(false),
@@ -3933,6 +4863,10 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 self.compile_throw(throw_stmt, function, locals, return_type)?;
                 Ok(true)
             }
+            Statement::Yield(yield_stmt) => {
+                self.compile_yield(yield_stmt, function, locals)?;
+                Ok(false)
+            }
             Statement::Break(_) => {
                 if let Some(ctx) = &self.loop_context {
                     map_builder_error(self.builder.build_unconditional_branch(ctx.exit_block))?;
@@ -4154,6 +5088,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         match (&statement.expression, return_type) {
             (Some(_), ValueType::Void) => bail!("return with value in void function"),
             (None, ValueType::Void) => {
+                self.close_owned_iterators()?;
                 self.clear_error_state()?;
                 map_builder_error(self.builder.build_return(None))?;
                 return Ok(());
@@ -4178,6 +5113,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 self.emit_return_value(converted, ty)
             }
             (None, ValueType::Int) => {
+                self.close_owned_iterators()?;
                 if let Some(ret_ty) = function.get_type().get_return_type() {
                     if let BasicTypeEnum::IntType(int_ty) = ret_ty {
                         let zero = int_ty.const_zero();
@@ -4227,9 +5163,18 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
         return_type: &ValueType,
     ) -> Result<bool> {
-        let condition = self
-            .compile_expression(&statement.condition, function, locals)?
-            .into_bool()?;
+        let (condition, bound_value) = match &statement.binding {
+            Some(_) => {
+                let (present, value, inner) =
+                    self.compile_optional_presence(&statement.condition, function, locals)?;
+                (present, Some((value, inner)))
+            }
+            None => (
+                self.compile_expression(&statement.condition, function, locals)?
+                    .into_bool()?,
+                None,
+            ),
+        };
 
         let then_block = self.context.append_basic_block(function, "if_then");
         let else_block = statement
@@ -4245,13 +5190,26 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         ))?;
 
         self.builder.position_at_end(then_block);
-        let then_terminated = self.compile_block(
-            &statement.consequent.statements,
-            function,
-            locals,
-            return_type,
-            true,
-        )?;
+        let then_terminated = match (&statement.binding, bound_value) {
+            (Some(binding), Some((value, inner))) => {
+                let mut scope_locals = locals.clone();
+                self.bind_optional_value(binding, value, inner, function, &mut scope_locals)?;
+                self.compile_block(
+                    &statement.consequent.statements,
+                    function,
+                    &mut scope_locals,
+                    return_type,
+                    false,
+                )?
+            }
+            _ => self.compile_block(
+                &statement.consequent.statements,
+                function,
+                locals,
+                return_type,
+                true,
+            )?,
+        };
         if !then_terminated {
             map_builder_error(self.builder.build_unconditional_branch(merge_block))?;
         }
@@ -4297,6 +5255,105 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         }
     }
 
+    fn compile_guard(
+        &mut self,
+        statement: &GuardStatement,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        return_type: &ValueType,
+    ) -> Result<()> {
+        let (condition, bound_value) = match &statement.binding {
+            Some(_) => {
+                let (present, value, inner) =
+                    self.compile_optional_presence(&statement.condition, function, locals)?;
+                (present, Some((value, inner)))
+            }
+            None => (
+                self.compile_expression(&statement.condition, function, locals)?
+                    .into_bool()?,
+                None,
+            ),
+        };
+
+        let else_block = self.context.append_basic_block(function, "guard_else");
+        let continue_block = self.context.append_basic_block(function, "guard_continue");
+        map_builder_error(self.builder.build_conditional_branch(
+            condition,
+            continue_block,
+            else_block,
+        ))?;
+
+        self.builder.position_at_end(else_block);
+        let else_terminated = self.compile_block(
+            &statement.alternative.statements,
+            function,
+            locals,
+            return_type,
+            true,
+        )?;
+        if !else_terminated {
+            bail!("guard else block must exit the enclosing scope");
+        }
+
+        self.builder.position_at_end(continue_block);
+        if let (Some(binding), Some((value, inner))) = (&statement.binding, bound_value) {
+            if locals.contains_key(&binding.name) {
+                bail!("variable '{}' already exists", binding.name);
+            }
+            self.bind_optional_value(binding, value, inner, function, locals)?;
+        }
+        Ok(())
+    }
+
+    /// Compile an optional value and test whether it holds a value, for `if var`,
+    /// `while var`, and `guard var`.
+    fn compile_optional_presence(
+        &mut self,
+        expression: &Expression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<(IntValue<'ctx>, StructValue<'ctx>, ValueType)> {
+        let ExprValue::Optional { value, inner } =
+            self.compile_expression(expression, function, locals)?
+        else {
+            bail!("optional binding requires an optional value");
+        };
+        let is_nil_fn = self.ensure_util_is_nil_fn();
+        let is_nil_raw = self
+            .call_function(is_nil_fn, &[value.into()], "binding_is_nil")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_util_is_nil returned no value"))?
+            .into_int_value();
+        let is_nil = self.i32_to_bool(is_nil_raw, "binding_is_nil_bool")?;
+        let present = map_builder_error(self.builder.build_not(is_nil, "binding_present"))?;
+        Ok((present, value, *inner))
+    }
+
+    fn bind_optional_value(
+        &mut self,
+        binding: &OptionalBinding,
+        optional_value: StructValue<'ctx>,
+        inner: ValueType,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<()> {
+        let unwrapped = self.tea_value_to_expr(optional_value, inner.clone())?;
+        let alloca = self.create_entry_alloca(function, &binding.name, self.basic_type(&inner)?)?;
+        self.store_expr_in_pointer(alloca, &inner, unwrapped, &binding.name)?;
+        locals.insert(
+            binding.name.clone(),
+            LocalVariable {
+                pointer: Some(alloca),
+                value: None,
+                ty: inner,
+                mutable: true,
+                string_builder: None,
+            },
+        );
+        Ok(())
+    }
+
     fn compile_loop(
         &mut self,
         statement: &LoopStatement,
@@ -4306,8 +5363,16 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
     ) -> Result<bool> {
         match &statement.header {
             LoopHeader::Condition(expr) => {
-                self.compile_while_loop(statement, expr, function, locals, return_type)
+                self.compile_while_loop(statement, expr, None, function, locals, return_type)
             }
+            LoopHeader::Binding { binding, value } => self.compile_while_loop(
+                statement,
+                value,
+                Some(binding),
+                function,
+                locals,
+                return_type,
+            ),
             LoopHeader::For { pattern, iterator } => {
                 self.compile_for_loop(statement, pattern, iterator, function, locals, return_type)
             }
@@ -4318,6 +5383,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         &mut self,
         statement: &LoopStatement,
         cond_expr: &Expression,
+        binding: Option<&OptionalBinding>,
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
         return_type: &ValueType,
@@ -4378,10 +5444,17 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
 
         self.builder.position_at_end(cond_block);
 
-        // Compile the loop condition
-        let cond_value = self
-            .compile_expression(cond_expr, function, locals)?
-            .into_bool()?;
+        // Compile the loop condition; `while var` loops run while the value is non-nil
+        let (cond_value, bound_value) = if binding.is_some() {
+            let (present, value, inner) =
+                self.compile_optional_presence(cond_expr, function, locals)?;
+            (present, Some((value, inner)))
+        } else {
+            let cond_value = self
+                .compile_expression(cond_expr, function, locals)?
+                .into_bool()?;
+            (cond_value, None)
+        };
 
         map_builder_error(
             self.builder
@@ -4390,6 +5463,16 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
 
         self.builder.position_at_end(body_block);
 
+        let mut scope_locals;
+        let locals = match (binding, bound_value) {
+            (Some(binding), Some((value, inner))) => {
+                scope_locals = locals.clone();
+                self.bind_optional_value(binding, value, inner, function, &mut scope_locals)?;
+                &mut scope_locals
+            }
+            _ => locals,
+        };
+
         let modulo_states = self.prepare_loop_modulo_states(statement, function, locals)?;
 
         // Set loop context for break/continue (continue goes to condition for while loops)
@@ -4449,20 +5532,38 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
         return_type: &ValueType,
     ) -> Result<bool> {
-        let current_block = self
-            .builder
-            .get_insert_block()
-            .ok_or_else(|| anyhow!("missing insertion block"))?;
-
-        // Create blocks for for-loop structure
-        let cond_block = self.context.append_basic_block(function, "for_cond");
+        // Compile the iterator expression to get the collection
+        let iterator_value = self.compile_expression(iterator, function, locals)?;
+        if let ExprValue::Iterator {
+            handle,
+            element_type,
+        } = iterator_value
+        {
+            // A loop over a named iterator leaves it open so it can be resumed
+            // later; one it created itself is closed however the loop exits.
+            let owned = !matches!(iterator.kind, ExpressionKind::Identifier(_));
+            return self.compile_iterator_for_loop(
+                statement,
+                pattern,
+                (handle, owned),
+                *element_type,
+                function,
+                locals,
+                return_type,
+            );
+        }
+
+        let current_block = self
+            .builder
+            .get_insert_block()
+            .ok_or_else(|| anyhow!("missing insertion block"))?;
+
+        // Create blocks for for-loop structure
+        let cond_block = self.context.append_basic_block(function, "for_cond");
         let body_block = self.context.append_basic_block(function, "for_body");
         let inc_block = self.context.append_basic_block(function, "for_inc");
         let exit_block = self.context.append_basic_block(function, "for_exit");
 
-        // Compile the iterator expression to get the collection
-        let iterator_value = self.compile_expression(iterator, function, locals)?;
-
         // Get the list pointer and element type, or dict info
         let (list_ptr, element_type, is_dict, dict_ptr, value_type) = match &iterator_value {
             ExprValue::List {
@@ -4507,42 +5608,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         .ok_or_else(|| anyhow!("expected i64 from list_len"))?
         .into_int_value();
 
-        // Identify variables that are mutated in the loop body
-        let mut mutated_vars = std::collections::HashSet::new();
-        self.find_mutated_in_statements(&statement.body.statements, &mut mutated_vars);
-
-        // For for-loops, convert SSA variables to alloca-based storage to handle
-        // continue correctly. When continue is called, it jumps to the increment
-        // block, and we need the current value of mutated variables to be available
-        // regardless of where continue was called from. Using allocas avoids PHI
-        // complexity with multiple paths to the increment block.
-        let mut converted_vars: Vec<(String, ValueType)> = Vec::new();
-        for var_name in &mutated_vars {
-            if let Some(var) = locals.get(var_name) {
-                if var.mutable && var.pointer.is_none() {
-                    // Variable uses SSA value, convert to alloca
-                    if let Some(ssa_value) = var.value {
-                        let alloca = self.create_entry_alloca(
-                            function,
-                            &format!("{}_for_loop", var_name),
-                            self.basic_type(&var.ty)?,
-                        )?;
-                        map_builder_error(self.builder.build_store(alloca, ssa_value))?;
-                        converted_vars.push((var_name.clone(), var.ty.clone()));
-                        locals.insert(
-                            var_name.clone(),
-                            LocalVariable {
-                                pointer: Some(alloca),
-                                value: None,
-                                ty: var.ty.clone(),
-                                mutable: true,
-                                string_builder: var.string_builder.clone(),
-                            },
-                        );
-                    }
-                }
-            }
-        }
+        self.spill_loop_mutations(statement, function, locals)?;
 
         // Branch to condition block
         map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
@@ -4731,6 +5797,170 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         Ok(false)
     }
 
+    /// Move variables mutated in a for-loop body from SSA values into allocas.
+    ///
+    /// When continue is called, it jumps to the increment block, and we need the
+    /// current value of mutated variables to be available regardless of where
+    /// continue was called from. Using allocas avoids PHI complexity with multiple
+    /// paths to the increment block.
+    fn spill_loop_mutations(
+        &mut self,
+        statement: &LoopStatement,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<()> {
+        let mut mutated_vars = std::collections::HashSet::new();
+        self.find_mutated_in_statements(&statement.body.statements, &mut mutated_vars);
+
+        for var_name in &mutated_vars {
+            if let Some(var) = locals.get(var_name) {
+                if var.mutable && var.pointer.is_none() {
+                    // Variable uses SSA value, convert to alloca
+                    if let Some(ssa_value) = var.value {
+                        let alloca = self.create_entry_alloca(
+                            function,
+                            &format!("{}_for_loop", var_name),
+                            self.basic_type(&var.ty)?,
+                        )?;
+                        map_builder_error(self.builder.build_store(alloca, ssa_value))?;
+                        locals.insert(
+                            var_name.clone(),
+                            LocalVariable {
+                                pointer: Some(alloca),
+                                value: None,
+                                ty: var.ty.clone(),
+                                mutable: true,
+                                string_builder: var.string_builder.clone(),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Compile `for item in iterator`, resuming the generator once per iteration.
+    #[allow(clippy::too_many_arguments)]
+    fn compile_iterator_for_loop(
+        &mut self,
+        statement: &LoopStatement,
+        pattern: &ForPattern,
+        (handle, owned): (IntValue<'ctx>, bool),
+        element_type: ValueType,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        return_type: &ValueType,
+    ) -> Result<bool> {
+        let ForPattern::Single(ident) = pattern else {
+            bail!("for loop with two variables requires a Dict iterator");
+        };
+
+        let cond_block = self.context.append_basic_block(function, "iter_cond");
+        let body_block = self.context.append_basic_block(function, "iter_body");
+        let exit_block = self.context.append_basic_block(function, "iter_exit");
+
+        self.spill_loop_mutations(statement, function, locals)?;
+        let next_alloca =
+            self.create_entry_alloca(function, "iter_next", self.value_type().into())?;
+        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
+
+        // === COND BLOCK ===
+        self.builder.position_at_end(cond_block);
+        let next_fn = self.ensure_iter_next_fn();
+        let produced = self
+            .call_function(
+                next_fn,
+                &[next_alloca.into(), handle.into()],
+                "tea_iter_next",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_iter_next returned no value"))?
+            .into_int_value();
+        let has_value = map_builder_error(self.builder.build_int_compare(
+            inkwell::IntPredicate::NE,
+            produced,
+            self.context.i32_type().const_zero(),
+            "iter_has_value",
+        ))?;
+        map_builder_error(
+            self.builder
+                .build_conditional_branch(has_value, body_block, exit_block),
+        )?;
+
+        // === BODY BLOCK ===
+        self.builder.position_at_end(body_block);
+        let tea_value = map_builder_error(self.builder.build_load(
+            self.value_type(),
+            next_alloca,
+            "iter_value",
+        ))?
+        .into_struct_value();
+        let element_value = self.tea_value_to_expr(tea_value, element_type.clone())?;
+        let basic_value = element_value
+            .into_basic_value()
+            .ok_or_else(|| anyhow!("loop variable cannot be void"))?;
+        locals.insert(
+            ident.name.clone(),
+            LocalVariable {
+                pointer: None,
+                value: Some(basic_value),
+                ty: element_type,
+                mutable: false,
+                string_builder: None,
+            },
+        );
+
+        let old_loop_context = self.loop_context.take();
+        self.loop_context = Some(LoopContext {
+            continue_block: cond_block,
+            exit_block,
+            modulo_states: Vec::new(),
+        });
+        if owned {
+            self.push_owned_iterator(handle);
+        }
+
+        let mut body = Ok(());
+        for (idx, stmt) in statement.body.statements.iter().enumerate() {
+            let is_last = idx == statement.body.statements.len() - 1;
+            match self.compile_statement(stmt, function, locals, return_type, is_last) {
+                Ok(false) => {}
+                Ok(true) => break,
+                Err(error) => {
+                    body = Err(error);
+                    break;
+                }
+            }
+        }
+
+        if owned {
+            self.pop_owned_iterator();
+        }
+        self.loop_context = old_loop_context;
+        body?;
+        locals.remove(&ident.name);
+
+        let body_terminated = self
+            .builder
+            .get_insert_block()
+            .ok_or_else(|| anyhow!("missing block"))?
+            .get_terminator()
+            .is_some();
+        if !body_terminated {
+            map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
+        }
+
+        // === EXIT BLOCK ===
+        self.builder.position_at_end(exit_block);
+        if owned {
+            let close_fn = self.ensure_iter_close_fn();
+            self.call_function(close_fn, &[handle.into()], "tea_iter_close")?;
+        }
+        Ok(false)
+    }
+
     /// Ensure the tea_list_len_ffi FFI function is declared
     fn ensure_list_len_ffi_fn(&mut self) -> FunctionValue<'ctx> {
         if let Some(func) = self.list_len_ffi_fn {
@@ -4891,7 +6121,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 .into_int_value();
                 Ok(ExprValue::Bool(bool_val))
             }
-            ValueType::String => {
+            ValueType::String | ValueType::Enum(_) => {
                 let value_as_string = self.ensure_value_as_string();
                 let str_ptr = map_builder_error(self.builder.build_call(
                     value_as_string,
@@ -5451,7 +6681,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         // Load the current collection (dict or list)
-        let (collection_ptr, collection_ty, is_local, _is_mutable) =
+        let (collection_ptr, collection_ty, _is_local, _is_mutable) =
             if let Some(variable) = locals.get(base_name) {
                 if !variable.mutable {
                     bail!("cannot mutate const '{}'", base_name);
@@ -5549,10 +6779,35 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
 
                 Ok(ExprValue::Void)
             }
-            ExprValue::List {
-                pointer,
-                element_type,
-            } => {
+            ExprValue::List { pointer, .. } if matches!(index.kind, ExpressionKind::Range(_)) => {
+                let ExpressionKind::Range(range) = &index.kind else {
+                    unreachable!()
+                };
+                if range.step.is_some() {
+                    bail!("slice assignment does not support a step");
+                }
+                let (start_value, end_value, _, inclusive_value) =
+                    self.compile_slice_bounds(range, function, locals)?;
+                let replacement = self.compile_expression(value, function, locals)?;
+                let replacement_ptr = match replacement {
+                    ExprValue::List { pointer, .. } => pointer,
+                    _ => bail!("slice assignment expects a List value"),
+                };
+                let splice_fn = self.ensure_list_splice_fn();
+                self.call_function(
+                    splice_fn,
+                    &[
+                        pointer.into(),
+                        start_value.into(),
+                        end_value.into(),
+                        inclusive_value.into(),
+                        replacement_ptr.into(),
+                    ],
+                    "list_splice",
+                )?;
+                Ok(ExprValue::Void)
+            }
+            ExprValue::List { pointer, .. } => {
                 // Compile the index expression
                 let index_expr = self.compile_expression(index, function, locals)?;
                 let index_value = index_expr.into_int()?;
@@ -5560,32 +6815,14 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 // Compile the new value
                 let new_value = self.compile_expression(value, function, locals)?;
 
-                // Call list_set which returns a new list
+                // list_set mutates the list in place
                 let list_set = self.ensure_list_set();
                 let tea_value = self.expr_to_tea_value(new_value)?;
-                let new_list_ptr = self
-                    .call_function(
-                        list_set,
-                        &[pointer.into(), index_value.into(), tea_value.into()],
-                        "list_set",
-                    )?
-                    .try_as_basic_value()
-                    .left()
-                    .ok_or_else(|| anyhow!("expected list pointer"))?
-                    .into_pointer_value();
-
-                // Store the new list back
-                let new_list = ExprValue::List {
-                    pointer: new_list_ptr,
-                    element_type,
-                };
-                self.store_expr_in_pointer(collection_ptr, &collection_ty, new_list, base_name)?;
-
-                if !is_local {
-                    if let Some(slot_mut) = self.global_slots.get_mut(base_name) {
-                        slot_mut.initialized = true;
-                    }
-                }
+                self.call_function(
+                    list_set,
+                    &[pointer.into(), index_value.into(), tea_value.into()],
+                    "list_set",
+                )?;
 
                 Ok(ExprValue::Void)
             }
@@ -5761,6 +6998,17 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         }
     }
 
+    fn expect_bytes_pointer(
+        &self,
+        expr: ExprValue<'ctx>,
+        context: &str,
+    ) -> Result<PointerValue<'ctx>> {
+        match expr {
+            ExprValue::Bytes(ptr) => Ok(ptr),
+            _ => bail!(context.to_string()),
+        }
+    }
+
     fn expect_int_value(&self, expr: ExprValue<'ctx>, context: &str) -> Result<IntValue<'ctx>> {
         match expr {
             ExprValue::Int(value) => Ok(value),
@@ -5840,14 +7088,8 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
 
         // Check if this is a slice operation (range index)
         if let ExpressionKind::Range(range) = &index.index.kind {
-            let start_expr = self.compile_expression(&range.start, function, locals)?;
-            let end_expr = self.compile_expression(&range.end, function, locals)?;
-            let start_value = start_expr.into_int()?;
-            let end_value = end_expr.into_int()?;
-            let inclusive_value = self
-                .context
-                .bool_type()
-                .const_int(if range.inclusive { 1 } else { 0 }, false);
+            let (start_value, end_value, step_value, inclusive_value) =
+                self.compile_slice_bounds(range, function, locals)?;
 
             match object {
                 ExprValue::String(string_ptr) => {
@@ -5859,6 +7101,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                                 string_ptr.into(),
                                 start_value.into(),
                                 end_value.into(),
+                                step_value.into(),
                                 inclusive_value.into(),
                             ],
                             "string_slice",
@@ -5881,6 +7124,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                                 pointer.into(),
                                 start_value.into(),
                                 end_value.into(),
+                                step_value.into(),
                                 inclusive_value.into(),
                             ],
                             "list_slice",
@@ -5894,7 +7138,27 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                         element_type,
                     })
                 }
-                _ => bail!("slicing expects a list or string value"),
+                ExprValue::Bytes(bytes_ptr) => {
+                    let slice_fn = self.ensure_bytes_slice_fn();
+                    let result_ptr = self
+                        .call_function(
+                            slice_fn,
+                            &[
+                                bytes_ptr.into(),
+                                start_value.into(),
+                                end_value.into(),
+                                step_value.into(),
+                                inclusive_value.into(),
+                            ],
+                            "bytes_slice",
+                        )?
+                        .try_as_basic_value()
+                        .left()
+                        .ok_or_else(|| anyhow!("expected Bytes from bytes_slice"))?
+                        .into_pointer_value();
+                    Ok(ExprValue::Bytes(result_ptr))
+                }
+                _ => bail!("slicing expects a list, string, or bytes value"),
             }
         } else {
             // Regular indexing
@@ -6001,17 +7265,91 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                         .into_pointer_value();
                     Ok(ExprValue::String(result_ptr))
                 }
-                _ => bail!("indexing expects a list, dict, or string value"),
+                ExprValue::Bytes(bytes_ptr) => {
+                    let key_expr = self.compile_expression(&index.index, function, locals)?;
+                    let index_value = key_expr.into_int()?;
+                    let bytes_get_fn = self.ensure_bytes_get_fn();
+                    let byte_value = self
+                        .call_function(
+                            bytes_get_fn,
+                            &[bytes_ptr.into(), index_value.into()],
+                            "bytes_get",
+                        )?
+                        .try_as_basic_value()
+                        .left()
+                        .ok_or_else(|| anyhow!("expected Int from bytes_get"))?
+                        .into_int_value();
+                    Ok(ExprValue::Int(byte_value))
+                }
+                _ => bail!("indexing expects a list, dict, string, or bytes value"),
             }
         }
     }
 
+    /// Lower slice bounds to runtime arguments: omitted bounds become `SLICE_OPEN_BOUND`
+    /// and an omitted step defaults to 1.
+    fn compile_slice_bounds(
+        &mut self,
+        range: &crate::ast::RangeExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<(
+        IntValue<'ctx>,
+        IntValue<'ctx>,
+        IntValue<'ctx>,
+        IntValue<'ctx>,
+    )> {
+        let i64_type = self.context.i64_type();
+        let open_bound = i64_type.const_int(SLICE_OPEN_BOUND as u64, true);
+        let start = match &range.start {
+            Some(start) => self
+                .compile_expression(start, function, locals)?
+                .into_int()?,
+            None => open_bound,
+        };
+        let end = match &range.end {
+            Some(end) => self.compile_expression(end, function, locals)?.into_int()?,
+            None => open_bound,
+        };
+        let step = match &range.step {
+            Some(step) => self
+                .compile_expression(step, function, locals)?
+                .into_int()?,
+            None => i64_type.const_int(1, false),
+        };
+        let inclusive = self
+            .context
+            .bool_type()
+            .const_int(if range.inclusive { 1 } else { 0 }, false);
+        Ok((start, end, step, inclusive))
+    }
+
     fn compile_member(
         &mut self,
         member: &crate::ast::MemberExpression,
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
+        // An enum variant is held as its name.
+        if let ExpressionKind::Identifier(ident) = &member.object.kind {
+            if !locals.contains_key(&ident.name) {
+                if let Some(definition) = self.enum_definitions_tc.get(&ident.name) {
+                    if !definition
+                        .variants
+                        .iter()
+                        .any(|variant| variant.name == member.property)
+                    {
+                        bail!(
+                            "enum '{}' has no variant named '{}'",
+                            ident.name,
+                            member.property
+                        );
+                    }
+                    return self.compile_string_literal(&member.property);
+                }
+            }
+        }
+
         let object = self.compile_expression(&member.object, function, locals)?;
         match object {
             ExprValue::Struct {
@@ -6734,10 +8072,13 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
 
         let fn_type = match &signature.return_type {
             ValueType::Void => self.context.void_type().fn_type(&param_types, false),
-            ValueType::Int => self.int_type().fn_type(&param_types, false),
+            ValueType::Int | ValueType::Iterator(_) => self.int_type().fn_type(&param_types, false),
             ValueType::Float => self.float_type().fn_type(&param_types, false),
             ValueType::Bool => self.bool_type().fn_type(&param_types, false),
-            ValueType::String => self.string_ptr_type().fn_type(&param_types, false),
+            ValueType::String | ValueType::Enum(_) => {
+                self.string_ptr_type().fn_type(&param_types, false)
+            }
+            ValueType::Bytes => self.bytes_ptr_type().fn_type(&param_types, false),
             ValueType::List(_) => self.list_ptr_type().fn_type(&param_types, false),
             ValueType::Dict(_) => self.dict_ptr_type().fn_type(&param_types, false),
             ValueType::Struct(_) => self.struct_ptr_type().fn_type(&param_types, false),
@@ -7016,6 +8357,29 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                         None => self.expr_to_string_pointer(value)?,
                     }
                 }
+                InterpolatedStringPart::Formatted(formatted) => {
+                    let value = self.compile_expression(&formatted.expression, function, locals)?;
+                    let value_ptr =
+                        self.build_tea_value_pointer(value, function, "format_value")?;
+                    let spec_ptr = self
+                        .compile_string_literal(&formatted.spec)?
+                        .into_string()?;
+                    let format_fn = self.ensure_format_value_fn();
+                    let part_ptr = self
+                        .call_function(
+                            format_fn,
+                            &[value_ptr.into(), spec_ptr.into()],
+                            "tea_format_value",
+                        )?
+                        .try_as_basic_value()
+                        .left()
+                        .ok_or_else(|| anyhow!("tea_format_value returned no value"))?
+                        .into_pointer_value();
+                    match current {
+                        Some(existing) => self.push_string_value(existing, part_ptr)?,
+                        None => part_ptr,
+                    }
+                }
             });
         }
 
@@ -7117,6 +8481,11 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             ExpressionKind::Identifier(ident) => {
                 if let Some(variable) = locals.get(ident.name.as_str()) {
                     self.load_local_variable(&ident.name, variable)
+                } else if let Some(value) = self.constants_tc.get(ident.name.as_str()) {
+                    // Folded constants compile to literals, so importers never
+                    // load another module's global.
+                    let literal = value.to_literal();
+                    self.compile_literal(&literal)
                 } else if let Some(slot) = self.global_slots.get(ident.name.as_str()).cloned() {
                     self.load_global_variable(&ident.name, slot)
                 } else {
@@ -7212,9 +8581,16 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
     ) -> Result<ExprValue<'ctx>> {
         match ty {
             ValueType::Int => Ok(ExprValue::Int(value.into_int_value())),
+            ValueType::Iterator(element_type) => Ok(ExprValue::Iterator {
+                handle: value.into_int_value(),
+                element_type: element_type.clone(),
+            }),
             ValueType::Float => Ok(ExprValue::Float(value.into_float_value())),
             ValueType::Bool => Ok(ExprValue::Bool(value.into_int_value())),
-            ValueType::String => Ok(ExprValue::String(value.into_pointer_value())),
+            ValueType::String | ValueType::Enum(_) => {
+                Ok(ExprValue::String(value.into_pointer_value()))
+            }
+            ValueType::Bytes => Ok(ExprValue::Bytes(value.into_pointer_value())),
             ValueType::List(inner) => Ok(ExprValue::List {
                 pointer: value.into_pointer_value(),
                 element_type: inner.clone(),
@@ -7263,6 +8639,14 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                     map_builder_error(self.builder.build_load(self.int_type(), pointer, name))?;
                 Ok(ExprValue::Int(loaded.into_int_value()))
             }
+            ValueType::Iterator(element_type) => {
+                let loaded =
+                    map_builder_error(self.builder.build_load(self.int_type(), pointer, name))?;
+                Ok(ExprValue::Iterator {
+                    handle: loaded.into_int_value(),
+                    element_type: element_type.clone(),
+                })
+            }
             ValueType::Float => {
                 let loaded =
                     map_builder_error(self.builder.build_load(self.float_type(), pointer, name))?;
@@ -7273,7 +8657,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                     map_builder_error(self.builder.build_load(self.bool_type(), pointer, name))?;
                 Ok(ExprValue::Bool(loaded.into_int_value()))
             }
-            ValueType::String => {
+            ValueType::String | ValueType::Enum(_) => {
                 let loaded = map_builder_error(self.builder.build_load(
                     self.string_ptr_type(),
                     pointer,
@@ -7281,13 +8665,21 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 ))?;
                 Ok(ExprValue::String(loaded.into_pointer_value()))
             }
-            ValueType::List(element_type) => {
+            ValueType::Bytes => {
                 let loaded = map_builder_error(self.builder.build_load(
-                    self.list_ptr_type(),
+                    self.bytes_ptr_type(),
                     pointer,
                     name,
                 ))?;
-                Ok(ExprValue::List {
+                Ok(ExprValue::Bytes(loaded.into_pointer_value()))
+            }
+            ValueType::List(element_type) => {
+                let loaded = map_builder_error(self.builder.build_load(
+                    self.list_ptr_type(),
+                    pointer,
+                    name,
+                ))?;
+                Ok(ExprValue::List {
                     pointer: loaded.into_pointer_value(),
                     element_type: element_type.clone(),
                 })
@@ -7448,20 +8840,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                     element_type,
                 } = &object
                 {
-                    if matches!(
-                        member.property.as_str(),
-                        "map"
-                            | "filter"
-                            | "reduce"
-                            | "find"
-                            | "any"
-                            | "all"
-                            | "contains"
-                            | "index_of"
-                            | "find_index"
-                            | "take"
-                            | "skip"
-                    ) {
+                    if LIST_METHODS.contains(&member.property.as_str()) {
                         return self.compile_list_method_call(
                             &member.property,
                             *pointer,
@@ -7531,38 +8910,17 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                     (identifier.name.clone(), identifier.name.clone())
                 };
                 if let Some(signature) = self.functions.get(&target_name).cloned() {
-                    if signature.param_types.len() != call.arguments.len() {
-                        bail!(
-                            "call to '{}' expects {} arguments, found {}",
-                            display_name,
-                            signature.param_types.len(),
-                            call.arguments.len()
-                        );
-                    }
-
-                    let mut args = Vec::with_capacity(call.arguments.len());
-                    for (index, argument) in call.arguments.iter().enumerate() {
-                        if argument.name.is_some() {
-                            bail!("named arguments are not supported by the LLVM backend yet");
-                        }
-                        let value =
-                            self.compile_expression(&argument.expression, function, locals)?;
-                        let expected = &signature.param_types[index];
-                        let converted =
-                            self.convert_expr_to_type(value, expected)
-                                .map_err(|error| {
-                                    anyhow!(
-                                        "argument {} to '{}' has mismatched type: {}",
-                                        index + 1,
-                                        display_name,
-                                        error
-                                    )
-                                })?;
-                        let basic = converted
-                            .into_basic_value()
-                            .ok_or_else(|| anyhow!("argument must produce a value"))?;
-                        args.push(basic.into());
-                    }
+                    let args = self
+                        .compile_function_arguments(
+                            &signature,
+                            &display_name,
+                            &call.arguments,
+                            function,
+                            locals,
+                        )?
+                        .into_iter()
+                        .map(|value| value.into())
+                        .collect::<Vec<_>>();
 
                     let call_site = self.call_function(signature.value, &args, &target_name)?;
 
@@ -7584,9 +8942,16 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
 
                     let expr = match signature.return_type {
                         ValueType::Int => ExprValue::Int(result.into_int_value()),
+                        ValueType::Iterator(element_type) => ExprValue::Iterator {
+                            handle: result.into_int_value(),
+                            element_type,
+                        },
                         ValueType::Float => ExprValue::Float(result.into_float_value()),
                         ValueType::Bool => ExprValue::Bool(result.into_int_value()),
-                        ValueType::String => ExprValue::String(result.into_pointer_value()),
+                        ValueType::String | ValueType::Enum(_) => {
+                            ExprValue::String(result.into_pointer_value())
+                        }
+                        ValueType::Bytes => ExprValue::Bytes(result.into_pointer_value()),
                         ValueType::List(inner) => ExprValue::List {
                             pointer: result.into_pointer_value(),
                             element_type: inner,
@@ -7745,6 +9110,39 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             StdFunctionKind::StringToUpper => {
                 self.compile_string_to_upper_call(&call.arguments, function, locals)
             }
+            StdFunctionKind::StringChars => {
+                self.compile_string_chars_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::StringGraphemes => {
+                self.compile_string_graphemes_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::StringCodePoints => {
+                self.compile_string_code_points_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::StringByteLen => {
+                self.compile_string_byte_len_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::StringDisplayWidth => {
+                self.compile_string_display_width_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::StringFoldCase => {
+                self.compile_string_fold_case_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::StringNormalizeNfc => {
+                self.compile_string_normalize_nfc_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::StringNormalizeNfd => {
+                self.compile_string_normalize_nfd_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::StringIsAlpha => {
+                self.compile_string_is_alpha_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::StringIsDigit => {
+                self.compile_string_is_digit_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::StringIsWhitespace => {
+                self.compile_string_is_whitespace_call(&call.arguments, function, locals)
+            }
             StdFunctionKind::MathFloor => {
                 self.compile_math_floor_call(&call.arguments, function, locals)
             }
@@ -7766,6 +9164,42 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             StdFunctionKind::MathMax => {
                 self.compile_math_max_call(&call.arguments, function, locals)
             }
+            StdFunctionKind::MathSin => {
+                self.compile_math_libm_call(&call.arguments, function, locals, "sin", "sin")
+            }
+            StdFunctionKind::MathCos => {
+                self.compile_math_libm_call(&call.arguments, function, locals, "cos", "cos")
+            }
+            StdFunctionKind::MathTan => {
+                self.compile_math_libm_call(&call.arguments, function, locals, "tan", "tan")
+            }
+            StdFunctionKind::MathExp => {
+                self.compile_math_libm_call(&call.arguments, function, locals, "exp", "exp")
+            }
+            StdFunctionKind::MathLn => {
+                self.compile_math_libm_call(&call.arguments, function, locals, "ln", "log")
+            }
+            StdFunctionKind::MathLog10 => {
+                self.compile_math_libm_call(&call.arguments, function, locals, "log10", "log10")
+            }
+            StdFunctionKind::MathLog2 => {
+                self.compile_math_libm_call(&call.arguments, function, locals, "log2", "log2")
+            }
+            StdFunctionKind::MathAtan2 => {
+                self.compile_math_libm_call(&call.arguments, function, locals, "atan2", "atan2")
+            }
+            StdFunctionKind::MathPow => {
+                self.compile_math_libm_call(&call.arguments, function, locals, "pow", "pow")
+            }
+            StdFunctionKind::MathHypot => {
+                self.compile_math_libm_call(&call.arguments, function, locals, "hypot", "hypot")
+            }
+            StdFunctionKind::MathIsNan => {
+                self.compile_math_is_nan_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::MathIsFinite => {
+                self.compile_math_is_finite_call(&call.arguments, function, locals)
+            }
             StdFunctionKind::EnvGet => self.compile_env_get_call(&call.arguments, function, locals),
             StdFunctionKind::EnvGetOr => {
                 self.compile_env_get_or_call(&call.arguments, function, locals)
@@ -7855,6 +9289,92 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             StdFunctionKind::FsReadText => {
                 self.compile_fs_read_text_call(&call.arguments, function, locals)
             }
+            StdFunctionKind::BytesFromList => {
+                self.compile_bytes_from_list_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::BytesToList => {
+                self.compile_bytes_to_list_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::BytesFromString => {
+                self.compile_bytes_from_string_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::BytesToString => {
+                self.compile_bytes_to_string_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::BytesToStringLossy => {
+                self.compile_bytes_to_string_lossy_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::BytesIsUtf8 => {
+                self.compile_bytes_is_utf8_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::BytesToHex => {
+                self.compile_bytes_to_hex_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::BytesFromHex => {
+                self.compile_bytes_from_hex_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::BytesToBase64 => {
+                self.compile_bytes_to_base64_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::BytesFromBase64 => {
+                self.compile_bytes_from_base64_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::BytesConcat => {
+                self.compile_bytes_concat_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::CryptoDigest => {
+                self.compile_crypto_digest_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::CryptoHmacSha256 => {
+                self.compile_crypto_hmac_sha256_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::CryptoConstantTimeEq => {
+                self.compile_crypto_constant_time_eq_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::TermIsTerminal => {
+                self.compile_term_is_terminal_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::TermColumns => {
+                let func = self.ensure_term_columns_fn();
+                self.compile_term_size_call("term_columns", func, &call.arguments)
+            }
+            StdFunctionKind::TermRows => {
+                let func = self.ensure_term_rows_fn();
+                self.compile_term_size_call("term_rows", func, &call.arguments)
+            }
+            StdFunctionKind::TermSetEcho => {
+                self.compile_term_set_echo_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::HttpServerBind
+            | StdFunctionKind::HttpServerAddress
+            | StdFunctionKind::HttpServerAccept
+            | StdFunctionKind::HttpServerRequest
+            | StdFunctionKind::HttpServerRespond
+            | StdFunctionKind::HttpServerShutdown
+            | StdFunctionKind::NetTcpConnect
+            | StdFunctionKind::NetTcpListen
+            | StdFunctionKind::NetUnixConnect
+            | StdFunctionKind::NetUnixListen
+            | StdFunctionKind::NetUdpBind
+            | StdFunctionKind::NetAccept
+            | StdFunctionKind::NetRead
+            | StdFunctionKind::NetReadLine
+            | StdFunctionKind::NetWrite
+            | StdFunctionKind::NetShutdownWrite
+            | StdFunctionKind::NetLocalAddress
+            | StdFunctionKind::NetPeerAddress
+            | StdFunctionKind::NetUdpSend
+            | StdFunctionKind::NetUdpReceive
+            | StdFunctionKind::NetUdpSender
+            | StdFunctionKind::NetClose
+            | StdFunctionKind::NetErrorKind
+            | StdFunctionKind::NetErrorMessage
+            | StdFunctionKind::BytesDecode
+            | StdFunctionKind::BytesDecodeError
+            | StdFunctionKind::JsonDecodeError
+            | StdFunctionKind::JsonDecodeErrorPath => {
+                self.compile_runtime_handle_call(kind, &call.arguments, function, locals)
+            }
             StdFunctionKind::FsReadBytes => {
                 self.compile_fs_read_bytes_call(&call.arguments, function, locals)
             }
@@ -7975,15 +9495,154 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             StdFunctionKind::RegexSplit => {
                 self.compile_regex_split_call(&call.arguments, function, locals)
             }
-            StdFunctionKind::JsonEncode => {
-                self.compile_json_encode_call(&call.arguments, function, locals)
+            StdFunctionKind::JsonEncode => self.compile_document_encode_call(
+                &call.arguments,
+                function,
+                locals,
+                "json_encode",
+                "tea_json_encode",
+            ),
+            StdFunctionKind::JsonDecode => self.compile_document_decode_call(
+                &call.arguments,
+                function,
+                locals,
+                "json_decode",
+                "tea_json_decode",
+            ),
+            StdFunctionKind::JsonEncodeWith => {
+                self.compile_json_encode_with_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::JsonDecodeAs => {
+                self.compile_json_decode_as_call(call, function, locals)
             }
-            StdFunctionKind::JsonDecode => {
-                self.compile_json_decode_call(&call.arguments, function, locals)
+            StdFunctionKind::YamlEncode => self.compile_document_encode_call(
+                &call.arguments,
+                function,
+                locals,
+                "yaml_encode",
+                "tea_yaml_encode",
+            ),
+            StdFunctionKind::YamlDecode => self.compile_document_decode_call(
+                &call.arguments,
+                function,
+                locals,
+                "yaml_decode",
+                "tea_yaml_decode",
+            ),
+            StdFunctionKind::TomlEncode => self.compile_document_encode_call(
+                &call.arguments,
+                function,
+                locals,
+                "toml_encode",
+                "tea_toml_encode",
+            ),
+            StdFunctionKind::TomlDecode => self.compile_document_decode_call(
+                &call.arguments,
+                function,
+                locals,
+                "toml_decode",
+                "tea_toml_decode",
+            ),
+            StdFunctionKind::CsvDecodeRecord => {
+                self.compile_csv_decode_record_call(call, function, locals)
             }
             StdFunctionKind::HttpSend => {
                 self.compile_http_send_call(&call.arguments, function, locals)
             }
+            StdFunctionKind::TaskSpawn => {
+                self.compile_task_spawn_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::TaskJoin => {
+                self.compile_task_join_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::ChannelNew => {
+                self.compile_channel_new_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::ChannelSend => {
+                self.compile_channel_send_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::ChannelRecv => {
+                self.compile_channel_recv_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::ChannelClose => {
+                self.compile_channel_close_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::IterNext => {
+                let func = self.ensure_iter_next_fn();
+                self.compile_handle_read_call("iter_next", func, &call.arguments, function, locals)
+            }
+            StdFunctionKind::FsOpenRead => {
+                self.compile_fs_open_read_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::FsReadLine => {
+                let func = self.ensure_fs_read_line_fn();
+                self.compile_handle_read_call(
+                    "fs_read_line",
+                    func,
+                    &call.arguments,
+                    function,
+                    locals,
+                )
+            }
+            StdFunctionKind::FsClose => {
+                self.compile_fs_close_call(&call.arguments, function, locals)
+            }
+            StdFunctionKind::ProcessReadStdoutLine => {
+                let func = self.ensure_process_read_stdout_line_fn();
+                self.compile_handle_read_call(
+                    "process_read_stdout_line",
+                    func,
+                    &call.arguments,
+                    function,
+                    locals,
+                )
+            }
+            StdFunctionKind::RandomSeeded => {
+                let func = self.ensure_random_seeded_fn();
+                self.compile_random_call("random_seeded", func, &call.arguments, function, locals)
+            }
+            StdFunctionKind::RandomInt => {
+                let func = self.ensure_random_int_fn();
+                self.compile_random_call("random_int", func, &call.arguments, function, locals)
+            }
+            StdFunctionKind::RandomFloat => {
+                let func = self.ensure_random_float_fn();
+                self.compile_random_call("random_float", func, &call.arguments, function, locals)
+            }
+        }
+    }
+
+    /// Random intrinsics take only Int arguments and return an Int or a Float.
+    fn compile_random_call(
+        &mut self,
+        name: &str,
+        func: FunctionValue<'ctx>,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        let expected = func.count_params() as usize;
+        if arguments.len() != expected {
+            bail!("{name} expects exactly {expected} arguments");
+        }
+        let mut values: Vec<BasicMetadataValueEnum<'ctx>> = Vec::with_capacity(expected);
+        for argument in arguments {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported for {name}");
+            }
+            let value = self.compile_expression(&argument.expression, function, locals)?;
+            let value = self.expect_int_value(value, &format!("{name} expects Int arguments"))?;
+            values.push(value.into());
+        }
+        let result = self
+            .call_function(func, &values, name)?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("{name} returned no value"))?;
+        if result.is_float_value() {
+            Ok(ExprValue::Float(result.into_float_value()))
+        } else {
+            Ok(ExprValue::Int(result.into_int_value()))
         }
     }
 
@@ -8228,16 +9887,15 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         // Fast path: inline length access for strings and lists
         match &value_expr {
             ExprValue::String(ptr) => {
-                // TeaString: { tag: i8, len: i8, data: [22 x i8] }
-                // For inline strings (tag=1): length is in field 1 as i8
-                // For heap strings (tag=0): length is in data[8..16] as i64
-                // Use FFI to handle both cases correctly
-                let func = self.ensure_string_len_ffi_fn();
+                // String length counts characters (Unicode scalar values) so it
+                // lines up with string indexing and slicing; byte length is
+                // available through string.byte_len.
+                let func = self.ensure_string_char_count_fn();
                 let length = self
-                    .call_function(func, &[(*ptr).into()], "tea_string_len_ffi")?
+                    .call_function(func, &[(*ptr).into()], "tea_string_char_count")?
                     .try_as_basic_value()
                     .left()
-                    .ok_or_else(|| anyhow!("tea_string_len_ffi returned no value"))?
+                    .ok_or_else(|| anyhow!("tea_string_char_count returned no value"))?
                     .into_int_value();
                 return Ok(ExprValue::Int(length));
             }
@@ -8255,6 +9913,16 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                     .into_int_value();
                 return Ok(ExprValue::Int(length));
             }
+            ExprValue::Bytes(ptr) => {
+                let func = self.ensure_bytes_len_fn();
+                let length = self
+                    .call_function(func, &[(*ptr).into()], "tea_bytes_len")?
+                    .try_as_basic_value()
+                    .left()
+                    .ok_or_else(|| anyhow!("tea_bytes_len returned no value"))?
+                    .into_int_value();
+                Ok(ExprValue::Int(length))
+            }
             _ => {
                 // Slow path: fall back to FFI for dicts and other types
                 let tea_value = self.expr_to_tea_value(value_expr)?;
@@ -8563,6 +10231,102 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         Ok(ExprValue::String(pointer))
     }
 
+    fn compile_single_string_argument(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        builtin_name: &str,
+    ) -> Result<PointerValue<'ctx>> {
+        if arguments.len() != 1 {
+            bail!("{builtin_name} expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for {builtin_name}");
+        }
+        let text_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        self.expect_string_pointer(
+            text_expr,
+            &format!("{builtin_name} expects a String argument"),
+        )
+    }
+
+    // Unicode string queries
+    compile_string_query_call!(
+        compile_string_chars_call,
+        ensure_string_chars_fn,
+        "tea_string_chars",
+        "string_chars",
+        list(ValueType::String)
+    );
+    compile_string_query_call!(
+        compile_string_graphemes_call,
+        ensure_string_graphemes_fn,
+        "tea_string_graphemes",
+        "string_graphemes",
+        list(ValueType::String)
+    );
+    compile_string_query_call!(
+        compile_string_code_points_call,
+        ensure_string_code_points_fn,
+        "tea_string_code_points",
+        "string_code_points",
+        list(ValueType::Int)
+    );
+    compile_string_query_call!(
+        compile_string_byte_len_call,
+        ensure_string_len_ffi_fn,
+        "tea_string_len_ffi",
+        "string_byte_len",
+        int
+    );
+    compile_string_query_call!(
+        compile_string_display_width_call,
+        ensure_string_display_width_fn,
+        "tea_string_display_width",
+        "string_display_width",
+        int
+    );
+    compile_string_query_call!(
+        compile_string_is_alpha_call,
+        ensure_string_is_alpha_fn,
+        "tea_string_is_alpha",
+        "string_is_alpha",
+        bool
+    );
+    compile_string_query_call!(
+        compile_string_is_digit_call,
+        ensure_string_is_digit_fn,
+        "tea_string_is_digit",
+        "string_is_digit",
+        bool
+    );
+    compile_string_query_call!(
+        compile_string_is_whitespace_call,
+        ensure_string_is_whitespace_fn,
+        "tea_string_is_whitespace",
+        "string_is_whitespace",
+        bool
+    );
+    compile_string_to_string_call!(
+        compile_string_fold_case_call,
+        ensure_string_fold_case_fn,
+        "tea_string_fold_case",
+        "string_fold_case"
+    );
+    compile_string_to_string_call!(
+        compile_string_normalize_nfc_call,
+        ensure_string_normalize_nfc_fn,
+        "tea_string_normalize_nfc",
+        "string_normalize_nfc"
+    );
+    compile_string_to_string_call!(
+        compile_string_normalize_nfd_call,
+        ensure_string_normalize_nfd_fn,
+        "tea_string_normalize_nfd",
+        "string_normalize_nfd"
+    );
+
     fn compile_math_floor_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
@@ -8776,7 +10540,17 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
 
         let (a_float, b_float) = match (a_expr, b_expr) {
             (ExprValue::Float(a), ExprValue::Float(b)) => (a, b),
-            _ => bail!("min expects two Float arguments"),
+            (ExprValue::Int(a), ExprValue::Int(b)) => {
+                let pick_a = map_builder_error(self.builder.build_int_compare(
+                    IntPredicate::SLT,
+                    a,
+                    b,
+                    "min_cmp",
+                ))?;
+                let result = map_builder_error(self.builder.build_select(pick_a, a, b, "min_int"))?;
+                return Ok(ExprValue::Int(result.into_int_value()));
+            }
+            _ => bail!("min expects two Int or two Float arguments"),
         };
 
         let func = self.ensure_fmin_fn();
@@ -8807,7 +10581,17 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
 
         let (a_float, b_float) = match (a_expr, b_expr) {
             (ExprValue::Float(a), ExprValue::Float(b)) => (a, b),
-            _ => bail!("max expects two Float arguments"),
+            (ExprValue::Int(a), ExprValue::Int(b)) => {
+                let pick_a = map_builder_error(self.builder.build_int_compare(
+                    IntPredicate::SGT,
+                    a,
+                    b,
+                    "max_cmp",
+                ))?;
+                let result = map_builder_error(self.builder.build_select(pick_a, a, b, "max_int"))?;
+                return Ok(ExprValue::Int(result.into_int_value()));
+            }
+            _ => bail!("max expects two Int or two Float arguments"),
         };
 
         let func = self.ensure_fmax_fn();
@@ -8821,6 +10605,99 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         Ok(ExprValue::Float(result))
     }
 
+    /// Lowers a Float math function onto the libm symbol of the same arity.
+    fn compile_math_libm_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        name: &str,
+        symbol: &str,
+    ) -> Result<ExprValue<'ctx>> {
+        let mut values: Vec<BasicMetadataValueEnum<'ctx>> = Vec::with_capacity(arguments.len());
+        for argument in arguments {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported for {name}");
+            }
+            match self.compile_expression(&argument.expression, function, locals)? {
+                ExprValue::Float(value) => values.push(value.into()),
+                _ => bail!("{name} expects Float arguments"),
+            }
+        }
+
+        let func = self.ensure_libm_fn(symbol, values.len());
+        let result = self
+            .call_function(func, &values, symbol)?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("{symbol} returned no value"))?
+            .into_float_value();
+
+        Ok(ExprValue::Float(result))
+    }
+
+    fn compile_math_is_nan_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 1 {
+            bail!("is_nan expects exactly 1 argument");
+        }
+        let float_val = match self.compile_expression(&arguments[0].expression, function, locals)? {
+            ExprValue::Float(v) => v,
+            _ => bail!("is_nan expects a Float argument"),
+        };
+
+        // Only NaN is unordered with itself.
+        let result = map_builder_error(self.builder.build_float_compare(
+            FloatPredicate::UNO,
+            float_val,
+            float_val,
+            "is_nan",
+        ))?;
+
+        Ok(ExprValue::Bool(result))
+    }
+
+    fn compile_math_is_finite_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 1 {
+            bail!("is_finite expects exactly 1 argument");
+        }
+        let float_val = match self.compile_expression(&arguments[0].expression, function, locals)? {
+            ExprValue::Float(v) => v,
+            _ => bail!("is_finite expects a Float argument"),
+        };
+
+        let abs_intrinsic = inkwell::intrinsics::Intrinsic::find("llvm.fabs")
+            .ok_or_else(|| anyhow!("llvm.fabs intrinsic not found"))?;
+        let intrinsic_fn = abs_intrinsic
+            .get_declaration(&self.module, &[self.float_type().into()])
+            .ok_or_else(|| anyhow!("failed to get llvm.fabs declaration"))?;
+        let magnitude = self
+            .call_function(intrinsic_fn, &[float_val.into()], "abs_result")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("abs returned no value"))?
+            .into_float_value();
+
+        // An ordered less-than comparison is false for both infinity and NaN.
+        let result = map_builder_error(self.builder.build_float_compare(
+            FloatPredicate::OLT,
+            magnitude,
+            self.float_type().const_float(f64::INFINITY),
+            "is_finite",
+        ))?;
+
+        Ok(ExprValue::Bool(result))
+    }
+
     fn compile_env_get_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
@@ -9116,2674 +10993,2605 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         })
     }
 
-    // Path string transforms (string -> string)
-    compile_string_to_string_call!(
-        compile_path_dirname_call,
-        ensure_path_dirname_fn,
-        "tea_path_dirname",
-        "path.dirname"
+    // Bytes conversions
+    compile_string_to_bytes_call!(
+        compile_bytes_from_string_call,
+        ensure_bytes_from_string_fn,
+        "tea_bytes_from_string",
+        "bytes.from_string"
     );
-    compile_string_to_string_call!(
-        compile_path_basename_call,
-        ensure_path_basename_fn,
-        "tea_path_basename",
-        "path.basename"
+    compile_string_to_bytes_call!(
+        compile_bytes_from_hex_call,
+        ensure_bytes_from_hex_fn,
+        "tea_bytes_from_hex",
+        "bytes.from_hex"
     );
-    compile_string_to_string_call!(
-        compile_path_extension_call,
-        ensure_path_extension_fn,
-        "tea_path_extension",
-        "path.extension"
+    compile_string_to_bytes_call!(
+        compile_bytes_from_base64_call,
+        ensure_bytes_from_base64_fn,
+        "tea_bytes_from_base64",
+        "bytes.from_base64"
+    );
+    compile_bytes_to_string_call!(
+        compile_bytes_to_string_call,
+        ensure_bytes_to_string_fn,
+        "tea_bytes_to_string",
+        "bytes.to_string"
+    );
+    compile_bytes_to_string_call!(
+        compile_bytes_to_string_lossy_call,
+        ensure_bytes_to_string_lossy_fn,
+        "tea_bytes_to_string_lossy",
+        "bytes.to_string_lossy"
+    );
+    compile_bytes_to_string_call!(
+        compile_bytes_to_hex_call,
+        ensure_bytes_to_hex_fn,
+        "tea_bytes_to_hex",
+        "bytes.to_hex"
+    );
+    compile_bytes_to_string_call!(
+        compile_bytes_to_base64_call,
+        ensure_bytes_to_base64_fn,
+        "tea_bytes_to_base64",
+        "bytes.to_base64"
     );
 
-    fn compile_path_set_extension_call(
+    fn compile_bytes_from_list_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 2 {
-            bail!("path.set_extension expects exactly 2 arguments");
+        if arguments.len() != 1 {
+            bail!("bytes.from_list expects exactly 1 argument");
         }
-        for argument in arguments {
-            if argument.name.is_some() {
-                bail!("named arguments are not supported for path.set_extension");
-            }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for bytes.from_list");
         }
-        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = match path_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("path.set_extension expects the path argument to be a String"),
-        };
-        let ext_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let ext_ptr = match ext_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("path.set_extension expects the extension argument to be a String"),
+        let list_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let list_value =
+            self.convert_expr_to_type(list_expr, &ValueType::List(Box::new(ValueType::Int)))?;
+        let list_ptr = match list_value {
+            ExprValue::List { pointer, .. } => pointer,
+            _ => bail!("bytes.from_list expects a List[Int] argument"),
         };
-        let func = self.ensure_path_set_extension_fn();
+        let func = self.ensure_bytes_from_list_fn();
         let pointer = self
-            .call_function(
-                func,
-                &[path_ptr.into(), ext_ptr.into()],
-                "tea_path_set_extension",
-            )?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_path_set_extension returned no value"))?
-            .into_pointer_value();
-        Ok(ExprValue::String(pointer))
-    }
-
-    compile_string_to_string_call!(
-        compile_path_strip_extension_call,
-        ensure_path_strip_extension_fn,
-        "tea_path_strip_extension",
-        "path.strip_extension"
-    );
-    compile_string_to_string_call!(
-        compile_path_normalize_call,
-        ensure_path_normalize_fn,
-        "tea_path_normalize",
-        "path.normalize"
-    );
-
-    fn compile_path_absolute_call(
-        &mut self,
-        arguments: &[crate::ast::CallArgument],
-        function: FunctionValue<'ctx>,
-        locals: &mut HashMap<String, LocalVariable<'ctx>>,
-    ) -> Result<ExprValue<'ctx>> {
-        if !(1..=2).contains(&arguments.len()) {
-            bail!("path.absolute expects 1 or 2 arguments");
-        }
-        for argument in arguments {
-            if argument.name.is_some() {
-                bail!("named arguments are not supported for path.absolute");
-            }
-        }
-        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = match path_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("path.absolute expects the path argument to be a String"),
-        };
-        let (base_ptr, has_base) = if arguments.len() == 2 {
-            let base_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-            let base_ptr = match base_expr {
-                ExprValue::String(ptr) => ptr,
-                _ => bail!("path.absolute expects the base argument to be a String"),
-            };
-            let flag = self.bool_to_i32(self.bool_type().const_all_ones(), "path_absolute_has")?;
-            (base_ptr, flag)
-        } else {
-            let null_ptr = self.string_ptr_type().const_null();
-            let flag = self.bool_to_i32(self.bool_type().const_zero(), "path_absolute_none")?;
-            (null_ptr, flag)
-        };
-        let func = self.ensure_path_absolute_fn();
-        let pointer = self
-            .call_function(
-                func,
-                &[path_ptr.into(), base_ptr.into(), has_base.into()],
-                "tea_path_absolute",
-            )?
+            .call_function(func, &[list_ptr.into()], "tea_bytes_from_list")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_path_absolute returned no value"))?
+            .ok_or_else(|| anyhow!("tea_bytes_from_list returned no value"))?
             .into_pointer_value();
-        Ok(ExprValue::String(pointer))
+        Ok(ExprValue::Bytes(pointer))
     }
 
-    fn compile_path_relative_call(
+    fn compile_bytes_to_list_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 2 {
-            bail!("path.relative expects exactly 2 arguments");
+        if arguments.len() != 1 {
+            bail!("bytes.to_list expects exactly 1 argument");
         }
-        for argument in arguments {
-            if argument.name.is_some() {
-                bail!("named arguments are not supported for path.relative");
-            }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for bytes.to_list");
         }
-        let target_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let target_ptr = match target_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("path.relative expects the target argument to be a String"),
-        };
-        let base_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let base_ptr = match base_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("path.relative expects the base argument to be a String"),
-        };
-        let func = self.ensure_path_relative_fn();
+        let bytes_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let bytes_ptr =
+            self.expect_bytes_pointer(bytes_expr, "bytes.to_list expects a Bytes argument")?;
+        let func = self.ensure_bytes_to_list_fn();
         let pointer = self
-            .call_function(
-                func,
-                &[target_ptr.into(), base_ptr.into()],
-                "tea_path_relative",
-            )?
+            .call_function(func, &[bytes_ptr.into()], "tea_bytes_to_list")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_path_relative returned no value"))?
+            .ok_or_else(|| anyhow!("tea_bytes_to_list returned no value"))?
             .into_pointer_value();
-        Ok(ExprValue::String(pointer))
+        Ok(ExprValue::List {
+            pointer,
+            element_type: Box::new(ValueType::Int),
+        })
     }
 
-    fn compile_path_is_absolute_call(
+    fn compile_bytes_is_utf8_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("path.is_absolute expects exactly 1 argument");
+            bail!("bytes.is_utf8 expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for path.is_absolute");
+            bail!("named arguments are not supported for bytes.is_utf8");
         }
-        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = match path_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("path.is_absolute expects the argument to be a String"),
-        };
-        let func = self.ensure_path_is_absolute_fn();
+        let bytes_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let bytes_ptr =
+            self.expect_bytes_pointer(bytes_expr, "bytes.is_utf8 expects a Bytes argument")?;
+        let func = self.ensure_bytes_is_utf8_fn();
         let raw = self
-            .call_function(func, &[path_ptr.into()], "tea_path_is_absolute")?
+            .call_function(func, &[bytes_ptr.into()], "tea_bytes_is_utf8")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_path_is_absolute returned no value"))?
+            .ok_or_else(|| anyhow!("tea_bytes_is_utf8 returned no value"))?
             .into_int_value();
-        let bool_val = self.i32_to_bool(raw, "tea_path_is_absolute_bool")?;
-        Ok(ExprValue::Bool(bool_val))
+        let value = self.i32_to_bool(raw, "bytes_is_utf8_bool")?;
+        Ok(ExprValue::Bool(value))
     }
 
-    fn compile_path_separator_call(
+    fn compile_bytes_concat_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if !arguments.is_empty() {
-            bail!("path.separator expects no arguments");
+        if arguments.len() != 2 {
+            bail!("bytes.concat expects exactly 2 arguments");
         }
-        let func = self.ensure_path_separator_fn();
+        for argument in arguments {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported for bytes.concat");
+            }
+        }
+        let left_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let left_ptr =
+            self.expect_bytes_pointer(left_expr, "bytes.concat expects Bytes arguments")?;
+        let right_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let right_ptr =
+            self.expect_bytes_pointer(right_expr, "bytes.concat expects Bytes arguments")?;
+        let func = self.ensure_bytes_concat_fn();
         let pointer = self
-            .call_function(func, &[], "tea_path_separator")?
+            .call_function(
+                func,
+                &[left_ptr.into(), right_ptr.into()],
+                "tea_bytes_concat",
+            )?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_path_separator returned no value"))?
+            .ok_or_else(|| anyhow!("tea_bytes_concat returned no value"))?
             .into_pointer_value();
-        Ok(ExprValue::String(pointer))
+        Ok(ExprValue::Bytes(pointer))
     }
 
-    compile_string_to_string_call!(
-        compile_url_encode_component_call,
-        ensure_url_encode_component_fn,
-        "tea_url_encode_component",
-        "url.encode_component"
-    );
-    compile_string_to_string_call!(
-        compile_url_decode_component_call,
-        ensure_url_decode_component_fn,
-        "tea_url_decode_component",
-        "url.decode_component"
-    );
-
-    fn compile_url_build_query_call(
+    fn compile_crypto_digest_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 1 {
-            bail!("url.build_query expects exactly 1 argument");
+        if arguments.len() != 2 {
+            bail!("crypto_digest expects exactly 2 arguments");
         }
-        if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for url.build_query");
+        for argument in arguments {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported for crypto_digest");
+            }
         }
-        let params_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let params_value =
-            self.convert_expr_to_type(params_expr, &ValueType::Dict(Box::new(ValueType::String)))?;
-        let params_ptr = match params_value {
-            ExprValue::Dict { pointer, .. } => pointer,
-            _ => bail!("url.build_query expects a Dict[String, String] argument"),
-        };
-        let func = self.ensure_url_build_query_fn();
+        let algorithm_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let algorithm_ptr = self.expect_string_pointer(
+            algorithm_expr,
+            "crypto_digest expects a String algorithm name",
+        )?;
+        let data_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let data_ptr =
+            self.expect_bytes_pointer(data_expr, "crypto_digest expects a Bytes argument")?;
+        let func = self.ensure_crypto_digest_fn();
         let pointer = self
-            .call_function(func, &[params_ptr.into()], "tea_url_build_query")?
+            .call_function(
+                func,
+                &[algorithm_ptr.into(), data_ptr.into()],
+                "tea_crypto_digest",
+            )?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_url_build_query returned no value"))?
+            .ok_or_else(|| anyhow!("tea_crypto_digest returned no value"))?
             .into_pointer_value();
-        Ok(ExprValue::String(pointer))
+        Ok(ExprValue::Bytes(pointer))
     }
 
-    fn compile_url_append_query_call(
+    fn compile_crypto_hmac_sha256_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 2 {
-            bail!("url.append_query expects exactly 2 arguments");
+            bail!("crypto_hmac_sha256 expects exactly 2 arguments");
         }
         for argument in arguments {
             if argument.name.is_some() {
-                bail!("named arguments are not supported for url.append_query");
+                bail!("named arguments are not supported for crypto_hmac_sha256");
             }
         }
-        let base_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let base_ptr = self.expect_string_pointer(
-            base_expr,
-            "url.append_query expects the base URL argument to be a String",
-        )?;
-        let params_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let params_value =
-            self.convert_expr_to_type(params_expr, &ValueType::Dict(Box::new(ValueType::String)))?;
-        let params_ptr = match params_value {
-            ExprValue::Dict { pointer, .. } => pointer,
-            _ => bail!("url.append_query expects a Dict[String, String] argument"),
-        };
-        let func = self.ensure_url_append_query_fn();
+        let key_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let key_ptr =
+            self.expect_bytes_pointer(key_expr, "crypto_hmac_sha256 expects Bytes arguments")?;
+        let message_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let message_ptr =
+            self.expect_bytes_pointer(message_expr, "crypto_hmac_sha256 expects Bytes arguments")?;
+        let func = self.ensure_crypto_hmac_sha256_fn();
         let pointer = self
             .call_function(
                 func,
-                &[base_ptr.into(), params_ptr.into()],
-                "tea_url_append_query",
+                &[key_ptr.into(), message_ptr.into()],
+                "tea_crypto_hmac_sha256",
             )?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_url_append_query returned no value"))?
+            .ok_or_else(|| anyhow!("tea_crypto_hmac_sha256 returned no value"))?
             .into_pointer_value();
-        Ok(ExprValue::String(pointer))
+        Ok(ExprValue::Bytes(pointer))
     }
 
-    fn compile_url_join_call(
+    fn compile_crypto_constant_time_eq_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 2 {
-            bail!("url.join expects exactly 2 arguments");
+            bail!("crypto_constant_time_eq expects exactly 2 arguments");
         }
         for argument in arguments {
             if argument.name.is_some() {
-                bail!("named arguments are not supported for url.join");
+                bail!("named arguments are not supported for crypto_constant_time_eq");
             }
         }
-        let base_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let base_ptr =
-            self.expect_string_pointer(base_expr, "url.join expects the base URL to be a String")?;
-        let next_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let next_ptr =
-            self.expect_string_pointer(next_expr, "url.join expects the path to be a String")?;
-        let func = self.ensure_url_join_fn();
-        let pointer = self
-            .call_function(func, &[base_ptr.into(), next_ptr.into()], "tea_url_join")?
+        let left_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let left_ptr = self
+            .expect_bytes_pointer(left_expr, "crypto_constant_time_eq expects Bytes arguments")?;
+        let right_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let right_ptr = self.expect_bytes_pointer(
+            right_expr,
+            "crypto_constant_time_eq expects Bytes arguments",
+        )?;
+        let func = self.ensure_crypto_constant_time_eq_fn();
+        let raw = self
+            .call_function(
+                func,
+                &[left_ptr.into(), right_ptr.into()],
+                "tea_crypto_constant_time_eq",
+            )?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_url_join returned no value"))?
-            .into_pointer_value();
-        Ok(ExprValue::String(pointer))
+            .ok_or_else(|| anyhow!("tea_crypto_constant_time_eq returned no value"))?
+            .into_int_value();
+        let value = self.i32_to_bool(raw, "crypto_constant_time_eq_bool")?;
+        Ok(ExprValue::Bool(value))
     }
 
-    fn compile_time_now_unix_millis_call(
+    /// Server and socket intrinsics pass handles and plain values straight to a
+    /// runtime function of the same name, so they share one lowering driven by the
+    /// signature in `std.intrinsics`. An `Any` result is written through an out
+    /// pointer so the runtime can return `nil`.
+    fn compile_runtime_handle_call(
         &mut self,
+        kind: StdFunctionKind,
         arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if !arguments.is_empty() {
-            bail!("time.now_unix_millis expects no arguments");
+        let signature = stdlib::intrinsic_function(kind)
+            .ok_or_else(|| anyhow!("{kind:?} is not an intrinsic function"))?;
+        let name = signature.name;
+        if arguments.len() != signature.params.len() {
+            bail!(
+                "{name} expects exactly {} arguments",
+                signature.params.len()
+            );
         }
-        let func = self.ensure_time_now_unix_millis_fn();
-        let value = self
-            .call_function(func, &[], "tea_time_now_unix_millis")?
+
+        let returns_value = signature.return_type == StdType::Any;
+        let result_alloca = if returns_value {
+            Some(self.create_entry_alloca(
+                function,
+                &format!("{name}_result"),
+                self.value_type().into(),
+            )?)
+        } else {
+            None
+        };
+        let mut values: Vec<BasicMetadataValueEnum<'ctx>> = Vec::new();
+        if let Some(alloca) = result_alloca {
+            values.push(alloca.into());
+        }
+        for (argument, param) in arguments.iter().zip(signature.params) {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported for {name}");
+            }
+            let expr = self.compile_expression(&argument.expression, function, locals)?;
+            let value: BasicMetadataValueEnum<'ctx> = match param {
+                StdType::Int => self
+                    .expect_int_value(expr, &format!("{name} expects an Int argument"))?
+                    .into(),
+                StdType::Bool => match expr {
+                    ExprValue::Bool(value) => self.bool_to_i32(value, name)?.into(),
+                    _ => bail!("{name} expects a Bool argument"),
+                },
+                StdType::String => self
+                    .expect_string_pointer(expr, &format!("{name} expects a String argument"))?
+                    .into(),
+                StdType::Bytes => self
+                    .expect_bytes_pointer(expr, &format!("{name} expects a Bytes argument"))?
+                    .into(),
+                StdType::Dict => match self
+                    .convert_expr_to_type(expr, &ValueType::Dict(Box::new(ValueType::String)))?
+                {
+                    ExprValue::Dict { pointer, .. } => pointer.into(),
+                    _ => bail!("{name} expects a Dict[String, String] argument"),
+                },
+                other => bail!("{name} has an unsupported {other:?} parameter"),
+            };
+            values.push(value);
+        }
+
+        let func = self.ensure_runtime_handle_fn(signature);
+        let result = self
+            .call_function(func, &values, &format!("tea_{name}"))?
             .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_time_now_unix_millis returned no value"))?
-            .into_int_value();
-        Ok(ExprValue::Int(value))
+            .left();
+        let returned = || anyhow!("tea_{name} returned no value");
+        match signature.return_type {
+            StdType::Void => Ok(ExprValue::Void),
+            StdType::Int => Ok(ExprValue::Int(
+                result.ok_or_else(returned)?.into_int_value(),
+            )),
+            StdType::Bool => {
+                let raw = result.ok_or_else(returned)?.into_int_value();
+                Ok(ExprValue::Bool(self.i32_to_bool(raw, name)?))
+            }
+            StdType::String => Ok(ExprValue::String(
+                result.ok_or_else(returned)?.into_pointer_value(),
+            )),
+            StdType::Bytes => Ok(ExprValue::Bytes(
+                result.ok_or_else(returned)?.into_pointer_value(),
+            )),
+            StdType::Dict => self.tea_value_to_expr(
+                result.ok_or_else(returned)?.into_struct_value(),
+                ValueType::Dict(Box::new(ValueType::Any)),
+            ),
+            StdType::Any => {
+                let alloca = result_alloca.ok_or_else(returned)?;
+                let value = map_builder_error(self.builder.build_load(
+                    self.value_type(),
+                    alloca,
+                    &format!("{name}_value"),
+                ))?
+                .into_struct_value();
+                Ok(ExprValue::Any { value })
+            }
+            other => bail!("{name} has an unsupported {other:?} result"),
+        }
     }
 
-    fn compile_time_unix_seconds_call(
+    fn compile_term_is_terminal_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("time.unix_seconds expects exactly 1 argument");
+            bail!("term_is_terminal expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for time.unix_seconds");
+            bail!("named arguments are not supported for term_is_terminal");
         }
-        let unix_millis_expr =
-            self.compile_expression(&arguments[0].expression, function, locals)?;
-        let unix_millis_value = self.expect_int_value(
-            unix_millis_expr,
-            "time.unix_seconds expects the unix_millis argument to be an Int",
-        )?;
-        let func = self.ensure_time_unix_seconds_fn();
-        let value = self
-            .call_function(func, &[unix_millis_value.into()], "tea_time_unix_seconds")?
+        let stream_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let stream =
+            self.expect_int_value(stream_expr, "term_is_terminal expects an Int stream")?;
+        let func = self.ensure_term_is_terminal_fn();
+        let raw = self
+            .call_function(func, &[stream.into()], "tea_term_is_terminal")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_time_unix_seconds returned no value"))?
+            .ok_or_else(|| anyhow!("tea_term_is_terminal returned no value"))?
             .into_int_value();
-        Ok(ExprValue::Int(value))
-    }
-
-    fn compile_time_sleep_ms_call(
-        &mut self,
-        arguments: &[crate::ast::CallArgument],
-        function: FunctionValue<'ctx>,
-        locals: &mut HashMap<String, LocalVariable<'ctx>>,
-    ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 1 {
-            bail!("time.sleep expects exactly 1 argument");
-        }
-        if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for time.sleep");
-        }
-        let milliseconds_expr =
-            self.compile_expression(&arguments[0].expression, function, locals)?;
-        let milliseconds_value = self.expect_int_value(
-            milliseconds_expr,
-            "time.sleep expects the milliseconds argument to be an Int",
-        )?;
-        let func = self.ensure_time_sleep_ms_fn();
-        self.call_function(func, &[milliseconds_value.into()], "tea_time_sleep_ms")?;
-        Ok(ExprValue::Void)
+        let value = self.i32_to_bool(raw, "term_is_terminal_bool")?;
+        Ok(ExprValue::Bool(value))
     }
 
-    fn compile_time_format_rfc3339_call(
+    /// `term_columns` and `term_rows` take no arguments and return an Int.
+    fn compile_term_size_call(
         &mut self,
+        name: &str,
+        func: FunctionValue<'ctx>,
         arguments: &[crate::ast::CallArgument],
-        function: FunctionValue<'ctx>,
-        locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 1 {
-            bail!("time.format_rfc3339 expects exactly 1 argument");
-        }
-        if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for time.format_rfc3339");
+        if !arguments.is_empty() {
+            bail!("{name} expects no arguments");
         }
-        let unix_millis_expr =
-            self.compile_expression(&arguments[0].expression, function, locals)?;
-        let unix_millis_value = self.expect_int_value(
-            unix_millis_expr,
-            "time.format_rfc3339 expects the unix_millis argument to be an Int",
-        )?;
-        let func = self.ensure_time_format_rfc3339_fn();
-        let pointer = self
-            .call_function(func, &[unix_millis_value.into()], "tea_time_format_rfc3339")?
+        let value = self
+            .call_function(func, &[], name)?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_time_format_rfc3339 returned no value"))?
-            .into_pointer_value();
-        Ok(ExprValue::String(pointer))
+            .ok_or_else(|| anyhow!("{name} returned no value"))?
+            .into_int_value();
+        Ok(ExprValue::Int(value))
     }
 
-    fn compile_time_parse_rfc3339_call(
+    fn compile_term_set_echo_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("time.parse_rfc3339 expects exactly 1 argument");
+            bail!("term_set_echo expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for time.parse_rfc3339");
+            bail!("named arguments are not supported for term_set_echo");
         }
-        let text_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let text_ptr = self.expect_string_pointer(
-            text_expr,
-            "time.parse_rfc3339 expects the text argument to be a String",
-        )?;
-
-        let func = self.ensure_time_parse_rfc3339_fn();
-        let value = self
-            .call_function(func, &[text_ptr.into()], "tea_time_parse_rfc3339")?
+        let enabled_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let enabled = match enabled_expr {
+            ExprValue::Bool(value) => self.bool_to_i32(value, "echo_enabled")?,
+            _ => bail!("term_set_echo expects a Bool argument"),
+        };
+        let func = self.ensure_term_set_echo_fn();
+        let raw = self
+            .call_function(func, &[enabled.into()], "tea_term_set_echo")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_time_parse_rfc3339 returned no value"))?
-            .into_struct_value();
-        self.tea_value_to_expr(value, ValueType::Dict(Box::new(ValueType::Any)))
+            .ok_or_else(|| anyhow!("tea_term_set_echo returned no value"))?
+            .into_int_value();
+        let value = self.i32_to_bool(raw, "term_set_echo_bool")?;
+        Ok(ExprValue::Bool(value))
     }
 
-    fn compile_io_read_bytes_call(
-        &mut self,
-        arguments: &[crate::ast::CallArgument],
-        _locals: &mut HashMap<String, LocalVariable<'ctx>>,
-    ) -> Result<ExprValue<'ctx>> {
-        if !arguments.is_empty() {
-            bail!("read_bytes expects no arguments");
-        }
-        let func = self.ensure_io_read_bytes();
-        let ptr = self
-            .call_function(func, &[], "tea_io_read_bytes")?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_io_read_bytes returned no value"))?
-            .into_pointer_value();
-        Ok(ExprValue::List {
-            pointer: ptr,
-            element_type: Box::new(ValueType::Int),
-        })
-    }
+    // Path string transforms (string -> string)
+    compile_string_to_string_call!(
+        compile_path_dirname_call,
+        ensure_path_dirname_fn,
+        "tea_path_dirname",
+        "path.dirname"
+    );
+    compile_string_to_string_call!(
+        compile_path_basename_call,
+        ensure_path_basename_fn,
+        "tea_path_basename",
+        "path.basename"
+    );
+    compile_string_to_string_call!(
+        compile_path_extension_call,
+        ensure_path_extension_fn,
+        "tea_path_extension",
+        "path.extension"
+    );
 
-    fn compile_cli_args_call(
+    fn compile_path_set_extension_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
-        _function: FunctionValue<'ctx>,
-        _locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if !arguments.is_empty() {
-            bail!("cli.args expects no arguments");
+        if arguments.len() != 2 {
+            bail!("path.set_extension expects exactly 2 arguments");
         }
-        let func = self.ensure_cli_args_fn();
+        for argument in arguments {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported for path.set_extension");
+            }
+        }
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr = match path_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("path.set_extension expects the path argument to be a String"),
+        };
+        let ext_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let ext_ptr = match ext_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("path.set_extension expects the extension argument to be a String"),
+        };
+        let func = self.ensure_path_set_extension_fn();
         let pointer = self
-            .call_function(func, &[], "tea_cli_args")?
+            .call_function(
+                func,
+                &[path_ptr.into(), ext_ptr.into()],
+                "tea_path_set_extension",
+            )?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_cli_args returned no value"))?
+            .ok_or_else(|| anyhow!("tea_path_set_extension returned no value"))?
             .into_pointer_value();
-        Ok(ExprValue::List {
-            pointer,
-            element_type: Box::new(ValueType::String),
-        })
+        Ok(ExprValue::String(pointer))
     }
 
-    fn compile_cli_parse_call(
+    compile_string_to_string_call!(
+        compile_path_strip_extension_call,
+        ensure_path_strip_extension_fn,
+        "tea_path_strip_extension",
+        "path.strip_extension"
+    );
+    compile_string_to_string_call!(
+        compile_path_normalize_call,
+        ensure_path_normalize_fn,
+        "tea_path_normalize",
+        "path.normalize"
+    );
+
+    fn compile_path_absolute_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.is_empty() || arguments.len() > 2 {
-            bail!("cli.parse expects 1 or 2 arguments");
+        if !(1..=2).contains(&arguments.len()) {
+            bail!("path.absolute expects 1 or 2 arguments");
         }
         for argument in arguments {
             if argument.name.is_some() {
-                bail!("named arguments are not supported for cli.parse");
+                bail!("named arguments are not supported for path.absolute");
             }
         }
-
-        let spec_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let spec_value = BasicMetadataValueEnum::from(self.expr_to_tea_value(spec_expr)?);
-
-        let override_value = if arguments.len() == 2 {
-            let value_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-            BasicMetadataValueEnum::from(self.expr_to_tea_value(value_expr)?)
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr = match path_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("path.absolute expects the path argument to be a String"),
+        };
+        let (base_ptr, has_base) = if arguments.len() == 2 {
+            let base_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+            let base_ptr = match base_expr {
+                ExprValue::String(ptr) => ptr,
+                _ => bail!("path.absolute expects the base argument to be a String"),
+            };
+            let flag = self.bool_to_i32(self.bool_type().const_all_ones(), "path_absolute_has")?;
+            (base_ptr, flag)
         } else {
-            let func_nil = self.ensure_value_nil();
-            let call = self.call_function(func_nil, &[], "val_nil")?;
-            let nil_value = call
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("tea_value_nil returned no value"))?;
-            BasicMetadataValueEnum::from(nil_value)
+            let null_ptr = self.string_ptr_type().const_null();
+            let flag = self.bool_to_i32(self.bool_type().const_zero(), "path_absolute_none")?;
+            (null_ptr, flag)
         };
-
-        let template_ptr = self.ensure_struct_template("CliParseResult")?;
-        let func = self.ensure_cli_parse_fn();
+        let func = self.ensure_path_absolute_fn();
         let pointer = self
             .call_function(
                 func,
-                &[template_ptr.into(), spec_value, override_value],
-                "tea_cli_parse",
+                &[path_ptr.into(), base_ptr.into(), has_base.into()],
+                "tea_path_absolute",
             )?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_cli_parse returned no value"))?
+            .ok_or_else(|| anyhow!("tea_path_absolute returned no value"))?
             .into_pointer_value();
-
-        Ok(ExprValue::Struct {
-            pointer,
-            struct_name: "CliParseResult".to_string(),
-        })
-    }
-
-    fn build_nil_value(&mut self) -> Result<BasicMetadataValueEnum<'ctx>> {
-        let func_nil = self.ensure_value_nil();
-        let call = self.call_function(func_nil, &[], "val_nil")?;
-        let nil_value = call
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_value_nil returned no value"))?;
-        Ok(BasicMetadataValueEnum::from(nil_value))
-    }
-
-    fn expr_to_metadata_value(
-        &mut self,
-        value: ExprValue<'ctx>,
-    ) -> Result<BasicMetadataValueEnum<'ctx>> {
-        let tea_value = self.expr_to_tea_value(value)?;
-        Ok(BasicMetadataValueEnum::from(tea_value))
+        Ok(ExprValue::String(pointer))
     }
 
-    fn compile_process_run_call(
+    fn compile_path_relative_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.is_empty() || arguments.len() > 5 {
-            bail!("process.run expects between 1 and 5 arguments");
+        if arguments.len() != 2 {
+            bail!("path.relative expects exactly 2 arguments");
         }
         for argument in arguments {
             if argument.name.is_some() {
-                bail!("named arguments are not supported for process.run");
+                bail!("named arguments are not supported for path.relative");
             }
         }
-
-        let tea_value_type = self
-            .context
-            .get_struct_type("TeaValue")
-            .ok_or_else(|| anyhow!("TeaValue type not found"))?;
-
-        let command_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let command_ptr = self.expect_string_pointer(
-            command_expr,
-            "process.run expects the command to be a String",
-        )?;
-
-        // Build args TeaValue and allocate on stack for ARM64 ABI compatibility
-        let args_tea_value = if arguments.len() >= 2 {
-            let expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-            self.expr_to_tea_value(expr)?
-        } else {
-            let nil_fn = self.ensure_value_nil();
-            self.call_function(nil_fn, &[], "val_nil")?
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("expected TeaValue"))?
-        };
-        let args_alloca = map_builder_error(self.builder.build_alloca(tea_value_type, "args_ptr"))?;
-        map_builder_error(self.builder.build_store(args_alloca, args_tea_value))?;
-
-        // Build env TeaValue and allocate on stack
-        let env_tea_value = if arguments.len() >= 3 {
-            let expr = self.compile_expression(&arguments[2].expression, function, locals)?;
-            self.expr_to_tea_value(expr)?
-        } else {
-            let nil_fn = self.ensure_value_nil();
-            self.call_function(nil_fn, &[], "val_nil")?
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("expected TeaValue"))?
-        };
-        let env_alloca = map_builder_error(self.builder.build_alloca(tea_value_type, "env_ptr"))?;
-        map_builder_error(self.builder.build_store(env_alloca, env_tea_value))?;
-
-        // Build cwd TeaValue and allocate on stack
-        let cwd_tea_value = if arguments.len() >= 4 {
-            let expr = self.compile_expression(&arguments[3].expression, function, locals)?;
-            self.expr_to_tea_value(expr)?
-        } else {
-            let nil_fn = self.ensure_value_nil();
-            self.call_function(nil_fn, &[], "val_nil")?
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("expected TeaValue"))?
+        let target_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let target_ptr = match target_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("path.relative expects the target argument to be a String"),
         };
-        let cwd_alloca = map_builder_error(self.builder.build_alloca(tea_value_type, "cwd_ptr"))?;
-        map_builder_error(self.builder.build_store(cwd_alloca, cwd_tea_value))?;
-
-        // Build stdin TeaValue and allocate on stack
-        let stdin_tea_value = if arguments.len() >= 5 {
-            let expr = self.compile_expression(&arguments[4].expression, function, locals)?;
-            self.expr_to_tea_value(expr)?
-        } else {
-            let nil_fn = self.ensure_value_nil();
-            self.call_function(nil_fn, &[], "val_nil")?
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("expected TeaValue"))?
+        let base_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let base_ptr = match base_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("path.relative expects the base argument to be a String"),
         };
-        let stdin_alloca =
-            map_builder_error(self.builder.build_alloca(tea_value_type, "stdin_ptr"))?;
-        map_builder_error(self.builder.build_store(stdin_alloca, stdin_tea_value))?;
-
-        let template_ptr = self.ensure_struct_template("ProcessResult")?;
-        let func = self.ensure_process_run_fn();
+        let func = self.ensure_path_relative_fn();
         let pointer = self
             .call_function(
                 func,
-                &[
-                    template_ptr.into(),
-                    command_ptr.as_basic_value_enum().into(),
-                    args_alloca.into(),
-                    env_alloca.into(),
-                    cwd_alloca.into(),
-                    stdin_alloca.into(),
-                ],
-                "tea_process_run",
+                &[target_ptr.into(), base_ptr.into()],
+                "tea_path_relative",
             )?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_process_run returned no value"))?
+            .ok_or_else(|| anyhow!("tea_path_relative returned no value"))?
             .into_pointer_value();
-
-        Ok(ExprValue::Struct {
-            pointer,
-            struct_name: "ProcessResult".to_string(),
-        })
+        Ok(ExprValue::String(pointer))
     }
 
-    fn compile_process_spawn_call(
+    fn compile_path_is_absolute_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.is_empty() || arguments.len() > 4 {
-            bail!("process.spawn expects between 1 and 4 arguments");
+        if arguments.len() != 1 {
+            bail!("path.is_absolute expects exactly 1 argument");
         }
-        for argument in arguments {
-            if argument.name.is_some() {
-                bail!("named arguments are not supported for process.spawn");
-            }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for path.is_absolute");
         }
-
-        let tea_value_type = self
-            .context
-            .get_struct_type("TeaValue")
-            .ok_or_else(|| anyhow!("TeaValue type not found"))?;
-
-        let command_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let command_ptr = self.expect_string_pointer(
-            command_expr,
-            "process.spawn expects the command to be a String",
-        )?;
-
-        // Build args TeaValue and allocate on stack for ARM64 ABI compatibility
-        let args_tea_value = if arguments.len() >= 2 {
-            let expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-            self.expr_to_tea_value(expr)?
-        } else {
-            let nil_fn = self.ensure_value_nil();
-            self.call_function(nil_fn, &[], "val_nil")?
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("expected TeaValue"))?
-        };
-        let args_alloca = map_builder_error(self.builder.build_alloca(tea_value_type, "args_ptr"))?;
-        map_builder_error(self.builder.build_store(args_alloca, args_tea_value))?;
-
-        // Build env TeaValue and allocate on stack
-        let env_tea_value = if arguments.len() >= 3 {
-            let expr = self.compile_expression(&arguments[2].expression, function, locals)?;
-            self.expr_to_tea_value(expr)?
-        } else {
-            let nil_fn = self.ensure_value_nil();
-            self.call_function(nil_fn, &[], "val_nil")?
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("expected TeaValue"))?
-        };
-        let env_alloca = map_builder_error(self.builder.build_alloca(tea_value_type, "env_ptr"))?;
-        map_builder_error(self.builder.build_store(env_alloca, env_tea_value))?;
-
-        // Build cwd TeaValue and allocate on stack
-        let cwd_tea_value = if arguments.len() >= 4 {
-            let expr = self.compile_expression(&arguments[3].expression, function, locals)?;
-            self.expr_to_tea_value(expr)?
-        } else {
-            let nil_fn = self.ensure_value_nil();
-            self.call_function(nil_fn, &[], "val_nil")?
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("expected TeaValue"))?
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr = match path_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("path.is_absolute expects the argument to be a String"),
         };
-        let cwd_alloca = map_builder_error(self.builder.build_alloca(tea_value_type, "cwd_ptr"))?;
-        map_builder_error(self.builder.build_store(cwd_alloca, cwd_tea_value))?;
-
-        let func = self.ensure_process_spawn_fn();
-        let value = self
-            .call_function(
-                func,
-                &[
-                    command_ptr.as_basic_value_enum().into(),
-                    args_alloca.into(),
-                    env_alloca.into(),
-                    cwd_alloca.into(),
-                ],
-                "tea_process_spawn",
-            )?
+        let func = self.ensure_path_is_absolute_fn();
+        let raw = self
+            .call_function(func, &[path_ptr.into()], "tea_path_is_absolute")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_process_spawn returned no value"))?
+            .ok_or_else(|| anyhow!("tea_path_is_absolute returned no value"))?
             .into_int_value();
+        let bool_val = self.i32_to_bool(raw, "tea_path_is_absolute_bool")?;
+        Ok(ExprValue::Bool(bool_val))
+    }
 
-        Ok(ExprValue::Int(value))
+    fn compile_path_separator_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+    ) -> Result<ExprValue<'ctx>> {
+        if !arguments.is_empty() {
+            bail!("path.separator expects no arguments");
+        }
+        let func = self.ensure_path_separator_fn();
+        let pointer = self
+            .call_function(func, &[], "tea_path_separator")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_path_separator returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::String(pointer))
     }
 
-    fn compile_process_kill_call(
+    compile_string_to_string_call!(
+        compile_url_encode_component_call,
+        ensure_url_encode_component_fn,
+        "tea_url_encode_component",
+        "url.encode_component"
+    );
+    compile_string_to_string_call!(
+        compile_url_decode_component_call,
+        ensure_url_decode_component_fn,
+        "tea_url_decode_component",
+        "url.decode_component"
+    );
+
+    fn compile_url_build_query_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("process.kill expects exactly 1 argument");
+            bail!("url.build_query expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for process.kill");
+            bail!("named arguments are not supported for url.build_query");
         }
-        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let handle_value =
-            self.expect_int_value(handle_expr, "process.kill expects the handle to be an Int")?;
-        let func = self.ensure_process_kill_fn();
-        let raw = self
-            .call_function(func, &[handle_value.into()], "tea_process_kill")?
+        let params_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let params_value =
+            self.convert_expr_to_type(params_expr, &ValueType::Dict(Box::new(ValueType::String)))?;
+        let params_ptr = match params_value {
+            ExprValue::Dict { pointer, .. } => pointer,
+            _ => bail!("url.build_query expects a Dict[String, String] argument"),
+        };
+        let func = self.ensure_url_build_query_fn();
+        let pointer = self
+            .call_function(func, &[params_ptr.into()], "tea_url_build_query")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_process_kill returned no value"))?
-            .into_int_value();
-        let bool_value = self.i32_to_bool(raw, "process_kill_result")?;
-        Ok(ExprValue::Bool(bool_value))
+            .ok_or_else(|| anyhow!("tea_url_build_query returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::String(pointer))
     }
 
-    fn compile_process_read_call(
+    fn compile_url_append_query_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
-        stdout: bool,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.is_empty() || arguments.len() > 2 {
-            bail!("process.read_* expects between 1 and 2 arguments");
+        if arguments.len() != 2 {
+            bail!("url.append_query expects exactly 2 arguments");
         }
         for argument in arguments {
             if argument.name.is_some() {
-                bail!("named arguments are not supported for process.read_*");
+                bail!("named arguments are not supported for url.append_query");
             }
         }
-        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let handle_value = self.expect_int_value(
-            handle_expr,
-            "process.read_* expects the handle to be an Int",
+        let base_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let base_ptr = self.expect_string_pointer(
+            base_expr,
+            "url.append_query expects the base URL argument to be a String",
         )?;
-        let size_value = if arguments.len() == 2 {
-            let expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-            self.expect_int_value(expr, "process.read_* expects bytes to be an Int")?
-        } else {
-            self.int_type().const_all_ones()
-        };
-        let func = if stdout {
-            self.ensure_process_read_stdout_fn()
-        } else {
-            self.ensure_process_read_stderr_fn()
+        let params_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let params_value =
+            self.convert_expr_to_type(params_expr, &ValueType::Dict(Box::new(ValueType::String)))?;
+        let params_ptr = match params_value {
+            ExprValue::Dict { pointer, .. } => pointer,
+            _ => bail!("url.append_query expects a Dict[String, String] argument"),
         };
+        let func = self.ensure_url_append_query_fn();
         let pointer = self
             .call_function(
                 func,
-                &[handle_value.into(), size_value.into()],
-                if stdout {
-                    "tea_process_read_stdout"
-                } else {
-                    "tea_process_read_stderr"
-                },
+                &[base_ptr.into(), params_ptr.into()],
+                "tea_url_append_query",
             )?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_process_read_* returned no value"))?
+            .ok_or_else(|| anyhow!("tea_url_append_query returned no value"))?
             .into_pointer_value();
         Ok(ExprValue::String(pointer))
     }
 
-    fn compile_process_read_bytes_call(
+    fn compile_url_join_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
-        stdout: bool,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 2 {
-            bail!("process.read_*_bytes expects exactly 2 arguments");
+            bail!("url.join expects exactly 2 arguments");
         }
         for argument in arguments {
             if argument.name.is_some() {
-                bail!("named arguments are not supported for process.read_*_bytes");
+                bail!("named arguments are not supported for url.join");
             }
         }
-        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let handle_value = self.expect_int_value(
-            handle_expr,
-            "process.read_*_bytes expects the handle to be an Int",
-        )?;
-        let size_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let size_value =
-            self.expect_int_value(size_expr, "process.read_*_bytes expects bytes to be an Int")?;
-        let func = if stdout {
-            self.ensure_process_read_stdout_bytes_fn()
-        } else {
-            self.ensure_process_read_stderr_bytes_fn()
-        };
+        let base_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let base_ptr =
+            self.expect_string_pointer(base_expr, "url.join expects the base URL to be a String")?;
+        let next_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let next_ptr =
+            self.expect_string_pointer(next_expr, "url.join expects the path to be a String")?;
+        let func = self.ensure_url_join_fn();
         let pointer = self
-            .call_function(
-                func,
-                &[handle_value.into(), size_value.into()],
-                if stdout {
-                    "tea_process_read_stdout_bytes"
-                } else {
-                    "tea_process_read_stderr_bytes"
-                },
-            )?
+            .call_function(func, &[base_ptr.into(), next_ptr.into()], "tea_url_join")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_process_read_*_bytes returned no value"))?
+            .ok_or_else(|| anyhow!("tea_url_join returned no value"))?
             .into_pointer_value();
-        Ok(ExprValue::List {
-            pointer,
-            element_type: Box::new(ValueType::Int),
-        })
+        Ok(ExprValue::String(pointer))
     }
 
-    fn compile_process_write_stdin_call(
+    fn compile_time_now_unix_millis_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
-        function: FunctionValue<'ctx>,
-        locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 2 {
-            bail!("process.write_stdin expects exactly 2 arguments");
-        }
-        if arguments[0].name.is_some() || arguments[1].name.is_some() {
-            bail!("named arguments are not supported for process.write_stdin");
+        if !arguments.is_empty() {
+            bail!("time.now_unix_millis expects no arguments");
         }
-        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let handle_value = self.expect_int_value(
-            handle_expr,
-            "process.write_stdin expects the handle to be an Int",
-        )?;
-        let data_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let data_value = self.expr_to_metadata_value(data_expr)?;
-        let func = self.ensure_process_write_stdin_fn();
-        self.call_function(
-            func,
-            &[handle_value.into(), data_value],
-            "tea_process_write_stdin",
-        )?;
-        Ok(ExprValue::Void)
+        let func = self.ensure_time_now_unix_millis_fn();
+        let value = self
+            .call_function(func, &[], "tea_time_now_unix_millis")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_time_now_unix_millis returned no value"))?
+            .into_int_value();
+        Ok(ExprValue::Int(value))
     }
 
-    fn compile_process_write_stdin_bytes_call(
+    fn compile_time_unix_seconds_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 2 {
-            bail!("process.write_stdin_bytes expects exactly 2 arguments");
+        if arguments.len() != 1 {
+            bail!("time.unix_seconds expects exactly 1 argument");
         }
-        if arguments[0].name.is_some() || arguments[1].name.is_some() {
-            bail!("named arguments are not supported for process.write_stdin_bytes");
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for time.unix_seconds");
         }
-        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let handle_value = self.expect_int_value(
-            handle_expr,
-            "process.write_stdin_bytes expects the handle to be an Int",
-        )?;
-        let data_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let data_value =
-            self.convert_expr_to_type(data_expr, &ValueType::List(Box::new(ValueType::Int)))?;
-        let data_ptr = match data_value {
-            ExprValue::List { pointer, .. } => pointer,
-            _ => bail!("process.write_stdin_bytes expects the data argument to be a List[Int]"),
-        };
-        let func = self.ensure_process_write_stdin_bytes_fn();
-        self.call_function(
-            func,
-            &[handle_value.into(), data_ptr.into()],
-            "tea_process_write_stdin_bytes",
+        let unix_millis_expr =
+            self.compile_expression(&arguments[0].expression, function, locals)?;
+        let unix_millis_value = self.expect_int_value(
+            unix_millis_expr,
+            "time.unix_seconds expects the unix_millis argument to be an Int",
         )?;
-        Ok(ExprValue::Void)
+        let func = self.ensure_time_unix_seconds_fn();
+        let value = self
+            .call_function(func, &[unix_millis_value.into()], "tea_time_unix_seconds")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_time_unix_seconds returned no value"))?
+            .into_int_value();
+        Ok(ExprValue::Int(value))
     }
 
-    fn compile_process_close_stdin_call(
+    fn compile_time_sleep_ms_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("process.close_stdin expects exactly 1 argument");
+            bail!("time.sleep expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for process.close_stdin");
+            bail!("named arguments are not supported for time.sleep");
         }
-        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let handle_value = self.expect_int_value(
-            handle_expr,
-            "process.close_stdin expects the handle to be an Int",
+        let milliseconds_expr =
+            self.compile_expression(&arguments[0].expression, function, locals)?;
+        let milliseconds_value = self.expect_int_value(
+            milliseconds_expr,
+            "time.sleep expects the milliseconds argument to be an Int",
         )?;
-        let func = self.ensure_process_close_stdin_fn();
-        self.call_function(func, &[handle_value.into()], "tea_process_close_stdin")?;
+        let func = self.ensure_time_sleep_ms_fn();
+        self.call_function(func, &[milliseconds_value.into()], "tea_time_sleep_ms")?;
         Ok(ExprValue::Void)
     }
 
-    fn compile_process_close_call(
+    fn compile_time_format_rfc3339_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("process.close expects exactly 1 argument");
+            bail!("time.format_rfc3339 expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for process.close");
+            bail!("named arguments are not supported for time.format_rfc3339");
         }
-        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let handle_value =
-            self.expect_int_value(handle_expr, "process.close expects the handle to be an Int")?;
-        let func = self.ensure_process_close_fn();
-        self.call_function(func, &[handle_value.into()], "tea_process_close")?;
-        Ok(ExprValue::Void)
+        let unix_millis_expr =
+            self.compile_expression(&arguments[0].expression, function, locals)?;
+        let unix_millis_value = self.expect_int_value(
+            unix_millis_expr,
+            "time.format_rfc3339 expects the unix_millis argument to be an Int",
+        )?;
+        let func = self.ensure_time_format_rfc3339_fn();
+        let pointer = self
+            .call_function(func, &[unix_millis_value.into()], "tea_time_format_rfc3339")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_time_format_rfc3339 returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::String(pointer))
     }
 
-    fn compile_process_wait_call(
+    fn compile_time_parse_rfc3339_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("process.wait expects exactly 1 argument");
+            bail!("time.parse_rfc3339 expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for process.wait");
+            bail!("named arguments are not supported for time.parse_rfc3339");
         }
-        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let handle_value =
-            self.expect_int_value(handle_expr, "process.wait expects the handle to be an Int")?;
+        let text_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let text_ptr = self.expect_string_pointer(
+            text_expr,
+            "time.parse_rfc3339 expects the text argument to be a String",
+        )?;
 
-        let template_ptr = self.ensure_struct_template("ProcessResult")?;
-        let func = self.ensure_process_wait_fn();
-        let pointer = self
-            .call_function(
-                func,
-                &[template_ptr.into(), handle_value.into()],
-                "tea_process_wait",
-            )?
+        let func = self.ensure_time_parse_rfc3339_fn();
+        let value = self
+            .call_function(func, &[text_ptr.into()], "tea_time_parse_rfc3339")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_process_wait returned no value"))?
-            .into_pointer_value();
-
-        Ok(ExprValue::Struct {
-            pointer,
-            struct_name: "ProcessResult".to_string(),
-        })
+            .ok_or_else(|| anyhow!("tea_time_parse_rfc3339 returned no value"))?
+            .into_struct_value();
+        self.tea_value_to_expr(value, ValueType::Dict(Box::new(ValueType::Any)))
     }
 
-    fn compile_process_read_stdout_call(
+    fn compile_io_read_bytes_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
-        function: FunctionValue<'ctx>,
-        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        _locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        self.compile_process_read_call(arguments, function, locals, true)
+        if !arguments.is_empty() {
+            bail!("read_bytes expects no arguments");
+        }
+        let func = self.ensure_io_read_bytes();
+        let ptr = self
+            .call_function(func, &[], "tea_io_read_bytes")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_io_read_bytes returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::Bytes(ptr))
     }
 
-    fn compile_process_read_stderr_call(
+    fn compile_cli_args_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
-        function: FunctionValue<'ctx>,
-        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        _function: FunctionValue<'ctx>,
+        _locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        self.compile_process_read_call(arguments, function, locals, false)
+        if !arguments.is_empty() {
+            bail!("cli.args expects no arguments");
+        }
+        let func = self.ensure_cli_args_fn();
+        let pointer = self
+            .call_function(func, &[], "tea_cli_args")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_cli_args returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::List {
+            pointer,
+            element_type: Box::new(ValueType::String),
+        })
     }
 
-    fn compile_process_read_stdout_bytes_call(
+    fn compile_cli_parse_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        self.compile_process_read_bytes_call(arguments, function, locals, true)
-    }
-
-    fn compile_process_read_stderr_bytes_call(
-        &mut self,
-        arguments: &[crate::ast::CallArgument],
-        function: FunctionValue<'ctx>,
-        locals: &mut HashMap<String, LocalVariable<'ctx>>,
-    ) -> Result<ExprValue<'ctx>> {
-        self.compile_process_read_bytes_call(arguments, function, locals, false)
-    }
-
-    fn compile_fs_read_text_call(
-        &mut self,
-        arguments: &[crate::ast::CallArgument],
-        function: FunctionValue<'ctx>,
-        locals: &mut HashMap<String, LocalVariable<'ctx>>,
-    ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 1 {
-            bail!("read_text expects exactly 1 argument");
+        if arguments.is_empty() || arguments.len() > 2 {
+            bail!("cli.parse expects 1 or 2 arguments");
         }
-        if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for read_text");
+        for argument in arguments {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported for cli.parse");
+            }
         }
-        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = match path_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("read_text expects the path argument to be a String"),
+
+        let spec_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let spec_value = BasicMetadataValueEnum::from(self.expr_to_tea_value(spec_expr)?);
+
+        let override_value = if arguments.len() == 2 {
+            let value_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+            BasicMetadataValueEnum::from(self.expr_to_tea_value(value_expr)?)
+        } else {
+            let func_nil = self.ensure_value_nil();
+            let call = self.call_function(func_nil, &[], "val_nil")?;
+            let nil_value = call
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!("tea_value_nil returned no value"))?;
+            BasicMetadataValueEnum::from(nil_value)
         };
-        let func = self.ensure_fs_read_text_fn();
+
+        let template_ptr = self.ensure_struct_template("CliParseResult")?;
+        let func = self.ensure_cli_parse_fn();
         let pointer = self
-            .call_function(func, &[path_ptr.into()], "tea_fs_read_text")?
+            .call_function(
+                func,
+                &[template_ptr.into(), spec_value, override_value],
+                "tea_cli_parse",
+            )?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_fs_read_text returned no value"))?
+            .ok_or_else(|| anyhow!("tea_cli_parse returned no value"))?
             .into_pointer_value();
-        Ok(ExprValue::String(pointer))
+
+        Ok(ExprValue::Struct {
+            pointer,
+            struct_name: "CliParseResult".to_string(),
+        })
     }
 
-    fn compile_fs_read_bytes_call(
-        &mut self,
-        arguments: &[crate::ast::CallArgument],
-        function: FunctionValue<'ctx>,
-        locals: &mut HashMap<String, LocalVariable<'ctx>>,
-    ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 1 {
-            bail!("read_bytes expects exactly 1 argument");
-        }
-        if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for read_bytes");
-        }
-        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = self.expect_string_pointer(
-            path_expr,
-            "read_bytes expects the path argument to be a String",
-        )?;
-        let func = self.ensure_fs_read_bytes_fn();
-        let pointer = self
-            .call_function(func, &[path_ptr.into()], "tea_fs_read_bytes")?
+    fn build_nil_value(&mut self) -> Result<BasicMetadataValueEnum<'ctx>> {
+        let func_nil = self.ensure_value_nil();
+        let call = self.call_function(func_nil, &[], "val_nil")?;
+        let nil_value = call
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_fs_read_bytes returned no value"))?
-            .into_pointer_value();
-        Ok(ExprValue::List {
-            pointer,
-            element_type: Box::new(ValueType::Int),
-        })
+            .ok_or_else(|| anyhow!("tea_value_nil returned no value"))?;
+        Ok(BasicMetadataValueEnum::from(nil_value))
     }
 
-    fn compile_fs_write_text_call(
+    fn expr_to_metadata_value(
         &mut self,
-        arguments: &[crate::ast::CallArgument],
-        function: FunctionValue<'ctx>,
-        locals: &mut HashMap<String, LocalVariable<'ctx>>,
-    ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 2 {
-            bail!("write_text expects exactly 2 arguments");
-        }
-        for argument in arguments {
-            if argument.name.is_some() {
-                bail!("named arguments are not supported for write_text");
-            }
-        }
-        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = match path_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("write_text expects the path argument to be a String"),
-        };
-        let contents_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let contents_ptr = match contents_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("write_text expects the contents argument to be a String"),
-        };
-        let func = self.ensure_fs_write_text_fn();
-        self.call_function(
-            func,
-            &[path_ptr.into(), contents_ptr.into()],
-            "tea_fs_write_text",
-        )?;
-        Ok(ExprValue::Void)
+        value: ExprValue<'ctx>,
+    ) -> Result<BasicMetadataValueEnum<'ctx>> {
+        let tea_value = self.expr_to_tea_value(value)?;
+        Ok(BasicMetadataValueEnum::from(tea_value))
     }
 
-    fn compile_fs_write_bytes_call(
+    fn compile_process_run_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 2 {
-            bail!("write_bytes expects exactly 2 arguments");
+        if arguments.is_empty() || arguments.len() > 5 {
+            bail!("process.run expects between 1 and 5 arguments");
         }
         for argument in arguments {
             if argument.name.is_some() {
-                bail!("named arguments are not supported for write_bytes");
+                bail!("named arguments are not supported for process.run");
             }
         }
-        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = self.expect_string_pointer(
-            path_expr,
-            "write_bytes expects the path argument to be a String",
+
+        let tea_value_type = self
+            .context
+            .get_struct_type("TeaValue")
+            .ok_or_else(|| anyhow!("TeaValue type not found"))?;
+
+        let command_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let command_ptr = self.expect_string_pointer(
+            command_expr,
+            "process.run expects the command to be a String",
         )?;
-        let data_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let data_value =
-            self.convert_expr_to_type(data_expr, &ValueType::List(Box::new(ValueType::Int)))?;
-        let data_ptr = match data_value {
-            ExprValue::List { pointer, .. } => pointer,
-            _ => bail!("write_bytes expects the data argument to be a List[Int]"),
+
+        // Build args TeaValue and allocate on stack for ARM64 ABI compatibility
+        let args_tea_value = if arguments.len() >= 2 {
+            let expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+            self.expr_to_tea_value(expr)?
+        } else {
+            let nil_fn = self.ensure_value_nil();
+            self.call_function(nil_fn, &[], "val_nil")?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!("expected TeaValue"))?
         };
-        let func = self.ensure_fs_write_bytes_fn();
-        self.call_function(
-            func,
-            &[path_ptr.into(), data_ptr.into()],
-            "tea_fs_write_bytes",
-        )?;
-        Ok(ExprValue::Void)
+        let args_alloca = map_builder_error(self.builder.build_alloca(tea_value_type, "args_ptr"))?;
+        map_builder_error(self.builder.build_store(args_alloca, args_tea_value))?;
+
+        // Build env TeaValue and allocate on stack
+        let env_tea_value = if arguments.len() >= 3 {
+            let expr = self.compile_expression(&arguments[2].expression, function, locals)?;
+            self.expr_to_tea_value(expr)?
+        } else {
+            let nil_fn = self.ensure_value_nil();
+            self.call_function(nil_fn, &[], "val_nil")?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!("expected TeaValue"))?
+        };
+        let env_alloca = map_builder_error(self.builder.build_alloca(tea_value_type, "env_ptr"))?;
+        map_builder_error(self.builder.build_store(env_alloca, env_tea_value))?;
+
+        // Build cwd TeaValue and allocate on stack
+        let cwd_tea_value = if arguments.len() >= 4 {
+            let expr = self.compile_expression(&arguments[3].expression, function, locals)?;
+            self.expr_to_tea_value(expr)?
+        } else {
+            let nil_fn = self.ensure_value_nil();
+            self.call_function(nil_fn, &[], "val_nil")?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!("expected TeaValue"))?
+        };
+        let cwd_alloca = map_builder_error(self.builder.build_alloca(tea_value_type, "cwd_ptr"))?;
+        map_builder_error(self.builder.build_store(cwd_alloca, cwd_tea_value))?;
+
+        // Build stdin TeaValue and allocate on stack
+        let stdin_tea_value = if arguments.len() >= 5 {
+            let expr = self.compile_expression(&arguments[4].expression, function, locals)?;
+            self.expr_to_tea_value(expr)?
+        } else {
+            let nil_fn = self.ensure_value_nil();
+            self.call_function(nil_fn, &[], "val_nil")?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!("expected TeaValue"))?
+        };
+        let stdin_alloca =
+            map_builder_error(self.builder.build_alloca(tea_value_type, "stdin_ptr"))?;
+        map_builder_error(self.builder.build_store(stdin_alloca, stdin_tea_value))?;
+
+        let template_ptr = self.ensure_struct_template("ProcessResult")?;
+        let func = self.ensure_process_run_fn();
+        let pointer = self
+            .call_function(
+                func,
+                &[
+                    template_ptr.into(),
+                    command_ptr.as_basic_value_enum().into(),
+                    args_alloca.into(),
+                    env_alloca.into(),
+                    cwd_alloca.into(),
+                    stdin_alloca.into(),
+                ],
+                "tea_process_run",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_process_run returned no value"))?
+            .into_pointer_value();
+
+        Ok(ExprValue::Struct {
+            pointer,
+            struct_name: "ProcessResult".to_string(),
+        })
     }
 
-    fn compile_fs_write_bytes_atomic_call(
+    fn compile_process_spawn_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 2 {
-            bail!("write_bytes_atomic expects exactly 2 arguments");
+        if arguments.is_empty() || arguments.len() > 4 {
+            bail!("process.spawn expects between 1 and 4 arguments");
         }
         for argument in arguments {
             if argument.name.is_some() {
-                bail!("named arguments are not supported for write_bytes_atomic");
+                bail!("named arguments are not supported for process.spawn");
             }
         }
-        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = self.expect_string_pointer(
-            path_expr,
-            "write_bytes_atomic expects the path argument to be a String",
-        )?;
-        let data_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let data_value =
-            self.convert_expr_to_type(data_expr, &ValueType::List(Box::new(ValueType::Int)))?;
-        let data_ptr = match data_value {
-            ExprValue::List { pointer, .. } => pointer,
-            _ => bail!("write_bytes_atomic expects the data argument to be a List[Int]"),
-        };
-        let func = self.ensure_fs_write_bytes_atomic_fn();
-        self.call_function(
-            func,
-            &[path_ptr.into(), data_ptr.into()],
-            "tea_fs_write_bytes_atomic",
+
+        let tea_value_type = self
+            .context
+            .get_struct_type("TeaValue")
+            .ok_or_else(|| anyhow!("TeaValue type not found"))?;
+
+        let command_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let command_ptr = self.expect_string_pointer(
+            command_expr,
+            "process.spawn expects the command to be a String",
         )?;
-        Ok(ExprValue::Void)
+
+        // Build args TeaValue and allocate on stack for ARM64 ABI compatibility
+        let args_tea_value = if arguments.len() >= 2 {
+            let expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+            self.expr_to_tea_value(expr)?
+        } else {
+            let nil_fn = self.ensure_value_nil();
+            self.call_function(nil_fn, &[], "val_nil")?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!("expected TeaValue"))?
+        };
+        let args_alloca = map_builder_error(self.builder.build_alloca(tea_value_type, "args_ptr"))?;
+        map_builder_error(self.builder.build_store(args_alloca, args_tea_value))?;
+
+        // Build env TeaValue and allocate on stack
+        let env_tea_value = if arguments.len() >= 3 {
+            let expr = self.compile_expression(&arguments[2].expression, function, locals)?;
+            self.expr_to_tea_value(expr)?
+        } else {
+            let nil_fn = self.ensure_value_nil();
+            self.call_function(nil_fn, &[], "val_nil")?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!("expected TeaValue"))?
+        };
+        let env_alloca = map_builder_error(self.builder.build_alloca(tea_value_type, "env_ptr"))?;
+        map_builder_error(self.builder.build_store(env_alloca, env_tea_value))?;
+
+        // Build cwd TeaValue and allocate on stack
+        let cwd_tea_value = if arguments.len() >= 4 {
+            let expr = self.compile_expression(&arguments[3].expression, function, locals)?;
+            self.expr_to_tea_value(expr)?
+        } else {
+            let nil_fn = self.ensure_value_nil();
+            self.call_function(nil_fn, &[], "val_nil")?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!("expected TeaValue"))?
+        };
+        let cwd_alloca = map_builder_error(self.builder.build_alloca(tea_value_type, "cwd_ptr"))?;
+        map_builder_error(self.builder.build_store(cwd_alloca, cwd_tea_value))?;
+
+        let func = self.ensure_process_spawn_fn();
+        let value = self
+            .call_function(
+                func,
+                &[
+                    command_ptr.as_basic_value_enum().into(),
+                    args_alloca.into(),
+                    env_alloca.into(),
+                    cwd_alloca.into(),
+                ],
+                "tea_process_spawn",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_process_spawn returned no value"))?
+            .into_int_value();
+
+        Ok(ExprValue::Int(value))
     }
 
-    fn compile_fs_append_text_call(
+    fn compile_process_kill_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 2 {
-            bail!("append_text expects exactly 2 arguments");
+        if arguments.len() != 1 {
+            bail!("process.kill expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for process.kill");
+        }
+        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let handle_value =
+            self.expect_int_value(handle_expr, "process.kill expects the handle to be an Int")?;
+        let func = self.ensure_process_kill_fn();
+        let raw = self
+            .call_function(func, &[handle_value.into()], "tea_process_kill")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_process_kill returned no value"))?
+            .into_int_value();
+        let bool_value = self.i32_to_bool(raw, "process_kill_result")?;
+        Ok(ExprValue::Bool(bool_value))
+    }
+
+    fn compile_process_read_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        stdout: bool,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.is_empty() || arguments.len() > 2 {
+            bail!("process.read_* expects between 1 and 2 arguments");
         }
         for argument in arguments {
             if argument.name.is_some() {
-                bail!("named arguments are not supported for append_text");
+                bail!("named arguments are not supported for process.read_*");
             }
         }
-        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = self.expect_string_pointer(
-            path_expr,
-            "append_text expects the path argument to be a String",
-        )?;
-        let contents_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let contents_ptr = self.expect_string_pointer(
-            contents_expr,
-            "append_text expects the contents argument to be a String",
-        )?;
-        let func = self.ensure_fs_append_text_fn();
-        self.call_function(
-            func,
-            &[path_ptr.into(), contents_ptr.into()],
-            "tea_fs_append_text",
+        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let handle_value = self.expect_int_value(
+            handle_expr,
+            "process.read_* expects the handle to be an Int",
         )?;
-        Ok(ExprValue::Void)
+        let size_value = if arguments.len() == 2 {
+            let expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+            self.expect_int_value(expr, "process.read_* expects bytes to be an Int")?
+        } else {
+            self.int_type().const_all_ones()
+        };
+        let func = if stdout {
+            self.ensure_process_read_stdout_fn()
+        } else {
+            self.ensure_process_read_stderr_fn()
+        };
+        let pointer = self
+            .call_function(
+                func,
+                &[handle_value.into(), size_value.into()],
+                if stdout {
+                    "tea_process_read_stdout"
+                } else {
+                    "tea_process_read_stderr"
+                },
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_process_read_* returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::String(pointer))
     }
 
-    fn compile_fs_append_bytes_call(
+    fn compile_process_read_bytes_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        stdout: bool,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 2 {
-            bail!("append_bytes expects exactly 2 arguments");
+            bail!("process.read_*_bytes expects exactly 2 arguments");
         }
         for argument in arguments {
             if argument.name.is_some() {
-                bail!("named arguments are not supported for append_bytes");
+                bail!("named arguments are not supported for process.read_*_bytes");
             }
         }
-        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = self.expect_string_pointer(
-            path_expr,
-            "append_bytes expects the path argument to be a String",
+        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let handle_value = self.expect_int_value(
+            handle_expr,
+            "process.read_*_bytes expects the handle to be an Int",
         )?;
-        let data_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let data_value =
-            self.convert_expr_to_type(data_expr, &ValueType::List(Box::new(ValueType::Int)))?;
-        let data_ptr = match data_value {
-            ExprValue::List { pointer, .. } => pointer,
-            _ => bail!("append_bytes expects the data argument to be a List[Int]"),
+        let size_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let size_value =
+            self.expect_int_value(size_expr, "process.read_*_bytes expects bytes to be an Int")?;
+        let func = if stdout {
+            self.ensure_process_read_stdout_bytes_fn()
+        } else {
+            self.ensure_process_read_stderr_bytes_fn()
         };
-        let func = self.ensure_fs_append_bytes_fn();
-        self.call_function(
-            func,
-            &[path_ptr.into(), data_ptr.into()],
-            "tea_fs_append_bytes",
-        )?;
-        Ok(ExprValue::Void)
+        let pointer = self
+            .call_function(
+                func,
+                &[handle_value.into(), size_value.into()],
+                if stdout {
+                    "tea_process_read_stdout_bytes"
+                } else {
+                    "tea_process_read_stderr_bytes"
+                },
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_process_read_*_bytes returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::Bytes(pointer))
     }
 
-    fn compile_fs_write_text_atomic_call(
+    fn compile_process_write_stdin_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 2 {
-            bail!("write_text_atomic expects exactly 2 arguments");
+            bail!("process.write_stdin expects exactly 2 arguments");
         }
-        for argument in arguments {
-            if argument.name.is_some() {
-                bail!("named arguments are not supported for write_text_atomic");
-            }
+        if arguments[0].name.is_some() || arguments[1].name.is_some() {
+            bail!("named arguments are not supported for process.write_stdin");
         }
-        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = match path_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("write_text_atomic expects the path argument to be a String"),
-        };
-        let contents_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let contents_ptr = match contents_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("write_text_atomic expects the contents argument to be a String"),
-        };
-        let func = self.ensure_fs_write_text_atomic_fn();
+        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let handle_value = self.expect_int_value(
+            handle_expr,
+            "process.write_stdin expects the handle to be an Int",
+        )?;
+        let data_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let data_value = self.expr_to_metadata_value(data_expr)?;
+        let func = self.ensure_process_write_stdin_fn();
         self.call_function(
             func,
-            &[path_ptr.into(), contents_ptr.into()],
-            "tea_fs_write_text_atomic",
+            &[handle_value.into(), data_value],
+            "tea_process_write_stdin",
         )?;
         Ok(ExprValue::Void)
     }
 
-    fn compile_fs_create_dir_call(
+    fn compile_process_write_stdin_bytes_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if !(1..=2).contains(&arguments.len()) {
-            bail!("create_dir expects 1 or 2 arguments");
+        if arguments.len() != 2 {
+            bail!("process.write_stdin_bytes expects exactly 2 arguments");
         }
-        for argument in arguments {
-            if argument.name.is_some() {
-                bail!("named arguments are not supported for create_dir");
-            }
+        if arguments[0].name.is_some() || arguments[1].name.is_some() {
+            bail!("named arguments are not supported for process.write_stdin_bytes");
         }
-        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = match path_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("create_dir expects the path argument to be a String"),
-        };
-        let recursive_flag = if arguments.len() == 2 {
-            let expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-            match expr {
-                ExprValue::Bool(flag) => flag,
-                _ => bail!("create_dir expects the optional flag to be a Bool"),
-            }
-        } else {
-            self.bool_type().const_zero()
-        };
-        let recursive_c = self.bool_to_i32(recursive_flag, "fs_create_dir_recursive")?;
-        let func = self.ensure_fs_create_dir_fn();
+        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let handle_value = self.expect_int_value(
+            handle_expr,
+            "process.write_stdin_bytes expects the handle to be an Int",
+        )?;
+        let data_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let data_ptr = self.expect_bytes_pointer(
+            data_expr,
+            "process.write_stdin_bytes expects the data argument to be Bytes",
+        )?;
+        let func = self.ensure_process_write_stdin_bytes_fn();
         self.call_function(
             func,
-            &[path_ptr.into(), recursive_c.into()],
-            "tea_fs_create_dir",
+            &[handle_value.into(), data_ptr.into()],
+            "tea_process_write_stdin_bytes",
         )?;
         Ok(ExprValue::Void)
     }
 
-    fn compile_fs_create_temp_dir_call(
+    fn compile_process_close_stdin_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("create_temp_dir expects exactly 1 argument");
+            bail!("process.close_stdin expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for create_temp_dir");
+            bail!("named arguments are not supported for process.close_stdin");
         }
-        let prefix_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let prefix_ptr = self.expect_string_pointer(
-            prefix_expr,
-            "create_temp_dir expects the prefix argument to be a String",
+        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let handle_value = self.expect_int_value(
+            handle_expr,
+            "process.close_stdin expects the handle to be an Int",
         )?;
-        let func = self.ensure_fs_create_temp_dir_fn();
-        let pointer = self
-            .call_function(func, &[prefix_ptr.into()], "tea_fs_create_temp_dir")?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_fs_create_temp_dir returned no value"))?
-            .into_pointer_value();
-        Ok(ExprValue::String(pointer))
+        let func = self.ensure_process_close_stdin_fn();
+        self.call_function(func, &[handle_value.into()], "tea_process_close_stdin")?;
+        Ok(ExprValue::Void)
     }
 
-    fn compile_fs_create_temp_file_call(
+    fn compile_process_close_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("create_temp_file expects exactly 1 argument");
+            bail!("process.close expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for create_temp_file");
+            bail!("named arguments are not supported for process.close");
         }
-        let prefix_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let prefix_ptr = self.expect_string_pointer(
-            prefix_expr,
-            "create_temp_file expects the prefix argument to be a String",
-        )?;
-        let func = self.ensure_fs_create_temp_file_fn();
-        let pointer = self
-            .call_function(func, &[prefix_ptr.into()], "tea_fs_create_temp_file")?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_fs_create_temp_file returned no value"))?
-            .into_pointer_value();
-        Ok(ExprValue::String(pointer))
+        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let handle_value =
+            self.expect_int_value(handle_expr, "process.close expects the handle to be an Int")?;
+        let func = self.ensure_process_close_fn();
+        self.call_function(func, &[handle_value.into()], "tea_process_close")?;
+        Ok(ExprValue::Void)
     }
 
-    fn compile_fs_ensure_dir_call(
+    fn compile_process_wait_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("ensure_dir expects exactly 1 argument");
+            bail!("process.wait expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for ensure_dir");
+            bail!("named arguments are not supported for process.wait");
         }
-        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = match path_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("ensure_dir expects the path argument to be a String"),
-        };
-        let func = self.ensure_fs_ensure_dir_fn();
-        self.call_function(func, &[path_ptr.into()], "tea_fs_ensure_dir")?;
-        Ok(ExprValue::Void)
+        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let handle_value =
+            self.expect_int_value(handle_expr, "process.wait expects the handle to be an Int")?;
+
+        let template_ptr = self.ensure_struct_template("ProcessResult")?;
+        let func = self.ensure_process_wait_fn();
+        let pointer = self
+            .call_function(
+                func,
+                &[template_ptr.into(), handle_value.into()],
+                "tea_process_wait",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_process_wait returned no value"))?
+            .into_pointer_value();
+
+        Ok(ExprValue::Struct {
+            pointer,
+            struct_name: "ProcessResult".to_string(),
+        })
     }
 
-    fn compile_fs_ensure_parent_call(
+    fn compile_process_read_stdout_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 1 {
-            bail!("ensure_parent expects exactly 1 argument");
-        }
-        if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for ensure_parent");
-        }
-        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = match path_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("ensure_parent expects the path argument to be a String"),
-        };
-        let func = self.ensure_fs_ensure_parent_fn();
-        self.call_function(func, &[path_ptr.into()], "tea_fs_ensure_parent")?;
-        Ok(ExprValue::Void)
+        self.compile_process_read_call(arguments, function, locals, true)
     }
 
-    fn compile_fs_remove_call(
+    fn compile_process_read_stderr_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 1 {
-            bail!("remove expects exactly 1 argument");
-        }
-        if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for remove");
-        }
-        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = match path_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("remove expects the path argument to be a String"),
-        };
-        let func = self.ensure_fs_remove_fn();
-        self.call_function(func, &[path_ptr.into()], "tea_fs_remove")?;
-        Ok(ExprValue::Void)
+        self.compile_process_read_call(arguments, function, locals, false)
     }
 
-    fn compile_fs_exists_call(
+    fn compile_process_read_stdout_bytes_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 1 {
-            bail!("exists expects exactly 1 argument");
-        }
-        if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for exists");
-        }
-        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = match path_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("exists expects the path argument to be a String"),
-        };
-        let func = self.ensure_fs_exists_fn();
-        let raw = self
-            .call_function(func, &[path_ptr.into()], "tea_fs_exists")?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_fs_exists returned no value"))?
-            .into_int_value();
-        let flag = self.i32_to_bool(raw, "fs_exists_result")?;
-        Ok(ExprValue::Bool(flag))
+        self.compile_process_read_bytes_call(arguments, function, locals, true)
     }
 
-    fn compile_fs_is_dir_call(
+    fn compile_process_read_stderr_bytes_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 1 {
-            bail!("is_dir expects exactly 1 argument");
-        }
-        if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for is_dir");
-        }
-        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = match path_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("is_dir expects the path argument to be a String"),
-        };
-        let func = self.ensure_fs_is_dir_fn();
-        let raw = self
-            .call_function(func, &[path_ptr.into()], "tea_fs_is_dir")?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_fs_is_dir returned no value"))?
-            .into_int_value();
-        let flag = self.i32_to_bool(raw, "fs_is_dir_result")?;
-        Ok(ExprValue::Bool(flag))
+        self.compile_process_read_bytes_call(arguments, function, locals, false)
     }
 
-    fn compile_fs_is_symlink_call(
+    fn compile_fs_read_text_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("is_symlink expects exactly 1 argument");
+            bail!("read_text expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for is_symlink");
+            bail!("named arguments are not supported for read_text");
         }
         let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
         let path_ptr = match path_expr {
             ExprValue::String(ptr) => ptr,
-            _ => bail!("is_symlink expects the path argument to be a String"),
+            _ => bail!("read_text expects the path argument to be a String"),
         };
-        let func = self.ensure_fs_is_symlink_fn();
-        let raw = self
-            .call_function(func, &[path_ptr.into()], "tea_fs_is_symlink")?
+        let func = self.ensure_fs_read_text_fn();
+        let pointer = self
+            .call_function(func, &[path_ptr.into()], "tea_fs_read_text")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_fs_is_symlink returned no value"))?
-            .into_int_value();
-        let flag = self.i32_to_bool(raw, "fs_is_symlink_result")?;
-        Ok(ExprValue::Bool(flag))
+            .ok_or_else(|| anyhow!("tea_fs_read_text returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::String(pointer))
     }
 
-    fn compile_fs_size_call(
+    fn compile_fs_read_bytes_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("size expects exactly 1 argument");
+            bail!("read_bytes expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for size");
+            bail!("named arguments are not supported for read_bytes");
         }
         let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = match path_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("size expects the path argument to be a String"),
-        };
-        let func = self.ensure_fs_size_fn();
-        let raw = self
-            .call_function(func, &[path_ptr.into()], "tea_fs_size")?
+        let path_ptr = self.expect_string_pointer(
+            path_expr,
+            "read_bytes expects the path argument to be a String",
+        )?;
+        let func = self.ensure_fs_read_bytes_fn();
+        let pointer = self
+            .call_function(func, &[path_ptr.into()], "tea_fs_read_bytes")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_fs_size returned no value"))?
-            .into_int_value();
-        Ok(ExprValue::Int(raw))
+            .ok_or_else(|| anyhow!("tea_fs_read_bytes returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::Bytes(pointer))
     }
 
-    fn compile_fs_modified_call(
+    fn compile_fs_write_text_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 1 {
-            bail!("modified expects exactly 1 argument");
+        if arguments.len() != 2 {
+            bail!("write_text expects exactly 2 arguments");
         }
-        if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for modified");
+        for argument in arguments {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported for write_text");
+            }
         }
         let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
         let path_ptr = match path_expr {
             ExprValue::String(ptr) => ptr,
-            _ => bail!("modified expects the path argument to be a String"),
+            _ => bail!("write_text expects the path argument to be a String"),
         };
-        let func = self.ensure_fs_modified_fn();
-        let raw = self
-            .call_function(func, &[path_ptr.into()], "tea_fs_modified")?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_fs_modified returned no value"))?
-            .into_int_value();
-        Ok(ExprValue::Int(raw))
+        let contents_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let contents_ptr = match contents_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("write_text expects the contents argument to be a String"),
+        };
+        let func = self.ensure_fs_write_text_fn();
+        self.call_function(
+            func,
+            &[path_ptr.into(), contents_ptr.into()],
+            "tea_fs_write_text",
+        )?;
+        Ok(ExprValue::Void)
     }
 
-    fn compile_fs_permissions_call(
+    fn compile_fs_write_bytes_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 1 {
-            bail!("permissions expects exactly 1 argument");
+        if arguments.len() != 2 {
+            bail!("write_bytes expects exactly 2 arguments");
         }
-        if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for permissions");
+        for argument in arguments {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported for write_bytes");
+            }
         }
         let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = match path_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("permissions expects the path argument to be a String"),
-        };
-        let func = self.ensure_fs_permissions_fn();
-        let raw = self
-            .call_function(func, &[path_ptr.into()], "tea_fs_permissions")?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_fs_permissions returned no value"))?
-            .into_int_value();
-        Ok(ExprValue::Int(raw))
+        let path_ptr = self.expect_string_pointer(
+            path_expr,
+            "write_bytes expects the path argument to be a String",
+        )?;
+        let data_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let data_ptr = self.expect_bytes_pointer(
+            data_expr,
+            "write_bytes expects the data argument to be Bytes",
+        )?;
+        let func = self.ensure_fs_write_bytes_fn();
+        self.call_function(
+            func,
+            &[path_ptr.into(), data_ptr.into()],
+            "tea_fs_write_bytes",
+        )?;
+        Ok(ExprValue::Void)
     }
 
-    fn compile_fs_is_readonly_call(
+    fn compile_fs_write_bytes_atomic_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 1 {
-            bail!("is_readonly expects exactly 1 argument");
+        if arguments.len() != 2 {
+            bail!("write_bytes_atomic expects exactly 2 arguments");
         }
-        if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for is_readonly");
+        for argument in arguments {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported for write_bytes_atomic");
+            }
         }
         let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = match path_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("is_readonly expects the path argument to be a String"),
-        };
-        let func = self.ensure_fs_is_readonly_fn();
-        let raw = self
-            .call_function(func, &[path_ptr.into()], "tea_fs_is_readonly")?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_fs_is_readonly returned no value"))?
-            .into_int_value();
-        let flag = self.i32_to_bool(raw, "fs_is_readonly_result")?;
-        Ok(ExprValue::Bool(flag))
+        let path_ptr = self.expect_string_pointer(
+            path_expr,
+            "write_bytes_atomic expects the path argument to be a String",
+        )?;
+        let data_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let data_ptr = self.expect_bytes_pointer(
+            data_expr,
+            "write_bytes_atomic expects the data argument to be Bytes",
+        )?;
+        let func = self.ensure_fs_write_bytes_atomic_fn();
+        self.call_function(
+            func,
+            &[path_ptr.into(), data_ptr.into()],
+            "tea_fs_write_bytes_atomic",
+        )?;
+        Ok(ExprValue::Void)
     }
 
-    fn compile_fs_list_dir_call(
+    fn compile_fs_append_text_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 1 {
-            bail!("list_dir expects exactly 1 argument");
+        if arguments.len() != 2 {
+            bail!("append_text expects exactly 2 arguments");
         }
-        if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for list_dir");
+        for argument in arguments {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported for append_text");
+            }
         }
         let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = match path_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("list_dir expects the path argument to be a String"),
-        };
-        let func = self.ensure_fs_list_dir_fn();
-        let pointer = self
-            .call_function(func, &[path_ptr.into()], "tea_fs_list_dir")?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_fs_list_dir returned no value"))?
-            .into_pointer_value();
-        Ok(ExprValue::List {
-            pointer,
-            element_type: Box::new(ValueType::String),
-        })
+        let path_ptr = self.expect_string_pointer(
+            path_expr,
+            "append_text expects the path argument to be a String",
+        )?;
+        let contents_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let contents_ptr = self.expect_string_pointer(
+            contents_expr,
+            "append_text expects the contents argument to be a String",
+        )?;
+        let func = self.ensure_fs_append_text_fn();
+        self.call_function(
+            func,
+            &[path_ptr.into(), contents_ptr.into()],
+            "tea_fs_append_text",
+        )?;
+        Ok(ExprValue::Void)
     }
 
-    fn compile_fs_walk_call(
+    fn compile_fs_append_bytes_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 1 {
-            bail!("walk expects exactly 1 argument");
+        if arguments.len() != 2 {
+            bail!("append_bytes expects exactly 2 arguments");
         }
-        if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for walk");
+        for argument in arguments {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported for append_bytes");
+            }
         }
         let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let path_ptr = match path_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("walk expects the path argument to be a String"),
-        };
-        let func = self.ensure_fs_walk_fn();
-        let pointer = self
-            .call_function(func, &[path_ptr.into()], "tea_fs_walk")?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_fs_walk returned no value"))?
-            .into_pointer_value();
-        Ok(ExprValue::List {
-            pointer,
-            element_type: Box::new(ValueType::String),
-        })
+        let path_ptr = self.expect_string_pointer(
+            path_expr,
+            "append_bytes expects the path argument to be a String",
+        )?;
+        let data_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let data_ptr = self.expect_bytes_pointer(
+            data_expr,
+            "append_bytes expects the data argument to be Bytes",
+        )?;
+        let func = self.ensure_fs_append_bytes_fn();
+        self.call_function(
+            func,
+            &[path_ptr.into(), data_ptr.into()],
+            "tea_fs_append_bytes",
+        )?;
+        Ok(ExprValue::Void)
     }
 
-    fn compile_fs_rename_call(
+    fn compile_fs_write_text_atomic_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 2 {
-            bail!("rename expects exactly 2 arguments");
+            bail!("write_text_atomic expects exactly 2 arguments");
         }
         for argument in arguments {
             if argument.name.is_some() {
-                bail!("named arguments are not supported for rename");
+                bail!("named arguments are not supported for write_text_atomic");
             }
         }
-        let source_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let source_ptr = match source_expr {
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr = match path_expr {
             ExprValue::String(ptr) => ptr,
-            _ => bail!("rename expects the source argument to be a String"),
+            _ => bail!("write_text_atomic expects the path argument to be a String"),
         };
-        let target_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let target_ptr = match target_expr {
+        let contents_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let contents_ptr = match contents_expr {
             ExprValue::String(ptr) => ptr,
-            _ => bail!("rename expects the target argument to be a String"),
+            _ => bail!("write_text_atomic expects the contents argument to be a String"),
         };
-        let func = self.ensure_fs_rename_fn();
+        let func = self.ensure_fs_write_text_atomic_fn();
         self.call_function(
             func,
-            &[source_ptr.into(), target_ptr.into()],
-            "tea_fs_rename",
+            &[path_ptr.into(), contents_ptr.into()],
+            "tea_fs_write_text_atomic",
         )?;
         Ok(ExprValue::Void)
     }
 
-    fn compile_fs_copy_call(
+    fn compile_fs_create_dir_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 2 {
-            bail!("copy expects exactly 2 arguments");
+        if !(1..=2).contains(&arguments.len()) {
+            bail!("create_dir expects 1 or 2 arguments");
         }
         for argument in arguments {
             if argument.name.is_some() {
-                bail!("named arguments are not supported for copy");
+                bail!("named arguments are not supported for create_dir");
             }
         }
-        let source_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let source_ptr = match source_expr {
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr = match path_expr {
             ExprValue::String(ptr) => ptr,
-            _ => bail!("copy expects the source argument to be a String"),
+            _ => bail!("create_dir expects the path argument to be a String"),
         };
-        let target_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let target_ptr = match target_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("copy expects the target argument to be a String"),
+        let recursive_flag = if arguments.len() == 2 {
+            let expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+            match expr {
+                ExprValue::Bool(flag) => flag,
+                _ => bail!("create_dir expects the optional flag to be a Bool"),
+            }
+        } else {
+            self.bool_type().const_zero()
         };
-        let func = self.ensure_fs_copy_fn();
-        self.call_function(func, &[source_ptr.into(), target_ptr.into()], "tea_fs_copy")?;
+        let recursive_c = self.bool_to_i32(recursive_flag, "fs_create_dir_recursive")?;
+        let func = self.ensure_fs_create_dir_fn();
+        self.call_function(
+            func,
+            &[path_ptr.into(), recursive_c.into()],
+            "tea_fs_create_dir",
+        )?;
         Ok(ExprValue::Void)
     }
 
-    fn compile_fs_stat_call(
+    fn compile_fs_create_temp_dir_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        self.compile_fs_metadata_call(arguments, function, locals)
+        if arguments.len() != 1 {
+            bail!("create_temp_dir expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for create_temp_dir");
+        }
+        let prefix_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let prefix_ptr = self.expect_string_pointer(
+            prefix_expr,
+            "create_temp_dir expects the prefix argument to be a String",
+        )?;
+        let func = self.ensure_fs_create_temp_dir_fn();
+        let pointer = self
+            .call_function(func, &[prefix_ptr.into()], "tea_fs_create_temp_dir")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_fs_create_temp_dir returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::String(pointer))
     }
 
-    fn compile_fs_glob_call(
+    fn compile_fs_create_temp_file_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("glob expects exactly 1 argument");
+            bail!("create_temp_file expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for glob");
+            bail!("named arguments are not supported for create_temp_file");
         }
-        let pattern_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let pattern_ptr = match pattern_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("glob expects the pattern argument to be a String"),
-        };
-        let func = self.ensure_fs_glob_fn();
+        let prefix_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let prefix_ptr = self.expect_string_pointer(
+            prefix_expr,
+            "create_temp_file expects the prefix argument to be a String",
+        )?;
+        let func = self.ensure_fs_create_temp_file_fn();
         let pointer = self
-            .call_function(func, &[pattern_ptr.into()], "tea_fs_glob")?
+            .call_function(func, &[prefix_ptr.into()], "tea_fs_create_temp_file")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_fs_glob returned no value"))?
+            .ok_or_else(|| anyhow!("tea_fs_create_temp_file returned no value"))?
             .into_pointer_value();
-        Ok(ExprValue::List {
-            pointer,
-            element_type: Box::new(ValueType::String),
-        })
+        Ok(ExprValue::String(pointer))
     }
 
-    fn compile_fs_metadata_call(
+    fn compile_fs_ensure_dir_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("metadata expects exactly 1 argument");
+            bail!("ensure_dir expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for metadata");
+            bail!("named arguments are not supported for ensure_dir");
         }
         let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
         let path_ptr = match path_expr {
             ExprValue::String(ptr) => ptr,
-            _ => bail!("metadata expects the path argument to be a String"),
+            _ => bail!("ensure_dir expects the path argument to be a String"),
         };
-        let func = self.ensure_fs_metadata_fn();
-        let value = self
-            .call_function(func, &[path_ptr.into()], "tea_fs_metadata")?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_fs_metadata returned no value"))?
-            .into_struct_value();
-        self.tea_value_to_expr(value, ValueType::Dict(Box::new(ValueType::Void)))
+        let func = self.ensure_fs_ensure_dir_fn();
+        self.call_function(func, &[path_ptr.into()], "tea_fs_ensure_dir")?;
+        Ok(ExprValue::Void)
     }
 
-    fn call_closure(
+    fn compile_fs_ensure_parent_call(
         &mut self,
-        closure_ptr: PointerValue<'ctx>,
-        param_types: &[ValueType],
-        return_type: &ValueType,
-        call: &CallExpression,
+        arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if param_types.len() != call.arguments.len() {
-            bail!(
-                "closure expects {} arguments, found {}",
-                param_types.len(),
-                call.arguments.len()
-            );
-        }
-
-        let mut arg_values: Vec<BasicValueEnum<'ctx>> = Vec::with_capacity(call.arguments.len());
-        for (index, (expected_type, argument)) in
-            param_types.iter().zip(call.arguments.iter()).enumerate()
-        {
-            if argument.name.is_some() {
-                bail!("named arguments are not supported by the LLVM backend yet");
-            }
-            let value = self.compile_expression(&argument.expression, function, locals)?;
-            let converted = self
-                .convert_expr_to_type(value, expected_type)
-                .map_err(|error| {
-                    anyhow!(
-                        "argument {} to closure has mismatched type: {}",
-                        index + 1,
-                        error
-                    )
-                })?;
-            let basic = converted
-                .into_basic_value()
-                .ok_or_else(|| anyhow!("argument must produce a value"))?;
-            arg_values.push(basic);
+        if arguments.len() != 1 {
+            bail!("ensure_parent expects exactly 1 argument");
         }
-
-        let fn_ptr_ptr = map_builder_error(self.builder.build_struct_gep(
-            self.tea_closure,
-            closure_ptr,
-            0,
-            "closure_fn_ptr",
-        ))?;
-        let raw_fn_ptr = map_builder_error(self.builder.build_load(
-            self.ptr_type,
-            fn_ptr_ptr,
-            "closure_fn",
-        ))?
-        .into_pointer_value();
-
-        let mut llvm_params: Vec<BasicMetadataTypeEnum<'ctx>> =
-            Vec::with_capacity(param_types.len() + 1);
-        llvm_params.push(self.closure_ptr_type().into());
-        for ty in param_types {
-            llvm_params.push(self.basic_type(ty)?.into());
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for ensure_parent");
         }
-
-        let fn_type = match return_type {
-            ValueType::Void => self.context.void_type().fn_type(&llvm_params, false),
-            ValueType::Int => self.int_type().fn_type(&llvm_params, false),
-            ValueType::Float => self.float_type().fn_type(&llvm_params, false),
-            ValueType::Bool => self.bool_type().fn_type(&llvm_params, false),
-            ValueType::String => self.string_ptr_type().fn_type(&llvm_params, false),
-            ValueType::List(_) => self.list_ptr_type().fn_type(&llvm_params, false),
-            ValueType::Dict(_) => self.dict_ptr_type().fn_type(&llvm_params, false),
-            ValueType::Struct(_) => self.struct_ptr_type().fn_type(&llvm_params, false),
-            ValueType::Error { .. } => self.error_ptr_type().fn_type(&llvm_params, false),
-            ValueType::Function(_, _) => self.closure_ptr_type().fn_type(&llvm_params, false),
-            ValueType::Optional(_) => self.value_type().fn_type(&llvm_params, false),
-            ValueType::Any => self.value_type().fn_type(&llvm_params, false),
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr = match path_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("ensure_parent expects the path argument to be a String"),
         };
+        let func = self.ensure_fs_ensure_parent_fn();
+        self.call_function(func, &[path_ptr.into()], "tea_fs_ensure_parent")?;
+        Ok(ExprValue::Void)
+    }
 
-        let typed_fn_ptr = map_builder_error(self.builder.build_bit_cast(
-            raw_fn_ptr,
-            self.ptr_type,
-            "closure_callee",
-        ))?
-        .into_pointer_value();
-
-        let mut args: Vec<BasicMetadataValueEnum<'ctx>> = Vec::with_capacity(arg_values.len() + 1);
-        args.push(closure_ptr.into());
-        for value in arg_values {
-            args.push(BasicMetadataValueEnum::from(value));
+    fn compile_fs_remove_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 1 {
+            bail!("remove expects exactly 1 argument");
         }
-
-        let call_site = map_builder_error(self.builder.build_indirect_call(
-            fn_type,
-            typed_fn_ptr,
-            &args,
-            "closure_call",
-        ))?;
-
-        if matches!(return_type, ValueType::Void) {
-            self.handle_possible_error(function)?;
-            return Ok(ExprValue::Void);
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for remove");
         }
-
-        let result = call_site
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("closure returned no value"))?;
-
-        let expr = match return_type {
-            ValueType::Int => ExprValue::Int(result.into_int_value()),
-            ValueType::Float => ExprValue::Float(result.into_float_value()),
-            ValueType::Bool => ExprValue::Bool(result.into_int_value()),
-            ValueType::String => ExprValue::String(result.into_pointer_value()),
-            ValueType::List(inner) => ExprValue::List {
-                pointer: result.into_pointer_value(),
-                element_type: inner.clone(),
-            },
-            ValueType::Dict(inner) => ExprValue::Dict {
-                pointer: result.into_pointer_value(),
-                value_type: inner.clone(),
-            },
-            ValueType::Struct(struct_name) => ExprValue::Struct {
-                pointer: result.into_pointer_value(),
-                struct_name: struct_name.clone(),
-            },
-            ValueType::Error {
-                error_name,
-                variant_name,
-            } => ExprValue::Error {
-                pointer: result.into_pointer_value(),
-                error_name: error_name.clone(),
-                variant_name: variant_name.clone(),
-            },
-            ValueType::Function(params, ret) => ExprValue::Closure {
-                pointer: result.into_pointer_value(),
-                param_types: params.clone(),
-                return_type: ret.clone(),
-            },
-            ValueType::Optional(inner) => ExprValue::Optional {
-                value: result.into_struct_value(),
-                inner: inner.clone(),
-            },
-            ValueType::Void => unreachable!(),
-            ValueType::Any => ExprValue::Any {
-                value: result.into_struct_value(),
-            },
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr = match path_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("remove expects the path argument to be a String"),
         };
-        self.handle_possible_error(function)?;
-
-        Ok(expr)
+        let func = self.ensure_fs_remove_fn();
+        self.call_function(func, &[path_ptr.into()], "tea_fs_remove")?;
+        Ok(ExprValue::Void)
     }
 
-    /// Compile a List method call.
-    fn compile_list_method_call(
+    fn compile_fs_exists_call(
         &mut self,
-        method_name: &str,
-        list_ptr: PointerValue<'ctx>,
-        element_type: Box<ValueType>,
-        call: &CallExpression,
+        arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        match method_name {
-            "map" => self.compile_list_map(list_ptr, element_type, call, function, locals),
-            "filter" => self.compile_list_filter(list_ptr, element_type, call, function, locals),
-            "reduce" => self.compile_list_reduce(list_ptr, element_type, call, function, locals),
-            "find" => self.compile_list_find(list_ptr, element_type, call, function, locals),
-            "any" => self.compile_list_any(list_ptr, element_type, call, function, locals),
-            "all" => self.compile_list_all(list_ptr, element_type, call, function, locals),
-            "contains" => {
-                self.compile_list_contains(list_ptr, element_type, call, function, locals)
-            }
-            "index_of" => {
-                self.compile_list_index_of(list_ptr, element_type, call, function, locals)
-            }
-            "find_index" => {
-                self.compile_list_find_index(list_ptr, element_type, call, function, locals)
-            }
-            "take" => self.compile_list_take(list_ptr, element_type, call, function, locals),
-            "skip" => self.compile_list_skip(list_ptr, element_type, call, function, locals),
-            _ => bail!("unknown List method: {}", method_name),
+        if arguments.len() != 1 {
+            bail!("exists expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for exists");
         }
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr = match path_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("exists expects the path argument to be a String"),
+        };
+        let func = self.ensure_fs_exists_fn();
+        let raw = self
+            .call_function(func, &[path_ptr.into()], "tea_fs_exists")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_fs_exists returned no value"))?
+            .into_int_value();
+        let flag = self.i32_to_bool(raw, "fs_exists_result")?;
+        Ok(ExprValue::Bool(flag))
     }
 
-    /// Compile list.map(fn) - transform each element
-    fn compile_list_map(
+    fn compile_fs_is_dir_call(
         &mut self,
-        list_ptr: PointerValue<'ctx>,
-        element_type: Box<ValueType>,
-        call: &CallExpression,
+        arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        // Get the callback closure
-        let callback = self.compile_expression(&call.arguments[0].expression, function, locals)?;
-        let (closure_ptr, param_types, return_type) = match callback {
-            ExprValue::Closure {
-                pointer,
-                param_types,
-                return_type,
-            } => (pointer, param_types, return_type),
-            _ => bail!("List.map expects a function argument"),
+        if arguments.len() != 1 {
+            bail!("is_dir expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for is_dir");
+        }
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr = match path_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("is_dir expects the path argument to be a String"),
         };
+        let func = self.ensure_fs_is_dir_fn();
+        let raw = self
+            .call_function(func, &[path_ptr.into()], "tea_fs_is_dir")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_fs_is_dir returned no value"))?
+            .into_int_value();
+        let flag = self.i32_to_bool(raw, "fs_is_dir_result")?;
+        Ok(ExprValue::Bool(flag))
+    }
 
-        // Get list length
-        let list_len_fn = self.ensure_list_len_ffi_fn();
-        let length = map_builder_error(self.builder.build_call(
-            list_len_fn,
-            &[list_ptr.into()],
-            "list_len",
-        ))?
-        .try_as_basic_value()
-        .left()
-        .ok_or_else(|| anyhow!("expected i64 from list_len"))?
-        .into_int_value();
-
-        // Allocate result list
-        let alloc_list_fn = self.ensure_alloc_list();
-        let result_list = map_builder_error(self.builder.build_call(
-            alloc_list_fn,
-            &[length.into()],
-            "result_list",
-        ))?
-        .try_as_basic_value()
-        .left()
-        .ok_or_else(|| anyhow!("expected pointer from alloc_list"))?
-        .into_pointer_value();
-
-        // Create loop blocks
-        let current_block = self
-            .builder
-            .get_insert_block()
-            .ok_or_else(|| anyhow!("missing insertion block"))?;
-        let cond_block = self.context.append_basic_block(function, "map_cond");
-        let body_block = self.context.append_basic_block(function, "map_body");
-        let exit_block = self.context.append_basic_block(function, "map_exit");
-
-        // Branch to condition
-        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
-
-        // Condition block
-        self.builder.position_at_end(cond_block);
-        let i64_type = self.context.i64_type();
-        let index_phi = map_builder_error(self.builder.build_phi(i64_type, "index"))?;
-        index_phi.add_incoming(&[(&i64_type.const_zero(), current_block)]);
-
-        let cond = map_builder_error(self.builder.build_int_compare(
-            IntPredicate::SLT,
-            index_phi.as_basic_value().into_int_value(),
-            length,
-            "map_cond",
-        ))?;
-        map_builder_error(
-            self.builder
-                .build_conditional_branch(cond, body_block, exit_block),
-        )?;
-
-        // Body block
-        self.builder.position_at_end(body_block);
-
-        // Get element at index
-        let list_get_fn = self.ensure_list_get();
-        let tea_value = map_builder_error(self.builder.build_call(
-            list_get_fn,
-            &[list_ptr.into(), index_phi.as_basic_value().into()],
-            "element_value",
-        ))?
-        .try_as_basic_value()
-        .left()
-        .ok_or_else(|| anyhow!("expected TeaValue"))?
-        .into_struct_value();
-
-        // Convert to proper type
-        let element = self.tea_value_to_expr_value(tea_value, &element_type)?;
-
-        // Call closure with element
-        let call_result = self.call_closure_with_args(
-            closure_ptr,
-            &param_types,
-            &return_type,
-            vec![element],
-            function,
-        )?;
-
-        // Convert result to TeaValue and store in result list
-        let result_tea_value = self.expr_to_tea_value(call_result)?;
-        let list_set_fn = self.ensure_list_set();
-        map_builder_error(self.builder.build_call(
-            list_set_fn,
-            &[
-                result_list.into(),
-                index_phi.as_basic_value().into(),
-                result_tea_value.into(),
-            ],
-            "",
-        ))?;
-
-        // Increment index
-        let next_index = map_builder_error(self.builder.build_int_add(
-            index_phi.as_basic_value().into_int_value(),
-            i64_type.const_int(1, false),
-            "next_index",
-        ))?;
-        index_phi.add_incoming(&[(&next_index, body_block)]);
-        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
+    fn compile_fs_is_symlink_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 1 {
+            bail!("is_symlink expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for is_symlink");
+        }
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr = match path_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("is_symlink expects the path argument to be a String"),
+        };
+        let func = self.ensure_fs_is_symlink_fn();
+        let raw = self
+            .call_function(func, &[path_ptr.into()], "tea_fs_is_symlink")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_fs_is_symlink returned no value"))?
+            .into_int_value();
+        let flag = self.i32_to_bool(raw, "fs_is_symlink_result")?;
+        Ok(ExprValue::Bool(flag))
+    }
 
-        // Exit block
-        self.builder.position_at_end(exit_block);
+    fn compile_fs_size_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 1 {
+            bail!("size expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for size");
+        }
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr = match path_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("size expects the path argument to be a String"),
+        };
+        let func = self.ensure_fs_size_fn();
+        let raw = self
+            .call_function(func, &[path_ptr.into()], "tea_fs_size")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_fs_size returned no value"))?
+            .into_int_value();
+        Ok(ExprValue::Int(raw))
+    }
 
-        Ok(ExprValue::List {
-            pointer: result_list,
-            element_type: return_type,
-        })
+    fn compile_fs_modified_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 1 {
+            bail!("modified expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for modified");
+        }
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr = match path_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("modified expects the path argument to be a String"),
+        };
+        let func = self.ensure_fs_modified_fn();
+        let raw = self
+            .call_function(func, &[path_ptr.into()], "tea_fs_modified")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_fs_modified returned no value"))?
+            .into_int_value();
+        Ok(ExprValue::Int(raw))
     }
 
-    /// Compile list.filter(fn) - keep elements matching predicate
-    fn compile_list_filter(
+    fn compile_fs_permissions_call(
         &mut self,
-        list_ptr: PointerValue<'ctx>,
-        element_type: Box<ValueType>,
-        call: &CallExpression,
+        arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        // Get the callback closure
-        let callback = self.compile_expression(&call.arguments[0].expression, function, locals)?;
-        let (closure_ptr, param_types, _return_type) = match callback {
-            ExprValue::Closure {
-                pointer,
-                param_types,
-                return_type,
-            } => (pointer, param_types, return_type),
-            _ => bail!("List.filter expects a function argument"),
+        if arguments.len() != 1 {
+            bail!("permissions expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for permissions");
+        }
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr = match path_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("permissions expects the path argument to be a String"),
         };
+        let func = self.ensure_fs_permissions_fn();
+        let raw = self
+            .call_function(func, &[path_ptr.into()], "tea_fs_permissions")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_fs_permissions returned no value"))?
+            .into_int_value();
+        Ok(ExprValue::Int(raw))
+    }
 
-        // Get list length
-        let list_len_fn = self.ensure_list_len_ffi_fn();
-        let length = map_builder_error(self.builder.build_call(
-            list_len_fn,
-            &[list_ptr.into()],
-            "list_len",
-        ))?
-        .try_as_basic_value()
-        .left()
-        .ok_or_else(|| anyhow!("expected i64 from list_len"))?
-        .into_int_value();
-
-        // Allocate result list (start with same capacity, will be smaller or equal)
-        let alloc_list_fn = self.ensure_alloc_list();
-        let result_list = map_builder_error(self.builder.build_call(
-            alloc_list_fn,
-            &[self.context.i64_type().const_zero().into()],
-            "result_list",
-        ))?
-        .try_as_basic_value()
-        .left()
-        .ok_or_else(|| anyhow!("expected pointer from alloc_list"))?
-        .into_pointer_value();
-
-        // Create loop blocks
-        let current_block = self
-            .builder
-            .get_insert_block()
-            .ok_or_else(|| anyhow!("missing insertion block"))?;
-        let cond_block = self.context.append_basic_block(function, "filter_cond");
-        let body_block = self.context.append_basic_block(function, "filter_body");
-        let append_block = self.context.append_basic_block(function, "filter_append");
-        let continue_block = self.context.append_basic_block(function, "filter_continue");
-        let exit_block = self.context.append_basic_block(function, "filter_exit");
-
-        // Branch to condition
-        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
-
-        // Condition block
-        self.builder.position_at_end(cond_block);
-        let i64_type = self.context.i64_type();
-        let index_phi = map_builder_error(self.builder.build_phi(i64_type, "index"))?;
-        index_phi.add_incoming(&[(&i64_type.const_zero(), current_block)]);
-
-        let cond = map_builder_error(self.builder.build_int_compare(
-            IntPredicate::SLT,
-            index_phi.as_basic_value().into_int_value(),
-            length,
-            "filter_cond",
-        ))?;
-        map_builder_error(
-            self.builder
-                .build_conditional_branch(cond, body_block, exit_block),
-        )?;
-
-        // Body block
-        self.builder.position_at_end(body_block);
-
-        // Get element at index
-        let list_get_fn = self.ensure_list_get();
-        let tea_value = map_builder_error(self.builder.build_call(
-            list_get_fn,
-            &[list_ptr.into(), index_phi.as_basic_value().into()],
-            "element_value",
-        ))?
-        .try_as_basic_value()
-        .left()
-        .ok_or_else(|| anyhow!("expected TeaValue"))?
-        .into_struct_value();
-
-        // Convert to proper type
-        let element = self.tea_value_to_expr_value(tea_value, &element_type)?;
-
-        // Call closure with element
-        let predicate_result = self.call_closure_with_args(
-            closure_ptr,
-            &param_types,
-            &Box::new(ValueType::Bool),
-            vec![element.clone()],
-            function,
-        )?;
-
-        // Check predicate result
-        let bool_val = match predicate_result {
-            ExprValue::Bool(v) => v,
-            _ => bail!("filter predicate must return Bool"),
+    fn compile_fs_is_readonly_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 1 {
+            bail!("is_readonly expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for is_readonly");
+        }
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr = match path_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("is_readonly expects the path argument to be a String"),
         };
+        let func = self.ensure_fs_is_readonly_fn();
+        let raw = self
+            .call_function(func, &[path_ptr.into()], "tea_fs_is_readonly")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_fs_is_readonly returned no value"))?
+            .into_int_value();
+        let flag = self.i32_to_bool(raw, "fs_is_readonly_result")?;
+        Ok(ExprValue::Bool(flag))
+    }
 
-        // Compare with true (1)
-        let is_true = map_builder_error(self.builder.build_int_compare(
-            IntPredicate::NE,
-            bool_val,
-            self.context.i32_type().const_zero(),
-            "is_true",
-        ))?;
-
-        map_builder_error(self.builder.build_conditional_branch(
-            is_true,
-            append_block,
-            continue_block,
-        ))?;
-
-        // Append block - add element to result
-        self.builder.position_at_end(append_block);
-        let append_fn = self.ensure_list_append_fn();
-        let element_tea_value = self.expr_to_tea_value(element)?;
-        map_builder_error(self.builder.build_call(
-            append_fn,
-            &[result_list.into(), element_tea_value.into()],
-            "",
-        ))?;
-        map_builder_error(self.builder.build_unconditional_branch(continue_block))?;
-
-        // Continue block - increment and loop
-        self.builder.position_at_end(continue_block);
-        let next_index = map_builder_error(self.builder.build_int_add(
-            index_phi.as_basic_value().into_int_value(),
-            i64_type.const_int(1, false),
-            "next_index",
-        ))?;
-        index_phi.add_incoming(&[(&next_index, continue_block)]);
-        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
-
-        // Exit block
-        self.builder.position_at_end(exit_block);
-
+    fn compile_fs_list_dir_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 1 {
+            bail!("list_dir expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for list_dir");
+        }
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr = match path_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("list_dir expects the path argument to be a String"),
+        };
+        let func = self.ensure_fs_list_dir_fn();
+        let pointer = self
+            .call_function(func, &[path_ptr.into()], "tea_fs_list_dir")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_fs_list_dir returned no value"))?
+            .into_pointer_value();
         Ok(ExprValue::List {
-            pointer: result_list,
-            element_type,
+            pointer,
+            element_type: Box::new(ValueType::String),
         })
     }
 
-    /// Compile list.reduce(init, fn) - fold list to single value
-    fn compile_list_reduce(
+    fn compile_fs_walk_call(
         &mut self,
-        list_ptr: PointerValue<'ctx>,
-        element_type: Box<ValueType>,
-        call: &CallExpression,
+        arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        // Get initial value
-        let init_value =
-            self.compile_expression(&call.arguments[0].expression, function, locals)?;
-        let init_type = init_value.ty();
+        if arguments.len() != 1 {
+            bail!("walk expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for walk");
+        }
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr = match path_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("walk expects the path argument to be a String"),
+        };
+        let func = self.ensure_fs_walk_fn();
+        let pointer = self
+            .call_function(func, &[path_ptr.into()], "tea_fs_walk")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_fs_walk returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::List {
+            pointer,
+            element_type: Box::new(ValueType::String),
+        })
+    }
 
-        // Get the callback closure
-        let callback = self.compile_expression(&call.arguments[1].expression, function, locals)?;
-        let (closure_ptr, param_types, return_type) = match callback {
-            ExprValue::Closure {
-                pointer,
-                param_types,
-                return_type,
-            } => (pointer, param_types, return_type),
-            _ => bail!("List.reduce expects a function argument"),
+    fn compile_fs_rename_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 2 {
+            bail!("rename expects exactly 2 arguments");
+        }
+        for argument in arguments {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported for rename");
+            }
+        }
+        let source_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let source_ptr = match source_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("rename expects the source argument to be a String"),
+        };
+        let target_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let target_ptr = match target_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("rename expects the target argument to be a String"),
         };
+        let func = self.ensure_fs_rename_fn();
+        self.call_function(
+            func,
+            &[source_ptr.into(), target_ptr.into()],
+            "tea_fs_rename",
+        )?;
+        Ok(ExprValue::Void)
+    }
 
-        // Get list length
-        let list_len_fn = self.ensure_list_len_ffi_fn();
-        let length = map_builder_error(self.builder.build_call(
-            list_len_fn,
-            &[list_ptr.into()],
-            "list_len",
-        ))?
-        .try_as_basic_value()
-        .left()
-        .ok_or_else(|| anyhow!("expected i64 from list_len"))?
-        .into_int_value();
-
-        // Store accumulator in alloca for updates within loop
-        let acc_alloca =
-            self.create_entry_alloca(function, "reduce_acc", self.basic_type(&init_type)?)?;
-        let init_basic = init_value
-            .into_basic_value()
-            .ok_or_else(|| anyhow!("init value must produce a basic value"))?;
-        map_builder_error(self.builder.build_store(acc_alloca, init_basic))?;
-
-        // Create loop blocks
-        let current_block = self
-            .builder
-            .get_insert_block()
-            .ok_or_else(|| anyhow!("missing insertion block"))?;
-        let cond_block = self.context.append_basic_block(function, "reduce_cond");
-        let body_block = self.context.append_basic_block(function, "reduce_body");
-        let exit_block = self.context.append_basic_block(function, "reduce_exit");
-
-        // Branch to condition
-        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
-
-        // Condition block
-        self.builder.position_at_end(cond_block);
-        let i64_type = self.context.i64_type();
-        let index_phi = map_builder_error(self.builder.build_phi(i64_type, "index"))?;
-        index_phi.add_incoming(&[(&i64_type.const_zero(), current_block)]);
-
-        let cond = map_builder_error(self.builder.build_int_compare(
-            IntPredicate::SLT,
-            index_phi.as_basic_value().into_int_value(),
-            length,
-            "reduce_cond",
-        ))?;
-        map_builder_error(
-            self.builder
-                .build_conditional_branch(cond, body_block, exit_block),
-        )?;
-
-        // Body block
-        self.builder.position_at_end(body_block);
-
-        // Load current accumulator
-        let acc_basic = map_builder_error(self.builder.build_load(
-            self.basic_type(&init_type)?,
-            acc_alloca,
-            "acc_load",
-        ))?;
-        let acc_expr = self.basic_to_expr_value(acc_basic, &init_type)?;
-
-        // Get element at index
-        let list_get_fn = self.ensure_list_get();
-        let tea_value = map_builder_error(self.builder.build_call(
-            list_get_fn,
-            &[list_ptr.into(), index_phi.as_basic_value().into()],
-            "element_value",
-        ))?
-        .try_as_basic_value()
-        .left()
-        .ok_or_else(|| anyhow!("expected TeaValue"))?
-        .into_struct_value();
-
-        // Convert to proper type
-        let element = self.tea_value_to_expr_value(tea_value, &element_type)?;
-
-        // Call closure with accumulator and element
-        let new_acc = self.call_closure_with_args(
-            closure_ptr,
-            &param_types,
-            &return_type,
-            vec![acc_expr, element],
-            function,
-        )?;
-
-        // Store new accumulator
-        let new_acc_basic = new_acc
-            .into_basic_value()
-            .ok_or_else(|| anyhow!("reducer must return a value"))?;
-        map_builder_error(self.builder.build_store(acc_alloca, new_acc_basic))?;
-
-        // Increment index
-        let next_index = map_builder_error(self.builder.build_int_add(
-            index_phi.as_basic_value().into_int_value(),
-            i64_type.const_int(1, false),
-            "next_index",
-        ))?;
-        index_phi.add_incoming(&[(&next_index, body_block)]);
-        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
+    fn compile_fs_open_read_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 1 {
+            bail!("fs_open_read expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for fs_open_read");
+        }
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr =
+            self.expect_string_pointer(path_expr, "fs_open_read expects a String path")?;
+        let func = self.ensure_fs_open_read_fn();
+        let handle = self
+            .call_function(func, &[path_ptr.into()], "tea_fs_open_read")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_fs_open_read returned no value"))?
+            .into_int_value();
+        Ok(ExprValue::Int(handle))
+    }
 
-        // Exit block
-        self.builder.position_at_end(exit_block);
-        let final_acc = map_builder_error(self.builder.build_load(
-            self.basic_type(&init_type)?,
-            acc_alloca,
-            "final_acc",
-        ))?;
-        self.basic_to_expr_value(final_acc, &init_type)
+    fn compile_fs_close_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 1 {
+            bail!("fs_close expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for fs_close");
+        }
+        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let handle = self.expect_int_value(handle_expr, "fs_close expects an Int handle")?;
+        let func = self.ensure_fs_close_fn();
+        self.call_function(func, &[handle.into()], "tea_fs_close")?;
+        Ok(ExprValue::Void)
     }
 
-    /// Compile list.find(fn) - first matching element (nil if none)
-    fn compile_list_find(
+    fn compile_fs_copy_call(
         &mut self,
-        list_ptr: PointerValue<'ctx>,
-        element_type: Box<ValueType>,
-        call: &CallExpression,
+        arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        // Get the callback closure
-        let callback = self.compile_expression(&call.arguments[0].expression, function, locals)?;
-        let (closure_ptr, param_types, _return_type) = match callback {
-            ExprValue::Closure {
-                pointer,
-                param_types,
-                return_type,
-            } => (pointer, param_types, return_type),
-            _ => bail!("List.find expects a function argument"),
+        if arguments.len() != 2 {
+            bail!("copy expects exactly 2 arguments");
+        }
+        for argument in arguments {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported for copy");
+            }
+        }
+        let source_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let source_ptr = match source_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("copy expects the source argument to be a String"),
         };
+        let target_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let target_ptr = match target_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("copy expects the target argument to be a String"),
+        };
+        let func = self.ensure_fs_copy_fn();
+        self.call_function(func, &[source_ptr.into(), target_ptr.into()], "tea_fs_copy")?;
+        Ok(ExprValue::Void)
+    }
 
-        // Get list length
-        let list_len_fn = self.ensure_list_len_ffi_fn();
-        let length = map_builder_error(self.builder.build_call(
-            list_len_fn,
-            &[list_ptr.into()],
-            "list_len",
-        ))?
-        .try_as_basic_value()
-        .left()
-        .ok_or_else(|| anyhow!("expected i64 from list_len"))?
-        .into_int_value();
+    fn compile_fs_stat_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        self.compile_fs_metadata_call(arguments, function, locals)
+    }
 
-        // Create result alloca for optional value
-        let result_alloca =
-            map_builder_error(self.builder.build_alloca(self.value_type(), "find_result"))?;
-        // Initialize with nil
-        let value_nil_fn = self.ensure_value_nil();
-        let nil_value = map_builder_error(self.builder.build_call(value_nil_fn, &[], "nil_value"))?
+    fn compile_fs_glob_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 1 {
+            bail!("glob expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for glob");
+        }
+        let pattern_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let pattern_ptr = match pattern_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("glob expects the pattern argument to be a String"),
+        };
+        let func = self.ensure_fs_glob_fn();
+        let pointer = self
+            .call_function(func, &[pattern_ptr.into()], "tea_fs_glob")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("expected TeaValue from value_nil"))?;
-        map_builder_error(self.builder.build_store(result_alloca, nil_value))?;
-
-        // Create loop blocks
-        let current_block = self
-            .builder
-            .get_insert_block()
-            .ok_or_else(|| anyhow!("missing insertion block"))?;
-        let cond_block = self.context.append_basic_block(function, "find_cond");
-        let body_block = self.context.append_basic_block(function, "find_body");
-        let found_block = self.context.append_basic_block(function, "find_found");
-        let continue_block = self.context.append_basic_block(function, "find_continue");
-        let exit_block = self.context.append_basic_block(function, "find_exit");
+            .ok_or_else(|| anyhow!("tea_fs_glob returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::List {
+            pointer,
+            element_type: Box::new(ValueType::String),
+        })
+    }
 
-        // Branch to condition
-        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
+    fn compile_fs_metadata_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 1 {
+            bail!("metadata expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for metadata");
+        }
+        let path_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let path_ptr = match path_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("metadata expects the path argument to be a String"),
+        };
+        let func = self.ensure_fs_metadata_fn();
+        let value = self
+            .call_function(func, &[path_ptr.into()], "tea_fs_metadata")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_fs_metadata returned no value"))?
+            .into_struct_value();
+        self.tea_value_to_expr(value, ValueType::Dict(Box::new(ValueType::Void)))
+    }
 
-        // Condition block
-        self.builder.position_at_end(cond_block);
-        let i64_type = self.context.i64_type();
-        let index_phi = map_builder_error(self.builder.build_phi(i64_type, "index"))?;
-        index_phi.add_incoming(&[(&i64_type.const_zero(), current_block)]);
-
-        let cond = map_builder_error(self.builder.build_int_compare(
-            IntPredicate::SLT,
-            index_phi.as_basic_value().into_int_value(),
-            length,
-            "find_cond",
-        ))?;
-        map_builder_error(
-            self.builder
-                .build_conditional_branch(cond, body_block, exit_block),
-        )?;
+    fn call_closure(
+        &mut self,
+        closure_ptr: PointerValue<'ctx>,
+        param_types: &[ValueType],
+        return_type: &ValueType,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if param_types.len() != call.arguments.len() {
+            bail!(
+                "closure expects {} arguments, found {}",
+                param_types.len(),
+                call.arguments.len()
+            );
+        }
 
-        // Body block
-        self.builder.position_at_end(body_block);
+        let mut arg_values: Vec<BasicValueEnum<'ctx>> = Vec::with_capacity(call.arguments.len());
+        for (index, (expected_type, argument)) in
+            param_types.iter().zip(call.arguments.iter()).enumerate()
+        {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported by the LLVM backend yet");
+            }
+            let value = self.compile_expression(&argument.expression, function, locals)?;
+            let converted = self
+                .convert_expr_to_type(value, expected_type)
+                .map_err(|error| {
+                    anyhow!(
+                        "argument {} to closure has mismatched type: {}",
+                        index + 1,
+                        error
+                    )
+                })?;
+            let basic = converted
+                .into_basic_value()
+                .ok_or_else(|| anyhow!("argument must produce a value"))?;
+            arg_values.push(basic);
+        }
 
-        // Get element at index
-        let list_get_fn = self.ensure_list_get();
-        let tea_value = map_builder_error(self.builder.build_call(
-            list_get_fn,
-            &[list_ptr.into(), index_phi.as_basic_value().into()],
-            "element_value",
+        let fn_ptr_ptr = map_builder_error(self.builder.build_struct_gep(
+            self.tea_closure,
+            closure_ptr,
+            0,
+            "closure_fn_ptr",
+        ))?;
+        let raw_fn_ptr = map_builder_error(self.builder.build_load(
+            self.ptr_type,
+            fn_ptr_ptr,
+            "closure_fn",
         ))?
-        .try_as_basic_value()
-        .left()
-        .ok_or_else(|| anyhow!("expected TeaValue"))?
-        .into_struct_value();
-
-        // Store tea_value for potential use in found_block
-        let element_alloca =
-            map_builder_error(self.builder.build_alloca(self.value_type(), "element_temp"))?;
-        map_builder_error(self.builder.build_store(element_alloca, tea_value))?;
-
-        // Convert to proper type
-        let element = self.tea_value_to_expr_value(tea_value, &element_type)?;
+        .into_pointer_value();
 
-        // Call closure with element
-        let predicate_result = self.call_closure_with_args(
-            closure_ptr,
-            &param_types,
-            &Box::new(ValueType::Bool),
-            vec![element],
-            function,
-        )?;
+        let mut llvm_params: Vec<BasicMetadataTypeEnum<'ctx>> =
+            Vec::with_capacity(param_types.len() + 1);
+        llvm_params.push(self.closure_ptr_type().into());
+        for ty in param_types {
+            llvm_params.push(self.basic_type(ty)?.into());
+        }
 
-        // Check predicate result
-        let bool_val = match predicate_result {
-            ExprValue::Bool(v) => v,
-            _ => bail!("find predicate must return Bool"),
+        let fn_type = match return_type {
+            ValueType::Void => self.context.void_type().fn_type(&llvm_params, false),
+            ValueType::Int | ValueType::Iterator(_) => self.int_type().fn_type(&llvm_params, false),
+            ValueType::Float => self.float_type().fn_type(&llvm_params, false),
+            ValueType::Bool => self.bool_type().fn_type(&llvm_params, false),
+            ValueType::String | ValueType::Enum(_) => {
+                self.string_ptr_type().fn_type(&llvm_params, false)
+            }
+            ValueType::Bytes => self.bytes_ptr_type().fn_type(&llvm_params, false),
+            ValueType::List(_) => self.list_ptr_type().fn_type(&llvm_params, false),
+            ValueType::Dict(_) => self.dict_ptr_type().fn_type(&llvm_params, false),
+            ValueType::Struct(_) => self.struct_ptr_type().fn_type(&llvm_params, false),
+            ValueType::Error { .. } => self.error_ptr_type().fn_type(&llvm_params, false),
+            ValueType::Function(_, _) => self.closure_ptr_type().fn_type(&llvm_params, false),
+            ValueType::Optional(_) => self.value_type().fn_type(&llvm_params, false),
+            ValueType::Any => self.value_type().fn_type(&llvm_params, false),
         };
 
-        let is_true = map_builder_error(self.builder.build_int_compare(
-            IntPredicate::NE,
-            bool_val,
-            self.context.i32_type().const_zero(),
-            "is_true",
-        ))?;
-
-        map_builder_error(self.builder.build_conditional_branch(
-            is_true,
-            found_block,
-            continue_block,
-        ))?;
+        let typed_fn_ptr = map_builder_error(self.builder.build_bit_cast(
+            raw_fn_ptr,
+            self.ptr_type,
+            "closure_callee",
+        ))?
+        .into_pointer_value();
 
-        // Found block - store element and exit
-        self.builder.position_at_end(found_block);
-        let found_element = map_builder_error(self.builder.build_load(
-            self.value_type(),
-            element_alloca,
-            "found_element",
-        ))?;
-        map_builder_error(self.builder.build_store(result_alloca, found_element))?;
-        map_builder_error(self.builder.build_unconditional_branch(exit_block))?;
+        let mut args: Vec<BasicMetadataValueEnum<'ctx>> = Vec::with_capacity(arg_values.len() + 1);
+        args.push(closure_ptr.into());
+        for value in arg_values {
+            args.push(BasicMetadataValueEnum::from(value));
+        }
 
-        // Continue block - increment and loop
-        self.builder.position_at_end(continue_block);
-        let next_index = map_builder_error(self.builder.build_int_add(
-            index_phi.as_basic_value().into_int_value(),
-            i64_type.const_int(1, false),
-            "next_index",
+        let call_site = map_builder_error(self.builder.build_indirect_call(
+            fn_type,
+            typed_fn_ptr,
+            &args,
+            "closure_call",
         ))?;
-        index_phi.add_incoming(&[(&next_index, continue_block)]);
-        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
 
-        // Exit block
-        self.builder.position_at_end(exit_block);
-        let final_result = map_builder_error(self.builder.build_load(
-            self.value_type(),
-            result_alloca,
-            "final_result",
-        ))?
-        .into_struct_value();
+        if matches!(return_type, ValueType::Void) {
+            self.handle_possible_error(function)?;
+            return Ok(ExprValue::Void);
+        }
 
-        Ok(ExprValue::Optional {
-            value: final_result,
-            inner: element_type,
-        })
-    }
+        let result = call_site
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("closure returned no value"))?;
 
-    /// Compile list.any(fn) - true if any element matches
-    fn compile_list_any(
-        &mut self,
-        list_ptr: PointerValue<'ctx>,
-        element_type: Box<ValueType>,
-        call: &CallExpression,
-        function: FunctionValue<'ctx>,
-        locals: &mut HashMap<String, LocalVariable<'ctx>>,
-    ) -> Result<ExprValue<'ctx>> {
-        self.compile_list_any_all(list_ptr, element_type, call, function, locals, true)
-    }
+        let expr = match return_type {
+            ValueType::Int => ExprValue::Int(result.into_int_value()),
+            ValueType::Iterator(element_type) => ExprValue::Iterator {
+                handle: result.into_int_value(),
+                element_type: element_type.clone(),
+            },
+            ValueType::Float => ExprValue::Float(result.into_float_value()),
+            ValueType::Bool => ExprValue::Bool(result.into_int_value()),
+            ValueType::String | ValueType::Enum(_) => {
+                ExprValue::String(result.into_pointer_value())
+            }
+            ValueType::Bytes => ExprValue::Bytes(result.into_pointer_value()),
+            ValueType::List(inner) => ExprValue::List {
+                pointer: result.into_pointer_value(),
+                element_type: inner.clone(),
+            },
+            ValueType::Dict(inner) => ExprValue::Dict {
+                pointer: result.into_pointer_value(),
+                value_type: inner.clone(),
+            },
+            ValueType::Struct(struct_name) => ExprValue::Struct {
+                pointer: result.into_pointer_value(),
+                struct_name: struct_name.clone(),
+            },
+            ValueType::Error {
+                error_name,
+                variant_name,
+            } => ExprValue::Error {
+                pointer: result.into_pointer_value(),
+                error_name: error_name.clone(),
+                variant_name: variant_name.clone(),
+            },
+            ValueType::Function(params, ret) => ExprValue::Closure {
+                pointer: result.into_pointer_value(),
+                param_types: params.clone(),
+                return_type: ret.clone(),
+            },
+            ValueType::Optional(inner) => ExprValue::Optional {
+                value: result.into_struct_value(),
+                inner: inner.clone(),
+            },
+            ValueType::Void => unreachable!(),
+            ValueType::Any => ExprValue::Any {
+                value: result.into_struct_value(),
+            },
+        };
+        self.handle_possible_error(function)?;
 
-    /// Compile list.all(fn) - true if all elements match
-    fn compile_list_all(
-        &mut self,
-        list_ptr: PointerValue<'ctx>,
-        element_type: Box<ValueType>,
-        call: &CallExpression,
-        function: FunctionValue<'ctx>,
-        locals: &mut HashMap<String, LocalVariable<'ctx>>,
-    ) -> Result<ExprValue<'ctx>> {
-        self.compile_list_any_all(list_ptr, element_type, call, function, locals, false)
+        Ok(expr)
     }
 
-    /// Compile list.contains(value) - true when the list contains the value.
-    fn compile_list_contains(
+    /// Compile a List method call.
+    fn compile_list_method_call(
         &mut self,
+        method_name: &str,
         list_ptr: PointerValue<'ctx>,
         element_type: Box<ValueType>,
         call: &CallExpression,
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        let needle_expr =
-            self.compile_expression(&call.arguments[0].expression, function, locals)?;
-        let needle = self.convert_expr_to_type(needle_expr, &element_type)?;
-
-        let list_len_fn = self.ensure_list_len_ffi_fn();
-        let length = map_builder_error(self.builder.build_call(
-            list_len_fn,
-            &[list_ptr.into()],
-            "list_len",
+        match method_name {
+            "map" => self.compile_list_map(list_ptr, element_type, call, function, locals),
+            "filter" => self.compile_list_filter(list_ptr, element_type, call, function, locals),
+            "reduce" => self.compile_list_reduce(list_ptr, element_type, call, function, locals),
+            "find" => self.compile_list_find(list_ptr, element_type, call, function, locals),
+            "any" => self.compile_list_any(list_ptr, element_type, call, function, locals),
+            "all" => self.compile_list_all(list_ptr, element_type, call, function, locals),
+            "contains" => {
+                self.compile_list_contains(list_ptr, element_type, call, function, locals)
+            }
+            "index_of" => {
+                self.compile_list_index_of(list_ptr, element_type, call, function, locals)
+            }
+            "find_index" => {
+                self.compile_list_find_index(list_ptr, element_type, call, function, locals)
+            }
+            "take" => self.compile_list_take(list_ptr, element_type, call, function, locals),
+            "skip" => self.compile_list_skip(list_ptr, element_type, call, function, locals),
+            "sort" | "sorted" | "reverse" | "reversed" => {
+                self.compile_list_reorder(method_name, list_ptr, element_type)
+            }
+            "sort_by" | "sorted_by" => self.compile_list_sort_by(
+                method_name == "sorted_by",
+                list_ptr,
+                element_type,
+                call,
+                function,
+                locals,
+            ),
+            "unique" => self.compile_list_unique(list_ptr, element_type),
+            "group_by" => {
+                self.compile_list_group_by(list_ptr, element_type, call, function, locals)
+            }
+            "partition" => {
+                self.compile_list_partition(list_ptr, element_type, call, function, locals)
+            }
+            "zip" => self.compile_list_zip(list_ptr, element_type, call, function, locals),
+            "flat_map" => {
+                self.compile_list_flat_map(list_ptr, element_type, call, function, locals)
+            }
+            "chunk" | "window" => self.compile_list_chunk_window(
+                method_name == "chunk",
+                list_ptr,
+                element_type,
+                call,
+                function,
+                locals,
+            ),
+            "join" => self.compile_list_join(list_ptr, call, function, locals),
+            "sum" => self.compile_list_sum(list_ptr, element_type),
+            "min" | "max" | "pop" => {
+                self.compile_list_optional_item(method_name, list_ptr, element_type)
+            }
+            "insert" => self.compile_list_insert(list_ptr, call, function, locals),
+            "remove_at" => {
+                self.compile_list_remove_at(list_ptr, element_type, call, function, locals)
+            }
+            _ => bail!("unknown List method: {}", method_name),
+        }
+    }
+
+    /// Compile list.map(fn) - transform each element
+    fn compile_list_map(
+        &mut self,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        // Get the callback closure
+        let callback = self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let (closure_ptr, param_types, return_type) = match callback {
+            ExprValue::Closure {
+                pointer,
+                param_types,
+                return_type,
+            } => (pointer, param_types, return_type),
+            _ => bail!("List.map expects a function argument"),
+        };
+
+        // Get list length
+        let list_len_fn = self.ensure_list_len_ffi_fn();
+        let length = map_builder_error(self.builder.build_call(
+            list_len_fn,
+            &[list_ptr.into()],
+            "list_len",
         ))?
         .try_as_basic_value()
         .left()
         .ok_or_else(|| anyhow!("expected i64 from list_len"))?
         .into_int_value();
 
-        let i32_type = self.context.i32_type();
-        let result_alloca =
-            map_builder_error(self.builder.build_alloca(i32_type, "list_contains_result"))?;
-        map_builder_error(
-            self.builder
-                .build_store(result_alloca, i32_type.const_zero()),
-        )?;
+        // Allocate result list
+        let alloc_list_fn = self.ensure_alloc_list();
+        let result_list = map_builder_error(self.builder.build_call(
+            alloc_list_fn,
+            &[length.into()],
+            "result_list",
+        ))?
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| anyhow!("expected pointer from alloc_list"))?
+        .into_pointer_value();
 
+        // Create loop blocks
         let current_block = self
             .builder
             .get_insert_block()
             .ok_or_else(|| anyhow!("missing insertion block"))?;
-        let cond_block = self.context.append_basic_block(function, "contains_cond");
-        let body_block = self.context.append_basic_block(function, "contains_body");
-        let found_block = self.context.append_basic_block(function, "contains_found");
-        let continue_block = self
-            .context
-            .append_basic_block(function, "contains_continue");
-        let exit_block = self.context.append_basic_block(function, "contains_exit");
+        let cond_block = self.context.append_basic_block(function, "map_cond");
+        let body_block = self.context.append_basic_block(function, "map_body");
+        let exit_block = self.context.append_basic_block(function, "map_exit");
 
+        // Branch to condition
         map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
 
+        // Condition block
         self.builder.position_at_end(cond_block);
         let i64_type = self.context.i64_type();
         let index_phi = map_builder_error(self.builder.build_phi(i64_type, "index"))?;
@@ -11793,14 +13601,17 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             IntPredicate::SLT,
             index_phi.as_basic_value().into_int_value(),
             length,
-            "contains_cond",
+            "map_cond",
         ))?;
         map_builder_error(
             self.builder
                 .build_conditional_branch(cond, body_block, exit_block),
         )?;
 
+        // Body block
         self.builder.position_at_end(body_block);
+
+        // Get element at index
         let list_get_fn = self.ensure_list_get();
         let tea_value = map_builder_error(self.builder.build_call(
             list_get_fn,
@@ -11812,48 +13623,51 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         .ok_or_else(|| anyhow!("expected TeaValue"))?
         .into_struct_value();
 
+        // Convert to proper type
         let element = self.tea_value_to_expr_value(tea_value, &element_type)?;
-        let equals = self.build_equality(function, element, needle.clone(), true)?;
-        let is_match = match equals {
-            ExprValue::Bool(value) => value,
-            _ => bail!("list.contains comparison must produce Bool"),
-        };
-
-        map_builder_error(self.builder.build_conditional_branch(
-            is_match,
-            found_block,
-            continue_block,
-        ))?;
 
-        self.builder.position_at_end(found_block);
-        map_builder_error(
-            self.builder
-                .build_store(result_alloca, i32_type.const_int(1, false)),
+        // Call closure with element
+        let call_result = self.call_closure_with_args(
+            closure_ptr,
+            &param_types,
+            &return_type,
+            vec![element],
+            function,
         )?;
-        map_builder_error(self.builder.build_unconditional_branch(exit_block))?;
 
-        self.builder.position_at_end(continue_block);
+        // Convert result to TeaValue and store in result list
+        let result_tea_value = self.expr_to_tea_value(call_result)?;
+        let list_set_fn = self.ensure_list_set();
+        map_builder_error(self.builder.build_call(
+            list_set_fn,
+            &[
+                result_list.into(),
+                index_phi.as_basic_value().into(),
+                result_tea_value.into(),
+            ],
+            "",
+        ))?;
+
+        // Increment index
         let next_index = map_builder_error(self.builder.build_int_add(
             index_phi.as_basic_value().into_int_value(),
             i64_type.const_int(1, false),
             "next_index",
         ))?;
-        index_phi.add_incoming(&[(&next_index, continue_block)]);
+        index_phi.add_incoming(&[(&next_index, body_block)]);
         map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
 
+        // Exit block
         self.builder.position_at_end(exit_block);
-        let result = map_builder_error(self.builder.build_load(
-            i32_type,
-            result_alloca,
-            "list_contains_final",
-        ))?
-        .into_int_value();
 
-        Ok(ExprValue::Bool(result))
+        Ok(ExprValue::List {
+            pointer: result_list,
+            element_type: return_type,
+        })
     }
 
-    /// Compile list.index_of(value) - the index of the first matching value or -1.
-    fn compile_list_index_of(
+    /// Compile list.filter(fn) - keep elements matching predicate
+    fn compile_list_filter(
         &mut self,
         list_ptr: PointerValue<'ctx>,
         element_type: Box<ValueType>,
@@ -11861,10 +13675,18 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        let needle_expr =
-            self.compile_expression(&call.arguments[0].expression, function, locals)?;
-        let needle = self.convert_expr_to_type(needle_expr, &element_type)?;
+        // Get the callback closure
+        let callback = self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let (closure_ptr, param_types, _return_type) = match callback {
+            ExprValue::Closure {
+                pointer,
+                param_types,
+                return_type,
+            } => (pointer, param_types, return_type),
+            _ => bail!("List.filter expects a function argument"),
+        };
 
+        // Get list length
         let list_len_fn = self.ensure_list_len_ffi_fn();
         let length = map_builder_error(self.builder.build_call(
             list_len_fn,
@@ -11876,29 +13698,35 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         .ok_or_else(|| anyhow!("expected i64 from list_len"))?
         .into_int_value();
 
-        let i64_type = self.context.i64_type();
-        let result_alloca =
-            map_builder_error(self.builder.build_alloca(i64_type, "list_index_of_result"))?;
-        map_builder_error(
-            self.builder
-                .build_store(result_alloca, i64_type.const_all_ones()),
-        )?;
+        // Allocate result list (start with same capacity, will be smaller or equal)
+        let alloc_list_fn = self.ensure_alloc_list();
+        let result_list = map_builder_error(self.builder.build_call(
+            alloc_list_fn,
+            &[self.context.i64_type().const_zero().into()],
+            "result_list",
+        ))?
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| anyhow!("expected pointer from alloc_list"))?
+        .into_pointer_value();
 
+        // Create loop blocks
         let current_block = self
             .builder
             .get_insert_block()
             .ok_or_else(|| anyhow!("missing insertion block"))?;
-        let cond_block = self.context.append_basic_block(function, "index_of_cond");
-        let body_block = self.context.append_basic_block(function, "index_of_body");
-        let found_block = self.context.append_basic_block(function, "index_of_found");
-        let continue_block = self
-            .context
-            .append_basic_block(function, "index_of_continue");
-        let exit_block = self.context.append_basic_block(function, "index_of_exit");
+        let cond_block = self.context.append_basic_block(function, "filter_cond");
+        let body_block = self.context.append_basic_block(function, "filter_body");
+        let append_block = self.context.append_basic_block(function, "filter_append");
+        let continue_block = self.context.append_basic_block(function, "filter_continue");
+        let exit_block = self.context.append_basic_block(function, "filter_exit");
 
+        // Branch to condition
         map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
 
+        // Condition block
         self.builder.position_at_end(cond_block);
+        let i64_type = self.context.i64_type();
         let index_phi = map_builder_error(self.builder.build_phi(i64_type, "index"))?;
         index_phi.add_incoming(&[(&i64_type.const_zero(), current_block)]);
 
@@ -11906,14 +13734,17 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             IntPredicate::SLT,
             index_phi.as_basic_value().into_int_value(),
             length,
-            "index_of_cond",
+            "filter_cond",
         ))?;
         map_builder_error(
             self.builder
                 .build_conditional_branch(cond, body_block, exit_block),
         )?;
 
+        // Body block
         self.builder.position_at_end(body_block);
+
+        // Get element at index
         let list_get_fn = self.ensure_list_get();
         let tea_value = map_builder_error(self.builder.build_call(
             list_get_fn,
@@ -11925,24 +13756,50 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         .ok_or_else(|| anyhow!("expected TeaValue"))?
         .into_struct_value();
 
+        // Convert to proper type
         let element = self.tea_value_to_expr_value(tea_value, &element_type)?;
-        let equals = self.build_equality(function, element, needle.clone(), true)?;
-        let is_match = match equals {
-            ExprValue::Bool(value) => value,
-            _ => bail!("list.index_of comparison must produce Bool"),
+
+        // Call closure with element
+        let predicate_result = self.call_closure_with_args(
+            closure_ptr,
+            &param_types,
+            &Box::new(ValueType::Bool),
+            vec![element.clone()],
+            function,
+        )?;
+
+        // Check predicate result
+        let bool_val = match predicate_result {
+            ExprValue::Bool(v) => v,
+            _ => bail!("filter predicate must return Bool"),
         };
 
+        // Compare with true (1)
+        let is_true = map_builder_error(self.builder.build_int_compare(
+            IntPredicate::NE,
+            bool_val,
+            self.context.i32_type().const_zero(),
+            "is_true",
+        ))?;
+
         map_builder_error(self.builder.build_conditional_branch(
-            is_match,
-            found_block,
+            is_true,
+            append_block,
             continue_block,
         ))?;
 
-        self.builder.position_at_end(found_block);
-        let current_index = index_phi.as_basic_value().into_int_value();
-        map_builder_error(self.builder.build_store(result_alloca, current_index))?;
-        map_builder_error(self.builder.build_unconditional_branch(exit_block))?;
+        // Append block - add element to result
+        self.builder.position_at_end(append_block);
+        let append_fn = self.ensure_list_append_fn();
+        let element_tea_value = self.expr_to_tea_value(element)?;
+        map_builder_error(self.builder.build_call(
+            append_fn,
+            &[result_list.into(), element_tea_value.into()],
+            "",
+        ))?;
+        map_builder_error(self.builder.build_unconditional_branch(continue_block))?;
 
+        // Continue block - increment and loop
         self.builder.position_at_end(continue_block);
         let next_index = map_builder_error(self.builder.build_int_add(
             index_phi.as_basic_value().into_int_value(),
@@ -11952,19 +13809,17 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         index_phi.add_incoming(&[(&next_index, continue_block)]);
         map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
 
+        // Exit block
         self.builder.position_at_end(exit_block);
-        let result = map_builder_error(self.builder.build_load(
-            i64_type,
-            result_alloca,
-            "list_index_of_final",
-        ))?
-        .into_int_value();
 
-        Ok(ExprValue::Int(result))
+        Ok(ExprValue::List {
+            pointer: result_list,
+            element_type,
+        })
     }
 
-    /// Compile list.find_index(fn) - the first matching index or -1.
-    fn compile_list_find_index(
+    /// Compile list.reduce(init, fn) - fold list to single value
+    fn compile_list_reduce(
         &mut self,
         list_ptr: PointerValue<'ctx>,
         element_type: Box<ValueType>,
@@ -11972,16 +13827,23 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        let callback = self.compile_expression(&call.arguments[0].expression, function, locals)?;
-        let (closure_ptr, param_types, _return_type) = match callback {
+        // Get initial value
+        let init_value =
+            self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let init_type = init_value.ty();
+
+        // Get the callback closure
+        let callback = self.compile_expression(&call.arguments[1].expression, function, locals)?;
+        let (closure_ptr, param_types, return_type) = match callback {
             ExprValue::Closure {
                 pointer,
                 param_types,
                 return_type,
             } => (pointer, param_types, return_type),
-            _ => bail!("List.find_index expects a function argument"),
+            _ => bail!("List.reduce expects a function argument"),
         };
 
+        // Get list length
         let list_len_fn = self.ensure_list_len_ffi_fn();
         let length = map_builder_error(self.builder.build_call(
             list_len_fn,
@@ -11993,33 +13855,163 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         .ok_or_else(|| anyhow!("expected i64 from list_len"))?
         .into_int_value();
 
+        // Store accumulator in alloca for updates within loop
+        let acc_alloca =
+            self.create_entry_alloca(function, "reduce_acc", self.basic_type(&init_type)?)?;
+        let init_basic = init_value
+            .into_basic_value()
+            .ok_or_else(|| anyhow!("init value must produce a basic value"))?;
+        map_builder_error(self.builder.build_store(acc_alloca, init_basic))?;
+
+        // Create loop blocks
+        let current_block = self
+            .builder
+            .get_insert_block()
+            .ok_or_else(|| anyhow!("missing insertion block"))?;
+        let cond_block = self.context.append_basic_block(function, "reduce_cond");
+        let body_block = self.context.append_basic_block(function, "reduce_body");
+        let exit_block = self.context.append_basic_block(function, "reduce_exit");
+
+        // Branch to condition
+        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
+
+        // Condition block
+        self.builder.position_at_end(cond_block);
         let i64_type = self.context.i64_type();
-        let result_alloca = map_builder_error(
-            self.builder
-                .build_alloca(i64_type, "list_find_index_result"),
-        )?;
+        let index_phi = map_builder_error(self.builder.build_phi(i64_type, "index"))?;
+        index_phi.add_incoming(&[(&i64_type.const_zero(), current_block)]);
+
+        let cond = map_builder_error(self.builder.build_int_compare(
+            IntPredicate::SLT,
+            index_phi.as_basic_value().into_int_value(),
+            length,
+            "reduce_cond",
+        ))?;
         map_builder_error(
             self.builder
-                .build_store(result_alloca, i64_type.const_all_ones()),
+                .build_conditional_branch(cond, body_block, exit_block),
+        )?;
+
+        // Body block
+        self.builder.position_at_end(body_block);
+
+        // Load current accumulator
+        let acc_basic = map_builder_error(self.builder.build_load(
+            self.basic_type(&init_type)?,
+            acc_alloca,
+            "acc_load",
+        ))?;
+        let acc_expr = self.basic_to_expr_value(acc_basic, &init_type)?;
+
+        // Get element at index
+        let list_get_fn = self.ensure_list_get();
+        let tea_value = map_builder_error(self.builder.build_call(
+            list_get_fn,
+            &[list_ptr.into(), index_phi.as_basic_value().into()],
+            "element_value",
+        ))?
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| anyhow!("expected TeaValue"))?
+        .into_struct_value();
+
+        // Convert to proper type
+        let element = self.tea_value_to_expr_value(tea_value, &element_type)?;
+
+        // Call closure with accumulator and element
+        let new_acc = self.call_closure_with_args(
+            closure_ptr,
+            &param_types,
+            &return_type,
+            vec![acc_expr, element],
+            function,
         )?;
 
+        // Store new accumulator
+        let new_acc_basic = new_acc
+            .into_basic_value()
+            .ok_or_else(|| anyhow!("reducer must return a value"))?;
+        map_builder_error(self.builder.build_store(acc_alloca, new_acc_basic))?;
+
+        // Increment index
+        let next_index = map_builder_error(self.builder.build_int_add(
+            index_phi.as_basic_value().into_int_value(),
+            i64_type.const_int(1, false),
+            "next_index",
+        ))?;
+        index_phi.add_incoming(&[(&next_index, body_block)]);
+        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
+
+        // Exit block
+        self.builder.position_at_end(exit_block);
+        let final_acc = map_builder_error(self.builder.build_load(
+            self.basic_type(&init_type)?,
+            acc_alloca,
+            "final_acc",
+        ))?;
+        self.basic_to_expr_value(final_acc, &init_type)
+    }
+
+    /// Compile list.find(fn) - first matching element (nil if none)
+    fn compile_list_find(
+        &mut self,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        // Get the callback closure
+        let callback = self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let (closure_ptr, param_types, _return_type) = match callback {
+            ExprValue::Closure {
+                pointer,
+                param_types,
+                return_type,
+            } => (pointer, param_types, return_type),
+            _ => bail!("List.find expects a function argument"),
+        };
+
+        // Get list length
+        let list_len_fn = self.ensure_list_len_ffi_fn();
+        let length = map_builder_error(self.builder.build_call(
+            list_len_fn,
+            &[list_ptr.into()],
+            "list_len",
+        ))?
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| anyhow!("expected i64 from list_len"))?
+        .into_int_value();
+
+        // Create result alloca for optional value
+        let result_alloca =
+            map_builder_error(self.builder.build_alloca(self.value_type(), "find_result"))?;
+        // Initialize with nil
+        let value_nil_fn = self.ensure_value_nil();
+        let nil_value = map_builder_error(self.builder.build_call(value_nil_fn, &[], "nil_value"))?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected TeaValue from value_nil"))?;
+        map_builder_error(self.builder.build_store(result_alloca, nil_value))?;
+
+        // Create loop blocks
         let current_block = self
             .builder
             .get_insert_block()
             .ok_or_else(|| anyhow!("missing insertion block"))?;
-        let cond_block = self.context.append_basic_block(function, "find_index_cond");
-        let body_block = self.context.append_basic_block(function, "find_index_body");
-        let found_block = self
-            .context
-            .append_basic_block(function, "find_index_found");
-        let continue_block = self
-            .context
-            .append_basic_block(function, "find_index_continue");
-        let exit_block = self.context.append_basic_block(function, "find_index_exit");
+        let cond_block = self.context.append_basic_block(function, "find_cond");
+        let body_block = self.context.append_basic_block(function, "find_body");
+        let found_block = self.context.append_basic_block(function, "find_found");
+        let continue_block = self.context.append_basic_block(function, "find_continue");
+        let exit_block = self.context.append_basic_block(function, "find_exit");
 
+        // Branch to condition
         map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
 
+        // Condition block
         self.builder.position_at_end(cond_block);
+        let i64_type = self.context.i64_type();
         let index_phi = map_builder_error(self.builder.build_phi(i64_type, "index"))?;
         index_phi.add_incoming(&[(&i64_type.const_zero(), current_block)]);
 
@@ -12027,14 +14019,17 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             IntPredicate::SLT,
             index_phi.as_basic_value().into_int_value(),
             length,
-            "find_index_cond",
+            "find_cond",
         ))?;
         map_builder_error(
             self.builder
                 .build_conditional_branch(cond, body_block, exit_block),
         )?;
 
+        // Body block
         self.builder.position_at_end(body_block);
+
+        // Get element at index
         let list_get_fn = self.ensure_list_get();
         let tea_value = map_builder_error(self.builder.build_call(
             list_get_fn,
@@ -12046,7 +14041,15 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         .ok_or_else(|| anyhow!("expected TeaValue"))?
         .into_struct_value();
 
+        // Store tea_value for potential use in found_block
+        let element_alloca =
+            map_builder_error(self.builder.build_alloca(self.value_type(), "element_temp"))?;
+        map_builder_error(self.builder.build_store(element_alloca, tea_value))?;
+
+        // Convert to proper type
         let element = self.tea_value_to_expr_value(tea_value, &element_type)?;
+
+        // Call closure with element
         let predicate_result = self.call_closure_with_args(
             closure_ptr,
             &param_types,
@@ -12055,23 +14058,36 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             function,
         )?;
 
+        // Check predicate result
         let bool_val = match predicate_result {
             ExprValue::Bool(v) => v,
-            _ => bail!("find_index predicate must return Bool"),
+            _ => bail!("find predicate must return Bool"),
         };
 
-        let is_true = bool_val;
+        let is_true = map_builder_error(self.builder.build_int_compare(
+            IntPredicate::NE,
+            bool_val,
+            self.context.i32_type().const_zero(),
+            "is_true",
+        ))?;
+
         map_builder_error(self.builder.build_conditional_branch(
             is_true,
             found_block,
             continue_block,
         ))?;
 
+        // Found block - store element and exit
         self.builder.position_at_end(found_block);
-        let current_index = index_phi.as_basic_value().into_int_value();
-        map_builder_error(self.builder.build_store(result_alloca, current_index))?;
+        let found_element = map_builder_error(self.builder.build_load(
+            self.value_type(),
+            element_alloca,
+            "found_element",
+        ))?;
+        map_builder_error(self.builder.build_store(result_alloca, found_element))?;
         map_builder_error(self.builder.build_unconditional_branch(exit_block))?;
 
+        // Continue block - increment and loop
         self.builder.position_at_end(continue_block);
         let next_index = map_builder_error(self.builder.build_int_add(
             index_phi.as_basic_value().into_int_value(),
@@ -12081,18 +14097,23 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         index_phi.add_incoming(&[(&next_index, continue_block)]);
         map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
 
+        // Exit block
         self.builder.position_at_end(exit_block);
-        let result = map_builder_error(self.builder.build_load(
-            i64_type,
+        let final_result = map_builder_error(self.builder.build_load(
+            self.value_type(),
             result_alloca,
-            "list_find_index_final",
+            "final_result",
         ))?
-        .into_int_value();
+        .into_struct_value();
 
-        Ok(ExprValue::Int(result))
+        Ok(ExprValue::Optional {
+            value: final_result,
+            inner: element_type,
+        })
     }
 
-    fn compile_list_take(
+    /// Compile list.any(fn) - true if any element matches
+    fn compile_list_any(
         &mut self,
         list_ptr: PointerValue<'ctx>,
         element_type: Box<ValueType>,
@@ -12100,10 +14121,11 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        self.compile_list_take_skip(list_ptr, element_type, call, function, locals, true)
+        self.compile_list_any_all(list_ptr, element_type, call, function, locals, true)
     }
 
-    fn compile_list_skip(
+    /// Compile list.all(fn) - true if all elements match
+    fn compile_list_all(
         &mut self,
         list_ptr: PointerValue<'ctx>,
         element_type: Box<ValueType>,
@@ -12111,22 +14133,21 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        self.compile_list_take_skip(list_ptr, element_type, call, function, locals, false)
+        self.compile_list_any_all(list_ptr, element_type, call, function, locals, false)
     }
 
-    fn compile_list_take_skip(
+    /// Compile list.contains(value) - true when the list contains the value.
+    fn compile_list_contains(
         &mut self,
         list_ptr: PointerValue<'ctx>,
         element_type: Box<ValueType>,
         call: &CallExpression,
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
-        take: bool,
     ) -> Result<ExprValue<'ctx>> {
-        let count_expr =
+        let needle_expr =
             self.compile_expression(&call.arguments[0].expression, function, locals)?;
-        let count_value = self.convert_expr_to_type(count_expr, &ValueType::Int)?;
-        let count = count_value.into_int()?;
+        let needle = self.convert_expr_to_type(needle_expr, &element_type)?;
 
         let list_len_fn = self.ensure_list_len_ffi_fn();
         let length = map_builder_error(self.builder.build_call(
@@ -12139,85 +14160,109 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         .ok_or_else(|| anyhow!("expected i64 from list_len"))?
         .into_int_value();
 
-        let i64_type = self.context.i64_type();
-        let zero = i64_type.const_zero();
-        let is_negative = map_builder_error(self.builder.build_int_compare(
+        let i32_type = self.context.i32_type();
+        let result_alloca =
+            map_builder_error(self.builder.build_alloca(i32_type, "list_contains_result"))?;
+        map_builder_error(
+            self.builder
+                .build_store(result_alloca, i32_type.const_zero()),
+        )?;
+
+        let current_block = self
+            .builder
+            .get_insert_block()
+            .ok_or_else(|| anyhow!("missing insertion block"))?;
+        let cond_block = self.context.append_basic_block(function, "contains_cond");
+        let body_block = self.context.append_basic_block(function, "contains_body");
+        let found_block = self.context.append_basic_block(function, "contains_found");
+        let continue_block = self
+            .context
+            .append_basic_block(function, "contains_continue");
+        let exit_block = self.context.append_basic_block(function, "contains_exit");
+
+        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
+
+        self.builder.position_at_end(cond_block);
+        let i64_type = self.context.i64_type();
+        let index_phi = map_builder_error(self.builder.build_phi(i64_type, "index"))?;
+        index_phi.add_incoming(&[(&i64_type.const_zero(), current_block)]);
+
+        let cond = map_builder_error(self.builder.build_int_compare(
             IntPredicate::SLT,
-            count,
-            zero,
-            "list_slice_negative",
+            index_phi.as_basic_value().into_int_value(),
+            length,
+            "contains_cond",
         ))?;
-        let non_negative = map_builder_error(self.builder.build_select(
-            is_negative,
-            zero,
-            count,
-            "list_slice_non_negative",
+        map_builder_error(
+            self.builder
+                .build_conditional_branch(cond, body_block, exit_block),
+        )?;
+
+        self.builder.position_at_end(body_block);
+        let list_get_fn = self.ensure_list_get();
+        let tea_value = map_builder_error(self.builder.build_call(
+            list_get_fn,
+            &[list_ptr.into(), index_phi.as_basic_value().into()],
+            "element_value",
         ))?
-        .into_int_value();
-        let is_too_large = map_builder_error(self.builder.build_int_compare(
-            IntPredicate::SGT,
-            non_negative,
-            length,
-            "list_slice_too_large",
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| anyhow!("expected TeaValue"))?
+        .into_struct_value();
+
+        let element = self.tea_value_to_expr_value(tea_value, &element_type)?;
+        let equals = self.build_equality(function, element, needle.clone(), true)?;
+        let is_match = match equals {
+            ExprValue::Bool(value) => value,
+            _ => bail!("list.contains comparison must produce Bool"),
+        };
+
+        map_builder_error(self.builder.build_conditional_branch(
+            is_match,
+            found_block,
+            continue_block,
         ))?;
-        let clamped = map_builder_error(self.builder.build_select(
-            is_too_large,
-            length,
-            non_negative,
-            "list_slice_clamped",
+
+        self.builder.position_at_end(found_block);
+        map_builder_error(
+            self.builder
+                .build_store(result_alloca, i32_type.const_int(1, false)),
+        )?;
+        map_builder_error(self.builder.build_unconditional_branch(exit_block))?;
+
+        self.builder.position_at_end(continue_block);
+        let next_index = map_builder_error(self.builder.build_int_add(
+            index_phi.as_basic_value().into_int_value(),
+            i64_type.const_int(1, false),
+            "next_index",
+        ))?;
+        index_phi.add_incoming(&[(&next_index, continue_block)]);
+        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
+
+        self.builder.position_at_end(exit_block);
+        let result = map_builder_error(self.builder.build_load(
+            i32_type,
+            result_alloca,
+            "list_contains_final",
         ))?
         .into_int_value();
 
-        let start = if take { zero } else { clamped };
-        let end = if take { clamped } else { length };
-        let slice_fn = self.ensure_list_slice();
-        let result_ptr = self
-            .call_function(
-                slice_fn,
-                &[
-                    list_ptr.into(),
-                    start.into(),
-                    end.into(),
-                    self.context.bool_type().const_zero().into(),
-                ],
-                if take { "list_take" } else { "list_skip" },
-            )?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("expected List from list_slice"))?
-            .into_pointer_value();
-
-        Ok(ExprValue::List {
-            pointer: result_ptr,
-            element_type,
-        })
+        Ok(ExprValue::Bool(result))
     }
 
-    /// Shared implementation for any/all
-    fn compile_list_any_all(
+    /// Compile list.index_of(value) - the index of the first matching value or -1.
+    fn compile_list_index_of(
         &mut self,
         list_ptr: PointerValue<'ctx>,
         element_type: Box<ValueType>,
         call: &CallExpression,
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
-        is_any: bool,
     ) -> Result<ExprValue<'ctx>> {
-        // Get the callback closure
-        let callback = self.compile_expression(&call.arguments[0].expression, function, locals)?;
-        let (closure_ptr, param_types, _return_type) = match callback {
-            ExprValue::Closure {
-                pointer,
-                param_types,
-                return_type,
-            } => (pointer, param_types, return_type),
-            _ => bail!(
-                "List.{} expects a function argument",
-                if is_any { "any" } else { "all" }
-            ),
-        };
+        let needle_expr =
+            self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let needle = self.convert_expr_to_type(needle_expr, &element_type)?;
 
-        // Get list length
         let list_len_fn = self.ensure_list_len_ffi_fn();
         let length = map_builder_error(self.builder.build_call(
             list_len_fn,
@@ -12229,39 +14274,29 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         .ok_or_else(|| anyhow!("expected i64 from list_len"))?
         .into_int_value();
 
-        // Create result alloca
-        let i32_type = self.context.i32_type();
+        let i64_type = self.context.i64_type();
         let result_alloca =
-            map_builder_error(self.builder.build_alloca(i32_type, "any_all_result"))?;
-        // Initialize: any starts with false (0), all starts with true (1)
-        let initial = if is_any {
-            i32_type.const_zero()
-        } else {
-            i32_type.const_int(1, false)
-        };
-        map_builder_error(self.builder.build_store(result_alloca, initial))?;
+            map_builder_error(self.builder.build_alloca(i64_type, "list_index_of_result"))?;
+        map_builder_error(
+            self.builder
+                .build_store(result_alloca, i64_type.const_all_ones()),
+        )?;
 
-        // Create loop blocks
         let current_block = self
             .builder
             .get_insert_block()
             .ok_or_else(|| anyhow!("missing insertion block"))?;
-        let cond_block = self.context.append_basic_block(function, "any_all_cond");
-        let body_block = self.context.append_basic_block(function, "any_all_body");
-        let early_exit_block = self
-            .context
-            .append_basic_block(function, "any_all_early_exit");
+        let cond_block = self.context.append_basic_block(function, "index_of_cond");
+        let body_block = self.context.append_basic_block(function, "index_of_body");
+        let found_block = self.context.append_basic_block(function, "index_of_found");
         let continue_block = self
             .context
-            .append_basic_block(function, "any_all_continue");
-        let exit_block = self.context.append_basic_block(function, "any_all_exit");
+            .append_basic_block(function, "index_of_continue");
+        let exit_block = self.context.append_basic_block(function, "index_of_exit");
 
-        // Branch to condition
         map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
 
-        // Condition block
         self.builder.position_at_end(cond_block);
-        let i64_type = self.context.i64_type();
         let index_phi = map_builder_error(self.builder.build_phi(i64_type, "index"))?;
         index_phi.add_incoming(&[(&i64_type.const_zero(), current_block)]);
 
@@ -12269,17 +14304,14 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             IntPredicate::SLT,
             index_phi.as_basic_value().into_int_value(),
             length,
-            "any_all_cond",
+            "index_of_cond",
         ))?;
         map_builder_error(
             self.builder
                 .build_conditional_branch(cond, body_block, exit_block),
         )?;
 
-        // Body block
         self.builder.position_at_end(body_block);
-
-        // Get element at index
         let list_get_fn = self.ensure_list_get();
         let tea_value = map_builder_error(self.builder.build_call(
             list_get_fn,
@@ -12291,58 +14323,24 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         .ok_or_else(|| anyhow!("expected TeaValue"))?
         .into_struct_value();
 
-        // Convert to proper type
         let element = self.tea_value_to_expr_value(tea_value, &element_type)?;
-
-        // Call closure with element
-        let predicate_result = self.call_closure_with_args(
-            closure_ptr,
-            &param_types,
-            &Box::new(ValueType::Bool),
-            vec![element],
-            function,
-        )?;
-
-        // Check predicate result
-        let bool_val = match predicate_result {
-            ExprValue::Bool(v) => v,
-            _ => bail!("predicate must return Bool"),
+        let equals = self.build_equality(function, element, needle.clone(), true)?;
+        let is_match = match equals {
+            ExprValue::Bool(value) => value,
+            _ => bail!("list.index_of comparison must produce Bool"),
         };
 
-        let is_true = map_builder_error(self.builder.build_int_compare(
-            IntPredicate::NE,
-            bool_val,
-            i32_type.const_zero(),
-            "is_true",
+        map_builder_error(self.builder.build_conditional_branch(
+            is_match,
+            found_block,
+            continue_block,
         ))?;
 
-        // For any: if true, early exit with true
-        // For all: if false, early exit with false
-        if is_any {
-            map_builder_error(self.builder.build_conditional_branch(
-                is_true,
-                early_exit_block,
-                continue_block,
-            ))?;
-        } else {
-            map_builder_error(self.builder.build_conditional_branch(
-                is_true,
-                continue_block,
-                early_exit_block,
-            ))?;
-        }
-
-        // Early exit block
-        self.builder.position_at_end(early_exit_block);
-        let early_result = if is_any {
-            i32_type.const_int(1, false) // true for any
-        } else {
-            i32_type.const_zero() // false for all
-        };
-        map_builder_error(self.builder.build_store(result_alloca, early_result))?;
+        self.builder.position_at_end(found_block);
+        let current_index = index_phi.as_basic_value().into_int_value();
+        map_builder_error(self.builder.build_store(result_alloca, current_index))?;
         map_builder_error(self.builder.build_unconditional_branch(exit_block))?;
 
-        // Continue block
         self.builder.position_at_end(continue_block);
         let next_index = map_builder_error(self.builder.build_int_add(
             index_phi.as_basic_value().into_int_value(),
@@ -12352,1556 +14350,3567 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         index_phi.add_incoming(&[(&next_index, continue_block)]);
         map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
 
-        // Exit block
         self.builder.position_at_end(exit_block);
-        let final_result = map_builder_error(self.builder.build_load(
-            i32_type,
+        let result = map_builder_error(self.builder.build_load(
+            i64_type,
             result_alloca,
-            "final_result",
+            "list_index_of_final",
         ))?
         .into_int_value();
 
-        Ok(ExprValue::Bool(final_result))
+        Ok(ExprValue::Int(result))
     }
 
-    /// Helper to call a closure with pre-compiled arguments
-    fn call_closure_with_args(
+    /// Compile list.find_index(fn) - the first matching index or -1.
+    fn compile_list_find_index(
         &mut self,
-        closure_ptr: PointerValue<'ctx>,
-        param_types: &[ValueType],
-        return_type: &Box<ValueType>,
-        args: Vec<ExprValue<'ctx>>,
-        function: FunctionValue<'ctx>,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        let mut arg_values: Vec<BasicValueEnum<'ctx>> = Vec::with_capacity(args.len());
-        for (index, (expected_type, arg)) in param_types.iter().zip(args.into_iter()).enumerate() {
-            let converted = self
-                .convert_expr_to_type(arg, expected_type)
-                .map_err(|error| {
-                    anyhow!("argument {} has mismatched type: {}", index + 1, error)
-                })?;
-            let basic = converted
-                .into_basic_value()
-                .ok_or_else(|| anyhow!("argument must produce a value"))?;
-            arg_values.push(basic);
-        }
+        let callback = self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let (closure_ptr, param_types, _return_type) = match callback {
+            ExprValue::Closure {
+                pointer,
+                param_types,
+                return_type,
+            } => (pointer, param_types, return_type),
+            _ => bail!("List.find_index expects a function argument"),
+        };
 
-        let fn_ptr_ptr = map_builder_error(self.builder.build_struct_gep(
-            self.tea_closure,
-            closure_ptr,
-            0,
-            "closure_fn_ptr",
+        let list_len_fn = self.ensure_list_len_ffi_fn();
+        let length = map_builder_error(self.builder.build_call(
+            list_len_fn,
+            &[list_ptr.into()],
+            "list_len",
+        ))?
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| anyhow!("expected i64 from list_len"))?
+        .into_int_value();
+
+        let i64_type = self.context.i64_type();
+        let result_alloca = map_builder_error(
+            self.builder
+                .build_alloca(i64_type, "list_find_index_result"),
+        )?;
+        map_builder_error(
+            self.builder
+                .build_store(result_alloca, i64_type.const_all_ones()),
+        )?;
+
+        let current_block = self
+            .builder
+            .get_insert_block()
+            .ok_or_else(|| anyhow!("missing insertion block"))?;
+        let cond_block = self.context.append_basic_block(function, "find_index_cond");
+        let body_block = self.context.append_basic_block(function, "find_index_body");
+        let found_block = self
+            .context
+            .append_basic_block(function, "find_index_found");
+        let continue_block = self
+            .context
+            .append_basic_block(function, "find_index_continue");
+        let exit_block = self.context.append_basic_block(function, "find_index_exit");
+
+        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
+
+        self.builder.position_at_end(cond_block);
+        let index_phi = map_builder_error(self.builder.build_phi(i64_type, "index"))?;
+        index_phi.add_incoming(&[(&i64_type.const_zero(), current_block)]);
+
+        let cond = map_builder_error(self.builder.build_int_compare(
+            IntPredicate::SLT,
+            index_phi.as_basic_value().into_int_value(),
+            length,
+            "find_index_cond",
         ))?;
-        let raw_fn_ptr = map_builder_error(self.builder.build_load(
-            self.ptr_type,
-            fn_ptr_ptr,
-            "closure_fn",
+        map_builder_error(
+            self.builder
+                .build_conditional_branch(cond, body_block, exit_block),
+        )?;
+
+        self.builder.position_at_end(body_block);
+        let list_get_fn = self.ensure_list_get();
+        let tea_value = map_builder_error(self.builder.build_call(
+            list_get_fn,
+            &[list_ptr.into(), index_phi.as_basic_value().into()],
+            "element_value",
         ))?
-        .into_pointer_value();
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| anyhow!("expected TeaValue"))?
+        .into_struct_value();
 
-        let mut llvm_params: Vec<BasicMetadataTypeEnum<'ctx>> =
-            Vec::with_capacity(param_types.len() + 1);
-        llvm_params.push(self.closure_ptr_type().into());
-        for ty in param_types {
-            llvm_params.push(self.basic_type(ty)?.into());
-        }
+        let element = self.tea_value_to_expr_value(tea_value, &element_type)?;
+        let predicate_result = self.call_closure_with_args(
+            closure_ptr,
+            &param_types,
+            &Box::new(ValueType::Bool),
+            vec![element],
+            function,
+        )?;
 
-        let fn_type = match return_type.as_ref() {
-            ValueType::Void => self.context.void_type().fn_type(&llvm_params, false),
-            ValueType::Int => self.int_type().fn_type(&llvm_params, false),
-            ValueType::Float => self.float_type().fn_type(&llvm_params, false),
-            ValueType::Bool => self.bool_type().fn_type(&llvm_params, false),
-            ValueType::String => self.string_ptr_type().fn_type(&llvm_params, false),
-            ValueType::List(_) => self.list_ptr_type().fn_type(&llvm_params, false),
-            ValueType::Dict(_) => self.dict_ptr_type().fn_type(&llvm_params, false),
-            ValueType::Struct(_) => self.struct_ptr_type().fn_type(&llvm_params, false),
-            ValueType::Error { .. } => self.error_ptr_type().fn_type(&llvm_params, false),
-            ValueType::Function(_, _) => self.closure_ptr_type().fn_type(&llvm_params, false),
-            ValueType::Optional(_) => self.value_type().fn_type(&llvm_params, false),
-            ValueType::Any => self.value_type().fn_type(&llvm_params, false),
+        let bool_val = match predicate_result {
+            ExprValue::Bool(v) => v,
+            _ => bail!("find_index predicate must return Bool"),
         };
 
-        let typed_fn_ptr = map_builder_error(self.builder.build_bit_cast(
-            raw_fn_ptr,
-            self.ptr_type,
-            "closure_callee",
-        ))?
-        .into_pointer_value();
+        let is_true = bool_val;
+        map_builder_error(self.builder.build_conditional_branch(
+            is_true,
+            found_block,
+            continue_block,
+        ))?;
 
-        let mut call_args: Vec<BasicMetadataValueEnum<'ctx>> =
-            Vec::with_capacity(arg_values.len() + 1);
-        call_args.push(closure_ptr.into());
-        for value in arg_values {
-            call_args.push(BasicMetadataValueEnum::from(value));
-        }
+        self.builder.position_at_end(found_block);
+        let current_index = index_phi.as_basic_value().into_int_value();
+        map_builder_error(self.builder.build_store(result_alloca, current_index))?;
+        map_builder_error(self.builder.build_unconditional_branch(exit_block))?;
 
-        let call_site = map_builder_error(self.builder.build_indirect_call(
-            fn_type,
-            typed_fn_ptr,
-            &call_args,
-            "closure_call",
+        self.builder.position_at_end(continue_block);
+        let next_index = map_builder_error(self.builder.build_int_add(
+            index_phi.as_basic_value().into_int_value(),
+            i64_type.const_int(1, false),
+            "next_index",
         ))?;
+        index_phi.add_incoming(&[(&next_index, continue_block)]);
+        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
 
-        if matches!(return_type.as_ref(), ValueType::Void) {
-            self.handle_possible_error(function)?;
-            return Ok(ExprValue::Void);
-        }
+        self.builder.position_at_end(exit_block);
+        let result = map_builder_error(self.builder.build_load(
+            i64_type,
+            result_alloca,
+            "list_find_index_final",
+        ))?
+        .into_int_value();
 
-        let result = call_site
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("closure returned no value"))?;
+        Ok(ExprValue::Int(result))
+    }
 
-        let expr = match return_type.as_ref() {
-            ValueType::Int => ExprValue::Int(result.into_int_value()),
-            ValueType::Float => ExprValue::Float(result.into_float_value()),
-            ValueType::Bool => ExprValue::Bool(result.into_int_value()),
-            ValueType::String => ExprValue::String(result.into_pointer_value()),
-            ValueType::List(inner) => ExprValue::List {
-                pointer: result.into_pointer_value(),
-                element_type: inner.clone(),
-            },
-            ValueType::Dict(inner) => ExprValue::Dict {
-                pointer: result.into_pointer_value(),
-                value_type: inner.clone(),
-            },
-            ValueType::Struct(struct_name) => ExprValue::Struct {
-                pointer: result.into_pointer_value(),
-                struct_name: struct_name.clone(),
-            },
-            ValueType::Error {
-                error_name,
-                variant_name,
-            } => ExprValue::Error {
-                pointer: result.into_pointer_value(),
-                error_name: error_name.clone(),
-                variant_name: variant_name.clone(),
-            },
-            ValueType::Function(params, ret) => ExprValue::Closure {
-                pointer: result.into_pointer_value(),
-                param_types: params.clone(),
-                return_type: ret.clone(),
-            },
-            ValueType::Optional(inner) => ExprValue::Optional {
-                value: result.into_struct_value(),
-                inner: inner.clone(),
-            },
-            ValueType::Void => unreachable!(),
-            ValueType::Any => ExprValue::Any {
-                value: result.into_struct_value(),
-            },
-        };
-        self.handle_possible_error(function)?;
+    fn compile_list_take(
+        &mut self,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        self.compile_list_take_skip(list_ptr, element_type, call, function, locals, true)
+    }
 
-        Ok(expr)
+    fn compile_list_skip(
+        &mut self,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        self.compile_list_take_skip(list_ptr, element_type, call, function, locals, false)
     }
 
-    /// Helper to convert BasicValueEnum to ExprValue
-    fn basic_to_expr_value(
-        &self,
-        value: BasicValueEnum<'ctx>,
-        ty: &ValueType,
+    fn compile_list_take_skip(
+        &mut self,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        take: bool,
     ) -> Result<ExprValue<'ctx>> {
-        match ty {
-            ValueType::Int => Ok(ExprValue::Int(value.into_int_value())),
-            ValueType::Float => Ok(ExprValue::Float(value.into_float_value())),
-            ValueType::Bool => Ok(ExprValue::Bool(value.into_int_value())),
-            ValueType::String => Ok(ExprValue::String(value.into_pointer_value())),
-            ValueType::List(inner) => Ok(ExprValue::List {
-                pointer: value.into_pointer_value(),
-                element_type: inner.clone(),
-            }),
-            ValueType::Dict(inner) => Ok(ExprValue::Dict {
-                pointer: value.into_pointer_value(),
-                value_type: inner.clone(),
-            }),
-            ValueType::Struct(name) => Ok(ExprValue::Struct {
-                pointer: value.into_pointer_value(),
-                struct_name: name.clone(),
-            }),
-            ValueType::Error {
-                error_name,
-                variant_name,
-            } => Ok(ExprValue::Error {
-                pointer: value.into_pointer_value(),
-                error_name: error_name.clone(),
-                variant_name: variant_name.clone(),
-            }),
-            ValueType::Function(params, ret) => Ok(ExprValue::Closure {
-                pointer: value.into_pointer_value(),
-                param_types: params.clone(),
-                return_type: ret.clone(),
-            }),
-            ValueType::Optional(inner) => Ok(ExprValue::Optional {
-                value: value.into_struct_value(),
-                inner: inner.clone(),
-            }),
-            ValueType::Void => bail!("cannot convert void to ExprValue"),
-            ValueType::Any => Ok(ExprValue::Any {
-                value: value.into_struct_value(),
-            }),
-        }
-    }
+        let count_expr =
+            self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let count_value = self.convert_expr_to_type(count_expr, &ValueType::Int)?;
+        let count = count_value.into_int()?;
 
-    /// Compile a Dict method call.
-    fn compile_dict_method_call(
-        &mut self,
-        method_name: &str,
-        dict_ptr: PointerValue<'ctx>,
-        value_type: Box<ValueType>,
-        call: &CallExpression,
-        function: FunctionValue<'ctx>,
-        locals: &mut HashMap<String, LocalVariable<'ctx>>,
-    ) -> Result<ExprValue<'ctx>> {
-        match method_name {
-            "keys" => {
-                let dict_keys_fn = self.ensure_dict_keys_fn();
-                let keys_ptr = map_builder_error(self.builder.build_call(
-                    dict_keys_fn,
-                    &[dict_ptr.into()],
-                    "dict_keys",
-                ))?
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("expected pointer from dict_keys"))?
-                .into_pointer_value();
-                Ok(ExprValue::List {
-                    pointer: keys_ptr,
-                    element_type: Box::new(ValueType::String),
-                })
-            }
-            "values" => {
-                let dict_values_fn = self.ensure_dict_values_fn();
-                let values_ptr = map_builder_error(self.builder.build_call(
-                    dict_values_fn,
-                    &[dict_ptr.into()],
-                    "dict_values",
-                ))?
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("expected pointer from dict_values"))?
-                .into_pointer_value();
-                Ok(ExprValue::List {
-                    pointer: values_ptr,
-                    element_type: value_type,
-                })
-            }
-            "entries" => {
-                let dict_entries_fn = self.ensure_dict_entries_fn();
-                let entries_ptr = map_builder_error(self.builder.build_call(
-                    dict_entries_fn,
-                    &[dict_ptr.into()],
-                    "dict_entries",
-                ))?
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("expected pointer from dict_entries"))?
-                .into_pointer_value();
-                // Each entry is a dict with the same value type
-                Ok(ExprValue::List {
-                    pointer: entries_ptr,
-                    element_type: Box::new(ValueType::Dict(value_type)),
-                })
-            }
-            "items" => {
-                let dict_entries_fn = self.ensure_dict_entries_fn();
-                let entries_ptr = map_builder_error(self.builder.build_call(
-                    dict_entries_fn,
-                    &[dict_ptr.into()],
-                    "dict_items",
-                ))?
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("expected pointer from dict_entries"))?
-                .into_pointer_value();
-                Ok(ExprValue::List {
-                    pointer: entries_ptr,
-                    element_type: Box::new(ValueType::Dict(value_type)),
-                })
-            }
-            "has" => self.compile_dict_has(dict_ptr, call, function, locals),
-            "get_or" => self.compile_dict_get_or(dict_ptr, value_type, call, function, locals),
-            "merge" => self.compile_dict_merge(dict_ptr, value_type, call, function, locals),
-            _ => bail!("unknown Dict method: {}", method_name),
-        }
-    }
+        let list_len_fn = self.ensure_list_len_ffi_fn();
+        let length = map_builder_error(self.builder.build_call(
+            list_len_fn,
+            &[list_ptr.into()],
+            "list_len",
+        ))?
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| anyhow!("expected i64 from list_len"))?
+        .into_int_value();
 
-    fn compile_dict_has(
-        &mut self,
-        dict_ptr: PointerValue<'ctx>,
-        call: &CallExpression,
-        function: FunctionValue<'ctx>,
-        locals: &mut HashMap<String, LocalVariable<'ctx>>,
-    ) -> Result<ExprValue<'ctx>> {
-        let key_expr = self.compile_expression(&call.arguments[0].expression, function, locals)?;
-        let key_value = self.convert_expr_to_type(key_expr, &ValueType::String)?;
-        let key_ptr = match key_value {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("Dict.has key must be a String"),
-        };
+        let i64_type = self.context.i64_type();
+        let zero = i64_type.const_zero();
+        let is_negative = map_builder_error(self.builder.build_int_compare(
+            IntPredicate::SLT,
+            count,
+            zero,
+            "list_slice_negative",
+        ))?;
+        let non_negative = map_builder_error(self.builder.build_select(
+            is_negative,
+            zero,
+            count,
+            "list_slice_non_negative",
+        ))?
+        .into_int_value();
+        let is_too_large = map_builder_error(self.builder.build_int_compare(
+            IntPredicate::SGT,
+            non_negative,
+            length,
+            "list_slice_too_large",
+        ))?;
+        let clamped = map_builder_error(self.builder.build_select(
+            is_too_large,
+            length,
+            non_negative,
+            "list_slice_clamped",
+        ))?
+        .into_int_value();
 
-        let dict_has_fn = self.ensure_dict_has();
-        let raw = self
-            .call_function(dict_has_fn, &[dict_ptr.into(), key_ptr.into()], "dict_has")?
+        let start = if take { zero } else { clamped };
+        let end = if take { clamped } else { length };
+        let slice_fn = self.ensure_list_slice();
+        let result_ptr = self
+            .call_function(
+                slice_fn,
+                &[
+                    list_ptr.into(),
+                    start.into(),
+                    end.into(),
+                    i64_type.const_int(1, false).into(),
+                    self.context.bool_type().const_zero().into(),
+                ],
+                if take { "list_take" } else { "list_skip" },
+            )?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("expected bool from dict_has"))?
-            .into_int_value();
-        let result = self.i32_to_bool(raw, "dict_has_bool")?;
+            .ok_or_else(|| anyhow!("expected List from list_slice"))?
+            .into_pointer_value();
 
-        Ok(ExprValue::Bool(result))
+        Ok(ExprValue::List {
+            pointer: result_ptr,
+            element_type,
+        })
     }
 
-    fn compile_dict_get_or(
+    /// Emit a loop over `0..length`, calling `body` with each index.
+    fn build_index_loop(
         &mut self,
-        dict_ptr: PointerValue<'ctx>,
-        value_type: Box<ValueType>,
-        call: &CallExpression,
+        length: IntValue<'ctx>,
         function: FunctionValue<'ctx>,
-        locals: &mut HashMap<String, LocalVariable<'ctx>>,
-    ) -> Result<ExprValue<'ctx>> {
-        let key_expr = self.compile_expression(&call.arguments[0].expression, function, locals)?;
-        let key_value = self.convert_expr_to_type(key_expr, &ValueType::String)?;
-        let key_ptr = match key_value {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("Dict.get_or key must be a String"),
-        };
-
-        let dict_has_fn = self.ensure_dict_has();
-        let has_raw = self
-            .call_function(dict_has_fn, &[dict_ptr.into(), key_ptr.into()], "dict_has")?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("expected bool from dict_has"))?
-            .into_int_value();
-        let has_value = map_builder_error(self.builder.build_int_compare(
-            IntPredicate::NE,
-            has_raw,
-            self.context.i32_type().const_zero(),
-            "dict_has_value",
-        ))?;
-
-        let result_alloca = self.create_entry_alloca(
-            function,
-            "dict_get_or_result",
-            self.basic_type(&value_type)?,
-        )?;
-        let current_block = self
+        label: &str,
+        mut body: impl FnMut(&mut Self, IntValue<'ctx>) -> Result<()>,
+    ) -> Result<()> {
+        let i64_type = self.context.i64_type();
+        let preheader = self
             .builder
             .get_insert_block()
             .ok_or_else(|| anyhow!("missing insertion block"))?;
-        let found_block = self
+        let cond_block = self
             .context
-            .append_basic_block(function, "dict_get_or_found");
-        let fallback_block = self
+            .append_basic_block(function, &format!("{label}_cond"));
+        let body_block = self
             .context
-            .append_basic_block(function, "dict_get_or_fallback");
-        let merge_block = self
+            .append_basic_block(function, &format!("{label}_body"));
+        let exit_block = self
             .context
-            .append_basic_block(function, "dict_get_or_merge");
+            .append_basic_block(function, &format!("{label}_exit"));
+        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
 
-        map_builder_error(self.builder.build_conditional_branch(
-            has_value,
-            found_block,
-            fallback_block,
+        self.builder.position_at_end(cond_block);
+        let index_phi = map_builder_error(self.builder.build_phi(i64_type, "index"))?;
+        index_phi.add_incoming(&[(&i64_type.const_zero(), preheader)]);
+        let index = index_phi.as_basic_value().into_int_value();
+        let cond = map_builder_error(self.builder.build_int_compare(
+            IntPredicate::SLT,
+            index,
+            length,
+            &format!("{label}_cond"),
         ))?;
+        map_builder_error(
+            self.builder
+                .build_conditional_branch(cond, body_block, exit_block),
+        )?;
 
-        self.builder.position_at_end(found_block);
-        let dict_get_fn = self.ensure_dict_get();
-        let raw_value = self
-            .call_function(dict_get_fn, &[dict_ptr.into(), key_ptr.into()], "dict_get")?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("expected TeaValue from dict_get"))?
-            .into_struct_value();
-        let found_value = self.tea_value_to_expr_value(raw_value, &value_type)?;
-        self.store_expr_in_pointer(result_alloca, &value_type, found_value, "dict_get_or_found")?;
-        map_builder_error(self.builder.build_unconditional_branch(merge_block))?;
+        self.builder.position_at_end(body_block);
+        body(self, index)?;
+        let next_index = map_builder_error(self.builder.build_int_add(
+            index,
+            i64_type.const_int(1, false),
+            "next_index",
+        ))?;
+        let latch = self
+            .builder
+            .get_insert_block()
+            .ok_or_else(|| anyhow!("missing insertion block"))?;
+        index_phi.add_incoming(&[(&next_index, latch)]);
+        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
 
-        self.builder.position_at_end(fallback_block);
-        let fallback = self.compile_expression(&call.arguments[1].expression, function, locals)?;
-        self.store_expr_in_pointer(result_alloca, &value_type, fallback, "dict_get_or_fallback")?;
-        map_builder_error(self.builder.build_unconditional_branch(merge_block))?;
+        self.builder.position_at_end(exit_block);
+        Ok(())
+    }
 
-        self.builder.position_at_end(merge_block);
-        let loaded = map_builder_error(self.builder.build_load(
-            self.basic_type(&value_type)?,
-            result_alloca,
-            "dict_get_or_value",
-        ))?;
-        let _ = current_block;
-        self.basic_to_expr_value(loaded, &value_type)
+    fn build_list_len(&mut self, list_ptr: PointerValue<'ctx>) -> Result<IntValue<'ctx>> {
+        let list_len_fn = self.ensure_list_len_ffi_fn();
+        Ok(self
+            .call_function(list_len_fn, &[list_ptr.into()], "list_len")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected i64 from list_len"))?
+            .into_int_value())
     }
 
-    fn compile_dict_merge(
+    fn build_list_get(
         &mut self,
-        dict_ptr: PointerValue<'ctx>,
-        value_type: Box<ValueType>,
+        list_ptr: PointerValue<'ctx>,
+        index: IntValue<'ctx>,
+        element_type: &ValueType,
+    ) -> Result<ExprValue<'ctx>> {
+        let list_get_fn = self.ensure_list_get();
+        let tea_value = self
+            .call_function(
+                list_get_fn,
+                &[list_ptr.into(), index.into()],
+                "element_value",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected TeaValue"))?
+            .into_struct_value();
+        self.tea_value_to_expr_value(tea_value, element_type)
+    }
+
+    /// Store a value in an entry-block slot and return a pointer to it, for runtime
+    /// functions that take `*const TeaValue`.
+    fn build_tea_value_pointer(
+        &mut self,
+        value: ExprValue<'ctx>,
+        function: FunctionValue<'ctx>,
+        name: &str,
+    ) -> Result<PointerValue<'ctx>> {
+        let tea_value = self.expr_to_tea_value(value)?;
+        let slot = self.create_entry_alloca(function, name, self.value_type().into())?;
+        map_builder_error(self.builder.build_store(slot, tea_value))?;
+        Ok(slot)
+    }
+
+    fn build_list_copy(&mut self, list_ptr: PointerValue<'ctx>) -> Result<PointerValue<'ctx>> {
+        let i64_type = self.context.i64_type();
+        let open_bound = i64_type.const_int(SLICE_OPEN_BOUND as u64, true);
+        let slice_fn = self.ensure_list_slice();
+        Ok(self
+            .call_function(
+                slice_fn,
+                &[
+                    list_ptr.into(),
+                    open_bound.into(),
+                    open_bound.into(),
+                    i64_type.const_int(1, false).into(),
+                    self.context.bool_type().const_zero().into(),
+                ],
+                "list_copy",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected List from list_slice"))?
+            .into_pointer_value())
+    }
+
+    fn expect_closure_argument(
+        &mut self,
+        method: &str,
+        argument: &Expression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<(PointerValue<'ctx>, Vec<ValueType>, Box<ValueType>)> {
+        match self.compile_expression(argument, function, locals)? {
+            ExprValue::Closure {
+                pointer,
+                param_types,
+                return_type,
+            } => Ok((pointer, param_types, return_type)),
+            _ => bail!("List.{method} expects a function argument"),
+        }
+    }
+
+    /// Compile list.sort(), sorted(), reverse() and reversed(); the copying forms
+    /// reorder a fresh copy of the list.
+    fn compile_list_reorder(
+        &mut self,
+        method_name: &str,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
+    ) -> Result<ExprValue<'ctx>> {
+        let copying = matches!(method_name, "sorted" | "reversed");
+        let target = if copying {
+            self.build_list_copy(list_ptr)?
+        } else {
+            list_ptr
+        };
+        let func = if matches!(method_name, "sort" | "sorted") {
+            self.ensure_list_sort_fn()
+        } else {
+            self.ensure_list_reverse_fn()
+        };
+        self.call_function(func, &[target.into()], method_name)?;
+        if copying {
+            Ok(ExprValue::List {
+                pointer: target,
+                element_type,
+            })
+        } else {
+            Ok(ExprValue::Void)
+        }
+    }
+
+    /// Compile list.sort_by(compare) and sorted_by(compare). The runtime sorts with a
+    /// generated comparator that unpacks both items and calls the closure.
+    fn compile_list_sort_by(
+        &mut self,
+        copying: bool,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
         call: &CallExpression,
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        let other_expr =
-            self.compile_expression(&call.arguments[0].expression, function, locals)?;
-        let other_dict =
-            self.convert_expr_to_type(other_expr, &ValueType::Dict(value_type.clone()))?;
-        let other_ptr = match other_dict {
-            ExprValue::Dict { pointer, .. } => pointer,
-            _ => bail!("Dict.merge expects another Dict"),
+        let (closure_ptr, param_types, return_type) = self.expect_closure_argument(
+            "sort_by",
+            &call.arguments[0].expression,
+            function,
+            locals,
+        )?;
+
+        let comparator_type = self.int_type().fn_type(
+            &[
+                self.closure_ptr_type().into(),
+                self.ptr_type.into(),
+                self.ptr_type.into(),
+            ],
+            false,
+        );
+        let comparator = self.module.add_function(
+            &format!("tea_list_compare${}", self.list_comparator_count),
+            comparator_type,
+            Some(Linkage::Internal),
+        );
+        self.list_comparator_count += 1;
+
+        let outer_block = self
+            .builder
+            .get_insert_block()
+            .ok_or_else(|| anyhow!("missing insertion block"))?;
+        let entry = self.context.append_basic_block(comparator, "entry");
+        self.builder.position_at_end(entry);
+        let closure_param = comparator
+            .get_nth_param(0)
+            .ok_or_else(|| anyhow!("missing closure parameter"))?
+            .into_pointer_value();
+        let mut arguments = Vec::with_capacity(2);
+        for index in 1..=2 {
+            let pointer = comparator
+                .get_nth_param(index)
+                .ok_or_else(|| anyhow!("missing comparator argument"))?
+                .into_pointer_value();
+            let tea_value =
+                map_builder_error(self.builder.build_load(self.value_type(), pointer, "item"))?
+                    .into_struct_value();
+            arguments.push(self.tea_value_to_expr_value(tea_value, &element_type)?);
+        }
+        let ordering = self.call_closure_with_args(
+            closure_param,
+            &param_types,
+            &return_type,
+            arguments,
+            comparator,
+        )?;
+        let ordering = self
+            .convert_expr_to_type(ordering, &ValueType::Int)?
+            .into_int()?;
+        map_builder_error(self.builder.build_return(Some(&ordering)))?;
+        self.builder.position_at_end(outer_block);
+
+        let target = if copying {
+            self.build_list_copy(list_ptr)?
+        } else {
+            list_ptr
         };
+        let sort_by_fn = self.ensure_list_sort_by_fn();
+        self.call_function(
+            sort_by_fn,
+            &[
+                target.into(),
+                comparator.as_global_value().as_pointer_value().into(),
+                closure_ptr.into(),
+            ],
+            "list_sort_by",
+        )?;
+        if copying {
+            Ok(ExprValue::List {
+                pointer: target,
+                element_type,
+            })
+        } else {
+            Ok(ExprValue::Void)
+        }
+    }
 
-        let dict_new_fn = self.ensure_dict_new();
-        let result_ptr = self
-            .call_function(dict_new_fn, &[], "dict_new")?
+    /// Compile list.unique() - a copy without repeated items, keeping first occurrences.
+    fn compile_list_unique(
+        &mut self,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
+    ) -> Result<ExprValue<'ctx>> {
+        let unique_fn = self.ensure_list_unique_fn();
+        let pointer = self
+            .call_function(unique_fn, &[list_ptr.into()], "list_unique")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("expected pointer from dict_new"))?
+            .ok_or_else(|| anyhow!("expected List from list_unique"))?
             .into_pointer_value();
-
-        self.copy_dict_entries_into(dict_ptr, result_ptr, value_type.clone(), function)?;
-        self.copy_dict_entries_into(other_ptr, result_ptr, value_type.clone(), function)?;
-
-        Ok(ExprValue::Dict {
-            pointer: result_ptr,
-            value_type,
+        Ok(ExprValue::List {
+            pointer,
+            element_type,
         })
     }
 
-    fn copy_dict_entries_into(
+    /// Compile list.group_by(key) - a dict from each key to the items that produced it.
+    fn compile_list_group_by(
         &mut self,
-        source_dict: PointerValue<'ctx>,
-        target_dict: PointerValue<'ctx>,
-        value_type: Box<ValueType>,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
+        call: &CallExpression,
         function: FunctionValue<'ctx>,
-    ) -> Result<()> {
-        let dict_keys_fn = self.ensure_dict_keys_fn();
-        let keys_ptr = self
-            .call_function(dict_keys_fn, &[source_dict.into()], "dict_keys")?
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        let (closure_ptr, param_types, return_type) = self.expect_closure_argument(
+            "group_by",
+            &call.arguments[0].expression,
+            function,
+            locals,
+        )?;
+        let dict_new_fn = self.ensure_dict_new();
+        let dict_ptr = self
+            .call_function(dict_new_fn, &[], "group_by_result")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("expected pointer from dict_keys"))?
+            .ok_or_else(|| anyhow!("expected Dict from dict_new"))?
             .into_pointer_value();
-        let list_len_fn = self.ensure_list_len_ffi_fn();
-        let length = self
-            .call_function(list_len_fn, &[keys_ptr.into()], "keys_len")?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("expected i64 from list_len"))?
-            .into_int_value();
 
-        let current_block = self
-            .builder
-            .get_insert_block()
-            .ok_or_else(|| anyhow!("missing insertion block"))?;
-        let cond_block = self.context.append_basic_block(function, "dict_copy_cond");
-        let body_block = self.context.append_basic_block(function, "dict_copy_body");
-        let exit_block = self.context.append_basic_block(function, "dict_copy_exit");
-        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
+        let length = self.build_list_len(list_ptr)?;
+        self.build_index_loop(length, function, "group_by", |this, index| {
+            let element = this.build_list_get(list_ptr, index, &element_type)?;
+            let key = this.call_closure_with_args(
+                closure_ptr,
+                &param_types,
+                &return_type,
+                vec![element.clone()],
+                function,
+            )?;
+            let ExprValue::String(key_ptr) = key else {
+                bail!("List.group_by key must be a String");
+            };
+            let value_ptr = this.build_tea_value_pointer(element, function, "group_by_item")?;
+            let group_append_fn = this.ensure_dict_group_append_fn();
+            this.call_function(
+                group_append_fn,
+                &[dict_ptr.into(), key_ptr.into(), value_ptr.into()],
+                "group_by_append",
+            )?;
+            Ok(())
+        })?;
 
-        self.builder.position_at_end(cond_block);
-        let i64_type = self.context.i64_type();
-        let index_phi = map_builder_error(self.builder.build_phi(i64_type, "index"))?;
-        index_phi.add_incoming(&[(&i64_type.const_zero(), current_block)]);
-        let cond = map_builder_error(self.builder.build_int_compare(
-            IntPredicate::SLT,
-            index_phi.as_basic_value().into_int_value(),
-            length,
-            "dict_copy_cond",
-        ))?;
-        map_builder_error(
-            self.builder
-                .build_conditional_branch(cond, body_block, exit_block),
+        Ok(ExprValue::Dict {
+            pointer: dict_ptr,
+            value_type: Box::new(ValueType::List(element_type)),
+        })
+    }
+
+    /// Compile list.partition(fn) - `[matching, rest]`, both in the original order.
+    fn compile_list_partition(
+        &mut self,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        let (closure_ptr, param_types, _return_type) = self.expect_closure_argument(
+            "partition",
+            &call.arguments[0].expression,
+            function,
+            locals,
         )?;
+        let alloc_list_fn = self.ensure_alloc_list();
+        let zero = self.int_type().const_zero();
+        let mut halves = Vec::with_capacity(2);
+        for name in ["partition_matching", "partition_rest"] {
+            halves.push(
+                self.call_function(alloc_list_fn, &[zero.into()], name)?
+                    .try_as_basic_value()
+                    .left()
+                    .ok_or_else(|| anyhow!("expected pointer from alloc_list"))?
+                    .into_pointer_value(),
+            );
+        }
+        let (matching, rest) = (halves[0], halves[1]);
 
-        self.builder.position_at_end(body_block);
-        let list_get_fn = self.ensure_list_get();
-        let key_value = self
+        let length = self.build_list_len(list_ptr)?;
+        self.build_index_loop(length, function, "partition", |this, index| {
+            let element = this.build_list_get(list_ptr, index, &element_type)?;
+            let keep = this.call_closure_with_args(
+                closure_ptr,
+                &param_types,
+                &Box::new(ValueType::Bool),
+                vec![element.clone()],
+                function,
+            )?;
+            let ExprValue::Bool(keep) = keep else {
+                bail!("partition predicate must return Bool");
+            };
+            let is_true = map_builder_error(this.builder.build_int_compare(
+                IntPredicate::NE,
+                keep,
+                keep.get_type().const_zero(),
+                "is_true",
+            ))?;
+            let target = map_builder_error(this.builder.build_select(
+                is_true,
+                matching,
+                rest,
+                "partition_target",
+            ))?
+            .into_pointer_value();
+            let value_ptr = this.build_tea_value_pointer(element, function, "partition_item")?;
+            let append_fn = this.ensure_list_append_fn();
+            this.call_function(append_fn, &[target.into(), value_ptr.into()], "")?;
+            Ok(())
+        })?;
+
+        let result = self
             .call_function(
-                list_get_fn,
-                &[keys_ptr.into(), index_phi.as_basic_value().into()],
-                "dict_copy_key",
+                alloc_list_fn,
+                &[self.int_type().const_int(2, false).into()],
+                "partition_result",
             )?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("expected TeaValue"))?
-            .into_struct_value();
-        let key_expr = self.tea_value_to_expr_value(key_value, &ValueType::String)?;
-        let key_ptr = match key_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("dict key must be a String"),
-        };
+            .ok_or_else(|| anyhow!("expected pointer from alloc_list"))?
+            .into_pointer_value();
+        let list_set_fn = self.ensure_list_set();
+        for (index, half) in [matching, rest].into_iter().enumerate() {
+            let value = self.expr_to_tea_value(ExprValue::List {
+                pointer: half,
+                element_type: element_type.clone(),
+            })?;
+            self.call_function(
+                list_set_fn,
+                &[
+                    result.into(),
+                    self.int_type().const_int(index as u64, false).into(),
+                    value.into(),
+                ],
+                "",
+            )?;
+        }
 
-        let dict_get_fn = self.ensure_dict_get();
-        let value = self
+        Ok(ExprValue::List {
+            pointer: result,
+            element_type: Box::new(ValueType::List(element_type)),
+        })
+    }
+
+    /// Compile list.zip(other, combine) - combine items pairwise up to the shorter length.
+    fn compile_list_zip(
+        &mut self,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        let other = self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let ExprValue::List {
+            pointer: other_ptr,
+            element_type: other_type,
+        } = other
+        else {
+            bail!("List.zip expects a List as its first argument");
+        };
+        let (closure_ptr, param_types, return_type) =
+            self.expect_closure_argument("zip", &call.arguments[1].expression, function, locals)?;
+
+        let left_len = self.build_list_len(list_ptr)?;
+        let right_len = self.build_list_len(other_ptr)?;
+        let left_shorter = map_builder_error(self.builder.build_int_compare(
+            IntPredicate::SLT,
+            left_len,
+            right_len,
+            "zip_left_shorter",
+        ))?;
+        let length = map_builder_error(self.builder.build_select(
+            left_shorter,
+            left_len,
+            right_len,
+            "zip_len",
+        ))?
+        .into_int_value();
+        let alloc_list_fn = self.ensure_alloc_list();
+        let result = self
+            .call_function(alloc_list_fn, &[length.into()], "zip_result")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected pointer from alloc_list"))?
+            .into_pointer_value();
+
+        self.build_index_loop(length, function, "zip", |this, index| {
+            let left = this.build_list_get(list_ptr, index, &element_type)?;
+            let right = this.build_list_get(other_ptr, index, &other_type)?;
+            let combined = this.call_closure_with_args(
+                closure_ptr,
+                &param_types,
+                &return_type,
+                vec![left, right],
+                function,
+            )?;
+            let value = this.expr_to_tea_value(combined)?;
+            let list_set_fn = this.ensure_list_set();
+            this.call_function(
+                list_set_fn,
+                &[result.into(), index.into(), value.into()],
+                "",
+            )?;
+            Ok(())
+        })?;
+
+        Ok(ExprValue::List {
+            pointer: result,
+            element_type: return_type,
+        })
+    }
+
+    /// Compile list.flat_map(fn) - concatenate the lists returned for each item.
+    fn compile_list_flat_map(
+        &mut self,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        let (closure_ptr, param_types, return_type) = self.expect_closure_argument(
+            "flat_map",
+            &call.arguments[0].expression,
+            function,
+            locals,
+        )?;
+        let ValueType::List(result_element) = return_type.as_ref().clone() else {
+            bail!("List.flat_map callback must return a List");
+        };
+        let alloc_list_fn = self.ensure_alloc_list();
+        let result = self
             .call_function(
-                dict_get_fn,
-                &[source_dict.into(), key_ptr.into()],
-                "dict_get",
+                alloc_list_fn,
+                &[self.int_type().const_zero().into()],
+                "flat_map_result",
             )?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("expected TeaValue from dict_get"))?
+            .ok_or_else(|| anyhow!("expected pointer from alloc_list"))?
+            .into_pointer_value();
+
+        let length = self.build_list_len(list_ptr)?;
+        self.build_index_loop(length, function, "flat_map", |this, index| {
+            let element = this.build_list_get(list_ptr, index, &element_type)?;
+            let mapped = this.call_closure_with_args(
+                closure_ptr,
+                &param_types,
+                &return_type,
+                vec![element],
+                function,
+            )?;
+            let ExprValue::List { pointer, .. } = mapped else {
+                bail!("List.flat_map callback must return a List");
+            };
+            let extend_fn = this.ensure_list_extend_fn();
+            this.call_function(extend_fn, &[result.into(), pointer.into()], "")?;
+            Ok(())
+        })?;
+
+        Ok(ExprValue::List {
+            pointer: result,
+            element_type: result_element,
+        })
+    }
+
+    /// Compile list.chunk(size) and list.window(size).
+    fn compile_list_chunk_window(
+        &mut self,
+        chunk: bool,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        let size = self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let size = self
+            .convert_expr_to_type(size, &ValueType::Int)?
+            .into_int()?;
+        let func = if chunk {
+            self.ensure_list_chunk_fn()
+        } else {
+            self.ensure_list_window_fn()
+        };
+        let pointer = self
+            .call_function(
+                func,
+                &[list_ptr.into(), size.into()],
+                if chunk { "list_chunk" } else { "list_window" },
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected List from list grouping"))?
+            .into_pointer_value();
+        Ok(ExprValue::List {
+            pointer,
+            element_type: Box::new(ValueType::List(element_type)),
+        })
+    }
+
+    /// Compile list.join(separator) for a List[String].
+    fn compile_list_join(
+        &mut self,
+        list_ptr: PointerValue<'ctx>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        let separator = self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let ExprValue::String(separator_ptr) = separator else {
+            bail!("List.join expects a String separator");
+        };
+        let join_fn = self.ensure_list_join_fn();
+        let pointer = self
+            .call_function(
+                join_fn,
+                &[list_ptr.into(), separator_ptr.into()],
+                "list_join",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected String from list_join"))?
+            .into_pointer_value();
+        Ok(ExprValue::String(pointer))
+    }
+
+    /// Compile list.sum() for Int and Float lists; an empty list sums to zero.
+    fn compile_list_sum(
+        &mut self,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
+    ) -> Result<ExprValue<'ctx>> {
+        match element_type.as_ref() {
+            ValueType::Int => {
+                let func = self.ensure_list_sum_int_fn();
+                let sum = self
+                    .call_function(func, &[list_ptr.into()], "list_sum")?
+                    .try_as_basic_value()
+                    .left()
+                    .ok_or_else(|| anyhow!("expected Int from list_sum"))?
+                    .into_int_value();
+                Ok(ExprValue::Int(sum))
+            }
+            ValueType::Float => {
+                let func = self.ensure_list_sum_float_fn();
+                let sum = self
+                    .call_function(func, &[list_ptr.into()], "list_sum")?
+                    .try_as_basic_value()
+                    .left()
+                    .ok_or_else(|| anyhow!("expected Float from list_sum"))?
+                    .into_float_value();
+                Ok(ExprValue::Float(sum))
+            }
+            other => bail!("List.sum requires Int or Float items, found {other:?}"),
+        }
+    }
+
+    /// Compile list.min(), max() and pop(), which are nil for an empty list.
+    fn compile_list_optional_item(
+        &mut self,
+        method_name: &str,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
+    ) -> Result<ExprValue<'ctx>> {
+        let func = match method_name {
+            "min" => self.ensure_list_min_fn(),
+            "max" => self.ensure_list_max_fn(),
+            _ => self.ensure_list_pop_fn(),
+        };
+        let value = self
+            .call_function(func, &[list_ptr.into()], method_name)?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected TeaValue from List.{method_name}"))?
             .into_struct_value();
-        let value_expr = self.tea_value_to_expr_value(value, &value_type)?;
-        let tea_value = self.expr_to_tea_value(value_expr)?.into_struct_value();
-        let tea_value_type = self
-            .context
-            .get_struct_type("TeaValue")
-            .ok_or_else(|| anyhow!("TeaValue type not found"))?;
-        let temp_ptr =
-            map_builder_error(self.builder.build_alloca(tea_value_type, "dict_copy_value"))?;
-        map_builder_error(self.builder.build_store(temp_ptr, tea_value))?;
+        Ok(ExprValue::Optional {
+            value,
+            inner: element_type,
+        })
+    }
 
-        let dict_set_fn = self.ensure_dict_set();
+    /// Compile list.insert(index, value).
+    fn compile_list_insert(
+        &mut self,
+        list_ptr: PointerValue<'ctx>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        let index = self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let index = self
+            .convert_expr_to_type(index, &ValueType::Int)?
+            .into_int()?;
+        let value = self.compile_expression(&call.arguments[1].expression, function, locals)?;
+        let value_ptr = self.build_tea_value_pointer(value, function, "insert_value")?;
+        let insert_fn = self.ensure_list_insert_fn();
         self.call_function(
-            dict_set_fn,
-            &[target_dict.into(), key_ptr.into(), temp_ptr.into()],
-            "dict_set",
+            insert_fn,
+            &[list_ptr.into(), index.into(), value_ptr.into()],
+            "list_insert",
         )?;
+        Ok(ExprValue::Void)
+    }
 
-        let next_index = map_builder_error(self.builder.build_int_add(
-            index_phi.as_basic_value().into_int_value(),
-            i64_type.const_int(1, false),
-            "next_index",
-        ))?;
-        index_phi.add_incoming(&[(&next_index, body_block)]);
+    /// Compile list.remove_at(index), returning the removed item.
+    fn compile_list_remove_at(
+        &mut self,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        let index = self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let index = self
+            .convert_expr_to_type(index, &ValueType::Int)?
+            .into_int()?;
+        let remove_fn = self.ensure_list_remove_at_fn();
+        let removed = self
+            .call_function(
+                remove_fn,
+                &[list_ptr.into(), index.into()],
+                "list_remove_at",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected TeaValue from list_remove_at"))?
+            .into_struct_value();
+        self.tea_value_to_expr_value(removed, &element_type)
+    }
+
+    /// Shared implementation for any/all
+    fn compile_list_any_all(
+        &mut self,
+        list_ptr: PointerValue<'ctx>,
+        element_type: Box<ValueType>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        is_any: bool,
+    ) -> Result<ExprValue<'ctx>> {
+        // Get the callback closure
+        let callback = self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let (closure_ptr, param_types, _return_type) = match callback {
+            ExprValue::Closure {
+                pointer,
+                param_types,
+                return_type,
+            } => (pointer, param_types, return_type),
+            _ => bail!(
+                "List.{} expects a function argument",
+                if is_any { "any" } else { "all" }
+            ),
+        };
+
+        // Get list length
+        let list_len_fn = self.ensure_list_len_ffi_fn();
+        let length = map_builder_error(self.builder.build_call(
+            list_len_fn,
+            &[list_ptr.into()],
+            "list_len",
+        ))?
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| anyhow!("expected i64 from list_len"))?
+        .into_int_value();
+
+        // Create result alloca
+        let i32_type = self.context.i32_type();
+        let result_alloca =
+            map_builder_error(self.builder.build_alloca(i32_type, "any_all_result"))?;
+        // Initialize: any starts with false (0), all starts with true (1)
+        let initial = if is_any {
+            i32_type.const_zero()
+        } else {
+            i32_type.const_int(1, false)
+        };
+        map_builder_error(self.builder.build_store(result_alloca, initial))?;
+
+        // Create loop blocks
+        let current_block = self
+            .builder
+            .get_insert_block()
+            .ok_or_else(|| anyhow!("missing insertion block"))?;
+        let cond_block = self.context.append_basic_block(function, "any_all_cond");
+        let body_block = self.context.append_basic_block(function, "any_all_body");
+        let early_exit_block = self
+            .context
+            .append_basic_block(function, "any_all_early_exit");
+        let continue_block = self
+            .context
+            .append_basic_block(function, "any_all_continue");
+        let exit_block = self.context.append_basic_block(function, "any_all_exit");
+
+        // Branch to condition
         map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
 
-        self.builder.position_at_end(exit_block);
-        Ok(())
+        // Condition block
+        self.builder.position_at_end(cond_block);
+        let i64_type = self.context.i64_type();
+        let index_phi = map_builder_error(self.builder.build_phi(i64_type, "index"))?;
+        index_phi.add_incoming(&[(&i64_type.const_zero(), current_block)]);
+
+        let cond = map_builder_error(self.builder.build_int_compare(
+            IntPredicate::SLT,
+            index_phi.as_basic_value().into_int_value(),
+            length,
+            "any_all_cond",
+        ))?;
+        map_builder_error(
+            self.builder
+                .build_conditional_branch(cond, body_block, exit_block),
+        )?;
+
+        // Body block
+        self.builder.position_at_end(body_block);
+
+        // Get element at index
+        let list_get_fn = self.ensure_list_get();
+        let tea_value = map_builder_error(self.builder.build_call(
+            list_get_fn,
+            &[list_ptr.into(), index_phi.as_basic_value().into()],
+            "element_value",
+        ))?
+        .try_as_basic_value()
+        .left()
+        .ok_or_else(|| anyhow!("expected TeaValue"))?
+        .into_struct_value();
+
+        // Convert to proper type
+        let element = self.tea_value_to_expr_value(tea_value, &element_type)?;
+
+        // Call closure with element
+        let predicate_result = self.call_closure_with_args(
+            closure_ptr,
+            &param_types,
+            &Box::new(ValueType::Bool),
+            vec![element],
+            function,
+        )?;
+
+        // Check predicate result
+        let bool_val = match predicate_result {
+            ExprValue::Bool(v) => v,
+            _ => bail!("predicate must return Bool"),
+        };
+
+        let is_true = map_builder_error(self.builder.build_int_compare(
+            IntPredicate::NE,
+            bool_val,
+            i32_type.const_zero(),
+            "is_true",
+        ))?;
+
+        // For any: if true, early exit with true
+        // For all: if false, early exit with false
+        if is_any {
+            map_builder_error(self.builder.build_conditional_branch(
+                is_true,
+                early_exit_block,
+                continue_block,
+            ))?;
+        } else {
+            map_builder_error(self.builder.build_conditional_branch(
+                is_true,
+                continue_block,
+                early_exit_block,
+            ))?;
+        }
+
+        // Early exit block
+        self.builder.position_at_end(early_exit_block);
+        let early_result = if is_any {
+            i32_type.const_int(1, false) // true for any
+        } else {
+            i32_type.const_zero() // false for all
+        };
+        map_builder_error(self.builder.build_store(result_alloca, early_result))?;
+        map_builder_error(self.builder.build_unconditional_branch(exit_block))?;
+
+        // Continue block
+        self.builder.position_at_end(continue_block);
+        let next_index = map_builder_error(self.builder.build_int_add(
+            index_phi.as_basic_value().into_int_value(),
+            i64_type.const_int(1, false),
+            "next_index",
+        ))?;
+        index_phi.add_incoming(&[(&next_index, continue_block)]);
+        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
+
+        // Exit block
+        self.builder.position_at_end(exit_block);
+        let final_result = map_builder_error(self.builder.build_load(
+            i32_type,
+            result_alloca,
+            "final_result",
+        ))?
+        .into_int_value();
+
+        Ok(ExprValue::Bool(final_result))
+    }
+
+    /// Helper to call a closure with pre-compiled arguments
+    fn call_closure_with_args(
+        &mut self,
+        closure_ptr: PointerValue<'ctx>,
+        param_types: &[ValueType],
+        return_type: &ValueType,
+        args: Vec<ExprValue<'ctx>>,
+        function: FunctionValue<'ctx>,
+    ) -> Result<ExprValue<'ctx>> {
+        let mut arg_values: Vec<BasicValueEnum<'ctx>> = Vec::with_capacity(args.len());
+        for (index, (expected_type, arg)) in param_types.iter().zip(args).enumerate() {
+            let converted = self
+                .convert_expr_to_type(arg, expected_type)
+                .map_err(|error| {
+                    anyhow!("argument {} has mismatched type: {}", index + 1, error)
+                })?;
+            let basic = converted
+                .into_basic_value()
+                .ok_or_else(|| anyhow!("argument must produce a value"))?;
+            arg_values.push(basic);
+        }
+
+        let fn_ptr_ptr = map_builder_error(self.builder.build_struct_gep(
+            self.tea_closure,
+            closure_ptr,
+            0,
+            "closure_fn_ptr",
+        ))?;
+        let raw_fn_ptr = map_builder_error(self.builder.build_load(
+            self.ptr_type,
+            fn_ptr_ptr,
+            "closure_fn",
+        ))?
+        .into_pointer_value();
+
+        let mut llvm_params: Vec<BasicMetadataTypeEnum<'ctx>> =
+            Vec::with_capacity(param_types.len() + 1);
+        llvm_params.push(self.closure_ptr_type().into());
+        for ty in param_types {
+            llvm_params.push(self.basic_type(ty)?.into());
+        }
+
+        let fn_type = match return_type {
+            ValueType::Void => self.context.void_type().fn_type(&llvm_params, false),
+            ValueType::Int | ValueType::Iterator(_) => self.int_type().fn_type(&llvm_params, false),
+            ValueType::Float => self.float_type().fn_type(&llvm_params, false),
+            ValueType::Bool => self.bool_type().fn_type(&llvm_params, false),
+            ValueType::String | ValueType::Enum(_) => {
+                self.string_ptr_type().fn_type(&llvm_params, false)
+            }
+            ValueType::Bytes => self.bytes_ptr_type().fn_type(&llvm_params, false),
+            ValueType::List(_) => self.list_ptr_type().fn_type(&llvm_params, false),
+            ValueType::Dict(_) => self.dict_ptr_type().fn_type(&llvm_params, false),
+            ValueType::Struct(_) => self.struct_ptr_type().fn_type(&llvm_params, false),
+            ValueType::Error { .. } => self.error_ptr_type().fn_type(&llvm_params, false),
+            ValueType::Function(_, _) => self.closure_ptr_type().fn_type(&llvm_params, false),
+            ValueType::Optional(_) => self.value_type().fn_type(&llvm_params, false),
+            ValueType::Any => self.value_type().fn_type(&llvm_params, false),
+        };
+
+        let typed_fn_ptr = map_builder_error(self.builder.build_bit_cast(
+            raw_fn_ptr,
+            self.ptr_type,
+            "closure_callee",
+        ))?
+        .into_pointer_value();
+
+        let mut call_args: Vec<BasicMetadataValueEnum<'ctx>> =
+            Vec::with_capacity(arg_values.len() + 1);
+        call_args.push(closure_ptr.into());
+        for value in arg_values {
+            call_args.push(BasicMetadataValueEnum::from(value));
+        }
+
+        let call_site = map_builder_error(self.builder.build_indirect_call(
+            fn_type,
+            typed_fn_ptr,
+            &call_args,
+            "closure_call",
+        ))?;
+
+        if matches!(return_type, ValueType::Void) {
+            self.handle_possible_error(function)?;
+            return Ok(ExprValue::Void);
+        }
+
+        let result = call_site
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("closure returned no value"))?;
+
+        let expr = match return_type {
+            ValueType::Int => ExprValue::Int(result.into_int_value()),
+            ValueType::Iterator(element_type) => ExprValue::Iterator {
+                handle: result.into_int_value(),
+                element_type: element_type.clone(),
+            },
+            ValueType::Float => ExprValue::Float(result.into_float_value()),
+            ValueType::Bool => ExprValue::Bool(result.into_int_value()),
+            ValueType::String | ValueType::Enum(_) => {
+                ExprValue::String(result.into_pointer_value())
+            }
+            ValueType::Bytes => ExprValue::Bytes(result.into_pointer_value()),
+            ValueType::List(inner) => ExprValue::List {
+                pointer: result.into_pointer_value(),
+                element_type: inner.clone(),
+            },
+            ValueType::Dict(inner) => ExprValue::Dict {
+                pointer: result.into_pointer_value(),
+                value_type: inner.clone(),
+            },
+            ValueType::Struct(struct_name) => ExprValue::Struct {
+                pointer: result.into_pointer_value(),
+                struct_name: struct_name.clone(),
+            },
+            ValueType::Error {
+                error_name,
+                variant_name,
+            } => ExprValue::Error {
+                pointer: result.into_pointer_value(),
+                error_name: error_name.clone(),
+                variant_name: variant_name.clone(),
+            },
+            ValueType::Function(params, ret) => ExprValue::Closure {
+                pointer: result.into_pointer_value(),
+                param_types: params.clone(),
+                return_type: ret.clone(),
+            },
+            ValueType::Optional(inner) => ExprValue::Optional {
+                value: result.into_struct_value(),
+                inner: inner.clone(),
+            },
+            ValueType::Void => unreachable!(),
+            ValueType::Any => ExprValue::Any {
+                value: result.into_struct_value(),
+            },
+        };
+        self.handle_possible_error(function)?;
+
+        Ok(expr)
+    }
+
+    /// Helper to convert BasicValueEnum to ExprValue
+    fn basic_to_expr_value(
+        &self,
+        value: BasicValueEnum<'ctx>,
+        ty: &ValueType,
+    ) -> Result<ExprValue<'ctx>> {
+        match ty {
+            ValueType::Int => Ok(ExprValue::Int(value.into_int_value())),
+            ValueType::Iterator(element_type) => Ok(ExprValue::Iterator {
+                handle: value.into_int_value(),
+                element_type: element_type.clone(),
+            }),
+            ValueType::Float => Ok(ExprValue::Float(value.into_float_value())),
+            ValueType::Bool => Ok(ExprValue::Bool(value.into_int_value())),
+            ValueType::String | ValueType::Enum(_) => {
+                Ok(ExprValue::String(value.into_pointer_value()))
+            }
+            ValueType::Bytes => Ok(ExprValue::Bytes(value.into_pointer_value())),
+            ValueType::List(inner) => Ok(ExprValue::List {
+                pointer: value.into_pointer_value(),
+                element_type: inner.clone(),
+            }),
+            ValueType::Dict(inner) => Ok(ExprValue::Dict {
+                pointer: value.into_pointer_value(),
+                value_type: inner.clone(),
+            }),
+            ValueType::Struct(name) => Ok(ExprValue::Struct {
+                pointer: value.into_pointer_value(),
+                struct_name: name.clone(),
+            }),
+            ValueType::Error {
+                error_name,
+                variant_name,
+            } => Ok(ExprValue::Error {
+                pointer: value.into_pointer_value(),
+                error_name: error_name.clone(),
+                variant_name: variant_name.clone(),
+            }),
+            ValueType::Function(params, ret) => Ok(ExprValue::Closure {
+                pointer: value.into_pointer_value(),
+                param_types: params.clone(),
+                return_type: ret.clone(),
+            }),
+            ValueType::Optional(inner) => Ok(ExprValue::Optional {
+                value: value.into_struct_value(),
+                inner: inner.clone(),
+            }),
+            ValueType::Void => bail!("cannot convert void to ExprValue"),
+            ValueType::Any => Ok(ExprValue::Any {
+                value: value.into_struct_value(),
+            }),
+        }
+    }
+
+    /// Compile a Dict method call.
+    fn compile_dict_method_call(
+        &mut self,
+        method_name: &str,
+        dict_ptr: PointerValue<'ctx>,
+        value_type: Box<ValueType>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        match method_name {
+            "keys" => {
+                let dict_keys_fn = self.ensure_dict_keys_fn();
+                let keys_ptr = map_builder_error(self.builder.build_call(
+                    dict_keys_fn,
+                    &[dict_ptr.into()],
+                    "dict_keys",
+                ))?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!("expected pointer from dict_keys"))?
+                .into_pointer_value();
+                Ok(ExprValue::List {
+                    pointer: keys_ptr,
+                    element_type: Box::new(ValueType::String),
+                })
+            }
+            "values" => {
+                let dict_values_fn = self.ensure_dict_values_fn();
+                let values_ptr = map_builder_error(self.builder.build_call(
+                    dict_values_fn,
+                    &[dict_ptr.into()],
+                    "dict_values",
+                ))?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!("expected pointer from dict_values"))?
+                .into_pointer_value();
+                Ok(ExprValue::List {
+                    pointer: values_ptr,
+                    element_type: value_type,
+                })
+            }
+            "entries" => {
+                let dict_entries_fn = self.ensure_dict_entries_fn();
+                let entries_ptr = map_builder_error(self.builder.build_call(
+                    dict_entries_fn,
+                    &[dict_ptr.into()],
+                    "dict_entries",
+                ))?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!("expected pointer from dict_entries"))?
+                .into_pointer_value();
+                // Each entry is a dict with the same value type
+                Ok(ExprValue::List {
+                    pointer: entries_ptr,
+                    element_type: Box::new(ValueType::Dict(value_type)),
+                })
+            }
+            "items" => {
+                let dict_entries_fn = self.ensure_dict_entries_fn();
+                let entries_ptr = map_builder_error(self.builder.build_call(
+                    dict_entries_fn,
+                    &[dict_ptr.into()],
+                    "dict_items",
+                ))?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!("expected pointer from dict_entries"))?
+                .into_pointer_value();
+                Ok(ExprValue::List {
+                    pointer: entries_ptr,
+                    element_type: Box::new(ValueType::Dict(value_type)),
+                })
+            }
+            "has" => self.compile_dict_has(dict_ptr, call, function, locals),
+            "get_or" => self.compile_dict_get_or(dict_ptr, value_type, call, function, locals),
+            "merge" => self.compile_dict_merge(dict_ptr, value_type, call, function, locals),
+            _ => bail!("unknown Dict method: {}", method_name),
+        }
+    }
+
+    fn compile_dict_has(
+        &mut self,
+        dict_ptr: PointerValue<'ctx>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        let key_expr = self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let key_value = self.convert_expr_to_type(key_expr, &ValueType::String)?;
+        let key_ptr = match key_value {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("Dict.has key must be a String"),
+        };
+
+        let dict_has_fn = self.ensure_dict_has();
+        let raw = self
+            .call_function(dict_has_fn, &[dict_ptr.into(), key_ptr.into()], "dict_has")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected bool from dict_has"))?
+            .into_int_value();
+        let result = self.i32_to_bool(raw, "dict_has_bool")?;
+
+        Ok(ExprValue::Bool(result))
+    }
+
+    fn compile_dict_get_or(
+        &mut self,
+        dict_ptr: PointerValue<'ctx>,
+        value_type: Box<ValueType>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        let key_expr = self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let key_value = self.convert_expr_to_type(key_expr, &ValueType::String)?;
+        let key_ptr = match key_value {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("Dict.get_or key must be a String"),
+        };
+
+        let dict_has_fn = self.ensure_dict_has();
+        let has_raw = self
+            .call_function(dict_has_fn, &[dict_ptr.into(), key_ptr.into()], "dict_has")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected bool from dict_has"))?
+            .into_int_value();
+        let has_value = map_builder_error(self.builder.build_int_compare(
+            IntPredicate::NE,
+            has_raw,
+            self.context.i32_type().const_zero(),
+            "dict_has_value",
+        ))?;
+
+        let result_alloca = self.create_entry_alloca(
+            function,
+            "dict_get_or_result",
+            self.basic_type(&value_type)?,
+        )?;
+        let current_block = self
+            .builder
+            .get_insert_block()
+            .ok_or_else(|| anyhow!("missing insertion block"))?;
+        let found_block = self
+            .context
+            .append_basic_block(function, "dict_get_or_found");
+        let fallback_block = self
+            .context
+            .append_basic_block(function, "dict_get_or_fallback");
+        let merge_block = self
+            .context
+            .append_basic_block(function, "dict_get_or_merge");
+
+        map_builder_error(self.builder.build_conditional_branch(
+            has_value,
+            found_block,
+            fallback_block,
+        ))?;
+
+        self.builder.position_at_end(found_block);
+        let dict_get_fn = self.ensure_dict_get();
+        let raw_value = self
+            .call_function(dict_get_fn, &[dict_ptr.into(), key_ptr.into()], "dict_get")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected TeaValue from dict_get"))?
+            .into_struct_value();
+        let found_value = self.tea_value_to_expr_value(raw_value, &value_type)?;
+        self.store_expr_in_pointer(result_alloca, &value_type, found_value, "dict_get_or_found")?;
+        map_builder_error(self.builder.build_unconditional_branch(merge_block))?;
+
+        self.builder.position_at_end(fallback_block);
+        let fallback = self.compile_expression(&call.arguments[1].expression, function, locals)?;
+        self.store_expr_in_pointer(result_alloca, &value_type, fallback, "dict_get_or_fallback")?;
+        map_builder_error(self.builder.build_unconditional_branch(merge_block))?;
+
+        self.builder.position_at_end(merge_block);
+        let loaded = map_builder_error(self.builder.build_load(
+            self.basic_type(&value_type)?,
+            result_alloca,
+            "dict_get_or_value",
+        ))?;
+        let _ = current_block;
+        self.basic_to_expr_value(loaded, &value_type)
+    }
+
+    fn compile_dict_merge(
+        &mut self,
+        dict_ptr: PointerValue<'ctx>,
+        value_type: Box<ValueType>,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        let other_expr =
+            self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let other_dict =
+            self.convert_expr_to_type(other_expr, &ValueType::Dict(value_type.clone()))?;
+        let other_ptr = match other_dict {
+            ExprValue::Dict { pointer, .. } => pointer,
+            _ => bail!("Dict.merge expects another Dict"),
+        };
+
+        let dict_new_fn = self.ensure_dict_new();
+        let result_ptr = self
+            .call_function(dict_new_fn, &[], "dict_new")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected pointer from dict_new"))?
+            .into_pointer_value();
+
+        self.copy_dict_entries_into(dict_ptr, result_ptr, value_type.clone(), function)?;
+        self.copy_dict_entries_into(other_ptr, result_ptr, value_type.clone(), function)?;
+
+        Ok(ExprValue::Dict {
+            pointer: result_ptr,
+            value_type,
+        })
+    }
+
+    fn copy_dict_entries_into(
+        &mut self,
+        source_dict: PointerValue<'ctx>,
+        target_dict: PointerValue<'ctx>,
+        value_type: Box<ValueType>,
+        function: FunctionValue<'ctx>,
+    ) -> Result<()> {
+        let dict_keys_fn = self.ensure_dict_keys_fn();
+        let keys_ptr = self
+            .call_function(dict_keys_fn, &[source_dict.into()], "dict_keys")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected pointer from dict_keys"))?
+            .into_pointer_value();
+        let list_len_fn = self.ensure_list_len_ffi_fn();
+        let length = self
+            .call_function(list_len_fn, &[keys_ptr.into()], "keys_len")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected i64 from list_len"))?
+            .into_int_value();
+
+        let current_block = self
+            .builder
+            .get_insert_block()
+            .ok_or_else(|| anyhow!("missing insertion block"))?;
+        let cond_block = self.context.append_basic_block(function, "dict_copy_cond");
+        let body_block = self.context.append_basic_block(function, "dict_copy_body");
+        let exit_block = self.context.append_basic_block(function, "dict_copy_exit");
+        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
+
+        self.builder.position_at_end(cond_block);
+        let i64_type = self.context.i64_type();
+        let index_phi = map_builder_error(self.builder.build_phi(i64_type, "index"))?;
+        index_phi.add_incoming(&[(&i64_type.const_zero(), current_block)]);
+        let cond = map_builder_error(self.builder.build_int_compare(
+            IntPredicate::SLT,
+            index_phi.as_basic_value().into_int_value(),
+            length,
+            "dict_copy_cond",
+        ))?;
+        map_builder_error(
+            self.builder
+                .build_conditional_branch(cond, body_block, exit_block),
+        )?;
+
+        self.builder.position_at_end(body_block);
+        let list_get_fn = self.ensure_list_get();
+        let key_value = self
+            .call_function(
+                list_get_fn,
+                &[keys_ptr.into(), index_phi.as_basic_value().into()],
+                "dict_copy_key",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected TeaValue"))?
+            .into_struct_value();
+        let key_expr = self.tea_value_to_expr_value(key_value, &ValueType::String)?;
+        let key_ptr = match key_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("dict key must be a String"),
+        };
+
+        let dict_get_fn = self.ensure_dict_get();
+        let value = self
+            .call_function(
+                dict_get_fn,
+                &[source_dict.into(), key_ptr.into()],
+                "dict_get",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected TeaValue from dict_get"))?
+            .into_struct_value();
+        let value_expr = self.tea_value_to_expr_value(value, &value_type)?;
+        let tea_value = self.expr_to_tea_value(value_expr)?.into_struct_value();
+        let tea_value_type = self
+            .context
+            .get_struct_type("TeaValue")
+            .ok_or_else(|| anyhow!("TeaValue type not found"))?;
+        let temp_ptr =
+            map_builder_error(self.builder.build_alloca(tea_value_type, "dict_copy_value"))?;
+        map_builder_error(self.builder.build_store(temp_ptr, tea_value))?;
+
+        let dict_set_fn = self.ensure_dict_set();
+        self.call_function(
+            dict_set_fn,
+            &[target_dict.into(), key_ptr.into(), temp_ptr.into()],
+            "dict_set",
+        )?;
+
+        let next_index = map_builder_error(self.builder.build_int_add(
+            index_phi.as_basic_value().into_int_value(),
+            i64_type.const_int(1, false),
+            "next_index",
+        ))?;
+        index_phi.add_incoming(&[(&next_index, body_block)]);
+        map_builder_error(self.builder.build_unconditional_branch(cond_block))?;
+
+        self.builder.position_at_end(exit_block);
+        Ok(())
+    }
+
+    fn compile_struct_constructor(
+        &mut self,
+        name: &str,
+        span: SourceSpan,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        let (field_names, field_types_default, field_defaults) = {
+            let info = self
+                .structs
+                .get(name)
+                .ok_or_else(|| anyhow!(format!("unknown struct '{name}'")))?;
+            (
+                info.field_names.clone(),
+                info.field_types.clone(),
+                info.field_defaults.clone(),
+            )
+        };
+        let mut field_types = field_types_default.clone();
+        let mut variant_name = name.to_string();
+        if let Some((_, instance)) = self.struct_call_metadata_tc.get(&span) {
+            let instance = instance.clone();
+            let mut converted = Vec::with_capacity(instance.field_types.len());
+            for ty in &instance.field_types {
+                converted.push(self.resolve_type_with_bindings_to_value(ty)?);
+            }
+            field_types = converted;
+            let mut resolved_args = Vec::with_capacity(instance.type_arguments.len());
+            for arg in &instance.type_arguments {
+                resolved_args.push(self.resolve_type_with_bindings(arg)?);
+            }
+            let struct_type = StructType {
+                name: name.to_string(),
+                type_arguments: resolved_args,
+            };
+            variant_name = format_struct_type_name(&struct_type);
+            self.struct_field_variants
+                .entry(variant_name.clone())
+                .or_insert_with(|| field_types.clone());
+            self.struct_variant_bases
+                .entry(variant_name.clone())
+                .or_insert_with(|| name.to_string());
+        } else {
+            if field_types.is_empty() {
+                bail!(format!("missing struct field type metadata for '{}'", name));
+            }
+            self.struct_field_variants
+                .entry(name.to_string())
+                .or_insert_with(|| field_types.clone());
+            self.struct_variant_bases
+                .entry(name.to_string())
+                .or_insert_with(|| name.to_string());
+        }
+
+        let template_ptr = self.ensure_struct_template(name)?;
+        let alloc_fn = self.ensure_alloc_struct();
+        let call = self.call_function(alloc_fn, &[template_ptr.into()], "struct_alloc")?;
+        let struct_ptr = call
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected struct pointer"))?
+            .into_pointer_value();
+
+        // Copy-with-update calls (`Name(..base, field: value)`) read untouched fields
+        // straight from the base instance instead of re-evaluating anything.
+        let base_ptr = match arguments.iter().find(|argument| argument.spread) {
+            Some(argument) => {
+                match self.compile_expression(&argument.expression, function, locals)? {
+                    ExprValue::Struct { pointer, .. } => Some(pointer),
+                    other => bail!(
+                        "'..' base for struct '{}' must be a struct value, found {:?}",
+                        name,
+                        other.ty()
+                    ),
+                }
+            }
+            None => None,
+        };
+        let arguments: Vec<&crate::ast::CallArgument> = arguments
+            .iter()
+            .filter(|argument| !argument.spread)
+            .collect();
+
+        let field_count = field_names.len();
+        let has_named = arguments.iter().any(|arg| arg.name.is_some());
+        if has_named && arguments.iter().any(|arg| arg.name.is_none()) {
+            bail!("cannot mix named and positional arguments in struct constructor");
+        }
+
+        if !has_named && arguments.len() > field_count {
+            bail!(
+                "struct '{}' expects {} arguments, found {}",
+                name,
+                field_count,
+                arguments.len()
+            );
+        }
+
+        let mut seen = vec![false; field_count];
+        if has_named {
+            let mut lookup = HashMap::with_capacity(field_count);
+            for (index, field_name) in field_names.iter().enumerate() {
+                lookup.insert(field_name.clone(), index);
+            }
+            for argument in &arguments {
+                let field_name = argument
+                    .name
+                    .as_ref()
+                    .expect("named argument expected for struct");
+                let index = lookup.get(field_name.as_str()).copied().ok_or_else(|| {
+                    anyhow!(format!(
+                        "struct '{}' has no field named '{}'",
+                        name, field_name
+                    ))
+                })?;
+                if seen[index] {
+                    bail!(format!(
+                        "field '{}' provided multiple times to struct '{}'",
+                        field_name, name
+                    ));
+                }
+                self.compile_struct_field_value(
+                    struct_ptr,
+                    name,
+                    &field_names[index],
+                    index,
+                    &field_types[index],
+                    &argument.expression,
+                    function,
+                    locals,
+                )?;
+                seen[index] = true;
+            }
+        } else {
+            for (index, argument) in arguments.iter().enumerate() {
+                self.compile_struct_field_value(
+                    struct_ptr,
+                    name,
+                    &field_names[index],
+                    index,
+                    &field_types[index],
+                    &argument.expression,
+                    function,
+                    locals,
+                )?;
+                seen[index] = true;
+            }
+        }
+
+        let mut missing = Vec::new();
+        for (index, assigned) in seen.iter().enumerate() {
+            if *assigned {
+                continue;
+            }
+            if let Some(base_ptr) = base_ptr {
+                self.copy_struct_field(base_ptr, struct_ptr, index)?;
+            } else if let Some(default) = field_defaults.get(index).cloned().flatten() {
+                // Defaults are evaluated in module scope, not the caller's locals.
+                let mut default_locals = HashMap::new();
+                self.compile_struct_field_value(
+                    struct_ptr,
+                    name,
+                    &field_names[index],
+                    index,
+                    &field_types[index],
+                    &default,
+                    function,
+                    &mut default_locals,
+                )?;
+            } else {
+                missing.push(field_names[index].as_str());
+            }
+        }
+        if !missing.is_empty() {
+            bail!(format!(
+                "missing fields for struct '{}': {}",
+                name,
+                missing.join(", ")
+            ));
+        }
+
+        Ok(ExprValue::Struct {
+            pointer: struct_ptr,
+            struct_name: variant_name,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn compile_struct_field_value(
+        &mut self,
+        struct_ptr: PointerValue<'ctx>,
+        struct_name: &str,
+        field_name: &str,
+        index: usize,
+        expected: &ValueType,
+        expression: &Expression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<()> {
+        let value = self.compile_expression(expression, function, locals)?;
+        let converted = self
+            .convert_expr_to_type(value, expected)
+            .map_err(|error| {
+                anyhow!(
+                    "field '{}' in struct '{}' expects {:?}: {}",
+                    field_name,
+                    struct_name,
+                    expected,
+                    error
+                )
+            })?;
+        // Pass TeaValue by pointer to avoid ARM64 ABI issues
+        let tea_value = self.expr_to_tea_value(converted)?.into_struct_value();
+        let tea_value_type = self
+            .context
+            .get_struct_type("TeaValue")
+            .ok_or_else(|| anyhow!("TeaValue type not found"))?;
+        let tea_value_alloca = map_builder_error(
+            self.builder
+                .build_alloca(tea_value_type, "struct_field_value"),
+        )?;
+        map_builder_error(self.builder.build_store(tea_value_alloca, tea_value))?;
+        let set_fn = self.ensure_struct_set();
+        self.call_function(
+            set_fn,
+            &[
+                struct_ptr.into(),
+                self.int_type().const_int(index as u64, false).into(),
+                tea_value_alloca.into(),
+            ],
+            "struct_set",
+        )?;
+        Ok(())
+    }
+
+    fn copy_struct_field(
+        &mut self,
+        source_ptr: PointerValue<'ctx>,
+        target_ptr: PointerValue<'ctx>,
+        index: usize,
+    ) -> Result<()> {
+        let tea_value_type = self
+            .context
+            .get_struct_type("TeaValue")
+            .ok_or_else(|| anyhow!("TeaValue type not found"))?;
+        let slot = map_builder_error(
+            self.builder
+                .build_alloca(tea_value_type, "struct_copy_value"),
+        )?;
+        let index_value = self.int_type().const_int(index as u64, false);
+        let get_fn = self.ensure_struct_get();
+        self.call_function(
+            get_fn,
+            &[source_ptr.into(), index_value.into(), slot.into()],
+            "struct_get",
+        )?;
+        let set_fn = self.ensure_struct_set();
+        self.call_function(
+            set_fn,
+            &[target_ptr.into(), index_value.into(), slot.into()],
+            "struct_set",
+        )?;
+        Ok(())
+    }
+
+    fn try_compile_error_constructor(
+        &mut self,
+        error_name: &str,
+        variant: Option<&str>,
+        call: &CallExpression,
+        _span: SourceSpan,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<Option<ExprValue<'ctx>>> {
+        let definition = match self.error_definitions_tc.get(error_name) {
+            Some(def) => def,
+            None => return Ok(None),
+        };
+
+        let (variant_name, variant_def) = match variant {
+            Some(name) => match definition.variants.get(name) {
+                Some(def) => (name.to_string(), def),
+                None => return Ok(None),
+            },
+            None => {
+                if definition.variants.len() == 1 {
+                    let (name, def) = definition
+                        .variants
+                        .iter()
+                        .next()
+                        .expect("error with single variant");
+                    (name.clone(), def)
+                } else {
+                    return Ok(None);
+                }
+            }
+        };
+
+        if call.arguments.len() != variant_def.fields.len() {
+            bail!(format!(
+                "error variant '{}.{}' expects {} argument(s) but {} provided",
+                error_name,
+                variant_name,
+                variant_def.fields.len(),
+                call.arguments.len()
+            ));
+        }
+
+        let mut values = Vec::with_capacity(call.arguments.len());
+        for argument in &call.arguments {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported for error constructors");
+            }
+            values.push(self.compile_expression(&argument.expression, function, locals)?);
+        }
+        let error_ptr = self.build_error_instance(error_name, &variant_name, values)?;
+
+        Ok(Some(ExprValue::Error {
+            pointer: error_ptr,
+            error_name: error_name.to_string(),
+            variant_name: Some(variant_name),
+        }))
+    }
+
+    /// Allocate an instance of `error_name.variant_name` holding `values` as its
+    /// fields, in declaration order.
+    fn build_error_instance(
+        &mut self,
+        error_name: &str,
+        variant_name: &str,
+        values: Vec<ExprValue<'ctx>>,
+    ) -> Result<PointerValue<'ctx>> {
+        self.ensure_error_variant_metadata(error_name, variant_name)?;
+        let field_types = self
+            .errors
+            .get(error_name)
+            .and_then(|variants| variants.get(variant_name))
+            .map(|entry| entry.field_types.clone())
+            .ok_or_else(|| {
+                anyhow!(
+                    "missing field metadata for error '{}.{}'",
+                    error_name,
+                    variant_name
+                )
+            })?;
+        let template_ptr = self.ensure_error_template(error_name, variant_name)?;
+
+        let alloc_fn = self.ensure_error_alloc();
+        let call_site = self.call_function(alloc_fn, &[template_ptr.into()], "error_alloc")?;
+        let error_ptr = call_site
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected error pointer"))?
+            .into_pointer_value();
+
+        let set_fn = self.ensure_error_set();
+        for (index, value) in values.into_iter().enumerate() {
+            let expected = field_types.get(index).ok_or_else(|| {
+                anyhow!(
+                    "missing field metadata for error '{}.{}'",
+                    error_name,
+                    variant_name
+                )
+            })?;
+            let converted = self
+                .convert_expr_to_type(value, expected)
+                .map_err(|error| {
+                    anyhow!(
+                        "argument {} for error '{}.{}' has mismatched type: {}",
+                        index + 1,
+                        error_name,
+                        variant_name,
+                        error
+                    )
+                })?;
+            // Pass TeaValue by pointer to avoid ARM64 ABI issues
+            let tea_value = self.expr_to_tea_value(converted)?.into_struct_value();
+            let tea_value_type = self
+                .context
+                .get_struct_type("TeaValue")
+                .ok_or_else(|| anyhow!("TeaValue type not found"))?;
+            let tea_value_alloca = map_builder_error(
+                self.builder
+                    .build_alloca(tea_value_type, "error_field_value"),
+            )?;
+            map_builder_error(self.builder.build_store(tea_value_alloca, tea_value))?;
+            self.call_function(
+                set_fn,
+                &[
+                    error_ptr.into(),
+                    self.int_type().const_int(index as u64, false).into(),
+                    tea_value_alloca.into(),
+                ],
+                "error_set",
+            )?;
+        }
+
+        Ok(error_ptr)
+    }
+
+    fn compile_print_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        for argument in arguments {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported by the LLVM backend yet");
+            }
+            let value = self.compile_expression(&argument.expression, function, locals)?;
+            match value {
+                ExprValue::Int(v) => {
+                    let func = self.ensure_print_int();
+                    self.call_function(func, &[v.into()], "print_int")?;
+                }
+                ExprValue::Float(v) => {
+                    let func = self.ensure_print_float();
+                    self.call_function(func, &[v.into()], "print_float")?;
+                }
+                ExprValue::Bool(v) => {
+                    let cast = self.bool_to_i32(v, "print_bool")?;
+                    let func = self.ensure_print_bool();
+                    self.call_function(func, &[cast.into()], "print_bool")?;
+                }
+                ExprValue::String(ptr) => {
+                    let func = self.ensure_print_string();
+                    self.call_function(func, &[ptr.into()], "print_string")?;
+                }
+                ExprValue::Bytes(ptr) => {
+                    let string_ptr = self.bytes_to_display_string(ptr)?;
+                    let func = self.ensure_print_string();
+                    self.call_function(func, &[string_ptr.into()], "print_bytes")?;
+                }
+                ExprValue::List { pointer, .. } => {
+                    let func = self.ensure_print_list();
+                    self.call_function(func, &[pointer.into()], "print_list")?;
+                }
+                ExprValue::Dict { pointer, .. } => {
+                    let func = self.ensure_print_dict();
+                    self.call_function(func, &[pointer.into()], "print_dict")?;
+                }
+                ExprValue::Struct { pointer, .. } => {
+                    let func = self.ensure_print_struct();
+                    self.call_function(func, &[pointer.into()], "print_struct")?;
+                }
+                ExprValue::Error { pointer, .. } => {
+                    let func = self.ensure_print_error();
+                    self.call_function(func, &[pointer.into()], "print_error")?;
+                }
+                ExprValue::Closure { pointer, .. } => {
+                    let func = self.ensure_print_closure();
+                    self.call_function(func, &[pointer.into()], "print_closure")?;
+                }
+                ExprValue::Optional { value, .. } => {
+                    let tea_value_type = self
+                        .context
+                        .get_struct_type("TeaValue")
+                        .ok_or_else(|| anyhow!("TeaValue type not found"))?;
+                    // Pass by pointer for ARM64 ABI compatibility
+                    let alloca = map_builder_error(
+                        self.builder.build_alloca(tea_value_type, "opt_tea_val_tmp"),
+                    )?;
+                    map_builder_error(self.builder.build_store(alloca, value))?;
+                    let to_string = self.ensure_util_to_string_fn();
+                    let string_ptr = self
+                        .call_function(to_string, &[alloca.into()], "optional_to_string")?
+                        .try_as_basic_value()
+                        .left()
+                        .ok_or_else(|| anyhow!("tea_util_to_string returned no value"))?
+                        .into_pointer_value();
+                    let func = self.ensure_print_string();
+                    self.call_function(func, &[string_ptr.into()], "print_optional")?;
+                }
+                ExprValue::Iterator { .. } => {
+                    bail!("iterators cannot be printed; collect them into a list first");
+                }
+                ExprValue::Void => {
+                    let nil_string = self.compile_string_literal("nil")?;
+                    if let ExprValue::String(ptr) = nil_string {
+                        let func = self.ensure_print_string();
+                        self.call_function(func, &[ptr.into()], "print_nil")?;
+                    } else {
+                        bail!("expected string literal for nil printing");
+                    }
+                }
+                ExprValue::Any { value } => {
+                    // Any holds a TeaValue struct - convert to string and print
+                    let tea_value_type = self
+                        .context
+                        .get_struct_type("TeaValue")
+                        .ok_or_else(|| anyhow!("TeaValue type not found"))?;
+                    let alloca = map_builder_error(
+                        self.builder.build_alloca(tea_value_type, "any_tea_val_tmp"),
+                    )?;
+                    map_builder_error(self.builder.build_store(alloca, value))?;
+                    let to_string = self.ensure_util_to_string_fn();
+                    let string_ptr = self
+                        .call_function(to_string, &[alloca.into()], "any_to_string")?
+                        .try_as_basic_value()
+                        .left()
+                        .ok_or_else(|| anyhow!("tea_util_to_string returned no value"))?
+                        .into_pointer_value();
+                    let func = self.ensure_print_string();
+                    self.call_function(func, &[string_ptr.into()], "print_any")?;
+                }
+            }
+        }
+        Ok(ExprValue::Void)
     }
 
-    fn compile_struct_constructor(
+    fn compile_println_call(
         &mut self,
-        name: &str,
-        span: SourceSpan,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        let (field_names, field_types_default) = {
-            let info = self
-                .structs
-                .get(name)
-                .ok_or_else(|| anyhow!(format!("unknown struct '{name}'")))?;
-            (info.field_names.clone(), info.field_types.clone())
-        };
-        let mut field_types = field_types_default.clone();
-        let mut variant_name = name.to_string();
-        if let Some((_, instance)) = self.struct_call_metadata_tc.get(&span) {
-            let instance = instance.clone();
-            let mut converted = Vec::with_capacity(instance.field_types.len());
-            for ty in &instance.field_types {
-                converted.push(self.resolve_type_with_bindings_to_value(ty)?);
-            }
-            field_types = converted;
-            let mut resolved_args = Vec::with_capacity(instance.type_arguments.len());
-            for arg in &instance.type_arguments {
-                resolved_args.push(self.resolve_type_with_bindings(arg)?);
-            }
-            let struct_type = StructType {
-                name: name.to_string(),
-                type_arguments: resolved_args,
-            };
-            variant_name = format_struct_type_name(&struct_type);
-            self.struct_field_variants
-                .entry(variant_name.clone())
-                .or_insert_with(|| field_types.clone());
-            self.struct_variant_bases
-                .entry(variant_name.clone())
-                .or_insert_with(|| name.to_string());
-        } else {
-            if field_types.is_empty() {
-                bail!(format!("missing struct field type metadata for '{}'", name));
-            }
-            self.struct_field_variants
-                .entry(name.to_string())
-                .or_insert_with(|| field_types.clone());
-            self.struct_variant_bases
-                .entry(name.to_string())
-                .or_insert_with(|| name.to_string());
-        }
-
-        let template_ptr = self.ensure_struct_template(name)?;
-        let alloc_fn = self.ensure_alloc_struct();
-        let call = self.call_function(alloc_fn, &[template_ptr.into()], "struct_alloc")?;
-        let struct_ptr = call
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("expected struct pointer"))?
-            .into_pointer_value();
-        let set_fn = self.ensure_struct_set();
-
-        let field_count = field_names.len();
-        let has_named = arguments.iter().any(|arg| arg.name.is_some());
-        if has_named && arguments.iter().any(|arg| arg.name.is_none()) {
-            bail!("cannot mix named and positional arguments in struct constructor");
-        }
-
-        if !has_named && arguments.len() != field_count {
-            bail!(
-                "struct '{}' expects {} arguments, found {}",
-                name,
-                field_count,
-                arguments.len()
-            );
-        }
-
-        if has_named {
-            let mut lookup = HashMap::with_capacity(field_count);
-            for (index, field_name) in field_names.iter().enumerate() {
-                lookup.insert(field_name.clone(), index);
+        // Same as print but calls println functions instead
+        for argument in arguments {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported by the LLVM backend yet");
             }
-            let mut seen = vec![false; field_count];
-            for argument in arguments {
-                let field_name = argument
-                    .name
-                    .as_ref()
-                    .expect("named argument expected for struct");
-                let index = lookup.get(field_name.as_str()).copied().ok_or_else(|| {
-                    anyhow!(format!(
-                        "struct '{}' has no field named '{}'",
-                        name, field_name
-                    ))
-                })?;
-                if seen[index] {
-                    bail!(format!(
-                        "field '{}' provided multiple times to struct '{}'",
-                        field_name, name
-                    ));
+            let value = self.compile_expression(&argument.expression, function, locals)?;
+            match value {
+                ExprValue::Int(v) => {
+                    let func = self.ensure_println_int();
+                    self.call_function(func, &[v.into()], "println_int")?;
                 }
-                let value = self.compile_expression(&argument.expression, function, locals)?;
-                let expected = &field_types[index];
-                let converted = self
-                    .convert_expr_to_type(value, expected)
-                    .map_err(|error| {
-                        anyhow!(
-                            "field '{}' in struct '{}' expects {:?}: {}",
-                            field_name,
-                            name,
-                            expected,
-                            error
-                        )
-                    })?;
-                // Pass TeaValue by pointer to avoid ARM64 ABI issues
-                let tea_value = self.expr_to_tea_value(converted)?.into_struct_value();
-                let tea_value_type = self
-                    .context
-                    .get_struct_type("TeaValue")
-                    .ok_or_else(|| anyhow!("TeaValue type not found"))?;
-                let tea_value_alloca = map_builder_error(
-                    self.builder
-                        .build_alloca(tea_value_type, "struct_field_value"),
-                )?;
-                map_builder_error(self.builder.build_store(tea_value_alloca, tea_value))?;
-                self.call_function(
-                    set_fn,
-                    &[
-                        struct_ptr.into(),
-                        self.int_type().const_int(index as u64, false).into(),
-                        tea_value_alloca.into(),
-                    ],
-                    "struct_set",
-                )?;
-                seen[index] = true;
-            }
-            if seen.iter().any(|assigned| !*assigned) {
-                let missing: Vec<&str> = field_names
-                    .iter()
-                    .enumerate()
-                    .filter_map(|(index, field)| (!seen[index]).then(|| field.as_str()))
-                    .collect();
-                if !missing.is_empty() {
-                    bail!(format!(
-                        "missing fields for struct '{}': {}",
-                        name,
-                        missing.join(", ")
-                    ));
+                ExprValue::Float(v) => {
+                    let func = self.ensure_println_float();
+                    self.call_function(func, &[v.into()], "println_float")?;
                 }
-            }
-        } else {
-            for (index, argument) in arguments.iter().enumerate() {
-                if argument.name.is_some() {
-                    bail!("cannot mix named arguments in positional struct constructor");
+                ExprValue::Bool(v) => {
+                    let cast = self.bool_to_i32(v, "println_bool")?;
+                    let func = self.ensure_println_bool();
+                    self.call_function(func, &[cast.into()], "println_bool")?;
+                }
+                ExprValue::String(ptr) => {
+                    let func = self.ensure_println_string();
+                    self.call_function(func, &[ptr.into()], "println_string")?;
+                }
+                ExprValue::Bytes(ptr) => {
+                    let string_ptr = self.bytes_to_display_string(ptr)?;
+                    let func = self.ensure_println_string();
+                    self.call_function(func, &[string_ptr.into()], "println_bytes")?;
+                }
+                ExprValue::List { pointer, .. } => {
+                    let func = self.ensure_println_list();
+                    self.call_function(func, &[pointer.into()], "println_list")?;
+                }
+                ExprValue::Dict { pointer, .. } => {
+                    let func = self.ensure_println_dict();
+                    self.call_function(func, &[pointer.into()], "println_dict")?;
+                }
+                ExprValue::Struct { pointer, .. } => {
+                    let func = self.ensure_println_struct();
+                    self.call_function(func, &[pointer.into()], "println_struct")?;
+                }
+                ExprValue::Error { pointer, .. } => {
+                    let func = self.ensure_println_error();
+                    self.call_function(func, &[pointer.into()], "println_error")?;
+                }
+                ExprValue::Closure { pointer, .. } => {
+                    let func = self.ensure_println_closure();
+                    self.call_function(func, &[pointer.into()], "println_closure")?;
+                }
+                ExprValue::Optional { value, .. } => {
+                    let tea_value_type = self
+                        .context
+                        .get_struct_type("TeaValue")
+                        .ok_or_else(|| anyhow!("TeaValue type not found"))?;
+                    // Pass by pointer for ARM64 ABI compatibility
+                    let alloca = map_builder_error(
+                        self.builder.build_alloca(tea_value_type, "opt_tea_val_tmp"),
+                    )?;
+                    map_builder_error(self.builder.build_store(alloca, value))?;
+                    let to_string = self.ensure_util_to_string_fn();
+                    let string_ptr = self
+                        .call_function(to_string, &[alloca.into()], "optional_to_string")?
+                        .try_as_basic_value()
+                        .left()
+                        .ok_or_else(|| anyhow!("tea_util_to_string returned no value"))?
+                        .into_pointer_value();
+                    let func = self.ensure_println_string();
+                    self.call_function(func, &[string_ptr.into()], "println_optional")?;
+                }
+                ExprValue::Iterator { .. } => {
+                    bail!("iterators cannot be printed; collect them into a list first");
+                }
+                ExprValue::Void => {
+                    let nil_string = self.compile_string_literal("nil")?;
+                    if let ExprValue::String(ptr) = nil_string {
+                        let func = self.ensure_println_string();
+                        self.call_function(func, &[ptr.into()], "println_nil")?;
+                    } else {
+                        bail!("expected string literal for nil printing");
+                    }
+                }
+                ExprValue::Any { value } => {
+                    // Any holds a TeaValue struct - convert to string and println
+                    let tea_value_type = self
+                        .context
+                        .get_struct_type("TeaValue")
+                        .ok_or_else(|| anyhow!("TeaValue type not found"))?;
+                    let alloca = map_builder_error(
+                        self.builder.build_alloca(tea_value_type, "any_tea_val_tmp"),
+                    )?;
+                    map_builder_error(self.builder.build_store(alloca, value))?;
+                    let to_string = self.ensure_util_to_string_fn();
+                    let string_ptr = self
+                        .call_function(to_string, &[alloca.into()], "any_to_string")?
+                        .try_as_basic_value()
+                        .left()
+                        .ok_or_else(|| anyhow!("tea_util_to_string returned no value"))?
+                        .into_pointer_value();
+                    let func = self.ensure_println_string();
+                    self.call_function(func, &[string_ptr.into()], "println_any")?;
                 }
-                let value = self.compile_expression(&argument.expression, function, locals)?;
-                let expected = &field_types[index];
-                let converted = self
-                    .convert_expr_to_type(value, expected)
-                    .map_err(|error| {
-                        anyhow!(
-                            "field '{}' in struct '{}' expects {:?}: {}",
-                            field_names[index],
-                            name,
-                            expected,
-                            error
-                        )
-                    })?;
-                // Pass TeaValue by pointer to avoid ARM64 ABI issues
-                let tea_value = self.expr_to_tea_value(converted)?.into_struct_value();
-                let tea_value_type = self
-                    .context
-                    .get_struct_type("TeaValue")
-                    .ok_or_else(|| anyhow!("TeaValue type not found"))?;
-                let tea_value_alloca = map_builder_error(
-                    self.builder
-                        .build_alloca(tea_value_type, "struct_field_value"),
-                )?;
-                map_builder_error(self.builder.build_store(tea_value_alloca, tea_value))?;
-                self.call_function(
-                    set_fn,
-                    &[
-                        struct_ptr.into(),
-                        self.int_type().const_int(index as u64, false).into(),
-                        tea_value_alloca.into(),
-                    ],
-                    "struct_set",
-                )?;
             }
         }
+        Ok(ExprValue::Void)
+    }
 
-        Ok(ExprValue::Struct {
-            pointer: struct_ptr,
-            struct_name: variant_name,
-        })
+    fn compile_to_string_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        // Same as UtilToString
+        self.compile_util_to_string_call(arguments, function, locals)
+    }
+
+    fn compile_type_of_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 1 {
+            bail!("type_of expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for type_of");
+        }
+        let value_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let tea_value = self.expr_to_tea_value(value_expr)?;
+        let func = self.ensure_type_of_fn();
+        let pointer = self
+            .call_function(func, &[tea_value.into()], "tea_type_of")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_type_of returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::String(pointer))
     }
 
-    fn try_compile_error_constructor(
+    fn compile_panic_call(
         &mut self,
-        error_name: &str,
-        variant: Option<&str>,
-        call: &CallExpression,
-        _span: SourceSpan,
+        arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
-    ) -> Result<Option<ExprValue<'ctx>>> {
-        let definition = match self.error_definitions_tc.get(error_name) {
-            Some(def) => def,
-            None => return Ok(None),
-        };
-
-        let (variant_name, variant_def) = match variant {
-            Some(name) => match definition.variants.get(name) {
-                Some(def) => (name.to_string(), def),
-                None => return Ok(None),
-            },
-            None => {
-                if definition.variants.len() == 1 {
-                    let (name, def) = definition
-                        .variants
-                        .iter()
-                        .next()
-                        .expect("error with single variant");
-                    (name.clone(), def)
-                } else {
-                    return Ok(None);
-                }
-            }
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 1 {
+            bail!("panic expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for panic");
+        }
+        let message_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let message_ptr = match message_expr {
+            ExprValue::String(ptr) => ptr,
+            _ => bail!("panic expects a String argument"),
         };
+        let func = self.ensure_panic_fn();
+        self.call_function(func, &[message_ptr.into()], "tea_panic")?;
+        Ok(ExprValue::Void)
+    }
 
-        if call.arguments.len() != variant_def.fields.len() {
-            bail!(format!(
-                "error variant '{}.{}' expects {} argument(s) but {} provided",
-                error_name,
-                variant_name,
-                variant_def.fields.len(),
-                call.arguments.len()
-            ));
+    fn compile_exit_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 1 {
+            bail!("exit expects exactly 1 argument");
         }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for exit");
+        }
+        let code_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let code = match code_expr {
+            ExprValue::Int(val) => val,
+            _ => bail!("exit expects an Int argument"),
+        };
+        let func = self.ensure_exit_fn();
+        self.call_function(func, &[code.into()], "tea_exit")?;
+        Ok(ExprValue::Void)
+    }
 
-        self.ensure_error_variant_metadata(error_name, &variant_name)?;
-        let field_types = self
-            .errors
-            .get(error_name)
-            .and_then(|variants| variants.get(&variant_name))
-            .map(|entry| entry.field_types.clone())
-            .ok_or_else(|| {
-                anyhow!(
-                    "missing field metadata for error '{}.{}'",
-                    error_name,
-                    variant_name
-                )
-            })?;
-        let template_ptr = self.ensure_error_template(error_name, &variant_name)?;
+    fn compile_args_call(&mut self) -> Result<ExprValue<'ctx>> {
+        let func = self.ensure_cli_args_fn();
+        let pointer = self
+            .call_function(func, &[], "tea_cli_args")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_cli_args returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::List {
+            pointer,
+            element_type: Box::new(ValueType::String),
+        })
+    }
 
-        let alloc_fn = self.ensure_error_alloc();
-        let call_site = self.call_function(alloc_fn, &[template_ptr.into()], "error_alloc")?;
-        let error_ptr = call_site
+    fn compile_args_program_call(&mut self) -> Result<ExprValue<'ctx>> {
+        let func = self.ensure_args_program_fn();
+        let pointer = self
+            .call_function(func, &[], "tea_args_program")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("expected error pointer"))?
+            .ok_or_else(|| anyhow!("tea_args_program returned no value"))?
             .into_pointer_value();
+        Ok(ExprValue::String(pointer))
+    }
 
-        let set_fn = self.ensure_error_set();
-        for (index, argument) in call.arguments.iter().enumerate() {
-            if argument.name.is_some() {
-                bail!("named arguments are not supported for error constructors");
+    // Regex functions
+    fn compile_regex_compile_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 1 {
+            bail!("regex.compile expects exactly 1 argument");
+        }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for regex.compile");
+        }
+        let pattern_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let pattern_ptr = self.expect_string_pointer(
+            pattern_expr,
+            "regex.compile expects the pattern to be a String",
+        )?;
+        let func = self.ensure_regex_compile_fn();
+        let handle = self
+            .call_function(func, &[pattern_ptr.into()], "tea_regex_compile")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_regex_compile returned no value"))?
+            .into_int_value();
+        Ok(ExprValue::Int(handle))
+    }
+
+    fn compile_regex_is_match_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 2 {
+            bail!("regex.is_match expects exactly 2 arguments");
+        }
+        for arg in arguments {
+            if arg.name.is_some() {
+                bail!("named arguments are not supported for regex.is_match");
             }
-            let value = self.compile_expression(&argument.expression, function, locals)?;
-            let expected = field_types.get(index).ok_or_else(|| {
-                anyhow!(
-                    "missing field metadata for error '{}.{}'",
-                    error_name,
-                    variant_name
-                )
-            })?;
-            let converted = self
-                .convert_expr_to_type(value, expected)
-                .map_err(|error| {
-                    anyhow!(
-                        "argument {} for error '{}.{}' has mismatched type: {}",
-                        index + 1,
-                        error_name,
-                        variant_name,
-                        error
-                    )
-                })?;
-            // Pass TeaValue by pointer to avoid ARM64 ABI issues
-            let tea_value = self.expr_to_tea_value(converted)?.into_struct_value();
-            let tea_value_type = self
-                .context
-                .get_struct_type("TeaValue")
-                .ok_or_else(|| anyhow!("TeaValue type not found"))?;
-            let tea_value_alloca = map_builder_error(
-                self.builder
-                    .build_alloca(tea_value_type, "error_field_value"),
-            )?;
-            map_builder_error(self.builder.build_store(tea_value_alloca, tea_value))?;
-            self.call_function(
-                set_fn,
-                &[
-                    error_ptr.into(),
-                    self.int_type().const_int(index as u64, false).into(),
-                    tea_value_alloca.into(),
-                ],
-                "error_set",
-            )?;
         }
+        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let handle_value = self.expect_int_value(
+            handle_expr,
+            "regex.is_match expects the handle to be an Int",
+        )?;
+        let text_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let text_ptr = self
+            .expect_string_pointer(text_expr, "regex.is_match expects the text to be a String")?;
+        let func = self.ensure_regex_is_match_fn();
+        let raw = self
+            .call_function(
+                func,
+                &[handle_value.into(), text_ptr.into()],
+                "tea_regex_is_match",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_regex_is_match returned no value"))?
+            .into_int_value();
+        let bool_value = self.i32_to_bool(raw, "regex_is_match_result")?;
+        Ok(ExprValue::Bool(bool_value))
+    }
 
-        Ok(Some(ExprValue::Error {
-            pointer: error_ptr,
-            error_name: error_name.to_string(),
-            variant_name: Some(variant_name),
-        }))
+    fn compile_regex_find_all_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 2 {
+            bail!("regex.find_all expects exactly 2 arguments");
+        }
+        for arg in arguments {
+            if arg.name.is_some() {
+                bail!("named arguments are not supported for regex.find_all");
+            }
+        }
+        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let handle_value = self.expect_int_value(
+            handle_expr,
+            "regex.find_all expects the handle to be an Int",
+        )?;
+        let text_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let text_ptr = self
+            .expect_string_pointer(text_expr, "regex.find_all expects the text to be a String")?;
+        let func = self.ensure_regex_find_all_fn();
+        let pointer = self
+            .call_function(
+                func,
+                &[handle_value.into(), text_ptr.into()],
+                "tea_regex_find_all",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_regex_find_all returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::List {
+            pointer,
+            element_type: Box::new(ValueType::String),
+        })
     }
 
-    fn compile_print_call(
+    fn compile_regex_captures_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        for argument in arguments {
-            if argument.name.is_some() {
-                bail!("named arguments are not supported by the LLVM backend yet");
-            }
-            let value = self.compile_expression(&argument.expression, function, locals)?;
-            match value {
-                ExprValue::Int(v) => {
-                    let func = self.ensure_print_int();
-                    self.call_function(func, &[v.into()], "print_int")?;
-                }
-                ExprValue::Float(v) => {
-                    let func = self.ensure_print_float();
-                    self.call_function(func, &[v.into()], "print_float")?;
-                }
-                ExprValue::Bool(v) => {
-                    let cast = self.bool_to_i32(v, "print_bool")?;
-                    let func = self.ensure_print_bool();
-                    self.call_function(func, &[cast.into()], "print_bool")?;
-                }
-                ExprValue::String(ptr) => {
-                    let func = self.ensure_print_string();
-                    self.call_function(func, &[ptr.into()], "print_string")?;
-                }
-                ExprValue::List { pointer, .. } => {
-                    let func = self.ensure_print_list();
-                    self.call_function(func, &[pointer.into()], "print_list")?;
-                }
-                ExprValue::Dict { pointer, .. } => {
-                    let func = self.ensure_print_dict();
-                    self.call_function(func, &[pointer.into()], "print_dict")?;
-                }
-                ExprValue::Struct { pointer, .. } => {
-                    let func = self.ensure_print_struct();
-                    self.call_function(func, &[pointer.into()], "print_struct")?;
-                }
-                ExprValue::Error { pointer, .. } => {
-                    let func = self.ensure_print_error();
-                    self.call_function(func, &[pointer.into()], "print_error")?;
-                }
-                ExprValue::Closure { pointer, .. } => {
-                    let func = self.ensure_print_closure();
-                    self.call_function(func, &[pointer.into()], "print_closure")?;
-                }
-                ExprValue::Optional { value, .. } => {
-                    let tea_value_type = self
-                        .context
-                        .get_struct_type("TeaValue")
-                        .ok_or_else(|| anyhow!("TeaValue type not found"))?;
-                    // Pass by pointer for ARM64 ABI compatibility
-                    let alloca = map_builder_error(
-                        self.builder.build_alloca(tea_value_type, "opt_tea_val_tmp"),
-                    )?;
-                    map_builder_error(self.builder.build_store(alloca, value))?;
-                    let to_string = self.ensure_util_to_string_fn();
-                    let string_ptr = self
-                        .call_function(to_string, &[alloca.into()], "optional_to_string")?
-                        .try_as_basic_value()
-                        .left()
-                        .ok_or_else(|| anyhow!("tea_util_to_string returned no value"))?
-                        .into_pointer_value();
-                    let func = self.ensure_print_string();
-                    self.call_function(func, &[string_ptr.into()], "print_optional")?;
-                }
-                ExprValue::Void => {
-                    let nil_string = self.compile_string_literal("nil")?;
-                    if let ExprValue::String(ptr) = nil_string {
-                        let func = self.ensure_print_string();
-                        self.call_function(func, &[ptr.into()], "print_nil")?;
-                    } else {
-                        bail!("expected string literal for nil printing");
-                    }
-                }
-                ExprValue::Any { value } => {
-                    // Any holds a TeaValue struct - convert to string and print
-                    let tea_value_type = self
-                        .context
-                        .get_struct_type("TeaValue")
-                        .ok_or_else(|| anyhow!("TeaValue type not found"))?;
-                    let alloca = map_builder_error(
-                        self.builder.build_alloca(tea_value_type, "any_tea_val_tmp"),
-                    )?;
-                    map_builder_error(self.builder.build_store(alloca, value))?;
-                    let to_string = self.ensure_util_to_string_fn();
-                    let string_ptr = self
-                        .call_function(to_string, &[alloca.into()], "any_to_string")?
-                        .try_as_basic_value()
-                        .left()
-                        .ok_or_else(|| anyhow!("tea_util_to_string returned no value"))?
-                        .into_pointer_value();
-                    let func = self.ensure_print_string();
-                    self.call_function(func, &[string_ptr.into()], "print_any")?;
-                }
+        if arguments.len() != 2 {
+            bail!("regex.captures expects exactly 2 arguments");
+        }
+        for arg in arguments {
+            if arg.name.is_some() {
+                bail!("named arguments are not supported for regex.captures");
             }
         }
-        Ok(ExprValue::Void)
+        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let handle_value = self.expect_int_value(
+            handle_expr,
+            "regex.captures expects the handle to be an Int",
+        )?;
+        let text_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let text_ptr = self
+            .expect_string_pointer(text_expr, "regex.captures expects the text to be a String")?;
+        let func = self.ensure_regex_captures_fn();
+        let pointer = self
+            .call_function(
+                func,
+                &[handle_value.into(), text_ptr.into()],
+                "tea_regex_captures",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_regex_captures returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::List {
+            pointer,
+            element_type: Box::new(ValueType::String),
+        })
     }
 
-    fn compile_println_call(
+    fn compile_regex_replace_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        // Same as print but calls println functions instead
-        for argument in arguments {
-            if argument.name.is_some() {
-                bail!("named arguments are not supported by the LLVM backend yet");
+        if arguments.len() != 3 {
+            bail!("regex.replace expects exactly 3 arguments");
+        }
+        for arg in arguments {
+            if arg.name.is_some() {
+                bail!("named arguments are not supported for regex.replace");
             }
-            let value = self.compile_expression(&argument.expression, function, locals)?;
-            match value {
-                ExprValue::Int(v) => {
-                    let func = self.ensure_println_int();
-                    self.call_function(func, &[v.into()], "println_int")?;
-                }
-                ExprValue::Float(v) => {
-                    let func = self.ensure_println_float();
-                    self.call_function(func, &[v.into()], "println_float")?;
-                }
-                ExprValue::Bool(v) => {
-                    let cast = self.bool_to_i32(v, "println_bool")?;
-                    let func = self.ensure_println_bool();
-                    self.call_function(func, &[cast.into()], "println_bool")?;
-                }
-                ExprValue::String(ptr) => {
-                    let func = self.ensure_println_string();
-                    self.call_function(func, &[ptr.into()], "println_string")?;
-                }
-                ExprValue::List { pointer, .. } => {
-                    let func = self.ensure_println_list();
-                    self.call_function(func, &[pointer.into()], "println_list")?;
-                }
-                ExprValue::Dict { pointer, .. } => {
-                    let func = self.ensure_println_dict();
-                    self.call_function(func, &[pointer.into()], "println_dict")?;
-                }
-                ExprValue::Struct { pointer, .. } => {
-                    let func = self.ensure_println_struct();
-                    self.call_function(func, &[pointer.into()], "println_struct")?;
-                }
-                ExprValue::Error { pointer, .. } => {
-                    let func = self.ensure_println_error();
-                    self.call_function(func, &[pointer.into()], "println_error")?;
-                }
-                ExprValue::Closure { pointer, .. } => {
-                    let func = self.ensure_println_closure();
-                    self.call_function(func, &[pointer.into()], "println_closure")?;
-                }
-                ExprValue::Optional { value, .. } => {
-                    let tea_value_type = self
-                        .context
-                        .get_struct_type("TeaValue")
-                        .ok_or_else(|| anyhow!("TeaValue type not found"))?;
-                    // Pass by pointer for ARM64 ABI compatibility
-                    let alloca = map_builder_error(
-                        self.builder.build_alloca(tea_value_type, "opt_tea_val_tmp"),
-                    )?;
-                    map_builder_error(self.builder.build_store(alloca, value))?;
-                    let to_string = self.ensure_util_to_string_fn();
-                    let string_ptr = self
-                        .call_function(to_string, &[alloca.into()], "optional_to_string")?
-                        .try_as_basic_value()
-                        .left()
-                        .ok_or_else(|| anyhow!("tea_util_to_string returned no value"))?
-                        .into_pointer_value();
-                    let func = self.ensure_println_string();
-                    self.call_function(func, &[string_ptr.into()], "println_optional")?;
-                }
-                ExprValue::Void => {
-                    let nil_string = self.compile_string_literal("nil")?;
-                    if let ExprValue::String(ptr) = nil_string {
-                        let func = self.ensure_println_string();
-                        self.call_function(func, &[ptr.into()], "println_nil")?;
-                    } else {
-                        bail!("expected string literal for nil printing");
-                    }
-                }
-                ExprValue::Any { value } => {
-                    // Any holds a TeaValue struct - convert to string and println
-                    let tea_value_type = self
-                        .context
-                        .get_struct_type("TeaValue")
-                        .ok_or_else(|| anyhow!("TeaValue type not found"))?;
-                    let alloca = map_builder_error(
-                        self.builder.build_alloca(tea_value_type, "any_tea_val_tmp"),
-                    )?;
-                    map_builder_error(self.builder.build_store(alloca, value))?;
-                    let to_string = self.ensure_util_to_string_fn();
-                    let string_ptr = self
-                        .call_function(to_string, &[alloca.into()], "any_to_string")?
-                        .try_as_basic_value()
-                        .left()
-                        .ok_or_else(|| anyhow!("tea_util_to_string returned no value"))?
-                        .into_pointer_value();
-                    let func = self.ensure_println_string();
-                    self.call_function(func, &[string_ptr.into()], "println_any")?;
-                }
+        }
+        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let handle_value =
+            self.expect_int_value(handle_expr, "regex.replace expects the handle to be an Int")?;
+        let text_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let text_ptr =
+            self.expect_string_pointer(text_expr, "regex.replace expects the text to be a String")?;
+        let replacement_expr =
+            self.compile_expression(&arguments[2].expression, function, locals)?;
+        let replacement_ptr = self.expect_string_pointer(
+            replacement_expr,
+            "regex.replace expects the replacement to be a String",
+        )?;
+        let func = self.ensure_regex_replace_fn();
+        let pointer = self
+            .call_function(
+                func,
+                &[handle_value.into(), text_ptr.into(), replacement_ptr.into()],
+                "tea_regex_replace",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_regex_replace returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::String(pointer))
+    }
+
+    fn compile_regex_replace_all_call(
+        &mut self,
+        arguments: &[crate::ast::CallArgument],
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if arguments.len() != 3 {
+            bail!("regex.replace_all expects exactly 3 arguments");
+        }
+        for arg in arguments {
+            if arg.name.is_some() {
+                bail!("named arguments are not supported for regex.replace_all");
             }
         }
-        Ok(ExprValue::Void)
+        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let handle_value = self.expect_int_value(
+            handle_expr,
+            "regex.replace_all expects the handle to be an Int",
+        )?;
+        let text_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let text_ptr = self.expect_string_pointer(
+            text_expr,
+            "regex.replace_all expects the text to be a String",
+        )?;
+        let replacement_expr =
+            self.compile_expression(&arguments[2].expression, function, locals)?;
+        let replacement_ptr = self.expect_string_pointer(
+            replacement_expr,
+            "regex.replace_all expects the replacement to be a String",
+        )?;
+        let func = self.ensure_regex_replace_all_fn();
+        let pointer = self
+            .call_function(
+                func,
+                &[handle_value.into(), text_ptr.into(), replacement_ptr.into()],
+                "tea_regex_replace_all",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_regex_replace_all returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::String(pointer))
     }
 
-    fn compile_to_string_call(
+    fn compile_regex_split_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        // Same as UtilToString
-        self.compile_util_to_string_call(arguments, function, locals)
+        if arguments.len() != 2 {
+            bail!("regex.split expects exactly 2 arguments");
+        }
+        for arg in arguments {
+            if arg.name.is_some() {
+                bail!("named arguments are not supported for regex.split");
+            }
+        }
+        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let handle_value =
+            self.expect_int_value(handle_expr, "regex.split expects the handle to be an Int")?;
+        let text_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let text_ptr =
+            self.expect_string_pointer(text_expr, "regex.split expects the text to be a String")?;
+        let func = self.ensure_regex_split_fn();
+        let pointer = self
+            .call_function(
+                func,
+                &[handle_value.into(), text_ptr.into()],
+                "tea_regex_split",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_regex_split returned no value"))?
+            .into_pointer_value();
+        Ok(ExprValue::List {
+            pointer,
+            element_type: Box::new(ValueType::String),
+        })
     }
 
-    fn compile_type_of_call(
+    /// Lower a JSON, YAML or TOML encode intrinsic to its runtime `symbol`.
+    fn compile_document_encode_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        name: &str,
+        symbol: &str,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("type_of expects exactly 1 argument");
+            bail!("{name} expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for type_of");
+            bail!("named arguments are not supported for {name}");
         }
-        let value_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let tea_value = self.expr_to_tea_value(value_expr)?;
-        let func = self.ensure_type_of_fn();
-        let pointer = self
-            .call_function(func, &[tea_value.into()], "tea_type_of")?
+        let value = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let tea_value = self.expr_to_tea_value(value)?.into_struct_value();
+
+        // ARM64 ABI fix: Pass TeaValue by pointer instead of by value
+        let tea_value_type = self
+            .context
+            .get_struct_type("TeaValue")
+            .ok_or_else(|| anyhow!("TeaValue type not found"))?;
+        let alloca = map_builder_error(
+            self.builder
+                .build_alloca(tea_value_type, "document_tea_value_tmp"),
+        )?;
+        map_builder_error(self.builder.build_store(alloca, tea_value))?;
+
+        let func = self.ensure_document_encode_fn(symbol);
+        let result = self
+            .call_function(func, &[alloca.into()], symbol)?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_type_of returned no value"))?
+            .ok_or_else(|| anyhow!("{symbol} returned no value"))?
             .into_pointer_value();
-        Ok(ExprValue::String(pointer))
+        Ok(ExprValue::String(result))
     }
 
-    fn compile_panic_call(
+    /// Lower a JSON, YAML or TOML decode intrinsic to its runtime `symbol`.
+    fn compile_document_decode_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        name: &str,
+        symbol: &str,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("panic expects exactly 1 argument");
+            bail!("{name} expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for panic");
+            bail!("named arguments are not supported for {name}");
         }
-        let message_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let message_ptr = match message_expr {
-            ExprValue::String(ptr) => ptr,
-            _ => bail!("panic expects a String argument"),
-        };
-        let func = self.ensure_panic_fn();
-        self.call_function(func, &[message_ptr.into()], "tea_panic")?;
-        Ok(ExprValue::Void)
+        let value = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let string_ptr =
+            self.expect_string_pointer(value, &format!("{name} expects a String argument"))?;
+
+        // Allocate space for the result on the stack
+        let tea_value_type = self
+            .context
+            .get_struct_type("TeaValue")
+            .ok_or_else(|| anyhow!("TeaValue type not found"))?;
+        let result_alloca = map_builder_error(
+            self.builder
+                .build_alloca(tea_value_type, "document_decode_result"),
+        )?;
+
+        // Call with out-pointer as first argument
+        let func = self.ensure_document_decode_fn(symbol);
+        self.call_function(func, &[result_alloca.into(), string_ptr.into()], symbol)?;
+
+        // Load the result
+        let tea_value = map_builder_error(self.builder.build_load(
+            tea_value_type,
+            result_alloca,
+            "loaded",
+        ))?
+        .into_struct_value();
+
+        // Return the TeaValue as an Any type - the actual type is determined at runtime
+        Ok(ExprValue::Any { value: tea_value })
     }
 
-    fn compile_exit_call(
+    /// Lower `json_encode_with(value, rename, omit_nil)`.
+    fn compile_json_encode_with_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 1 {
-            bail!("exit expects exactly 1 argument");
+        if arguments.len() != 3 {
+            bail!("json_encode_with expects exactly 3 arguments");
         }
-        if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for exit");
+        for arg in arguments {
+            if arg.name.is_some() {
+                bail!("named arguments are not supported for json_encode_with");
+            }
         }
-        let code_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let code = match code_expr {
-            ExprValue::Int(val) => val,
-            _ => bail!("exit expects an Int argument"),
+        let value = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let tea_value = self.expr_to_tea_value(value)?.into_struct_value();
+        let rename_ptr = self.compile_json_rename_argument(&arguments[1], function, locals)?;
+        let omit_expr = self.compile_expression(&arguments[2].expression, function, locals)?;
+        let omit_nil = match omit_expr {
+            ExprValue::Bool(value) => self.bool_to_i32(value, "omit_nil")?,
+            _ => bail!("json_encode_with expects omit_nil to be a Bool"),
         };
-        let func = self.ensure_exit_fn();
-        self.call_function(func, &[code.into()], "tea_exit")?;
-        Ok(ExprValue::Void)
-    }
 
-    fn compile_args_call(&mut self) -> Result<ExprValue<'ctx>> {
-        let func = self.ensure_cli_args_fn();
-        let pointer = self
-            .call_function(func, &[], "tea_cli_args")?
+        // ARM64 ABI fix: Pass TeaValue by pointer instead of by value
+        let tea_value_type = self
+            .context
+            .get_struct_type("TeaValue")
+            .ok_or_else(|| anyhow!("TeaValue type not found"))?;
+        let alloca = map_builder_error(
+            self.builder
+                .build_alloca(tea_value_type, "json_tea_value_tmp"),
+        )?;
+        map_builder_error(self.builder.build_store(alloca, tea_value))?;
+
+        let func = self.ensure_json_encode_with_fn();
+        let result = self
+            .call_function(
+                func,
+                &[alloca.into(), rename_ptr.into(), omit_nil.into()],
+                "tea_json_encode_with",
+            )?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_cli_args returned no value"))?
+            .ok_or_else(|| anyhow!("tea_json_encode_with returned no value"))?
             .into_pointer_value();
-        Ok(ExprValue::List {
-            pointer,
-            element_type: Box::new(ValueType::String),
-        })
+        Ok(ExprValue::String(result))
     }
 
-    fn compile_args_program_call(&mut self) -> Result<ExprValue<'ctx>> {
-        let func = self.ensure_args_program_fn();
-        let pointer = self
-            .call_function(func, &[], "tea_args_program")?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_args_program returned no value"))?
-            .into_pointer_value();
-        Ok(ExprValue::String(pointer))
+    /// Lower `json_decode_as[T](text, rename)`, validating the document against `T`.
+    fn compile_json_decode_as_call(
+        &mut self,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        let (target, text_ptr, schema_ptr, rename_ptr) =
+            self.compile_json_typed_decode_arguments(call, function, locals, "json_decode_as")?;
+
+        // Allocate space for the result on the stack
+        let tea_value_type = self
+            .context
+            .get_struct_type("TeaValue")
+            .ok_or_else(|| anyhow!("TeaValue type not found"))?;
+        let result_alloca = map_builder_error(
+            self.builder
+                .build_alloca(tea_value_type, "json_decode_result"),
+        )?;
+
+        let func = self.ensure_json_decode_typed_fn();
+        self.call_function(
+            func,
+            &[
+                result_alloca.into(),
+                text_ptr.into(),
+                schema_ptr.into(),
+                rename_ptr.into(),
+            ],
+            "tea_json_decode_typed",
+        )?;
+
+        let tea_value = map_builder_error(self.builder.build_load(
+            tea_value_type,
+            result_alloca,
+            "loaded",
+        ))?
+        .into_struct_value();
+        self.tea_value_to_expr(tea_value, target)
+    }
+
+    /// Lower `csv_decode_record[T](record, rename, row)`, parsing each column of
+    /// the record into the matching field of struct `T`.
+    fn compile_csv_decode_record_call(
+        &mut self,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<ExprValue<'ctx>> {
+        if call.type_arguments.len() != 1 {
+            bail!("csv_decode_record expects exactly 1 type argument");
+        }
+        if call.arguments.len() != 3 {
+            bail!("csv_decode_record expects exactly 3 arguments");
+        }
+        for arg in &call.arguments {
+            if arg.name.is_some() {
+                bail!("named arguments are not supported for csv_decode_record");
+            }
+        }
+        let target = self.parse_type(&call.type_arguments[0])?;
+        if !matches!(target, ValueType::Struct(_)) {
+            bail!("csv.decode needs a struct type to decode records into, found {target:?}");
+        }
+        let schema = self.json_decode_schema(&target)?;
+        let schema_ptr = self.create_c_string_constant(&schema);
+
+        let record = self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let record_ptr = match record {
+            ExprValue::Dict { pointer, .. } => pointer,
+            _ => bail!("csv_decode_record expects a Dict[String, String] record"),
+        };
+        let rename_ptr = self.compile_json_rename_argument(&call.arguments[1], function, locals)?;
+        let row = self.compile_expression(&call.arguments[2].expression, function, locals)?;
+        let row = self.expect_int_value(row, "csv_decode_record expects an Int row number")?;
+
+        let tea_value_type = self
+            .context
+            .get_struct_type("TeaValue")
+            .ok_or_else(|| anyhow!("TeaValue type not found"))?;
+        let result_alloca = map_builder_error(
+            self.builder
+                .build_alloca(tea_value_type, "csv_decode_result"),
+        )?;
+
+        let func = self.ensure_csv_decode_record_fn();
+        self.call_function(
+            func,
+            &[
+                result_alloca.into(),
+                record_ptr.into(),
+                schema_ptr.into(),
+                rename_ptr.into(),
+                row.into(),
+            ],
+            "tea_csv_decode_record",
+        )?;
+
+        let tea_value = map_builder_error(self.builder.build_load(
+            tea_value_type,
+            result_alloca,
+            "loaded",
+        ))?
+        .into_struct_value();
+        self.tea_value_to_expr(tea_value, target)
+    }
+
+    /// Compile the text and rename arguments of a typed JSON decode and describe
+    /// its type argument as a schema constant.
+    #[allow(clippy::type_complexity)]
+    fn compile_json_typed_decode_arguments(
+        &mut self,
+        call: &CallExpression,
+        function: FunctionValue<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+        name: &str,
+    ) -> Result<(
+        ValueType,
+        PointerValue<'ctx>,
+        PointerValue<'ctx>,
+        PointerValue<'ctx>,
+    )> {
+        if call.type_arguments.len() != 1 {
+            bail!("{name} expects exactly 1 type argument");
+        }
+        if call.arguments.len() != 2 {
+            bail!("{name} expects exactly 2 arguments");
+        }
+        for arg in &call.arguments {
+            if arg.name.is_some() {
+                bail!("named arguments are not supported for {name}");
+            }
+        }
+        let target = self.parse_type(&call.type_arguments[0])?;
+        let schema = self.json_decode_schema(&target)?;
+        let schema_ptr = self.create_c_string_constant(&schema);
+
+        let text = self.compile_expression(&call.arguments[0].expression, function, locals)?;
+        let text_ptr =
+            self.expect_string_pointer(text, &format!("{name} expects a String argument"))?;
+        let rename_ptr = self.compile_json_rename_argument(&call.arguments[1], function, locals)?;
+        Ok((target, text_ptr, schema_ptr, rename_ptr))
     }
 
-    // Regex functions
-    fn compile_regex_compile_call(
+    fn compile_json_rename_argument(
         &mut self,
-        arguments: &[crate::ast::CallArgument],
+        argument: &crate::ast::CallArgument,
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
-    ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 1 {
-            bail!("regex.compile expects exactly 1 argument");
-        }
-        if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for regex.compile");
+    ) -> Result<PointerValue<'ctx>> {
+        let rename = self.compile_expression(&argument.expression, function, locals)?;
+        let rename =
+            self.convert_expr_to_type(rename, &ValueType::Dict(Box::new(ValueType::String)))?;
+        match rename {
+            ExprValue::Dict { pointer, .. } => Ok(pointer),
+            _ => bail!("JSON rename must be a Dict[String, String]"),
         }
-        let pattern_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let pattern_ptr = self.expect_string_pointer(
-            pattern_expr,
-            "regex.compile expects the pattern to be a String",
-        )?;
-        let func = self.ensure_regex_compile_fn();
-        let handle = self
-            .call_function(func, &[pattern_ptr.into()], "tea_regex_compile")?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_regex_compile returned no value"))?
-            .into_int_value();
-        Ok(ExprValue::Int(handle))
     }
 
-    fn compile_regex_is_match_call(
+    /// Describe `target` for the runtime decoder as
+    /// `{"type": shape, "structs": {"Name": [[field, shape], ...]}}`, where an enum
+    /// shape lists the variant names it accepts.
+    fn json_decode_schema(&mut self, target: &ValueType) -> Result<String> {
+        let mut structs = serde_json::Map::new();
+        let shape = self.json_schema_shape(target, &mut structs)?;
+        Ok(serde_json::json!({ "type": shape, "structs": structs }).to_string())
+    }
+
+    fn json_schema_shape(
+        &mut self,
+        ty: &ValueType,
+        structs: &mut serde_json::Map<String, serde_json::Value>,
+    ) -> Result<serde_json::Value> {
+        let shape = match ty {
+            ValueType::Int => serde_json::json!("Int"),
+            ValueType::Float => serde_json::json!("Float"),
+            ValueType::Bool => serde_json::json!("Bool"),
+            ValueType::String => serde_json::json!("String"),
+            ValueType::Bytes => serde_json::json!("Bytes"),
+            ValueType::Any => serde_json::json!("Any"),
+            ValueType::Enum(name) => {
+                let definition = self
+                    .enum_definitions_tc
+                    .get(name)
+                    .ok_or_else(|| anyhow!(format!("unknown enum '{name}'")))?;
+                let variants: Vec<&str> = definition
+                    .variants
+                    .iter()
+                    .map(|variant| variant.name.as_str())
+                    .collect();
+                serde_json::json!({ "Enum": { "name": name, "variants": variants } })
+            }
+            ValueType::List(inner) => {
+                serde_json::json!({ "List": self.json_schema_shape(inner, structs)? })
+            }
+            ValueType::Dict(inner) => {
+                serde_json::json!({ "Dict": self.json_schema_shape(inner, structs)? })
+            }
+            ValueType::Optional(inner) => {
+                serde_json::json!({ "Optional": self.json_schema_shape(inner, structs)? })
+            }
+            ValueType::Struct(name) => {
+                if !structs.contains_key(name) {
+                    // Reserve the entry first so recursive structs terminate.
+                    structs.insert(name.clone(), serde_json::Value::Null);
+                    self.ensure_struct_template(name)?;
+                    let lowering = self
+                        .structs
+                        .get(name)
+                        .ok_or_else(|| anyhow!(format!("unknown struct '{name}'")))?;
+                    let fields: Vec<(String, ValueType)> = lowering
+                        .field_names
+                        .iter()
+                        .cloned()
+                        .zip(lowering.field_types.iter().cloned())
+                        .collect();
+                    let mut described = Vec::with_capacity(fields.len());
+                    for (field, field_type) in &fields {
+                        described.push(serde_json::json!([
+                            field,
+                            self.json_schema_shape(field_type, structs)?
+                        ]));
+                    }
+                    structs.insert(name.clone(), serde_json::Value::Array(described));
+                }
+                serde_json::json!({ "Struct": name })
+            }
+            ValueType::Iterator(_)
+            | ValueType::Function(_, _)
+            | ValueType::Error { .. }
+            | ValueType::Void => {
+                bail!("json.decode cannot decode into {ty:?}; use a struct, collection or primitive type")
+            }
+        };
+        Ok(shape)
+    }
+
+    fn compile_http_send_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 2 {
-            bail!("regex.is_match expects exactly 2 arguments");
+        if arguments.len() != 5 {
+            bail!("http.send expects exactly 5 arguments");
         }
-        for arg in arguments {
-            if arg.name.is_some() {
-                bail!("named arguments are not supported for regex.is_match");
+        for argument in arguments {
+            if argument.name.is_some() {
+                bail!("named arguments are not supported for http.send");
             }
         }
-        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let handle_value = self.expect_int_value(
-            handle_expr,
-            "regex.is_match expects the handle to be an Int",
+
+        let method_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let method_ptr = self.expect_string_pointer(
+            method_expr,
+            "http.send expects the method argument to be a String",
         )?;
-        let text_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let text_ptr = self
-            .expect_string_pointer(text_expr, "regex.is_match expects the text to be a String")?;
-        let func = self.ensure_regex_is_match_fn();
-        let raw = self
+        let url_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let url_ptr = self.expect_string_pointer(
+            url_expr,
+            "http.send expects the URL argument to be a String",
+        )?;
+        let headers_expr = self.compile_expression(&arguments[2].expression, function, locals)?;
+        let headers_value =
+            self.convert_expr_to_type(headers_expr, &ValueType::Dict(Box::new(ValueType::String)))?;
+        let headers_ptr = match headers_value {
+            ExprValue::Dict { pointer, .. } => pointer,
+            _ => bail!("http.send expects the headers argument to be a Dict[String, String]"),
+        };
+        let body_expr = self.compile_expression(&arguments[3].expression, function, locals)?;
+        let body_ptr = self.expect_string_pointer(
+            body_expr,
+            "http.send expects the body argument to be a String",
+        )?;
+        let timeout_expr = self.compile_expression(&arguments[4].expression, function, locals)?;
+        let timeout_value = self.expect_int_value(
+            timeout_expr,
+            "http.send expects the timeout argument to be an Int",
+        )?;
+
+        let func = self.ensure_http_send_fn();
+        let value = self
             .call_function(
                 func,
-                &[handle_value.into(), text_ptr.into()],
-                "tea_regex_is_match",
+                &[
+                    method_ptr.into(),
+                    url_ptr.into(),
+                    headers_ptr.into(),
+                    body_ptr.into(),
+                    timeout_value.into(),
+                ],
+                "tea_http_send",
             )?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_regex_is_match returned no value"))?
-            .into_int_value();
-        let bool_value = self.i32_to_bool(raw, "regex_is_match_result")?;
-        Ok(ExprValue::Bool(bool_value))
+            .ok_or_else(|| anyhow!("tea_http_send returned no value"))?
+            .into_struct_value();
+        self.tea_value_to_expr(value, ValueType::Dict(Box::new(ValueType::Any)))
     }
 
-    fn compile_regex_find_all_call(
+    fn compile_task_spawn_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 2 {
-            bail!("regex.find_all expects exactly 2 arguments");
+        if arguments.len() != 1 {
+            bail!("task_spawn expects exactly 1 argument");
         }
-        for arg in arguments {
-            if arg.name.is_some() {
-                bail!("named arguments are not supported for regex.find_all");
-            }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for task_spawn");
         }
-        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let handle_value = self.expect_int_value(
-            handle_expr,
-            "regex.find_all expects the handle to be an Int",
-        )?;
-        let text_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let text_ptr = self
-            .expect_string_pointer(text_expr, "regex.find_all expects the text to be a String")?;
-        let func = self.ensure_regex_find_all_fn();
-        let pointer = self
-            .call_function(
-                func,
-                &[handle_value.into(), text_ptr.into()],
-                "tea_regex_find_all",
-            )?
+        let work = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let ExprValue::Closure {
+            pointer,
+            param_types,
+            return_type,
+        } = work
+        else {
+            bail!("task_spawn expects a function argument");
+        };
+        if !param_types.is_empty() {
+            bail!("task_spawn expects a function without parameters");
+        }
+
+        let entry = self.ensure_task_entry_fn(&return_type)?;
+        let entry_ptr = entry.as_global_value().as_pointer_value();
+        let func = self.ensure_task_spawn_fn();
+        let handle = self
+            .call_function(func, &[pointer.into(), entry_ptr.into()], "tea_task_spawn")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_regex_find_all returned no value"))?
+            .ok_or_else(|| anyhow!("tea_task_spawn returned no value"))?
+            .into_int_value();
+        Ok(ExprValue::Int(handle))
+    }
+
+    /// Build the thread entry point for closures returning `return_type`.
+    ///
+    /// The runtime calls it on the new thread with the closure and an out-pointer,
+    /// and it stores the closure's result there as a TeaValue.
+    fn ensure_task_entry_fn(&mut self, return_type: &ValueType) -> Result<FunctionValue<'ctx>> {
+        if let Some((_, func)) = self
+            .task_entry_fns
+            .iter()
+            .find(|(existing, _)| existing == return_type)
+        {
+            return Ok(*func);
+        }
+
+        let fn_type = self.context.void_type().fn_type(
+            &[self.closure_ptr_type().into(), self.ptr_type.into()],
+            false,
+        );
+        let name = format!("tea_task_entry_{}", self.task_entry_fns.len());
+        let entry_fn = self
+            .module
+            .add_function(&name, fn_type, Some(Linkage::Private));
+        self.task_entry_fns.push((return_type.clone(), entry_fn));
+
+        let saved_block = self.builder.get_insert_block();
+        let entry_block = self.context.append_basic_block(entry_fn, "entry");
+        self.builder.position_at_end(entry_block);
+
+        let closure_ptr = entry_fn
+            .get_nth_param(0)
+            .ok_or_else(|| anyhow!("task entry is missing its closure parameter"))?
             .into_pointer_value();
-        Ok(ExprValue::List {
-            pointer,
-            element_type: Box::new(ValueType::String),
-        })
+        let out_ptr = entry_fn
+            .get_nth_param(1)
+            .ok_or_else(|| anyhow!("task entry is missing its result parameter"))?
+            .into_pointer_value();
+
+        // Errors raised by the task stay in its thread; the result is whatever it returned.
+        self.push_error_mode(ErrorHandlingMode::Capture);
+        let result = self.call_closure_with_args(
+            closure_ptr,
+            &[],
+            &Box::new(return_type.clone()),
+            Vec::new(),
+            entry_fn,
+        );
+        self.pop_error_mode();
+        let tea_value = self.expr_to_tea_value(result?)?;
+        map_builder_error(self.builder.build_store(out_ptr, tea_value))?;
+        map_builder_error(self.builder.build_return(None))?;
+
+        if let Some(block) = saved_block {
+            self.builder.position_at_end(block);
+        }
+        Ok(entry_fn)
     }
 
-    fn compile_regex_captures_call(
+    fn compile_task_join_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 2 {
-            bail!("regex.captures expects exactly 2 arguments");
+        if arguments.len() != 1 {
+            bail!("task_join expects exactly 1 argument");
         }
-        for arg in arguments {
-            if arg.name.is_some() {
-                bail!("named arguments are not supported for regex.captures");
-            }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for task_join");
         }
         let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let handle_value = self.expect_int_value(
-            handle_expr,
-            "regex.captures expects the handle to be an Int",
+        let handle = self.expect_int_value(handle_expr, "task_join expects an Int handle")?;
+
+        // Return via out-pointer to avoid ARM64 ABI struct return issues
+        let result_alloca =
+            map_builder_error(self.builder.build_alloca(self.value_type(), "task_result"))?;
+        let func = self.ensure_task_join_fn();
+        self.call_function(
+            func,
+            &[result_alloca.into(), handle.into()],
+            "tea_task_join",
         )?;
-        let text_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let text_ptr = self
-            .expect_string_pointer(text_expr, "regex.captures expects the text to be a String")?;
-        let func = self.ensure_regex_captures_fn();
-        let pointer = self
-            .call_function(
-                func,
-                &[handle_value.into(), text_ptr.into()],
-                "tea_regex_captures",
-            )?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_regex_captures returned no value"))?
-            .into_pointer_value();
-        Ok(ExprValue::List {
-            pointer,
-            element_type: Box::new(ValueType::String),
-        })
+        let value = map_builder_error(self.builder.build_load(
+            self.value_type(),
+            result_alloca,
+            "task_result_value",
+        ))?
+        .into_struct_value();
+        Ok(ExprValue::Any { value })
     }
 
-    fn compile_regex_replace_call(
+    fn compile_channel_new_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 3 {
-            bail!("regex.replace expects exactly 3 arguments");
+        if arguments.len() != 1 {
+            bail!("channel_new expects exactly 1 argument");
         }
-        for arg in arguments {
-            if arg.name.is_some() {
-                bail!("named arguments are not supported for regex.replace");
-            }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for channel_new");
         }
-        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let handle_value =
-            self.expect_int_value(handle_expr, "regex.replace expects the handle to be an Int")?;
-        let text_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let text_ptr =
-            self.expect_string_pointer(text_expr, "regex.replace expects the text to be a String")?;
-        let replacement_expr =
-            self.compile_expression(&arguments[2].expression, function, locals)?;
-        let replacement_ptr = self.expect_string_pointer(
-            replacement_expr,
-            "regex.replace expects the replacement to be a String",
-        )?;
-        let func = self.ensure_regex_replace_fn();
-        let pointer = self
-            .call_function(
-                func,
-                &[handle_value.into(), text_ptr.into(), replacement_ptr.into()],
-                "tea_regex_replace",
-            )?
+        let capacity_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let capacity =
+            self.expect_int_value(capacity_expr, "channel_new expects an Int capacity")?;
+        let func = self.ensure_channel_new_fn();
+        let handle = self
+            .call_function(func, &[capacity.into()], "tea_channel_new")?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_regex_replace returned no value"))?
-            .into_pointer_value();
-        Ok(ExprValue::String(pointer))
+            .ok_or_else(|| anyhow!("tea_channel_new returned no value"))?
+            .into_int_value();
+        Ok(ExprValue::Int(handle))
     }
 
-    fn compile_regex_replace_all_call(
+    fn compile_channel_send_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 3 {
-            bail!("regex.replace_all expects exactly 3 arguments");
+        if arguments.len() != 2 {
+            bail!("channel_send expects exactly 2 arguments");
         }
-        for arg in arguments {
-            if arg.name.is_some() {
-                bail!("named arguments are not supported for regex.replace_all");
-            }
+        if arguments.iter().any(|argument| argument.name.is_some()) {
+            bail!("named arguments are not supported for channel_send");
         }
         let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let handle_value = self.expect_int_value(
-            handle_expr,
-            "regex.replace_all expects the handle to be an Int",
-        )?;
-        let text_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let text_ptr = self.expect_string_pointer(
-            text_expr,
-            "regex.replace_all expects the text to be a String",
-        )?;
-        let replacement_expr =
-            self.compile_expression(&arguments[2].expression, function, locals)?;
-        let replacement_ptr = self.expect_string_pointer(
-            replacement_expr,
-            "regex.replace_all expects the replacement to be a String",
+        let handle = self.expect_int_value(handle_expr, "channel_send expects an Int handle")?;
+        let value = self.compile_expression(&arguments[1].expression, function, locals)?;
+        let tea_value = self.expr_to_tea_value(value)?;
+
+        // ARM64 ABI fix: Pass TeaValue by pointer instead of by value
+        let value_alloca = map_builder_error(
+            self.builder
+                .build_alloca(self.value_type(), "channel_value"),
         )?;
-        let func = self.ensure_regex_replace_all_fn();
-        let pointer = self
+        map_builder_error(self.builder.build_store(value_alloca, tea_value))?;
+
+        let func = self.ensure_channel_send_fn();
+        let raw = self
             .call_function(
                 func,
-                &[handle_value.into(), text_ptr.into(), replacement_ptr.into()],
-                "tea_regex_replace_all",
+                &[handle.into(), value_alloca.into()],
+                "tea_channel_send",
             )?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_regex_replace_all returned no value"))?
-            .into_pointer_value();
-        Ok(ExprValue::String(pointer))
+            .ok_or_else(|| anyhow!("tea_channel_send returned no value"))?
+            .into_int_value();
+        let sent = self.i32_to_bool(raw, "channel_send_result")?;
+        Ok(ExprValue::Bool(sent))
     }
 
-    fn compile_regex_split_call(
+    fn compile_channel_recv_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 2 {
-            bail!("regex.split expects exactly 2 arguments");
+        if arguments.len() != 1 {
+            bail!("channel_recv expects exactly 1 argument");
         }
-        for arg in arguments {
-            if arg.name.is_some() {
-                bail!("named arguments are not supported for regex.split");
-            }
+        if arguments[0].name.is_some() {
+            bail!("named arguments are not supported for channel_recv");
         }
         let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let handle_value =
-            self.expect_int_value(handle_expr, "regex.split expects the handle to be an Int")?;
-        let text_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let text_ptr =
-            self.expect_string_pointer(text_expr, "regex.split expects the text to be a String")?;
-        let func = self.ensure_regex_split_fn();
-        let pointer = self
-            .call_function(
-                func,
-                &[handle_value.into(), text_ptr.into()],
-                "tea_regex_split",
-            )?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_regex_split returned no value"))?
-            .into_pointer_value();
-        Ok(ExprValue::List {
-            pointer,
-            element_type: Box::new(ValueType::String),
-        })
+        let handle = self.expect_int_value(handle_expr, "channel_recv expects an Int handle")?;
+
+        // A closed, drained channel leaves Nil in the out-pointer.
+        let result_alloca = map_builder_error(
+            self.builder
+                .build_alloca(self.value_type(), "channel_result"),
+        )?;
+        let func = self.ensure_channel_recv_fn();
+        self.call_function(
+            func,
+            &[result_alloca.into(), handle.into()],
+            "tea_channel_recv",
+        )?;
+        let value = map_builder_error(self.builder.build_load(
+            self.value_type(),
+            result_alloca,
+            "channel_result_value",
+        ))?
+        .into_struct_value();
+        Ok(ExprValue::Any { value })
     }
 
-    fn compile_json_encode_call(
+    fn compile_channel_close_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("json_encode expects exactly 1 argument");
+            bail!("channel_close expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for json_encode");
+            bail!("named arguments are not supported for channel_close");
         }
-        let value = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let tea_value = self.expr_to_tea_value(value)?.into_struct_value();
-
-        // ARM64 ABI fix: Pass TeaValue by pointer instead of by value
-        let tea_value_type = self
-            .context
-            .get_struct_type("TeaValue")
-            .ok_or_else(|| anyhow!("TeaValue type not found"))?;
-        let alloca = map_builder_error(
-            self.builder
-                .build_alloca(tea_value_type, "json_tea_value_tmp"),
-        )?;
-        map_builder_error(self.builder.build_store(alloca, tea_value))?;
-
-        let func = self.ensure_json_encode();
-        let result = self
-            .call_function(func, &[alloca.into()], "tea_json_encode")?
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow!("tea_json_encode returned no value"))?
-            .into_pointer_value();
-        Ok(ExprValue::String(result))
+        let handle_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
+        let handle = self.expect_int_value(handle_expr, "channel_close expects an Int handle")?;
+        let func = self.ensure_channel_close_fn();
+        self.call_function(func, &[handle.into()], "tea_channel_close")?;
+        Ok(ExprValue::Void)
     }
 
-    fn compile_json_decode_call(
+    /// Call a runtime reader that writes the next value for a handle to an
+    /// out-pointer, leaving Nil once the source is exhausted.
+    fn compile_handle_read_call(
         &mut self,
+        operation: &str,
+        func: FunctionValue<'ctx>,
         arguments: &[crate::ast::CallArgument],
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
     ) -> Result<ExprValue<'ctx>> {
         if arguments.len() != 1 {
-            bail!("json_decode expects exactly 1 argument");
+            bail!("{operation} expects exactly 1 argument");
         }
         if arguments[0].name.is_some() {
-            bail!("named arguments are not supported for json_decode");
+            bail!("named arguments are not supported for {operation}");
         }
-        let value = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let string_ptr =
-            self.expect_string_pointer(value, "json_decode expects a String argument")?;
-
-        // Allocate space for the result on the stack
-        let tea_value_type = self
-            .context
-            .get_struct_type("TeaValue")
-            .ok_or_else(|| anyhow!("TeaValue type not found"))?;
-        let result_alloca = map_builder_error(
-            self.builder
-                .build_alloca(tea_value_type, "json_decode_result"),
+        let handle = match self.compile_expression(&arguments[0].expression, function, locals)? {
+            ExprValue::Int(handle) | ExprValue::Iterator { handle, .. } => handle,
+            _ => bail!("{operation} expects a handle"),
+        };
+        // Readers are often called in loops, so the slot lives in the entry block.
+        let result_alloca = self.create_entry_alloca(
+            function,
+            &format!("{operation}_result"),
+            self.value_type().into(),
         )?;
-
-        // Call with out-pointer as first argument
-        let func = self.ensure_json_decode();
         self.call_function(
             func,
-            &[result_alloca.into(), string_ptr.into()],
-            "tea_json_decode",
+            &[result_alloca.into(), handle.into()],
+            &format!("tea_{operation}"),
         )?;
-
-        // Load the result
-        let tea_value = map_builder_error(self.builder.build_load(
-            tea_value_type,
+        let value = map_builder_error(self.builder.build_load(
+            self.value_type(),
             result_alloca,
-            "loaded",
+            &format!("{operation}_value"),
         ))?
         .into_struct_value();
-
-        // Return the TeaValue as an Any type - the actual type is determined at runtime
-        Ok(ExprValue::Any { value: tea_value })
+        Ok(ExprValue::Any { value })
     }
 
-    fn compile_http_send_call(
+    fn compile_yield(
         &mut self,
-        arguments: &[crate::ast::CallArgument],
+        statement: &YieldStatement,
         function: FunctionValue<'ctx>,
         locals: &mut HashMap<String, LocalVariable<'ctx>>,
-    ) -> Result<ExprValue<'ctx>> {
-        if arguments.len() != 5 {
-            bail!("http.send expects exactly 5 arguments");
-        }
-        for argument in arguments {
-            if argument.name.is_some() {
-                bail!("named arguments are not supported for http.send");
+    ) -> Result<()> {
+        let value = self.compile_expression(&statement.expression, function, locals)?;
+        let tea_value = self.expr_to_tea_value(value)?;
+        let value_alloca =
+            self.create_entry_alloca(function, "yield_value", self.value_type().into())?;
+        map_builder_error(self.builder.build_store(value_alloca, tea_value))?;
+        let func = self.ensure_generator_yield_fn();
+        let resumed = self
+            .call_function(func, &[value_alloca.into()], "tea_generator_yield")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_generator_yield returned no value"))?
+            .into_int_value();
+        let is_resumed = map_builder_error(self.builder.build_int_compare(
+            inkwell::IntPredicate::NE,
+            resumed,
+            self.context.i32_type().const_zero(),
+            "yield_resumed",
+        ))?;
+
+        // A closed generator returns from its body at the pending `yield`.
+        let closed_block = self.context.append_basic_block(function, "yield_closed");
+        let resume_block = self.context.append_basic_block(function, "yield_resume");
+        map_builder_error(self.builder.build_conditional_branch(
+            is_resumed,
+            resume_block,
+            closed_block,
+        ))?;
+        self.builder.position_at_end(closed_block);
+        self.close_owned_iterators()?;
+        self.clear_error_state()?;
+        map_builder_error(self.builder.build_return(None))?;
+        self.builder.position_at_end(resume_block);
+        Ok(())
+    }
+
+    /// Compile a generator function.
+    ///
+    /// The declared function only packages its arguments into a closure and hands
+    /// it to the runtime, which runs the body on its own thread the first time
+    /// the iterator is advanced. The body lives in `{fn_name}$generator` and
+    /// reads the arguments back from the closure captures.
+    fn compile_generator_function(
+        &mut self,
+        function: &FunctionStatement,
+        fn_name: &str,
+        signature: &FunctionSignature<'ctx>,
+        locals: &mut HashMap<String, LocalVariable<'ctx>>,
+    ) -> Result<()> {
+        let body_type = self
+            .context
+            .void_type()
+            .fn_type(&[self.closure_ptr_type().into()], false);
+        let body_fn = self.module.add_function(
+            &format!("{fn_name}$generator"),
+            body_type,
+            Some(Linkage::Internal),
+        );
+
+        let outer_block = self
+            .builder
+            .get_insert_block()
+            .ok_or_else(|| anyhow!("missing insertion block"))?;
+        let entry = self.context.append_basic_block(body_fn, "entry");
+        self.builder.position_at_end(entry);
+        self.push_function_return(ValueType::Void);
+        self.push_function_can_throw(false);
+        self.push_error_mode(ErrorHandlingMode::Propagate);
+
+        let closure_param = body_fn
+            .get_nth_param(0)
+            .ok_or_else(|| anyhow!("missing closure parameter"))?
+            .into_pointer_value();
+        let get_fn = self.ensure_closure_get();
+        let mut body_locals: HashMap<String, LocalVariable<'ctx>> = HashMap::new();
+        for (index, (parameter, param_type)) in function
+            .parameters
+            .iter()
+            .zip(signature.param_types.iter())
+            .enumerate()
+        {
+            let tea_value = self
+                .call_function(
+                    get_fn,
+                    &[
+                        closure_param.into(),
+                        self.int_type().const_int(index as u64, false).into(),
+                    ],
+                    &format!("get_argument_{index}"),
+                )?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| anyhow!("expected TeaValue from capture"))?
+                .into_struct_value();
+            let expr = self.tea_value_to_expr(tea_value, param_type.clone())?;
+            // Iterators passed to a generator are closed when it finishes.
+            if let ExprValue::Iterator { handle, .. } = &expr {
+                self.push_owned_iterator(*handle);
             }
+            let alloca =
+                self.create_entry_alloca(body_fn, &parameter.name, self.basic_type(param_type)?)?;
+            if let Some(basic) = expr.into_basic_value() {
+                map_builder_error(self.builder.build_store(alloca, basic))?;
+            }
+            body_locals.insert(
+                parameter.name.clone(),
+                LocalVariable {
+                    pointer: Some(alloca),
+                    value: None,
+                    ty: param_type.clone(),
+                    mutable: true,
+                    string_builder: None,
+                },
+            );
         }
 
-        let method_expr = self.compile_expression(&arguments[0].expression, function, locals)?;
-        let method_ptr = self.expect_string_pointer(
-            method_expr,
-            "http.send expects the method argument to be a String",
-        )?;
-        let url_expr = self.compile_expression(&arguments[1].expression, function, locals)?;
-        let url_ptr = self.expect_string_pointer(
-            url_expr,
-            "http.send expects the URL argument to be a String",
-        )?;
-        let headers_expr = self.compile_expression(&arguments[2].expression, function, locals)?;
-        let headers_value =
-            self.convert_expr_to_type(headers_expr, &ValueType::Dict(Box::new(ValueType::String)))?;
-        let headers_ptr = match headers_value {
-            ExprValue::Dict { pointer, .. } => pointer,
-            _ => bail!("http.send expects the headers argument to be a Dict[String, String]"),
-        };
-        let body_expr = self.compile_expression(&arguments[3].expression, function, locals)?;
-        let body_ptr = self.expect_string_pointer(
-            body_expr,
-            "http.send expects the body argument to be a String",
-        )?;
-        let timeout_expr = self.compile_expression(&arguments[4].expression, function, locals)?;
-        let timeout_value = self.expect_int_value(
-            timeout_expr,
-            "http.send expects the timeout argument to be an Int",
-        )?;
+        let body = self.compile_block(
+            &function.body.statements,
+            body_fn,
+            &mut body_locals,
+            &ValueType::Void,
+            false,
+        );
+        let finished = body.and_then(|terminated| {
+            if !terminated {
+                self.close_owned_iterators()?;
+                self.clear_error_state()?;
+                map_builder_error(self.builder.build_return(None))?;
+            }
+            Ok(())
+        });
+        self.pop_error_mode();
+        self.pop_function_return();
+        self.pop_function_can_throw();
+        finished?;
+
+        self.builder.position_at_end(outer_block);
+        let raw_ptr = map_builder_error(self.builder.build_bit_cast(
+            body_fn.as_global_value().as_pointer_value(),
+            self.ptr_type,
+            "generator_fn_ptr",
+        ))?
+        .into_pointer_value();
+        let closure_new = self.ensure_closure_new();
+        let argument_count = self
+            .int_type()
+            .const_int(function.parameters.len() as u64, false);
+        let closure_ptr = self
+            .call_function(
+                closure_new,
+                &[raw_ptr.into(), argument_count.into()],
+                "generator_closure",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("expected closure pointer"))?
+            .into_pointer_value();
+        let set_fn = self.ensure_closure_set();
+        for (index, parameter) in function.parameters.iter().enumerate() {
+            let variable = locals
+                .get(parameter.name.as_str())
+                .ok_or_else(|| anyhow!("parameter '{}' is undefined", parameter.name))?
+                .clone();
+            let value = self.load_local_variable(&parameter.name, &variable)?;
+            let tea_value = self.expr_to_tea_value(value)?;
+            let index_const = self.int_type().const_int(index as u64, false);
+            self.call_function(
+                set_fn,
+                &[
+                    closure_ptr.into(),
+                    index_const.into(),
+                    BasicMetadataValueEnum::from(tea_value),
+                ],
+                &format!("generator_argument_{index}"),
+            )?;
+        }
 
-        let func = self.ensure_http_send_fn();
-        let value = self
+        let entry_fn = self.ensure_task_entry_fn(&ValueType::Void)?;
+        let new_fn = self.ensure_generator_new_fn();
+        let handle = self
             .call_function(
-                func,
+                new_fn,
                 &[
-                    method_ptr.into(),
-                    url_ptr.into(),
-                    headers_ptr.into(),
-                    body_ptr.into(),
-                    timeout_value.into(),
+                    closure_ptr.into(),
+                    entry_fn.as_global_value().as_pointer_value().into(),
                 ],
-                "tea_http_send",
+                "tea_generator_new",
             )?
             .try_as_basic_value()
             .left()
-            .ok_or_else(|| anyhow!("tea_http_send returned no value"))?
-            .into_struct_value();
-        self.tea_value_to_expr(value, ValueType::Dict(Box::new(ValueType::Any)))
+            .ok_or_else(|| anyhow!("tea_generator_new returned no value"))?
+            .into_int_value();
+        self.clear_error_state()?;
+        map_builder_error(self.builder.build_return(Some(&handle)))?;
+        Ok(())
     }
 
     fn compile_read_line_call(&mut self) -> Result<ExprValue<'ctx>> {
@@ -13926,6 +17935,29 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         Ok(ExprValue::String(pointer))
     }
 
+    fn bytes_to_display_string(
+        &mut self,
+        pointer: PointerValue<'ctx>,
+    ) -> Result<PointerValue<'ctx>> {
+        let tea_value = self
+            .expr_to_tea_value(ExprValue::Bytes(pointer))?
+            .into_struct_value();
+        let tea_value_type = self
+            .context
+            .get_struct_type("TeaValue")
+            .ok_or_else(|| anyhow!("TeaValue type not found"))?;
+        // Pass by pointer for ARM64 ABI compatibility
+        let alloca = map_builder_error(self.builder.build_alloca(tea_value_type, "bytes_tmp"))?;
+        map_builder_error(self.builder.build_store(alloca, tea_value))?;
+        let to_string = self.ensure_util_to_string_fn();
+        Ok(self
+            .call_function(to_string, &[alloca.into()], "bytes_to_string")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("tea_util_to_string returned no value"))?
+            .into_pointer_value())
+    }
+
     fn compile_eprint_call(
         &mut self,
         arguments: &[crate::ast::CallArgument],
@@ -13955,6 +17987,11 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                     let func = self.ensure_eprint_string();
                     self.call_function(func, &[ptr.into()], "eprint_string")?;
                 }
+                ExprValue::Bytes(ptr) => {
+                    let string_ptr = self.bytes_to_display_string(ptr)?;
+                    let func = self.ensure_eprint_string();
+                    self.call_function(func, &[string_ptr.into()], "eprint_bytes")?;
+                }
                 ExprValue::List { pointer, .. } => {
                     let func = self.ensure_eprint_list();
                     self.call_function(func, &[pointer.into()], "eprint_list")?;
@@ -13978,6 +18015,9 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 ExprValue::Void | ExprValue::Optional { .. } => {
                     bail!("cannot eprint void or optional values");
                 }
+                ExprValue::Iterator { .. } => {
+                    bail!("iterators cannot be printed; collect them into a list first");
+                }
                 ExprValue::Any { value } => {
                     // Any holds a TeaValue struct - convert to string and eprint
                     let tea_value_type = self
@@ -14032,6 +18072,11 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                     let func = self.ensure_eprintln_string();
                     self.call_function(func, &[ptr.into()], "eprintln_string")?;
                 }
+                ExprValue::Bytes(ptr) => {
+                    let string_ptr = self.bytes_to_display_string(ptr)?;
+                    let func = self.ensure_eprintln_string();
+                    self.call_function(func, &[string_ptr.into()], "eprintln_bytes")?;
+                }
                 ExprValue::List { pointer, .. } => {
                     let func = self.ensure_eprintln_list();
                     self.call_function(func, &[pointer.into()], "eprintln_list")?;
@@ -14055,6 +18100,9 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 ExprValue::Void | ExprValue::Optional { .. } => {
                     bail!("cannot eprintln void or optional values");
                 }
+                ExprValue::Iterator { .. } => {
+                    bail!("iterators cannot be printed; collect them into a list first");
+                }
                 ExprValue::Any { value } => {
                     // Any holds a TeaValue struct - convert to string and eprintln
                     let tea_value_type = self
@@ -14505,6 +18553,21 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                     map_builder_error(self.builder.build_not(bool_val, "list_neq"))?
                 }
             }
+            (ExprValue::Bytes(lhs), ExprValue::Bytes(rhs)) => {
+                let func = self.ensure_bytes_equal_fn();
+                let call = self.call_function(func, &[lhs.into(), rhs.into()], "bytes_eq")?;
+                let raw = call
+                    .try_as_basic_value()
+                    .left()
+                    .ok_or_else(|| anyhow!("expected bool from tea_bytes_equal"))?
+                    .into_int_value();
+                let bool_val = self.i32_to_bool(raw, "bytes_eq_bool")?;
+                if is_equal {
+                    bool_val
+                } else {
+                    map_builder_error(self.builder.build_not(bool_val, "bytes_neq"))?
+                }
+            }
             (
                 ExprValue::Struct {
                     pointer: lhs,
@@ -14710,7 +18773,8 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
     fn expr_to_tea_value(&mut self, value: ExprValue<'ctx>) -> Result<BasicValueEnum<'ctx>> {
         // Inline construction of TeaValue to avoid ABI issues with struct returns.
         // TeaValue is { tag: i32, payload: i64 } - 16 bytes total with ABI padding.
-        // Tags: Int=0, Float=1, Bool=2, String=3, List=4, Dict=5, Struct=6, Error=7, Closure=8, Nil=9
+        // Tags: Int=0, Float=1, Bool=2, String=3, List=4, Dict=5, Struct=6, Error=7, Closure=8, Nil=9,
+        // Bytes=10
         let tea_value_type = self
             .module
             .get_struct_type("TeaValue")
@@ -14771,6 +18835,21 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                     "string",
                 )
             }
+            ExprValue::Bytes(pointer) => {
+                let payload = map_builder_error(self.builder.build_ptr_to_int(
+                    pointer,
+                    self.context.i64_type(),
+                    "ptr_i64",
+                ))?;
+                build_tea_value(
+                    self.context,
+                    &self.builder,
+                    tea_value_type,
+                    TeaValueTag::Bytes,
+                    payload,
+                    "bytes",
+                )
+            }
             ExprValue::List { pointer, .. } => {
                 let payload = map_builder_error(self.builder.build_ptr_to_int(
                     pointer,
@@ -14846,6 +18925,14 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                     "closure",
                 )
             }
+            ExprValue::Iterator { handle, .. } => build_tea_value(
+                self.context,
+                &self.builder,
+                tea_value_type,
+                TeaValueTag::Int,
+                handle,
+                "iterator",
+            ),
             ExprValue::Void => {
                 // Tag = Nil, payload = 0
                 // Use const_named_struct for compile-time constant (more efficient)
@@ -14897,7 +18984,9 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 ExprValue::List {
                     pointer,
                     element_type,
-                } if *element_type == ValueType::Void || *element_type == **target_inner => {
+                } if *element_type == ValueType::Void
+                    || same_representation(&element_type, target_inner) =>
+                {
                     Ok(ExprValue::List {
                         pointer,
                         element_type: target_inner.clone(),
@@ -14919,7 +19008,9 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 ExprValue::Dict {
                     pointer,
                     value_type,
-                } if *value_type == ValueType::Void || *value_type == **target_inner => {
+                } if *value_type == ValueType::Void
+                    || same_representation(&value_type, target_inner) =>
+                {
                     Ok(ExprValue::Dict {
                         pointer,
                         value_type: target_inner.clone(),
@@ -14939,10 +19030,14 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             },
             ValueType::Optional(inner) => match value {
                 ExprValue::Optional {
-                    inner: ref current, ..
+                    value: optional_value,
+                    inner: ref current,
                 } => {
-                    if **current == **inner {
-                        Ok(value)
+                    if same_representation(current, inner) {
+                        Ok(ExprValue::Optional {
+                            value: optional_value,
+                            inner: inner.clone(),
+                        })
                     } else {
                         bail!(
                             "optional conversion mismatch: expected {:?}, found {:?}",
@@ -15012,6 +19107,14 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                     other.ty()
                 ),
             },
+            ValueType::Enum(name) => match value {
+                ExprValue::String(pointer) => Ok(ExprValue::String(pointer)),
+                other => bail!(
+                    "expected a variant of enum '{}', found {:?}",
+                    name,
+                    other.ty()
+                ),
+            },
             _ => {
                 if value.ty() == *target {
                     Ok(value)
@@ -15047,6 +19150,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
     }
 
     fn emit_return_value(&mut self, value: ExprValue<'ctx>, ty: &ValueType) -> Result<()> {
+        self.close_owned_iterators()?;
         self.clear_error_state()?;
         let value_ty = value.ty();
         match (ty, value) {
@@ -15062,7 +19166,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 map_builder_error(self.builder.build_return(Some(&v)))?;
                 Ok(())
             }
-            (ValueType::String, ExprValue::String(ptr)) => {
+            (ValueType::String | ValueType::Enum(_), ExprValue::String(ptr)) => {
                 map_builder_error(self.builder.build_return(Some(&ptr)))?;
                 Ok(())
             }
@@ -15151,6 +19255,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
     }
 
     fn emit_error_return(&mut self, function: FunctionValue<'ctx>, ty: &ValueType) -> Result<()> {
+        self.close_owned_iterators()?;
         if let ValueType::Optional(inner) = ty {
             let nil_value = self.optional_nil(inner)?;
             if let ExprValue::Optional { value, .. } = nil_value {
@@ -15382,6 +19487,10 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 // Payload is the int value directly
                 Ok(ExprValue::Int(payload))
             }
+            ValueType::Iterator(element_type) => Ok(ExprValue::Iterator {
+                handle: payload,
+                element_type,
+            }),
             ValueType::Float => {
                 // Payload is the float value bitcast from i64
                 let float_val = map_builder_error(self.builder.build_bit_cast(
@@ -15402,7 +19511,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 ))?;
                 Ok(ExprValue::Bool(bool_val))
             }
-            ValueType::String => {
+            ValueType::String | ValueType::Enum(_) => {
                 // Payload is a pointer stored as i64
                 let ptr = map_builder_error(self.builder.build_int_to_ptr(
                     payload,
@@ -15411,6 +19520,15 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 ))?;
                 Ok(ExprValue::String(ptr))
             }
+            ValueType::Bytes => {
+                // Payload is a pointer stored as i64
+                let ptr = map_builder_error(self.builder.build_int_to_ptr(
+                    payload,
+                    self.ptr_type,
+                    "bytes_ptr",
+                ))?;
+                Ok(ExprValue::Bytes(ptr))
+            }
             ValueType::List(inner) => {
                 // Payload is a pointer stored as i64
                 let ptr = map_builder_error(self.builder.build_int_to_ptr(
@@ -15549,6 +19667,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 "Float" => Ok(ValueType::Float),
                 "Bool" => Ok(ValueType::Bool),
                 "String" => Ok(ValueType::String),
+                "Bytes" => Ok(ValueType::Bytes),
                 "Nil" => Ok(ValueType::Void),
                 "Void" => Ok(ValueType::Void),
                 "List" => {
@@ -15565,6 +19684,20 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                         _ => bail!("expected '[' after 'List'"),
                     }
                 }
+                "Iterator" => {
+                    skip_ws(chars);
+                    match chars.next() {
+                        Some('[') => {
+                            let inner = parse_inner(this, chars, structs)?;
+                            skip_ws(chars);
+                            match chars.next() {
+                                Some(']') => Ok(ValueType::Iterator(Box::new(inner))),
+                                _ => bail!("expected ']' to close iterator type"),
+                            }
+                        }
+                        _ => bail!("expected '[' after 'Iterator'"),
+                    }
+                }
                 "Dict" => {
                     skip_ws(chars);
                     match chars.next() {
@@ -15632,6 +19765,8 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                         Ok(mapped)
                     } else if structs.contains_key(other) {
                         Ok(ValueType::Struct(other.to_string()))
+                    } else if this.enum_definitions_tc.contains_key(other) {
+                        Ok(ValueType::Enum(other.to_string()))
                     } else {
                         bail!("unsupported type '{other}' in LLVM backend")
                     }
@@ -15689,10 +19824,13 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             .collect::<Result<Vec<_>>>()?;
         let fn_type = match return_type {
             ValueType::Void => self.context.void_type().fn_type(&param_types, false),
-            ValueType::Int => self.int_type().fn_type(&param_types, false),
+            ValueType::Int | ValueType::Iterator(_) => self.int_type().fn_type(&param_types, false),
             ValueType::Float => self.float_type().fn_type(&param_types, false),
             ValueType::Bool => self.bool_type().fn_type(&param_types, false),
-            ValueType::String => self.string_ptr_type().fn_type(&param_types, false),
+            ValueType::String | ValueType::Enum(_) => {
+                self.string_ptr_type().fn_type(&param_types, false)
+            }
+            ValueType::Bytes => self.bytes_ptr_type().fn_type(&param_types, false),
             ValueType::List(_) => self.list_ptr_type().fn_type(&param_types, false),
             ValueType::Dict(_) => self.dict_ptr_type().fn_type(&param_types, false),
             ValueType::Function(_, _) => self.closure_ptr_type().fn_type(&param_types, false),
@@ -15706,10 +19844,11 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
 
     fn basic_type(&self, ty: &ValueType) -> Result<BasicTypeEnum<'ctx>> {
         match ty {
-            ValueType::Int => Ok(self.int_type().into()),
+            ValueType::Int | ValueType::Iterator(_) => Ok(self.int_type().into()),
             ValueType::Float => Ok(self.float_type().into()),
             ValueType::Bool => Ok(self.bool_type().into()),
-            ValueType::String => Ok(self.string_ptr_type().into()),
+            ValueType::String | ValueType::Enum(_) => Ok(self.string_ptr_type().into()),
+            ValueType::Bytes => Ok(self.bytes_ptr_type().into()),
             ValueType::List(_) => Ok(self.list_ptr_type().into()),
             ValueType::Dict(_) => Ok(self.dict_ptr_type().into()),
             ValueType::Function(_, _) => Ok(self.closure_ptr_type().into()),
@@ -16067,6 +20206,16 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         func
     }
 
+    fn ensure_libm_fn(&mut self, symbol: &str, arity: usize) -> FunctionValue<'ctx> {
+        if let Some(func) = self.module.get_function(symbol) {
+            return func;
+        }
+        let params: Vec<BasicMetadataTypeEnum<'ctx>> = vec![self.float_type().into(); arity];
+        let fn_type = self.float_type().fn_type(&params, false);
+        self.module
+            .add_function(symbol, fn_type, Some(Linkage::External))
+    }
+
     fn ensure_fmin_fn(&mut self) -> FunctionValue<'ctx> {
         if let Some(func) = self.builtin_fmin_fn {
             return func;
@@ -16451,103 +20600,376 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
     );
     define_ffi_string2_fn!(ensure_url_join_fn, url_join_fn, "tea_url_join");
 
-    fn ensure_time_now_unix_millis_fn(&mut self) -> FunctionValue<'ctx> {
-        if let Some(func) = self.time_now_unix_millis_fn {
+    fn ensure_time_now_unix_millis_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.time_now_unix_millis_fn {
+            return func;
+        }
+        let fn_type = self.int_type().fn_type(&[], false);
+        let func =
+            self.module
+                .add_function("tea_time_now_unix_millis", fn_type, Some(Linkage::External));
+        self.time_now_unix_millis_fn = Some(func);
+        func
+    }
+
+    fn ensure_time_unix_seconds_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.time_unix_seconds_fn {
+            return func;
+        }
+        let fn_type = self.int_type().fn_type(&[self.int_type().into()], false);
+        let func =
+            self.module
+                .add_function("tea_time_unix_seconds", fn_type, Some(Linkage::External));
+        self.time_unix_seconds_fn = Some(func);
+        func
+    }
+
+    fn ensure_time_sleep_ms_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.time_sleep_ms_fn {
+            return func;
+        }
+        let fn_type = self
+            .context
+            .void_type()
+            .fn_type(&[self.int_type().into()], false);
+        let func = self
+            .module
+            .add_function("tea_time_sleep_ms", fn_type, Some(Linkage::External));
+        self.time_sleep_ms_fn = Some(func);
+        func
+    }
+
+    fn ensure_time_format_rfc3339_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.time_format_rfc3339_fn {
+            return func;
+        }
+        let fn_type = self
+            .string_ptr_type()
+            .fn_type(&[self.int_type().into()], false);
+        let func =
+            self.module
+                .add_function("tea_time_format_rfc3339", fn_type, Some(Linkage::External));
+        self.time_format_rfc3339_fn = Some(func);
+        func
+    }
+
+    fn ensure_time_parse_rfc3339_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.time_parse_rfc3339_fn {
+            return func;
+        }
+        let fn_type = self
+            .value_type()
+            .fn_type(&[self.string_ptr_type().into()], false);
+        let func =
+            self.module
+                .add_function("tea_time_parse_rfc3339", fn_type, Some(Linkage::External));
+        self.time_parse_rfc3339_fn = Some(func);
+        func
+    }
+
+    fn ensure_url_build_query_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.url_build_query_fn {
+            return func;
+        }
+        let fn_type = self
+            .string_ptr_type()
+            .fn_type(&[self.dict_ptr_type().into()], false);
+        let func =
+            self.module
+                .add_function("tea_url_build_query", fn_type, Some(Linkage::External));
+        self.url_build_query_fn = Some(func);
+        func
+    }
+
+    fn ensure_url_append_query_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.url_append_query_fn {
+            return func;
+        }
+        let param_types = [self.string_ptr_type().into(), self.dict_ptr_type().into()];
+        let fn_type = self.string_ptr_type().fn_type(&param_types, false);
+        let func =
+            self.module
+                .add_function("tea_url_append_query", fn_type, Some(Linkage::External));
+        self.url_append_query_fn = Some(func);
+        func
+    }
+
+    // Bytes functions
+    define_ffi_bytes_to_string_fn!(
+        ensure_bytes_to_string_fn,
+        bytes_to_string_fn,
+        "tea_bytes_to_string"
+    );
+    define_ffi_bytes_to_string_fn!(
+        ensure_bytes_to_string_lossy_fn,
+        bytes_to_string_lossy_fn,
+        "tea_bytes_to_string_lossy"
+    );
+    define_ffi_bytes_to_string_fn!(ensure_bytes_to_hex_fn, bytes_to_hex_fn, "tea_bytes_to_hex");
+    define_ffi_bytes_to_string_fn!(
+        ensure_bytes_to_base64_fn,
+        bytes_to_base64_fn,
+        "tea_bytes_to_base64"
+    );
+    define_ffi_string_to_bytes_fn!(
+        ensure_bytes_from_string_fn,
+        bytes_from_string_fn,
+        "tea_bytes_from_string"
+    );
+    define_ffi_string_to_bytes_fn!(
+        ensure_bytes_from_hex_fn,
+        bytes_from_hex_fn,
+        "tea_bytes_from_hex"
+    );
+    define_ffi_string_to_bytes_fn!(
+        ensure_bytes_from_base64_fn,
+        bytes_from_base64_fn,
+        "tea_bytes_from_base64"
+    );
+
+    fn ensure_bytes_len_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.bytes_len_fn {
+            return func;
+        }
+        let fn_type = self
+            .int_type()
+            .fn_type(&[self.bytes_ptr_type().into()], false);
+        let func = self
+            .module
+            .add_function("tea_bytes_len", fn_type, Some(Linkage::External));
+        self.bytes_len_fn = Some(func);
+        func
+    }
+
+    fn ensure_bytes_get_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.bytes_get_fn {
+            return func;
+        }
+        let param_types = [self.bytes_ptr_type().into(), self.int_type().into()];
+        let fn_type = self.int_type().fn_type(&param_types, false);
+        let func = self
+            .module
+            .add_function("tea_bytes_get", fn_type, Some(Linkage::External));
+        self.bytes_get_fn = Some(func);
+        func
+    }
+
+    fn ensure_bytes_slice_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.bytes_slice_fn {
+            return func;
+        }
+        let param_types = [
+            self.bytes_ptr_type().into(),
+            self.int_type().into(),
+            self.int_type().into(),
+            self.int_type().into(),
+            self.context.bool_type().into(),
+        ];
+        let fn_type = self.bytes_ptr_type().fn_type(&param_types, false);
+        let func = self
+            .module
+            .add_function("tea_bytes_slice", fn_type, Some(Linkage::External));
+        self.bytes_slice_fn = Some(func);
+        func
+    }
+
+    fn ensure_bytes_equal_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.bytes_equal_fn {
+            return func;
+        }
+        let param_types = [self.bytes_ptr_type().into(), self.bytes_ptr_type().into()];
+        let fn_type = self.context.i32_type().fn_type(&param_types, false);
+        let func = self
+            .module
+            .add_function("tea_bytes_equal", fn_type, Some(Linkage::External));
+        self.bytes_equal_fn = Some(func);
+        func
+    }
+
+    fn ensure_bytes_concat_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.bytes_concat_fn {
+            return func;
+        }
+        let param_types = [self.bytes_ptr_type().into(), self.bytes_ptr_type().into()];
+        let fn_type = self.bytes_ptr_type().fn_type(&param_types, false);
+        let func = self
+            .module
+            .add_function("tea_bytes_concat", fn_type, Some(Linkage::External));
+        self.bytes_concat_fn = Some(func);
+        func
+    }
+
+    fn ensure_crypto_digest_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.crypto_digest_fn {
+            return func;
+        }
+        let param_types = [self.string_ptr_type().into(), self.bytes_ptr_type().into()];
+        let fn_type = self.bytes_ptr_type().fn_type(&param_types, false);
+        let func = self
+            .module
+            .add_function("tea_crypto_digest", fn_type, Some(Linkage::External));
+        self.crypto_digest_fn = Some(func);
+        func
+    }
+
+    fn ensure_crypto_hmac_sha256_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.crypto_hmac_sha256_fn {
             return func;
         }
-        let fn_type = self.int_type().fn_type(&[], false);
+        let param_types = [self.bytes_ptr_type().into(), self.bytes_ptr_type().into()];
+        let fn_type = self.bytes_ptr_type().fn_type(&param_types, false);
         let func =
             self.module
-                .add_function("tea_time_now_unix_millis", fn_type, Some(Linkage::External));
-        self.time_now_unix_millis_fn = Some(func);
+                .add_function("tea_crypto_hmac_sha256", fn_type, Some(Linkage::External));
+        self.crypto_hmac_sha256_fn = Some(func);
         func
     }
 
-    fn ensure_time_unix_seconds_fn(&mut self) -> FunctionValue<'ctx> {
-        if let Some(func) = self.time_unix_seconds_fn {
+    fn ensure_crypto_constant_time_eq_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.crypto_constant_time_eq_fn {
             return func;
         }
-        let fn_type = self.int_type().fn_type(&[self.int_type().into()], false);
-        let func =
-            self.module
-                .add_function("tea_time_unix_seconds", fn_type, Some(Linkage::External));
-        self.time_unix_seconds_fn = Some(func);
+        let param_types = [self.bytes_ptr_type().into(), self.bytes_ptr_type().into()];
+        let fn_type = self.context.i32_type().fn_type(&param_types, false);
+        let func = self.module.add_function(
+            "tea_crypto_constant_time_eq",
+            fn_type,
+            Some(Linkage::External),
+        );
+        self.crypto_constant_time_eq_fn = Some(func);
         func
     }
 
-    fn ensure_time_sleep_ms_fn(&mut self) -> FunctionValue<'ctx> {
-        if let Some(func) = self.time_sleep_ms_fn {
+    fn ensure_runtime_handle_fn(&mut self, signature: &StdFunction) -> FunctionValue<'ctx> {
+        let symbol = format!("tea_{}", signature.name);
+        if let Some(func) = self.module.get_function(&symbol) {
+            return func;
+        }
+        let mut param_types: Vec<BasicMetadataTypeEnum<'ctx>> = Vec::new();
+        if signature.return_type == StdType::Any {
+            param_types.push(self.ptr_type.into());
+        }
+        for param in signature.params {
+            param_types.push(match param {
+                StdType::Bool => self.context.i32_type().into(),
+                StdType::String => self.string_ptr_type().into(),
+                StdType::Bytes => self.bytes_ptr_type().into(),
+                StdType::Dict => self.dict_ptr_type().into(),
+                _ => self.int_type().into(),
+            });
+        }
+        let fn_type = match signature.return_type {
+            StdType::Int => self.int_type().fn_type(&param_types, false),
+            StdType::Bool => self.context.i32_type().fn_type(&param_types, false),
+            StdType::String => self.string_ptr_type().fn_type(&param_types, false),
+            StdType::Bytes => self.bytes_ptr_type().fn_type(&param_types, false),
+            StdType::Dict => self.value_type().fn_type(&param_types, false),
+            _ => self.context.void_type().fn_type(&param_types, false),
+        };
+        self.module
+            .add_function(&symbol, fn_type, Some(Linkage::External))
+    }
+
+    fn ensure_term_is_terminal_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.term_is_terminal_fn {
             return func;
         }
         let fn_type = self
             .context
-            .void_type()
+            .i32_type()
             .fn_type(&[self.int_type().into()], false);
+        let func =
+            self.module
+                .add_function("tea_term_is_terminal", fn_type, Some(Linkage::External));
+        self.term_is_terminal_fn = Some(func);
+        func
+    }
+
+    fn ensure_term_columns_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.term_columns_fn {
+            return func;
+        }
+        let fn_type = self.int_type().fn_type(&[], false);
         let func = self
             .module
-            .add_function("tea_time_sleep_ms", fn_type, Some(Linkage::External));
-        self.time_sleep_ms_fn = Some(func);
+            .add_function("tea_term_columns", fn_type, Some(Linkage::External));
+        self.term_columns_fn = Some(func);
         func
     }
 
-    fn ensure_time_format_rfc3339_fn(&mut self) -> FunctionValue<'ctx> {
-        if let Some(func) = self.time_format_rfc3339_fn {
+    fn ensure_term_rows_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.term_rows_fn {
+            return func;
+        }
+        let fn_type = self.int_type().fn_type(&[], false);
+        let func = self
+            .module
+            .add_function("tea_term_rows", fn_type, Some(Linkage::External));
+        self.term_rows_fn = Some(func);
+        func
+    }
+
+    fn ensure_term_set_echo_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.term_set_echo_fn {
             return func;
         }
         let fn_type = self
-            .string_ptr_type()
-            .fn_type(&[self.int_type().into()], false);
-        let func =
-            self.module
-                .add_function("tea_time_format_rfc3339", fn_type, Some(Linkage::External));
-        self.time_format_rfc3339_fn = Some(func);
+            .context
+            .i32_type()
+            .fn_type(&[self.context.i32_type().into()], false);
+        let func = self
+            .module
+            .add_function("tea_term_set_echo", fn_type, Some(Linkage::External));
+        self.term_set_echo_fn = Some(func);
         func
     }
 
-    fn ensure_time_parse_rfc3339_fn(&mut self) -> FunctionValue<'ctx> {
-        if let Some(func) = self.time_parse_rfc3339_fn {
+    fn ensure_bytes_is_utf8_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.bytes_is_utf8_fn {
             return func;
         }
         let fn_type = self
-            .value_type()
-            .fn_type(&[self.string_ptr_type().into()], false);
-        let func =
-            self.module
-                .add_function("tea_time_parse_rfc3339", fn_type, Some(Linkage::External));
-        self.time_parse_rfc3339_fn = Some(func);
+            .context
+            .i32_type()
+            .fn_type(&[self.bytes_ptr_type().into()], false);
+        let func = self
+            .module
+            .add_function("tea_bytes_is_utf8", fn_type, Some(Linkage::External));
+        self.bytes_is_utf8_fn = Some(func);
         func
     }
 
-    fn ensure_url_build_query_fn(&mut self) -> FunctionValue<'ctx> {
-        if let Some(func) = self.url_build_query_fn {
+    fn ensure_bytes_from_list_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.bytes_from_list_fn {
             return func;
         }
         let fn_type = self
-            .string_ptr_type()
-            .fn_type(&[self.dict_ptr_type().into()], false);
+            .bytes_ptr_type()
+            .fn_type(&[self.list_ptr_type().into()], false);
         let func =
             self.module
-                .add_function("tea_url_build_query", fn_type, Some(Linkage::External));
-        self.url_build_query_fn = Some(func);
+                .add_function("tea_bytes_from_list", fn_type, Some(Linkage::External));
+        self.bytes_from_list_fn = Some(func);
         func
     }
 
-    fn ensure_url_append_query_fn(&mut self) -> FunctionValue<'ctx> {
-        if let Some(func) = self.url_append_query_fn {
+    fn ensure_bytes_to_list_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.bytes_to_list_fn {
             return func;
         }
-        let param_types = [self.string_ptr_type().into(), self.dict_ptr_type().into()];
-        let fn_type = self.string_ptr_type().fn_type(&param_types, false);
-        let func =
-            self.module
-                .add_function("tea_url_append_query", fn_type, Some(Linkage::External));
-        self.url_append_query_fn = Some(func);
+        let fn_type = self
+            .list_ptr_type()
+            .fn_type(&[self.bytes_ptr_type().into()], false);
+        let func = self
+            .module
+            .add_function("tea_bytes_to_list", fn_type, Some(Linkage::External));
+        self.bytes_to_list_fn = Some(func);
         func
     }
 
     // Filesystem functions
     define_ffi_string_transform_fn!(ensure_fs_read_text_fn, fs_read_text_fn, "tea_fs_read_text");
-    define_ffi_string_to_list_fn!(
+    define_ffi_string_to_bytes_fn!(
         ensure_fs_read_bytes_fn,
         fs_read_bytes_fn,
         "tea_fs_read_bytes"
@@ -16611,7 +21033,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         if let Some(func) = self.fs_write_bytes_fn {
             return func;
         }
-        let param_types = [self.string_ptr_type().into(), self.list_ptr_type().into()];
+        let param_types = [self.string_ptr_type().into(), self.bytes_ptr_type().into()];
         let fn_type = self.context.void_type().fn_type(&param_types, false);
         let func = self
             .module
@@ -16624,7 +21046,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         if let Some(func) = self.fs_write_bytes_atomic_fn {
             return func;
         }
-        let param_types = [self.string_ptr_type().into(), self.list_ptr_type().into()];
+        let param_types = [self.string_ptr_type().into(), self.bytes_ptr_type().into()];
         let fn_type = self.context.void_type().fn_type(&param_types, false);
         let func = self.module.add_function(
             "tea_fs_write_bytes_atomic",
@@ -16652,7 +21074,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         if let Some(func) = self.fs_append_bytes_fn {
             return func;
         }
-        let param_types = [self.string_ptr_type().into(), self.list_ptr_type().into()];
+        let param_types = [self.string_ptr_type().into(), self.bytes_ptr_type().into()];
         let fn_type = self.context.void_type().fn_type(&param_types, false);
         let func =
             self.module
@@ -16779,7 +21201,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             return func;
         }
         let param_types = [self.int_type().into(), self.int_type().into()];
-        let fn_type = self.list_ptr_type().fn_type(&param_types, false);
+        let fn_type = self.bytes_ptr_type().fn_type(&param_types, false);
         let func = self
             .module
             .add_function("tea_fs_read_chunk", fn_type, Some(Linkage::External));
@@ -16787,6 +21209,64 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         func
     }
 
+    fn ensure_fs_read_line_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.fs_read_line_fn {
+            return func;
+        }
+        let fn_type = self
+            .context
+            .void_type()
+            .fn_type(&[self.ptr_type.into(), self.int_type().into()], false);
+        let func = self
+            .module
+            .add_function("tea_fs_read_line", fn_type, Some(Linkage::External));
+        self.fs_read_line_fn = Some(func);
+        func
+    }
+
+    fn ensure_random_seeded_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.random_seeded_fn {
+            return func;
+        }
+        let fn_type = self.int_type().fn_type(&[self.int_type().into()], false);
+        let func = self
+            .module
+            .add_function("tea_random_seeded", fn_type, Some(Linkage::External));
+        self.random_seeded_fn = Some(func);
+        func
+    }
+
+    fn ensure_random_int_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.random_int_fn {
+            return func;
+        }
+        let fn_type = self.int_type().fn_type(
+            &[
+                self.int_type().into(),
+                self.int_type().into(),
+                self.int_type().into(),
+            ],
+            false,
+        );
+        let func = self
+            .module
+            .add_function("tea_random_int", fn_type, Some(Linkage::External));
+        self.random_int_fn = Some(func);
+        func
+    }
+
+    fn ensure_random_float_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.random_float_fn {
+            return func;
+        }
+        let fn_type = self.float_type().fn_type(&[self.int_type().into()], false);
+        let func = self
+            .module
+            .add_function("tea_random_float", fn_type, Some(Linkage::External));
+        self.random_float_fn = Some(func);
+        func
+    }
+
     fn ensure_fs_close_fn(&mut self) -> FunctionValue<'ctx> {
         if let Some(func) = self.fs_close_fn {
             return func;
@@ -17083,34 +21563,196 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
                 self.string_ptr_type().into(),
                 self.int_type().into(),
                 self.int_type().into(),
+                self.int_type().into(),
+                self.context.bool_type().into(),
+            ],
+            false,
+        );
+        let func = self
+            .module
+            .add_function("tea_string_slice", fn_type, Some(Linkage::External));
+        self.string_slice_fn = Some(func);
+        func
+    }
+
+    fn ensure_list_splice_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.list_splice_fn {
+            return func;
+        }
+        let fn_type = self.context.void_type().fn_type(
+            &[
+                self.list_ptr_type().into(),
+                self.int_type().into(),
+                self.int_type().into(),
+                self.context.bool_type().into(),
+                self.list_ptr_type().into(),
+            ],
+            false,
+        );
+        let func = self
+            .module
+            .add_function("tea_list_splice", fn_type, Some(Linkage::External));
+        self.list_splice_fn = Some(func);
+        func
+    }
+
+    fn ensure_list_slice(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.list_slice_fn {
+            return func;
+        }
+        let fn_type = self.list_ptr_type().fn_type(
+            &[
+                self.list_ptr_type().into(),
+                self.int_type().into(),
+                self.int_type().into(),
+                self.int_type().into(),
                 self.context.bool_type().into(),
             ],
             false,
         );
         let func = self
             .module
-            .add_function("tea_string_slice", fn_type, Some(Linkage::External));
-        self.string_slice_fn = Some(func);
+            .add_function("tea_list_slice", fn_type, Some(Linkage::External));
+        self.list_slice_fn = Some(func);
+        func
+    }
+
+    define_ffi_list_mutator_fn!(ensure_list_sort_fn, list_sort_fn, "tea_list_sort");
+    define_ffi_list_mutator_fn!(ensure_list_reverse_fn, list_reverse_fn, "tea_list_reverse");
+    define_ffi_list_transform_fn!(ensure_list_unique_fn, list_unique_fn, "tea_list_unique");
+    define_ffi_list_grouping_fn!(ensure_list_chunk_fn, list_chunk_fn, "tea_list_chunk");
+    define_ffi_list_grouping_fn!(ensure_list_window_fn, list_window_fn, "tea_list_window");
+    define_ffi_list_item_fn!(ensure_list_min_fn, list_min_fn, "tea_list_min");
+    define_ffi_list_item_fn!(ensure_list_max_fn, list_max_fn, "tea_list_max");
+    define_ffi_list_item_fn!(ensure_list_pop_fn, list_pop_fn, "tea_list_pop");
+
+    fn ensure_list_sort_by_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.list_sort_by_fn {
+            return func;
+        }
+        let fn_type = self.context.void_type().fn_type(
+            &[
+                self.list_ptr_type().into(),
+                self.ptr_type.into(),
+                self.closure_ptr_type().into(),
+            ],
+            false,
+        );
+        let func = self
+            .module
+            .add_function("tea_list_sort_by", fn_type, Some(Linkage::External));
+        self.list_sort_by_fn = Some(func);
+        func
+    }
+
+    fn ensure_list_extend_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.list_extend_fn {
+            return func;
+        }
+        let fn_type = self.context.void_type().fn_type(
+            &[self.list_ptr_type().into(), self.list_ptr_type().into()],
+            false,
+        );
+        let func = self
+            .module
+            .add_function("tea_list_extend", fn_type, Some(Linkage::External));
+        self.list_extend_fn = Some(func);
+        func
+    }
+
+    fn ensure_list_join_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.list_join_fn {
+            return func;
+        }
+        let fn_type = self.string_ptr_type().fn_type(
+            &[self.list_ptr_type().into(), self.string_ptr_type().into()],
+            false,
+        );
+        let func = self
+            .module
+            .add_function("tea_list_join", fn_type, Some(Linkage::External));
+        self.list_join_fn = Some(func);
+        func
+    }
+
+    fn ensure_list_sum_int_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.list_sum_int_fn {
+            return func;
+        }
+        let fn_type = self
+            .int_type()
+            .fn_type(&[self.list_ptr_type().into()], false);
+        let func = self
+            .module
+            .add_function("tea_list_sum_int", fn_type, Some(Linkage::External));
+        self.list_sum_int_fn = Some(func);
+        func
+    }
+
+    fn ensure_list_sum_float_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.list_sum_float_fn {
+            return func;
+        }
+        let fn_type = self
+            .float_type()
+            .fn_type(&[self.list_ptr_type().into()], false);
+        let func = self
+            .module
+            .add_function("tea_list_sum_float", fn_type, Some(Linkage::External));
+        self.list_sum_float_fn = Some(func);
+        func
+    }
+
+    fn ensure_list_insert_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.list_insert_fn {
+            return func;
+        }
+        let fn_type = self.context.void_type().fn_type(
+            &[
+                self.list_ptr_type().into(),
+                self.int_type().into(),
+                self.ptr_type.into(),
+            ],
+            false,
+        );
+        let func = self
+            .module
+            .add_function("tea_list_insert", fn_type, Some(Linkage::External));
+        self.list_insert_fn = Some(func);
+        func
+    }
+
+    fn ensure_list_remove_at_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.list_remove_at_fn {
+            return func;
+        }
+        let fn_type = self.value_type().fn_type(
+            &[self.list_ptr_type().into(), self.int_type().into()],
+            false,
+        );
+        let func = self
+            .module
+            .add_function("tea_list_remove_at", fn_type, Some(Linkage::External));
+        self.list_remove_at_fn = Some(func);
         func
     }
 
-    fn ensure_list_slice(&mut self) -> FunctionValue<'ctx> {
-        if let Some(func) = self.list_slice_fn {
+    fn ensure_dict_group_append_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.dict_group_append_fn {
             return func;
         }
-        let fn_type = self.list_ptr_type().fn_type(
+        let fn_type = self.context.void_type().fn_type(
             &[
-                self.list_ptr_type().into(),
-                self.int_type().into(),
-                self.int_type().into(),
-                self.context.bool_type().into(),
+                self.dict_ptr_type().into(),
+                self.string_ptr_type().into(),
+                self.ptr_type.into(),
             ],
             false,
         );
-        let func = self
-            .module
-            .add_function("tea_list_slice", fn_type, Some(Linkage::External));
-        self.list_slice_fn = Some(func);
+        let func =
+            self.module
+                .add_function("tea_dict_group_append", fn_type, Some(Linkage::External));
+        self.dict_group_append_fn = Some(func);
         func
     }
 
@@ -17800,7 +22442,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         if let Some(func) = self.io_read_bytes_fn {
             return func;
         }
-        let fn_type = self.list_ptr_type().fn_type(&[], false);
+        let fn_type = self.bytes_ptr_type().fn_type(&[], false);
         let func = self
             .module
             .add_function("tea_io_read_bytes", fn_type, Some(Linkage::External));
@@ -17921,6 +22563,116 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         func
     }
 
+    define_ffi_string_to_list_fn!(ensure_string_chars_fn, string_chars_fn, "tea_string_chars");
+    define_ffi_string_to_list_fn!(
+        ensure_string_graphemes_fn,
+        string_graphemes_fn,
+        "tea_string_graphemes"
+    );
+    define_ffi_string_to_list_fn!(
+        ensure_string_code_points_fn,
+        string_code_points_fn,
+        "tea_string_code_points"
+    );
+    define_ffi_string_to_int_fn!(
+        ensure_string_char_count_fn,
+        string_char_count_fn,
+        "tea_string_char_count"
+    );
+    define_ffi_string_to_int_fn!(
+        ensure_string_display_width_fn,
+        string_display_width_fn,
+        "tea_string_display_width"
+    );
+    define_ffi_string_transform_fn!(
+        ensure_string_fold_case_fn,
+        string_fold_case_fn,
+        "tea_string_fold_case"
+    );
+    define_ffi_string_transform_fn!(
+        ensure_string_normalize_nfc_fn,
+        string_normalize_nfc_fn,
+        "tea_string_normalize_nfc"
+    );
+    define_ffi_string_transform_fn!(
+        ensure_string_normalize_nfd_fn,
+        string_normalize_nfd_fn,
+        "tea_string_normalize_nfd"
+    );
+    define_ffi_string_predicate_fn!(
+        ensure_string_is_alpha_fn,
+        string_is_alpha_fn,
+        "tea_string_is_alpha"
+    );
+    define_ffi_string_predicate_fn!(
+        ensure_string_is_digit_fn,
+        string_is_digit_fn,
+        "tea_string_is_digit"
+    );
+    define_ffi_string_predicate_fn!(
+        ensure_string_is_whitespace_fn,
+        string_is_whitespace_fn,
+        "tea_string_is_whitespace"
+    );
+
+    fn ensure_format_value_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.format_value_fn {
+            return func;
+        }
+        let fn_type = self.string_ptr_type().fn_type(
+            &[self.ptr_type.into(), self.string_ptr_type().into()],
+            false,
+        );
+        let func = self
+            .module
+            .add_function("tea_format_value", fn_type, Some(Linkage::External));
+        self.format_value_fn = Some(func);
+        func
+    }
+
+    fn ensure_string_to_cstring_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.string_to_cstring_fn {
+            return func;
+        }
+        let fn_type = self
+            .ptr_type
+            .fn_type(&[self.string_ptr_type().into()], false);
+        let func =
+            self.module
+                .add_function("tea_string_to_cstring", fn_type, Some(Linkage::External));
+        self.string_to_cstring_fn = Some(func);
+        func
+    }
+
+    fn ensure_cstring_free_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.cstring_free_fn {
+            return func;
+        }
+        let fn_type = self
+            .context
+            .void_type()
+            .fn_type(&[self.ptr_type.into()], false);
+        let func = self
+            .module
+            .add_function("tea_cstring_free", fn_type, Some(Linkage::External));
+        self.cstring_free_fn = Some(func);
+        func
+    }
+
+    fn ensure_string_from_cstring_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.string_from_cstring_fn {
+            return func;
+        }
+        let fn_type = self
+            .string_ptr_type()
+            .fn_type(&[self.ptr_type.into()], false);
+        let func =
+            self.module
+                .add_function("tea_string_from_cstring", fn_type, Some(Linkage::External));
+        self.string_from_cstring_fn = Some(func);
+        func
+    }
+
     // Regex ensure functions
     fn ensure_regex_compile_fn(&mut self) -> FunctionValue<'ctx> {
         if let Some(func) = self.regex_compile_fn {
@@ -18173,6 +22925,23 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         func
     }
 
+    fn ensure_process_read_stdout_line_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.process_read_stdout_line_fn {
+            return func;
+        }
+        let fn_type = self
+            .context
+            .void_type()
+            .fn_type(&[self.ptr_type.into(), self.int_type().into()], false);
+        let func = self.module.add_function(
+            "tea_process_read_stdout_line",
+            fn_type,
+            Some(Linkage::External),
+        );
+        self.process_read_stdout_line_fn = Some(func);
+        func
+    }
+
     fn ensure_process_read_stderr_fn(&mut self) -> FunctionValue<'ctx> {
         if let Some(func) = self.process_read_stderr_fn {
             return func;
@@ -18191,7 +22960,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             return func;
         }
         let param_types = [self.int_type().into(), self.int_type().into()];
-        let fn_type = self.list_ptr_type().fn_type(&param_types, false);
+        let fn_type = self.bytes_ptr_type().fn_type(&param_types, false);
         let func = self.module.add_function(
             "tea_process_read_stdout_bytes",
             fn_type,
@@ -18206,7 +22975,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             return func;
         }
         let param_types = [self.int_type().into(), self.int_type().into()];
-        let fn_type = self.list_ptr_type().fn_type(&param_types, false);
+        let fn_type = self.bytes_ptr_type().fn_type(&param_types, false);
         let func = self.module.add_function(
             "tea_process_read_stderr_bytes",
             fn_type,
@@ -18233,7 +23002,7 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         if let Some(func) = self.process_write_stdin_bytes_fn {
             return func;
         }
-        let param_types = [self.int_type().into(), self.list_ptr_type().into()];
+        let param_types = [self.int_type().into(), self.bytes_ptr_type().into()];
         let fn_type = self.context.void_type().fn_type(&param_types, false);
         let func = self.module.add_function(
             "tea_process_write_stdin_bytes",
@@ -18274,22 +23043,19 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         func
     }
 
-    fn ensure_json_encode(&mut self) -> FunctionValue<'ctx> {
-        if let Some(func) = self.json_encode_fn {
+    fn ensure_document_encode_fn(&mut self, symbol: &str) -> FunctionValue<'ctx> {
+        if let Some(func) = self.module.get_function(symbol) {
             return func;
         }
         // Pass TeaValue by pointer to avoid ARM64 ABI struct passing issues
         let ptr_type = self.context.ptr_type(AddressSpace::default());
         let fn_type = self.string_ptr_type().fn_type(&[ptr_type.into()], false);
-        let func = self
-            .module
-            .add_function("tea_json_encode", fn_type, Some(Linkage::External));
-        self.json_encode_fn = Some(func);
-        func
+        self.module
+            .add_function(symbol, fn_type, Some(Linkage::External))
     }
 
-    fn ensure_json_decode(&mut self) -> FunctionValue<'ctx> {
-        if let Some(func) = self.json_decode_fn {
+    fn ensure_document_decode_fn(&mut self, symbol: &str) -> FunctionValue<'ctx> {
+        if let Some(func) = self.module.get_function(symbol) {
             return func;
         }
         // Return via out-pointer to avoid ARM64 ABI struct return issues
@@ -18298,11 +23064,65 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
             .context
             .void_type()
             .fn_type(&[ptr_type.into(), self.string_ptr_type().into()], false);
-        let func = self
-            .module
-            .add_function("tea_json_decode", fn_type, Some(Linkage::External));
-        self.json_decode_fn = Some(func);
-        func
+        self.module
+            .add_function(symbol, fn_type, Some(Linkage::External))
+    }
+
+    fn ensure_json_encode_with_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.module.get_function("tea_json_encode_with") {
+            return func;
+        }
+        // Pass TeaValue by pointer to avoid ARM64 ABI struct passing issues
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let fn_type = self.string_ptr_type().fn_type(
+            &[
+                ptr_type.into(),
+                self.dict_ptr_type().into(),
+                self.context.i32_type().into(),
+            ],
+            false,
+        );
+        self.module
+            .add_function("tea_json_encode_with", fn_type, Some(Linkage::External))
+    }
+
+    fn ensure_json_decode_typed_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.module.get_function("tea_json_decode_typed") {
+            return func;
+        }
+        // Return via out-pointer to avoid ARM64 ABI struct return issues
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let fn_type = self.context.void_type().fn_type(
+            &[
+                ptr_type.into(),
+                self.string_ptr_type().into(),
+                ptr_type.into(),
+                self.dict_ptr_type().into(),
+            ],
+            false,
+        );
+        self.module
+            .add_function("tea_json_decode_typed", fn_type, Some(Linkage::External))
+    }
+
+    fn ensure_csv_decode_record_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.module.get_function("tea_csv_decode_record") {
+            return func;
+        }
+        // Return via out-pointer to avoid ARM64 ABI struct return issues
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let fn_type = self.context.void_type().fn_type(
+            &[
+                ptr_type.into(),
+                self.dict_ptr_type().into(),
+                ptr_type.into(),
+                self.dict_ptr_type().into(),
+                self.int_type().into(),
+            ],
+            false,
+        );
+        self.module
+            .add_function("tea_csv_decode_record", fn_type, Some(Linkage::External))
     }
 
     fn ensure_http_send_fn(&mut self) -> FunctionValue<'ctx> {
@@ -18323,6 +23143,153 @@ impl<'ctx> LlvmCodeGenerator<'ctx> {
         self.http_send_fn = Some(func);
         func
     }
+
+    fn ensure_task_spawn_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.task_spawn_fn {
+            return func;
+        }
+        let fn_type = self.int_type().fn_type(
+            &[self.closure_ptr_type().into(), self.ptr_type.into()],
+            false,
+        );
+        let func = self
+            .module
+            .add_function("tea_task_spawn", fn_type, Some(Linkage::External));
+        self.task_spawn_fn = Some(func);
+        func
+    }
+
+    fn ensure_generator_new_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.generator_new_fn {
+            return func;
+        }
+        let fn_type = self.int_type().fn_type(
+            &[self.closure_ptr_type().into(), self.ptr_type.into()],
+            false,
+        );
+        let func = self
+            .module
+            .add_function("tea_generator_new", fn_type, Some(Linkage::External));
+        self.generator_new_fn = Some(func);
+        func
+    }
+
+    fn ensure_generator_yield_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.generator_yield_fn {
+            return func;
+        }
+        let fn_type = self
+            .context
+            .i32_type()
+            .fn_type(&[self.ptr_type.into()], false);
+        let func =
+            self.module
+                .add_function("tea_generator_yield", fn_type, Some(Linkage::External));
+        self.generator_yield_fn = Some(func);
+        func
+    }
+
+    fn ensure_iter_close_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.iter_close_fn {
+            return func;
+        }
+        let fn_type = self
+            .context
+            .void_type()
+            .fn_type(&[self.int_type().into()], false);
+        let func = self
+            .module
+            .add_function("tea_iter_close", fn_type, Some(Linkage::External));
+        self.iter_close_fn = Some(func);
+        func
+    }
+
+    fn ensure_iter_next_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.iter_next_fn {
+            return func;
+        }
+        let fn_type = self
+            .context
+            .i32_type()
+            .fn_type(&[self.ptr_type.into(), self.int_type().into()], false);
+        let func = self
+            .module
+            .add_function("tea_iter_next", fn_type, Some(Linkage::External));
+        self.iter_next_fn = Some(func);
+        func
+    }
+
+    fn ensure_task_join_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.task_join_fn {
+            return func;
+        }
+        let fn_type = self
+            .context
+            .void_type()
+            .fn_type(&[self.ptr_type.into(), self.int_type().into()], false);
+        let func = self
+            .module
+            .add_function("tea_task_join", fn_type, Some(Linkage::External));
+        self.task_join_fn = Some(func);
+        func
+    }
+
+    fn ensure_channel_new_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.channel_new_fn {
+            return func;
+        }
+        let fn_type = self.int_type().fn_type(&[self.int_type().into()], false);
+        let func = self
+            .module
+            .add_function("tea_channel_new", fn_type, Some(Linkage::External));
+        self.channel_new_fn = Some(func);
+        func
+    }
+
+    fn ensure_channel_send_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.channel_send_fn {
+            return func;
+        }
+        let fn_type = self
+            .context
+            .i32_type()
+            .fn_type(&[self.int_type().into(), self.ptr_type.into()], false);
+        let func = self
+            .module
+            .add_function("tea_channel_send", fn_type, Some(Linkage::External));
+        self.channel_send_fn = Some(func);
+        func
+    }
+
+    fn ensure_channel_recv_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.channel_recv_fn {
+            return func;
+        }
+        let fn_type = self
+            .context
+            .void_type()
+            .fn_type(&[self.ptr_type.into(), self.int_type().into()], false);
+        let func = self
+            .module
+            .add_function("tea_channel_recv", fn_type, Some(Linkage::External));
+        self.channel_recv_fn = Some(func);
+        func
+    }
+
+    fn ensure_channel_close_fn(&mut self) -> FunctionValue<'ctx> {
+        if let Some(func) = self.channel_close_fn {
+            return func;
+        }
+        let fn_type = self
+            .context
+            .void_type()
+            .fn_type(&[self.int_type().into()], false);
+        let func = self
+            .module
+            .add_function("tea_channel_close", fn_type, Some(Linkage::External));
+        self.channel_close_fn = Some(func);
+        func
+    }
 }
 
 pub fn normalize_target_triple(triple: &str) -> String {
@@ -3,6 +3,7 @@ use inkwell::values::{
     BasicValueEnum, FloatValue, GlobalValue, IntValue, PointerValue, StructValue,
 };
 
+use crate::ast::{Expression, FunctionParameter, TypeExpression};
 use crate::typechecker::{StructType, Type};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -11,10 +12,15 @@ pub enum ValueType {
     Float,
     Bool,
     String,
+    Bytes,
     List(Box<ValueType>),
     Dict(Box<ValueType>),
+    /// Handle to a runtime generator yielding values of the element type
+    Iterator(Box<ValueType>),
     Function(Vec<ValueType>, Box<ValueType>),
     Struct(String),
+    /// A variant of the named enum, held as the variant's name
+    Enum(String),
     Error {
         error_name: String,
         variant_name: Option<String>,
@@ -42,6 +48,7 @@ pub struct FunctionSignature<'ctx> {
     pub value: inkwell::values::FunctionValue<'ctx>,
     pub return_type: ValueType,
     pub param_types: Vec<ValueType>,
+    pub parameters: Vec<FunctionParameter>,
     pub can_throw: bool,
 }
 
@@ -67,6 +74,7 @@ pub struct LocalVariable<'ctx> {
 pub struct StructLowering<'ctx> {
     pub field_names: Vec<String>,
     pub field_types: Vec<ValueType>,
+    pub field_defaults: Vec<Option<Expression>>,
     pub template_global: Option<GlobalValue<'ctx>>,
     pub field_names_global: Option<GlobalValue<'ctx>>,
     pub template_pointer: Option<PointerValue<'ctx>>,
@@ -77,6 +85,7 @@ impl<'ctx> StructLowering<'ctx> {
         Self {
             field_names: Vec::new(),
             field_types: Vec::new(),
+            field_defaults: Vec::new(),
             template_global: None,
             field_names_global: None,
             template_pointer: None,
@@ -135,6 +144,7 @@ pub enum ExprValue<'ctx> {
     Float(FloatValue<'ctx>),
     Bool(IntValue<'ctx>),
     String(PointerValue<'ctx>),
+    Bytes(PointerValue<'ctx>),
     List {
         pointer: PointerValue<'ctx>,
         element_type: Box<ValueType>,
@@ -143,6 +153,10 @@ pub enum ExprValue<'ctx> {
         pointer: PointerValue<'ctx>,
         value_type: Box<ValueType>,
     },
+    Iterator {
+        handle: IntValue<'ctx>,
+        element_type: Box<ValueType>,
+    },
     Struct {
         pointer: PointerValue<'ctx>,
         struct_name: String,
@@ -175,8 +189,10 @@ impl<'ctx> ExprValue<'ctx> {
             ExprValue::Float(_) => ValueType::Float,
             ExprValue::Bool(_) => ValueType::Bool,
             ExprValue::String(_) => ValueType::String,
+            ExprValue::Bytes(_) => ValueType::Bytes,
             ExprValue::List { element_type, .. } => ValueType::List(element_type.clone()),
             ExprValue::Dict { value_type, .. } => ValueType::Dict(value_type.clone()),
+            ExprValue::Iterator { element_type, .. } => ValueType::Iterator(element_type.clone()),
             ExprValue::Struct { struct_name, .. } => ValueType::Struct(struct_name.clone()),
             ExprValue::Error {
                 error_name,
@@ -203,8 +219,10 @@ impl<'ctx> ExprValue<'ctx> {
             ExprValue::Float(v) => Some(v.into()),
             ExprValue::Bool(v) => Some(v.into()),
             ExprValue::String(ptr) => Some(ptr.into()),
+            ExprValue::Bytes(ptr) => Some(ptr.into()),
             ExprValue::List { pointer, .. } => Some(pointer.into()),
             ExprValue::Dict { pointer, .. } => Some(pointer.into()),
+            ExprValue::Iterator { handle, .. } => Some(handle.into()),
             ExprValue::Struct { pointer, .. } => Some(pointer.into()),
             ExprValue::Error { pointer, .. } => Some(pointer.into()),
             ExprValue::Closure { pointer, .. } => Some(pointer.into()),
@@ -242,10 +260,12 @@ pub(crate) fn format_type_name(ty: &Type) -> String {
         Type::Int => "Int".to_string(),
         Type::Float => "Float".to_string(),
         Type::String => "String".to_string(),
+        Type::Bytes => "Bytes".to_string(),
         Type::Nil => "Nil".to_string(),
         Type::Void => "Void".to_string(),
         Type::List(inner) => format!("List[{}]", format_type_name(inner)),
         Type::Dict(inner) => format!("Dict[String, {}]", format_type_name(inner)),
+        Type::Iterator(inner) => format!("Iterator[{}]", format_type_name(inner)),
         Type::Optional(inner) => format!("{}?", format_type_name(inner)),
         Type::Function(params, return_type) => {
             let param_str = if params.is_empty() {
@@ -292,6 +312,7 @@ pub(crate) fn type_to_value_type(ty: &Type) -> Result<ValueType> {
         Type::Int => Ok(ValueType::Int),
         Type::Float => Ok(ValueType::Float),
         Type::String => Ok(ValueType::String),
+        Type::Bytes => Ok(ValueType::Bytes),
         Type::Nil => Ok(ValueType::Void),
         Type::Void => Ok(ValueType::Void),
         Type::List(inner) => Ok(ValueType::List(Box::new(type_to_value_type(inner)?))),
@@ -306,10 +327,7 @@ pub(crate) fn type_to_value_type(ty: &Type) -> Result<ValueType> {
             ))
         }
         Type::Struct(struct_type) => Ok(ValueType::Struct(format_struct_type_name(struct_type))),
-        Type::Enum(enum_type) => bail!(format!(
-            "LLVM backend does not yet support enums like '{}'",
-            enum_type.name
-        )),
+        Type::Enum(enum_type) => Ok(ValueType::Enum(enum_type.name.clone())),
         Type::Union(union_type) => bail!(format!(
             "LLVM backend does not yet support union '{}'",
             union_type.name
@@ -321,6 +339,7 @@ pub(crate) fn type_to_value_type(ty: &Type) -> Result<ValueType> {
             ))
         }
         Type::Dict(inner) => Ok(ValueType::Dict(Box::new(type_to_value_type(inner)?))),
+        Type::Iterator(inner) => Ok(ValueType::Iterator(Box::new(type_to_value_type(inner)?))),
         Type::Optional(inner) => Ok(ValueType::Optional(Box::new(type_to_value_type(inner)?))),
         Type::Error(error_type) => Ok(ValueType::Error {
             error_name: error_type.name.clone(),
@@ -330,6 +349,20 @@ pub(crate) fn type_to_value_type(ty: &Type) -> Result<ValueType> {
     }
 }
 
+/// Whether values of the two types share a runtime representation, as an enum
+/// does with the String holding its variant name.
+pub(crate) fn same_representation(left: &ValueType, right: &ValueType) -> bool {
+    match (left, right) {
+        (ValueType::Enum(_), ValueType::String) | (ValueType::String, ValueType::Enum(_)) => true,
+        (ValueType::List(left), ValueType::List(right))
+        | (ValueType::Dict(left), ValueType::Dict(right))
+        | (ValueType::Optional(left), ValueType::Optional(right)) => {
+            same_representation(left, right)
+        }
+        _ => left == right,
+    }
+}
+
 pub fn sanitize_symbol_component(component: &str) -> String {
     component
         .chars()
@@ -343,6 +376,59 @@ pub fn sanitize_symbol_component(component: &str) -> String {
         .collect()
 }
 
+/// Symbol of the Tea-ABI wrapper around an `extern def`, kept distinct from the C
+/// symbol it calls so `extern def strlen` does not define `strlen` itself.
+pub(crate) fn extern_wrapper_name(name: &str) -> String {
+    format!("{}$extern", name)
+}
+
+/// C representation of a value crossing an `extern def` boundary.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CAbiType {
+    /// `int64_t`
+    Int,
+    /// `double`
+    Float,
+    /// `bool`
+    Bool,
+    /// `const char*`, copied to and from a Tea `String`
+    CString,
+    /// `void*`, carried in Tea as an `Int`
+    CPointer,
+}
+
+impl CAbiType {
+    /// Read the C type of an extern parameter or return annotation; `None` is `void`.
+    pub fn from_annotation(annotation: Option<&TypeExpression>) -> Result<Option<Self>> {
+        let Some(annotation) = annotation else {
+            return Ok(None);
+        };
+        let lexemes: Vec<&str> = annotation
+            .tokens
+            .iter()
+            .map(|token| token.lexeme.as_str())
+            .collect();
+        Ok(Some(match lexemes.as_slice() {
+            ["Void"] => return Ok(None),
+            ["Int"] => Self::Int,
+            ["Float"] => Self::Float,
+            ["Bool"] => Self::Bool,
+            ["CString"] => Self::CString,
+            ["CPointer"] => Self::CPointer,
+            _ => bail!("type '{}' cannot cross the C boundary", lexemes.join("")),
+        }))
+    }
+
+    pub fn value_type(self) -> ValueType {
+        match self {
+            Self::Int | Self::CPointer => ValueType::Int,
+            Self::Float => ValueType::Float,
+            Self::Bool => ValueType::Bool,
+            Self::CString => ValueType::String,
+        }
+    }
+}
+
 pub(crate) fn mangle_function_name(name: &str, type_arguments: &[Type]) -> String {
     if type_arguments.is_empty() {
         return name.to_string();
@@ -65,7 +65,13 @@ fn visit_statement(
         }
         Statement::Function(function) => visit_function(diagnostics, alias_paths, function),
         Statement::Test(test_stmt) => visit_block(diagnostics, alias_paths, &test_stmt.body),
-        Statement::Struct(_) => {}
+        Statement::Struct(struct_stmt) => {
+            for field in &struct_stmt.fields {
+                if let Some(default_value) = &field.default_value {
+                    visit_expression(diagnostics, alias_paths, default_value);
+                }
+            }
+        }
         Statement::Union(union_stmt) => diagnostics.push_error_with_span(
             format!(
                 "union '{}' is not yet supported by the browser runner",
@@ -99,17 +105,28 @@ fn visit_statement(
                 crate::ast::LoopHeader::For { iterator, .. } => {
                     visit_expression(diagnostics, alias_paths, iterator);
                 }
-                crate::ast::LoopHeader::Condition(condition) => {
+                crate::ast::LoopHeader::Condition(condition)
+                | crate::ast::LoopHeader::Binding {
+                    value: condition, ..
+                } => {
                     visit_expression(diagnostics, alias_paths, condition);
                 }
             }
             visit_block(diagnostics, alias_paths, &loop_stmt.body);
         }
+        Statement::Guard(guard) => {
+            visit_expression(diagnostics, alias_paths, &guard.condition);
+            visit_block(diagnostics, alias_paths, &guard.alternative);
+        }
         Statement::Break(_) | Statement::Continue(_) => {}
         Statement::Throw(throw_stmt) => diagnostics.push_error_with_span(
             "throw is not yet supported by the browser runner",
             Some(throw_stmt.span),
         ),
+        Statement::Yield(yield_stmt) => diagnostics.push_error_with_span(
+            "generators are not yet supported by the browser runner",
+            Some(yield_stmt.span),
+        ),
         Statement::Return(return_stmt) => {
             if let Some(expression) = &return_stmt.expression {
                 visit_expression(diagnostics, alias_paths, expression);
@@ -136,6 +153,16 @@ fn visit_function(
     alias_paths: &HashMap<String, String>,
     function: &FunctionStatement,
 ) {
+    if let Some(extern_function) = &function.extern_function {
+        diagnostics.push_error_with_span(
+            format!(
+                "extern function '{}' cannot be called in the browser target",
+                extern_function.symbol
+            ),
+            Some(extern_function.span),
+        );
+        return;
+    }
     for parameter in &function.parameters {
         if let Some(default_value) = &parameter.default_value {
             visit_expression(diagnostics, alias_paths, default_value);
@@ -232,7 +259,11 @@ fn visit_expression(
         ExpressionKind::Identifier(_) | ExpressionKind::Literal(_) => {}
         ExpressionKind::InterpolatedString(interpolated) => {
             for part in &interpolated.parts {
-                if let crate::ast::InterpolatedStringPart::Expression(expression) = part {
+                if let crate::ast::InterpolatedStringPart::Expression(expression)
+                | crate::ast::InterpolatedStringPart::Formatted(
+                    crate::ast::FormattedInterpolation { expression, .. },
+                ) = part
+                {
                     visit_expression(diagnostics, alias_paths, expression);
                 }
             }
@@ -266,8 +297,12 @@ fn visit_expression(
             visit_expression(diagnostics, alias_paths, &index.index);
         }
         ExpressionKind::Range(range) => {
-            visit_expression(diagnostics, alias_paths, &range.start);
-            visit_expression(diagnostics, alias_paths, &range.end);
+            for bound in [&range.start, &range.end, &range.step]
+                .into_iter()
+                .flatten()
+            {
+                visit_expression(diagnostics, alias_paths, bound);
+            }
         }
         ExpressionKind::Lambda(lambda) => match &lambda.body {
             LambdaBody::Expression(expression) => {
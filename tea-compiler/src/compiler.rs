@@ -5,16 +5,17 @@ use std::sync::Arc;
 use anyhow::{bail, Result};
 
 use crate::analysis::SemanticAnalysis;
-use crate::ast::{Module, SourceSpan};
+use crate::ast::{Module, SourceSpan, Statement};
 use crate::browser::validate_browser_target;
 use crate::diagnostics::Diagnostics;
-use crate::expansion::{ExpandedModule, ModuleExpander};
+use crate::expansion::{ExpandedModule, ModuleExpander, ModuleGraph};
 use crate::lexer::{Lexer, LexerError};
 use crate::loader::ModuleLoader;
 use crate::parser::Parser;
 use crate::resolver::{ModuleAliasBinding, Resolver, ResolverOutput};
 use crate::source::SourceFile;
 use crate::typechecker::TypeChecker;
+use crate::units::{build_units, CompilationUnit};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CompileTarget {
@@ -44,6 +45,28 @@ impl Default for CompileOptions {
 pub struct Compilation {
     pub module: Module,
     pub analysis: SemanticAnalysis,
+    /// One unit per source module, entry module first.
+    pub units: Vec<CompilationUnit>,
+}
+
+impl Compilation {
+    /// Libraries named by `extern def ... from "lib"` declarations, in declaration
+    /// order. The C library is always linked, so `"c"` is left out.
+    pub fn extern_libraries(&self) -> Vec<String> {
+        let mut libraries: Vec<String> = Vec::new();
+        for statement in &self.module.statements {
+            let Statement::Function(function) = statement else {
+                continue;
+            };
+            let Some(extern_function) = &function.extern_function else {
+                continue;
+            };
+            if extern_function.library != "c" && !libraries.contains(&extern_function.library) {
+                libraries.push(extern_function.library.clone());
+            }
+        }
+        libraries
+    }
 }
 
 pub struct ParsedModule {
@@ -67,6 +90,7 @@ pub struct ResolvedModule {
     alias_exports: HashMap<String, Vec<String>>,
     alias_export_renames: HashMap<String, HashMap<String, String>>,
     alias_export_docstrings: HashMap<String, HashMap<String, String>>,
+    graph: ModuleGraph,
 }
 
 impl ResolvedModule {
@@ -139,12 +163,11 @@ impl Compiler {
         source: &SourceFile,
         parsed: ParsedModule,
     ) -> Result<ExpandedModule> {
-        let entry_path = source.path.clone();
         let mut expander = ModuleExpander::new(
             self.options.module_overrides.clone(),
             self.options.module_loader.clone(),
         )?;
-        let expanded_module = match expander.expand(&parsed.module, &entry_path) {
+        let expanded_module = match expander.expand(&parsed.module, source) {
             Ok(module) => module,
             Err(err) => {
                 let diagnostics = expander.into_diagnostics();
@@ -162,7 +185,8 @@ impl Compiler {
         Ok(expanded_module)
     }
 
-    pub fn resolve_module(&mut self, expanded: ExpandedModule) -> Result<ResolvedModule> {
+    pub fn resolve_module(&mut self, mut expanded: ExpandedModule) -> Result<ResolvedModule> {
+        let graph = expanded.take_graph();
         let (module, alias_exports, alias_export_renames, alias_export_docstrings) =
             expanded.into_parts();
 
@@ -186,6 +210,7 @@ impl Compiler {
             alias_exports,
             alias_export_renames,
             alias_export_docstrings,
+            graph,
         })
     }
 
@@ -197,9 +222,11 @@ impl Compiler {
             alias_exports,
             alias_export_renames,
             alias_export_docstrings,
+            graph,
         } = resolved;
 
         let mut type_checker = TypeChecker::new();
+        type_checker.set_lambda_captures(lambda_captures.clone());
         type_checker.check_module(&module);
         let analysis = SemanticAnalysis::from_parts(
             lambda_captures,
@@ -209,6 +236,7 @@ impl Compiler {
             &alias_export_renames,
             &alias_export_docstrings,
         );
+        let units = build_units(&module, &graph, &type_checker);
         let mut type_diagnostics = type_checker.into_diagnostics();
         let type_errors = type_diagnostics.has_errors();
         if !alias_export_renames.is_empty() {
@@ -218,6 +246,20 @@ impl Compiler {
                     reverse_names.insert(renamed.clone(), format!("{}.{}", alias, original));
                 }
             }
+            for statement in &module.statements {
+                let Statement::Use(use_stmt) = statement else {
+                    continue;
+                };
+                let Some(renames) = alias_export_renames.get(&use_stmt.alias.name) else {
+                    continue;
+                };
+                for item in &use_stmt.items {
+                    if let Some(renamed) = renames.get(&item.name) {
+                        let local_name = item.alias.as_deref().unwrap_or(&item.name);
+                        reverse_names.insert(renamed.clone(), local_name.to_string());
+                    }
+                }
+            }
             if !reverse_names.is_empty() {
                 for diagnostic in type_diagnostics.entries_mut() {
                     let mut message = diagnostic.message.clone();
@@ -235,7 +277,11 @@ impl Compiler {
             bail!("Type checking failed");
         }
 
-        let compilation = Compilation { module, analysis };
+        let compilation = Compilation {
+            module,
+            analysis,
+            units,
+        };
         if self.options.target == CompileTarget::Browser {
             let diagnostics = validate_browser_target(&compilation.module, &compilation.analysis);
             let has_errors = diagnostics.has_errors();
@@ -0,0 +1,393 @@
+//! Compile-time evaluation of constant expressions.
+//!
+//! Module-level `const` initializers built from literals, other constants and
+//! operators are folded to a value while type checking. Code generation then
+//! substitutes that value for every reference, so importers of a module see
+//! its `pub const` values as literals instead of loading a global.
+//! `pub const` initializers must fold. Other constants and match patterns may
+//! call functions or build values at runtime, but one written only with
+//! literals and operators must fold, so `const LIMIT = count + 1` over a
+//! `var` is an error rather than a silent runtime value.
+//!
+//! The rules mirror the native backend: Int arithmetic is signed 64-bit,
+//! division truncates, and Floats render with Rust's shortest representation.
+
+use tea_support::{FormatArg, FormatSpec};
+
+use crate::ast::{
+    BinaryOperator, Expression, ExpressionKind, FormattedInterpolation, InterpolatedStringPart,
+    Literal, SourceSpan, UnaryOperator,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ConstValue {
+    Nil,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl ConstValue {
+    fn type_name(&self) -> &'static str {
+        match self {
+            ConstValue::Nil => "Nil",
+            ConstValue::Bool(_) => "Bool",
+            ConstValue::Int(_) => "Int",
+            ConstValue::Float(_) => "Float",
+            ConstValue::String(_) => "String",
+        }
+    }
+
+    /// The value as the runtime prints it.
+    fn render(&self) -> String {
+        match self {
+            ConstValue::Nil => "nil".to_string(),
+            ConstValue::Bool(value) => value.to_string(),
+            ConstValue::Int(value) => value.to_string(),
+            ConstValue::Float(value) => value.to_string(),
+            ConstValue::String(value) => value.clone(),
+        }
+    }
+
+    /// The value as Tea source, used in module interfaces.
+    pub(crate) fn to_source(&self) -> String {
+        match self {
+            ConstValue::Float(value) => format!("{value:?}"),
+            ConstValue::String(value) => {
+                let mut source = String::with_capacity(value.len() + 2);
+                source.push('"');
+                for ch in value.chars() {
+                    match ch {
+                        '"' => source.push_str("\\\""),
+                        '\\' => source.push_str("\\\\"),
+                        '\n' => source.push_str("\\n"),
+                        '\r' => source.push_str("\\r"),
+                        '\t' => source.push_str("\\t"),
+                        other => source.push(other),
+                    }
+                }
+                source.push('"');
+                source
+            }
+            other => other.render(),
+        }
+    }
+
+    pub(crate) fn to_literal(&self) -> Literal {
+        match self {
+            ConstValue::Nil => Literal::Nil,
+            ConstValue::Bool(value) => Literal::Boolean(*value),
+            ConstValue::Int(value) => Literal::Integer(*value),
+            ConstValue::Float(value) => Literal::Float(*value),
+            ConstValue::String(value) => Literal::String(value.clone()),
+        }
+    }
+}
+
+/// What stopped an expression from being evaluated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConstErrorKind {
+    /// The expression calls a function or builds a value at runtime.
+    Runtime,
+    /// The expression names a variable or parameter rather than a constant.
+    NotConstant,
+    /// The operators themselves fail: overflow, division by zero or a type
+    /// mismatch.
+    Invalid,
+}
+
+/// Why an expression could not be evaluated, pointing at the offending part.
+#[derive(Debug, Clone)]
+pub(crate) struct ConstError {
+    pub kind: ConstErrorKind,
+    pub message: String,
+    pub span: SourceSpan,
+}
+
+impl ConstError {
+    fn new(message: impl Into<String>, span: SourceSpan) -> Self {
+        Self::with_kind(ConstErrorKind::Invalid, message, span)
+    }
+
+    fn with_kind(kind: ConstErrorKind, message: impl Into<String>, span: SourceSpan) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+type ConstResult = Result<ConstValue, ConstError>;
+
+/// Evaluate `expression`, resolving identifiers through `lookup`. `lookup`
+/// returns `None` for names that are not compile-time constants in scope.
+pub(crate) fn evaluate(
+    expression: &Expression,
+    lookup: &dyn Fn(&str) -> Option<ConstValue>,
+) -> ConstResult {
+    let span = expression.span;
+    match &expression.kind {
+        ExpressionKind::Literal(literal) => Ok(match literal {
+            Literal::Integer(value) => ConstValue::Int(*value),
+            Literal::Float(value) => ConstValue::Float(*value),
+            Literal::String(value) => ConstValue::String(value.clone()),
+            Literal::Boolean(value) => ConstValue::Bool(*value),
+            Literal::Nil => ConstValue::Nil,
+        }),
+        ExpressionKind::Identifier(identifier) => lookup(&identifier.name).ok_or_else(|| {
+            ConstError::with_kind(
+                ConstErrorKind::NotConstant,
+                format!("'{}' is not a compile-time constant", identifier.name),
+                span,
+            )
+        }),
+        ExpressionKind::Grouping(inner) => evaluate(inner, lookup),
+        ExpressionKind::InterpolatedString(template) => {
+            let mut text = String::new();
+            for part in &template.parts {
+                match part {
+                    InterpolatedStringPart::Literal(literal) => text.push_str(literal),
+                    InterpolatedStringPart::Expression(expression) => {
+                        text.push_str(&evaluate(expression, lookup)?.render());
+                    }
+                    InterpolatedStringPart::Formatted(FormattedInterpolation {
+                        expression,
+                        spec,
+                        spec_span,
+                    }) => {
+                        let value = evaluate(expression, lookup)?;
+                        let rendered = value.render();
+                        let argument = match &value {
+                            ConstValue::Int(number) => FormatArg::Int(*number),
+                            ConstValue::Float(number) => FormatArg::Float(*number),
+                            ConstValue::String(text) => FormatArg::String(text),
+                            _ => FormatArg::Other(&rendered),
+                        };
+                        let formatted = FormatSpec::parse(spec)
+                            .and_then(|spec| spec.apply(argument))
+                            .map_err(|message| ConstError::new(message, *spec_span))?;
+                        text.push_str(&formatted);
+                    }
+                }
+            }
+            Ok(ConstValue::String(text))
+        }
+        ExpressionKind::Unary(unary) => {
+            let operand = evaluate(&unary.operand, lookup)?;
+            match (unary.operator, operand) {
+                (UnaryOperator::Positive, value @ (ConstValue::Int(_) | ConstValue::Float(_))) => {
+                    Ok(value)
+                }
+                (UnaryOperator::Negative, ConstValue::Int(value)) => value
+                    .checked_neg()
+                    .map(ConstValue::Int)
+                    .ok_or_else(|| overflow(span)),
+                (UnaryOperator::Negative, ConstValue::Float(value)) => {
+                    Ok(ConstValue::Float(-value))
+                }
+                (UnaryOperator::Not, ConstValue::Bool(value)) => Ok(ConstValue::Bool(!value)),
+                (operator, value) => Err(ConstError::new(
+                    format!(
+                        "unary '{}' cannot be applied to {}",
+                        unary_symbol(operator),
+                        value.type_name()
+                    ),
+                    span,
+                )),
+            }
+        }
+        ExpressionKind::Binary(binary) => {
+            let left = evaluate(&binary.left, lookup)?;
+            match (binary.operator, &left) {
+                (BinaryOperator::And, ConstValue::Bool(false)) => {
+                    return Ok(ConstValue::Bool(false))
+                }
+                (BinaryOperator::Or, ConstValue::Bool(true)) => return Ok(ConstValue::Bool(true)),
+                (BinaryOperator::Coalesce, ConstValue::Nil) => {
+                    return evaluate(&binary.right, lookup)
+                }
+                (BinaryOperator::Coalesce, _) => return Ok(left),
+                _ => {}
+            }
+            let right = evaluate(&binary.right, lookup)?;
+            evaluate_binary(binary.operator, left, right, span)
+        }
+        ExpressionKind::Conditional(conditional) => {
+            match evaluate(&conditional.condition, lookup)? {
+                ConstValue::Bool(true) => evaluate(&conditional.consequent, lookup),
+                ConstValue::Bool(false) => evaluate(&conditional.alternative, lookup),
+                other => Err(ConstError::new(
+                    format!("condition must be a Bool, found {}", other.type_name()),
+                    conditional.condition.span,
+                )),
+            }
+        }
+        _ => Err(ConstError::with_kind(
+            ConstErrorKind::Runtime,
+            "only literals, constants and operators can be evaluated at compile time",
+            span,
+        )),
+    }
+}
+
+/// Whether `expression` calls a function or builds a value, so it is computed
+/// at runtime even when every name in it is a constant.
+pub(crate) fn is_runtime(expression: &Expression) -> bool {
+    match &expression.kind {
+        ExpressionKind::Literal(_) | ExpressionKind::Identifier(_) => false,
+        ExpressionKind::Grouping(inner) => is_runtime(inner),
+        ExpressionKind::Unary(unary) => is_runtime(&unary.operand),
+        ExpressionKind::Binary(binary) => is_runtime(&binary.left) || is_runtime(&binary.right),
+        ExpressionKind::Conditional(conditional) => {
+            is_runtime(&conditional.condition)
+                || is_runtime(&conditional.consequent)
+                || is_runtime(&conditional.alternative)
+        }
+        ExpressionKind::InterpolatedString(template) => {
+            template.parts.iter().any(|part| match part {
+                InterpolatedStringPart::Literal(_) => false,
+                InterpolatedStringPart::Expression(expression) => is_runtime(expression),
+                InterpolatedStringPart::Formatted(formatted) => is_runtime(&formatted.expression),
+            })
+        }
+        _ => true,
+    }
+}
+
+fn evaluate_binary(
+    operator: BinaryOperator,
+    left: ConstValue,
+    right: ConstValue,
+    span: SourceSpan,
+) -> ConstResult {
+    use ConstValue::{Bool, Float, Int, String};
+
+    let mismatch = |left: &ConstValue, right: &ConstValue| {
+        ConstError::new(
+            format!(
+                "'{}' cannot be applied to {} and {}",
+                binary_symbol(operator),
+                left.type_name(),
+                right.type_name()
+            ),
+            span,
+        )
+    };
+
+    match operator {
+        BinaryOperator::Add => match (&left, &right) {
+            (Int(a), Int(b)) => a.checked_add(*b).map(Int).ok_or_else(|| overflow(span)),
+            (String(a), String(b)) => Ok(String(format!("{a}{b}"))),
+            _ => float_operands(&left, &right)
+                .map(|(a, b)| Float(a + b))
+                .ok_or_else(|| mismatch(&left, &right)),
+        },
+        BinaryOperator::Subtract => match (&left, &right) {
+            (Int(a), Int(b)) => a.checked_sub(*b).map(Int).ok_or_else(|| overflow(span)),
+            _ => float_operands(&left, &right)
+                .map(|(a, b)| Float(a - b))
+                .ok_or_else(|| mismatch(&left, &right)),
+        },
+        BinaryOperator::Multiply => match (&left, &right) {
+            (Int(a), Int(b)) => a.checked_mul(*b).map(Int).ok_or_else(|| overflow(span)),
+            _ => float_operands(&left, &right)
+                .map(|(a, b)| Float(a * b))
+                .ok_or_else(|| mismatch(&left, &right)),
+        },
+        BinaryOperator::Divide | BinaryOperator::Modulo => match (&left, &right) {
+            (Int(_), Int(0)) => Err(ConstError::new("division by zero", span)),
+            (Int(a), Int(b)) => {
+                let result = if matches!(operator, BinaryOperator::Divide) {
+                    a.checked_div(*b)
+                } else {
+                    a.checked_rem(*b)
+                };
+                result.map(Int).ok_or_else(|| overflow(span))
+            }
+            _ => float_operands(&left, &right)
+                .map(|(a, b)| {
+                    if matches!(operator, BinaryOperator::Divide) {
+                        Float(a / b)
+                    } else {
+                        Float(a % b)
+                    }
+                })
+                .ok_or_else(|| mismatch(&left, &right)),
+        },
+        BinaryOperator::Equal | BinaryOperator::NotEqual => {
+            let equal = match (&left, &right) {
+                (Int(a), Int(b)) => a == b,
+                _ => match float_operands(&left, &right) {
+                    Some((a, b)) => a == b,
+                    None => left == right,
+                },
+            };
+            Ok(Bool(equal == matches!(operator, BinaryOperator::Equal)))
+        }
+        BinaryOperator::Greater
+        | BinaryOperator::GreaterEqual
+        | BinaryOperator::Less
+        | BinaryOperator::LessEqual => {
+            let ordering = match (&left, &right) {
+                (Int(a), Int(b)) => Some(a.cmp(b)),
+                _ => float_operands(&left, &right).and_then(|(a, b)| a.partial_cmp(&b)),
+            };
+            let Some(ordering) = ordering else {
+                return Err(mismatch(&left, &right));
+            };
+            Ok(Bool(match operator {
+                BinaryOperator::Greater => ordering.is_gt(),
+                BinaryOperator::GreaterEqual => ordering.is_ge(),
+                BinaryOperator::Less => ordering.is_lt(),
+                _ => ordering.is_le(),
+            }))
+        }
+        BinaryOperator::And | BinaryOperator::Or => match (&left, &right) {
+            (Bool(_), Bool(value)) => Ok(Bool(*value)),
+            _ => Err(mismatch(&left, &right)),
+        },
+        BinaryOperator::Coalesce => Ok(left),
+    }
+}
+
+fn float_operands(left: &ConstValue, right: &ConstValue) -> Option<(f64, f64)> {
+    match (left, right) {
+        (ConstValue::Float(a), ConstValue::Float(b)) => Some((*a, *b)),
+        (ConstValue::Int(a), ConstValue::Float(b)) => Some((*a as f64, *b)),
+        (ConstValue::Float(a), ConstValue::Int(b)) => Some((*a, *b as f64)),
+        _ => None,
+    }
+}
+
+fn overflow(span: SourceSpan) -> ConstError {
+    ConstError::new("arithmetic overflows Int", span)
+}
+
+fn unary_symbol(operator: UnaryOperator) -> &'static str {
+    match operator {
+        UnaryOperator::Positive => "+",
+        UnaryOperator::Negative => "-",
+        UnaryOperator::Not => "not",
+    }
+}
+
+fn binary_symbol(operator: BinaryOperator) -> &'static str {
+    match operator {
+        BinaryOperator::Add => "+",
+        BinaryOperator::Subtract => "-",
+        BinaryOperator::Multiply => "*",
+        BinaryOperator::Divide => "/",
+        BinaryOperator::Modulo => "%",
+        BinaryOperator::Equal => "==",
+        BinaryOperator::NotEqual => "!=",
+        BinaryOperator::Greater => ">",
+        BinaryOperator::GreaterEqual => ">=",
+        BinaryOperator::Less => "<",
+        BinaryOperator::LessEqual => "<=",
+        BinaryOperator::And => "&&",
+        BinaryOperator::Or => "||",
+        BinaryOperator::Coalesce => "??",
+    }
+}
@@ -6,8 +6,8 @@ use anyhow::{bail, Context, Result};
 
 use crate::ast::{
     Block, CatchHandler, CatchKind, ErrorAnnotation, ErrorTypeSpecifier, Expression,
-    ExpressionKind, Identifier, InterpolatedStringPart, LambdaBody, LoopHeader, MatchPattern,
-    Module, SourceSpan, Statement, TypeExpression,
+    ExpressionKind, ForPattern, FormattedInterpolation, Identifier, InterpolatedStringPart,
+    LambdaBody, LoopHeader, MatchPattern, Module, SourceSpan, Statement, TypeExpression,
 };
 use crate::diagnostics::Diagnostics;
 use crate::lexer::{Lexer, Token, TokenKind};
@@ -20,6 +20,32 @@ pub struct ExpandedModule {
     alias_exports: HashMap<String, Vec<String>>,
     alias_export_renames: HashMap<String, HashMap<String, String>>,
     alias_export_docstrings: HashMap<String, HashMap<String, String>>,
+    loaded_sources: Vec<(PathBuf, String)>,
+    entry_path: PathBuf,
+    entry_source: String,
+    origins: Vec<StatementOrigin>,
+    module_imports: HashMap<PathBuf, Vec<PathBuf>>,
+}
+
+/// Where a statement of the expanded module was written. Kept parallel to
+/// `Module::statements` so later stages can split the flattened program back
+/// into per-module compilation units.
+#[derive(Debug, Clone)]
+pub(crate) struct StatementOrigin {
+    pub(crate) path: PathBuf,
+    /// Names the statement declares, as written in its own module.
+    pub(crate) names: Vec<String>,
+    /// Whether the declaration is visible to importers.
+    pub(crate) is_public: bool,
+}
+
+/// The module-graph facts the compiler needs to form compilation units.
+pub(crate) struct ModuleGraph {
+    pub(crate) entry_path: PathBuf,
+    pub(crate) origins: Vec<StatementOrigin>,
+    pub(crate) imports: HashMap<PathBuf, Vec<PathBuf>>,
+    /// Contents of every module in the program, entry file first.
+    pub(crate) sources: Vec<(PathBuf, String)>,
 }
 
 impl ExpandedModule {
@@ -27,6 +53,25 @@ impl ExpandedModule {
         &self.module
     }
 
+    /// Path and contents of every imported module, in the order they were loaded.
+    pub fn loaded_sources(&self) -> &[(PathBuf, String)] {
+        &self.loaded_sources
+    }
+
+    pub(crate) fn take_graph(&mut self) -> ModuleGraph {
+        ModuleGraph {
+            sources: std::iter::once((
+                self.entry_path.clone(),
+                std::mem::take(&mut self.entry_source),
+            ))
+            .chain(self.loaded_sources.iter().cloned())
+            .collect(),
+            entry_path: self.entry_path.clone(),
+            origins: std::mem::take(&mut self.origins),
+            imports: std::mem::take(&mut self.module_imports),
+        }
+    }
+
     pub(crate) fn into_parts(
         self,
     ) -> (
@@ -54,6 +99,27 @@ pub(crate) struct ModuleExpander {
     alias_export_docstrings: HashMap<String, HashMap<String, String>>,
     module_overrides: HashMap<PathBuf, String>,
     loader: Arc<dyn ModuleLoader>,
+    loaded_sources: Vec<(PathBuf, String)>,
+    module_reexports: HashMap<PathBuf, ReExports>,
+    statement_origins: HashMap<PathBuf, Vec<StatementOrigin>>,
+    module_imports: HashMap<PathBuf, Vec<PathBuf>>,
+}
+
+/// Names a module re-exports through `pub use`, mapped to the declarations
+/// they point at inside that module's expanded statements. Submodule
+/// re-exports use dotted keys such as `text.shout`.
+#[derive(Default)]
+struct ReExports {
+    renames: HashMap<String, String>,
+    docstrings: HashMap<String, String>,
+}
+
+#[derive(Clone)]
+struct RenamedModule {
+    statements: Vec<Statement>,
+    export_renames: HashMap<String, String>,
+    docstrings: HashMap<String, String>,
+    private_names: HashSet<String>,
 }
 
 impl ModuleExpander {
@@ -71,16 +137,34 @@ impl ModuleExpander {
             alias_export_docstrings: HashMap::new(),
             module_overrides,
             loader: default_loader(loader)?,
+            loaded_sources: Vec::new(),
+            module_reexports: HashMap::new(),
+            statement_origins: HashMap::new(),
+            module_imports: HashMap::new(),
         })
     }
 
-    pub(crate) fn expand(&mut self, module: &Module, path: &Path) -> Result<ExpandedModule> {
+    pub(crate) fn expand(
+        &mut self,
+        module: &Module,
+        source: &SourceFile,
+    ) -> Result<ExpandedModule> {
+        let path = source.path.as_path();
         let module = self.expand_module(module, path)?;
         Ok(ExpandedModule {
             module,
             alias_exports: self.alias_exports.clone(),
             alias_export_renames: self.alias_export_renames.clone(),
             alias_export_docstrings: self.alias_export_docstrings.clone(),
+            loaded_sources: self.loaded_sources.clone(),
+            entry_path: path.to_path_buf(),
+            entry_source: source.contents.clone(),
+            origins: self
+                .statement_origins
+                .get(path)
+                .cloned()
+                .unwrap_or_default(),
+            module_imports: self.module_imports.clone(),
         })
     }
 
@@ -116,17 +200,40 @@ impl ModuleExpander {
         base_path: &Path,
     ) -> Result<Vec<Statement>> {
         let mut result = Vec::new();
+        // Parallel to `result`: true for statements written in this module,
+        // false for declarations spliced in from imports.
+        let mut own_statements = Vec::new();
+        let mut origins = Vec::new();
         let mut alias_maps: HashMap<String, HashMap<String, String>> = HashMap::new();
+        let mut imported_names: HashMap<String, String> = HashMap::new();
+        let mut imported_spans: HashMap<String, SourceSpan> = HashMap::new();
+        let mut reexports = ReExports::default();
+        // Modules already spliced into this file, without their statements.
+        let mut spliced: HashMap<PathBuf, RenamedModule> = HashMap::new();
 
         for statement in statements {
             match statement {
                 Statement::Use(use_stmt) => {
                     result.push(statement.clone());
+                    own_statements.push(true);
+                    origins.push(Self::statement_origin(statement, base_path));
                     let path = &use_stmt.module_path;
 
                     let resolved_path = match self.loader.resolve_import(base_path, path) {
                         Ok(Some(resolved_path)) => resolved_path,
-                        Ok(None) => continue,
+                        Ok(None) => {
+                            if !use_stmt.items.is_empty() || use_stmt.is_public {
+                                let short_name = path.rsplit('.').next().unwrap_or(path);
+                                self.diagnostics.push_error_with_span(
+                                    format!(
+                                        "built-in module '{}' only supports `use {} from \"{}\"`",
+                                        path, short_name, path
+                                    ),
+                                    Some(use_stmt.module_span),
+                                );
+                            }
+                            continue;
+                        }
                         Err(err) => {
                             let span = use_stmt.module_span;
                             self.diagnostics.push_error_with_span(
@@ -166,8 +273,85 @@ impl ModuleExpander {
                         }
                     };
 
-                    let (mut renamed, export_renames, docstrings) =
-                        self.rename_module_statements(module, &use_stmt.alias.name);
+                    // A module imported twice in one file, say under an alias and
+                    // again for selected names, is spliced in once; later imports
+                    // bind to the first copy so its state and diagnostics are shared.
+                    let first_import = !spliced.contains_key(&canonical);
+                    let RenamedModule {
+                        statements: mut renamed,
+                        export_renames,
+                        docstrings,
+                        private_names,
+                    } = match spliced.get(&canonical) {
+                        Some(first) => first.clone(),
+                        None => {
+                            let renamed = self.rename_module_statements(
+                                module,
+                                &use_stmt.alias.name,
+                                &canonical,
+                            );
+                            spliced.insert(
+                                canonical.clone(),
+                                RenamedModule {
+                                    statements: Vec::new(),
+                                    export_renames: renamed.export_renames.clone(),
+                                    docstrings: renamed.docstrings.clone(),
+                                    private_names: renamed.private_names.clone(),
+                                },
+                            );
+                            renamed
+                        }
+                    };
+
+                    for item in &use_stmt.items {
+                        let local_name = item.alias.as_deref().unwrap_or(&item.name);
+                        let Some(target) = export_renames.get(&item.name) else {
+                            let message = if private_names.contains(&item.name) {
+                                format!(
+                                    "'{}' is not public in module '{}'; mark it `pub` to import it",
+                                    item.name, path
+                                )
+                            } else {
+                                format!("module '{}' has no export named '{}'", path, item.name)
+                            };
+                            self.diagnostics
+                                .push_error_with_span(message, Some(item.span));
+                            continue;
+                        };
+                        if imported_names.contains_key(local_name) {
+                            self.diagnostics.push_error_with_span(
+                                format!("'{local_name}' is already imported"),
+                                Some(item.span),
+                            );
+                            continue;
+                        }
+                        imported_names.insert(local_name.to_string(), target.clone());
+                        imported_spans.insert(local_name.to_string(), item.span);
+                        if use_stmt.is_public {
+                            reexports
+                                .renames
+                                .insert(local_name.to_string(), target.clone());
+                            if let Some(doc) = docstrings.get(&item.name) {
+                                reexports
+                                    .docstrings
+                                    .insert(local_name.to_string(), doc.clone());
+                            }
+                        }
+                    }
+                    if use_stmt.is_public && use_stmt.items.is_empty() {
+                        let alias = &use_stmt.alias.name;
+                        for (export, target) in &export_renames {
+                            reexports
+                                .renames
+                                .insert(format!("{alias}.{export}"), target.clone());
+                        }
+                        for (export, doc) in &docstrings {
+                            reexports
+                                .docstrings
+                                .insert(format!("{alias}.{export}"), doc.clone());
+                        }
+                    }
+
                     let exports = export_renames.keys().cloned().collect();
                     self.alias_exports
                         .insert(use_stmt.alias.name.clone(), exports);
@@ -178,26 +362,109 @@ impl ModuleExpander {
                             .insert(use_stmt.alias.name.clone(), docstrings);
                     }
                     alias_maps.insert(use_stmt.alias.name.clone(), export_renames);
+                    own_statements.extend(std::iter::repeat_n(false, renamed.len()));
+                    if first_import {
+                        origins.extend(
+                            self.statement_origins
+                                .get(&canonical)
+                                .cloned()
+                                .unwrap_or_default(),
+                        );
+                    }
+                    let imports = self
+                        .module_imports
+                        .entry(base_path.to_path_buf())
+                        .or_default();
+                    if !imports.contains(&canonical) {
+                        imports.push(canonical);
+                    }
                     result.append(&mut renamed);
                 }
-                _ => result.push(statement.clone()),
+                _ => {
+                    result.push(statement.clone());
+                    own_statements.push(true);
+                    origins.push(Self::statement_origin(statement, base_path));
+                }
+            }
+        }
+
+        if !imported_names.is_empty() {
+            for (statement, own) in result.iter_mut().zip(&own_statements) {
+                if !*own {
+                    continue;
+                }
+                for name in Self::declared_names(statement) {
+                    if let Some(import_span) = imported_spans.get(&name) {
+                        self.diagnostics.push_error_with_span(
+                            format!("'{name}' is declared here but also imported"),
+                            Some(*import_span),
+                        );
+                    }
+                }
+                // Imported names are rewritten without regard to scope, so a
+                // local that reuses one would lose its uses to the import.
+                let mut locals = Vec::new();
+                Self::statement_local_bindings(statement, &mut locals);
+                for (kind, name, span) in locals {
+                    if let Some(import_span) = imported_spans.get(&name) {
+                        self.diagnostics.push_error_with_span(
+                            format!(
+                                "redeclaration of {kind} '{name}' shadows the name imported at line {}, column {}",
+                                import_span.line, import_span.column
+                            ),
+                            Some(span),
+                        );
+                    }
+                }
+                self.rewrite_statement_identifiers(statement, &imported_names);
             }
         }
+        if !reexports.renames.is_empty() {
+            self.module_reexports
+                .insert(base_path.to_path_buf(), reexports);
+        }
 
         self.rewrite_alias_access(&mut result, &alias_maps);
+        self.statement_origins
+            .insert(base_path.to_path_buf(), origins);
 
         Ok(result)
     }
 
-    fn rename_module_statements(
-        &self,
-        module: Module,
-        alias: &str,
-    ) -> (
-        Vec<Statement>,
-        HashMap<String, String>,
-        HashMap<String, String>,
-    ) {
+    fn statement_origin(statement: &Statement, path: &Path) -> StatementOrigin {
+        let is_public = match statement {
+            Statement::Function(function) => function.is_public,
+            Statement::Var(var_stmt) => var_stmt.is_public && var_stmt.is_const,
+            Statement::Struct(struct_stmt) => struct_stmt.is_public,
+            Statement::Union(union_stmt) => union_stmt.is_public,
+            Statement::Enum(enum_stmt) => enum_stmt.is_public,
+            Statement::Error(error_stmt) => error_stmt.is_public,
+            _ => false,
+        };
+        StatementOrigin {
+            path: path.to_path_buf(),
+            names: Self::declared_names(statement),
+            is_public,
+        }
+    }
+
+    pub(crate) fn declared_names(statement: &Statement) -> Vec<String> {
+        match statement {
+            Statement::Function(function) => vec![function.name.clone()],
+            Statement::Var(var_stmt) => var_stmt
+                .bindings
+                .iter()
+                .map(|binding| binding.name.clone())
+                .collect(),
+            Statement::Struct(struct_stmt) => vec![struct_stmt.name.clone()],
+            Statement::Union(union_stmt) => vec![union_stmt.name.clone()],
+            Statement::Enum(enum_stmt) => vec![enum_stmt.name.clone()],
+            Statement::Error(error_stmt) => vec![error_stmt.name.clone()],
+            _ => Vec::new(),
+        }
+    }
+
+    fn rename_module_statements(&self, module: Module, alias: &str, path: &Path) -> RenamedModule {
         let mut all_renames: HashMap<String, String> = HashMap::new();
         let mut export_renames: HashMap<String, String> = HashMap::new();
         let mut docstrings: HashMap<String, String> = HashMap::new();
@@ -293,6 +560,29 @@ impl ModuleExpander {
             }
         }
 
+        if let Some(reexports) = self.module_reexports.get(path) {
+            for (name, target) in &reexports.renames {
+                if let Some(renamed) = all_renames.get(target) {
+                    export_renames.insert(name.clone(), renamed.clone());
+                }
+            }
+            for (name, doc) in &reexports.docstrings {
+                docstrings.insert(name.clone(), doc.clone());
+            }
+        }
+
+        let private_names = all_renames
+            .keys()
+            .filter(|name| {
+                !name.starts_with("__")
+                    && !export_renames.contains_key(*name)
+                    && !module.statements.iter().any(|statement| {
+                        matches!(statement, Statement::Use(use_stmt) if use_stmt.alias.name == **name)
+                    })
+            })
+            .cloned()
+            .collect();
+
         let mut renamed = Vec::new();
         for mut statement in module.statements {
             match &mut statement {
@@ -368,7 +658,12 @@ impl ModuleExpander {
             renamed.push(statement);
         }
 
-        (renamed, export_renames, docstrings)
+        RenamedModule {
+            statements: renamed,
+            export_renames,
+            docstrings,
+            private_names,
+        }
     }
 
     fn rewrite_alias_access(
@@ -384,6 +679,237 @@ impl ModuleExpander {
         }
     }
 
+    /// Names bound inside `statement`: parameters, local variables and
+    /// functions, loop variables and optional and catch bindings. The names a
+    /// top-level statement declares come from `declared_names` instead.
+    fn statement_local_bindings(
+        statement: &Statement,
+        out: &mut Vec<(&'static str, String, SourceSpan)>,
+    ) {
+        match statement {
+            Statement::Var(var_stmt) => {
+                for binding in &var_stmt.bindings {
+                    if let Some(initializer) = &binding.initializer {
+                        Self::expression_local_bindings(initializer, out);
+                    }
+                }
+            }
+            Statement::Function(function_stmt) => {
+                for parameter in &function_stmt.parameters {
+                    out.push(("parameter", parameter.name.clone(), parameter.span));
+                    if let Some(default_value) = &parameter.default_value {
+                        Self::expression_local_bindings(default_value, out);
+                    }
+                }
+                Self::block_local_bindings(&function_stmt.body, out);
+            }
+            Statement::Test(test_stmt) => Self::block_local_bindings(&test_stmt.body, out),
+            Statement::Struct(struct_stmt) => {
+                for field in &struct_stmt.fields {
+                    if let Some(default_value) = &field.default_value {
+                        Self::expression_local_bindings(default_value, out);
+                    }
+                }
+            }
+            Statement::Conditional(cond_stmt) => {
+                if let Some(binding) = &cond_stmt.binding {
+                    out.push(("variable", binding.name.clone(), binding.span));
+                }
+                Self::expression_local_bindings(&cond_stmt.condition, out);
+                Self::block_local_bindings(&cond_stmt.consequent, out);
+                if let Some(alternative) = &cond_stmt.alternative {
+                    Self::block_local_bindings(alternative, out);
+                }
+            }
+            Statement::Loop(loop_stmt) => {
+                match &loop_stmt.header {
+                    LoopHeader::For { pattern, iterator } => {
+                        let names = match pattern {
+                            ForPattern::Single(name) => vec![name],
+                            ForPattern::Pair(first, second) => vec![first, second],
+                        };
+                        for name in names {
+                            out.push(("variable", name.name.clone(), name.span));
+                        }
+                        Self::expression_local_bindings(iterator, out);
+                    }
+                    LoopHeader::Condition(condition) => {
+                        Self::expression_local_bindings(condition, out);
+                    }
+                    LoopHeader::Binding { binding, value } => {
+                        out.push(("variable", binding.name.clone(), binding.span));
+                        Self::expression_local_bindings(value, out);
+                    }
+                }
+                Self::block_local_bindings(&loop_stmt.body, out);
+            }
+            Statement::Guard(guard_stmt) => {
+                if let Some(binding) = &guard_stmt.binding {
+                    out.push(("variable", binding.name.clone(), binding.span));
+                }
+                Self::expression_local_bindings(&guard_stmt.condition, out);
+                Self::block_local_bindings(&guard_stmt.alternative, out);
+            }
+            Statement::Return(ret_stmt) => {
+                if let Some(expression) = &ret_stmt.expression {
+                    Self::expression_local_bindings(expression, out);
+                }
+            }
+            Statement::Throw(throw_stmt) => {
+                Self::expression_local_bindings(&throw_stmt.expression, out);
+            }
+            Statement::Yield(yield_stmt) => {
+                Self::expression_local_bindings(&yield_stmt.expression, out);
+            }
+            Statement::Match(match_stmt) => {
+                Self::expression_local_bindings(&match_stmt.scrutinee, out);
+                for arm in &match_stmt.arms {
+                    Self::block_local_bindings(&arm.block, out);
+                }
+            }
+            Statement::Expression(expr_stmt) => {
+                Self::expression_local_bindings(&expr_stmt.expression, out);
+            }
+            Statement::Use(_)
+            | Statement::Union(_)
+            | Statement::Enum(_)
+            | Statement::Error(_)
+            | Statement::Break(_)
+            | Statement::Continue(_) => {}
+        }
+    }
+
+    fn block_local_bindings(block: &Block, out: &mut Vec<(&'static str, String, SourceSpan)>) {
+        for statement in &block.statements {
+            // Unlike top-level ones, these declarations are locals.
+            match statement {
+                Statement::Var(var_stmt) => {
+                    for binding in &var_stmt.bindings {
+                        out.push(("variable", binding.name.clone(), binding.span));
+                    }
+                }
+                Statement::Function(function_stmt) => {
+                    out.push((
+                        "function",
+                        function_stmt.name.clone(),
+                        function_stmt.name_span,
+                    ));
+                }
+                _ => {}
+            }
+            Self::statement_local_bindings(statement, out);
+        }
+    }
+
+    fn expression_local_bindings(
+        expression: &Expression,
+        out: &mut Vec<(&'static str, String, SourceSpan)>,
+    ) {
+        match &expression.kind {
+            ExpressionKind::Identifier(_) | ExpressionKind::Literal(_) => {}
+            ExpressionKind::InterpolatedString(template) => {
+                for part in &template.parts {
+                    if let InterpolatedStringPart::Expression(expr)
+                    | InterpolatedStringPart::Formatted(FormattedInterpolation {
+                        expression: expr,
+                        ..
+                    }) = part
+                    {
+                        Self::expression_local_bindings(expr, out);
+                    }
+                }
+            }
+            ExpressionKind::List(list) => {
+                for element in &list.elements {
+                    Self::expression_local_bindings(element, out);
+                }
+            }
+            ExpressionKind::Dict(dict) => {
+                for entry in &dict.entries {
+                    Self::expression_local_bindings(&entry.value, out);
+                }
+            }
+            ExpressionKind::Unary(unary) => Self::expression_local_bindings(&unary.operand, out),
+            ExpressionKind::Binary(binary) => {
+                Self::expression_local_bindings(&binary.left, out);
+                Self::expression_local_bindings(&binary.right, out);
+            }
+            ExpressionKind::Is(is_expr) => Self::expression_local_bindings(&is_expr.value, out),
+            ExpressionKind::Call(call) => {
+                Self::expression_local_bindings(&call.callee, out);
+                for argument in &call.arguments {
+                    Self::expression_local_bindings(&argument.expression, out);
+                }
+            }
+            ExpressionKind::Member(member) => Self::expression_local_bindings(&member.object, out),
+            ExpressionKind::Index(index) => {
+                Self::expression_local_bindings(&index.object, out);
+                Self::expression_local_bindings(&index.index, out);
+            }
+            ExpressionKind::Range(range) => {
+                for bound in [&range.start, &range.end, &range.step]
+                    .into_iter()
+                    .flatten()
+                {
+                    Self::expression_local_bindings(bound, out);
+                }
+            }
+            ExpressionKind::Lambda(lambda) => {
+                for parameter in &lambda.parameters {
+                    out.push(("parameter", parameter.name.clone(), parameter.span));
+                    if let Some(default_value) = &parameter.default_value {
+                        Self::expression_local_bindings(default_value, out);
+                    }
+                }
+                match &lambda.body {
+                    LambdaBody::Expression(expr) => Self::expression_local_bindings(expr, out),
+                    LambdaBody::Block(block) => Self::block_local_bindings(block, out),
+                }
+            }
+            ExpressionKind::Assignment(assignment) => {
+                Self::expression_local_bindings(&assignment.target, out);
+                Self::expression_local_bindings(&assignment.value, out);
+            }
+            ExpressionKind::Conditional(cond) => {
+                Self::expression_local_bindings(&cond.condition, out);
+                Self::expression_local_bindings(&cond.consequent, out);
+                Self::expression_local_bindings(&cond.alternative, out);
+            }
+            ExpressionKind::Match(match_expr) => {
+                Self::expression_local_bindings(&match_expr.scrutinee, out);
+                for arm in &match_expr.arms {
+                    Self::expression_local_bindings(&arm.expression, out);
+                }
+            }
+            ExpressionKind::Try(try_expr) => {
+                Self::expression_local_bindings(&try_expr.expression, out);
+                if let Some(clause) = &try_expr.catch {
+                    if let Some(binding) = &clause.binding {
+                        out.push(("variable", binding.name.clone(), binding.span));
+                    }
+                    match &clause.kind {
+                        CatchKind::Fallback(expr) => Self::expression_local_bindings(expr, out),
+                        CatchKind::Arms(arms) => {
+                            for arm in arms {
+                                match &arm.handler {
+                                    CatchHandler::Expression(expr) => {
+                                        Self::expression_local_bindings(expr, out);
+                                    }
+                                    CatchHandler::Block(block) => {
+                                        Self::block_local_bindings(block, out);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            ExpressionKind::Unwrap(inner) | ExpressionKind::Grouping(inner) => {
+                Self::expression_local_bindings(inner, out);
+            }
+        }
+    }
+
     fn rewrite_block_identifiers(&self, block: &mut Block, rename_map: &HashMap<String, String>) {
         for statement in &mut block.statements {
             self.rewrite_statement_identifiers(statement, rename_map);
@@ -418,12 +944,15 @@ impl ModuleExpander {
         type_expression: &mut TypeExpression,
         rename_map: &HashMap<String, String>,
     ) {
+        let mut after_dot = false;
         for token in &mut type_expression.tokens {
-            if matches!(token.kind, TokenKind::Identifier) {
+            // `alias.Name` refers to the module's export, not a local `Name`.
+            if matches!(token.kind, TokenKind::Identifier) && !after_dot {
                 if let Some(new_name) = rename_map.get(&token.lexeme) {
                     token.lexeme = new_name.clone();
                 }
             }
+            after_dot = matches!(token.kind, TokenKind::Dot);
         }
     }
 
@@ -447,12 +976,27 @@ impl ModuleExpander {
                 && matches!(third.kind, TokenKind::Identifier)
             {
                 if let Some(map) = alias_maps.get(&first.lexeme) {
-                    match map.get(&third.lexeme) {
-                        Some(replacement) => {
+                    // Re-exported submodules make longer chains such as
+                    // `pkg.text.Point`; prefer the longest exported path.
+                    let mut segments = vec![third.lexeme.clone()];
+                    let mut end = index + 3;
+                    while end + 1 < type_expression.tokens.len()
+                        && matches!(type_expression.tokens[end].kind, TokenKind::Dot)
+                        && matches!(type_expression.tokens[end + 1].kind, TokenKind::Identifier)
+                    {
+                        segments.push(type_expression.tokens[end + 1].lexeme.clone());
+                        end += 2;
+                    }
+                    let matched = (1..=segments.len()).rev().find_map(|count| {
+                        map.get(&segments[..count].join(".")).map(|r| (count, r))
+                    });
+                    match matched {
+                        Some((count, replacement)) => {
                             type_expression.tokens[index].lexeme = replacement.clone();
                             type_expression.tokens[index].kind = TokenKind::Identifier;
-                            type_expression.tokens.remove(index + 2);
-                            type_expression.tokens.remove(index + 1);
+                            type_expression
+                                .tokens
+                                .drain(index + 1..index + 1 + 2 * count);
                             continue;
                         }
                         None => {
@@ -517,6 +1061,9 @@ impl ModuleExpander {
                         &mut field.type_annotation,
                         rename_map,
                     );
+                    if let Some(default_value) = &mut field.default_value {
+                        self.rewrite_expression_identifiers(default_value, rename_map);
+                    }
                 }
             }
             Statement::Union(union_stmt) => {
@@ -550,12 +1097,16 @@ impl ModuleExpander {
                     LoopHeader::For { iterator, .. } => {
                         self.rewrite_expression_identifiers(iterator, rename_map);
                     }
-                    LoopHeader::Condition(expr) => {
+                    LoopHeader::Condition(expr) | LoopHeader::Binding { value: expr, .. } => {
                         self.rewrite_expression_identifiers(expr, rename_map);
                     }
                 }
                 self.rewrite_block_identifiers(&mut loop_stmt.body, rename_map);
             }
+            Statement::Guard(guard_stmt) => {
+                self.rewrite_expression_identifiers(&mut guard_stmt.condition, rename_map);
+                self.rewrite_block_identifiers(&mut guard_stmt.alternative, rename_map);
+            }
             Statement::Return(ret_stmt) => {
                 if let Some(expression) = &mut ret_stmt.expression {
                     self.rewrite_expression_identifiers(expression, rename_map);
@@ -565,6 +1116,9 @@ impl ModuleExpander {
             Statement::Throw(throw_stmt) => {
                 self.rewrite_expression_identifiers(&mut throw_stmt.expression, rename_map);
             }
+            Statement::Yield(yield_stmt) => {
+                self.rewrite_expression_identifiers(&mut yield_stmt.expression, rename_map);
+            }
             Statement::Match(match_stmt) => {
                 self.rewrite_expression_identifiers(&mut match_stmt.scrutinee, rename_map);
                 for arm in &mut match_stmt.arms {
@@ -602,7 +1156,12 @@ impl ModuleExpander {
             ExpressionKind::Literal(_) => {}
             ExpressionKind::InterpolatedString(template) => {
                 for part in &mut template.parts {
-                    if let InterpolatedStringPart::Expression(expr) = part {
+                    if let InterpolatedStringPart::Expression(expr)
+                    | InterpolatedStringPart::Formatted(FormattedInterpolation {
+                        expression: expr,
+                        ..
+                    }) = part
+                    {
                         self.rewrite_expression_identifiers(expr, rename_map);
                     }
                 }
@@ -642,17 +1201,31 @@ impl ModuleExpander {
                 self.rewrite_expression_identifiers(&mut index.index, rename_map);
             }
             ExpressionKind::Range(range) => {
-                self.rewrite_expression_identifiers(&mut range.start, rename_map);
-                self.rewrite_expression_identifiers(&mut range.end, rename_map);
+                for bound in [&mut range.start, &mut range.end, &mut range.step]
+                    .into_iter()
+                    .flatten()
+                {
+                    self.rewrite_expression_identifiers(bound, rename_map);
+                }
             }
-            ExpressionKind::Lambda(lambda) => match &mut lambda.body {
-                LambdaBody::Expression(expr) => {
-                    self.rewrite_expression_identifiers(expr, rename_map);
+            ExpressionKind::Lambda(lambda) => {
+                for parameter in &mut lambda.parameters {
+                    if let Some(type_annotation) = &mut parameter.type_annotation {
+                        self.rewrite_type_expression_identifiers(type_annotation, rename_map);
+                    }
+                    if let Some(default_value) = &mut parameter.default_value {
+                        self.rewrite_expression_identifiers(default_value, rename_map);
+                    }
                 }
-                LambdaBody::Block(block) => {
-                    self.rewrite_block_identifiers(block, rename_map);
+                match &mut lambda.body {
+                    LambdaBody::Expression(expr) => {
+                        self.rewrite_expression_identifiers(expr, rename_map);
+                    }
+                    LambdaBody::Block(block) => {
+                        self.rewrite_block_identifiers(block, rename_map);
+                    }
                 }
-            },
+            }
             ExpressionKind::Assignment(assignment) => {
                 self.rewrite_expression_identifiers(&mut assignment.target, rename_map);
                 self.rewrite_expression_identifiers(&mut assignment.value, rename_map);
@@ -766,6 +1339,9 @@ impl ModuleExpander {
             Statement::Struct(struct_stmt) => {
                 for field in &mut struct_stmt.fields {
                     self.rewrite_type_expression_alias(&mut field.type_annotation, alias_maps);
+                    if let Some(default_value) = &mut field.default_value {
+                        self.rewrite_expression_alias(default_value, alias_maps);
+                    }
                 }
             }
             Statement::Union(union_stmt) => {
@@ -793,12 +1369,16 @@ impl ModuleExpander {
                     LoopHeader::For { iterator, .. } => {
                         self.rewrite_expression_alias(iterator, alias_maps);
                     }
-                    LoopHeader::Condition(expr) => {
+                    LoopHeader::Condition(expr) | LoopHeader::Binding { value: expr, .. } => {
                         self.rewrite_expression_alias(expr, alias_maps);
                     }
                 }
                 self.rewrite_block_alias(&mut loop_stmt.body, alias_maps);
             }
+            Statement::Guard(guard_stmt) => {
+                self.rewrite_expression_alias(&mut guard_stmt.condition, alias_maps);
+                self.rewrite_block_alias(&mut guard_stmt.alternative, alias_maps);
+            }
             Statement::Return(ret_stmt) => {
                 if let Some(expression) = &mut ret_stmt.expression {
                     self.rewrite_expression_alias(expression, alias_maps);
@@ -808,6 +1388,9 @@ impl ModuleExpander {
             Statement::Throw(throw_stmt) => {
                 self.rewrite_expression_alias(&mut throw_stmt.expression, alias_maps);
             }
+            Statement::Yield(yield_stmt) => {
+                self.rewrite_expression_alias(&mut yield_stmt.expression, alias_maps);
+            }
             Statement::Match(match_stmt) => {
                 self.rewrite_expression_alias(&mut match_stmt.scrutinee, alias_maps);
                 for arm in &mut match_stmt.arms {
@@ -909,6 +1492,38 @@ impl ModuleExpander {
         expression: &mut Expression,
         alias_maps: &HashMap<String, HashMap<String, String>>,
     ) {
+        if let Some(path) = member_path(expression) {
+            if path.len() > 2 {
+                if let Some(map) = alias_maps.get(&path[0]) {
+                    let ExpressionKind::Member(member) = &expression.kind else {
+                        unreachable!("member paths come from member expressions");
+                    };
+                    let property_span = member.property_span;
+                    let key = path[1..].join(".");
+                    if let Some(replacement) = map.get(&key) {
+                        expression.kind = ExpressionKind::Identifier(Identifier {
+                            name: replacement.clone(),
+                            span: property_span,
+                        });
+                        return;
+                    }
+                    let parent = path[1..path.len() - 1].join(".");
+                    if is_submodule(map, &parent) {
+                        self.diagnostics.push_error_with_span(
+                            format!(
+                                "module '{}.{}' has no export named '{}'",
+                                path[0],
+                                parent,
+                                path[path.len() - 1]
+                            ),
+                            Some(property_span),
+                        );
+                        return;
+                    }
+                }
+            }
+        }
+
         match &mut expression.kind {
             ExpressionKind::Member(member) => {
                 self.rewrite_expression_alias(&mut member.object, alias_maps);
@@ -922,6 +1537,7 @@ impl ModuleExpander {
                                 });
                                 return;
                             }
+                            None if is_submodule(map, &member.property) => {}
                             None => {
                                 self.diagnostics.push_error_with_span(
                                     format!(
@@ -937,7 +1553,12 @@ impl ModuleExpander {
             }
             ExpressionKind::InterpolatedString(template) => {
                 for part in &mut template.parts {
-                    if let InterpolatedStringPart::Expression(expr) = part {
+                    if let InterpolatedStringPart::Expression(expr)
+                    | InterpolatedStringPart::Formatted(FormattedInterpolation {
+                        expression: expr,
+                        ..
+                    }) = part
+                    {
                         self.rewrite_expression_alias(expr, alias_maps);
                     }
                 }
@@ -974,17 +1595,31 @@ impl ModuleExpander {
                 }
             }
             ExpressionKind::Range(range) => {
-                self.rewrite_expression_alias(&mut range.start, alias_maps);
-                self.rewrite_expression_alias(&mut range.end, alias_maps);
+                for bound in [&mut range.start, &mut range.end, &mut range.step]
+                    .into_iter()
+                    .flatten()
+                {
+                    self.rewrite_expression_alias(bound, alias_maps);
+                }
             }
-            ExpressionKind::Lambda(lambda) => match &mut lambda.body {
-                LambdaBody::Expression(expr) => {
-                    self.rewrite_expression_alias(expr, alias_maps);
+            ExpressionKind::Lambda(lambda) => {
+                for parameter in &mut lambda.parameters {
+                    if let Some(type_annotation) = &mut parameter.type_annotation {
+                        self.rewrite_type_expression_alias(type_annotation, alias_maps);
+                    }
+                    if let Some(default_value) = &mut parameter.default_value {
+                        self.rewrite_expression_alias(default_value, alias_maps);
+                    }
                 }
-                LambdaBody::Block(block) => {
-                    self.rewrite_block_alias(block, alias_maps);
+                match &mut lambda.body {
+                    LambdaBody::Expression(expr) => {
+                        self.rewrite_expression_alias(expr, alias_maps);
+                    }
+                    LambdaBody::Block(block) => {
+                        self.rewrite_block_alias(block, alias_maps);
+                    }
                 }
-            },
+            }
             ExpressionKind::Conditional(cond) => {
                 self.rewrite_expression_alias(&mut cond.condition, alias_maps);
                 self.rewrite_expression_alias(&mut cond.consequent, alias_maps);
@@ -1069,6 +1704,8 @@ impl ModuleExpander {
     }
 
     fn load_module_from_contents(&mut self, path: &Path, contents: String) -> Result<Module> {
+        self.loaded_sources
+            .push((path.to_path_buf(), contents.clone()));
         let source = SourceFile::new(SourceId(self.next_source_id), path.to_path_buf(), contents);
         self.next_source_id += 1;
 
@@ -1090,6 +1727,24 @@ impl ModuleExpander {
     }
 }
 
+/// `a.b.c` as `["a", "b", "c"]` when every segment is a plain name.
+fn member_path(expression: &Expression) -> Option<Vec<String>> {
+    match &expression.kind {
+        ExpressionKind::Identifier(identifier) => Some(vec![identifier.name.clone()]),
+        ExpressionKind::Member(member) => {
+            let mut path = member_path(&member.object)?;
+            path.push(member.property.clone());
+            Some(path)
+        }
+        _ => None,
+    }
+}
+
+fn is_submodule(exports: &HashMap<String, String>, name: &str) -> bool {
+    let prefix = format!("{name}.");
+    exports.keys().any(|export| export.starts_with(&prefix))
+}
+
 fn default_loader(loader: Option<Arc<dyn ModuleLoader>>) -> Result<Arc<dyn ModuleLoader>> {
     if let Some(loader) = loader {
         return Ok(loader);
@@ -1097,7 +1752,7 @@ fn default_loader(loader: Option<Arc<dyn ModuleLoader>>) -> Result<Arc<dyn Modul
 
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Ok(Arc::new(crate::loader::NativeModuleLoader))
+        Ok(Arc::new(crate::loader::NativeModuleLoader::default()))
     }
 
     #[cfg(target_arch = "wasm32")]
@@ -1628,7 +1628,9 @@ fn is_block_closer(code: &str) -> bool {
 }
 
 fn opens_block(code: &str) -> bool {
-    const BLOCK_KEYWORDS: &[&str] = &["def", "if", "for", "while", "test", "else", "match"];
+    const BLOCK_KEYWORDS: &[&str] = &[
+        "def", "if", "for", "while", "guard", "test", "else", "match",
+    ];
 
     if BLOCK_KEYWORDS
         .iter()
@@ -1659,7 +1661,7 @@ fn is_function_header(code: &str) -> bool {
 }
 
 fn is_conditional_header(code: &str) -> bool {
-    if line_starts_with_keyword(code, "if") {
+    if line_starts_with_keyword(code, "if") || line_starts_with_keyword(code, "guard") {
         return true;
     }
 
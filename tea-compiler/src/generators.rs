@@ -0,0 +1,41 @@
+//! Recognizes generator functions.
+//!
+//! A function is a generator when its own body contains a `yield` statement.
+//! Yields inside nested lambdas belong to the lambda, which the type checker
+//! rejects, so they never turn the enclosing function into a generator.
+
+use crate::ast::{Block, FunctionStatement, Statement};
+
+pub(crate) fn is_generator(function: &FunctionStatement) -> bool {
+    block_yields(&function.body)
+}
+
+fn block_yields(block: &Block) -> bool {
+    block.statements.iter().any(statement_yields)
+}
+
+fn statement_yields(statement: &Statement) -> bool {
+    match statement {
+        Statement::Yield(_) => true,
+        Statement::Conditional(conditional) => {
+            block_yields(&conditional.consequent)
+                || conditional.alternative.as_ref().is_some_and(block_yields)
+        }
+        Statement::Loop(loop_stmt) => block_yields(&loop_stmt.body),
+        Statement::Guard(guard) => block_yields(&guard.alternative),
+        Statement::Match(match_stmt) => match_stmt.arms.iter().any(|arm| block_yields(&arm.block)),
+        Statement::Use(_)
+        | Statement::Var(_)
+        | Statement::Function(_)
+        | Statement::Test(_)
+        | Statement::Struct(_)
+        | Statement::Union(_)
+        | Statement::Enum(_)
+        | Statement::Error(_)
+        | Statement::Break(_)
+        | Statement::Continue(_)
+        | Statement::Throw(_)
+        | Statement::Return(_)
+        | Statement::Expression(_) => false,
+    }
+}
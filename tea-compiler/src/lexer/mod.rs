@@ -35,6 +35,7 @@ pub enum TokenKind {
     InterpolatedStringSegment(String),
     InterpolatedStringExprStart,
     InterpolatedStringExprEnd,
+    InterpolatedStringFormatSpec(String),
     InterpolatedStringEnd,
     DocComment(String),
     BooleanLiteral(bool),
@@ -88,6 +89,7 @@ pub enum Keyword {
     Var,
     Const,
     Def,
+    Extern,
     Pub,
     Test,
     Match,
@@ -98,8 +100,10 @@ pub enum Keyword {
     Try,
     Catch,
     Throw,
+    Yield,
     For,
     While,
+    Guard,
     Break,
     Continue,
     Return,
@@ -126,7 +130,13 @@ pub struct Lexer<'a> {
 #[derive(Debug, Clone)]
 enum TemplateState {
     Literal,
-    Expression { brace_depth: usize },
+    Expression {
+        brace_depth: usize,
+        /// Open parentheses and brackets, inside which `:` belongs to the expression.
+        group_depth: usize,
+        /// Whether the lexer is between the pipes of a lambda parameter list.
+        in_lambda_params: bool,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -524,7 +534,11 @@ impl<'a> Lexer<'a> {
                         expr_start_column,
                     ));
                     if let Some(ctx) = self.template_stack.get_mut(idx) {
-                        ctx.state = TemplateState::Expression { brace_depth: 0 };
+                        ctx.state = TemplateState::Expression {
+                            brace_depth: 0,
+                            group_depth: 0,
+                            in_lambda_params: false,
+                        };
                         ctx.segment_start_position = self.position;
                         ctx.segment_start_line = self.line;
                         ctx.segment_start_column = self.column;
@@ -593,15 +607,50 @@ impl<'a> Lexer<'a> {
                 self.pending_tokens.push_back(token);
                 if let TemplateState::Expression {
                     ref mut brace_depth,
+                    ..
                 } = self.template_stack[idx].state
                 {
                     *brace_depth += 1;
                 }
                 Ok(true)
             }
+            '(' | '[' | ')' | ']' | '|' => {
+                // Track nesting so a top-level `:` can start a format specifier, then
+                // let the regular lexer produce the token.
+                let closes_pipe = ch == '|' && self.peek_next_char() != Some('|');
+                if let TemplateState::Expression {
+                    ref mut group_depth,
+                    ref mut in_lambda_params,
+                    ..
+                } = self.template_stack[idx].state
+                {
+                    match ch {
+                        '(' | '[' => *group_depth += 1,
+                        ')' | ']' => *group_depth = group_depth.saturating_sub(1),
+                        _ if closes_pipe => *in_lambda_params = !*in_lambda_params,
+                        _ => {}
+                    }
+                }
+                Ok(false)
+            }
+            ':' => {
+                let at_top_level = matches!(
+                    self.template_stack[idx].state,
+                    TemplateState::Expression {
+                        brace_depth: 0,
+                        group_depth: 0,
+                        in_lambda_params: false,
+                    }
+                );
+                if !at_top_level {
+                    return Ok(false);
+                }
+                self.lex_interpolated_format_spec(idx)?;
+                Ok(true)
+            }
             '}' => {
                 let brace_depth = match self.template_stack[idx].state {
-                    TemplateState::Expression { brace_depth } => brace_depth,
+                    TemplateState::Expression { brace_depth, .. } => brace_depth,
                     _ => return Ok(false),
                 };
 
@@ -630,6 +679,7 @@ impl<'a> Lexer<'a> {
                     if let Some(ctx) = self.template_stack.get_mut(idx) {
                         if let TemplateState::Expression {
                             ref mut brace_depth,
+                            ..
                         } = ctx.state
                         {
                             *brace_depth -= 1;
@@ -642,6 +692,38 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    /// Lex the raw text between a top-level `:` and the closing `}` of an interpolation.
+    fn lex_interpolated_format_spec(&mut self, idx: usize) -> Result<()> {
+        let line = self.line;
+        let column = self.column;
+        self.advance_char(); // consume ':'
+        let start = self.position;
+        loop {
+            match self.peek_char() {
+                Some('}') => break,
+                Some('`') | None => {
+                    let ctx = &self.template_stack[idx];
+                    return Err(LexerError::UnterminatedStringLiteral {
+                        line: ctx.start_line,
+                        column: ctx.start_column,
+                    }
+                    .into());
+                }
+                Some(_) => {
+                    self.advance_char();
+                }
+            }
+        }
+        let spec = self.slice(start, self.position).to_string();
+        self.pending_tokens.push_back(Token::new(
+            TokenKind::InterpolatedStringFormatSpec(spec.clone()),
+            format!(":{spec}"),
+            line,
+            column,
+        ));
+        Ok(())
+    }
+
     fn lex_string(&mut self) -> Result<Token> {
         let start = self.position;
         let start_line = self.line;
@@ -1213,6 +1295,7 @@ fn keyword_from_lexeme(lexeme: &str) -> Option<Keyword> {
         "var" => Some(Keyword::Var),
         "const" => Some(Keyword::Const),
         "def" => Some(Keyword::Def),
+        "extern" => Some(Keyword::Extern),
         "pub" => Some(Keyword::Pub),
         "test" => Some(Keyword::Test),
         "match" => Some(Keyword::Match),
@@ -1223,8 +1306,10 @@ fn keyword_from_lexeme(lexeme: &str) -> Option<Keyword> {
         "try" => Some(Keyword::Try),
         "catch" => Some(Keyword::Catch),
         "throw" => Some(Keyword::Throw),
+        "yield" => Some(Keyword::Yield),
         "for" => Some(Keyword::For),
         "while" => Some(Keyword::While),
+        "guard" => Some(Keyword::Guard),
         "break" => Some(Keyword::Break),
         "continue" => Some(Keyword::Continue),
         "return" => Some(Keyword::Return),
@@ -2,18 +2,24 @@ mod analysis;
 mod ast;
 mod browser;
 mod compiler;
+mod consteval;
 mod diagnostics;
 mod expansion;
 mod formatter;
+mod generators;
 mod lexer;
 mod loader;
+#[cfg(not(target_arch = "wasm32"))]
+mod package;
 mod parser;
 #[cfg(not(target_arch = "wasm32"))]
 mod reference;
 mod resolver;
 mod source;
 mod stdlib;
+mod tasks;
 mod typechecker;
+mod units;
 
 pub use crate::analysis::SemanticAnalysis;
 pub use crate::ast::{
@@ -21,13 +27,14 @@ pub use crate::ast::{
     CallExpression, CatchArm, CatchClause, CatchHandler, CatchKind, ConditionalExpression,
     ConditionalKind, ConditionalStatement, ContinueStatement, DictEntry, DictLiteral,
     ErrorAnnotation, ErrorField, ErrorStatement, ErrorTypeSpecifier, ErrorVariant, Expression,
-    ExpressionKind, ExpressionStatement, ForPattern, FunctionParameter, FunctionStatement,
-    Identifier, IndexExpression, InterpolatedStringExpression, InterpolatedStringPart, LambdaBody,
-    LambdaExpression, ListLiteral, Literal, LoopHeader, LoopKind, LoopStatement, MatchArm,
-    MatchArmBlock, MatchExpression, MatchPattern, MatchStatement, MemberExpression, Module,
-    RangeExpression, ReturnStatement, SourceSpan, Statement, StructField, StructStatement,
-    ThrowStatement, TryExpression, TypeExpression, UnaryExpression, UnaryOperator, UseStatement,
-    VarBinding, VarStatement,
+    ExpressionKind, ExpressionStatement, ForPattern, FormattedInterpolation, FunctionParameter,
+    FunctionStatement, GuardStatement, Identifier, IndexExpression, InterpolatedStringExpression,
+    InterpolatedStringPart, LambdaBody, LambdaExpression, ListLiteral, Literal, LoopHeader,
+    LoopKind, LoopStatement, MatchArm, MatchArmBlock, MatchExpression, MatchPattern,
+    MatchStatement, MemberExpression, Module, OptionalBinding, RangeExpression, ReturnStatement,
+    SourceSpan, Statement, StructField, StructStatement, ThrowStatement, TryExpression,
+    TypeExpression, UnaryExpression, UnaryOperator, UseItem, UseStatement, VarBinding,
+    VarStatement,
 };
 pub use crate::compiler::{
     Compilation, CompileOptions, CompileTarget, Compiler, ParsedModule, ResolvedModule,
@@ -38,6 +45,11 @@ pub use crate::formatter::format_source;
 pub use crate::lexer::{Keyword, Lexer, Token, TokenKind};
 pub use crate::loader::{InMemoryModuleLoader, ModuleLoader};
 #[cfg(not(target_arch = "wasm32"))]
+pub use crate::package::{
+    validate_package_name, DependencySpec, LockedPackage, Lockfile, PackageManifest,
+    PackageMetadata, Project, LOCKFILE_FILE, MANIFEST_FILE, VENDOR_DIR,
+};
+#[cfg(not(target_arch = "wasm32"))]
 pub use crate::reference::{
     build_reference_manifest, ReferenceEntry, ReferenceEntryKind, ReferenceFunction,
     ReferenceManifest, BUILTIN_REFERENCE_SUMMARY,
@@ -48,6 +60,9 @@ pub use crate::stdlib::{
     find_module as stdlib_find_module, StdFunction, StdModule, StdType, BUILTINS as STDLIB_BUILTINS,
 };
 pub use crate::typechecker::TypeChecker;
+pub use crate::units::{
+    CompilationUnit, InterfaceDeclaration, InterfaceDeclarationKind, ModuleInterface,
+};
 
 #[cfg(not(target_arch = "wasm32"))]
 pub use crate::loader::NativeModuleLoader;
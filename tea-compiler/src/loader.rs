@@ -1,9 +1,15 @@
 use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::Mutex;
 
 use anyhow::{anyhow, Result};
 use path_clean::PathClean;
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::package::Project;
 use crate::stdlib;
 
 pub trait ModuleLoader: Send + Sync {
@@ -41,6 +47,8 @@ fn resolved_path(base_path: &Path, import: &str) -> PathBuf {
 
 fn browser_stdlib_path(module_path: &str) -> Option<PathBuf> {
     match module_path {
+        "std.math" => Some(PathBuf::from("/__tea_browser_stdlib/math/mod.tea")),
+        "std.random" => Some(PathBuf::from("/__tea_browser_stdlib/random/mod.tea")),
         "std.string" => Some(PathBuf::from("/__tea_browser_stdlib/string/mod.tea")),
         _ => None,
     }
@@ -50,8 +58,9 @@ fn browser_stdlib_path(module_path: &str) -> Option<PathBuf> {
 fn embedded_source_stdlib_path(module_path: &str) -> Option<PathBuf> {
     let module_name = module_path.strip_prefix("std.")?;
     match module_name {
-        "args" | "env" | "fs" | "http" | "parse" | "path" | "process" | "regex" | "string"
-        | "url" => Some(PathBuf::from(format!(
+        "args" | "bytes" | "crypto" | "csv" | "encoding" | "env" | "fs" | "http" | "iter"
+        | "json" | "log" | "math" | "net" | "parse" | "path" | "process" | "random" | "regex"
+        | "string" | "task" | "term" | "toml" | "url" | "yaml" => Some(PathBuf::from(format!(
             "/__tea_stdlib/{module_name}/mod.tea"
         ))),
         _ => None,
@@ -61,15 +70,29 @@ fn embedded_source_stdlib_path(module_path: &str) -> Option<PathBuf> {
 fn embedded_source_stdlib_contents(path: &Path) -> Option<&'static str> {
     match path.to_str()? {
         "/__tea_stdlib/args/mod.tea" => Some(include_str!("../../stdlib/args/mod.tea")),
+        "/__tea_stdlib/bytes/mod.tea" => Some(include_str!("../../stdlib/bytes/mod.tea")),
+        "/__tea_stdlib/crypto/mod.tea" => Some(include_str!("../../stdlib/crypto/mod.tea")),
+        "/__tea_stdlib/csv/mod.tea" => Some(include_str!("../../stdlib/csv/mod.tea")),
+        "/__tea_stdlib/encoding/mod.tea" => Some(include_str!("../../stdlib/encoding/mod.tea")),
         "/__tea_stdlib/env/mod.tea" => Some(include_str!("../../stdlib/env/mod.tea")),
         "/__tea_stdlib/fs/mod.tea" => Some(include_str!("../../stdlib/fs/mod.tea")),
         "/__tea_stdlib/http/mod.tea" => Some(include_str!("../../stdlib/http/mod.tea")),
+        "/__tea_stdlib/iter/mod.tea" => Some(include_str!("../../stdlib/iter/mod.tea")),
+        "/__tea_stdlib/json/mod.tea" => Some(include_str!("../../stdlib/json/mod.tea")),
+        "/__tea_stdlib/log/mod.tea" => Some(include_str!("../../stdlib/log/mod.tea")),
+        "/__tea_stdlib/math/mod.tea" => Some(include_str!("../../stdlib/math/mod.tea")),
+        "/__tea_stdlib/net/mod.tea" => Some(include_str!("../../stdlib/net/mod.tea")),
         "/__tea_stdlib/parse/mod.tea" => Some(include_str!("../../stdlib/parse/mod.tea")),
         "/__tea_stdlib/path/mod.tea" => Some(include_str!("../../stdlib/path/mod.tea")),
         "/__tea_stdlib/process/mod.tea" => Some(include_str!("../../stdlib/process/mod.tea")),
+        "/__tea_stdlib/random/mod.tea" => Some(include_str!("../../stdlib/random/mod.tea")),
         "/__tea_stdlib/regex/mod.tea" => Some(include_str!("../../stdlib/regex/mod.tea")),
         "/__tea_stdlib/string/mod.tea" => Some(include_str!("../../stdlib/string/mod.tea")),
+        "/__tea_stdlib/task/mod.tea" => Some(include_str!("../../stdlib/task/mod.tea")),
+        "/__tea_stdlib/term/mod.tea" => Some(include_str!("../../stdlib/term/mod.tea")),
+        "/__tea_stdlib/toml/mod.tea" => Some(include_str!("../../stdlib/toml/mod.tea")),
         "/__tea_stdlib/url/mod.tea" => Some(include_str!("../../stdlib/url/mod.tea")),
+        "/__tea_stdlib/yaml/mod.tea" => Some(include_str!("../../stdlib/yaml/mod.tea")),
         _ => None,
     }
 }
@@ -88,6 +111,14 @@ impl InMemoryModuleLoader {
     }
 
     pub fn with_browser_stdlib(mut self) -> Self {
+        self.files.insert(
+            PathBuf::from("/__tea_browser_stdlib/math/mod.tea"),
+            include_str!("../../stdlib/math/mod.tea").to_string(),
+        );
+        self.files.insert(
+            PathBuf::from("/__tea_browser_stdlib/random/mod.tea"),
+            include_str!("../../stdlib/random/mod.tea").to_string(),
+        );
         self.files.insert(
             PathBuf::from("/__tea_browser_stdlib/string/mod.tea"),
             include_str!("../../stdlib/string/mod.tea").to_string(),
@@ -123,10 +154,27 @@ impl ModuleLoader for InMemoryModuleLoader {
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-pub struct NativeModuleLoader;
+#[derive(Default)]
+pub struct NativeModuleLoader {
+    /// Projects whose `tea.lock` has been checked during this build.
+    verified_projects: Mutex<HashSet<PathBuf>>,
+}
 
 #[cfg(not(target_arch = "wasm32"))]
 impl NativeModuleLoader {
+    fn verify_lockfile(&self, project: &Project) -> Result<()> {
+        let mut verified = self
+            .verified_projects
+            .lock()
+            .map_err(|_| anyhow!("module loader lock poisoned"))?;
+        if verified.contains(&project.root) {
+            return Ok(());
+        }
+        project.verify_lockfile()?;
+        verified.insert(project.root.clone());
+        Ok(())
+    }
+
     fn resolve_source_stdlib_module(&self, module_path: &str, base_path: &Path) -> Option<PathBuf> {
         if !stdlib::is_source_stdlib_module(module_path) {
             return None;
@@ -178,6 +226,15 @@ impl ModuleLoader for NativeModuleLoader {
             return Ok(None);
         }
 
+        if !import.starts_with('.') && !Path::new(import).is_absolute() {
+            if let Some(project) = Project::discover(base_path)? {
+                if let Some(path) = project.resolve_import(import)? {
+                    self.verify_lockfile(&project)?;
+                    return Ok(Some(path));
+                }
+            }
+        }
+
         Ok(Some(resolved_path(base_path, import)))
     }
 
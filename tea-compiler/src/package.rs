@@ -0,0 +1,718 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use path_clean::PathClean;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub const MANIFEST_FILE: &str = "tea.toml";
+pub const LOCKFILE_FILE: &str = "tea.lock";
+pub const VENDOR_DIR: &str = "vendor";
+const LOCKFILE_VERSION: u32 = 2;
+
+/// Contents of a `tea.toml` project manifest.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PackageManifest {
+    pub package: PackageMetadata,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependencies: BTreeMap<String, DependencySpec>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PackageMetadata {
+    pub name: String,
+    pub version: String,
+    /// Entry-point scripts, relative to the manifest directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entry: Vec<String>,
+}
+
+/// Where a dependency's sources live. Path dependencies are read in place; git
+/// dependencies are read from a checkout under `vendor/<name>`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DependencySpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+}
+
+impl DependencySpec {
+    fn source_label(&self) -> Result<String> {
+        match (&self.path, &self.git) {
+            (Some(path), None) => Ok(format!("path+{path}")),
+            (None, Some(git)) => Ok(match &self.rev {
+                Some(rev) => format!("git+{git}#{rev}"),
+                None => format!("git+{git}"),
+            }),
+            (Some(_), Some(_)) => bail!("dependency cannot set both 'path' and 'git'"),
+            (None, None) => bail!("dependency must set either 'path' or 'git'"),
+        }
+    }
+}
+
+/// Contents of a `tea.lock` file: every package reachable from the root
+/// manifest, sorted by name.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Lockfile {
+    pub version: u32,
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    pub source: String,
+    /// Commit checked out under `vendor/<name>`, for git dependencies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// SHA-256 over the package's manifest and `.tea` sources.
+    #[serde(default)]
+    pub checksum: String,
+}
+
+/// A manifest together with the directory it was loaded from.
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub root: PathBuf,
+    pub manifest: PackageManifest,
+}
+
+impl PackageManifest {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            package: PackageMetadata {
+                name: name.into(),
+                version: "0.1.0".to_string(),
+                entry: vec!["main.tea".to_string()],
+            },
+            dependencies: BTreeMap::new(),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let manifest: PackageManifest = toml::from_str(contents)?;
+        for (name, spec) in &manifest.dependencies {
+            validate_package_name(name)?;
+            spec.source_label()
+                .with_context(|| format!("invalid dependency '{name}'"))?;
+        }
+        Ok(manifest)
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+}
+
+impl Lockfile {
+    pub fn parse(contents: &str) -> Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        let body = toml::to_string_pretty(self)?;
+        Ok(format!(
+            "# This file is generated by `tea`. Do not edit it by hand.\n{body}"
+        ))
+    }
+}
+
+impl Project {
+    pub fn load(root: &Path) -> Result<Self> {
+        let manifest_path = root.join(MANIFEST_FILE);
+        let contents = fs::read_to_string(&manifest_path)
+            .with_context(|| format!("failed to read {}", manifest_path.display()))?;
+        let manifest = PackageManifest::parse(&contents)
+            .with_context(|| format!("invalid manifest {}", manifest_path.display()))?;
+        Ok(Self {
+            root: root.to_path_buf(),
+            manifest,
+        })
+    }
+
+    /// Find the nearest `tea.toml` at or above `start`.
+    pub fn discover(start: &Path) -> Result<Option<Self>> {
+        let mut current = if start.is_dir() {
+            Some(start)
+        } else {
+            start.parent()
+        };
+        while let Some(dir) = current {
+            let dir = if dir.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                dir
+            };
+            if dir.join(MANIFEST_FILE).is_file() {
+                return Self::load(dir).map(Some);
+            }
+            current = dir.parent();
+        }
+        Ok(None)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let manifest_path = self.root.join(MANIFEST_FILE);
+        fs::write(&manifest_path, self.manifest.to_toml()?)
+            .with_context(|| format!("failed to write {}", manifest_path.display()))
+    }
+
+    /// Directory holding the sources of dependency `name`.
+    pub fn dependency_root(&self, name: &str) -> Result<PathBuf> {
+        let spec = self
+            .manifest
+            .dependencies
+            .get(name)
+            .ok_or_else(|| anyhow!("package '{}' has no dependency '{name}'", self.name()))?;
+        let root = match (&spec.path, &spec.git) {
+            (Some(path), _) => self.root.join(path).clean(),
+            (None, Some(_)) => self.root.join(VENDOR_DIR).join(name),
+            (None, None) => bail!("dependency '{name}' must set either 'path' or 'git'"),
+        };
+        if !root.is_dir() {
+            if spec.git.is_some() {
+                bail!(
+                    "dependency '{name}' is not vendored; expected a checkout at {}",
+                    root.display()
+                );
+            }
+            bail!(
+                "dependency '{name}' points at missing directory {}",
+                root.display()
+            );
+        }
+        Ok(root)
+    }
+
+    /// Resolve `use x from "pkg/module"` to a file inside dependency `pkg`.
+    /// Returns `None` when the first path segment is not a dependency.
+    pub fn resolve_import(&self, import: &str) -> Result<Option<PathBuf>> {
+        let (package, module) = match import.split_once('/') {
+            Some((package, module)) => (package, Some(module)),
+            None => (import, None),
+        };
+        if !self.manifest.dependencies.contains_key(package) {
+            return Ok(None);
+        }
+
+        let root = self.dependency_root(package)?;
+        let candidates = match module {
+            Some(module) => vec![
+                root.join(format!("{module}.tea")),
+                root.join(module).join("mod.tea"),
+            ],
+            None => vec![root.join("mod.tea")],
+        };
+        for candidate in &candidates {
+            if candidate.is_file() {
+                return Ok(Some(candidate.clone()));
+            }
+        }
+        bail!(
+            "package '{package}' has no module '{}'; looked for {}",
+            module.unwrap_or("mod"),
+            candidates
+                .iter()
+                .map(|candidate| candidate.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+
+    /// Build the lockfile for every package reachable from this project.
+    pub fn resolve_lockfile(&self) -> Result<Lockfile> {
+        let mut packages = BTreeMap::new();
+        let mut visiting = HashSet::new();
+        self.collect_locked_packages(&mut packages, &mut visiting)?;
+        Ok(Lockfile {
+            version: LOCKFILE_VERSION,
+            packages: packages
+                .into_values()
+                .map(|package| package.locked)
+                .collect(),
+        })
+    }
+
+    /// Check `tea.lock`, when there is one, against the packages on disk.
+    pub fn verify_lockfile(&self) -> Result<()> {
+        let lock_path = self.root.join(LOCKFILE_FILE);
+        if !lock_path.is_file() {
+            return Ok(());
+        }
+        let contents = fs::read_to_string(&lock_path)
+            .with_context(|| format!("failed to read {}", lock_path.display()))?;
+        let locked = Lockfile::parse(&contents)
+            .with_context(|| format!("invalid lockfile {}", lock_path.display()))?;
+        let resolved = self.resolve_lockfile()?;
+        if let Some(mismatch) = locked.mismatch(&resolved) {
+            bail!(
+                "{} is out of date: {mismatch}; run `tea lock` to update it",
+                lock_path.display()
+            );
+        }
+        Ok(())
+    }
+
+    pub fn write_lockfile(&self) -> Result<Lockfile> {
+        let lockfile = self.resolve_lockfile()?;
+        let lock_path = self.root.join(LOCKFILE_FILE);
+        fs::write(&lock_path, lockfile.to_toml()?)
+            .with_context(|| format!("failed to write {}", lock_path.display()))?;
+        Ok(lockfile)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.manifest.package.name
+    }
+
+    fn collect_locked_packages(
+        &self,
+        packages: &mut BTreeMap<String, ResolvedPackage>,
+        visiting: &mut HashSet<String>,
+    ) -> Result<()> {
+        for (name, spec) in &self.manifest.dependencies {
+            let root = self.dependency_root(name)?;
+            let root = root.canonicalize().unwrap_or(root);
+            let dependency = Project::load(&root)
+                .with_context(|| format!("failed to load dependency '{name}'"))?;
+            if dependency.name() != name {
+                bail!(
+                    "dependency '{name}' resolves to package '{}'",
+                    dependency.name()
+                );
+            }
+            let version = &dependency.manifest.package.version;
+
+            // Two packages may share a dependency only when both resolve it to
+            // the same version in the same place.
+            if let Some(existing) = packages.get(name) {
+                if existing.locked.version != *version || existing.root != root {
+                    bail!(
+                        "conflicting versions of package '{name}': {} at {} for '{}', and {version} at {} for '{}'",
+                        existing.locked.version,
+                        existing.root.display(),
+                        existing.required_by,
+                        root.display(),
+                        self.name()
+                    );
+                }
+                continue;
+            }
+            if !visiting.insert(name.clone()) {
+                continue;
+            }
+            dependency.collect_locked_packages(packages, visiting)?;
+            let commit = if spec.git.is_some() {
+                vendored_commit(&root)
+            } else {
+                None
+            };
+            // A checkout copied in without its `.git` has no commit to compare;
+            // its checksum still pins the sources.
+            if let (Some(rev), Some(commit)) = (&spec.rev, &commit) {
+                if !vendored_rev_matches(&root, rev, commit) {
+                    bail!(
+                        "dependency '{name}' pins rev {rev} but {commit} is checked out in {}",
+                        root.display()
+                    );
+                }
+            }
+            packages.insert(
+                name.clone(),
+                ResolvedPackage {
+                    locked: LockedPackage {
+                        name: name.clone(),
+                        version: version.clone(),
+                        source: spec.source_label()?,
+                        commit,
+                        checksum: package_checksum(&root)?,
+                    },
+                    root,
+                    required_by: self.name().to_string(),
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A locked package together with where it was found and who asked for it.
+struct ResolvedPackage {
+    locked: LockedPackage,
+    root: PathBuf,
+    required_by: String,
+}
+
+impl Lockfile {
+    /// Describe the first difference between this lockfile and `resolved`.
+    fn mismatch(&self, resolved: &Lockfile) -> Option<String> {
+        if self.version != resolved.version {
+            return Some(format!(
+                "it uses lockfile format {} rather than {}",
+                self.version, resolved.version
+            ));
+        }
+        for package in &resolved.packages {
+            let Some(locked) = self
+                .packages
+                .iter()
+                .find(|locked| locked.name == package.name)
+            else {
+                return Some(format!("package '{}' is not locked", package.name));
+            };
+            if locked.version != package.version {
+                return Some(format!(
+                    "package '{}' is locked at {} but {} is present",
+                    package.name, locked.version, package.version
+                ));
+            }
+            if locked.source != package.source {
+                return Some(format!(
+                    "package '{}' is locked from {} but the manifest uses {}",
+                    package.name, locked.source, package.source
+                ));
+            }
+            if locked.commit != package.commit {
+                return Some(format!(
+                    "package '{}' is locked at commit {} but {} is checked out",
+                    package.name,
+                    locked.commit.as_deref().unwrap_or("(none)"),
+                    package.commit.as_deref().unwrap_or("(none)")
+                ));
+            }
+            if locked.checksum != package.checksum {
+                return Some(format!(
+                    "the sources of package '{}' changed since it was locked",
+                    package.name
+                ));
+            }
+        }
+        self.packages
+            .iter()
+            .find(|locked| {
+                !resolved
+                    .packages
+                    .iter()
+                    .any(|package| package.name == locked.name)
+            })
+            .map(|locked| format!("package '{}' is no longer a dependency", locked.name))
+    }
+}
+
+/// Hash a package's `tea.toml` and `.tea` files, skipping its own `vendor/`
+/// directory and hidden directories such as `.git`.
+fn package_checksum(root: &Path) -> Result<String> {
+    let mut files = Vec::new();
+    collect_package_files(root, root, &mut files)?;
+    files.sort();
+    let mut hasher = Sha256::new();
+    for relative in files {
+        let contents =
+            fs::read(root.join(&relative)).with_context(|| format!("failed to read {relative}"))?;
+        hasher.update(relative.as_bytes());
+        hasher.update([0]);
+        hasher.update(&contents);
+        hasher.update([0]);
+    }
+    Ok(format!("sha256:{:x}", hasher.finalize()))
+}
+
+fn collect_package_files(root: &Path, dir: &Path, files: &mut Vec<String>) -> Result<()> {
+    let entries = fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if path.is_dir() {
+            if name.starts_with('.') || (dir == root && name == VENDOR_DIR) {
+                continue;
+            }
+            collect_package_files(root, &path, files)?;
+        } else if name == MANIFEST_FILE || path.extension().is_some_and(|ext| ext == "tea") {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            files.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(())
+}
+
+/// Read the commit a git checkout has at `HEAD` without running git.
+fn vendored_commit(root: &Path) -> Option<String> {
+    let git_dir = root.join(".git");
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+    match head.strip_prefix("ref: ") {
+        Some(reference) => read_git_ref(&git_dir, reference),
+        None => Some(head.to_string()),
+    }
+}
+
+/// Whether `rev` from the manifest names `commit` in the checkout at `root`:
+/// either as a (possibly abbreviated) commit hash or as a tag or branch.
+fn vendored_rev_matches(root: &Path, rev: &str, commit: &str) -> bool {
+    if rev.len() >= 4 && rev.chars().all(|ch| ch.is_ascii_hexdigit()) && commit.starts_with(rev) {
+        return true;
+    }
+    let git_dir = root.join(".git");
+    ["refs/tags/", "refs/heads/", "refs/remotes/origin/"]
+        .iter()
+        .filter_map(|prefix| read_git_ref(&git_dir, &format!("{prefix}{rev}")))
+        .any(|target| target == commit)
+}
+
+/// Read the commit a loose or packed ref points at. Packed annotated tags are
+/// peeled to the commit they tag.
+fn read_git_ref(git_dir: &Path, reference: &str) -> Option<String> {
+    if let Ok(commit) = fs::read_to_string(git_dir.join(reference)) {
+        return Some(commit.trim().to_string());
+    }
+    let packed = fs::read_to_string(git_dir.join("packed-refs")).ok()?;
+    let mut lines = packed.lines().peekable();
+    while let Some(line) = lines.next() {
+        let Some((commit, name)) = line.split_once(' ') else {
+            continue;
+        };
+        if name == reference {
+            let peeled = lines.peek().and_then(|next| next.strip_prefix('^'));
+            return Some(peeled.unwrap_or(commit).to_string());
+        }
+    }
+    None
+}
+
+pub fn validate_package_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .next()
+            .is_some_and(|ch| ch.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-');
+    if !valid {
+        bail!(
+            "invalid package name '{name}'; use letters, digits, '_' or '-', starting with a letter"
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, contents: &str) {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("create dir");
+        }
+        fs::write(path, contents).expect("write file");
+    }
+
+    #[test]
+    fn resolves_path_and_vendored_dependencies() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let app = dir.path().join("app");
+        write(
+            &app.join(MANIFEST_FILE),
+            r#"
+[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+helpers = { path = "../helpers" }
+extras = { git = "https://example.com/extras.git", rev = "abc123" }
+"#,
+        );
+        write(
+            &dir.path().join("helpers").join(MANIFEST_FILE),
+            "[package]\nname = \"helpers\"\nversion = \"1.2.0\"\n",
+        );
+        write(&dir.path().join("helpers/strings.tea"), "");
+        write(
+            &app.join("vendor/extras").join(MANIFEST_FILE),
+            "[package]\nname = \"extras\"\nversion = \"0.3.0\"\n",
+        );
+        write(&app.join("vendor/extras/mod.tea"), "");
+        write(
+            &app.join("vendor/extras/.git/HEAD"),
+            "abc1230000000000000000000000000000000000\n",
+        );
+
+        let project = Project::discover(&app.join("main.tea"))
+            .expect("discover")
+            .expect("manifest");
+        assert_eq!(
+            project.resolve_import("helpers/strings").expect("resolve"),
+            Some(dir.path().join("helpers/strings.tea"))
+        );
+        assert_eq!(
+            project.resolve_import("extras").expect("resolve"),
+            Some(app.join("vendor/extras/mod.tea"))
+        );
+        assert_eq!(
+            project.resolve_import("unknown/thing").expect("resolve"),
+            None
+        );
+        assert!(project.resolve_import("helpers/missing").is_err());
+
+        let lockfile = project.resolve_lockfile().expect("lockfile");
+        let summary: Vec<_> = lockfile
+            .packages
+            .iter()
+            .map(|package| {
+                (
+                    package.name.as_str(),
+                    package.version.as_str(),
+                    package.source.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "extras",
+                    "0.3.0",
+                    "git+https://example.com/extras.git#abc123"
+                ),
+                ("helpers", "1.2.0", "path+../helpers"),
+            ]
+        );
+        assert!(lockfile
+            .packages
+            .iter()
+            .all(|package| package.checksum.starts_with("sha256:")));
+        let round_trip = Lockfile::parse(&lockfile.to_toml().expect("serialize")).expect("parse");
+        assert_eq!(round_trip, lockfile);
+    }
+
+    #[test]
+    fn verifies_lockfile_against_dependency_sources() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        write(
+            &dir.path().join(MANIFEST_FILE),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nextras = { git = \"https://example.com/extras.git\" }\n",
+        );
+        let extras = dir.path().join("vendor/extras");
+        write(
+            &extras.join(MANIFEST_FILE),
+            "[package]\nname = \"extras\"\nversion = \"0.3.0\"\n",
+        );
+        write(&extras.join("mod.tea"), "pub const ANSWER = 42\n");
+        write(&extras.join(".git/HEAD"), "ref: refs/heads/main\n");
+        write(
+            &extras.join(".git/refs/heads/main"),
+            "0123456789abcdef0123456789abcdef01234567\n",
+        );
+
+        let project = Project::load(dir.path()).expect("load");
+        project.verify_lockfile().expect("no lockfile to verify");
+        let lockfile = project.write_lockfile().expect("write lockfile");
+        assert_eq!(
+            lockfile.packages[0].commit.as_deref(),
+            Some("0123456789abcdef0123456789abcdef01234567")
+        );
+        project.verify_lockfile().expect("lockfile matches");
+
+        write(&extras.join("mod.tea"), "pub const ANSWER = 43\n");
+        let error = project.verify_lockfile().unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("the sources of package 'extras' changed since it was locked"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn rejects_vendor_checkout_that_differs_from_pinned_rev() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        write(
+            &dir.path().join(MANIFEST_FILE),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nextras = { git = \"https://example.com/extras.git\", rev = \"v1.0\" }\n",
+        );
+        let extras = dir.path().join("vendor/extras");
+        write(
+            &extras.join(MANIFEST_FILE),
+            "[package]\nname = \"extras\"\nversion = \"1.0.0\"\n",
+        );
+        write(
+            &extras.join(".git/HEAD"),
+            "0123456789abcdef0123456789abcdef01234567\n",
+        );
+        write(
+            &extras.join(".git/packed-refs"),
+            "# pack-refs with: peeled fully-peeled sorted\n\
+             fedcba9876543210fedcba9876543210fedcba98 refs/tags/v1.0\n\
+             ^0123456789abcdef0123456789abcdef01234567\n",
+        );
+
+        let project = Project::load(dir.path()).expect("load");
+        project.resolve_lockfile().expect("tag resolves to HEAD");
+
+        write(
+            &extras.join(".git/HEAD"),
+            "89abcdef0123456789abcdef0123456789abcdef\n",
+        );
+        let error = project.resolve_lockfile().unwrap_err();
+        assert!(
+            error.to_string().contains(
+                "dependency 'extras' pins rev v1.0 but 89abcdef0123456789abcdef0123456789abcdef is checked out"
+            ),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn reports_conflicting_versions_of_a_shared_dependency() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        write(
+            &dir.path().join("app").join(MANIFEST_FILE),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nleft = { path = \"../left\" }\nright = { path = \"../right\" }\n",
+        );
+        for (name, shared) in [("left", "../shared-1"), ("right", "../shared-2")] {
+            write(
+                &dir.path().join(name).join(MANIFEST_FILE),
+                &format!(
+                    "[package]\nname = \"{name}\"\nversion = \"1.0.0\"\n\n[dependencies]\nshared = {{ path = \"{shared}\" }}\n"
+                ),
+            );
+        }
+        for (dir_name, version) in [("shared-1", "1.0.0"), ("shared-2", "2.0.0")] {
+            write(
+                &dir.path().join(dir_name).join(MANIFEST_FILE),
+                &format!("[package]\nname = \"shared\"\nversion = \"{version}\"\n"),
+            );
+        }
+
+        let project = Project::load(&dir.path().join("app")).expect("load");
+        let error = project.resolve_lockfile().unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("conflicting versions of package 'shared': 1.0.0"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn reports_missing_vendor_checkout() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        write(
+            &dir.path().join(MANIFEST_FILE),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nextras = { git = \"https://example.com/extras.git\" }\n",
+        );
+        let project = Project::load(dir.path()).expect("load");
+        let error = project.resolve_import("extras/util").unwrap_err();
+        assert!(error.to_string().contains("is not vendored"), "{error}");
+    }
+}
@@ -4,6 +4,7 @@ use crate::ast::*;
 use crate::diagnostics::Diagnostics;
 use crate::lexer::{Keyword, Token, TokenKind};
 use crate::source::SourceFile;
+use tea_support::FormatSpec;
 
 #[derive(Copy, Clone, PartialEq, PartialOrd)]
 enum Precedence {
@@ -44,6 +45,10 @@ impl Precedence {
     }
 }
 
+/// Lambda ids are numbered per source file so modules flattened into one
+/// program never reuse an id.
+const LAMBDA_IDS_PER_SOURCE: usize = 1 << 24;
+
 pub struct Parser<'a> {
     _source: &'a SourceFile,
     tokens: Vec<Token>,
@@ -59,7 +64,7 @@ impl<'a> Parser<'a> {
             tokens,
             current: 0,
             diagnostics: Diagnostics::new(),
-            next_lambda_id: 0,
+            next_lambda_id: source.id.0 as usize * LAMBDA_IDS_PER_SOURCE,
         }
     }
 
@@ -122,7 +127,7 @@ impl<'a> Parser<'a> {
     fn parse_statement(&mut self) -> Result<Statement> {
         let docstring = self.consume_doc_comments();
         match self.peek_kind() {
-            TokenKind::Keyword(Keyword::Use) => self.parse_use(),
+            TokenKind::Keyword(Keyword::Use) => self.parse_use(false),
             TokenKind::Keyword(Keyword::Var) => {
                 self.parse_binding(Keyword::Var, docstring.clone(), false)
             }
@@ -130,6 +135,7 @@ impl<'a> Parser<'a> {
                 self.parse_binding(Keyword::Const, docstring.clone(), false)
             }
             TokenKind::Keyword(Keyword::Def) => self.parse_function(docstring.clone(), false),
+            TokenKind::Keyword(Keyword::Extern) => self.parse_extern(docstring.clone(), false),
             TokenKind::Keyword(Keyword::Pub) => self.parse_public_statement(docstring.clone()),
             TokenKind::Keyword(Keyword::Test) => self.parse_test(docstring.clone()),
             TokenKind::Keyword(Keyword::Struct) => self.parse_struct(docstring, false),
@@ -139,11 +145,13 @@ impl<'a> Parser<'a> {
             TokenKind::Keyword(Keyword::If) => self.parse_conditional(ConditionalKind::If),
             TokenKind::Keyword(Keyword::For) => self.parse_for_loop(),
             TokenKind::Keyword(Keyword::While) => self.parse_loop(LoopKind::While),
+            TokenKind::Keyword(Keyword::Guard) => self.parse_guard(),
             TokenKind::Keyword(Keyword::Break) => self.parse_break(),
             TokenKind::Keyword(Keyword::Continue) => self.parse_continue(),
             TokenKind::Keyword(Keyword::Return) => self.parse_return(),
             TokenKind::Keyword(Keyword::Match) => self.parse_match_statement(),
             TokenKind::Keyword(Keyword::Throw) => self.parse_throw(),
+            TokenKind::Keyword(Keyword::Yield) => self.parse_yield(),
             _ => self.parse_expression_statement(),
         }
     }
@@ -155,6 +163,8 @@ impl<'a> Parser<'a> {
 
         match self.peek_kind() {
             TokenKind::Keyword(Keyword::Def) => self.parse_function(docstring, true),
+            TokenKind::Keyword(Keyword::Extern) => self.parse_extern(docstring, true),
+            TokenKind::Keyword(Keyword::Use) => self.parse_use(true),
             TokenKind::Keyword(Keyword::Const) => {
                 self.parse_binding(Keyword::Const, docstring, true)
             }
@@ -174,7 +184,7 @@ impl<'a> Parser<'a> {
                 let span = Self::span_from_token(&pub_token);
                 self.diagnostics.push_error_with_span(
                     format!(
-                        "unexpected {:?} after 'pub'; only const, def, struct, union, enum, and error declarations can be public",
+                        "unexpected {:?} after 'pub'; only use, const, def, extern, struct, union, enum, and error declarations can be public",
                         other
                     ),
                     Some(span),
@@ -184,50 +194,23 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_use(&mut self) -> Result<Statement> {
+    fn parse_use(&mut self, is_public: bool) -> Result<Statement> {
         self.advance(); // consume 'use'
         self.skip_newlines();
 
         let alias_token = self.peek().clone();
+        let mut items = Vec::new();
         let alias = match alias_token.kind {
+            TokenKind::LBrace => {
+                items = self.parse_use_items()?;
+                None
+            }
             TokenKind::Identifier => {
                 self.advance();
-                let alias_span = Self::span_from_token(&alias_token);
-                let alias_name = alias_token.lexeme;
-                match self.peek_kind() {
-                    TokenKind::Identifier if self.peek().lexeme == "from" => {
-                        self.advance(); // consume 'from'
-                        self.skip_newlines();
-                    }
-                    TokenKind::Equal => {
-                        self.diagnostics.push_with_location(
-                            "module imports use `from` (e.g. `use fs from \"std.fs\"`)",
-                            alias_token.line,
-                            alias_token.column,
-                        );
-                        bail!(
-                            "module imports use `from` (e.g. `use fs from \"std.fs\"`) at line {}, column {}",
-                            alias_token.line,
-                            alias_token.column
-                        );
-                    }
-                    _ => {
-                        self.diagnostics.push_with_location(
-                            "expected 'from' after module alias",
-                            alias_token.line,
-                            alias_token.column,
-                        );
-                        bail!(
-                            "expected 'from' after module alias at line {}, column {}",
-                            alias_token.line,
-                            alias_token.column
-                        );
-                    }
-                }
-                UseAlias {
-                    name: alias_name,
-                    span: alias_span,
-                }
+                Some(UseAlias {
+                    name: alias_token.lexeme.clone(),
+                    span: Self::span_from_token(&alias_token),
+                })
             }
             _ => {
                 self.diagnostics.push_with_location(
@@ -243,6 +226,40 @@ impl<'a> Parser<'a> {
             }
         };
 
+        match self.peek_kind() {
+            TokenKind::Identifier if self.peek().lexeme == "from" => {
+                self.advance(); // consume 'from'
+                self.skip_newlines();
+            }
+            TokenKind::Equal => {
+                self.diagnostics.push_with_location(
+                    "module imports use `from` (e.g. `use fs from \"std.fs\"`)",
+                    alias_token.line,
+                    alias_token.column,
+                );
+                bail!(
+                    "module imports use `from` (e.g. `use fs from \"std.fs\"`) at line {}, column {}",
+                    alias_token.line,
+                    alias_token.column
+                );
+            }
+            _ => {
+                let message = if alias.is_some() {
+                    "expected 'from' after module alias"
+                } else {
+                    "expected 'from' after imported names"
+                };
+                self.diagnostics
+                    .push_with_location(message, alias_token.line, alias_token.column);
+                bail!(
+                    "{} at line {}, column {}",
+                    message,
+                    alias_token.line,
+                    alias_token.column
+                );
+            }
+        }
+
         let module_token = self.peek().clone();
         let module_span = Self::span_from_token(&module_token);
         let module_path = match &module_token.kind {
@@ -268,13 +285,106 @@ impl<'a> Parser<'a> {
         }
         self.expect_newline("expected newline after use statement")?;
 
+        // Selective imports bind no module name of their own; the expander
+        // still needs an alias to namespace the imported declarations under.
+        let alias = alias.unwrap_or_else(|| UseAlias {
+            name: selective_use_alias(&module_path, module_span),
+            span: module_span,
+        });
+
         Ok(Statement::Use(UseStatement {
+            is_public,
             alias,
+            items,
             module_path,
             module_span,
         }))
     }
 
+    fn parse_use_items(&mut self) -> Result<Vec<UseItem>> {
+        self.advance(); // consume '{'
+        let mut items: Vec<UseItem> = Vec::new();
+        loop {
+            self.skip_newlines();
+            if matches!(self.peek_kind(), TokenKind::RBrace) {
+                break;
+            }
+
+            let name_token = self.peek().clone();
+            if !matches!(name_token.kind, TokenKind::Identifier) {
+                let span = Self::span_from_token(&name_token);
+                self.diagnostics.push_error_with_span(
+                    format!("expected a name to import, found {:?}", name_token.kind),
+                    Some(span),
+                );
+                bail!("invalid selective import");
+            }
+            self.advance();
+            let mut span = Self::span_from_token(&name_token);
+
+            let mut alias = None;
+            if matches!(self.peek_kind(), TokenKind::Identifier) && self.peek().lexeme == "as" {
+                self.advance(); // consume 'as'
+                let alias_token = self.peek().clone();
+                if !matches!(alias_token.kind, TokenKind::Identifier) {
+                    let alias_span = Self::span_from_token(&alias_token);
+                    self.diagnostics.push_error_with_span(
+                        format!(
+                            "expected a local name after 'as', found {:?}",
+                            alias_token.kind
+                        ),
+                        Some(alias_span),
+                    );
+                    bail!("invalid selective import alias");
+                }
+                self.advance();
+                span = SourceSpan::union(&span, &Self::span_from_token(&alias_token));
+                alias = Some(alias_token.lexeme.clone());
+            }
+
+            let local_name = alias.as_deref().unwrap_or(&name_token.lexeme);
+            if items
+                .iter()
+                .any(|item| item.alias.as_deref().unwrap_or(&item.name) == local_name)
+            {
+                self.diagnostics.push_error_with_span(
+                    format!("'{local_name}' is imported more than once"),
+                    Some(span),
+                );
+            }
+            items.push(UseItem {
+                name: name_token.lexeme.clone(),
+                alias,
+                span,
+            });
+
+            self.skip_newlines();
+            if matches!(self.peek_kind(), TokenKind::Comma) {
+                self.advance();
+                continue;
+            }
+            break;
+        }
+
+        self.skip_newlines();
+        let close = self.peek().clone();
+        if !matches!(close.kind, TokenKind::RBrace) {
+            let span = Self::span_from_token(&close);
+            self.diagnostics
+                .push_error_with_span("expected '}' to close the import list", Some(span));
+            bail!("unterminated selective import");
+        }
+        self.advance();
+
+        if items.is_empty() {
+            let span = Self::span_from_token(&close);
+            self.diagnostics
+                .push_error_with_span("selective imports must name at least one item", Some(span));
+            bail!("empty selective import");
+        }
+        Ok(items)
+    }
+
     fn parse_binding(
         &mut self,
         keyword: Keyword,
@@ -364,7 +474,9 @@ impl<'a> Parser<'a> {
         let name_token = self.peek().clone();
         let name_span = Self::span_from_token(&name_token);
         let name = match &name_token.kind {
-            TokenKind::Identifier => {
+            // `error` only opens a declaration at the start of a statement, so it
+            // can also name a module function that is called as `log.error(...)`.
+            TokenKind::Identifier | TokenKind::Keyword(Keyword::Error) => {
                 self.advance();
                 name_token.lexeme
             }
@@ -422,11 +534,145 @@ impl<'a> Parser<'a> {
             parameters,
             return_type,
             error_annotation,
+            extern_function: None,
             body,
             docstring,
         }))
     }
 
+    /// Parse `extern def name(params) -> Type from "library"`, a body-less
+    /// declaration of a C function.
+    fn parse_extern(&mut self, docstring: Option<String>, is_public: bool) -> Result<Statement> {
+        let extern_token = self.peek().clone();
+        self.advance(); // consume 'extern'
+        if !matches!(self.peek_kind(), TokenKind::Keyword(Keyword::Def)) {
+            let span = Self::span_from_token(&self.peek().clone());
+            self.diagnostics
+                .push_error_with_span("expected 'def' after 'extern'", Some(span));
+            bail!("invalid extern declaration");
+        }
+        self.advance(); // consume 'def'
+
+        let name_token = self.peek().clone();
+        let name_span = Self::span_from_token(&name_token);
+        let name = match &name_token.kind {
+            TokenKind::Identifier => {
+                self.advance();
+                name_token.lexeme
+            }
+            _ => bail!(
+                "expected function name after 'extern def' at line {}, column {}",
+                name_token.line,
+                name_token.column
+            ),
+        };
+
+        if matches!(self.peek_kind(), TokenKind::LBracket) {
+            let span = Self::span_from_token(&self.peek().clone());
+            self.diagnostics.push_error_with_span(
+                format!("extern function '{}' cannot declare type parameters", name),
+                Some(span),
+            );
+            bail!("generic extern function");
+        }
+
+        let lparen_token = self.peek().clone();
+        if !matches!(lparen_token.kind, TokenKind::LParen) {
+            let span = Self::span_from_token(&lparen_token);
+            self.diagnostics
+                .push_error_with_span("expected '(' after function name", Some(span));
+            bail!("missing function parameter list");
+        }
+        self.advance(); // consume '('
+        let parameters = self.parse_parameters()?;
+        for parameter in &parameters {
+            let problem = if parameter.variadic {
+                Some("cannot be variadic")
+            } else if parameter.default_value.is_some() {
+                Some("cannot have a default value")
+            } else if parameter.type_annotation.is_none() {
+                Some("requires a type annotation")
+            } else {
+                None
+            };
+            if let Some(problem) = problem {
+                self.diagnostics.push_error_with_span(
+                    format!(
+                        "parameter '{}' of extern function '{}' {}",
+                        parameter.name, name, problem
+                    ),
+                    Some(parameter.span),
+                );
+                bail!("invalid extern parameter");
+            }
+        }
+
+        let return_type = if matches!(self.peek_kind(), TokenKind::Arrow) {
+            self.advance();
+            let tokens = self.collect_type_tokens();
+            Some(TypeExpression { tokens })
+        } else {
+            None
+        };
+
+        if matches!(self.peek_kind(), TokenKind::Bang) {
+            let span = Self::span_from_token(&self.peek().clone());
+            self.diagnostics.push_error_with_span(
+                format!("extern function '{}' cannot declare errors", name),
+                Some(span),
+            );
+            bail!("extern function with error annotation");
+        }
+
+        let from_token = self.peek().clone();
+        if !(matches!(from_token.kind, TokenKind::Identifier) && from_token.lexeme == "from") {
+            let span = Self::span_from_token(&from_token);
+            self.diagnostics.push_error_with_span(
+                "expected 'from \"library\"' after extern function signature",
+                Some(span),
+            );
+            bail!("missing extern library");
+        }
+        self.advance(); // consume 'from'
+
+        let library_token = self.peek().clone();
+        let library = match &library_token.kind {
+            TokenKind::StringLiteral(value) if !value.is_empty() => {
+                self.advance();
+                value.clone()
+            }
+            _ => {
+                let span = Self::span_from_token(&library_token);
+                self.diagnostics
+                    .push_error_with_span("expected library name string after 'from'", Some(span));
+                bail!("missing extern library");
+            }
+        };
+        let span = Self::union_spans(
+            &Self::span_from_token(&extern_token),
+            &Self::span_from_token(&library_token),
+        );
+
+        self.expect_newline("expected newline after extern declaration")?;
+
+        Ok(Statement::Function(FunctionStatement {
+            is_public,
+            name: name.clone(),
+            name_span,
+            type_parameters: Vec::new(),
+            parameters,
+            return_type,
+            error_annotation: None,
+            extern_function: Some(ExternFunction {
+                symbol: name,
+                library,
+                span,
+            }),
+            body: Block::default(),
+            docstring,
+        }))
+    }
+
     fn parse_test(&mut self, docstring: Option<String>) -> Result<Statement> {
         self.advance(); // consume 'test'
         let name_token = self.peek().clone();
@@ -465,6 +711,12 @@ impl<'a> Parser<'a> {
         }
 
         loop {
+            let variadic = if matches!(self.peek_kind(), TokenKind::DotDotDot) {
+                self.advance();
+                true
+            } else {
+                false
+            };
             let name_token = self.peek().clone();
             let span = Self::span_from_token(&name_token);
             let name = match &name_token.kind {
@@ -500,6 +752,7 @@ impl<'a> Parser<'a> {
                 span,
                 type_annotation,
                 default_value,
+                variadic,
             });
 
             match self.peek_kind() {
@@ -694,12 +947,19 @@ impl<'a> Parser<'a> {
                 );
                 bail!("missing field type");
             }
+            let default_value = if matches!(self.peek_kind(), TokenKind::Equal) {
+                self.advance();
+                Some(self.parse_expression_with(default_expression_terminator)?)
+            } else {
+                None
+            };
             fields.push(StructField {
                 name: field_name,
                 span: field_span,
                 type_annotation: TypeExpression {
                     tokens: type_tokens,
                 },
+                default_value,
                 docstring: field_docstring,
             });
 
@@ -1209,6 +1469,15 @@ impl<'a> Parser<'a> {
         Ok(Statement::Throw(ThrowStatement { expression, span }))
     }
 
+    fn parse_yield(&mut self) -> Result<Statement> {
+        let yield_token = self.advance().clone();
+        let yield_span = Self::span_from_token(&yield_token);
+        let expression = self.parse_expression_with(default_expression_terminator)?;
+        let span = Self::union_spans(&yield_span, &expression.span);
+        self.expect_newline("expected newline after yield expression")?;
+        Ok(Statement::Yield(YieldStatement { expression, span }))
+    }
+
     fn consume_doc_comments(&mut self) -> Option<String> {
         let mut parts = Vec::new();
         let mut consumed_any = false;
@@ -1248,7 +1517,7 @@ impl<'a> Parser<'a> {
         expect_end: bool,
     ) -> Result<Statement> {
         self.advance(); // consume keyword
-        let condition = self.parse_expression_with(default_expression_terminator)?;
+        let (binding, condition) = self.parse_condition_header()?;
 
         self.expect_newline("expected newline after conditional header")?;
         let consequent = self.parse_block_until(&[Keyword::Else, Keyword::End])?;
@@ -1279,6 +1548,7 @@ impl<'a> Parser<'a> {
 
         Ok(Statement::Conditional(ConditionalStatement {
             kind,
+            binding,
             condition,
             consequent,
             alternative,
@@ -1360,7 +1630,10 @@ impl<'a> Parser<'a> {
         let loop_token = self.peek().clone();
         let span = Self::span_from_token(&loop_token);
         self.advance(); // consume keyword
-        let condition = self.parse_expression_with(default_expression_terminator)?;
+        let header = match self.parse_condition_header()? {
+            (Some(binding), value) => LoopHeader::Binding { binding, value },
+            (None, condition) => LoopHeader::Condition(condition),
+        };
         self.expect_newline("expected newline after loop header")?;
         let body = self.parse_block_until(&[Keyword::End])?;
         self.expect_keyword(Keyword::End, "expected 'end' to close loop")?;
@@ -1368,12 +1641,72 @@ impl<'a> Parser<'a> {
 
         Ok(Statement::Loop(LoopStatement {
             kind,
-            header: LoopHeader::Condition(condition),
+            header,
             body,
             span,
         }))
     }
 
+    fn parse_guard(&mut self) -> Result<Statement> {
+        let guard_token = self.peek().clone();
+        let span = Self::span_from_token(&guard_token);
+        self.advance(); // consume 'guard'
+        let (binding, condition) = self.parse_condition_header()?;
+        self.expect_keyword(Keyword::Else, "expected 'else' after guard condition")?;
+        self.expect_newline("expected newline after guard else")?;
+        let alternative = self.parse_block_until(&[Keyword::End])?;
+        self.expect_keyword(Keyword::End, "expected 'end' to close guard")?;
+        self.expect_newline("expected newline after guard end")?;
+
+        Ok(Statement::Guard(GuardStatement {
+            binding,
+            condition,
+            alternative,
+            span,
+        }))
+    }
+
+    /// Parse the header of `if`, `while`, or `guard`: either a plain condition or an
+    /// optional binding `var name = value`.
+    fn parse_condition_header(&mut self) -> Result<(Option<OptionalBinding>, Expression)> {
+        if !self.check_keyword(Keyword::Var) {
+            let condition = self.parse_expression_with(default_expression_terminator)?;
+            return Ok((None, condition));
+        }
+
+        self.advance(); // consume 'var'
+        let name_token = self.peek().clone();
+        let span = Self::span_from_token(&name_token);
+        if !matches!(name_token.kind, TokenKind::Identifier) {
+            self.diagnostics
+                .push_error_with_span("expected identifier after 'var'", Some(span));
+            bail!("invalid optional binding name");
+        }
+        self.advance();
+
+        if !matches!(self.peek_kind(), TokenKind::Equal) {
+            let token_span = Self::span_from_token(self.peek());
+            self.diagnostics.push_error_with_span(
+                format!(
+                    "expected '=' after '{}' in optional binding",
+                    name_token.lexeme
+                ),
+                Some(token_span),
+            );
+            bail!("invalid optional binding");
+        }
+        self.advance(); // consume '='
+
+        let value = self.parse_expression_with(default_expression_terminator)?;
+        Ok((
+            Some(OptionalBinding {
+                name: name_token.lexeme,
+                span,
+            }),
+            value,
+        ))
+    }
+
     fn parse_return(&mut self) -> Result<Statement> {
         let return_token = self.peek().clone();
         let span = Self::span_from_token(&return_token);
@@ -1653,13 +1986,43 @@ impl<'a> Parser<'a> {
                 TokenKind::InterpolatedStringExprStart => {
                     self.advance();
                     let expression = self.parse_expression_with(|kind| {
-                        matches!(kind, TokenKind::InterpolatedStringExprEnd)
+                        matches!(
+                            kind,
+                            TokenKind::InterpolatedStringExprEnd
+                                | TokenKind::InterpolatedStringFormatSpec(_)
+                        )
                     })?;
+                    let format = match self.peek_kind() {
+                        TokenKind::InterpolatedStringFormatSpec(_) => {
+                            let spec_token = self.advance().clone();
+                            let spec_span = Self::span_from_token(&spec_token);
+                            let TokenKind::InterpolatedStringFormatSpec(spec) = spec_token.kind
+                            else {
+                                unreachable!("peeked a format spec token");
+                            };
+                            if let Err(message) = FormatSpec::parse(&spec) {
+                                self.diagnostics
+                                    .push_error_with_span(message, Some(spec_span));
+                                bail!("invalid format specifier");
+                            }
+                            Some((spec, spec_span))
+                        }
+                        _ => None,
+                    };
                     self.expect_token(
                         TokenKind::InterpolatedStringExprEnd,
                         "expected '}' to close interpolation",
                     )?;
-                    parts.push(InterpolatedStringPart::Expression(expression));
+                    parts.push(match format {
+                        Some((spec, spec_span)) => {
+                            InterpolatedStringPart::Formatted(FormattedInterpolation {
+                                expression,
+                                spec,
+                                spec_span,
+                            })
+                        }
+                        None => InterpolatedStringPart::Expression(expression),
+                    });
                 }
                 TokenKind::InterpolatedStringEnd => {
                     let end_token = self.advance().clone();
@@ -2062,9 +2425,15 @@ impl<'a> Parser<'a> {
                 kind: ExpressionKind::Member(member),
             })),
             ExpressionKind::Grouping(inner) => self.build_match_pattern(*inner),
+            // Operators; folded when every name in them is a constant.
+            kind @ (ExpressionKind::Unary(_)
+            | ExpressionKind::Binary(_)
+            | ExpressionKind::InterpolatedString(_)) => {
+                Ok(MatchPattern::Expression(Expression { span, kind }))
+            }
             _ => {
                 self.diagnostics.push_error_with_span(
-                    "match patterns may only contain literals, identifiers, or enum variants",
+                    "match patterns may only contain literals, identifiers, operators, or enum variants",
                     Some(span),
                 );
                 bail!("invalid match pattern");
@@ -2213,6 +2582,7 @@ impl<'a> Parser<'a> {
                     span,
                     type_annotation,
                     default_value: None,
+                    variadic: false,
                 });
 
                 match self.peek_kind() {
@@ -2338,6 +2708,7 @@ impl<'a> Parser<'a> {
                 span,
                 type_annotation,
                 default_value: None,
+                variadic: false,
             });
 
             match self.peek_kind() {
@@ -2473,19 +2844,7 @@ impl<'a> Parser<'a> {
                 ))
             }
             TokenKind::DotDot | TokenKind::DotDotDot => {
-                // .. is exclusive (doesn't include end), ... is inclusive (includes end)
-                let inclusive = matches!(operator_token.kind, TokenKind::DotDotDot);
-                let right = self.parse_expression_prec(precedence, terminator)?;
-                let span =
-                    Self::union_spans(&Self::union_spans(&left.span, &operator_span), &right.span);
-                Ok(Self::make_expression(
-                    span,
-                    ExpressionKind::Range(RangeExpression {
-                        start: Box::new(left),
-                        end: Box::new(right),
-                        inclusive,
-                    }),
-                ))
+                self.finish_range(Some(left), &operator_token, precedence, terminator)
             }
             TokenKind::Keyword(Keyword::Is) => {
                 let (type_expression, type_span) =
@@ -2552,6 +2911,8 @@ impl<'a> Parser<'a> {
                 | TokenKind::Pipe
                 | TokenKind::FatArrow => depth == 0,
                 TokenKind::Comma | TokenKind::RParen => depth == 0,
+                // `extern def f() -> Int from "c"` ends its return type at `from`.
+                TokenKind::Identifier => depth == 0 && self.peek().lexeme == "from",
                 _ => false,
             };
 
@@ -2853,7 +3214,13 @@ impl<'a> Parser<'a> {
         loop {
             self.skip_newlines();
 
-            let (name, name_span) = if matches!(self.peek_kind(), TokenKind::Identifier)
+            let spread = matches!(self.peek_kind(), TokenKind::DotDot);
+            if spread {
+                self.advance(); // consume '..'
+            }
+
+            let (name, name_span) = if !spread
+                && matches!(self.peek_kind(), TokenKind::Identifier)
                 && matches!(self.peek_kind_at(1), Some(TokenKind::Colon))
             {
                 let name_token = self.advance().clone();
@@ -2872,6 +3239,7 @@ impl<'a> Parser<'a> {
                 name,
                 name_span,
                 expression,
+                spread,
             });
             match self.peek_kind() {
                 TokenKind::Comma => {
@@ -2914,7 +3282,19 @@ impl<'a> Parser<'a> {
         self.expect_token(TokenKind::LBracket, "expected '[' for index expression")?;
         let open_token = self.tokens.get(self.current - 1).cloned().unwrap();
         let open_span = Self::span_from_token(&open_token);
-        let index = self.parse_expression_prec(Precedence::Lowest, terminator_rbracket)?;
+        self.skip_newlines();
+        let index = if matches!(self.peek_kind(), TokenKind::DotDot | TokenKind::DotDotDot) {
+            // Open-start slice such as `xs[..n]`
+            let operator_token = self.advance().clone();
+            self.finish_range(
+                None,
+                &operator_token,
+                Precedence::Range,
+                terminator_rbracket,
+            )?
+        } else {
+            self.parse_expression_prec(Precedence::Lowest, terminator_rbracket)?
+        };
         let closing_token = self.peek().clone();
         self.expect_token(TokenKind::RBracket, "expected ']' after index expression")?;
         let closing_span = Self::span_from_token(&closing_token);
@@ -2933,6 +3313,62 @@ impl<'a> Parser<'a> {
         ))
     }
 
+    /// Finish a range after its `..`/`...` operator has been consumed. Either bound may be
+    /// omitted (`xs[..n]`, `xs[2..]`) and a step may follow with `by` (`xs[0..10 by 2]`).
+    fn finish_range(
+        &mut self,
+        start: Option<Expression>,
+        operator_token: &Token,
+        precedence: Precedence,
+        terminator: fn(&TokenKind) -> bool,
+    ) -> Result<Expression> {
+        // .. is exclusive (doesn't include end), ... is inclusive (includes end)
+        let inclusive = matches!(operator_token.kind, TokenKind::DotDotDot);
+        let mut span = Self::span_from_token(operator_token);
+        if let Some(start) = &start {
+            span = Self::union_spans(&start.span, &span);
+        }
+
+        let end = if self.is_at_end() || terminator(self.peek_kind()) || self.at_range_step() {
+            None
+        } else {
+            let end = self.parse_expression_prec(precedence, terminator)?;
+            span = Self::union_spans(&span, &end.span);
+            Some(Box::new(end))
+        };
+
+        let step = if self.at_range_step() {
+            self.advance();
+            let step = self.parse_expression_prec(precedence, terminator)?;
+            span = Self::union_spans(&span, &step.span);
+            Some(Box::new(step))
+        } else {
+            None
+        };
+
+        Ok(Self::make_expression(
+            span,
+            ExpressionKind::Range(RangeExpression {
+                start: start.map(Box::new),
+                end,
+                inclusive,
+                step,
+            }),
+        ))
+    }
+
+    /// `by` is contextual: it only introduces a step when an expression follows it, so
+    /// `xs[0..by]` still slices up to a variable named `by`.
+    fn at_range_step(&self) -> bool {
+        let token = self.peek();
+        token.kind == TokenKind::Identifier
+            && token.lexeme == "by"
+            && !matches!(
+                self.peek_kind_at(1),
+                None | Some(TokenKind::RBracket) | Some(TokenKind::Newline) | Some(TokenKind::Eof)
+            )
+    }
+
     fn finish_member(&mut self, object: Expression) -> Result<Expression> {
         self.expect_token(TokenKind::Dot, "expected '.' for member access")?;
         let dot_token = self.tokens.get(self.current - 1).cloned().unwrap();
@@ -3207,6 +3643,16 @@ impl<'a> Parser<'a> {
     }
 }
 
+/// Hidden module alias for `use { ... } from "path"`. The line keeps two
+/// selective imports of the same module from sharing an alias.
+fn selective_use_alias(module_path: &str, span: SourceSpan) -> String {
+    let sanitized: String = module_path
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+        .collect();
+    format!("__use_{}_{}", sanitized.trim_matches('_'), span.line)
+}
+
 fn default_expression_terminator(kind: &TokenKind) -> bool {
     matches!(
         kind,
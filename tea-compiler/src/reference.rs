@@ -307,6 +307,7 @@ fn std_type_name(ty: StdType) -> &'static str {
         StdType::Int => "Int",
         StdType::Float => "Float",
         StdType::String => "String",
+        StdType::Bytes => "Bytes",
         StdType::List => "List",
         StdType::Dict => "Dict",
         StdType::Struct => "Struct",
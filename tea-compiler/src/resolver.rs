@@ -3,11 +3,12 @@ use std::collections::{HashMap, HashSet};
 use crate::ast::{
     AssignmentExpression, BinaryExpression, Block, CallExpression, CatchArm, CatchHandler,
     CatchKind, ConditionalStatement, DictLiteral, EnumStatement, ErrorStatement, Expression,
-    ExpressionKind, ForPattern, FunctionParameter, FunctionStatement, Identifier, IndexExpression,
-    InterpolatedStringPart, LambdaBody, LambdaExpression, ListLiteral, LoopHeader, LoopKind,
-    LoopStatement, MatchPattern, MatchStatement, MemberExpression, Module, ReturnStatement,
-    SourceSpan, Statement, StructStatement, TestStatement, ThrowStatement, TryExpression,
-    UnaryExpression, UnionStatement, UseStatement, VarStatement,
+    ExpressionKind, ForPattern, FormattedInterpolation, FunctionParameter, FunctionStatement,
+    GuardStatement, Identifier, IndexExpression, InterpolatedStringPart, LambdaBody,
+    LambdaExpression, ListLiteral, LoopHeader, LoopKind, LoopStatement, MatchPattern,
+    MatchStatement, MemberExpression, Module, OptionalBinding, ReturnStatement, SourceSpan,
+    Statement, StructStatement, TestStatement, ThrowStatement, TryExpression, UnaryExpression,
+    UnionStatement, UseStatement, VarStatement,
 };
 use crate::diagnostics::Diagnostics;
 use crate::stdlib;
@@ -136,11 +137,13 @@ impl Resolver {
             Statement::Error(error_stmt) => self.resolve_error(error_stmt),
             Statement::Conditional(cond_stmt) => self.resolve_conditional(cond_stmt),
             Statement::Loop(loop_stmt) => self.resolve_loop(loop_stmt),
+            Statement::Guard(guard_stmt) => self.resolve_guard(guard_stmt),
             Statement::Break(_) | Statement::Continue(_) => {
                 // Break and continue are valid loop control statements
                 // No resolution needed as they don't reference variables
             }
             Statement::Throw(throw_stmt) => self.resolve_throw(throw_stmt),
+            Statement::Yield(yield_stmt) => self.resolve_expression(&yield_stmt.expression),
             Statement::Return(ret_stmt) => self.resolve_return(ret_stmt),
             Statement::Match(match_stmt) => self.resolve_match_statement(match_stmt),
             Statement::Expression(expr_stmt) => self.resolve_expression(&expr_stmt.expression),
@@ -239,6 +242,10 @@ impl Resolver {
             BindingKind::Function,
             true,
         );
+        // An extern declaration has no body to use its parameters in.
+        if function_stmt.extern_function.is_some() {
+            return;
+        }
 
         self.push_scope();
         for parameter in &function_stmt.parameters {
@@ -261,6 +268,11 @@ impl Resolver {
             BindingKind::Struct,
             true,
         );
+        for field in &struct_stmt.fields {
+            if let Some(default_value) = &field.default_value {
+                self.resolve_expression(default_value);
+            }
+        }
     }
 
     fn resolve_union(&mut self, union_stmt: &UnionStatement) {
@@ -292,7 +304,7 @@ impl Resolver {
 
     fn resolve_conditional(&mut self, cond_stmt: &ConditionalStatement) {
         self.resolve_expression(&cond_stmt.condition);
-        self.resolve_block(&cond_stmt.consequent);
+        self.resolve_bound_block(cond_stmt.binding.as_ref(), &cond_stmt.consequent);
         if let Some(alternative) = &cond_stmt.alternative {
             self.resolve_block(alternative);
         }
@@ -347,15 +359,38 @@ impl Resolver {
                     );
                 }
             }
-            LoopKind::While => {
-                if let LoopHeader::Condition(condition) = &loop_stmt.header {
+            LoopKind::While => match &loop_stmt.header {
+                LoopHeader::Condition(condition) => {
                     self.resolve_expression(condition);
+                    self.resolve_block(&loop_stmt.body);
                 }
-                self.resolve_block(&loop_stmt.body);
-            }
+                LoopHeader::Binding { binding, value } => {
+                    self.resolve_expression(value);
+                    self.resolve_bound_block(Some(binding), &loop_stmt.body);
+                }
+                LoopHeader::For { .. } => self.resolve_block(&loop_stmt.body),
+            },
         }
     }
 
+    fn resolve_guard(&mut self, guard_stmt: &GuardStatement) {
+        self.resolve_expression(&guard_stmt.condition);
+        self.resolve_block(&guard_stmt.alternative);
+        // The bound name stays in scope for the rest of the enclosing block.
+        if let Some(binding) = &guard_stmt.binding {
+            self.declare_binding(&binding.name, binding.span, BindingKind::Variable, true);
+        }
+    }
+
+    fn resolve_bound_block(&mut self, binding: Option<&OptionalBinding>, block: &Block) {
+        self.push_scope();
+        if let Some(binding) = binding {
+            self.declare_binding(&binding.name, binding.span, BindingKind::Variable, true);
+        }
+        self.resolve_statements(&block.statements);
+        self.pop_scope();
+    }
+
     fn resolve_return(&mut self, return_stmt: &ReturnStatement) {
         if let Some(expr) = &return_stmt.expression {
             self.resolve_expression(expr);
@@ -390,7 +425,12 @@ impl Resolver {
             ExpressionKind::Literal(_) => {}
             ExpressionKind::InterpolatedString(template) => {
                 for part in &template.parts {
-                    if let InterpolatedStringPart::Expression(expr) = part {
+                    if let InterpolatedStringPart::Expression(expr)
+                    | InterpolatedStringPart::Formatted(FormattedInterpolation {
+                        expression: expr,
+                        ..
+                    }) = part
+                    {
                         self.resolve_expression(expr);
                     }
                 }
@@ -403,8 +443,12 @@ impl Resolver {
             ExpressionKind::Member(member) => self.resolve_member(member),
             ExpressionKind::Index(index) => self.resolve_index(index),
             ExpressionKind::Range(range) => {
-                self.resolve_expression(&range.start);
-                self.resolve_expression(&range.end);
+                for bound in [&range.start, &range.end, &range.step]
+                    .into_iter()
+                    .flatten()
+                {
+                    self.resolve_expression(bound);
+                }
             }
             ExpressionKind::Lambda(lambda) => self.resolve_lambda(lambda),
             ExpressionKind::Is(is_expr) => self.resolve_expression(&is_expr.value),
@@ -12,7 +12,9 @@ pub(super) const fn function_doc(kind: StdFunctionKind) -> &'static str {
         StdFunctionKind::Panic => "Terminate the program immediately with an error message.",
         StdFunctionKind::Exit => "Exit the program with the specified exit code.",
         StdFunctionKind::Args => "Return command-line arguments as a list of strings.",
-        StdFunctionKind::Length => "Return the number of elements in a String, List, or Dict.",
+        StdFunctionKind::Length => {
+            "Return the number of characters in a String or elements in a List, Bytes, or Dict."
+        }
         StdFunctionKind::Assert => {
             "Assert that a condition holds; optionally provide a failure message."
         }
@@ -32,13 +34,75 @@ pub(super) const fn function_doc(kind: StdFunctionKind) -> &'static str {
         }
         StdFunctionKind::StringToLower => "Convert a string to lowercase.",
         StdFunctionKind::StringToUpper => "Convert a string to uppercase.",
+        StdFunctionKind::StringChars => {
+            "Split a string into its Unicode characters (scalar values)."
+        }
+        StdFunctionKind::StringGraphemes => {
+            "Split a string into user-perceived characters (extended grapheme clusters)."
+        }
+        StdFunctionKind::StringCodePoints => {
+            "Return the Unicode code point of every character in a string."
+        }
+        StdFunctionKind::StringByteLen => "Return the length of a string in UTF-8 bytes.",
+        StdFunctionKind::StringDisplayWidth => {
+            "Return the number of terminal columns a string occupies."
+        }
+        StdFunctionKind::StringFoldCase => {
+            "Apply Unicode case folding for case-insensitive comparison."
+        }
+        StdFunctionKind::StringNormalizeNfc => "Convert a string to Unicode Normalization Form C.",
+        StdFunctionKind::StringNormalizeNfd => "Convert a string to Unicode Normalization Form D.",
+        StdFunctionKind::StringIsAlpha => {
+            "Return true when a non-empty string contains only alphabetic characters."
+        }
+        StdFunctionKind::StringIsDigit => {
+            "Return true when a non-empty string contains only numeric characters."
+        }
+        StdFunctionKind::StringIsWhitespace => {
+            "Return true when a non-empty string contains only whitespace."
+        }
+        StdFunctionKind::BytesFromList => "Build a byte buffer from a list of values in 0..=255.",
+        StdFunctionKind::BytesToList => "Convert a byte buffer into a list of integer values.",
+        StdFunctionKind::BytesFromString => "Encode a string as UTF-8 bytes.",
+        StdFunctionKind::BytesToString => {
+            "Decode UTF-8 bytes into a string, failing on invalid sequences."
+        }
+        StdFunctionKind::BytesToStringLossy => {
+            "Decode UTF-8 bytes into a string, replacing invalid sequences with U+FFFD."
+        }
+        StdFunctionKind::BytesIsUtf8 => "Return true when the bytes are valid UTF-8.",
+        StdFunctionKind::BytesToHex => "Encode bytes as a lowercase hexadecimal string.",
+        StdFunctionKind::BytesFromHex => "Decode a hexadecimal string into bytes.",
+        StdFunctionKind::BytesToBase64 => "Encode bytes as standard padded base64.",
+        StdFunctionKind::BytesFromBase64 => "Decode a standard padded base64 string into bytes.",
+        StdFunctionKind::BytesDecode => {
+            "Decode \"hex\" or \"base64\" text, recording a failure instead of panicking."
+        }
+        StdFunctionKind::BytesDecodeError => {
+            "Return the message of the last failed decode on this thread."
+        }
+        StdFunctionKind::BytesConcat => "Concatenate two byte buffers into a new buffer.",
         StdFunctionKind::MathFloor => "Round a float down to the nearest integer.",
         StdFunctionKind::MathCeil => "Round a float up to the nearest integer.",
         StdFunctionKind::MathRound => "Round a float to the nearest integer.",
         StdFunctionKind::MathAbs => "Return the absolute value of a float.",
         StdFunctionKind::MathSqrt => "Return the square root of a float.",
-        StdFunctionKind::MathMin => "Return the minimum of two floats.",
-        StdFunctionKind::MathMax => "Return the maximum of two floats.",
+        StdFunctionKind::MathMin => "Return the smaller of two Ints or two Floats.",
+        StdFunctionKind::MathMax => "Return the larger of two Ints or two Floats.",
+        StdFunctionKind::MathSin => "Return the sine of an angle in radians.",
+        StdFunctionKind::MathCos => "Return the cosine of an angle in radians.",
+        StdFunctionKind::MathTan => "Return the tangent of an angle in radians.",
+        StdFunctionKind::MathAtan2 => {
+            "Return the angle in radians of the point (x, y) from the positive x axis."
+        }
+        StdFunctionKind::MathExp => "Return e raised to the given power.",
+        StdFunctionKind::MathLn => "Return the natural logarithm of a float.",
+        StdFunctionKind::MathLog10 => "Return the base-10 logarithm of a float.",
+        StdFunctionKind::MathLog2 => "Return the base-2 logarithm of a float.",
+        StdFunctionKind::MathPow => "Raise a float to a floating-point power.",
+        StdFunctionKind::MathHypot => "Return the Euclidean distance from the origin to (x, y).",
+        StdFunctionKind::MathIsNan => "Return true when a float is NaN.",
+        StdFunctionKind::MathIsFinite => "Return true when a float is neither infinite nor NaN.",
         StdFunctionKind::EnvGet => {
             "Lookup an environment variable, returning an empty string when unset."
         }
@@ -56,15 +120,15 @@ pub(super) const fn function_doc(kind: StdFunctionKind) -> &'static str {
         StdFunctionKind::EnvHomeDir => "Return the user home directory when available.",
         StdFunctionKind::EnvConfigDir => "Return the user configuration directory when available.",
         StdFunctionKind::FsReadText => "Read an entire text file into a string.",
-        StdFunctionKind::FsReadBytes => "Read an entire file into a list of byte values.",
+        StdFunctionKind::FsReadBytes => "Read an entire file into a byte buffer.",
         StdFunctionKind::FsWriteText => "Write a string to a file, replacing existing contents.",
         StdFunctionKind::FsWriteTextAtomic => "Write a string to a file atomically.",
         StdFunctionKind::FsWriteBytes => {
-            "Write a list of byte values to a file, replacing existing contents."
+            "Write a byte buffer to a file, replacing existing contents."
         }
-        StdFunctionKind::FsWriteBytesAtomic => "Write a list of byte values to a file atomically.",
+        StdFunctionKind::FsWriteBytesAtomic => "Write a byte buffer to a file atomically.",
         StdFunctionKind::FsAppendText => "Append text to a file, creating it when needed.",
-        StdFunctionKind::FsAppendBytes => "Append byte values to a file, creating it when needed.",
+        StdFunctionKind::FsAppendBytes => "Append bytes to a file, creating it when needed.",
         StdFunctionKind::FsCreateDir => {
             "Create a directory. Parent directories must already exist."
         }
@@ -159,7 +223,101 @@ pub(super) const fn function_doc(kind: StdFunctionKind) -> &'static str {
         // JSON module
         StdFunctionKind::JsonEncode => "Encode a value as a JSON string.",
         StdFunctionKind::JsonDecode => "Decode a JSON string into a Tea value.",
+        StdFunctionKind::JsonEncodeWith => {
+            "Encode a value as JSON, renaming struct fields and optionally omitting nil fields."
+        }
+        StdFunctionKind::JsonDecodeAs => {
+            "Decode a JSON string into the type given as the type argument, validating its shape."
+        }
+        StdFunctionKind::JsonDecodeError => {
+            "Return the mismatch found by the last typed JSON decode, or an empty string."
+        }
+        StdFunctionKind::JsonDecodeErrorPath => {
+            "Return the JSON path of the last decode mismatch, or an empty string."
+        }
+        // YAML and TOML modules
+        StdFunctionKind::YamlEncode => "Encode a value as a YAML document.",
+        StdFunctionKind::YamlDecode => "Decode a YAML document into a Tea value.",
+        StdFunctionKind::TomlEncode => "Encode a Dict as a TOML document.",
+        StdFunctionKind::TomlDecode => "Decode a TOML document into a Dict.",
+        // CSV module
+        StdFunctionKind::CsvDecodeRecord => {
+            "Convert a CSV record into the struct given as the type argument, parsing each column."
+        }
         // HTTP module
         StdFunctionKind::HttpSend => "Send an HTTP request and return a response dictionary.",
+        // Task module
+        StdFunctionKind::TaskSpawn => "Run a closure on a new thread and return a task handle.",
+        StdFunctionKind::TaskJoin => "Wait for a task to finish and return its result.",
+        StdFunctionKind::ChannelNew => "Create a bounded channel with the given capacity.",
+        StdFunctionKind::ChannelSend => "Send a value, blocking while the channel is full.",
+        StdFunctionKind::ChannelRecv => {
+            "Receive a value, or nil once the channel is closed and empty."
+        }
+        StdFunctionKind::ChannelClose => "Close a channel so no further values can be sent.",
+        // Iterator module
+        StdFunctionKind::IterNext => "Advance an iterator, or return nil once it is exhausted.",
+        // Streaming readers
+        StdFunctionKind::FsOpenRead => "Open a file for reading and return a handle.",
+        StdFunctionKind::FsReadLine => "Read the next line from a file, or nil at end of file.",
+        StdFunctionKind::FsClose => "Close a file handle.",
+        StdFunctionKind::ProcessReadStdoutLine => {
+            "Read the next stdout line from a process, or nil once it is exhausted."
+        }
+        // Random module
+        StdFunctionKind::RandomSeeded => "Create a generator whose sequence is fixed by a seed.",
+        StdFunctionKind::RandomInt => "Draw a uniform integer from an inclusive range.",
+        StdFunctionKind::RandomFloat => "Draw a uniform float from 0.0 up to but excluding 1.0.",
+        // Crypto module
+        StdFunctionKind::CryptoDigest => "Hash bytes with sha256, sha1, md5 or blake3.",
+        StdFunctionKind::CryptoHmacSha256 => "Compute an HMAC-SHA256 tag for a message.",
+        StdFunctionKind::CryptoConstantTimeEq => {
+            "Compare two byte buffers without leaking where they differ."
+        }
+        // Terminal module
+        StdFunctionKind::TermIsTerminal => {
+            "Return true if stdin (0), stdout (1) or stderr (2) is a terminal."
+        }
+        StdFunctionKind::TermColumns => "Return the terminal width, or 0 when it is unknown.",
+        StdFunctionKind::TermRows => "Return the terminal height, or 0 when it is unknown.",
+        StdFunctionKind::TermSetEcho => "Turn echoing of typed input on or off.",
+        // HTTP server
+        StdFunctionKind::HttpServerBind => "Listen for HTTP requests on an address.",
+        StdFunctionKind::HttpServerAddress => "Return the address a server is listening on.",
+        StdFunctionKind::HttpServerAccept => {
+            "Wait for the next request, returning its connection or 0 after shutdown."
+        }
+        StdFunctionKind::HttpServerRequest => {
+            "Return the method, URL, headers and body of a request."
+        }
+        StdFunctionKind::HttpServerRespond => "Send a response and close the connection.",
+        StdFunctionKind::HttpServerShutdown => "Stop a server after the current request.",
+        // Sockets
+        StdFunctionKind::NetTcpConnect => {
+            "Connect to a TCP address, returning a handle or 0 on failure."
+        }
+        StdFunctionKind::NetTcpListen => "Listen for TCP connections on an address.",
+        StdFunctionKind::NetUnixConnect => "Connect to a Unix domain socket.",
+        StdFunctionKind::NetUnixListen => "Listen on a Unix domain socket path.",
+        StdFunctionKind::NetUdpBind => "Bind a UDP socket to an address.",
+        StdFunctionKind::NetAccept => "Wait for the next connection on a listener.",
+        StdFunctionKind::NetRead => {
+            "Read the bytes available on a connection, empty at end of stream."
+        }
+        StdFunctionKind::NetReadLine => "Read through the next line break, empty at end of stream.",
+        StdFunctionKind::NetWrite => "Write bytes to a connection.",
+        StdFunctionKind::NetShutdownWrite => "Close the sending half of a connection.",
+        StdFunctionKind::NetLocalAddress => "Return the local address of a socket.",
+        StdFunctionKind::NetPeerAddress => "Return the address at the other end of a connection.",
+        StdFunctionKind::NetUdpSend => "Send a datagram to an address.",
+        StdFunctionKind::NetUdpReceive => "Wait for the next datagram on a UDP socket.",
+        StdFunctionKind::NetUdpSender => "Return the sender of the last datagram received.",
+        StdFunctionKind::NetClose => "Close a socket.",
+        StdFunctionKind::NetErrorKind => {
+            "Return the kind of the last socket failure on this thread."
+        }
+        StdFunctionKind::NetErrorMessage => {
+            "Return the message of the last socket failure on this thread."
+        }
     }
 }
@@ -13,7 +13,7 @@ const FS_FUNCTIONS: &[StdFunction] = &[
         StdFunctionKind::FsReadBytes,
         StdArity::Exact(1),
         &[StdType::String],
-        StdType::List,
+        StdType::Bytes,
     ),
     std_function(
         "write_file",
@@ -26,14 +26,14 @@ const FS_FUNCTIONS: &[StdFunction] = &[
         "write_bytes",
         StdFunctionKind::FsWriteBytes,
         StdArity::Exact(2),
-        &[StdType::String, StdType::List],
+        &[StdType::String, StdType::Bytes],
         StdType::Void,
     ),
     std_function(
         "write_bytes_atomic",
         StdFunctionKind::FsWriteBytesAtomic,
         StdArity::Exact(2),
-        &[StdType::String, StdType::List],
+        &[StdType::String, StdType::Bytes],
         StdType::Void,
     ),
     std_function(
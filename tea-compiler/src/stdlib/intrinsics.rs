@@ -53,6 +53,175 @@ const INTRINSIC_FUNCTIONS: &[StdFunction] = &[
         &[StdType::String],
         StdType::String,
     ),
+    std_function(
+        "string_chars",
+        StdFunctionKind::StringChars,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::List,
+    ),
+    std_function(
+        "string_graphemes",
+        StdFunctionKind::StringGraphemes,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::List,
+    ),
+    std_function(
+        "string_code_points",
+        StdFunctionKind::StringCodePoints,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::List,
+    ),
+    std_function(
+        "string_byte_len",
+        StdFunctionKind::StringByteLen,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::Int,
+    ),
+    std_function(
+        "string_display_width",
+        StdFunctionKind::StringDisplayWidth,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::Int,
+    ),
+    std_function(
+        "string_fold_case",
+        StdFunctionKind::StringFoldCase,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::String,
+    ),
+    std_function(
+        "string_normalize_nfc",
+        StdFunctionKind::StringNormalizeNfc,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::String,
+    ),
+    std_function(
+        "string_normalize_nfd",
+        StdFunctionKind::StringNormalizeNfd,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::String,
+    ),
+    std_function(
+        "string_is_alpha",
+        StdFunctionKind::StringIsAlpha,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::Bool,
+    ),
+    std_function(
+        "string_is_digit",
+        StdFunctionKind::StringIsDigit,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::Bool,
+    ),
+    std_function(
+        "string_is_whitespace",
+        StdFunctionKind::StringIsWhitespace,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::Bool,
+    ),
+    // Bytes
+    std_function(
+        "bytes_from_list",
+        StdFunctionKind::BytesFromList,
+        StdArity::Exact(1),
+        &[StdType::List],
+        StdType::Bytes,
+    ),
+    std_function(
+        "bytes_to_list",
+        StdFunctionKind::BytesToList,
+        StdArity::Exact(1),
+        &[StdType::Bytes],
+        StdType::List,
+    ),
+    std_function(
+        "bytes_from_string",
+        StdFunctionKind::BytesFromString,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::Bytes,
+    ),
+    std_function(
+        "bytes_to_string",
+        StdFunctionKind::BytesToString,
+        StdArity::Exact(1),
+        &[StdType::Bytes],
+        StdType::String,
+    ),
+    std_function(
+        "bytes_to_string_lossy",
+        StdFunctionKind::BytesToStringLossy,
+        StdArity::Exact(1),
+        &[StdType::Bytes],
+        StdType::String,
+    ),
+    std_function(
+        "bytes_is_utf8",
+        StdFunctionKind::BytesIsUtf8,
+        StdArity::Exact(1),
+        &[StdType::Bytes],
+        StdType::Bool,
+    ),
+    std_function(
+        "bytes_to_hex",
+        StdFunctionKind::BytesToHex,
+        StdArity::Exact(1),
+        &[StdType::Bytes],
+        StdType::String,
+    ),
+    std_function(
+        "bytes_from_hex",
+        StdFunctionKind::BytesFromHex,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::Bytes,
+    ),
+    std_function(
+        "bytes_to_base64",
+        StdFunctionKind::BytesToBase64,
+        StdArity::Exact(1),
+        &[StdType::Bytes],
+        StdType::String,
+    ),
+    std_function(
+        "bytes_from_base64",
+        StdFunctionKind::BytesFromBase64,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::Bytes,
+    ),
+    std_function(
+        "bytes_decode",
+        StdFunctionKind::BytesDecode,
+        StdArity::Exact(2),
+        &[StdType::String, StdType::String],
+        StdType::Bytes,
+    ),
+    std_function(
+        "bytes_decode_error",
+        StdFunctionKind::BytesDecodeError,
+        StdArity::Exact(0),
+        &[],
+        StdType::String,
+    ),
+    std_function(
+        "bytes_concat",
+        StdFunctionKind::BytesConcat,
+        StdArity::Exact(2),
+        &[StdType::Bytes, StdType::Bytes],
+        StdType::Bytes,
+    ),
     // Math
     std_function(
         "math_floor",
@@ -103,6 +272,90 @@ const INTRINSIC_FUNCTIONS: &[StdFunction] = &[
         &[StdType::Float, StdType::Float],
         StdType::Float,
     ),
+    std_function(
+        "math_sin",
+        StdFunctionKind::MathSin,
+        StdArity::Exact(1),
+        &[StdType::Float],
+        StdType::Float,
+    ),
+    std_function(
+        "math_cos",
+        StdFunctionKind::MathCos,
+        StdArity::Exact(1),
+        &[StdType::Float],
+        StdType::Float,
+    ),
+    std_function(
+        "math_tan",
+        StdFunctionKind::MathTan,
+        StdArity::Exact(1),
+        &[StdType::Float],
+        StdType::Float,
+    ),
+    std_function(
+        "math_atan2",
+        StdFunctionKind::MathAtan2,
+        StdArity::Exact(2),
+        &[StdType::Float, StdType::Float],
+        StdType::Float,
+    ),
+    std_function(
+        "math_exp",
+        StdFunctionKind::MathExp,
+        StdArity::Exact(1),
+        &[StdType::Float],
+        StdType::Float,
+    ),
+    std_function(
+        "math_ln",
+        StdFunctionKind::MathLn,
+        StdArity::Exact(1),
+        &[StdType::Float],
+        StdType::Float,
+    ),
+    std_function(
+        "math_log10",
+        StdFunctionKind::MathLog10,
+        StdArity::Exact(1),
+        &[StdType::Float],
+        StdType::Float,
+    ),
+    std_function(
+        "math_log2",
+        StdFunctionKind::MathLog2,
+        StdArity::Exact(1),
+        &[StdType::Float],
+        StdType::Float,
+    ),
+    std_function(
+        "math_pow",
+        StdFunctionKind::MathPow,
+        StdArity::Exact(2),
+        &[StdType::Float, StdType::Float],
+        StdType::Float,
+    ),
+    std_function(
+        "math_hypot",
+        StdFunctionKind::MathHypot,
+        StdArity::Exact(2),
+        &[StdType::Float, StdType::Float],
+        StdType::Float,
+    ),
+    std_function(
+        "math_is_nan",
+        StdFunctionKind::MathIsNan,
+        StdArity::Exact(1),
+        &[StdType::Float],
+        StdType::Bool,
+    ),
+    std_function(
+        "math_is_finite",
+        StdFunctionKind::MathIsFinite,
+        StdArity::Exact(1),
+        &[StdType::Float],
+        StdType::Bool,
+    ),
     // Assertions
     std_function(
         "fail",
@@ -209,7 +462,7 @@ const INTRINSIC_FUNCTIONS: &[StdFunction] = &[
         StdFunctionKind::FsReadBytes,
         StdArity::Exact(1),
         &[StdType::String],
-        StdType::List,
+        StdType::Bytes,
     ),
     std_function(
         "fs_write_text",
@@ -229,14 +482,14 @@ const INTRINSIC_FUNCTIONS: &[StdFunction] = &[
         "fs_write_bytes",
         StdFunctionKind::FsWriteBytes,
         StdArity::Exact(2),
-        &[StdType::String, StdType::List],
+        &[StdType::String, StdType::Bytes],
         StdType::Void,
     ),
     std_function(
         "fs_write_bytes_atomic",
         StdFunctionKind::FsWriteBytesAtomic,
         StdArity::Exact(2),
-        &[StdType::String, StdType::List],
+        &[StdType::String, StdType::Bytes],
         StdType::Void,
     ),
     std_function(
@@ -250,7 +503,7 @@ const INTRINSIC_FUNCTIONS: &[StdFunction] = &[
         "fs_append_bytes",
         StdFunctionKind::FsAppendBytes,
         StdArity::Exact(2),
-        &[StdType::String, StdType::List],
+        &[StdType::String, StdType::Bytes],
         StdType::Void,
     ),
     std_function(
@@ -578,20 +831,20 @@ const INTRINSIC_FUNCTIONS: &[StdFunction] = &[
         StdFunctionKind::ProcessReadStdoutBytes,
         StdArity::Exact(2),
         &[StdType::Int, StdType::Int],
-        StdType::List,
+        StdType::Bytes,
     ),
     std_function(
         "process_read_stderr_bytes",
         StdFunctionKind::ProcessReadStderrBytes,
         StdArity::Exact(2),
         &[StdType::Int, StdType::Int],
-        StdType::List,
+        StdType::Bytes,
     ),
     std_function(
         "process_write_stdin_bytes",
         StdFunctionKind::ProcessWriteStdinBytes,
         StdArity::Exact(2),
-        &[StdType::Int, StdType::List],
+        &[StdType::Int, StdType::Bytes],
         StdType::Void,
     ),
     std_function(
@@ -698,6 +951,71 @@ const INTRINSIC_FUNCTIONS: &[StdFunction] = &[
         &[StdType::String],
         StdType::Any,
     ),
+    std_function(
+        "json_encode_with",
+        StdFunctionKind::JsonEncodeWith,
+        StdArity::Exact(3),
+        &[StdType::Any, StdType::Dict, StdType::Bool],
+        StdType::String,
+    ),
+    std_function(
+        "json_decode_as",
+        StdFunctionKind::JsonDecodeAs,
+        StdArity::Exact(2),
+        &[StdType::String, StdType::Dict],
+        StdType::Any,
+    ),
+    std_function(
+        "json_decode_error",
+        StdFunctionKind::JsonDecodeError,
+        StdArity::Exact(0),
+        &[],
+        StdType::String,
+    ),
+    std_function(
+        "json_decode_error_path",
+        StdFunctionKind::JsonDecodeErrorPath,
+        StdArity::Exact(0),
+        &[],
+        StdType::String,
+    ),
+    // YAML and TOML encoding/decoding
+    std_function(
+        "yaml_encode",
+        StdFunctionKind::YamlEncode,
+        StdArity::Exact(1),
+        &[StdType::Any],
+        StdType::String,
+    ),
+    std_function(
+        "yaml_decode",
+        StdFunctionKind::YamlDecode,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::Any,
+    ),
+    std_function(
+        "toml_encode",
+        StdFunctionKind::TomlEncode,
+        StdArity::Exact(1),
+        &[StdType::Any],
+        StdType::String,
+    ),
+    std_function(
+        "toml_decode",
+        StdFunctionKind::TomlDecode,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::Any,
+    ),
+    // CSV
+    std_function(
+        "csv_decode_record",
+        StdFunctionKind::CsvDecodeRecord,
+        StdArity::Exact(3),
+        &[StdType::Dict, StdType::Dict, StdType::Int],
+        StdType::Any,
+    ),
     // HTTP
     std_function(
         "http_send",
@@ -712,6 +1030,329 @@ const INTRINSIC_FUNCTIONS: &[StdFunction] = &[
         ],
         StdType::Dict,
     ),
+    // HTTP server
+    std_function(
+        "http_server_bind",
+        StdFunctionKind::HttpServerBind,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::Int,
+    ),
+    std_function(
+        "http_server_address",
+        StdFunctionKind::HttpServerAddress,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::String,
+    ),
+    std_function(
+        "http_server_accept",
+        StdFunctionKind::HttpServerAccept,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::Int,
+    ),
+    std_function(
+        "http_server_request",
+        StdFunctionKind::HttpServerRequest,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::Dict,
+    ),
+    std_function(
+        "http_server_respond",
+        StdFunctionKind::HttpServerRespond,
+        StdArity::Exact(4),
+        &[StdType::Int, StdType::Int, StdType::Dict, StdType::Bytes],
+        StdType::Void,
+    ),
+    std_function(
+        "http_server_shutdown",
+        StdFunctionKind::HttpServerShutdown,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::Void,
+    ),
+    // Sockets
+    std_function(
+        "net_tcp_connect",
+        StdFunctionKind::NetTcpConnect,
+        StdArity::Exact(2),
+        &[StdType::String, StdType::Int],
+        StdType::Int,
+    ),
+    std_function(
+        "net_tcp_listen",
+        StdFunctionKind::NetTcpListen,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::Int,
+    ),
+    std_function(
+        "net_unix_connect",
+        StdFunctionKind::NetUnixConnect,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::Int,
+    ),
+    std_function(
+        "net_unix_listen",
+        StdFunctionKind::NetUnixListen,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::Int,
+    ),
+    std_function(
+        "net_udp_bind",
+        StdFunctionKind::NetUdpBind,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::Int,
+    ),
+    std_function(
+        "net_accept",
+        StdFunctionKind::NetAccept,
+        StdArity::Exact(2),
+        &[StdType::Int, StdType::Int],
+        StdType::Int,
+    ),
+    std_function(
+        "net_read",
+        StdFunctionKind::NetRead,
+        StdArity::Exact(3),
+        &[StdType::Int, StdType::Int, StdType::Int],
+        StdType::Bytes,
+    ),
+    std_function(
+        "net_read_line",
+        StdFunctionKind::NetReadLine,
+        StdArity::Exact(2),
+        &[StdType::Int, StdType::Int],
+        StdType::String,
+    ),
+    std_function(
+        "net_write",
+        StdFunctionKind::NetWrite,
+        StdArity::Exact(3),
+        &[StdType::Int, StdType::Bytes, StdType::Int],
+        StdType::Void,
+    ),
+    std_function(
+        "net_shutdown_write",
+        StdFunctionKind::NetShutdownWrite,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::Void,
+    ),
+    std_function(
+        "net_local_address",
+        StdFunctionKind::NetLocalAddress,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::String,
+    ),
+    std_function(
+        "net_peer_address",
+        StdFunctionKind::NetPeerAddress,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::String,
+    ),
+    std_function(
+        "net_udp_send",
+        StdFunctionKind::NetUdpSend,
+        StdArity::Exact(3),
+        &[StdType::Int, StdType::String, StdType::Bytes],
+        StdType::Int,
+    ),
+    std_function(
+        "net_udp_receive",
+        StdFunctionKind::NetUdpReceive,
+        StdArity::Exact(3),
+        &[StdType::Int, StdType::Int, StdType::Int],
+        StdType::Bytes,
+    ),
+    std_function(
+        "net_udp_sender",
+        StdFunctionKind::NetUdpSender,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::String,
+    ),
+    std_function(
+        "net_close",
+        StdFunctionKind::NetClose,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::Void,
+    ),
+    std_function(
+        "net_error_kind",
+        StdFunctionKind::NetErrorKind,
+        StdArity::Exact(0),
+        &[],
+        StdType::String,
+    ),
+    std_function(
+        "net_error_message",
+        StdFunctionKind::NetErrorMessage,
+        StdArity::Exact(0),
+        &[],
+        StdType::String,
+    ),
+    // Tasks and channels
+    std_function(
+        "task_spawn",
+        StdFunctionKind::TaskSpawn,
+        StdArity::Exact(1),
+        &[StdType::Any],
+        StdType::Int,
+    ),
+    std_function(
+        "task_join",
+        StdFunctionKind::TaskJoin,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::Any,
+    ),
+    std_function(
+        "channel_new",
+        StdFunctionKind::ChannelNew,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::Int,
+    ),
+    std_function(
+        "channel_send",
+        StdFunctionKind::ChannelSend,
+        StdArity::Exact(2),
+        &[StdType::Int, StdType::Any],
+        StdType::Bool,
+    ),
+    std_function(
+        "channel_recv",
+        StdFunctionKind::ChannelRecv,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::Any,
+    ),
+    std_function(
+        "channel_close",
+        StdFunctionKind::ChannelClose,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::Void,
+    ),
+    // Iterators
+    std_function(
+        "iter_next",
+        StdFunctionKind::IterNext,
+        StdArity::Exact(1),
+        &[StdType::Any],
+        StdType::Any,
+    ),
+    // Streaming readers
+    std_function(
+        "fs_open_read",
+        StdFunctionKind::FsOpenRead,
+        StdArity::Exact(1),
+        &[StdType::String],
+        StdType::Int,
+    ),
+    std_function(
+        "fs_read_line",
+        StdFunctionKind::FsReadLine,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::Any,
+    ),
+    std_function(
+        "fs_close",
+        StdFunctionKind::FsClose,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::Void,
+    ),
+    std_function(
+        "process_read_stdout_line",
+        StdFunctionKind::ProcessReadStdoutLine,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::Any,
+    ),
+    // Random
+    std_function(
+        "random_seeded",
+        StdFunctionKind::RandomSeeded,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::Int,
+    ),
+    std_function(
+        "random_int",
+        StdFunctionKind::RandomInt,
+        StdArity::Exact(3),
+        &[StdType::Int, StdType::Int, StdType::Int],
+        StdType::Int,
+    ),
+    std_function(
+        "random_float",
+        StdFunctionKind::RandomFloat,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::Float,
+    ),
+    // Crypto
+    std_function(
+        "crypto_digest",
+        StdFunctionKind::CryptoDigest,
+        StdArity::Exact(2),
+        &[StdType::String, StdType::Bytes],
+        StdType::Bytes,
+    ),
+    std_function(
+        "crypto_hmac_sha256",
+        StdFunctionKind::CryptoHmacSha256,
+        StdArity::Exact(2),
+        &[StdType::Bytes, StdType::Bytes],
+        StdType::Bytes,
+    ),
+    std_function(
+        "crypto_constant_time_eq",
+        StdFunctionKind::CryptoConstantTimeEq,
+        StdArity::Exact(2),
+        &[StdType::Bytes, StdType::Bytes],
+        StdType::Bool,
+    ),
+    // Terminal
+    std_function(
+        "term_is_terminal",
+        StdFunctionKind::TermIsTerminal,
+        StdArity::Exact(1),
+        &[StdType::Int],
+        StdType::Bool,
+    ),
+    std_function(
+        "term_columns",
+        StdFunctionKind::TermColumns,
+        StdArity::Exact(0),
+        &[],
+        StdType::Int,
+    ),
+    std_function(
+        "term_rows",
+        StdFunctionKind::TermRows,
+        StdArity::Exact(0),
+        &[],
+        StdType::Int,
+    ),
+    std_function(
+        "term_set_echo",
+        StdFunctionKind::TermSetEcho,
+        StdArity::Exact(1),
+        &[StdType::Bool],
+        StdType::Bool,
+    ),
 ];
 
 pub const MODULE: StdModule = std_module!(
@@ -16,32 +16,59 @@ pub use builtins::BUILTINS;
 
 pub const SOURCE_STDLIB_MODULES: &[&str] = &[
     "std.args",
+    "std.bytes",
+    "std.crypto",
+    "std.csv",
+    "std.encoding",
     "std.env",
     "std.fs",
     "std.http",
+    "std.iter",
+    "std.json",
+    "std.log",
+    "std.math",
+    "std.net",
     "std.parse",
     "std.path",
     "std.process",
+    "std.random",
     "std.regex",
     "std.string",
+    "std.task",
+    "std.term",
     "std.time",
+    "std.toml",
     "std.url",
+    "std.yaml",
 ];
 
 pub const REFERENCE_STDLIB_MODULES: &[&str] = &[
     "std.args",
     "std.assert",
+    "std.bytes",
+    "std.crypto",
+    "std.csv",
+    "std.encoding",
     "std.env",
     "std.fs",
     "std.http",
+    "std.iter",
     "std.json",
+    "std.log",
+    "std.math",
+    "std.net",
     "std.parse",
     "std.path",
     "std.process",
+    "std.random",
     "std.regex",
     "std.string",
+    "std.task",
+    "std.term",
     "std.time",
+    "std.toml",
     "std.url",
+    "std.yaml",
 ];
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -66,6 +93,30 @@ pub enum StdFunctionKind {
     StringReplace,
     StringToLower,
     StringToUpper,
+    StringChars,
+    StringGraphemes,
+    StringCodePoints,
+    StringByteLen,
+    StringDisplayWidth,
+    StringFoldCase,
+    StringNormalizeNfc,
+    StringNormalizeNfd,
+    StringIsAlpha,
+    StringIsDigit,
+    StringIsWhitespace,
+    BytesFromList,
+    BytesToList,
+    BytesFromString,
+    BytesToString,
+    BytesToStringLossy,
+    BytesIsUtf8,
+    BytesToHex,
+    BytesFromHex,
+    BytesToBase64,
+    BytesFromBase64,
+    BytesDecode,
+    BytesDecodeError,
+    BytesConcat,
     MathFloor,
     MathCeil,
     MathRound,
@@ -73,6 +124,18 @@ pub enum StdFunctionKind {
     MathSqrt,
     MathMin,
     MathMax,
+    MathSin,
+    MathCos,
+    MathTan,
+    MathAtan2,
+    MathExp,
+    MathLn,
+    MathLog10,
+    MathLog2,
+    MathPow,
+    MathHypot,
+    MathIsNan,
+    MathIsFinite,
     EnvGet,
     EnvGetOr,
     EnvHas,
@@ -163,8 +226,72 @@ pub enum StdFunctionKind {
     // JSON module
     JsonEncode,
     JsonDecode,
+    JsonEncodeWith,
+    JsonDecodeAs,
+    JsonDecodeError,
+    JsonDecodeErrorPath,
+    // YAML and TOML modules
+    YamlEncode,
+    YamlDecode,
+    TomlEncode,
+    TomlDecode,
+    // CSV module
+    CsvDecodeRecord,
     // HTTP module
     HttpSend,
+    // Task module
+    TaskSpawn,
+    TaskJoin,
+    ChannelNew,
+    ChannelSend,
+    ChannelRecv,
+    ChannelClose,
+    // Iterator module
+    IterNext,
+    // Streaming readers
+    FsOpenRead,
+    FsReadLine,
+    FsClose,
+    ProcessReadStdoutLine,
+    // Random module
+    RandomSeeded,
+    RandomInt,
+    RandomFloat,
+    // Crypto module
+    CryptoDigest,
+    CryptoHmacSha256,
+    CryptoConstantTimeEq,
+    // Terminal module
+    TermIsTerminal,
+    TermColumns,
+    TermRows,
+    TermSetEcho,
+    // HTTP server
+    HttpServerBind,
+    HttpServerAddress,
+    HttpServerAccept,
+    HttpServerRequest,
+    HttpServerRespond,
+    HttpServerShutdown,
+    // Sockets
+    NetTcpConnect,
+    NetTcpListen,
+    NetUnixConnect,
+    NetUnixListen,
+    NetUdpBind,
+    NetAccept,
+    NetRead,
+    NetReadLine,
+    NetWrite,
+    NetShutdownWrite,
+    NetLocalAddress,
+    NetPeerAddress,
+    NetUdpSend,
+    NetUdpReceive,
+    NetUdpSender,
+    NetClose,
+    NetErrorKind,
+    NetErrorMessage,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -200,6 +327,7 @@ pub enum StdType {
     Int,
     Float,
     String,
+    Bytes,
     List,
     Dict,
     Struct,
@@ -267,6 +395,14 @@ pub fn find_module(path: &str) -> Option<&'static StdModule> {
     MODULES.iter().find(|module| module.path == path)
 }
 
+/// The `std.intrinsics` entry for a function kind.
+pub fn intrinsic_function(kind: StdFunctionKind) -> Option<&'static StdFunction> {
+    intrinsics::MODULE
+        .functions
+        .iter()
+        .find(|function| function.kind == kind)
+}
+
 pub fn is_source_stdlib_module(path: &str) -> bool {
     SOURCE_STDLIB_MODULES.contains(&path)
 }
@@ -289,7 +425,7 @@ pub fn module_function_kind(module_path: &str, name: &str) -> Option<StdFunction
 pub fn is_browser_safe_stdlib_module(path: &str) -> bool {
     matches!(
         path,
-        "std.assert" | "std.intrinsics" | "std.json" | "std.string"
+        "std.assert" | "std.intrinsics" | "std.json" | "std.math" | "std.random" | "std.string"
     )
 }
 
@@ -310,6 +446,17 @@ pub fn is_browser_safe_function(kind: StdFunctionKind) -> bool {
             | StdFunctionKind::StringReplace
             | StdFunctionKind::StringToLower
             | StdFunctionKind::StringToUpper
+            | StdFunctionKind::StringChars
+            | StdFunctionKind::StringGraphemes
+            | StdFunctionKind::StringCodePoints
+            | StdFunctionKind::StringByteLen
+            | StdFunctionKind::StringDisplayWidth
+            | StdFunctionKind::StringFoldCase
+            | StdFunctionKind::StringNormalizeNfc
+            | StdFunctionKind::StringNormalizeNfd
+            | StdFunctionKind::StringIsAlpha
+            | StdFunctionKind::StringIsDigit
+            | StdFunctionKind::StringIsWhitespace
             | StdFunctionKind::MathFloor
             | StdFunctionKind::MathCeil
             | StdFunctionKind::MathRound
@@ -317,6 +464,21 @@ pub fn is_browser_safe_function(kind: StdFunctionKind) -> bool {
             | StdFunctionKind::MathSqrt
             | StdFunctionKind::MathMin
             | StdFunctionKind::MathMax
+            | StdFunctionKind::MathSin
+            | StdFunctionKind::MathCos
+            | StdFunctionKind::MathTan
+            | StdFunctionKind::MathAtan2
+            | StdFunctionKind::MathExp
+            | StdFunctionKind::MathLn
+            | StdFunctionKind::MathLog10
+            | StdFunctionKind::MathLog2
+            | StdFunctionKind::MathPow
+            | StdFunctionKind::MathHypot
+            | StdFunctionKind::MathIsNan
+            | StdFunctionKind::MathIsFinite
+            | StdFunctionKind::RandomSeeded
+            | StdFunctionKind::RandomInt
+            | StdFunctionKind::RandomFloat
             | StdFunctionKind::Assert
             | StdFunctionKind::AssertEq
             | StdFunctionKind::AssertNe
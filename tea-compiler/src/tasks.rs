@@ -0,0 +1,512 @@
+//! Finds the function parameters whose values run on another thread.
+//!
+//! `std.task` hands closures to the runtime through `intrinsics.task_spawn`. A
+//! parameter that reaches that call, either directly or through a lambda that
+//! captures it, makes its function a spawn boundary: lambdas passed in that
+//! position at call sites must only capture values that are safe to share.
+//!
+//! Closures also reach module variables without capturing them, so the module
+//! variables each function and lambda reads, directly or through the functions
+//! it calls, are recorded as well.
+//!
+//! Values sent through `intrinsics.channel_send` reach another thread too. The
+//! parameters that flow into it are tracked the same way, so call sites can
+//! check that the values they send are safe to share.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::ast::{
+    Block, CallExpression, CatchHandler, CatchKind, Expression, ExpressionKind,
+    FormattedInterpolation, FunctionStatement, InterpolatedStringPart, LambdaBody, LoopHeader,
+    MatchPattern, Module, Statement,
+};
+
+const INTRINSICS_MODULE: &str = "std.intrinsics";
+const TASK_SPAWN_INTRINSIC: &str = "task_spawn";
+const CHANNEL_SEND_INTRINSIC: &str = "channel_send";
+
+#[derive(Debug, Default)]
+pub(crate) struct SpawnBoundaries {
+    intrinsic_aliases: HashSet<String>,
+    parameters: HashMap<String, Vec<usize>>,
+    /// Parameters of each function whose values are sent through a channel.
+    sent_parameters: HashMap<String, Vec<usize>>,
+    /// Module variables, mapped to whether they are `const`.
+    globals: HashMap<String, bool>,
+    /// Module variables each top-level function reads, including through the functions it calls.
+    function_globals: HashMap<String, BTreeSet<String>>,
+    /// Module variables each lambda reads, including through the functions it calls.
+    lambda_globals: HashMap<usize, BTreeSet<String>>,
+}
+
+impl SpawnBoundaries {
+    pub(crate) fn collect(module: &Module, lambda_captures: &HashMap<usize, Vec<String>>) -> Self {
+        let mut boundaries = SpawnBoundaries {
+            intrinsic_aliases: module
+                .statements
+                .iter()
+                .filter_map(|statement| match statement {
+                    Statement::Use(use_stmt) if use_stmt.module_path == INTRINSICS_MODULE => {
+                        Some(use_stmt.alias.name.clone())
+                    }
+                    _ => None,
+                })
+                .collect(),
+            ..SpawnBoundaries::default()
+        };
+        if boundaries.intrinsic_aliases.is_empty() {
+            return boundaries;
+        }
+
+        let functions: Vec<&FunctionStatement> = module
+            .statements
+            .iter()
+            .filter_map(|statement| match statement {
+                Statement::Function(function) => Some(function),
+                _ => None,
+            })
+            .collect();
+
+        // A boundary found in one function can make its callers boundaries too,
+        // so repeat until no new parameters are marked.
+        loop {
+            let mut changed = false;
+            for function in &functions {
+                let parameters: Vec<&str> = function
+                    .parameters
+                    .iter()
+                    .map(|parameter| parameter.name.as_str())
+                    .collect();
+                let mut spawned = HashSet::new();
+                let mut sent = HashSet::new();
+                walk_block(&function.body, &mut |expression| {
+                    if let ExpressionKind::Call(call) = &expression.kind {
+                        boundaries.mark_spawned(call, lambda_captures, &parameters, &mut spawned);
+                        boundaries.mark_sent(call, &parameters, &mut sent);
+                    }
+                });
+                changed |= add_indices(&mut boundaries.parameters, &function.name, spawned);
+                changed |= add_indices(&mut boundaries.sent_parameters, &function.name, sent);
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        boundaries.collect_globals(module, &functions);
+        boundaries
+    }
+
+    fn mark_spawned(
+        &self,
+        call: &CallExpression,
+        lambda_captures: &HashMap<usize, Vec<String>>,
+        parameters: &[&str],
+        spawned: &mut HashSet<usize>,
+    ) {
+        let mut mark = |name: &str| {
+            if let Some(index) = parameters.iter().position(|parameter| *parameter == name) {
+                spawned.insert(index);
+            }
+        };
+        for &index in self.spawned_arguments(call) {
+            let Some(argument) = call.arguments.get(index) else {
+                continue;
+            };
+            match &argument.expression.kind {
+                ExpressionKind::Identifier(identifier) => mark(&identifier.name),
+                ExpressionKind::Lambda(lambda) => {
+                    for name in lambda_captures.get(&lambda.id).into_iter().flatten() {
+                        mark(name);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn mark_sent(&self, call: &CallExpression, parameters: &[&str], sent: &mut HashSet<usize>) {
+        for &index in self.sent_arguments(call) {
+            if let Some(ExpressionKind::Identifier(identifier)) = call
+                .arguments
+                .get(index)
+                .map(|argument| &argument.expression.kind)
+            {
+                if let Some(position) = parameters
+                    .iter()
+                    .position(|parameter| *parameter == identifier.name)
+                {
+                    sent.insert(position);
+                }
+            }
+        }
+    }
+
+    fn collect_globals(&mut self, module: &Module, functions: &[&FunctionStatement]) {
+        for statement in &module.statements {
+            if let Statement::Var(var_stmt) = statement {
+                for binding in &var_stmt.bindings {
+                    self.globals.insert(binding.name.clone(), var_stmt.is_const);
+                }
+            }
+        }
+
+        // Record what each function reads directly, then fold in its callees
+        // until nothing changes.
+        let mut callees: HashMap<&str, BTreeSet<String>> = HashMap::new();
+        for function in functions {
+            let mut globals = BTreeSet::new();
+            let mut called = BTreeSet::new();
+            let mut visit = |expression: &Expression| {
+                if let ExpressionKind::Identifier(identifier) = &expression.kind {
+                    if self.globals.contains_key(&identifier.name) {
+                        globals.insert(identifier.name.clone());
+                    } else if identifier.name != function.name {
+                        called.insert(identifier.name.clone());
+                    }
+                }
+            };
+            for parameter in &function.parameters {
+                if let Some(default_value) = &parameter.default_value {
+                    walk_expression(default_value, &mut visit);
+                }
+            }
+            walk_block(&function.body, &mut visit);
+            self.function_globals.insert(function.name.clone(), globals);
+            callees.insert(function.name.as_str(), called);
+        }
+        loop {
+            let mut changed = false;
+            for (name, called) in &callees {
+                let reached: Vec<String> = called
+                    .iter()
+                    .filter_map(|callee| self.function_globals.get(callee))
+                    .flatten()
+                    .cloned()
+                    .collect();
+                let Some(globals) = self.function_globals.get_mut(*name) else {
+                    continue;
+                };
+                for global in reached {
+                    changed |= globals.insert(global);
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let mut lambda_globals = HashMap::new();
+        let mut visit_lambdas = |expression: &Expression| {
+            if let ExpressionKind::Lambda(lambda) = &expression.kind {
+                let mut globals = BTreeSet::new();
+                let mut visit = |expression: &Expression| {
+                    if let ExpressionKind::Identifier(identifier) = &expression.kind {
+                        globals.extend(self.globals_read_by(&identifier.name));
+                    }
+                };
+                match &lambda.body {
+                    LambdaBody::Expression(expression) => walk_expression(expression, &mut visit),
+                    LambdaBody::Block(block) => walk_block(block, &mut visit),
+                }
+                lambda_globals.insert(lambda.id, globals);
+            }
+        };
+        for statement in &module.statements {
+            match statement {
+                Statement::Function(function) => {
+                    for parameter in &function.parameters {
+                        if let Some(default_value) = &parameter.default_value {
+                            walk_expression(default_value, &mut visit_lambdas);
+                        }
+                    }
+                    walk_block(&function.body, &mut visit_lambdas);
+                }
+                Statement::Test(test) => walk_block(&test.body, &mut visit_lambdas),
+                _ => walk_statement(statement, &mut visit_lambdas),
+            }
+        }
+        self.lambda_globals = lambda_globals;
+    }
+
+    /// Module variables read when `name` is evaluated: the variable itself, or
+    /// every module variable a top-level function of that name reads.
+    fn globals_read_by(&self, name: &str) -> BTreeSet<String> {
+        if self.globals.contains_key(name) {
+            return BTreeSet::from([name.to_string()]);
+        }
+        self.function_globals.get(name).cloned().unwrap_or_default()
+    }
+
+    /// Positions of the arguments to `call` that run on another thread.
+    pub(crate) fn spawned_arguments(&self, call: &CallExpression) -> &[usize] {
+        match &call.callee.kind {
+            ExpressionKind::Identifier(identifier) => self
+                .parameters
+                .get(&identifier.name)
+                .map(Vec::as_slice)
+                .unwrap_or(&[]),
+            ExpressionKind::Member(member) if member.property == TASK_SPAWN_INTRINSIC => {
+                match &member.object.kind {
+                    ExpressionKind::Identifier(alias)
+                        if self.intrinsic_aliases.contains(&alias.name) =>
+                    {
+                        &[0]
+                    }
+                    _ => &[],
+                }
+            }
+            _ => &[],
+        }
+    }
+
+    /// Positions of the arguments to `call` that are sent through a channel.
+    pub(crate) fn sent_arguments(&self, call: &CallExpression) -> &[usize] {
+        match &call.callee.kind {
+            ExpressionKind::Identifier(identifier) => self
+                .sent_parameters
+                .get(&identifier.name)
+                .map(Vec::as_slice)
+                .unwrap_or(&[]),
+            ExpressionKind::Member(member) if member.property == CHANNEL_SEND_INTRINSIC => {
+                match &member.object.kind {
+                    ExpressionKind::Identifier(alias)
+                        if self.intrinsic_aliases.contains(&alias.name) =>
+                    {
+                        &[1]
+                    }
+                    _ => &[],
+                }
+            }
+            _ => &[],
+        }
+    }
+
+    /// Names of the parameters of `function` that run on another thread.
+    pub(crate) fn spawned_parameters(&self, function: &FunctionStatement) -> HashSet<String> {
+        self.parameters
+            .get(&function.name)
+            .into_iter()
+            .flatten()
+            .filter_map(|index| function.parameters.get(*index))
+            .map(|parameter| parameter.name.clone())
+            .collect()
+    }
+
+    /// Whether `name` is a module variable.
+    pub(crate) fn is_global(&self, name: &str) -> bool {
+        self.globals.contains_key(name)
+    }
+
+    /// Whether `name` is a module variable declared with `var`.
+    pub(crate) fn is_mutable_global(&self, name: &str) -> bool {
+        self.globals.get(name) == Some(&false)
+    }
+
+    /// Whether `name` is a top-level function.
+    pub(crate) fn is_function(&self, name: &str) -> bool {
+        self.function_globals.contains_key(name)
+    }
+
+    /// Module variables read by the top-level function `name`.
+    pub(crate) fn function_globals(&self, name: &str) -> BTreeSet<String> {
+        self.function_globals.get(name).cloned().unwrap_or_default()
+    }
+
+    /// Module variables read by the lambda with id `lambda_id`.
+    pub(crate) fn lambda_globals(&self, lambda_id: usize) -> BTreeSet<String> {
+        self.lambda_globals
+            .get(&lambda_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Record `indices` for `function`, returning whether any were new.
+fn add_indices(
+    table: &mut HashMap<String, Vec<usize>>,
+    function: &str,
+    indices: HashSet<usize>,
+) -> bool {
+    if indices.is_empty() {
+        return false;
+    }
+    let entry = table.entry(function.to_string()).or_default();
+    let mut changed = false;
+    for index in indices {
+        if !entry.contains(&index) {
+            entry.push(index);
+            changed = true;
+        }
+    }
+    entry.sort_unstable();
+    changed
+}
+
+fn walk_block(block: &Block, visit: &mut dyn FnMut(&Expression)) {
+    for statement in &block.statements {
+        walk_statement(statement, visit);
+    }
+}
+
+fn walk_statement(statement: &Statement, visit: &mut dyn FnMut(&Expression)) {
+    match statement {
+        Statement::Var(var_stmt) => {
+            for binding in &var_stmt.bindings {
+                if let Some(initializer) = &binding.initializer {
+                    walk_expression(initializer, visit);
+                }
+            }
+        }
+        Statement::Conditional(conditional) => {
+            walk_expression(&conditional.condition, visit);
+            walk_block(&conditional.consequent, visit);
+            if let Some(alternative) = &conditional.alternative {
+                walk_block(alternative, visit);
+            }
+        }
+        Statement::Loop(loop_stmt) => {
+            match &loop_stmt.header {
+                LoopHeader::For { iterator, .. } => walk_expression(iterator, visit),
+                LoopHeader::Condition(condition)
+                | LoopHeader::Binding {
+                    value: condition, ..
+                } => walk_expression(condition, visit),
+            }
+            walk_block(&loop_stmt.body, visit);
+        }
+        Statement::Guard(guard) => {
+            walk_expression(&guard.condition, visit);
+            walk_block(&guard.alternative, visit);
+        }
+        Statement::Throw(throw_stmt) => walk_expression(&throw_stmt.expression, visit),
+        Statement::Yield(yield_stmt) => walk_expression(&yield_stmt.expression, visit),
+        Statement::Return(return_stmt) => {
+            if let Some(expression) = &return_stmt.expression {
+                walk_expression(expression, visit);
+            }
+        }
+        Statement::Match(match_stmt) => {
+            walk_expression(&match_stmt.scrutinee, visit);
+            for arm in &match_stmt.arms {
+                walk_patterns(&arm.patterns, visit);
+                walk_block(&arm.block, visit);
+            }
+        }
+        Statement::Expression(expression) => walk_expression(&expression.expression, visit),
+        Statement::Use(_)
+        | Statement::Function(_)
+        | Statement::Test(_)
+        | Statement::Struct(_)
+        | Statement::Union(_)
+        | Statement::Enum(_)
+        | Statement::Error(_)
+        | Statement::Break(_)
+        | Statement::Continue(_) => {}
+    }
+}
+
+fn walk_patterns(patterns: &[MatchPattern], visit: &mut dyn FnMut(&Expression)) {
+    for pattern in patterns {
+        if let MatchPattern::Expression(expression) = pattern {
+            walk_expression(expression, visit);
+        }
+    }
+}
+
+/// Call `visit` on `expression` and every expression nested inside it.
+fn walk_expression(expression: &Expression, visit: &mut dyn FnMut(&Expression)) {
+    visit(expression);
+    match &expression.kind {
+        ExpressionKind::Identifier(_) | ExpressionKind::Literal(_) => {}
+        ExpressionKind::InterpolatedString(interpolated) => {
+            for part in &interpolated.parts {
+                if let InterpolatedStringPart::Expression(expression)
+                | InterpolatedStringPart::Formatted(FormattedInterpolation {
+                    expression,
+                    ..
+                }) = part
+                {
+                    walk_expression(expression, visit);
+                }
+            }
+        }
+        ExpressionKind::List(list) => {
+            for element in &list.elements {
+                walk_expression(element, visit);
+            }
+        }
+        ExpressionKind::Dict(dict) => {
+            for entry in &dict.entries {
+                walk_expression(&entry.value, visit);
+            }
+        }
+        ExpressionKind::Unary(unary) => walk_expression(&unary.operand, visit),
+        ExpressionKind::Binary(binary) => {
+            walk_expression(&binary.left, visit);
+            walk_expression(&binary.right, visit);
+        }
+        ExpressionKind::Is(is_expr) => walk_expression(&is_expr.value, visit),
+        ExpressionKind::Call(call) => {
+            walk_expression(&call.callee, visit);
+            for argument in &call.arguments {
+                walk_expression(&argument.expression, visit);
+            }
+        }
+        ExpressionKind::Member(member) => walk_expression(&member.object, visit),
+        ExpressionKind::Index(index) => {
+            walk_expression(&index.object, visit);
+            walk_expression(&index.index, visit);
+        }
+        ExpressionKind::Range(range) => {
+            for bound in [&range.start, &range.end, &range.step]
+                .into_iter()
+                .flatten()
+            {
+                walk_expression(bound, visit);
+            }
+        }
+        ExpressionKind::Lambda(lambda) => match &lambda.body {
+            LambdaBody::Expression(expression) => walk_expression(expression, visit),
+            LambdaBody::Block(block) => walk_block(block, visit),
+        },
+        ExpressionKind::Assignment(assignment) => {
+            walk_expression(&assignment.target, visit);
+            walk_expression(&assignment.value, visit);
+        }
+        ExpressionKind::Match(match_expr) => {
+            walk_expression(&match_expr.scrutinee, visit);
+            for arm in &match_expr.arms {
+                walk_patterns(&arm.patterns, visit);
+                walk_expression(&arm.expression, visit);
+            }
+        }
+        ExpressionKind::Conditional(conditional) => {
+            walk_expression(&conditional.condition, visit);
+            walk_expression(&conditional.consequent, visit);
+            walk_expression(&conditional.alternative, visit);
+        }
+        ExpressionKind::Unwrap(inner) | ExpressionKind::Grouping(inner) => {
+            walk_expression(inner, visit);
+        }
+        ExpressionKind::Try(try_expr) => {
+            walk_expression(&try_expr.expression, visit);
+            if let Some(catch_clause) = &try_expr.catch {
+                match &catch_clause.kind {
+                    CatchKind::Fallback(expression) => walk_expression(expression, visit),
+                    CatchKind::Arms(arms) => {
+                        for arm in arms {
+                            walk_patterns(&arm.patterns, visit);
+                            match &arm.handler {
+                                CatchHandler::Expression(expression) => {
+                                    walk_expression(expression, visit);
+                                }
+                                CatchHandler::Block(block) => walk_block(block, visit),
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,19 +1,62 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use crate::ast::{
     BinaryExpression, BinaryOperator, Block, CallArgument, CallExpression, CatchHandler, CatchKind,
     ConditionalExpression, ConditionalKind, ConditionalStatement, DictLiteral, ErrorAnnotation,
-    ErrorTypeSpecifier, Expression, ExpressionKind, ForPattern, FunctionStatement, Identifier,
-    IndexExpression, InterpolatedStringPart, LambdaBody, LambdaExpression, ListLiteral, Literal,
-    LoopHeader, LoopKind, LoopStatement, MatchExpression, MatchPattern, MatchStatement, Module,
+    ErrorTypeSpecifier, Expression, ExpressionKind, ForPattern, FormattedInterpolation,
+    FunctionStatement, GuardStatement, Identifier, IndexExpression, InterpolatedStringPart,
+    LambdaBody, LambdaExpression, ListLiteral, Literal, LoopHeader, LoopKind, LoopStatement,
+    MatchExpression, MatchPattern, MatchStatement, Module, OptionalBinding, RangeExpression,
     ReturnStatement, SourceSpan, Statement, StructStatement, TestStatement, TryExpression,
-    TypeExpression, TypeParameter, UnaryExpression, UnaryOperator, VarStatement,
+    TypeExpression, TypeParameter, UnaryExpression, UnaryOperator, VarStatement, YieldStatement,
 };
+use crate::consteval::{self, ConstErrorKind, ConstValue};
 use crate::diagnostics::Diagnostics;
+use crate::generators::is_generator;
 use crate::lexer::{Keyword, Token, TokenKind};
 use crate::stdlib::{self, StdArity, StdFunction, StdFunctionKind, StdType};
+use crate::tasks::SpawnBoundaries;
 use serde_json::Value as JsonValue;
 use serde_yaml::Value as YamlValue;
+use tea_support::{FormatClass, FormatSpec};
+
+/// Error thrown across the C boundary by `extern def` wrappers.
+pub(crate) const EXTERN_ERROR: &str = "ExternError";
+
+/// Methods callable on a List value, in the order they are documented.
+pub(crate) const LIST_METHODS: &[&str] = &[
+    "map",
+    "filter",
+    "reduce",
+    "find",
+    "any",
+    "all",
+    "contains",
+    "index_of",
+    "find_index",
+    "take",
+    "skip",
+    "sort",
+    "sorted",
+    "sort_by",
+    "sorted_by",
+    "reverse",
+    "reversed",
+    "unique",
+    "group_by",
+    "partition",
+    "zip",
+    "flat_map",
+    "chunk",
+    "window",
+    "join",
+    "sum",
+    "min",
+    "max",
+    "insert",
+    "remove_at",
+    "pop",
+];
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub(crate) enum Type {
@@ -21,12 +64,14 @@ pub(crate) enum Type {
     Int,
     Float,
     String,
+    Bytes,
     Nil,
     Void,
     Error(ErrorType),
     Optional(Box<Type>),
     List(Box<Type>),
     Dict(Box<Type>),
+    Iterator(Box<Type>),
     Function(Vec<Type>, Box<Type>),
     Struct(StructType),
     Enum(EnumType),
@@ -42,11 +87,13 @@ impl Type {
             Type::Int => "Int".to_string(),
             Type::Float => "Float".to_string(),
             Type::String => "String".to_string(),
+            Type::Bytes => "Bytes".to_string(),
             Type::Nil => "Nil".to_string(),
             Type::Void => "Void".to_string(),
             Type::Optional(inner) => format!("{}?", inner.describe()),
             Type::List(element) => format!("List[{}]", element.describe()),
             Type::Dict(value) => format!("Dict[String, {}]", value.describe()),
+            Type::Iterator(element) => format!("Iterator[{}]", element.describe()),
             Type::Function(params, return_type) => {
                 let param_str = if params.is_empty() {
                     String::from("()")
@@ -129,6 +176,10 @@ pub(crate) struct FunctionSignature {
     pub return_type: Type,
     pub arity: StdArity,
     pub type_parameters: Vec<String>,
+    /// Parameter names accepted as named arguments; empty for native functions.
+    pub param_names: Vec<String>,
+    /// Whether the last parameter collects extra positional arguments as a list.
+    pub variadic: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -229,7 +280,6 @@ impl ErrorSet {
 #[derive(Debug, Clone)]
 struct ModuleFunctionInfo {
     signature: FunctionSignature,
-    #[allow(dead_code)]
     kind: StdFunctionKind,
 }
 
@@ -250,6 +300,8 @@ pub(crate) struct StructFieldType {
     pub ty: Type,
     #[allow(dead_code)]
     pub span: SourceSpan,
+    /// Whether constructor calls may omit this field.
+    pub has_default: bool,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -285,11 +337,17 @@ struct FunctionContext {
     saw_explicit_return: bool,
     last_expression_type: Option<Type>,
     explicit_return_types: Vec<Type>,
+    /// Parameters that run on another thread, so lambdas may capture them.
+    spawned_parameters: HashSet<String>,
+    /// Element type produced by `yield` when checking a generator function.
+    yield_type: Option<Type>,
 }
 
 pub struct TypeChecker {
     scopes: Vec<HashMap<String, Type>>,
     const_scopes: Vec<HashSet<String>>,
+    /// Lambda ids bound to `const` names, so closures passed to `task.spawn` by name can be checked.
+    const_lambda_scopes: Vec<HashMap<String, usize>>,
     non_nil_scopes: Vec<HashSet<String>>,
     functions: HashMap<String, FunctionSignature>,
     structs: HashMap<String, StructDefinition>,
@@ -314,6 +372,15 @@ pub struct TypeChecker {
     match_exhaustiveness: HashMap<SourceSpan, Vec<String>>,
     type_test_metadata: HashMap<SourceSpan, Type>,
     suppress_list_element_errors: bool,
+    lambda_captures: HashMap<usize, Vec<String>>,
+    spawn_boundaries: SpawnBoundaries,
+    checked_spawned_arguments: HashSet<SourceSpan>,
+    /// Module constants read by spawned code, checked once every global's type is known.
+    spawned_constant_uses: Vec<(String, String, SourceSpan)>,
+    /// Values of module-level constants whose initializers fold at compile time.
+    constants: HashMap<String, ConstValue>,
+    /// C symbols bound by `extern def`, with the first binding's name and C signature.
+    extern_symbols: HashMap<String, (String, String)>,
 }
 
 impl TypeChecker {
@@ -321,6 +388,7 @@ impl TypeChecker {
         let mut checker = Self {
             scopes: vec![HashMap::new()],
             const_scopes: vec![HashSet::new()],
+            const_lambda_scopes: vec![HashMap::new()],
             non_nil_scopes: vec![HashSet::new()],
             functions: HashMap::new(),
             structs: HashMap::new(),
@@ -345,13 +413,26 @@ impl TypeChecker {
             match_exhaustiveness: HashMap::new(),
             type_test_metadata: HashMap::new(),
             suppress_list_element_errors: false,
+            lambda_captures: HashMap::new(),
+            spawn_boundaries: SpawnBoundaries::default(),
+            checked_spawned_arguments: HashSet::new(),
+            spawned_constant_uses: Vec::new(),
+            constants: HashMap::new(),
+            extern_symbols: HashMap::new(),
         };
         checker.register_builtin_structs();
+        checker.register_builtin_errors();
         checker.register_builtin_functions();
         checker
     }
 
+    /// Provide the resolver's lambda captures so closures handed to other threads can be checked.
+    pub(crate) fn set_lambda_captures(&mut self, lambda_captures: HashMap<usize, Vec<String>>) {
+        self.lambda_captures = lambda_captures;
+    }
+
     pub fn check_module(&mut self, module: &Module) {
+        self.spawn_boundaries = SpawnBoundaries::collect(module, &self.lambda_captures);
         self.collect_unions(&module.statements);
         self.collect_enums(&module.statements);
         self.collect_structs(&module.statements);
@@ -359,6 +440,7 @@ impl TypeChecker {
         self.populate_unions();
         self.populate_errors();
         self.check_statements(&module.statements);
+        self.check_spawned_constant_uses();
         self.validate_public_declarations(&module.statements);
     }
 
@@ -420,6 +502,10 @@ impl TypeChecker {
         &self.type_test_metadata
     }
 
+    pub(crate) fn constants(&self) -> &HashMap<String, ConstValue> {
+        &self.constants
+    }
+
     fn report_error<S: Into<String>>(&mut self, message: S, span: Option<SourceSpan>) {
         let message = message.into();
         if let Some(span) = span {
@@ -632,17 +718,18 @@ impl TypeChecker {
         exported_errors: &HashMap<String, HashSet<String>>,
     ) -> Option<&'a str> {
         match ty {
-            Type::Optional(inner) | Type::List(inner) | Type::Dict(inner) => {
-                Self::first_unexported_type_name(
-                    inner,
-                    owner,
-                    globally_visible_structs,
-                    exported_structs,
-                    exported_unions,
-                    exported_enums,
-                    exported_errors,
-                )
-            }
+            Type::Optional(inner)
+            | Type::List(inner)
+            | Type::Dict(inner)
+            | Type::Iterator(inner) => Self::first_unexported_type_name(
+                inner,
+                owner,
+                globally_visible_structs,
+                exported_structs,
+                exported_unions,
+                exported_enums,
+                exported_errors,
+            ),
             Type::Function(params, return_type) => params
                 .iter()
                 .find_map(|param| {
@@ -731,6 +818,7 @@ impl TypeChecker {
             | Type::Int
             | Type::Float
             | Type::String
+            | Type::Bytes
             | Type::Nil
             | Type::Void
             | Type::GenericParameter(_)
@@ -804,7 +892,10 @@ impl TypeChecker {
                         );
                     }
                 }
-                Statement::Function(function) if function.is_public => {
+                // Extern signatures only hold C scalars, which cannot leak private types.
+                Statement::Function(function)
+                    if function.is_public && function.extern_function.is_none() =>
+                {
                     let (owner, _) = Self::split_public_api_name(&function.name, &aliases);
                     self.push_type_parameters(&function.type_parameters);
 
@@ -961,16 +1052,19 @@ impl TypeChecker {
                     name: "exit".to_string(),
                     ty: Type::Int,
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
                 StructFieldType {
                     name: "stdout".to_string(),
                     ty: Type::String,
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
                 StructFieldType {
                     name: "stderr".to_string(),
                     ty: Type::String,
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
             ],
         };
@@ -983,51 +1077,61 @@ impl TypeChecker {
                     name: "ok".to_string(),
                     ty: Type::Bool,
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
                 StructFieldType {
                     name: "exit".to_string(),
                     ty: Type::Int,
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
                 StructFieldType {
                     name: "command".to_string(),
                     ty: Type::String,
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
                 StructFieldType {
                     name: "path".to_string(),
                     ty: Type::List(Box::new(Type::String)),
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
                 StructFieldType {
                     name: "options".to_string(),
                     ty: Type::Dict(Box::new(Type::Unknown)),
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
                 StructFieldType {
                     name: "positionals".to_string(),
                     ty: Type::Dict(Box::new(Type::Unknown)),
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
                 StructFieldType {
                     name: "scopes".to_string(),
                     ty: Type::List(Box::new(Type::Dict(Box::new(Type::Unknown)))),
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
                 StructFieldType {
                     name: "rest".to_string(),
                     ty: Type::List(Box::new(Type::String)),
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
                 StructFieldType {
                     name: "message".to_string(),
                     ty: Type::String,
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
                 StructFieldType {
                     name: "help".to_string(),
                     ty: Type::String,
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
             ],
         };
@@ -1041,26 +1145,31 @@ impl TypeChecker {
                     name: "exit".to_string(),
                     ty: Type::Int,
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
                 StructFieldType {
                     name: "success".to_string(),
                     ty: Type::Bool,
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
                 StructFieldType {
                     name: "stdout".to_string(),
                     ty: Type::String,
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
                 StructFieldType {
                     name: "stderr".to_string(),
                     ty: Type::String,
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
                 StructFieldType {
                     name: "command".to_string(),
                     ty: Type::String,
                     span: SourceSpan::default(),
+                    has_default: false,
                 },
             ],
         };
@@ -1068,6 +1177,24 @@ impl TypeChecker {
             .insert("ProcessResult".to_string(), process_result);
     }
 
+    /// `ExternError.NulByte` is thrown by an `extern def` wrapper when a
+    /// `CString` argument contains a NUL byte, which C would read as the end of
+    /// the string.
+    fn register_builtin_errors(&mut self) {
+        let mut variants = HashMap::new();
+        variants.insert(
+            "NulByte".to_string(),
+            ErrorVariantDefinition {
+                fields: vec![ErrorFieldDefinition {
+                    name: "message".to_string(),
+                    ty: Type::String,
+                }],
+            },
+        );
+        self.errors
+            .insert(EXTERN_ERROR.to_string(), ErrorDefinition { variants });
+    }
+
     fn register_builtin_functions(&mut self) {
         for function in stdlib::BUILTINS {
             let signature = self.std_function_signature(function);
@@ -1292,6 +1419,7 @@ impl TypeChecker {
                 name: field.name.clone(),
                 ty: field_type,
                 span: field.span,
+                has_default: field.default_value.is_some(),
             });
         }
 
@@ -1636,6 +1764,7 @@ impl TypeChecker {
             | Type::Int
             | Type::Float
             | Type::String
+            | Type::Bytes
             | Type::Nil
             | Type::Struct(_)
             | Type::Enum(_)
@@ -1660,16 +1789,18 @@ impl TypeChecker {
             }
             Statement::Conditional(cond) => self.check_conditional(cond),
             Statement::Loop(loop_stmt) => self.check_loop(loop_stmt),
+            Statement::Guard(guard_stmt) => self.check_guard(guard_stmt),
             Statement::Break(_) | Statement::Continue(_) => {
                 // Break and continue are checked in the context of loops
                 // No additional type checking needed here
             }
             Statement::Return(ret) => self.check_return(ret),
+            Statement::Yield(yield_stmt) => self.check_yield(yield_stmt),
             Statement::Function(func) => self.check_function(func),
             Statement::Test(test_stmt) => self.check_test(test_stmt),
             Statement::Use(use_stmt) => self.register_use(use_stmt),
             Statement::Match(match_stmt) => self.check_match_statement(match_stmt),
-            Statement::Struct(_) => {}
+            Statement::Struct(struct_stmt) => self.check_struct_defaults(struct_stmt),
             Statement::Union(_) => {}
             Statement::Enum(_) => {}
             Statement::Error(_) => {}
@@ -1767,12 +1898,110 @@ impl TypeChecker {
             };
 
             self.insert(name.clone(), target_type.clone(), !statement.is_const);
+            if let (true, Some(initializer)) = (statement.is_const, &binding.initializer) {
+                if let ExpressionKind::Lambda(lambda) = &initializer.kind {
+                    if let Some(scope) = self.const_lambda_scopes.last_mut() {
+                        scope.insert(name.clone(), lambda.id);
+                    }
+                }
+            }
             self.binding_types.insert(binding.span, target_type.clone());
             self.update_non_nil_fact(name, &inferred);
+
+            if statement.is_const && self.scopes.len() == 1 {
+                if let Some(initializer) = &binding.initializer {
+                    self.fold_constant(statement, name, initializer, &target_type);
+                }
+            }
+        }
+    }
+
+    /// Evaluate a module-level constant's initializer so code generation can
+    /// substitute its value. `pub const` initializers must fold; other
+    /// constants must fold unless they call a function or build a value.
+    fn fold_constant(
+        &mut self,
+        statement: &VarStatement,
+        name: &str,
+        initializer: &Expression,
+        ty: &Type,
+    ) {
+        let constants = &self.constants;
+        match consteval::evaluate(initializer, &|name| constants.get(name).cloned()) {
+            Ok(value) => {
+                let value = match (ty, value) {
+                    (Type::Float, ConstValue::Int(number)) => ConstValue::Float(number as f64),
+                    (Type::Int, value @ ConstValue::Int(_))
+                    | (Type::Float, value @ ConstValue::Float(_))
+                    | (Type::Bool, value @ ConstValue::Bool(_))
+                    | (Type::String, value @ ConstValue::String(_)) => value,
+                    _ => return,
+                };
+                self.constants.insert(name.to_string(), value);
+            }
+            Err(error) if statement.is_public => {
+                self.report_error(
+                    format!(
+                        "public const '{}' must be a compile-time constant: {}",
+                        name, error.message
+                    ),
+                    Some(error.span),
+                );
+            }
+            Err(error)
+                if error.kind == ConstErrorKind::Invalid || !consteval::is_runtime(initializer) =>
+            {
+                self.report_error(
+                    format!(
+                        "const '{}' must be a compile-time constant: {}",
+                        name, error.message
+                    ),
+                    Some(error.span),
+                );
+            }
+            Err(_) => {}
+        }
+    }
+
+    /// Match patterns naming a variable compare against its runtime value;
+    /// those built only from constants are folded, so operators that overflow
+    /// or divide by zero are reported here.
+    fn check_constant_pattern(&mut self, pattern: &Expression) {
+        if matches!(
+            pattern.kind,
+            ExpressionKind::Literal(_) | ExpressionKind::Member(_)
+        ) {
+            return;
+        }
+        let local_names: HashSet<String> = self.scopes[1..]
+            .iter()
+            .flat_map(|scope| scope.keys().cloned())
+            .collect();
+        let constants = &self.constants;
+        let lookup = |name: &str| {
+            if local_names.contains(name) {
+                None
+            } else {
+                constants.get(name).cloned()
+            }
+        };
+        match consteval::evaluate(pattern, &lookup) {
+            Err(error) if error.kind == ConstErrorKind::Invalid => {
+                self.report_error(
+                    format!("invalid match pattern: {}", error.message),
+                    Some(error.span),
+                );
+            }
+            _ => {}
         }
     }
 
     fn check_conditional(&mut self, statement: &ConditionalStatement) {
+        if let Some(binding) = &statement.binding {
+            self.check_binding_conditional(statement, binding);
+            return;
+        }
+
         let condition = self.infer_expression(&statement.condition);
         let bool_type = Type::Bool;
         self.ensure_compatible(
@@ -1868,9 +2097,119 @@ impl TypeChecker {
         }
     }
 
+    fn check_binding_conditional(
+        &mut self,
+        statement: &ConditionalStatement,
+        binding: &OptionalBinding,
+    ) {
+        let bound_type = self.infer_optional_binding(binding, &statement.condition);
+        self.run_branch(|checker| {
+            checker.check_bound_statements(binding, &bound_type, &statement.consequent.statements);
+        });
+        if let Some(alt) = &statement.alternative {
+            self.run_branch(|checker| {
+                checker.check_statements(&alt.statements);
+            });
+        }
+    }
+
+    /// Infer the value of an optional binding and return the unwrapped type bound to its name.
+    fn infer_optional_binding(&mut self, binding: &OptionalBinding, value: &Expression) -> Type {
+        match self.infer_expression(value) {
+            Type::Optional(inner) => *inner,
+            Type::Unknown => Type::Unknown,
+            other => {
+                self.report_error(
+                    format!(
+                        "optional binding '{}' requires an optional value, found {}",
+                        binding.name,
+                        other.describe()
+                    ),
+                    Some(value.span),
+                );
+                Type::Unknown
+            }
+        }
+    }
+
+    fn check_bound_statements(
+        &mut self,
+        binding: &OptionalBinding,
+        bound_type: &Type,
+        statements: &[Statement],
+    ) {
+        self.push_scope();
+        self.insert(binding.name.clone(), bound_type.clone(), true);
+        self.binding_types.insert(binding.span, bound_type.clone());
+        self.check_statements(statements);
+        self.pop_scope();
+    }
+
+    fn check_guard(&mut self, statement: &GuardStatement) {
+        let bound_type = match &statement.binding {
+            Some(binding) => {
+                let bound_type = self.infer_optional_binding(binding, &statement.condition);
+                self.run_branch(|checker| {
+                    checker.check_statements(&statement.alternative.statements);
+                });
+                Some((binding, bound_type))
+            }
+            None => {
+                let condition = self.infer_expression(&statement.condition);
+                self.ensure_compatible(
+                    &Type::Bool,
+                    &condition,
+                    "guard condition",
+                    Some(statement.condition.span),
+                );
+                let guard = self.extract_optional_guard(&statement.condition);
+                match guard
+                    .as_ref()
+                    .and_then(|g| g.when_false.map(|state| (g, state)))
+                {
+                    Some((guard, state)) => {
+                        self.with_branch_guard(guard.name.as_str(), state, |checker| {
+                            checker.check_statements(&statement.alternative.statements);
+                        });
+                    }
+                    None => {
+                        self.run_branch(|checker| {
+                            checker.check_statements(&statement.alternative.statements);
+                        });
+                    }
+                }
+                if let Some(guard) = guard {
+                    self.clear_non_nil_fact(&guard.name);
+                    if matches!(guard.when_true, Some(OptionalGuardState::IsNonNil)) {
+                        self.mark_non_nil_fact(&guard.name);
+                    }
+                }
+                None
+            }
+        };
+
+        if !self.block_exits_early(&statement.alternative) {
+            self.report_error(
+                "guard else block must end with return, throw, break, or continue",
+                Some(statement.span),
+            );
+        }
+
+        // A guard binding stays in scope for the rest of the enclosing block.
+        if let Some((binding, bound_type)) = bound_type {
+            self.insert(binding.name.clone(), bound_type.clone(), true);
+            self.binding_types.insert(binding.span, bound_type);
+        }
+    }
+
     fn check_loop(&mut self, statement: &LoopStatement) {
         match statement.kind {
             LoopKind::While => {
+                if let LoopHeader::Binding { binding, value } = &statement.header {
+                    let bound_type = self.infer_optional_binding(binding, value);
+                    self.check_bound_statements(binding, &bound_type, &statement.body.statements);
+                    return;
+                }
                 let LoopHeader::Condition(condition) = &statement.header else {
                     self.report_error(
                         "loop header is not supported by the type checker yet",
@@ -1907,11 +2246,13 @@ impl TypeChecker {
                     ForPattern::Single(ident) => {
                         // Single variable: iterate over list elements
                         let element_type = match &iterator_type {
-                            Type::List(element_type) => element_type.as_ref().clone(),
+                            Type::List(element_type) | Type::Iterator(element_type) => {
+                                element_type.as_ref().clone()
+                            }
                             _ => {
                                 self.report_error(
                                     format!(
-                                        "cannot iterate over type {}, expected List[T]",
+                                        "cannot iterate over type {}, expected List[T] or Iterator[T]",
                                         iterator_type.describe()
                                     ),
                                     Some(iterator.span),
@@ -2027,6 +2368,7 @@ impl TypeChecker {
                     }
                     MatchPattern::Expression(pattern_expr) => {
                         let pattern_type = self.infer_expression(pattern_expr);
+                        self.check_constant_pattern(pattern_expr);
                         if scrutinee_type != Type::Unknown
                             && pattern_type != Type::Unknown
                             && pattern_type != scrutinee_type
@@ -2241,6 +2583,19 @@ impl TypeChecker {
             ctx.last_expression_type = None;
         }
 
+        let in_generator = self
+            .contexts
+            .last()
+            .is_some_and(|ctx| ctx.yield_type.is_some());
+        if let (true, Some(expr)) = (in_generator, &statement.expression) {
+            self.infer_expression(expr);
+            self.report_error(
+                "generator functions cannot return a value; use yield to produce items",
+                Some(statement.span),
+            );
+            return;
+        }
+
         let expected = self
             .contexts
             .last()
@@ -2291,6 +2646,18 @@ impl TypeChecker {
         }
     }
 
+    fn check_yield(&mut self, statement: &YieldStatement) {
+        let actual = self.infer_expression(&statement.expression);
+        let Some(expected) = self.contexts.last().and_then(|ctx| ctx.yield_type.clone()) else {
+            self.report_error(
+                "yield is only allowed inside generator functions",
+                Some(statement.span),
+            );
+            return;
+        };
+        self.ensure_compatible(&expected, &actual, "yield expression", Some(statement.span));
+    }
+
     fn with_branch_guard<F>(
         &mut self,
         name: &str,
@@ -2359,6 +2726,27 @@ impl TypeChecker {
             .unwrap_or(false)
     }
 
+    /// Whether control never falls off the end of `block`: it ends in an exit, or
+    /// in an `if` whose branches, including a final `else`, all exit.
+    fn block_exits_early(&self, block: &Block) -> bool {
+        match block.statements.last() {
+            Some(
+                Statement::Return(_)
+                | Statement::Throw(_)
+                | Statement::Break(_)
+                | Statement::Continue(_),
+            ) => true,
+            Some(Statement::Conditional(conditional)) => {
+                self.block_exits_early(&conditional.consequent)
+                    && conditional
+                        .alternative
+                        .as_ref()
+                        .is_some_and(|alternative| self.block_exits_early(alternative))
+            }
+            _ => false,
+        }
+    }
+
     fn extract_optional_guard(&self, expression: &Expression) -> Option<OptionalGuard> {
         let ExpressionKind::Binary(binary) = &expression.kind else {
             return None;
@@ -2423,11 +2811,68 @@ impl TypeChecker {
         }
     }
 
+    /// Check that defaulted parameters trail the required ones and that a variadic
+    /// parameter comes last, returning the arity callers must satisfy.
+    fn check_parameter_order(&mut self, function: &FunctionStatement) -> StdArity {
+        let count = function.parameters.len();
+        let mut required = 0;
+        let mut saw_optional = false;
+        for (index, param) in function.parameters.iter().enumerate() {
+            if param.variadic {
+                if index + 1 != count {
+                    self.report_error(
+                        format!(
+                            "variadic parameter '{}' must be the last parameter of function '{}'",
+                            param.name, function.name
+                        ),
+                        Some(param.span),
+                    );
+                }
+                if param.default_value.is_some() {
+                    self.report_error(
+                        format!(
+                            "variadic parameter '{}' in function '{}' cannot have a default value",
+                            param.name, function.name
+                        ),
+                        Some(param.span),
+                    );
+                }
+                saw_optional = true;
+            } else if param.default_value.is_some() {
+                saw_optional = true;
+            } else if saw_optional {
+                self.report_error(
+                    format!(
+                        "parameter '{}' in function '{}' must have a default value because an earlier parameter has one",
+                        param.name, function.name
+                    ),
+                    Some(param.span),
+                );
+            } else {
+                required += 1;
+            }
+        }
+
+        let variadic = function.parameters.iter().any(|param| param.variadic);
+        if required == count {
+            StdArity::Exact(count)
+        } else {
+            StdArity::Range {
+                min: required,
+                max: if variadic { None } else { Some(count) },
+            }
+        }
+    }
+
     fn is_nil_literal(expression: &Expression) -> bool {
         matches!(expression.kind, ExpressionKind::Literal(Literal::Nil))
     }
 
     fn check_function(&mut self, function: &FunctionStatement) {
+        if function.extern_function.is_some() {
+            self.check_extern_function(function);
+            return;
+        }
         self.push_type_parameters(&function.type_parameters);
         let mut param_types = Vec::with_capacity(function.parameters.len());
         for param in &function.parameters {
@@ -2445,8 +2890,13 @@ impl TypeChecker {
                 .as_ref()
                 .and_then(|annotation| self.parse_type(annotation))
                 .unwrap_or(Type::Unknown);
-            param_types.push(ty);
+            if param.variadic {
+                param_types.push(Type::List(Box::new(ty)));
+            } else {
+                param_types.push(ty);
+            }
         }
+        let arity = self.check_parameter_order(function);
 
         let declared_return_type = if let Some(annotation) = &function.return_type {
             self.parse_type(annotation)
@@ -2459,21 +2909,60 @@ impl TypeChecker {
         }
         .unwrap_or(Type::Unknown);
 
+        let yield_type = if is_generator(function) {
+            match &declared_return_type {
+                Type::Iterator(element) => Some(element.as_ref().clone()),
+                Type::Unknown => Some(Type::Unknown),
+                other => {
+                    self.report_error(
+                        format!(
+                            "generator function '{}' must return Iterator[T], found {}",
+                            function.name,
+                            other.describe()
+                        ),
+                        Some(function.name_span),
+                    );
+                    Some(Type::Unknown)
+                }
+            }
+        } else {
+            None
+        };
+        if yield_type.is_some() && function.error_annotation.is_some() {
+            self.report_error(
+                format!(
+                    "generator function '{}' cannot declare errors; handle them inside the generator",
+                    function.name
+                ),
+                Some(function.name_span),
+            );
+        }
+
         let allowed_errors = function
             .error_annotation
             .as_ref()
+            .filter(|_| yield_type.is_none())
             .map(|annotation| self.resolve_error_annotation(annotation))
             .unwrap_or_else(ErrorSet::empty);
 
         let signature = FunctionSignature {
             params: param_types.clone(),
             return_type: declared_return_type.clone(),
-            arity: StdArity::Exact(function.parameters.len()),
+            arity,
             type_parameters: function
                 .type_parameters
                 .iter()
                 .map(|param| param.name.clone())
                 .collect(),
+            param_names: function
+                .parameters
+                .iter()
+                .map(|param| param.name.clone())
+                .collect(),
+            variadic: function
+                .parameters
+                .last()
+                .is_some_and(|param| param.variadic),
         };
         self.functions
             .insert(function.name.clone(), signature.clone());
@@ -2487,11 +2976,18 @@ impl TypeChecker {
 
         self.push_scope();
         self.contexts.push(FunctionContext {
-            return_type: declared_return_type.clone(),
+            // A generator's body only ends the iteration with a bare `return`.
+            return_type: if yield_type.is_some() {
+                Type::Void
+            } else {
+                declared_return_type.clone()
+            },
             allowed_errors: allowed_errors.clone(),
             saw_explicit_return: false,
             last_expression_type: None,
             explicit_return_types: Vec::new(),
+            spawned_parameters: self.spawn_boundaries.spawned_parameters(function),
+            yield_type,
         });
 
         for (param, expected_type) in function.parameters.iter().zip(param_types.iter()) {
@@ -2514,7 +3010,7 @@ impl TypeChecker {
         self.check_statements(&function.body.statements);
 
         let context = self.contexts.pop().unwrap();
-        if !context.saw_explicit_return {
+        if context.yield_type.is_none() && !context.saw_explicit_return {
             match context.last_expression_type {
                 Some(last_type) => {
                     self.ensure_compatible(
@@ -2546,61 +3042,241 @@ impl TypeChecker {
         self.pop_type_parameters();
     }
 
-    fn check_test(&mut self, test: &TestStatement) {
-        self.push_scope();
-        self.check_statements(&test.body.statements);
-        self.pop_scope();
-    }
-
-    fn ensure_compatible(
-        &mut self,
-        expected: &Type,
-        actual: &Type,
-        context: &str,
-        span: Option<SourceSpan>,
-    ) -> bool {
-        if matches!(expected, Type::Unknown) || matches!(actual, Type::Unknown) {
-            return true;
+    /// Register an `extern def` signature. Only types with a fixed C representation may
+    /// cross the boundary: `Int` (int64_t), `Float` (double), `Bool` (bool), `CString`
+    /// (const char*, seen as `String` in Tea) and `CPointer` (void*, seen as `Int`).
+    fn check_extern_function(&mut self, function: &FunctionStatement) {
+        if self.scopes.len() != 1 {
+            self.report_error(
+                format!(
+                    "extern function '{}' must be declared at module level",
+                    function.name
+                ),
+                Some(function.name_span),
+            );
         }
 
-        match (expected, actual) {
-            (Type::Optional(_), Type::Nil) => true,
-            (Type::Optional(expected_inner), Type::Optional(actual_inner)) => {
-                self.ensure_compatible(expected_inner, actual_inner, context, span)
-            }
-            (Type::Optional(expected_inner), other) => {
-                self.ensure_compatible(expected_inner, other, context, span)
-            }
-            (Type::List(expected_inner), Type::List(actual_inner)) => {
-                self.ensure_compatible(expected_inner, actual_inner, context, span)
-            }
-            (Type::Dict(expected_inner), Type::Dict(actual_inner)) => {
-                self.ensure_compatible(expected_inner, actual_inner, context, span)
-            }
-            (Type::Struct(expected_struct), Type::Struct(actual_struct)) => {
-                if !Self::same_nominal_name(&expected_struct.name, &actual_struct.name) {
-                    self.report_error(
-                        format!(
-                            "{}: expected {}, found {}",
-                            context,
-                            expected.describe(),
-                            actual.describe()
+        let mut param_types = Vec::with_capacity(function.parameters.len());
+        for param in &function.parameters {
+            let ty = param
+                .type_annotation
+                .as_ref()
+                .and_then(|annotation| {
+                    self.parse_extern_type(
+                        annotation,
+                        false,
+                        &format!(
+                            "parameter '{}' of extern function '{}'",
+                            param.name, function.name
                         ),
-                        span,
-                    );
-                    return false;
-                }
+                        param.span,
+                    )
+                })
+                .unwrap_or(Type::Unknown);
+            param_types.push(ty);
+        }
+        let arity = self.check_parameter_order(function);
 
-                if expected_struct.type_arguments.len() != actual_struct.type_arguments.len() {
-                    self.report_error(
-                        format!(
-                            "{}: expected {}, found {}",
-                            context,
-                            expected.describe(),
-                            actual.describe()
-                        ),
-                        span,
-                    );
+        let return_type = match &function.return_type {
+            Some(annotation) => self
+                .parse_extern_type(
+                    annotation,
+                    true,
+                    &format!("return type of extern function '{}'", function.name),
+                    function.name_span,
+                )
+                .unwrap_or(Type::Unknown),
+            None => Type::Void,
+        };
+
+        let signature = FunctionSignature {
+            params: param_types,
+            return_type,
+            arity,
+            type_parameters: Vec::new(),
+            param_names: function
+                .parameters
+                .iter()
+                .map(|param| param.name.clone())
+                .collect(),
+            variadic: false,
+        };
+        let function_type = Type::Function(
+            signature.params.clone(),
+            Box::new(signature.return_type.clone()),
+        );
+        self.functions.insert(function.name.clone(), signature);
+        self.assign_global(function.name.clone(), function_type.clone());
+        self.binding_types.insert(function.name_span, function_type);
+        self.check_extern_symbol(function);
+    }
+
+    /// Every `extern def` binding one C symbol must agree on its C signature, since
+    /// they all call the same declaration.
+    fn check_extern_symbol(&mut self, function: &FunctionStatement) {
+        let Some(extern_function) = &function.extern_function else {
+            return;
+        };
+        let annotation_text = |annotation: Option<&TypeExpression>| {
+            annotation.map_or_else(
+                || "Void".to_string(),
+                |annotation| {
+                    annotation
+                        .tokens
+                        .iter()
+                        .map(|token| token.lexeme.as_str())
+                        .collect::<String>()
+                },
+            )
+        };
+        let params: Vec<String> = function
+            .parameters
+            .iter()
+            .map(|param| annotation_text(param.type_annotation.as_ref()))
+            .collect();
+        let c_signature = format!(
+            "({}) -> {}",
+            params.join(", "),
+            annotation_text(function.return_type.as_ref())
+        );
+        match self.extern_symbols.get(&extern_function.symbol) {
+            Some((first, existing)) if *existing != c_signature => {
+                let message = format!(
+                    "extern function '{}' binds C symbol '{}' as {}, but '{}' binds it as {}",
+                    function.name, extern_function.symbol, c_signature, first, existing
+                );
+                self.report_error(message, Some(function.name_span));
+            }
+            Some(_) => {}
+            None => {
+                self.extern_symbols.insert(
+                    extern_function.symbol.clone(),
+                    (function.name.clone(), c_signature),
+                );
+            }
+        }
+    }
+
+    fn parse_extern_type(
+        &mut self,
+        annotation: &TypeExpression,
+        allow_void: bool,
+        context: &str,
+        span: SourceSpan,
+    ) -> Option<Type> {
+        if let [token] = annotation.tokens.as_slice() {
+            match token.lexeme.as_str() {
+                "CString" => return Some(Type::String),
+                "CPointer" => return Some(Type::Int),
+                _ => {}
+            }
+        }
+        let ty = self.parse_type(annotation)?;
+        match ty {
+            Type::Int | Type::Float | Type::Bool => Some(ty),
+            Type::Void if allow_void => Some(ty),
+            other => {
+                let hint = if matches!(other, Type::String) {
+                    "; use CString to pass text"
+                } else {
+                    ""
+                };
+                self.report_error(
+                    format!(
+                        "{} cannot cross the C boundary as {}; only Int, Float, Bool, CString and CPointer are allowed{}",
+                        context,
+                        other.describe(),
+                        hint
+                    ),
+                    Some(span),
+                );
+                None
+            }
+        }
+    }
+
+    fn check_struct_defaults(&mut self, struct_stmt: &StructStatement) {
+        let Some(definition) = self.structs.get(&struct_stmt.name).cloned() else {
+            return;
+        };
+        for field in &struct_stmt.fields {
+            let Some(default_value) = &field.default_value else {
+                continue;
+            };
+            let Some(field_type) = definition.field(&field.name) else {
+                continue;
+            };
+            let actual = self.infer_expression(default_value);
+            self.ensure_compatible(
+                &field_type.ty,
+                &actual,
+                &format!(
+                    "default value for field '{}' in struct '{}'",
+                    field.name, struct_stmt.name
+                ),
+                Some(default_value.span),
+            );
+        }
+    }
+
+    fn check_test(&mut self, test: &TestStatement) {
+        self.push_scope();
+        self.check_statements(&test.body.statements);
+        self.pop_scope();
+    }
+
+    fn ensure_compatible(
+        &mut self,
+        expected: &Type,
+        actual: &Type,
+        context: &str,
+        span: Option<SourceSpan>,
+    ) -> bool {
+        if matches!(expected, Type::Unknown) || matches!(actual, Type::Unknown) {
+            return true;
+        }
+
+        match (expected, actual) {
+            (Type::Optional(_), Type::Nil) => true,
+            (Type::Optional(expected_inner), Type::Optional(actual_inner)) => {
+                self.ensure_compatible(expected_inner, actual_inner, context, span)
+            }
+            (Type::Optional(expected_inner), other) => {
+                self.ensure_compatible(expected_inner, other, context, span)
+            }
+            (Type::List(expected_inner), Type::List(actual_inner)) => {
+                self.ensure_compatible(expected_inner, actual_inner, context, span)
+            }
+            (Type::Dict(expected_inner), Type::Dict(actual_inner)) => {
+                self.ensure_compatible(expected_inner, actual_inner, context, span)
+            }
+            (Type::Iterator(expected_inner), Type::Iterator(actual_inner)) => {
+                self.ensure_compatible(expected_inner, actual_inner, context, span)
+            }
+            (Type::Struct(expected_struct), Type::Struct(actual_struct)) => {
+                if !Self::same_nominal_name(&expected_struct.name, &actual_struct.name) {
+                    self.report_error(
+                        format!(
+                            "{}: expected {}, found {}",
+                            context,
+                            expected.describe(),
+                            actual.describe()
+                        ),
+                        span,
+                    );
+                    return false;
+                }
+
+                if expected_struct.type_arguments.len() != actual_struct.type_arguments.len() {
+                    self.report_error(
+                        format!(
+                            "{}: expected {}, found {}",
+                            context,
+                            expected.describe(),
+                            actual.describe()
+                        ),
+                        span,
+                    );
                     return false;
                 }
 
@@ -2908,6 +3584,7 @@ impl TypeChecker {
             Type::Optional(inner) => Type::Optional(Box::new(self.substitute_type(inner, mapping))),
             Type::List(inner) => Type::List(Box::new(self.substitute_type(inner, mapping))),
             Type::Dict(inner) => Type::Dict(Box::new(self.substitute_type(inner, mapping))),
+            Type::Iterator(inner) => Type::Iterator(Box::new(self.substitute_type(inner, mapping))),
             Type::Function(params, return_type) => {
                 let substituted_params = params
                     .iter()
@@ -2928,6 +3605,19 @@ impl TypeChecker {
         }
     }
 
+    /// Whether `name` is the whole return type of `signature` and appears in none
+    /// of its parameters, so only an explicit type argument can bind it.
+    fn returns_only_type_parameter(&self, signature: &FunctionSignature, name: &str) -> bool {
+        if signature.return_type != Type::GenericParameter(name.to_string()) {
+            return false;
+        }
+        let probe = HashMap::from([(name.to_string(), Type::Unknown)]);
+        signature
+            .params
+            .iter()
+            .all(|param| self.substitute_type(param, &probe) == *param)
+    }
+
     fn unify_types(
         &mut self,
         expected: &Type,
@@ -2965,6 +3655,13 @@ impl TypeChecker {
                     self.ensure_compatible(expected, actual, context, span)
                 }
             }
+            Type::Iterator(expected_inner) => {
+                if let Type::Iterator(actual_inner) = actual {
+                    self.unify_types(expected_inner, actual_inner, mapping, context, span)
+                } else {
+                    self.ensure_compatible(expected, actual, context, span)
+                }
+            }
             Type::Function(expected_params, expected_ret) => {
                 if let Type::Function(actual_params, actual_ret) = actual {
                     if expected_params.len() != actual_params.len() {
@@ -3070,6 +3767,10 @@ impl TypeChecker {
                 let merged = self.merge_binding_type(*existing_inner, *new_inner, context, span);
                 Type::Dict(Box::new(merged))
             }
+            (Type::Iterator(existing_inner), Type::Iterator(new_inner)) => {
+                let merged = self.merge_binding_type(*existing_inner, *new_inner, context, span);
+                Type::Iterator(Box::new(merged))
+            }
             (
                 Type::Function(existing_params, existing_ret),
                 Type::Function(new_params, new_ret),
@@ -3204,6 +3905,11 @@ impl TypeChecker {
                     *first = Type::List(Box::new(Type::String));
                 }
             }
+            StdFunctionKind::BytesFromList => {
+                if let Some(first) = params.get_mut(0) {
+                    *first = Type::List(Box::new(Type::Int));
+                }
+            }
 
             _ => {}
         }
@@ -3213,9 +3919,12 @@ impl TypeChecker {
             StdFunctionKind::PathComponents => {
                 return_type = Type::List(Box::new(Type::String));
             }
-            StdFunctionKind::FsReadBytes => {
+            StdFunctionKind::BytesToList | StdFunctionKind::StringCodePoints => {
                 return_type = Type::List(Box::new(Type::Int));
             }
+            StdFunctionKind::StringChars | StdFunctionKind::StringGraphemes => {
+                return_type = Type::List(Box::new(Type::String));
+            }
             StdFunctionKind::FsListDir | StdFunctionKind::FsWalk | StdFunctionKind::FsGlob => {
                 return_type = Type::List(Box::new(Type::String));
             }
@@ -3239,6 +3948,8 @@ impl TypeChecker {
             return_type,
             arity: function.arity,
             type_parameters,
+            param_names: Vec::new(),
+            variadic: false,
         }
     }
 
@@ -3278,6 +3989,32 @@ impl TypeChecker {
         }
     }
 
+    /// Validate a `${value:spec}` specifier against the interpolated value's type. Syntax
+    /// errors are reported by the parser, so only type mismatches are reported here.
+    fn check_format_spec(&mut self, formatted: &FormattedInterpolation, value_type: &Type) {
+        let Ok(spec) = FormatSpec::parse(&formatted.spec) else {
+            return;
+        };
+        let class = match value_type {
+            Type::Int => FormatClass::Int,
+            Type::Float => FormatClass::Float,
+            Type::String => FormatClass::String,
+            Type::Unknown | Type::GenericParameter(_) => return,
+            _ => FormatClass::Other,
+        };
+        if let Err(message) = spec.check(class) {
+            self.report_error(
+                format!(
+                    "invalid format specifier '{}' for {}: {}",
+                    formatted.spec,
+                    value_type.describe(),
+                    message
+                ),
+                Some(formatted.spec_span),
+            );
+        }
+    }
+
     fn std_type_to_type(&self, ty: StdType) -> Type {
         match ty {
             StdType::Any => Type::Unknown,
@@ -3285,6 +4022,7 @@ impl TypeChecker {
             StdType::Int => Type::Int,
             StdType::Float => Type::Float,
             StdType::String => Type::String,
+            StdType::Bytes => Type::Bytes,
             StdType::List => Type::List(Box::new(Type::Unknown)),
             StdType::Dict => Type::Dict(Box::new(Type::Unknown)),
             StdType::Struct => Type::Unknown,
@@ -3351,8 +4089,15 @@ impl TypeChecker {
             ExpressionKind::Literal(literal) => self.type_from_literal(literal),
             ExpressionKind::InterpolatedString(template) => {
                 for part in &template.parts {
-                    if let InterpolatedStringPart::Expression(expr) = part {
-                        self.infer_expression(expr);
+                    match part {
+                        InterpolatedStringPart::Literal(_) => {}
+                        InterpolatedStringPart::Expression(expr) => {
+                            self.infer_expression(expr);
+                        }
+                        InterpolatedStringPart::Formatted(formatted) => {
+                            let value_type = self.infer_expression(&formatted.expression);
+                            self.check_format_spec(formatted, &value_type);
+                        }
                     }
                 }
                 Type::String
@@ -3399,15 +4144,25 @@ impl TypeChecker {
                 }
                 Type::Bool
             }
-            ExpressionKind::Assignment(assignment) => {
-                if let ExpressionKind::Identifier(identifier) = &assignment.target.kind {
+            ExpressionKind::Assignment(assignment) => match &assignment.target.kind {
+                ExpressionKind::Identifier(identifier) => {
                     let value_type = self.infer_expression(&assignment.value);
                     self.assign(&identifier.name, value_type.clone(), Some(identifier.span));
                     value_type
-                } else {
+                }
+                ExpressionKind::Index(index) => {
+                    if let ExpressionKind::Range(range) = &index.index.kind {
+                        self.check_slice_assignment(
+                            index,
+                            range,
+                            &assignment.value,
+                            expression.span,
+                        );
+                    }
                     Type::Unknown
                 }
-            }
+                _ => Type::Unknown,
+            },
             ExpressionKind::Grouping(expr) => self.infer_expression(expr),
             ExpressionKind::Call(call) => self.type_from_call(call, expression.span),
             ExpressionKind::Lambda(lambda) => self.type_from_lambda(lambda),
@@ -3586,6 +4341,7 @@ impl TypeChecker {
                     }
                     MatchPattern::Expression(pattern_expr) => {
                         let pattern_type = self.infer_expression(pattern_expr);
+                        self.check_constant_pattern(pattern_expr);
                         if scrutinee_type != Type::Unknown
                             && pattern_type != Type::Unknown
                             && pattern_type != scrutinee_type
@@ -3878,6 +4634,51 @@ impl TypeChecker {
         Type::Dict(Box::new(value_type))
     }
 
+    /// Slice bounds and steps are all optional, but any that are present must be Int.
+    fn check_range_bounds(&mut self, range: &RangeExpression, span: SourceSpan) {
+        let int_type = Type::Int;
+        for (bound, context) in [
+            (&range.start, "range start"),
+            (&range.end, "range end"),
+            (&range.step, "range step"),
+        ] {
+            if let Some(bound) = bound {
+                let bound_type = self.infer_expression(bound);
+                self.ensure_compatible(&int_type, &bound_type, context, Some(span));
+            }
+        }
+    }
+
+    /// `xs[a..b] = ys` replaces a contiguous run of a List with the elements of another List.
+    fn check_slice_assignment(
+        &mut self,
+        index: &IndexExpression,
+        range: &RangeExpression,
+        value: &Expression,
+        span: SourceSpan,
+    ) {
+        self.check_range_bounds(range, span);
+        if range.step.is_some() {
+            self.report_error("slice assignment does not support a step", Some(span));
+        }
+        let object_type = self.infer_expression(&index.object);
+        let value_type = self.infer_expression(value);
+        match object_type {
+            Type::List(element_type) => {
+                let expected = Type::List(element_type);
+                self.ensure_compatible(&expected, &value_type, "slice assignment", Some(span));
+            }
+            Type::Unknown => {}
+            other => self.report_error(
+                format!(
+                    "slice assignment requires a List, found {}",
+                    other.describe()
+                ),
+                Some(span),
+            ),
+        }
+    }
+
     fn type_from_index(&mut self, index: &IndexExpression, span: SourceSpan) -> Type {
         let object_type = self.infer_expression(&index.object);
         let index_type = self.infer_expression(&index.index);
@@ -3886,12 +4687,7 @@ impl TypeChecker {
             Type::List(element_type) => {
                 // Check if it's a range (slice) or single index
                 if let ExpressionKind::Range(range_expr) = &index.index.kind {
-                    // Validate that range start and end are Int
-                    let start_type = self.infer_expression(&range_expr.start);
-                    let end_type = self.infer_expression(&range_expr.end);
-                    let int_type = Type::Int;
-                    self.ensure_compatible(&int_type, &start_type, "range start", Some(span));
-                    self.ensure_compatible(&int_type, &end_type, "range end", Some(span));
+                    self.check_range_bounds(range_expr, span);
                     // Return a list of the same element type
                     Type::List(element_type.clone())
                 } else {
@@ -3909,12 +4705,7 @@ impl TypeChecker {
             Type::String => {
                 // Check if it's a range (slice) or single index
                 if let ExpressionKind::Range(range_expr) = &index.index.kind {
-                    // Validate that range start and end are Int
-                    let start_type = self.infer_expression(&range_expr.start);
-                    let end_type = self.infer_expression(&range_expr.end);
-                    let int_type = Type::Int;
-                    self.ensure_compatible(&int_type, &start_type, "range start", Some(span));
-                    self.ensure_compatible(&int_type, &end_type, "range end", Some(span));
+                    self.check_range_bounds(range_expr, span);
                     Type::String
                 } else {
                     // Single character index
@@ -3923,11 +4714,22 @@ impl TypeChecker {
                     Type::String
                 }
             }
+            Type::Bytes => {
+                if let ExpressionKind::Range(range_expr) = &index.index.kind {
+                    self.check_range_bounds(range_expr, span);
+                    Type::Bytes
+                } else {
+                    // Single byte index yields the byte value
+                    let int_type = Type::Int;
+                    self.ensure_compatible(&int_type, &index_type, "bytes index", Some(span));
+                    Type::Int
+                }
+            }
             Type::Unknown => Type::Unknown,
             other => {
                 self.report_error(
                     format!(
-                        "indexing requires a list, dict, or string value, found {}",
+                        "indexing requires a list, dict, string, or bytes value, found {}",
                         other.describe()
                     ),
                     Some(span),
@@ -4147,16 +4949,16 @@ impl TypeChecker {
             Type::List(ref _element_type) => {
                 // List methods.
                 match member.property.as_str() {
-                    "map" | "filter" | "reduce" | "find" | "any" | "all" | "contains"
-                    | "index_of" | "find_index" | "take" | "skip" => {
+                    name if LIST_METHODS.contains(&name) => {
                         // Return Type::Unknown as a marker - actual type resolution happens in type_from_call
                         Type::Unknown
                     }
                     _ => {
                         self.report_error(
                             format!(
-                                "List has no method named '{}'. Available methods: map, filter, reduce, find, any, all, contains, index_of, find_index, take, skip",
-                                member.property
+                                "List has no method named '{}'. Available methods: {}",
+                                member.property,
+                                LIST_METHODS.join(", ")
                             ),
                             Some(member.property_span),
                         );
@@ -4472,53 +5274,367 @@ impl TypeChecker {
         }
     }
 
-    fn type_from_call(&mut self, call: &crate::ast::CallExpression, span: SourceSpan) -> Type {
-        if let ExpressionKind::Identifier(identifier) = &call.callee.kind {
-            if let Some(error_def) = self.errors.get(&identifier.name) {
-                if error_def.variants.len() == 1 {
-                    if let Some((variant_name, variant_def)) = error_def
-                        .variants
-                        .iter()
-                        .next()
-                        .map(|(name, def)| (name.clone(), def.clone()))
-                    {
-                        return self.type_from_error_constructor(
-                            &identifier.name,
-                            &variant_name,
-                            &variant_def,
-                            call,
-                            span,
-                        );
-                    }
-                } else {
-                    self.report_error(
-                        format!(
-                            "error '{}' has multiple variants; call a specific variant like '{}.<variant>'",
-                            identifier.name, identifier.name
-                        ),
-                        Some(identifier.span),
-                    );
-                    return Type::Unknown;
-                }
+    /// Reject lambdas handed to another thread that capture values unsafe to share.
+    fn check_spawned_captures(&mut self, call: &CallExpression) {
+        let spawned = self.spawn_boundaries.spawned_arguments(call).to_vec();
+        if spawned.is_empty() {
+            return;
+        }
+        let callee = match &call.callee.kind {
+            ExpressionKind::Identifier(identifier) => identifier.name.clone(),
+            ExpressionKind::Member(member) => member.property.clone(),
+            _ => "spawn".to_string(),
+        };
+        for index in spawned {
+            let Some(argument) = call.arguments.get(index) else {
+                continue;
+            };
+            if !self
+                .checked_spawned_arguments
+                .insert(argument.expression.span)
+            {
+                continue;
             }
+            self.check_spawned_value(
+                &callee,
+                &argument.expression,
+                argument.expression.span,
+                &mut HashSet::new(),
+            );
+        }
+    }
 
-            if let Some(struct_def) = self.structs.get(&identifier.name).cloned() {
-                return self.type_from_struct_call(identifier, struct_def, call, span);
+    /// Reject values sent through a channel that are not safe to share, since
+    /// the receiving task gets the very value the sender still holds.
+    fn check_sent_arguments(&mut self, call: &CallExpression, arg_types: &[Type]) {
+        let sent = self.spawn_boundaries.sent_arguments(call).to_vec();
+        for index in sent {
+            let (Some(argument), Some(ty)) = (call.arguments.get(index), arg_types.get(index))
+            else {
+                continue;
+            };
+            if self.is_shareable(ty, &mut HashSet::new()) {
+                continue;
             }
+            let callee = match &call.callee.kind {
+                ExpressionKind::Identifier(identifier) => identifier.name.clone(),
+                ExpressionKind::Member(member) => member.property.clone(),
+                _ => "send".to_string(),
+            };
+            self.report_error(
+                format!(
+                    "value sent through '{}' has type {}, which is not safe to share between tasks; send its items one at a time or build it inside the receiving task",
+                    callee,
+                    ty.describe()
+                ),
+                Some(argument.expression.span),
+            );
         }
+    }
 
-        if let ExpressionKind::Member(member) = &call.callee.kind {
-            // First check for error constructor calls (ErrorName.Variant)
-            if let ExpressionKind::Identifier(error_ident) = &member.object.kind {
-                if let Some(error_def) = self.errors.get(&error_ident.name) {
-                    if let Some(variant_def) = error_def.variants.get(&member.property).cloned() {
-                        return self.type_from_error_constructor(
-                            &error_ident.name,
-                            &member.property,
-                            &variant_def,
-                            call,
-                            span,
-                        );
+    /// Report the values `expression` would share with another thread that
+    /// are not safe to share. Closures are followed through their captures, the
+    /// const closures they capture and the module variables they read.
+    fn check_spawned_value(
+        &mut self,
+        callee: &str,
+        expression: &Expression,
+        span: SourceSpan,
+        visited: &mut HashSet<usize>,
+    ) {
+        match &expression.kind {
+            ExpressionKind::Literal(_) | ExpressionKind::InterpolatedString(_) => {}
+            ExpressionKind::Grouping(inner) => {
+                self.check_spawned_value(callee, inner, span, visited);
+            }
+            ExpressionKind::Lambda(lambda) => {
+                self.check_spawned_lambda(callee, lambda.id, span, visited);
+            }
+            ExpressionKind::Identifier(identifier) => {
+                self.check_spawned_name(callee, &identifier.name, "uses", span, visited);
+            }
+            // A closure returned by a top-level function can only hold on to
+            // the function's arguments and the module variables it reads.
+            ExpressionKind::Call(inner)
+                if matches!(
+                    &inner.callee.kind,
+                    ExpressionKind::Identifier(function)
+                        if self.spawn_boundaries.is_function(&function.name)
+                            && self.is_module_level(&function.name)
+                ) =>
+            {
+                let ExpressionKind::Identifier(function) = &inner.callee.kind else {
+                    return;
+                };
+                let globals = self.spawn_boundaries.function_globals(&function.name);
+                for global in globals {
+                    self.check_spawned_name(callee, &global, "uses", span, visited);
+                }
+                for argument in &inner.arguments {
+                    self.check_spawned_value(callee, &argument.expression, span, visited);
+                }
+            }
+            _ => self.report_error(
+                format!(
+                    "value passed to '{}' must be a closure, a function or a call to a function, so the values it shares between tasks can be checked",
+                    callee
+                ),
+                Some(span),
+            ),
+        }
+    }
+
+    fn check_spawned_lambda(
+        &mut self,
+        callee: &str,
+        lambda_id: usize,
+        span: SourceSpan,
+        visited: &mut HashSet<usize>,
+    ) {
+        if !visited.insert(lambda_id) {
+            return;
+        }
+        let captures: BTreeSet<String> = self
+            .lambda_captures
+            .get(&lambda_id)
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        for name in &captures {
+            self.check_spawned_name(callee, name, "captures", span, visited);
+        }
+        for global in self.spawn_boundaries.lambda_globals(lambda_id) {
+            if !captures.contains(&global) {
+                self.check_spawned_name(callee, &global, "uses", span, visited);
+            }
+        }
+    }
+
+    fn check_spawned_name(
+        &mut self,
+        callee: &str,
+        name: &str,
+        verb: &str,
+        span: SourceSpan,
+        visited: &mut HashSet<usize>,
+    ) {
+        let spawned_parameter = self
+            .contexts
+            .iter()
+            .any(|context| context.spawned_parameters.contains(name));
+        if spawned_parameter {
+            return;
+        }
+        if let Some(lambda_id) = self.const_lambda(name) {
+            self.check_spawned_lambda(callee, lambda_id, span, visited);
+            return;
+        }
+        if self.is_module_level(name) {
+            if self.spawn_boundaries.is_global(name) {
+                self.check_spawned_global(callee, name, span);
+                return;
+            }
+            if self.spawn_boundaries.is_function(name) {
+                for global in self.spawn_boundaries.function_globals(name) {
+                    self.check_spawned_name(callee, &global, "uses", span, visited);
+                }
+                return;
+            }
+        }
+        let Some(ty) = self.lookup(name) else {
+            return;
+        };
+        if self.is_shareable(&ty, &mut HashSet::new()) {
+            return;
+        }
+        self.report_error(
+            format!(
+                "closure passed to '{}' {} '{}' of type {}, which is not safe to share between tasks; send its items through a channel or build it inside the task",
+                callee,
+                verb,
+                name,
+                ty.describe()
+            ),
+            Some(span),
+        );
+    }
+
+    /// Whether `name` refers to a module-level declaration rather than a local.
+    fn is_module_level(&self, name: &str) -> bool {
+        self.scopes
+            .iter()
+            .rposition(|scope| scope.contains_key(name))
+            .is_none_or(|index| index == 0)
+    }
+
+    /// Module variables can be reassigned by any task, so spawned code may only
+    /// read constants, and only those whose values are safe to share.
+    fn check_spawned_global(&mut self, callee: &str, name: &str, span: SourceSpan) {
+        if self.spawn_boundaries.is_mutable_global(name) {
+            self.report_error(
+                format!(
+                    "closure passed to '{}' uses module variable '{}', which can change while the task runs; make it a const or pass its value to the task",
+                    callee, name
+                ),
+                Some(span),
+            );
+            return;
+        }
+        self.spawned_constant_uses
+            .push((callee.to_string(), name.to_string(), span));
+    }
+
+    fn check_spawned_constant_uses(&mut self) {
+        let uses = std::mem::take(&mut self.spawned_constant_uses);
+        let mut reported = HashSet::new();
+        for (callee, name, span) in uses {
+            if self.const_lambda(&name).is_some() || !reported.insert((name.clone(), span)) {
+                continue;
+            }
+            let Some(ty) = self.scopes[0].get(&name).cloned() else {
+                continue;
+            };
+            if self.is_shareable(&ty, &mut HashSet::new()) {
+                continue;
+            }
+            self.report_error(
+                format!(
+                    "closure passed to '{}' uses '{}' of type {}, which is not safe to share between tasks; send its items through a channel or build it inside the task",
+                    callee,
+                    name,
+                    ty.describe()
+                ),
+                Some(span),
+            );
+        }
+    }
+
+    /// Whether values of `ty` can be read from several threads at once.
+    ///
+    /// Lists and dicts are mutable in place and closures may capture them, so
+    /// only scalars and structured values built from scalars qualify.
+    fn is_shareable(&self, ty: &Type, visiting: &mut HashSet<String>) -> bool {
+        match ty {
+            Type::Bool
+            | Type::Int
+            | Type::Float
+            | Type::String
+            | Type::Bytes
+            | Type::Nil
+            | Type::Void
+            | Type::Enum(_)
+            | Type::GenericParameter(_)
+            | Type::Unknown => true,
+            Type::Optional(inner) => self.is_shareable(inner, visiting),
+            Type::List(_) | Type::Dict(_) | Type::Iterator(_) | Type::Function(_, _) => false,
+            Type::Struct(struct_type) => {
+                if !visiting.insert(struct_type.name.clone()) {
+                    return true;
+                }
+                let Some(definition) = self.structs.get(&struct_type.name) else {
+                    return true;
+                };
+                let mapping: HashMap<String, Type> = definition
+                    .type_parameters
+                    .iter()
+                    .cloned()
+                    .zip(struct_type.type_arguments.iter().cloned())
+                    .collect();
+                definition.fields.iter().all(|field| {
+                    let field_type = self.substitute_type(&field.ty, &mapping);
+                    self.is_shareable(&field_type, visiting)
+                })
+            }
+            Type::Union(union_type) => {
+                if !visiting.insert(union_type.name.clone()) {
+                    return true;
+                }
+                self.unions.get(&union_type.name).is_none_or(|definition| {
+                    definition
+                        .members
+                        .iter()
+                        .all(|member| self.is_shareable(member, visiting))
+                })
+            }
+            Type::Error(error_type) => {
+                if !visiting.insert(error_type.name.clone()) {
+                    return true;
+                }
+                self.errors.get(&error_type.name).is_none_or(|definition| {
+                    definition
+                        .variants
+                        .values()
+                        .flat_map(|variant| variant.fields.iter())
+                        .all(|field| self.is_shareable(&field.ty, visiting))
+                })
+            }
+        }
+    }
+
+    fn type_from_call(&mut self, call: &crate::ast::CallExpression, span: SourceSpan) -> Type {
+        self.check_spawned_captures(call);
+
+        let is_struct_call = matches!(
+            &call.callee.kind,
+            ExpressionKind::Identifier(identifier) if self.structs.contains_key(&identifier.name)
+        );
+        if !is_struct_call {
+            if let Some(argument) = call.arguments.iter().find(|argument| argument.spread) {
+                self.report_error(
+                    "'..' base values are only supported in struct constructors",
+                    Some(argument.expression.span),
+                );
+                return Type::Unknown;
+            }
+        }
+
+        if let ExpressionKind::Identifier(identifier) = &call.callee.kind {
+            if let Some(error_def) = self.errors.get(&identifier.name) {
+                if error_def.variants.len() == 1 {
+                    if let Some((variant_name, variant_def)) = error_def
+                        .variants
+                        .iter()
+                        .next()
+                        .map(|(name, def)| (name.clone(), def.clone()))
+                    {
+                        return self.type_from_error_constructor(
+                            &identifier.name,
+                            &variant_name,
+                            &variant_def,
+                            call,
+                            span,
+                        );
+                    }
+                } else {
+                    self.report_error(
+                        format!(
+                            "error '{}' has multiple variants; call a specific variant like '{}.<variant>'",
+                            identifier.name, identifier.name
+                        ),
+                        Some(identifier.span),
+                    );
+                    return Type::Unknown;
+                }
+            }
+
+            if let Some(struct_def) = self.structs.get(&identifier.name).cloned() {
+                return self.type_from_struct_call(identifier, struct_def, call, span);
+            }
+        }
+
+        if let ExpressionKind::Member(member) = &call.callee.kind {
+            // First check for error constructor calls (ErrorName.Variant)
+            if let ExpressionKind::Identifier(error_ident) = &member.object.kind {
+                if let Some(error_def) = self.errors.get(&error_ident.name) {
+                    if let Some(variant_def) = error_def.variants.get(&member.property).cloned() {
+                        return self.type_from_error_constructor(
+                            &error_ident.name,
+                            &member.property,
+                            &variant_def,
+                            call,
+                            span,
+                        );
                     } else {
                         self.report_error(
                             format!(
@@ -4536,10 +5652,7 @@ impl TypeChecker {
                 if self.lookup(&error_ident.name).is_some() {
                     let object_type = self.infer_expression(&member.object);
                     if let Type::List(ref element_type) = object_type {
-                        if matches!(
-                            member.property.as_str(),
-                            "map" | "filter" | "reduce" | "find" | "any" | "all"
-                        ) {
+                        if LIST_METHODS.contains(&member.property.as_str()) {
                             return self.type_from_list_method_call(
                                 member,
                                 element_type,
@@ -4559,10 +5672,7 @@ impl TypeChecker {
                 // Object is not a simple identifier, safe to infer its type for method calls
                 let object_type = self.infer_expression(&member.object);
                 if let Type::List(ref element_type) = object_type {
-                    if matches!(
-                        member.property.as_str(),
-                        "map" | "filter" | "reduce" | "find" | "any" | "all"
-                    ) {
+                    if LIST_METHODS.contains(&member.property.as_str()) {
                         return self.type_from_list_method_call(member, element_type, call, span);
                     }
                 }
@@ -4588,12 +5698,29 @@ impl TypeChecker {
             }
         }
 
-        if let Some(arg) = call.arguments.iter().find(|arg| arg.name.is_some()) {
-            let span = arg.name_span.or(Some(arg.expression.span)).unwrap_or(span);
-            self.report_error(
-                "named arguments are only supported when constructing structs",
-                Some(span),
-            );
+        let is_function_call = matches!(
+            &call.callee.kind,
+            ExpressionKind::Identifier(ident) if self.functions.contains_key(&ident.name)
+        );
+        if !is_function_call {
+            if let Some(arg) = call.arguments.iter().find(|arg| arg.name.is_some()) {
+                let span = arg.name_span.or(Some(arg.expression.span)).unwrap_or(span);
+                self.report_error(
+                    "named arguments are only supported when calling functions or constructing structs",
+                    Some(span),
+                );
+            }
+        }
+
+        if let ExpressionKind::Identifier(identifier) = &call.callee.kind {
+            if matches!(identifier.name.as_str(), "min" | "max")
+                && matches!(
+                    self.builtins.get(&identifier.name),
+                    Some(StdFunctionKind::MathMin | StdFunctionKind::MathMax)
+                )
+            {
+                return self.type_from_min_max_call(&identifier.name, call, span);
+            }
         }
 
         let is_non_generic_function_call =
@@ -4614,21 +5741,52 @@ impl TypeChecker {
                 .map(|arg| self.infer_expression(&arg.expression))
                 .collect()
         };
+        self.check_sent_arguments(call, &arg_types);
         if let ExpressionKind::Member(member) = &call.callee.kind {
             if let ExpressionKind::Identifier(alias_ident) = &member.object.kind {
                 if let Some(binding) = self.module_aliases.get(&alias_ident.name) {
                     if let Some(function) = binding.functions.get(&member.property).cloned() {
-                        if !call.type_arguments.is_empty() {
+                        let qualified = format!("{}.{}", alias_ident.name, member.property);
+                        // JSON and CSV decoding are the only module functions that are
+                        // told which type to produce; everything else is fully described
+                        // by its signature.
+                        let takes_target_type = matches!(
+                            function.kind,
+                            StdFunctionKind::JsonDecode
+                                | StdFunctionKind::JsonDecodeAs
+                                | StdFunctionKind::CsvDecodeRecord
+                        );
+                        if !takes_target_type && !call.type_arguments.is_empty() {
                             self.report_error(
                                 format!(
-                                    "module function '{}.{}' does not accept type arguments",
-                                    alias_ident.name, member.property
+                                    "module function '{qualified}' does not accept type arguments"
                                 ),
                                 Some(member.property_span),
                             );
                             return Type::Unknown;
                         }
-                        let qualified = format!("{}.{}", alias_ident.name, member.property);
+                        if takes_target_type && call.type_arguments.len() > 1 {
+                            self.report_error(
+                                format!(
+                                    "module function '{qualified}' expects a single type argument ({} provided)",
+                                    call.type_arguments.len()
+                                ),
+                                Some(member.property_span),
+                            );
+                            return Type::Unknown;
+                        }
+                        if call.type_arguments.is_empty()
+                            && matches!(
+                                function.kind,
+                                StdFunctionKind::JsonDecodeAs | StdFunctionKind::CsvDecodeRecord
+                            )
+                        {
+                            self.report_error(
+                                format!("module function '{qualified}' requires a type argument"),
+                                Some(member.property_span),
+                            );
+                            return Type::Unknown;
+                        }
                         self.verify_call_arguments(
                             &function.signature.params,
                             &arg_types,
@@ -4637,6 +5795,19 @@ impl TypeChecker {
                             Some(&qualified),
                             Some(span),
                         );
+                        if matches!(
+                            function.kind,
+                            StdFunctionKind::JsonDecode
+                                | StdFunctionKind::JsonDecodeAs
+                                | StdFunctionKind::CsvDecodeRecord
+                        ) {
+                            if let Some(target) = self
+                                .parse_type_argument_expressions(&call.type_arguments)
+                                .pop()
+                            {
+                                return target;
+                            }
+                        }
                         return function.signature.return_type.clone();
                     } else {
                         self.report_error(
@@ -4654,17 +5825,28 @@ impl TypeChecker {
         if let ExpressionKind::Identifier(identifier) = &call.callee.kind {
             if let Some(signature) = self.functions.get(&identifier.name).cloned() {
                 if signature.type_parameters.is_empty() {
+                    let Some(expected_types) = self.expected_argument_types(
+                        &signature,
+                        &call.arguments,
+                        &identifier.name,
+                        span,
+                    ) else {
+                        for arg in &call.arguments {
+                            self.infer_expression(&arg.expression);
+                        }
+                        return signature.return_type;
+                    };
                     let arg_types_for_call: Vec<Type> = call
                         .arguments
                         .iter()
                         .enumerate()
                         .map(|(index, arg)| {
-                            let expected = signature.params.get(index);
+                            let expected = expected_types.get(index);
                             self.infer_argument_with_expected_type(&arg.expression, expected)
                         })
                         .collect();
                     self.verify_call_arguments(
-                        &signature.params,
+                        &expected_types,
                         &arg_types_for_call,
                         &call.arguments,
                         signature.arity,
@@ -4674,6 +5856,14 @@ impl TypeChecker {
                     return signature.return_type;
                 }
 
+                let Some(expected_types) = self.expected_argument_types(
+                    &signature,
+                    &call.arguments,
+                    &identifier.name,
+                    span,
+                ) else {
+                    return Type::Unknown;
+                };
                 let mut mapping: HashMap<String, Type> = HashMap::new();
                 let mut ok = true;
 
@@ -4723,7 +5913,7 @@ impl TypeChecker {
                 }
 
                 for (index, (expected, actual)) in
-                    signature.params.iter().zip(arg_types.iter()).enumerate()
+                    expected_types.iter().zip(arg_types.iter()).enumerate()
                 {
                     let arg_span = call.arguments.get(index).map(|arg| arg.expression.span);
                     let context =
@@ -4734,6 +5924,13 @@ impl TypeChecker {
                 }
 
                 for name in &signature.type_parameters {
+                    if !mapping.contains_key(name)
+                        && self.returns_only_type_parameter(&signature, name)
+                    {
+                        // A function such as `json.decode[T]` that is told which
+                        // type to produce returns an untyped value when it is not.
+                        mapping.insert(name.clone(), Type::Unknown);
+                    }
                     if !mapping.contains_key(name) {
                         let suggestion = format!(
                             "{}[{}]",
@@ -4766,8 +5963,12 @@ impl TypeChecker {
                     .map(|param| self.substitute_type(param, &mapping))
                     .collect();
 
+                let instantiated_arguments: Vec<Type> = expected_types
+                    .iter()
+                    .map(|param| self.substitute_type(param, &mapping))
+                    .collect();
                 self.verify_call_arguments(
-                    &instantiated_params,
+                    &instantiated_arguments,
                     &arg_types,
                     &call.arguments,
                     signature.arity,
@@ -4828,26 +6029,57 @@ impl TypeChecker {
         span: SourceSpan,
     ) -> Type {
         let field_count = definition.fields.len();
+        let required_count = definition
+            .fields
+            .iter()
+            .filter(|field| !field.has_default)
+            .count();
         let mut assignments: Vec<(&StructFieldType, &CallArgument, Type)> = Vec::new();
         let mut assignment_ok = true;
-        if call.arguments.len() != field_count {
+
+        let mut base: Option<&CallArgument> = None;
+        let mut base_type_arguments: Vec<Type> = Vec::new();
+        let mut arguments: Vec<&CallArgument> = Vec::new();
+        for argument in &call.arguments {
+            if !argument.spread {
+                arguments.push(argument);
+            } else if base.is_some() {
+                self.report_error(
+                    format!(
+                        "struct '{}' constructor accepts a single '..' base value",
+                        identifier.name
+                    ),
+                    Some(argument.expression.span),
+                );
+                assignment_ok = false;
+            } else {
+                base = Some(argument);
+            }
+        }
+
+        if base.is_none() && (arguments.len() < required_count || arguments.len() > field_count) {
+            let expected = if required_count == field_count {
+                field_count.to_string()
+            } else {
+                format!("{} to {}", required_count, field_count)
+            };
             self.report_error(
                 format!(
                     "struct '{}' constructor expects {} arguments but got {}",
                     identifier.name,
-                    field_count,
-                    call.arguments.len()
+                    expected,
+                    arguments.len()
                 ),
                 Some(identifier.span),
             );
             assignment_ok = false;
         }
 
-        let all_named = call.arguments.iter().all(|arg| arg.name.is_some());
-        let any_named = call.arguments.iter().any(|arg| arg.name.is_some());
+        let all_named = arguments.iter().all(|arg| arg.name.is_some());
+        let any_named = arguments.iter().any(|arg| arg.name.is_some());
 
         if any_named && !all_named {
-            if let Some(arg) = call.arguments.iter().find(|arg| arg.name.is_none()) {
+            if let Some(arg) = arguments.iter().find(|arg| arg.name.is_none()) {
                 self.report_error(
                     "cannot mix positional and named arguments when constructing a struct",
                     Some(arg.expression.span),
@@ -4855,10 +6087,43 @@ impl TypeChecker {
             }
         }
 
+        if let Some(base) = base {
+            if let Some(arg) = arguments.iter().find(|arg| arg.name.is_none()) {
+                self.report_error(
+                    format!(
+                        "fields after '..' in struct '{}' constructor must be named",
+                        identifier.name
+                    ),
+                    Some(arg.expression.span),
+                );
+                assignment_ok = false;
+            }
+            match self.infer_expression(&base.expression) {
+                Type::Struct(base_type)
+                    if Self::same_nominal_name(&base_type.name, &identifier.name) =>
+                {
+                    base_type_arguments = base_type.type_arguments;
+                }
+                Type::Unknown => {}
+                other => {
+                    self.report_error(
+                        format!(
+                            "'..' base for struct '{}' must be a {} value, found {}",
+                            identifier.name,
+                            identifier.name,
+                            other.describe()
+                        ),
+                        Some(base.expression.span),
+                    );
+                    assignment_ok = false;
+                }
+            }
+        }
+
         if all_named {
             let mut seen = HashSet::new();
             let mut provided = HashSet::new();
-            for argument in &call.arguments {
+            for argument in &arguments {
                 let arg_name = match &argument.name {
                     Some(name) => name,
                     None => continue,
@@ -4896,23 +6161,25 @@ impl TypeChecker {
                 }
             }
 
-            for field in &definition.fields {
-                if !provided.contains(&field.name) {
-                    self.report_error(
-                        format!(
-                            "missing value for field '{}' in struct '{}'",
-                            field.name, identifier.name
-                        ),
-                        Some(identifier.span),
-                    );
-                    assignment_ok = false;
+            if base.is_none() {
+                for field in &definition.fields {
+                    if !field.has_default && !provided.contains(&field.name) {
+                        self.report_error(
+                            format!(
+                                "missing value for field '{}' in struct '{}'",
+                                field.name, identifier.name
+                            ),
+                            Some(identifier.span),
+                        );
+                        assignment_ok = false;
+                    }
                 }
             }
         } else {
             for (index, (field, argument)) in definition
                 .fields
                 .iter()
-                .zip(call.arguments.iter())
+                .zip(arguments.iter().copied())
                 .enumerate()
             {
                 if let Some(name_span) = argument.name_span {
@@ -4987,6 +6254,32 @@ impl TypeChecker {
             }
         }
 
+        // Fields copied from a `..base` value keep the base's type arguments.
+        for (param, ty) in definition.type_parameters.iter().zip(base_type_arguments) {
+            if matches!(ty, Type::Unknown) {
+                continue;
+            }
+            match mapping.get(param) {
+                Some(explicit) if *explicit != ty => {
+                    self.report_error(
+                        format!(
+                            "'..' base for struct '{}' has {} for type parameter '{}' but {} was given",
+                            identifier.name,
+                            ty.describe(),
+                            param,
+                            explicit.describe()
+                        ),
+                        base.map(|base| base.expression.span),
+                    );
+                    return Type::Unknown;
+                }
+                Some(_) => {}
+                None => {
+                    mapping.insert(param.clone(), ty);
+                }
+            }
+        }
+
         let mut unified_ok = true;
         for (field, argument, actual) in &assignments {
             let context = format!(
@@ -5352,107 +6645,335 @@ impl TypeChecker {
                     Type::Unknown
                 }
             }
-            "contains" | "index_of" => {
-                if call.arguments.len() != 1 {
-                    self.report_error(
-                        format!(
-                            "List.{} expects 1 argument, found {}",
-                            member.property,
-                            call.arguments.len()
-                        ),
-                        Some(span),
-                    );
+            "contains" | "index_of" => {
+                if call.arguments.len() != 1 {
+                    self.report_error(
+                        format!(
+                            "List.{} expects 1 argument, found {}",
+                            member.property,
+                            call.arguments.len()
+                        ),
+                        Some(span),
+                    );
+                    return Type::Unknown;
+                }
+
+                let argument_type = self.infer_expression(&call.arguments[0].expression);
+                self.ensure_compatible(
+                    element_type,
+                    &argument_type,
+                    &format!("List.{} argument", member.property),
+                    Some(call.arguments[0].expression.span),
+                );
+
+                if member.property == "contains" {
+                    Type::Bool
+                } else {
+                    Type::Int
+                }
+            }
+            "find_index" => {
+                if call.arguments.len() != 1 {
+                    self.report_error(
+                        format!(
+                            "List.find_index expects 1 argument, found {}",
+                            call.arguments.len()
+                        ),
+                        Some(span),
+                    );
+                    return Type::Unknown;
+                }
+
+                let expected_param = element_type.clone();
+                let fn_type = self.infer_lambda_with_expected(
+                    &call.arguments[0].expression,
+                    &[expected_param.clone()],
+                );
+
+                if let Type::Function(params, return_type) = fn_type {
+                    if params.len() != 1 {
+                        self.report_error(
+                            format!(
+                                "List.find_index callback must take 1 parameter, found {}",
+                                params.len()
+                            ),
+                            Some(call.arguments[0].expression.span),
+                        );
+                        return Type::Unknown;
+                    }
+                    self.ensure_compatible(
+                        &expected_param,
+                        &params[0],
+                        "List.find_index callback parameter",
+                        Some(call.arguments[0].expression.span),
+                    );
+                    self.ensure_compatible(
+                        &Type::Bool,
+                        &return_type,
+                        "List.find_index callback return type",
+                        Some(call.arguments[0].expression.span),
+                    );
+                    Type::Int
+                } else {
+                    self.report_error(
+                        format!(
+                            "List.find_index expects a function, found {}",
+                            fn_type.describe()
+                        ),
+                        Some(call.arguments[0].expression.span),
+                    );
+                    Type::Unknown
+                }
+            }
+            "take" | "skip" => {
+                if call.arguments.len() != 1 {
+                    self.report_error(
+                        format!(
+                            "List.{} expects 1 argument, found {}",
+                            member.property,
+                            call.arguments.len()
+                        ),
+                        Some(span),
+                    );
+                    return Type::Unknown;
+                }
+
+                let count_type = self.infer_expression(&call.arguments[0].expression);
+                self.ensure_compatible(
+                    &Type::Int,
+                    &count_type,
+                    &format!("List.{} count", member.property),
+                    Some(call.arguments[0].expression.span),
+                );
+                Type::List(Box::new(element_type.clone()))
+            }
+            "sort" | "sorted" => {
+                // sort() sorts in place; sorted() returns a sorted copy
+                if !self.check_list_method_arity(member, call, 0, span) {
+                    return Type::Unknown;
+                }
+                self.check_list_element_type(
+                    member,
+                    element_type,
+                    &[Type::Int, Type::Float, Type::String],
+                    "Int, Float, or String",
+                );
+                if member.property == "sort" {
+                    Type::Void
+                } else {
+                    Type::List(Box::new(element_type.clone()))
+                }
+            }
+            "sort_by" | "sorted_by" => {
+                // sort_by(compare: Func(T, T) -> Int) orders by the sign of compare(a, b)
+                if !self.check_list_method_arity(member, call, 1, span) {
+                    return Type::Unknown;
+                }
+                let Some(return_type) = self.check_list_method_callback(
+                    member,
+                    call,
+                    0,
+                    &[element_type.clone(), element_type.clone()],
+                ) else {
+                    return Type::Unknown;
+                };
+                self.ensure_compatible(
+                    &Type::Int,
+                    &return_type,
+                    &format!("List.{} comparator return type", member.property),
+                    Some(call.arguments[0].expression.span),
+                );
+                if member.property == "sort_by" {
+                    Type::Void
+                } else {
+                    Type::List(Box::new(element_type.clone()))
+                }
+            }
+            "reverse" | "reversed" | "unique" | "pop" => {
+                if !self.check_list_method_arity(member, call, 0, span) {
+                    return Type::Unknown;
+                }
+                match member.property.as_str() {
+                    "reverse" => Type::Void,
+                    "pop" => Type::Optional(Box::new(element_type.clone())),
+                    _ => Type::List(Box::new(element_type.clone())),
+                }
+            }
+            "group_by" => {
+                // group_by(key: Func(T) -> String) -> Dict[String, List[T]]
+                if !self.check_list_method_arity(member, call, 1, span) {
+                    return Type::Unknown;
+                }
+                let Some(return_type) = self.check_list_method_callback(
+                    member,
+                    call,
+                    0,
+                    std::slice::from_ref(element_type),
+                ) else {
+                    return Type::Unknown;
+                };
+                self.ensure_compatible(
+                    &Type::String,
+                    &return_type,
+                    "List.group_by key type",
+                    Some(call.arguments[0].expression.span),
+                );
+                Type::Dict(Box::new(Type::List(Box::new(element_type.clone()))))
+            }
+            "partition" => {
+                // partition(fn: Func(T) -> Bool) -> [matching, rest]
+                if !self.check_list_method_arity(member, call, 1, span) {
+                    return Type::Unknown;
+                }
+                let Some(return_type) = self.check_list_method_callback(
+                    member,
+                    call,
+                    0,
+                    std::slice::from_ref(element_type),
+                ) else {
+                    return Type::Unknown;
+                };
+                self.ensure_compatible(
+                    &Type::Bool,
+                    &return_type,
+                    "List.partition callback return type",
+                    Some(call.arguments[0].expression.span),
+                );
+                Type::List(Box::new(Type::List(Box::new(element_type.clone()))))
+            }
+            "zip" => {
+                // zip(other: List[U], combine: Func(T, U) -> R) -> List[R]
+                if !self.check_list_method_arity(member, call, 2, span) {
+                    return Type::Unknown;
+                }
+                let other_type = self.infer_expression(&call.arguments[0].expression);
+                let other_element = match other_type {
+                    Type::List(inner) => *inner,
+                    Type::Unknown => Type::Unknown,
+                    other => {
+                        self.report_error(
+                            format!(
+                                "List.zip expects a List as first argument, found {}",
+                                other.describe()
+                            ),
+                            Some(call.arguments[0].expression.span),
+                        );
+                        return Type::Unknown;
+                    }
+                };
+                match self.check_list_method_callback(
+                    member,
+                    call,
+                    1,
+                    &[element_type.clone(), other_element],
+                ) {
+                    Some(return_type) => Type::List(Box::new(return_type)),
+                    None => Type::Unknown,
+                }
+            }
+            "flat_map" => {
+                // flat_map(fn: Func(T) -> List[R]) -> List[R]
+                if !self.check_list_method_arity(member, call, 1, span) {
+                    return Type::Unknown;
+                }
+                let Some(return_type) = self.check_list_method_callback(
+                    member,
+                    call,
+                    0,
+                    std::slice::from_ref(element_type),
+                ) else {
+                    return Type::Unknown;
+                };
+                match return_type {
+                    Type::List(_) => return_type,
+                    Type::Unknown => Type::Unknown,
+                    other => {
+                        self.report_error(
+                            format!(
+                                "List.flat_map callback must return a List, found {}",
+                                other.describe()
+                            ),
+                            Some(call.arguments[0].expression.span),
+                        );
+                        Type::Unknown
+                    }
+                }
+            }
+            "chunk" | "window" => {
+                if !self.check_list_method_arity(member, call, 1, span) {
                     return Type::Unknown;
                 }
-
-                let argument_type = self.infer_expression(&call.arguments[0].expression);
+                let size_type = self.infer_expression(&call.arguments[0].expression);
                 self.ensure_compatible(
-                    element_type,
-                    &argument_type,
-                    &format!("List.{} argument", member.property),
+                    &Type::Int,
+                    &size_type,
+                    &format!("List.{} size", member.property),
                     Some(call.arguments[0].expression.span),
                 );
-
-                if member.property == "contains" {
-                    Type::Bool
-                } else {
-                    Type::Int
+                Type::List(Box::new(Type::List(Box::new(element_type.clone()))))
+            }
+            "join" => {
+                if !self.check_list_method_arity(member, call, 1, span) {
+                    return Type::Unknown;
                 }
+                self.check_list_element_type(member, element_type, &[Type::String], "String");
+                let separator_type = self.infer_expression(&call.arguments[0].expression);
+                self.ensure_compatible(
+                    &Type::String,
+                    &separator_type,
+                    "List.join separator",
+                    Some(call.arguments[0].expression.span),
+                );
+                Type::String
             }
-            "find_index" => {
-                if call.arguments.len() != 1 {
-                    self.report_error(
-                        format!(
-                            "List.find_index expects 1 argument, found {}",
-                            call.arguments.len()
-                        ),
-                        Some(span),
-                    );
+            "sum" | "min" | "max" => {
+                if !self.check_list_method_arity(member, call, 0, span) {
                     return Type::Unknown;
                 }
-
-                let expected_param = element_type.clone();
-                let fn_type = self.infer_lambda_with_expected(
-                    &call.arguments[0].expression,
-                    &[expected_param.clone()],
+                self.check_list_element_type(
+                    member,
+                    element_type,
+                    &[Type::Int, Type::Float],
+                    "Int or Float",
                 );
-
-                if let Type::Function(params, return_type) = fn_type {
-                    if params.len() != 1 {
-                        self.report_error(
-                            format!(
-                                "List.find_index callback must take 1 parameter, found {}",
-                                params.len()
-                            ),
-                            Some(call.arguments[0].expression.span),
-                        );
-                        return Type::Unknown;
-                    }
-                    self.ensure_compatible(
-                        &expected_param,
-                        &params[0],
-                        "List.find_index callback parameter",
-                        Some(call.arguments[0].expression.span),
-                    );
-                    self.ensure_compatible(
-                        &Type::Bool,
-                        &return_type,
-                        "List.find_index callback return type",
-                        Some(call.arguments[0].expression.span),
-                    );
-                    Type::Int
+                if member.property == "sum" {
+                    element_type.clone()
                 } else {
-                    self.report_error(
-                        format!(
-                            "List.find_index expects a function, found {}",
-                            fn_type.describe()
-                        ),
-                        Some(call.arguments[0].expression.span),
-                    );
-                    Type::Unknown
+                    Type::Optional(Box::new(element_type.clone()))
                 }
             }
-            "take" | "skip" => {
-                if call.arguments.len() != 1 {
-                    self.report_error(
-                        format!(
-                            "List.{} expects 1 argument, found {}",
-                            member.property,
-                            call.arguments.len()
-                        ),
-                        Some(span),
-                    );
+            "insert" => {
+                if !self.check_list_method_arity(member, call, 2, span) {
                     return Type::Unknown;
                 }
-
-                let count_type = self.infer_expression(&call.arguments[0].expression);
+                let index_type = self.infer_expression(&call.arguments[0].expression);
                 self.ensure_compatible(
                     &Type::Int,
-                    &count_type,
-                    &format!("List.{} count", member.property),
+                    &index_type,
+                    "List.insert index",
                     Some(call.arguments[0].expression.span),
                 );
-                Type::List(Box::new(element_type.clone()))
+                let value_type = self.infer_expression(&call.arguments[1].expression);
+                self.ensure_compatible(
+                    element_type,
+                    &value_type,
+                    "List.insert value",
+                    Some(call.arguments[1].expression.span),
+                );
+                Type::Void
+            }
+            "remove_at" => {
+                if !self.check_list_method_arity(member, call, 1, span) {
+                    return Type::Unknown;
+                }
+                let index_type = self.infer_expression(&call.arguments[0].expression);
+                self.ensure_compatible(
+                    &Type::Int,
+                    &index_type,
+                    "List.remove_at index",
+                    Some(call.arguments[0].expression.span),
+                );
+                element_type.clone()
             }
             _ => {
                 self.report_error(
@@ -5464,6 +6985,101 @@ impl TypeChecker {
         }
     }
 
+    /// Report a List method called with the wrong number of arguments.
+    fn check_list_method_arity(
+        &mut self,
+        member: &crate::ast::MemberExpression,
+        call: &CallExpression,
+        expected: usize,
+        span: SourceSpan,
+    ) -> bool {
+        if call.arguments.len() == expected {
+            return true;
+        }
+        self.report_error(
+            format!(
+                "List.{} expects {} argument{}, found {}",
+                member.property,
+                expected,
+                if expected == 1 { "" } else { "s" },
+                call.arguments.len()
+            ),
+            Some(span),
+        );
+        false
+    }
+
+    /// Check the callback passed as argument `index` against the parameter types it will be
+    /// called with, returning its return type.
+    fn check_list_method_callback(
+        &mut self,
+        member: &crate::ast::MemberExpression,
+        call: &CallExpression,
+        index: usize,
+        expected_params: &[Type],
+    ) -> Option<Type> {
+        let argument_span = call.arguments[index].expression.span;
+        let fn_type =
+            self.infer_lambda_with_expected(&call.arguments[index].expression, expected_params);
+        let Type::Function(params, return_type) = fn_type else {
+            if !matches!(fn_type, Type::Unknown) {
+                self.report_error(
+                    format!(
+                        "List.{} expects a function, found {}",
+                        member.property,
+                        fn_type.describe()
+                    ),
+                    Some(argument_span),
+                );
+            }
+            return None;
+        };
+        if params.len() != expected_params.len() {
+            self.report_error(
+                format!(
+                    "List.{} callback must take {} parameter{}, found {}",
+                    member.property,
+                    expected_params.len(),
+                    if expected_params.len() == 1 { "" } else { "s" },
+                    params.len()
+                ),
+                Some(argument_span),
+            );
+            return None;
+        }
+        for (expected, actual) in expected_params.iter().zip(params.iter()) {
+            self.ensure_compatible(
+                expected,
+                actual,
+                &format!("List.{} callback parameter", member.property),
+                Some(argument_span),
+            );
+        }
+        Some(*return_type)
+    }
+
+    /// Report a List method that only works on lists of particular element types.
+    fn check_list_element_type(
+        &mut self,
+        member: &crate::ast::MemberExpression,
+        element_type: &Type,
+        allowed: &[Type],
+        description: &str,
+    ) {
+        if matches!(element_type, Type::Unknown) || allowed.contains(element_type) {
+            return;
+        }
+        self.report_error(
+            format!(
+                "List.{} requires a List of {}, found List[{}]",
+                member.property,
+                description,
+                element_type.describe()
+            ),
+            Some(member.property_span),
+        );
+    }
+
     /// Type check a Dict method call.
     fn type_from_dict_method_call(
         &mut self,
@@ -5670,6 +7286,8 @@ impl TypeChecker {
                     saw_explicit_return: false,
                     last_expression_type: None,
                     explicit_return_types: Vec::new(),
+                    spawned_parameters: HashSet::new(),
+                    yield_type: None,
                 });
                 self.check_statements(&block.statements);
                 let context = self.contexts.pop().unwrap();
@@ -5842,6 +7460,157 @@ impl TypeChecker {
         }
     }
 
+    /// Line up each call argument with the type of the parameter it binds to,
+    /// matching named arguments by name and sending extra positional arguments to
+    /// a variadic parameter. Returns `None` after reporting a mismatch.
+    fn expected_argument_types(
+        &mut self,
+        signature: &FunctionSignature,
+        arguments: &[CallArgument],
+        name: &str,
+        span: SourceSpan,
+    ) -> Option<Vec<Type>> {
+        let fixed_count = if signature.variadic {
+            signature.params.len().saturating_sub(1)
+        } else {
+            signature.params.len()
+        };
+        let variadic_element = match signature.params.last() {
+            Some(Type::List(element)) if signature.variadic => Some(element.as_ref().clone()),
+            _ => None,
+        };
+        let mut bound = vec![false; fixed_count];
+        let mut expected = Vec::with_capacity(arguments.len());
+        let mut saw_named = false;
+
+        for argument in arguments {
+            let Some(argument_name) = &argument.name else {
+                if saw_named {
+                    self.report_error(
+                        format!(
+                            "positional arguments cannot follow named arguments in call to '{}'",
+                            name
+                        ),
+                        Some(argument.expression.span),
+                    );
+                    return None;
+                }
+                let index = expected.len();
+                if index < fixed_count {
+                    bound[index] = true;
+                    expected.push(signature.params[index].clone());
+                } else if let Some(element) = &variadic_element {
+                    expected.push(element.clone());
+                }
+                continue;
+            };
+
+            saw_named = true;
+            let name_span = argument.name_span.unwrap_or(argument.expression.span);
+            match signature
+                .param_names
+                .iter()
+                .position(|param| param == argument_name)
+            {
+                Some(index) if index < fixed_count => {
+                    if bound[index] {
+                        self.report_error(
+                            format!(
+                                "argument '{}' is provided more than once in call to '{}'",
+                                argument_name, name
+                            ),
+                            Some(name_span),
+                        );
+                        return None;
+                    }
+                    bound[index] = true;
+                    expected.push(signature.params[index].clone());
+                }
+                Some(_) => {
+                    self.report_error(
+                        format!(
+                            "variadic parameter '{}' cannot be passed by name",
+                            argument_name
+                        ),
+                        Some(name_span),
+                    );
+                    return None;
+                }
+                None => {
+                    self.report_error(
+                        format!(
+                            "function '{}' has no parameter named '{}'",
+                            name, argument_name
+                        ),
+                        Some(name_span),
+                    );
+                    return None;
+                }
+            }
+        }
+
+        if saw_named {
+            let required = match signature.arity {
+                StdArity::Exact(count) => count,
+                StdArity::Range { min, .. } => min,
+            };
+            if let Some(index) = (0..required.min(fixed_count)).find(|index| !bound[*index]) {
+                self.report_error(
+                    format!(
+                        "missing argument for parameter '{}' in call to '{}'",
+                        signature.param_names[index], name
+                    ),
+                    Some(span),
+                );
+                return None;
+            }
+        }
+
+        Some(expected)
+    }
+
+    /// `@min` and `@max` accept either two Ints or two Floats and return the same type.
+    fn type_from_min_max_call(
+        &mut self,
+        name: &str,
+        call: &crate::ast::CallExpression,
+        span: SourceSpan,
+    ) -> Type {
+        let arg_types: Vec<Type> = call
+            .arguments
+            .iter()
+            .map(|arg| self.infer_expression(&arg.expression))
+            .collect();
+        if arg_types.len() != 2 {
+            self.report_error(
+                format!(
+                    "function '{}' expected 2 arguments but got {}",
+                    name,
+                    arg_types.len()
+                ),
+                Some(span),
+            );
+            return Type::Unknown;
+        }
+        match (&arg_types[0], &arg_types[1]) {
+            (Type::Int, Type::Int) => Type::Int,
+            (Type::Float, Type::Float) => Type::Float,
+            (Type::Unknown, _) | (_, Type::Unknown) => Type::Unknown,
+            (left, right) => {
+                self.report_error(
+                    format!(
+                        "function '{}' expects two Int or two Float arguments, found {} and {}",
+                        name,
+                        left.describe(),
+                        right.describe()
+                    ),
+                    Some(span),
+                );
+                Type::Unknown
+            }
+        }
+    }
+
     fn verify_call_arguments(
         &mut self,
         expected: &[Type],
@@ -5971,6 +7740,8 @@ impl TypeChecker {
                     saw_explicit_return: false,
                     last_expression_type: None,
                     explicit_return_types: Vec::new(),
+                    spawned_parameters: HashSet::new(),
+                    yield_type: None,
                 });
                 self.check_statements(&block.statements);
                 let context = self.contexts.pop().unwrap();
@@ -6002,6 +7773,9 @@ impl TypeChecker {
         if let Some(scope) = self.scopes.last_mut() {
             scope.insert(name.clone(), ty);
         }
+        if let Some(scope) = self.const_lambda_scopes.last_mut() {
+            scope.remove(&name);
+        }
         if let Some(scope) = self.const_scopes.last_mut() {
             if mutable {
                 scope.remove(&name);
@@ -6034,6 +7808,9 @@ impl TypeChecker {
         if let Some(const_scope) = self.const_scopes.first_mut() {
             const_scope.remove(&name);
         }
+        if let Some(scope) = self.const_lambda_scopes.first_mut() {
+            scope.remove(&name);
+        }
         if let Some(global) = self.scopes.first_mut() {
             global.insert(name.clone(), ty.clone());
         }
@@ -6059,15 +7836,26 @@ impl TypeChecker {
         None
     }
 
+    /// The lambda bound to the `const` named `name`, if that is how it was declared.
+    fn const_lambda(&self, name: &str) -> Option<usize> {
+        let index = self
+            .scopes
+            .iter()
+            .rposition(|scope| scope.contains_key(name))?;
+        self.const_lambda_scopes.get(index)?.get(name).copied()
+    }
+
     fn push_scope(&mut self) {
         self.scopes.push(HashMap::new());
         self.const_scopes.push(HashSet::new());
+        self.const_lambda_scopes.push(HashMap::new());
         self.non_nil_scopes.push(HashSet::new());
     }
 
     fn pop_scope(&mut self) {
         self.scopes.pop();
         self.const_scopes.pop();
+        self.const_lambda_scopes.pop();
         self.non_nil_scopes.pop();
     }
 
@@ -6183,6 +7971,10 @@ impl<'a> TypeAnnotationParser<'a> {
                     self.advance();
                     Ok(Type::String)
                 }
+                "Bytes" => {
+                    self.advance();
+                    Ok(Type::Bytes)
+                }
                 "Nil" => {
                     self.advance();
                     Ok(Type::Nil)
@@ -6192,6 +7984,7 @@ impl<'a> TypeAnnotationParser<'a> {
                     Ok(Type::Void)
                 }
                 "List" => self.parse_list_type(),
+                "Iterator" => self.parse_iterator_type(),
                 "Dict" => self.parse_dict_type(),
                 "Func" | "Function" | "Fn" => self.parse_function_type(),
                 other => {
@@ -6304,6 +8097,18 @@ impl<'a> TypeAnnotationParser<'a> {
         Ok(Type::List(Box::new(element)))
     }
 
+    fn parse_iterator_type(&mut self) -> Result<Type, TypeError> {
+        let start = self.advance().unwrap().clone(); // consume 'Iterator'
+        self.expect(TokenKind::LBracket, "expected '[' after Iterator", &start)?;
+        let element = self.parse_type()?;
+        self.expect(
+            TokenKind::RBracket,
+            "expected ']' after Iterator element type",
+            &start,
+        )?;
+        Ok(Type::Iterator(Box::new(element)))
+    }
+
     fn parse_dict_type(&mut self) -> Result<Type, TypeError> {
         let start = self.advance().unwrap().clone(); // consume 'Dict'
         self.expect(TokenKind::LBracket, "expected '[' after Dict", &start)?;
@@ -0,0 +1,463 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::ast::{Module, Statement};
+use crate::expansion::{ModuleExpander, ModuleGraph};
+use crate::typechecker::{Type, TypeChecker};
+
+/// One source module of a program, compiled to its own object file.
+///
+/// Module expansion still hands the resolver and type checker a single
+/// flattened module; units split that module back along file boundaries so
+/// code generation can emit, cache and link each file separately. Nothing
+/// here lets a build skip type checking a module.
+#[derive(Debug, Clone)]
+pub struct CompilationUnit {
+    path: PathBuf,
+    is_entry: bool,
+    source: String,
+    top_level_source: String,
+    functions: Vec<String>,
+    dependencies: Vec<PathBuf>,
+    interface: ModuleInterface,
+}
+
+impl CompilationUnit {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The entry unit owns `main`, every global and all top-level statements.
+    pub fn is_entry(&self) -> bool {
+        self.is_entry
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The source with its function definitions left out: the declarations and
+    /// top-level code that the entry unit compiles on this unit's behalf.
+    pub fn top_level_source(&self) -> &str {
+        &self.top_level_source
+    }
+
+    /// Expanded names of the functions whose bodies this unit emits. Generic
+    /// functions are listed once and emit every instantiation the program uses.
+    pub fn functions(&self) -> &[String] {
+        &self.functions
+    }
+
+    /// Modules this unit imports directly.
+    pub fn dependencies(&self) -> &[PathBuf] {
+        &self.dependencies
+    }
+
+    pub fn interface(&self) -> &ModuleInterface {
+        &self.interface
+    }
+}
+
+/// What importers of a module can see without its function bodies: the
+/// declarations it makes, their types and link symbols, and the source of
+/// generic functions so importers' instantiations can be regenerated. Builds
+/// hash interfaces to decide which cached unit objects are still valid; they
+/// are not saved anywhere else.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ModuleInterface {
+    pub path: PathBuf,
+    pub declarations: Vec<InterfaceDeclaration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InterfaceDeclaration {
+    pub name: String,
+    pub kind: InterfaceDeclarationKind,
+    pub public: bool,
+    /// Names the declaration has in the expanded program, one per import alias.
+    pub symbols: Vec<String>,
+    pub signature: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub type_parameters: Vec<String>,
+    /// Instantiations of a generic function the program requests, such as `first[Int]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub instances: Vec<String>,
+    /// Source of a generic function or the initializer of a constant.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Compile-time value of a constant, as a Tea literal, when its initializer folds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InterfaceDeclarationKind {
+    Function,
+    Const,
+    Var,
+    Struct,
+    Union,
+    Enum,
+    Error,
+}
+
+impl ModuleInterface {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("module interface serializes to JSON")
+    }
+
+    pub fn declaration(&self, name: &str) -> Option<&InterfaceDeclaration> {
+        self.declarations
+            .iter()
+            .find(|declaration| declaration.name == name)
+    }
+}
+
+struct UnitBuilder {
+    path: PathBuf,
+    source: String,
+    /// 0-based line ranges of the unit's function definitions.
+    function_lines: Vec<(usize, usize)>,
+    functions: Vec<String>,
+    declarations: BTreeMap<String, InterfaceDeclaration>,
+}
+
+pub(crate) fn build_units(
+    module: &Module,
+    graph: &ModuleGraph,
+    type_checker: &TypeChecker,
+) -> Vec<CompilationUnit> {
+    let mut builders: Vec<UnitBuilder> = graph
+        .sources
+        .iter()
+        .map(|(path, source)| UnitBuilder {
+            path: path.clone(),
+            source: source.clone(),
+            function_lines: Vec::new(),
+            functions: Vec::new(),
+            declarations: BTreeMap::new(),
+        })
+        .collect();
+    let index_of: HashMap<PathBuf, usize> = builders
+        .iter()
+        .enumerate()
+        .map(|(index, builder)| (builder.path.clone(), index))
+        .collect();
+
+    let global_types = type_checker.global_binding_types();
+    let struct_definitions = type_checker.struct_definitions();
+    let union_definitions = type_checker.union_definitions();
+    let enum_definitions = type_checker.enum_definitions();
+    let error_definitions = type_checker.error_definitions();
+    let function_instances = type_checker.function_instances();
+    let constants = type_checker.constants();
+
+    for (statement, origin) in module.statements.iter().zip(&graph.origins) {
+        let Some(&index) = index_of.get(&origin.path) else {
+            continue;
+        };
+        let builder = &mut builders[index];
+        let symbols = ModuleExpander::declared_names(statement);
+        for (symbol, name) in symbols.iter().zip(&origin.names) {
+            let (kind, signature, type_parameters, instances, body) = match statement {
+                Statement::Function(function) => {
+                    builder.functions.push(symbol.clone());
+                    if let Some(lines) = function_lines(&builder.source, function.name_span.line) {
+                        if !builder.function_lines.contains(&lines) {
+                            builder.function_lines.push(lines);
+                        }
+                    }
+                    let type_parameters: Vec<String> = function
+                        .type_parameters
+                        .iter()
+                        .map(|param| param.name.clone())
+                        .collect();
+                    let instances = function_instances
+                        .get(symbol)
+                        .map(|instances| {
+                            instances
+                                .iter()
+                                .map(|instance| {
+                                    let args = instance
+                                        .type_arguments
+                                        .iter()
+                                        .map(Type::describe)
+                                        .collect::<Vec<_>>()
+                                        .join(", ");
+                                    format!("{name}[{args}]")
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    let body = if type_parameters.is_empty() {
+                        None
+                    } else {
+                        function_source(&builder.source, function.name_span.line)
+                    };
+                    (
+                        InterfaceDeclarationKind::Function,
+                        describe_global(&global_types, symbol),
+                        type_parameters,
+                        instances,
+                        body,
+                    )
+                }
+                Statement::Var(var_stmt) => {
+                    let binding = var_stmt
+                        .bindings
+                        .iter()
+                        .find(|binding| &binding.name == symbol);
+                    let body = binding
+                        .filter(|_| var_stmt.is_const)
+                        .and_then(|binding| binding.initializer.as_ref())
+                        .and_then(|initializer| {
+                            span_source(
+                                &builder.source,
+                                initializer.span.line,
+                                initializer.span.column,
+                                initializer.span.end_line,
+                                initializer.span.end_column,
+                            )
+                        });
+                    let kind = if var_stmt.is_const {
+                        InterfaceDeclarationKind::Const
+                    } else {
+                        InterfaceDeclarationKind::Var
+                    };
+                    (
+                        kind,
+                        describe_global(&global_types, symbol),
+                        Vec::new(),
+                        Vec::new(),
+                        body,
+                    )
+                }
+                Statement::Struct(_) => {
+                    let definition = struct_definitions.get(symbol);
+                    let fields = definition
+                        .map(|definition| {
+                            definition
+                                .fields
+                                .iter()
+                                .map(|field| format!("{}: {}", field.name, field.ty.describe()))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        })
+                        .unwrap_or_default();
+                    let type_parameters = definition
+                        .map(|definition| definition.type_parameters.clone())
+                        .unwrap_or_default();
+                    (
+                        InterfaceDeclarationKind::Struct,
+                        format!("{{ {fields} }}"),
+                        type_parameters,
+                        Vec::new(),
+                        None,
+                    )
+                }
+                Statement::Union(_) => {
+                    let members = union_definitions
+                        .get(symbol)
+                        .map(|definition| {
+                            definition
+                                .members
+                                .iter()
+                                .map(Type::describe)
+                                .collect::<Vec<_>>()
+                                .join(" | ")
+                        })
+                        .unwrap_or_default();
+                    (
+                        InterfaceDeclarationKind::Union,
+                        members,
+                        Vec::new(),
+                        Vec::new(),
+                        None,
+                    )
+                }
+                Statement::Enum(_) => {
+                    let variants = enum_definitions
+                        .get(symbol)
+                        .map(|definition| {
+                            definition
+                                .variants
+                                .iter()
+                                .map(|variant| variant.name.clone())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        })
+                        .unwrap_or_default();
+                    (
+                        InterfaceDeclarationKind::Enum,
+                        variants,
+                        Vec::new(),
+                        Vec::new(),
+                        None,
+                    )
+                }
+                Statement::Error(error_stmt) => {
+                    let definition = error_definitions.get(symbol);
+                    let variants = error_stmt
+                        .variants
+                        .iter()
+                        .map(|variant| {
+                            let fields = definition
+                                .and_then(|definition| definition.variants.get(&variant.name))
+                                .map(|variant| {
+                                    variant
+                                        .fields
+                                        .iter()
+                                        .map(|field| {
+                                            format!("{}: {}", field.name, field.ty.describe())
+                                        })
+                                        .collect::<Vec<_>>()
+                                        .join(", ")
+                                })
+                                .unwrap_or_default();
+                            format!("{}({fields})", variant.name)
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    (
+                        InterfaceDeclarationKind::Error,
+                        variants,
+                        Vec::new(),
+                        Vec::new(),
+                        None,
+                    )
+                }
+                _ => continue,
+            };
+
+            let declaration =
+                builder
+                    .declarations
+                    .entry(name.clone())
+                    .or_insert_with(|| InterfaceDeclaration {
+                        name: name.clone(),
+                        kind,
+                        public: origin.is_public,
+                        symbols: Vec::new(),
+                        signature,
+                        type_parameters,
+                        instances: Vec::new(),
+                        body,
+                        value: constants.get(symbol).map(|value| value.to_source()),
+                    });
+            if !declaration.symbols.contains(symbol) {
+                declaration.symbols.push(symbol.clone());
+            }
+            for instance in instances {
+                if !declaration.instances.contains(&instance) {
+                    declaration.instances.push(instance);
+                }
+            }
+        }
+    }
+
+    builders
+        .into_iter()
+        .map(|builder| {
+            let mut declarations: Vec<InterfaceDeclaration> =
+                builder.declarations.into_values().collect();
+            for declaration in &mut declarations {
+                declaration.symbols.sort();
+                declaration.instances.sort();
+            }
+            let top_level_source = builder
+                .source
+                .lines()
+                .enumerate()
+                .filter(|(index, _)| {
+                    !builder
+                        .function_lines
+                        .iter()
+                        .any(|(start, end)| (*start..=*end).contains(index))
+                })
+                .map(|(_, line)| line)
+                .collect::<Vec<_>>()
+                .join("\n");
+            CompilationUnit {
+                is_entry: builder.path == graph.entry_path,
+                dependencies: graph
+                    .imports
+                    .get(&builder.path)
+                    .cloned()
+                    .unwrap_or_default(),
+                interface: ModuleInterface {
+                    path: builder.path.clone(),
+                    declarations,
+                },
+                path: builder.path,
+                source: builder.source,
+                top_level_source,
+                functions: builder.functions,
+            }
+        })
+        .collect()
+}
+
+fn describe_global(global_types: &HashMap<String, Type>, symbol: &str) -> String {
+    global_types
+        .get(symbol)
+        .map(Type::describe)
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// The 0-based lines of a top-level `def` starting at `line`, through its
+/// closing `end`.
+fn function_lines(source: &str, line: usize) -> Option<(usize, usize)> {
+    let lines: Vec<&str> = source.lines().collect();
+    let start = line.checked_sub(1)?;
+    let header = lines.get(start)?;
+    let indent = &header[..header.len() - header.trim_start().len()];
+    let closing = format!("{indent}end");
+    let end = (start + 1..lines.len()).find(|&index| lines[index].trim_end() == closing)?;
+    Some((start, end))
+}
+
+/// The text of a top-level `def` starting at `line`, through its closing `end`.
+fn function_source(source: &str, line: usize) -> Option<String> {
+    let (start, end) = function_lines(source, line)?;
+    let lines: Vec<&str> = source.lines().collect();
+    Some(lines[start..=end].join("\n"))
+}
+
+/// The text between two 1-based positions, both inclusive.
+fn span_source(
+    source: &str,
+    line: usize,
+    column: usize,
+    end_line: usize,
+    end_column: usize,
+) -> Option<String> {
+    let lines: Vec<&str> = source.lines().collect();
+    if line == 0 || end_line < line || end_line > lines.len() {
+        return None;
+    }
+    let mut text = String::new();
+    for index in line..=end_line {
+        let current: Vec<char> = lines[index - 1].chars().collect();
+        let from = if index == line {
+            column.saturating_sub(1)
+        } else {
+            0
+        };
+        let to = if index == end_line {
+            end_column.min(current.len())
+        } else {
+            current.len()
+        };
+        if from < to {
+            text.extend(&current[from..to]);
+        }
+        if index != end_line {
+            text.push('\n');
+        }
+    }
+    let text = text.trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
@@ -41,6 +41,48 @@ use string from "std.string"
     );
 }
 
+#[test]
+fn browser_target_expands_std_random() {
+    let source = r#"
+use random from "std.random"
+
+const rng = random.seeded(7)
+@println(random.shuffle([1, 2, 3], rng))
+@println(random.uuid4())
+"#;
+
+    let (mut compiler, source_file) = browser_compiler(source);
+    compiler
+        .compile(&source_file)
+        .expect("browser compile to succeed");
+    assert!(
+        compiler.diagnostics().is_empty(),
+        "expected no diagnostics, found {:?}",
+        compiler.diagnostics().entries()
+    );
+}
+
+#[test]
+fn browser_target_expands_std_math() {
+    let source = r#"
+use math from "std.math"
+
+@println(math.gcd(12, 18))
+@println(math.hypot(3.0, 4.0))
+@println(math.PI)
+"#;
+
+    let (mut compiler, source_file) = browser_compiler(source);
+    compiler
+        .compile(&source_file)
+        .expect("browser compile to succeed");
+    assert!(
+        compiler.diagnostics().is_empty(),
+        "expected no diagnostics, found {:?}",
+        compiler.diagnostics().entries()
+    );
+}
+
 #[test]
 fn browser_target_rejects_native_only_modules() {
     let source = r#"
@@ -63,3 +105,19 @@ use fs from "std.fs"
         compiler.diagnostics().entries()
     );
 }
+
+#[test]
+fn browser_target_rejects_extern_functions() {
+    let source = r#"
+extern def strlen(s: CString) -> Int from "c"
+"#;
+
+    let (mut compiler, source_file) = browser_compiler(source);
+    assert!(compiler.compile(&source_file).is_err());
+    assert!(compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .any(|diagnostic| diagnostic.message
+            == "extern function 'strlen' cannot be called in the browser target"));
+}
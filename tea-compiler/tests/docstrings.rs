@@ -112,13 +112,23 @@ fn public_stdlib_functions_have_docstrings() -> anyhow::Result<()> {
     for path in [
         "stdlib/args/mod.tea",
         "stdlib/assert/mod.tea",
+        "stdlib/crypto/mod.tea",
+        "stdlib/csv/mod.tea",
+        "stdlib/encoding/mod.tea",
         "stdlib/env/mod.tea",
         "stdlib/fs/mod.tea",
         "stdlib/json/mod.tea",
+        "stdlib/log/mod.tea",
+        "stdlib/math/mod.tea",
+        "stdlib/net/mod.tea",
         "stdlib/path/mod.tea",
         "stdlib/process/mod.tea",
+        "stdlib/random/mod.tea",
         "stdlib/regex/mod.tea",
         "stdlib/string/mod.tea",
+        "stdlib/term/mod.tea",
+        "stdlib/toml/mod.tea",
+        "stdlib/yaml/mod.tea",
     ] {
         let absolute_path = workspace_root.join(path);
         let source_text = fs::read_to_string(&absolute_path)?;
@@ -656,3 +656,25 @@ print(count)
 
     assert_lines(&format_source(input), &expected);
 }
+
+#[test]
+fn keeps_extern_declarations_at_top_level() {
+    let input = r#"
+extern def strlen(s: CString) -> Int from "c"
+  pub extern def abs(value: Int) -> Int from "c"
+def twice(s: String) -> Int
+strlen(s) * 2
+end
+"#;
+
+    let expected = [
+        "extern def strlen(s: CString) -> Int from \"c\"",
+        "pub extern def abs(value: Int) -> Int from \"c\"",
+        "",
+        "def twice(s: String) -> Int",
+        "  strlen(s) * 2",
+        "end",
+    ];
+
+    assert_lines(&format_source(input), &expected);
+}
@@ -3,7 +3,9 @@ use std::fs;
 use anyhow::Result;
 #[cfg(feature = "llvm-backend")]
 use tea_compiler::aot;
-use tea_compiler::{CompileOptions, Compiler, SourceFile, SourceId};
+use tea_compiler::{
+    CompileOptions, Compiler, InterfaceDeclarationKind, Project, SourceFile, SourceId,
+};
 use tempfile::tempdir;
 
 #[test]
@@ -51,6 +53,119 @@ box.value + helper.SCALE
     Ok(())
 }
 
+#[test]
+fn expanded_module_records_every_loaded_source() -> Result<()> {
+    let dir = tempdir()?;
+    fs::write(
+        dir.path().join("inner.tea"),
+        "pub def scale(value: Int) -> Int\n  value * 2\nend\n",
+    )?;
+    fs::write(
+        dir.path().join("outer.tea"),
+        "use inner from \"./inner\"\n\npub def doubled(value: Int) -> Int\n  inner.scale(value)\nend\n",
+    )?;
+
+    let main_source = "use outer from \"./outer\"\n\nouter.doubled(4)\n";
+    let main_path = dir.path().join("main.tea");
+    fs::write(&main_path, main_source)?;
+
+    let source_file = SourceFile::new(SourceId(0), main_path, main_source.to_string());
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let parsed = compiler.parse_source(&source_file)?;
+    let expanded = compiler.expand_modules(&source_file, parsed)?;
+    let loaded: Vec<_> = expanded
+        .loaded_sources()
+        .iter()
+        .filter_map(|(path, _)| path.file_name().and_then(|name| name.to_str()))
+        .collect();
+    assert_eq!(loaded, vec!["outer.tea", "inner.tea"]);
+
+    Ok(())
+}
+
+#[test]
+fn package_imports_resolve_through_manifest_dependencies() -> Result<()> {
+    let dir = tempdir()?;
+    let helpers = dir.path().join("helpers");
+    fs::create_dir_all(helpers.join("text"))?;
+    fs::write(
+        helpers.join("tea.toml"),
+        "[package]\nname = \"helpers\"\nversion = \"0.2.0\"\n",
+    )?;
+    fs::write(
+        helpers.join("text").join("mod.tea"),
+        "pub def shout(value: String) -> String\n  `${value}!`\nend\n",
+    )?;
+
+    let app = dir.path().join("app");
+    fs::create_dir_all(&app)?;
+    fs::write(
+        app.join("tea.toml"),
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nhelpers = { path = \"../helpers\" }\n",
+    )?;
+    let main_source = "use text from \"helpers/text\"\n\n@println(text.shout(\"tea\"))\n";
+    let main_path = app.join("main.tea");
+    fs::write(&main_path, main_source)?;
+
+    let source_file = SourceFile::new(SourceId(0), main_path, main_source.to_string());
+    let mut compiler = Compiler::new(CompileOptions::default());
+    compiler.compile(&source_file)?;
+    assert!(
+        compiler.diagnostics().is_empty(),
+        "expected no diagnostics, found {:?}",
+        compiler.diagnostics()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn package_imports_fail_when_lockfile_is_stale() -> Result<()> {
+    let dir = tempdir()?;
+    let helpers = dir.path().join("helpers");
+    fs::create_dir_all(&helpers)?;
+    fs::write(
+        helpers.join("tea.toml"),
+        "[package]\nname = \"helpers\"\nversion = \"0.2.0\"\n",
+    )?;
+    fs::write(helpers.join("mod.tea"), "pub const GREETING = \"hi\"\n")?;
+
+    let app = dir.path().join("app");
+    fs::create_dir_all(&app)?;
+    fs::write(
+        app.join("tea.toml"),
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nhelpers = { path = \"../helpers\" }\n",
+    )?;
+    Project::load(&app)?.write_lockfile()?;
+    fs::write(
+        helpers.join("tea.toml"),
+        "[package]\nname = \"helpers\"\nversion = \"0.3.0\"\n",
+    )?;
+
+    let main_source = "use helpers from \"helpers\"\n\n@println(helpers.GREETING)\n";
+    let main_path = app.join("main.tea");
+    fs::write(&main_path, main_source)?;
+
+    let source_file = SourceFile::new(SourceId(0), main_path, main_source.to_string());
+    let mut compiler = Compiler::new(CompileOptions::default());
+    assert!(compiler.compile(&source_file).is_err());
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|diagnostic| diagnostic.message.clone())
+        .collect();
+    assert!(
+        messages
+            .iter()
+            .any(|message| message
+                .contains("package 'helpers' is locked at 0.2.0 but 0.3.0 is present")),
+        "expected stale lockfile diagnostic, found {messages:?}"
+    );
+
+    Ok(())
+}
+
 #[test]
 fn module_imports_respect_public_visibility() -> Result<()> {
     let dir = tempdir()?;
@@ -195,6 +310,45 @@ end
     Ok(())
 }
 
+#[test]
+fn module_types_resolve_in_lambda_parameter_annotations() -> Result<()> {
+    let dir = tempdir()?;
+    fs::write(
+        dir.path().join("helper.tea"),
+        r#"
+pub struct Box {
+  value: Int
+}
+
+pub def doubler() -> Func(Box) -> Int
+  |box: Box| => box.value * 2
+end
+"#,
+    )?;
+
+    let main_source = r#"
+use helper from "./helper"
+
+const measure = |box: helper.Box| => box.value + 1
+const double = helper.doubler()
+measure(helper.Box(value: 2)) + double(helper.Box(value: 3))
+"#;
+
+    let main_path = dir.path().join("main.tea");
+    fs::write(&main_path, main_source)?;
+
+    let source_file = SourceFile::new(SourceId(0), main_path.clone(), main_source.to_string());
+    let mut compiler = Compiler::new(CompileOptions::default());
+    compiler.compile(&source_file)?;
+    assert!(
+        compiler.diagnostics().is_empty(),
+        "expected no diagnostics, found {:?}",
+        compiler.diagnostics()
+    );
+
+    Ok(())
+}
+
 #[test]
 fn public_module_types_work_in_statement_match_patterns() -> Result<()> {
     let dir = tempdir()?;
@@ -521,6 +675,400 @@ helper.run(5)
     Ok(())
 }
 
+#[test]
+fn selective_imports_bind_exported_names_directly() -> Result<()> {
+    let dir = tempdir()?;
+    fs::write(
+        dir.path().join("shapes.tea"),
+        r#"
+pub struct Point {
+  x: Int
+  y: Int
+}
+
+pub def origin() -> Point
+  Point(x: 0, y: 0)
+end
+
+pub def shift(point: Point, by: Int) -> Point
+  Point(x: point.x + by, y: point.y + by)
+end
+"#,
+    )?;
+
+    let main_source = r#"
+use { Point, origin, shift as moved } from "./shapes"
+use { join } from "std.path"
+
+def describe(point: Point) -> String
+  join([`${point.x}`, `${point.y}`])
+end
+
+@println(describe(moved(origin(), 2)))
+"#;
+    let main_path = dir.path().join("main.tea");
+    fs::write(&main_path, main_source)?;
+
+    let source_file = SourceFile::new(SourceId(0), main_path, main_source.to_string());
+    let mut compiler = Compiler::new(CompileOptions::default());
+    compiler.compile(&source_file)?;
+    assert!(
+        compiler.diagnostics().is_empty(),
+        "expected no diagnostics, found {:?}",
+        compiler.diagnostics()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn module_imported_twice_in_one_file_is_checked_once() -> Result<()> {
+    let dir = tempdir()?;
+    fs::write(
+        dir.path().join("shapes.tea"),
+        r#"
+pub struct Point {
+  x: Int
+  y: Int = 0
+}
+
+pub def from_x(x: Int) -> Point
+  Point(..x, y: 1)
+end
+"#,
+    )?;
+
+    let main_source = r#"
+use shapes from "./shapes"
+use { Point } from "./shapes"
+
+const point: shapes.Point = Point(x: 1)
+@println(point.x)
+"#;
+    let main_path = dir.path().join("main.tea");
+    fs::write(&main_path, main_source)?;
+
+    let source_file = SourceFile::new(SourceId(0), main_path, main_source.to_string());
+    let mut compiler = Compiler::new(CompileOptions::default());
+    assert!(compiler.compile(&source_file).is_err());
+
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|diagnostic| diagnostic.message.clone())
+        .collect();
+    assert_eq!(
+        messages.len(),
+        1,
+        "expected one diagnostic, found {messages:?}"
+    );
+    assert!(
+        messages[0].contains("must be a Point value, found Int"),
+        "expected base diagnostic, found {messages:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn selective_imports_reject_private_and_missing_names() -> Result<()> {
+    let dir = tempdir()?;
+    fs::write(
+        dir.path().join("helper.tea"),
+        "pub def greet() -> String\n  secret()\nend\n\ndef secret() -> String\n  \"psst\"\nend\n",
+    )?;
+
+    let main_source = "use { greet, secret, missing } from \"./helper\"\n\ngreet()\n";
+    let main_path = dir.path().join("main.tea");
+    fs::write(&main_path, main_source)?;
+
+    let source_file = SourceFile::new(SourceId(0), main_path, main_source.to_string());
+    let mut compiler = Compiler::new(CompileOptions::default());
+    assert!(compiler.compile(&source_file).is_err());
+
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|diagnostic| diagnostic.message.clone())
+        .collect();
+    assert!(
+        messages
+            .iter()
+            .any(|message| message.contains("'secret' is not public in module './helper'")),
+        "expected private import diagnostic, found {messages:?}"
+    );
+    assert!(
+        messages
+            .iter()
+            .any(|message| message.contains("module './helper' has no export named 'missing'")),
+        "expected missing export diagnostic, found {messages:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn selective_imports_reject_locals_that_shadow_them() -> Result<()> {
+    let dir = tempdir()?;
+    let main_source = r#"
+use { to_upper } from "std.string"
+
+def label(to_upper: String) -> String
+  to_upper
+end
+
+const shout = |to_upper: String| => to_upper
+@println(label("a") + shout("b"))
+"#;
+    let main_path = dir.path().join("main.tea");
+    fs::write(&main_path, main_source)?;
+
+    let source_file = SourceFile::new(SourceId(0), main_path, main_source.to_string());
+    let mut compiler = Compiler::new(CompileOptions::default());
+    assert!(compiler.compile(&source_file).is_err());
+
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|diagnostic| diagnostic.message.clone())
+        .collect();
+    let shadowing: Vec<_> = messages
+        .iter()
+        .filter(|message| {
+            message.starts_with(
+                "redeclaration of parameter 'to_upper' shadows the name imported at line 2",
+            )
+        })
+        .collect();
+    assert_eq!(
+        shadowing.len(),
+        2,
+        "expected the function and lambda parameters to be rejected, found {messages:?}"
+    );
+    assert!(
+        !messages.iter().any(|message| message.contains("unused")),
+        "expected no unused-binding diagnostics, found {messages:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn pub_use_reexports_names_and_submodules() -> Result<()> {
+    let dir = tempdir()?;
+    let package = dir.path().join("helpers");
+    fs::create_dir_all(&package)?;
+    fs::write(
+        package.join("text.tea"),
+        r#"
+## Add emphasis.
+pub def shout(value: String) -> String
+  `${value}!`
+end
+
+pub struct Label {
+  text: String
+}
+"#,
+    )?;
+    fs::write(
+        package.join("mod.tea"),
+        "pub use text from \"./text\"\npub use { shout as emphasize } from \"./text\"\n",
+    )?;
+
+    let main_source = r#"
+use helpers from "./helpers/mod"
+
+def label(value: String) -> helpers.text.Label
+  helpers.text.Label(text: helpers.emphasize(value))
+end
+
+@println(helpers.text.shout(label("tea").text))
+"#;
+    let main_path = dir.path().join("main.tea");
+    fs::write(&main_path, main_source)?;
+
+    let source_file = SourceFile::new(SourceId(0), main_path, main_source.to_string());
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let compilation = compiler.compile(&source_file)?;
+    assert!(
+        compiler.diagnostics().is_empty(),
+        "expected no diagnostics, found {:?}",
+        compiler.diagnostics()
+    );
+
+    let binding = compilation
+        .analysis
+        .module_aliases()
+        .get("helpers")
+        .expect("helpers alias");
+    for export in ["emphasize", "text.shout", "text.Label"] {
+        assert!(
+            binding.exports.iter().any(|name| name == export),
+            "expected '{export}' in {:?}",
+            binding.exports
+        );
+    }
+    assert_eq!(
+        binding.export_docs.get("emphasize").map(String::as_str),
+        Some("Add emphasis.")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn compilation_units_follow_module_boundaries() -> Result<()> {
+    let dir = tempdir()?;
+    fs::write(
+        dir.path().join("shapes.tea"),
+        r#"
+pub const SIDES: Int = 4
+
+pub struct Square {
+  size: Int
+}
+
+pub def area(square: Square) -> Int
+  square.size * square.size
+end
+
+pub def first[T](items: List[T]) -> T
+  items[0]
+end
+
+def helper() -> Int
+  SIDES
+end
+"#,
+    )?;
+    fs::write(
+        dir.path().join("report.tea"),
+        "use shapes from \"./shapes\"\n\npub def describe(size: Int) -> Int\n  shapes.area(shapes.Square(size: size))\nend\n",
+    )?;
+
+    let main_source = r#"
+use report from "./report"
+use { first } from "./shapes"
+
+@println(report.describe(3) + first([1, 2]))
+"#;
+    let main_path = dir.path().join("main.tea");
+    fs::write(&main_path, main_source)?;
+
+    let source_file = SourceFile::new(SourceId(0), main_path.clone(), main_source.to_string());
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let compilation = compiler.compile(&source_file)?;
+
+    let names: Vec<String> = compilation
+        .units
+        .iter()
+        .map(|unit| {
+            unit.path()
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    assert_eq!(names, ["main.tea", "report.tea", "shapes.tea"]);
+    assert!(compilation.units[0].is_entry());
+    assert!(compilation.units[0].functions().is_empty());
+
+    let report = &compilation.units[1];
+    assert!(!report.is_entry());
+    assert_eq!(report.functions(), ["__module_report_describe"]);
+    assert!(report.dependencies()[0].ends_with("shapes.tea"));
+
+    let shapes = &compilation.units[2];
+    // `shapes` is imported twice, once through `report` and once directly, so
+    // each of its functions is emitted under both expanded names.
+    assert_eq!(shapes.functions().len(), 6, "{:?}", shapes.functions());
+    let interface = shapes.interface();
+    let area = interface.declaration("area").expect("area declaration");
+    assert_eq!(area.kind, InterfaceDeclarationKind::Function);
+    assert!(area.public);
+    assert!(
+        area.signature.ends_with("Square) -> Int"),
+        "{}",
+        area.signature
+    );
+    let first = interface.declaration("first").expect("first declaration");
+    assert_eq!(first.type_parameters, ["T"]);
+    assert_eq!(first.instances, ["first[Int]"]);
+    assert!(
+        first
+            .body
+            .as_deref()
+            .is_some_and(|body| body.ends_with("items[0]\nend")),
+        "{:?}",
+        first.body
+    );
+    let sides = interface.declaration("SIDES").expect("SIDES declaration");
+    assert_eq!(sides.kind, InterfaceDeclarationKind::Const);
+    assert_eq!(sides.body.as_deref(), Some("4"));
+    assert_eq!(sides.value.as_deref(), Some("4"));
+    assert!(!interface.declaration("helper").expect("helper").public);
+
+    assert!(shapes
+        .top_level_source()
+        .contains("pub const SIDES: Int = 4"));
+    assert!(!shapes.top_level_source().contains("square.size"));
+
+    Ok(())
+}
+
+#[test]
+fn public_constants_fold_across_modules() -> Result<()> {
+    let dir = tempdir()?;
+    fs::write(
+        dir.path().join("limits.tea"),
+        "pub const BASE: Int = 10\npub const RATIO: Float = BASE * 0.5\n",
+    )?;
+    fs::write(
+        dir.path().join("config.tea"),
+        r#"
+use limits from "./limits"
+
+pub const LIMIT = limits.BASE * 4 + 2
+pub const LABEL = `limit ${LIMIT:>4}`
+const STARTED = @len(@args())
+"#,
+    )?;
+
+    let main_source = "use config from \"./config\"\n\n@println(config.LABEL)\n";
+    let main_path = dir.path().join("main.tea");
+    fs::write(&main_path, main_source)?;
+
+    let source_file = SourceFile::new(SourceId(0), main_path, main_source.to_string());
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let compilation = compiler.compile(&source_file)?;
+
+    let value_of = |file: &str, name: &str| {
+        let unit = compilation
+            .units
+            .iter()
+            .find(|unit| unit.path().ends_with(file))
+            .expect("unit");
+        unit.interface()
+            .declaration(name)
+            .and_then(|declaration| declaration.value.clone())
+    };
+    assert_eq!(value_of("limits.tea", "RATIO").as_deref(), Some("5.0"));
+    assert_eq!(value_of("config.tea", "LIMIT").as_deref(), Some("42"));
+    assert_eq!(
+        value_of("config.tea", "LABEL").as_deref(),
+        Some("\"limit   42\"")
+    );
+    // Private constants may still be computed at runtime.
+    assert_eq!(value_of("config.tea", "STARTED"), None);
+
+    Ok(())
+}
+
 #[cfg(feature = "llvm-backend")]
 #[test]
 fn relative_module_imports_compile_in_aot() -> Result<()> {
@@ -558,3 +1106,90 @@ use helper from "./helper"
 
     Ok(())
 }
+
+#[cfg(feature = "llvm-backend")]
+#[test]
+fn compilation_units_compile_to_separate_objects() -> Result<()> {
+    let dir = tempdir()?;
+    fs::write(
+        dir.path().join("helper.tea"),
+        "pub def wrap(value: Int) -> Int\n  value * 3\nend\n",
+    )?;
+
+    let main_source = "use helper from \"./helper\"\n\n@println(helper.wrap(5))\n";
+    let main_path = dir.path().join("main.tea");
+    fs::write(&main_path, main_source)?;
+
+    let source_file = SourceFile::new(SourceId(0), main_path, main_source.to_string());
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let compilation = compiler.compile(&source_file)?;
+    assert_eq!(compilation.units.len(), 2);
+
+    let options = aot::ObjectCompileOptions::default();
+    for (index, unit) in compilation.units.iter().enumerate() {
+        let object = dir.path().join(format!("unit{index}.o"));
+        aot::compile_unit_to_object(&compilation, unit, &object, &options)?;
+        assert!(
+            object.is_file(),
+            "missing object for {}",
+            unit.path().display()
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn module_functions_may_be_named_error() -> Result<()> {
+    let dir = tempdir()?;
+    fs::write(
+        dir.path().join("report.tea"),
+        "pub def error(message: String) -> String\n  `error: ${message}`\nend\n",
+    )?;
+
+    let main_source =
+        "use report from \"./report\"\n\nvar line: String = report.error(\"disk full\")\n";
+    let main_path = dir.path().join("main.tea");
+    fs::write(&main_path, main_source)?;
+
+    let source_file = SourceFile::new(SourceId(0), main_path, main_source.to_string());
+    let mut compiler = Compiler::new(CompileOptions::default());
+    compiler.compile(&source_file)?;
+    assert!(
+        compiler.diagnostics().is_empty(),
+        "expected no diagnostics, found {:?}",
+        compiler.diagnostics()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn extern_bindings_of_one_symbol_must_agree() -> Result<()> {
+    let dir = tempdir()?;
+    fs::write(
+        dir.path().join("text.tea"),
+        "pub extern def strlen(text: CString) -> Int from \"c\"\n",
+    )?;
+
+    let main_source = "use text from \"./text\"\n\nextern def strlen(text: CPointer) -> Int from \"c\"\n\nvar length: Int = text.strlen(\"tea\")\n";
+    let main_path = dir.path().join("main.tea");
+    fs::write(&main_path, main_source)?;
+
+    let source_file = SourceFile::new(SourceId(0), main_path, main_source.to_string());
+    let mut compiler = Compiler::new(CompileOptions::default());
+    assert!(compiler.compile(&source_file).is_err());
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|diagnostic| diagnostic.message.clone())
+        .collect();
+    assert_eq!(messages.len(), 1, "{messages:?}");
+    assert!(
+        messages[0].contains("binds C symbol 'strlen' as (CPointer) -> Int, but"),
+        "{messages:?}"
+    );
+
+    Ok(())
+}
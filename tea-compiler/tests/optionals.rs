@@ -146,3 +146,191 @@ print(maybe_name!)
         messages
     );
 }
+
+#[test]
+fn optional_binding_and_guard_narrow_to_inner_type() {
+    let source = r#"
+struct Config {
+  name: String
+}
+
+def load(flag: Bool) -> Config?
+  if flag
+    return Config(name: "tea")
+  end
+  return nil
+end
+
+def shout(text: String) -> String
+  return text
+end
+
+def describe(flag: Bool) -> String
+  guard var cfg = load(flag) else
+    return "missing"
+  end
+  return shout(cfg.name)
+end
+
+def doubled(value: Int?) -> Int
+  guard value != nil else
+    return 0
+  end
+  return value! * 2
+end
+
+if var cfg = load(true)
+  print(shout(cfg.name))
+end
+"#;
+
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("optional_binding.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    if result.is_err() {
+        let messages: Vec<_> = compiler
+            .diagnostics()
+            .entries()
+            .iter()
+            .map(|entry| entry.message.clone())
+            .collect();
+        panic!(
+            "expected program to compile without errors; diagnostics: {:?}",
+            messages
+        );
+    }
+}
+
+#[test]
+fn rejects_guard_else_block_that_falls_through() {
+    let source = r#"
+def doubled(value: Int?) -> Int
+  guard value != nil else
+    print("missing")
+  end
+  return value! * 2
+end
+"#;
+
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("guard_fallthrough.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(result.is_err(), "expected guard without exit to fail");
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|entry| entry.message.as_str())
+        .collect();
+    assert!(
+        messages.iter().any(|message| message
+            .contains("guard else block must end with return, throw, break, or continue")),
+        "missing guard diagnostic: {:?}",
+        messages
+    );
+}
+
+#[test]
+fn guard_else_block_may_end_in_if_whose_branches_all_exit() {
+    let source = r#"
+def pick(value: Int?, strict: Bool) -> Int
+  guard value != nil else
+    if strict
+      return -1
+    else if value == nil
+      return 0
+    else
+      return 1
+    end
+  end
+  return value!
+end
+"#;
+
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("guard_if_else.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(
+        result.is_ok(),
+        "expected guard ending in a diverging if/else to compile; diagnostics: {:?}",
+        compiler.diagnostics().entries()
+    );
+}
+
+#[test]
+fn rejects_guard_else_block_ending_in_if_without_else() {
+    let source = r#"
+def pick(value: Int?, strict: Bool) -> Int
+  guard value != nil else
+    if strict
+      return -1
+    end
+  end
+  return value!
+end
+"#;
+
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("guard_if_only.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(
+        result.is_err(),
+        "expected guard that can fall through to fail"
+    );
+    assert!(
+        compiler.diagnostics().entries().iter().any(|entry| entry
+            .message
+            .contains("guard else block must end with return, throw, break, or continue")),
+        "missing guard diagnostic: {:?}",
+        compiler.diagnostics().entries()
+    );
+}
+
+#[test]
+fn rejects_optional_binding_of_non_optional_value() {
+    let source = r#"
+if var count = 3
+  print(count)
+end
+"#;
+
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("binding_non_optional.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(
+        result.is_err(),
+        "expected binding a non-optional value to fail"
+    );
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|entry| entry.message.as_str())
+        .collect();
+    assert!(
+        messages.iter().any(|message| message
+            .contains("optional binding 'count' requires an optional value, found Int")),
+        "missing binding diagnostic: {:?}",
+        messages
+    );
+}
@@ -9,7 +9,7 @@ use intrinsics from "std.intrinsics"
 
 def verify() -> Void
   var spec = intrinsics.json_decode("{\"name\":\"todo\",\"description\":\"Task manager\",\"options\":[{\"name\":\"verbose\",\"aliases\":[\"-v\",\"--verbose\"],\"kind\":\"flag\"},{\"name\":\"count\",\"aliases\":[\"-c\",\"--count\"],\"kind\":\"option\",\"type\":\"int\"},{\"name\":\"tag\",\"aliases\":[\"-t\",\"--tag\"],\"kind\":\"option\",\"type\":\"string\",\"multiple\":true}],\"positionals\":[{\"name\":\"file\",\"type\":\"string\"}]}")
-  var parsed = args.parse_with(spec, ["-v", "-c", "3", "--tag", "work", "--tag", "urgent", "tasks.txt"])
+  var parsed = args.parse(spec, ["-v", "-c", "3", "--tag", "work", "--tag", "urgent", "tasks.txt"])
   assert.ok(parsed.ok)
   assert.eq(parsed.options["verbose"], true)
   assert.eq(parsed.options["count"], 3)
@@ -36,7 +36,7 @@ use intrinsics from "std.intrinsics"
 
 def verify() -> Void
   var spec = intrinsics.json_decode("{\"name\":\"todo\",\"subcommands\":[{\"name\":\"done\",\"positionals\":[{\"name\":\"id\",\"type\":\"string\"}]}]}")
-  var parsed = args.parse_with(spec, ["done", "task-7"])
+  var parsed = args.parse(spec, ["done", "task-7"])
   assert.ok(parsed.ok)
   assert.eq(parsed.command, "done")
   assert.eq(@len(parsed.path), 2)
@@ -61,7 +61,7 @@ use intrinsics from "std.intrinsics"
 
 def usage_message() -> String
   var spec = intrinsics.json_decode("{\"name\":\"todo\",\"options\":[{\"name\":\"verbose\",\"aliases\":[\"-v\",\"--verbose\"],\"kind\":\"flag\"},{\"name\":\"count\",\"aliases\":[\"-c\",\"--count\"],\"kind\":\"option\",\"type\":\"int\"}],\"positionals\":[{\"name\":\"file\",\"type\":\"string\"}]}")
-  var parsed = args.parse_with(spec, [])
+  var parsed = args.parse(spec, [])
   try args.require(parsed) catch err
   case is args.ArgsError.Usage
     return err.message
@@ -74,7 +74,7 @@ end
 
 def missing_option_name() -> String
   var spec = intrinsics.json_decode("{\"name\":\"todo\",\"options\":[{\"name\":\"count\",\"aliases\":[\"-c\",\"--count\"],\"kind\":\"option\",\"type\":\"int\"}]}")
-  var parsed = args.parse_with(spec, [])
+  var parsed = args.parse(spec, [])
   try args.require_option_int(parsed, "count") catch err
   case is args.ArgsError.MissingOption
     return err.name
@@ -86,7 +86,7 @@ def missing_option_name() -> String
 end
 
 var spec = intrinsics.json_decode("{\"name\":\"todo\",\"options\":[{\"name\":\"verbose\",\"aliases\":[\"-v\",\"--verbose\"],\"kind\":\"flag\"},{\"name\":\"count\",\"aliases\":[\"-c\",\"--count\"],\"kind\":\"option\",\"type\":\"int\"}],\"positionals\":[{\"name\":\"file\",\"type\":\"string\"}]}")
-var parsed = args.parse_with(spec, ["-v", "-c", "3", "tasks.txt"])
+var parsed = args.parse(spec, ["-v", "-c", "3", "tasks.txt"])
 assert.ok(args.flag(parsed, "verbose"))
 assert.eq(args.option_int_or(parsed, "count", 0), 3)
 assert.eq(args.require_option_int(parsed, "count"), 3)
@@ -95,7 +95,7 @@ assert.eq(missing_option_name(), "count")
 assert.ok(@len(usage_message()) > 0)
 
 var sub_spec = intrinsics.json_decode("{\"name\":\"todo\",\"subcommands\":[{\"name\":\"done\",\"positionals\":[{\"name\":\"id\",\"type\":\"string\"}]}]}")
-var sub = args.parse_with(sub_spec, ["done", "task-7"])
+var sub = args.parse(sub_spec, ["done", "task-7"])
 assert.eq(args.require_subcommand(sub), "done")
 assert.ok(args.command_is(sub, "done"))
 @println("ok")
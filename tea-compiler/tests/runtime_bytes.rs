@@ -0,0 +1,64 @@
+mod support;
+
+#[test]
+fn bytes_support_indexing_slicing_and_equality() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use bytes from "std.bytes"
+
+const data = bytes.from_list([0, 1, 2, 254, 255])
+assert.eq(@len(data), 5)
+assert.eq(data[0], 0)
+assert.eq(data[4], 255)
+
+const middle = data[1..3]
+assert.eq(@len(middle), 2)
+assert.eq(middle[0], 1)
+assert.eq(middle[1], 2)
+assert.eq(@len(data[1...3]), 3)
+
+assert.ok(middle == bytes.from_list([1, 2]))
+assert.ok(middle != bytes.from_list([2, 1]))
+const as_list = bytes.to_list(middle)
+assert.eq(@len(as_list), 2)
+assert.eq(as_list[1], 2)
+
+const joined = bytes.concat(middle, bytes.from_list([3]))
+assert.eq(joined[2], 3)
+assert.eq(@type_of(joined), "Bytes")
+@println(joined)
+"#;
+
+    let stdout = support::build_and_run(source, "bytes-basics.tea", &[])?;
+    assert_eq!(stdout, "Bytes(010203)\n");
+    Ok(())
+}
+
+#[test]
+fn bytes_convert_between_strings_and_encodings() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use bytes from "std.bytes"
+
+const text = bytes.from_string("héllo")
+assert.eq(@len(text), 6)
+assert.ok(bytes.is_utf8(text))
+assert.eq(bytes.to_string(text), "héllo")
+
+const invalid = bytes.from_list([104, 255, 105])
+assert.ok(! bytes.is_utf8(invalid))
+const replaced = bytes.from_string(bytes.to_string_lossy(invalid))
+assert.ok(replaced == bytes.from_list([104, 239, 191, 189, 105]))
+
+assert.eq(bytes.to_hex(bytes.from_list([0, 15, 171])), "000fab")
+assert.ok(bytes.from_hex("000FAB") == bytes.from_list([0, 15, 171]))
+
+assert.eq(bytes.to_base64(bytes.from_string("tea")), "dGVh")
+assert.eq(bytes.to_string(bytes.from_base64("dGVh")), "tea")
+@println("ok")
+"#;
+
+    let stdout = support::build_and_run(source, "bytes-encoding.tea", &[])?;
+    assert_eq!(stdout, "ok\n");
+    Ok(())
+}
@@ -0,0 +1,96 @@
+mod support;
+
+#[test]
+fn encoding_round_trips_base64_and_hex() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use bytes from "std.bytes"
+use encoding from "std.encoding"
+
+const data = bytes.from_list([251, 255, 190])
+assert.eq(encoding.base64_encode(data), "+/++")
+assert.eq(encoding.base64_url_encode(data), "-_--")
+assert.ok(encoding.base64_url_decode("-_--") == data)
+
+assert.eq(encoding.base64_url_encode(bytes.from_string("tea?")), "dGVhPw")
+assert.eq(bytes.to_string(encoding.base64_url_decode("dGVhPw")), "tea?")
+assert.eq(bytes.to_string(encoding.base64_decode("dGVhPw==")), "tea?")
+
+assert.eq(encoding.hex_encode(data), "fbffbe")
+assert.ok(encoding.hex_decode("FBFFBE") == data)
+@println("ok")
+"#;
+
+    let stdout = support::build_and_run(source, "encoding.tea", &[])?;
+    assert_eq!(stdout, "ok\n");
+    Ok(())
+}
+
+#[test]
+fn encoding_try_decoders_throw_on_malformed_input() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use bytes from "std.bytes"
+use encoding from "std.encoding"
+
+def describe(text: String) -> String
+  var data = encoding.try_hex_decode(text) catch err
+    case is encoding.EncodingError.Invalid
+      return err.message
+  end
+  encoding.hex_encode(data)
+end
+
+def describe_base64(text: String) -> String
+  var data = encoding.try_base64_url_decode(text) catch err
+    case is encoding.EncodingError.Invalid
+      return err.message
+  end
+  bytes.to_string(data)
+end
+
+@println(describe("FBFFBE"))
+@println(describe("abc"))
+@println(describe("zz"))
+@println(describe_base64("dGVhPw"))
+@println(describe_base64("d*"))
+assert.ok(encoding.try_base64_decode("dGVhPw==") == bytes.from_string("tea?"))
+"#;
+
+    let stdout = support::build_and_run(source, "encoding-try.tea", &[])?;
+    assert_eq!(
+        stdout,
+        "fbffbe\n\
+         encoding.try_hex_decode: hex input must have an even number of digits\n\
+         encoding.try_hex_decode: invalid hex digit at offset 0\n\
+         tea?\n\
+         encoding.try_base64_url_decode: Invalid symbol 42, offset 1.\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn crypto_hashes_strings_and_bytes() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use bytes from "std.bytes"
+use crypto from "std.crypto"
+
+assert.eq(crypto.sha256("abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+assert.eq(crypto.sha256_bytes(bytes.from_string("abc")), crypto.sha256("abc"))
+assert.eq(crypto.sha1("abc"), "a9993e364706816aba3e25717850c26c9cd0d89d")
+assert.eq(crypto.md5("abc"), "900150983cd24fb0d6963f7d28e17f72")
+assert.eq(crypto.blake3("abc"), "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85")
+
+const tag = crypto.hmac_sha256("key", "The quick brown fox jumps over the lazy dog")
+assert.eq(tag, "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8")
+assert.ok(crypto.constant_time_eq(tag, "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"))
+assert.ok(! crypto.constant_time_eq(tag, "f7bc"))
+assert.ok(! crypto.constant_time_eq("abc", "abd"))
+@println("ok")
+"#;
+
+    let stdout = support::build_and_run(source, "crypto.tea", &[])?;
+    assert_eq!(stdout, "ok\n");
+    Ok(())
+}
@@ -0,0 +1,85 @@
+mod support;
+
+#[test]
+fn yaml_and_toml_round_trip_documents() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use toml from "std.toml"
+use yaml from "std.yaml"
+
+const config = yaml.decode[Dict[String, List[Int]]]("ports: [80, 443]\nworkers: [4]\n")
+assert.eq(config["ports"][1], 443)
+assert.eq(yaml.encode({"name": "tea", "tags": ["fast"]}), "name: tea\ntags:\n- fast\n")
+
+const manifest = toml.decode[Dict[String, Dict[String, String]]]("[owner]\nname = \"ada\"\nsince = 1979-05-27T07:32:00Z\n")
+assert.eq(manifest["owner"]["name"], "ada")
+assert.eq(manifest["owner"]["since"], "1979-05-27T07:32:00Z")
+assert.eq(toml.encode({"title": "tea", "owner": {"name": "ada"}}), "title = \"tea\"\n\n[owner]\nname = \"ada\"\n")
+@println("ok")
+"#;
+
+    let stdout = support::build_and_run(source, "data_formats.tea", &[])?;
+    assert_eq!(stdout, "ok\n");
+    Ok(())
+}
+
+#[test]
+fn csv_reads_quoted_fields_and_writes_them_back() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use csv from "std.csv"
+
+const rows = csv.parse_rows("a,b\n1,\"x, \"\"y\"\"\"\n\n2,\"multi\nline\"\n")
+assert.eq(@len(rows), 3)
+assert.eq(rows[1][1], "x, \"y\"")
+assert.eq(rows[2][1], "multi\nline")
+
+const records = csv.parse_records("name;age\r\nada;36\r\n", ";")
+assert.eq(records[0]["name"], "ada")
+assert.eq(records[0]["age"], "36")
+
+assert.eq(csv.format_row(["plain", "a,b", "say \"hi\""]), "plain,\"a,b\",\"say \"\"hi\"\"\"")
+assert.eq(csv.encode_records(["name", "age"], records, "\t"), "name\tage\nada\t36\n")
+@println("ok")
+"#;
+
+    let stdout = support::build_and_run(source, "csv.tea", &[])?;
+    assert_eq!(stdout, "ok\n");
+    Ok(())
+}
+
+#[test]
+fn csv_decodes_records_into_structs() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use csv from "std.csv"
+use string from "std.string"
+
+struct Order {
+  id: String
+  total: Int
+  paid: Bool
+  discount: Float?
+}
+
+const text = "order id,total,paid,discount\nA1,30,true,0.5\nB2,12,false,\n"
+const orders = csv.decode[Order](text, rename: {"id": "order id"})
+assert.eq(@len(orders), 2)
+assert.eq(orders[1].id, "B2")
+assert.eq(orders[0].total + orders[1].total, 42)
+assert.eq(orders[0].paid, true)
+assert.eq(orders[0].discount, 0.5)
+assert.eq(orders[1].discount, nil)
+
+const labels = csv.decode_with(text, |record: Dict[String, String]| => string.to_lower(record["order id"]) + "/" + record["total"])
+assert.eq(labels, ["a1/30", "b2/12"])
+
+const quoted = csv.parse_rows("name,notes\nada,\"line one\n\nline \"\"three\"\"\"\n")
+assert.eq(quoted[1][1], "line one\n\nline \"three\"")
+@println("ok")
+"#;
+
+    let stdout = support::build_and_run(source, "csv_decode.tea", &[])?;
+    assert_eq!(stdout, "ok\n");
+    Ok(())
+}
@@ -0,0 +1,42 @@
+mod support;
+
+#[test]
+fn extern_functions_call_libc() -> anyhow::Result<()> {
+    let source = r#"
+use bytes from "std.bytes"
+
+extern def strlen(text: CString) -> Int from "c"
+extern def strchr(text: CString, character: Int) -> CString from "c"
+# `abs` takes a C int; `labs` is its long (int64_t) counterpart.
+extern def labs(value: Int) -> Int from "c"
+extern def isatty(fd: Int) -> Bool from "c"
+extern def malloc(size: Int) -> CPointer from "c"
+extern def memset(block: CPointer, value: Int, size: Int) -> CPointer from "c"
+extern def free(block: CPointer) from "c"
+
+def measure(text: String) -> String
+  var length = strlen(text) catch err
+    case is ExternError.NulByte
+      return err.message
+  end
+  `${length}`
+end
+
+@println(strlen("héllo"))
+@println(strchr("tea-time", 45))
+@println(labs(-42))
+@println(isatty(0))
+var block = malloc(16)
+@println(memset(block, 0, 16) == block)
+free(block)
+@println(measure("tea"))
+@println(measure(bytes.to_string(bytes.from_list([116, 0, 97]))))
+"#;
+
+    let stdout = support::build_and_run(source, "extern-libc.tea", &[])?;
+    assert_eq!(
+        stdout,
+        "6\n-time\n42\nfalse\ntrue\n3\nargument 'text' of extern function 'strlen' contains a NUL byte\n"
+    );
+    Ok(())
+}
@@ -118,11 +118,12 @@ fn fs_bytes_roundtrip_through_runtime() -> anyhow::Result<()> {
     let source = format!(
         r#"
 use assert from "std.assert"
+use bytes from "std.bytes"
 use fs from "std.fs"
 
 fs.mkdir_p("{dir}")
-fs.write_bytes("{file}", [0, 1, 2, 255])
-fs.write_bytes_atomic("{atomic}", [5, 4, 3, 2, 1])
+fs.write_bytes("{file}", bytes.from_list([0, 1, 2, 255]))
+fs.write_bytes_atomic("{atomic}", bytes.from_list([5, 4, 3, 2, 1]))
 
 var first = fs.read_bytes("{file}")
 var second = fs.read_bytes("{atomic}")
@@ -160,6 +161,7 @@ fn fs_hardening_helpers_execute_through_runtime() -> anyhow::Result<()> {
     let source = format!(
         r#"
 use assert from "std.assert"
+use bytes from "std.bytes"
 use fs from "std.fs"
 use path from "std.path"
 
@@ -168,7 +170,7 @@ assert.ok(fs.exists(path.join(["{dir}", "nested"])))
 
 fs.write_file_atomic("{nested}", "tea")
 fs.append_file("{nested}", "-lang")
-fs.append_bytes("{nested}", [10, 33])
+fs.append_bytes("{nested}", bytes.from_list([10, 33]))
 
 var contents = fs.read_file("{nested}")
 assert.eq(contents, "tea-lang\n!")
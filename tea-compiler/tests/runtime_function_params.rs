@@ -0,0 +1,47 @@
+mod support;
+
+#[test]
+fn default_named_and_variadic_parameters() -> anyhow::Result<()> {
+    let source = r#"
+use string from "std.string"
+
+def greet(name: String, greeting: String = "Hello", punctuation: String = "!") -> String
+  `${greeting}, ${name}${punctuation}`
+end
+
+def join_all(separator: String, ...parts: String) -> String
+  string.join(parts, separator)
+end
+
+def scaled(value: Int, factor: Int = value) -> Int
+  value * factor
+end
+
+@println(greet("Tea"))
+@println(greet("Tea", "Hi"))
+@println(greet("Tea", punctuation: "?"))
+@println(greet(greeting: "Hey", name: "Tea"))
+@println(join_all("-"))
+@println(join_all("-", "a"))
+@println(join_all("-", "a", "b", "c"))
+@println(scaled(3))
+@println(scaled(3, factor: 2))
+@println(string.pad_start("7", 3, "0"))
+"#;
+
+    let stdout = support::build_and_run(source, "function_params.tea", &[])?;
+    assert_eq!(
+        stdout,
+        "Hello, Tea!\n\
+         Hi, Tea!\n\
+         Hello, Tea?\n\
+         Hey, Tea!\n\
+         \n\
+         a\n\
+         a-b-c\n\
+         9\n\
+         6\n\
+         007\n"
+    );
+    Ok(())
+}
@@ -210,3 +210,97 @@ fs.remove("{download_path}")
     server.join().expect("http server thread should join")?;
     Ok(())
 }
+
+#[test]
+fn http_server_routes_requests_and_serves_static_files() -> Result<()> {
+    let temp = tempdir()?;
+    let root = temp.path().join("public");
+    std::fs::create_dir(&root)?;
+    std::fs::write(root.join("index.html"), "<h1>home</h1>")?;
+    let root_str = root.to_string_lossy().replace('\\', "\\\\");
+
+    let source = format!(
+        r#"
+use assert from "std.assert"
+use http from "std.http"
+
+def echo(req: http.Request) -> http.Response
+  http.respond_json({{"method": req.method, "body": req.body, "q": http.request_query(req)["q"]}}, status: 201)
+end
+
+def routes() -> List[http.Route]
+  [
+    http.route("GET", "/hello/:name", |req: http.Request| => http.respond(200, `hello ${{req.params["name"]}}`)),
+    http.route("POST", "/echo", echo)
+  ]
+end
+
+const server = http.start_test_server(http.router(routes()))
+const base = http.server_url(server)
+
+const hello = http.get(base + "/hello/tea%20pot")
+assert.eq(hello.status, 200)
+assert.eq(hello.body, "hello tea pot")
+
+const echoed = http.post(base + "/echo?q=green+tea", "payload")
+assert.eq(echoed.status, 201)
+assert.eq(http.header_or(echoed, "content-type", ""), "application/json")
+const echoed_body = http.decode_json[Dict[String, String]](echoed)
+assert.eq(echoed_body["q"], "green tea")
+assert.eq(echoed_body["body"], "payload")
+
+assert.eq(http.get(base + "/missing").status, 404)
+const wrong = http.delete(base + "/echo")
+assert.eq(wrong.status, 405)
+assert.eq(http.header_or(wrong, "allow", ""), "POST")
+http.shutdown(server)
+
+const files = http.start_test_server(http.static_files("{root}", prefix: "/static"))
+const page = http.get(http.server_url(files) + "/static/")
+assert.eq(page.body, "<h1>home</h1>")
+assert.eq(http.header_or(page, "content-type", ""), "text/html; charset=utf-8")
+assert.eq(http.get(http.server_url(files) + "/static/%2E%2E/secret").status, 404)
+http.shutdown(files)
+@println("ok")
+"#,
+        root = root_str,
+    );
+
+    let stdout = support::build_and_run(&source, "http_server.tea", &[])?;
+    assert_eq!(stdout, "ok\n");
+    Ok(())
+}
+
+#[test]
+fn http_server_rejects_oversized_bodies_with_413() -> Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use http from "std.http"
+use net from "std.net"
+use string from "std.string"
+
+def send_raw(server: http.Server, request: String) -> String
+  const conn = net.connect(server.addr)
+  net.write(conn, request)
+  const reply = net.read(conn)
+  net.close(conn)
+  reply
+end
+
+const server = http.start_test_server(|req: http.Request| => http.respond(200, req.body))
+
+const declared = send_raw(server, "POST / HTTP/1.1\r\ncontent-length: 999999999999\r\n\r\n")
+assert.ok(string.starts_with(declared, "HTTP/1.1 413"))
+
+const chunked = send_raw(server, "POST / HTTP/1.1\r\ntransfer-encoding: chunked\r\n\r\nFFFFFFFF\r\nabc")
+assert.ok(string.starts_with(chunked, "HTTP/1.1 413"))
+
+assert.eq(http.post(http.server_url(server), "small").body, "small")
+http.shutdown(server)
+@println("ok")
+"#;
+
+    let stdout = support::build_and_run(source, "http_body_limit.tea", &[])?;
+    assert_eq!(stdout, "ok\n");
+    Ok(())
+}
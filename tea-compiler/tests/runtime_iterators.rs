@@ -0,0 +1,157 @@
+use std::fs;
+
+use tempfile::tempdir;
+
+mod support;
+
+#[test]
+fn generators_resume_lazily() -> anyhow::Result<()> {
+    let source = r#"
+use iter from "std.iter"
+
+def countdown(start: Int) -> Iterator[Int]
+  @println("start")
+  var current = start
+  while current > 0
+    yield current
+    current = current - 1
+  end
+  @println("done")
+end
+
+const numbers = countdown(3)
+@println("created")
+for value in numbers
+  @println(value)
+end
+@println(iter.next(countdown(0)) ?? -1)
+"#;
+
+    let stdout = support::build_and_run(source, "iter-generators.tea", &[])?;
+    assert_eq!(stdout, "created\nstart\n3\n2\n1\ndone\nstart\ndone\n-1\n");
+    Ok(())
+}
+
+#[test]
+fn adapters_only_pull_what_they_need() -> anyhow::Result<()> {
+    let source = r#"
+use iter from "std.iter"
+
+def naturals() -> Iterator[Int]
+  var current = 1
+  while true
+    yield current
+    current = current + 1
+  end
+end
+
+const evens = iter.filter(naturals(), |n: Int| => n % 2 == 0)
+const labels = iter.map(evens, |n: Int| => `#${n}`)
+@println(iter.collect(iter.take(labels, 3)))
+
+const small = iter.take_while(naturals(), |n: Int| => n < 4)
+@println(iter.collect(small))
+
+for entry in iter.enumerate(iter.chain(iter.from_list(["a", "b"]), iter.from_list(["c"])))
+  @println(`${entry.index}:${entry.value}`)
+end
+
+for pair in iter.zip(naturals(), iter.from_list(["x", "y"]))
+  @println(`${pair.first}${pair.second}`)
+end
+"#;
+
+    let stdout = support::build_and_run(source, "iter-adapters.tea", &[])?;
+    assert_eq!(stdout, "[#2, #4, #6]\n[1, 2, 3]\n0:a\n1:b\n2:c\n1x\n2y\n");
+    Ok(())
+}
+
+#[test]
+fn abandoned_generators_are_closed() -> anyhow::Result<()> {
+    let tmp = tempdir()?;
+    let path = tmp.path().join("lines.txt");
+    fs::write(&path, "one\ntwo\nthree\n")?;
+
+    let source = format!(
+        r#"
+use fs from "std.fs"
+use iter from "std.iter"
+
+def noisy() -> Iterator[Int]
+  yield 1
+  @println("resumed")
+  yield 2
+end
+
+def first_line(file_path: String) -> String
+  for line in fs.stream_lines(file_path)
+    return line
+  end
+  ""
+end
+
+for value in noisy()
+  @println(value)
+  break
+end
+
+const numbers = iter.from_list([1, 2, 3])
+for value in numbers
+  break
+end
+@println(iter.next(numbers) ?? -1)
+
+# Each abandoned stream would otherwise keep its thread and file open.
+var round = 0
+while round < 2000
+  for line in fs.stream_lines("{path}")
+    break
+  end
+  const taken = iter.collect(iter.take(fs.stream_lines("{path}"), 1))
+  if first_line("{path}") != taken[0]
+    @println("mismatch")
+  end
+  round = round + 1
+end
+@println(first_line("{path}"))
+"#,
+        path = path.to_string_lossy().replace('\\', "\\\\")
+    );
+
+    let stdout = support::build_and_run(&source, "iter-close.tea", &[])?;
+    assert_eq!(stdout, "1\n2\none\n");
+    Ok(())
+}
+
+#[test]
+fn fs_streams_lines_and_entries() -> anyhow::Result<()> {
+    let tmp = tempdir()?;
+    let root = tmp.path();
+    fs::write(root.join("notes.txt"), "first\r\nsecond\n\nlast")?;
+    fs::create_dir(root.join("nested"))?;
+    fs::write(root.join("nested").join("inner.txt"), "")?;
+
+    let root_str = root.to_string_lossy();
+    let source = format!(
+        r#"
+use fs from "std.fs"
+use string from "std.string"
+
+for line in fs.stream_lines("{root}/notes.txt")
+  @println(`[${{line}}]`)
+end
+
+for entry in fs.stream_walk("{root}")
+  @println(string.replace(entry, "{root}", "."))
+end
+"#,
+        root = root_str
+    );
+
+    let stdout = support::build_and_run(&source, "iter-fs.tea", &[])?;
+    assert_eq!(
+        stdout,
+        "[first]\n[second]\n[]\n[last]\n./nested\n./nested/inner.txt\n./notes.txt\n"
+    );
+    Ok(())
+}
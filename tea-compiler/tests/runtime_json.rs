@@ -0,0 +1,119 @@
+mod support;
+
+#[test]
+fn json_decodes_into_structs_and_encodes_with_options() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use json from "std.json"
+
+struct Port {
+  number: Int
+  name: String?
+}
+
+struct Server {
+  host: String
+  ports: List[Port]
+  weight: Float
+  labels: Dict[String, String]
+}
+
+const text = "[{\"hostname\": \"edge\", \"ports\": [{\"number\": 80, \"name\": \"http\"}, {\"number\": 443}], \"weight\": 2, \"labels\": {\"zone\": \"a\"}, \"extra\": true}]"
+const servers = json.decode[List[Server]](text, rename: {"host": "hostname"})
+assert.eq(servers[0].host, "edge")
+assert.eq(servers[0].ports[1].number, 443)
+assert.eq(servers[0].ports[1].name, nil)
+assert.eq(servers[0].weight, 2.0)
+assert.eq(servers[0].labels["zone"], "a")
+
+assert.eq(json.encode(servers[0].ports[1], omit_nil: true), "{\"number\":443}")
+assert.eq(json.encode(servers[0].ports[0], rename: {"number": "port"}), "{\"name\":\"http\",\"port\":80}")
+@println("ok")
+"#;
+
+    let stdout = support::build_and_run(source, "json_typed.tea", &[])?;
+    assert_eq!(stdout, "ok\n");
+    Ok(())
+}
+
+#[test]
+fn json_try_decode_reports_the_path_of_a_mismatch() -> anyhow::Result<()> {
+    let source = r#"
+use json from "std.json"
+
+struct Server {
+  host: String
+  port: Int
+}
+
+struct Config {
+  servers: List[Server]
+}
+
+def report(text: String) -> Void
+  const config = try json.try_decode[Config](text) catch err
+    case is json.JsonError.Mismatch
+      @println(`${err.path}: ${err.message}`)
+      Config(servers: [])
+    case is json.JsonError.Syntax
+      @println("syntax error")
+      Config(servers: [])
+  end
+  @println(@len(config.servers))
+end
+
+report("{\"servers\": [{\"host\": \"a\", \"port\": 1}, {\"host\": \"b\", \"port\": \"2\"}]}")
+report("{\"servers\": [{\"port\": 1}]}")
+report("{\"servers\": [")
+report("{\"servers\": [{\"host\": \"a\", \"port\": 1}]}")
+"#;
+
+    let stdout = support::build_and_run(source, "json_try_decode.tea", &[])?;
+    assert_eq!(
+        stdout,
+        "$.servers[1].port: expected Int, found String\n0\n$.servers[0]: missing field 'host'\n0\nsyntax error\n0\n1\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn json_decodes_enums_by_name_and_untyped_documents() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use json from "std.json"
+
+enum Level {
+  Debug
+  Info
+  Warn
+}
+
+struct Rule {
+  name: String
+  level: Level
+}
+
+const rules = json.decode[List[Rule]]("[{\"name\": \"db\", \"level\": \"Warn\"}]")
+assert.eq(rules[0].level == Level.Warn, true)
+assert.eq(json.encode(rules[0]), "{\"level\":\"Warn\",\"name\":\"db\"}")
+
+const rule = try json.try_decode[Rule]("{\"name\": \"db\", \"level\": \"Loud\"}") catch err
+  case is json.JsonError.Mismatch
+    @println(`${err.path}: ${err.message}`)
+    Rule(name: "", level: Level.Info)
+  case _
+    Rule(name: "", level: Level.Info)
+end
+@println(rule.level)
+
+const data = json.decode("{\"name\": \"tea\", \"tags\": [\"a\", \"b\"]}")
+@println(json.encode(data))
+"#;
+
+    let stdout = support::build_and_run(source, "json_enums.tea", &[])?;
+    assert_eq!(
+        stdout,
+        "$.level: expected one of Debug, Info, Warn for Level, found \"Loud\"\nInfo\n{\"name\":\"tea\",\"tags\":[\"a\",\"b\"]}\n"
+    );
+    Ok(())
+}
@@ -413,3 +413,27 @@ assert.eq(total, 36)
     assert_eq!(stdout, "ok\n");
     Ok(())
 }
+
+/// Programs under `tests/conformance` must print the same output in native builds as in
+/// the browser runner (see `tea-eval/tests/browser_eval.rs`).
+#[test]
+fn native_builds_match_conformance_programs() -> anyhow::Result<()> {
+    let directory = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/conformance");
+    let mut programs = std::fs::read_dir(&directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    programs.retain(|path| path.extension().is_some_and(|extension| extension == "tea"));
+    programs.sort();
+
+    for program in programs {
+        let source = std::fs::read_to_string(&program)?;
+        let expected = std::fs::read_to_string(program.with_extension("stdout"))?;
+        let file_name = program
+            .file_name()
+            .and_then(|name| name.to_str())
+            .expect("conformance file name");
+        let stdout = support::build_and_run(&source, file_name, &[])?;
+        assert_eq!(stdout, expected, "{}", program.display());
+    }
+    Ok(())
+}
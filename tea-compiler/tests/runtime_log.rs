@@ -0,0 +1,87 @@
+mod support;
+
+#[test]
+fn log_formats_records_as_text_and_json() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use log from "std.log"
+
+const logger = log.with_fields(log.Logger(), {"service": "api"})
+assert.eq(log.format_record(logger, "warn", "slow request", {"path": "/a b"}, "T"), "T WARN  slow request path=\"/a b\" service=api")
+
+const json_logger = log.with_format(logger, "json")
+assert.eq(log.format_record(json_logger, "info", "ready", {}, "T"), "{\"level\":\"info\",\"message\":\"ready\",\"service\":\"api\",\"time\":\"T\"}")
+assert.eq(log.format_record(json_logger, "info", "ready", {"level": "high"}, "T"), "{\"fields.level\":\"high\",\"level\":\"info\",\"message\":\"ready\",\"service\":\"api\",\"time\":\"T\"}")
+
+assert.ok(log.enabled(logger, "error"))
+assert.ok(! log.enabled(logger, "debug"))
+assert.ok(! log.enabled(log.with_level(logger, "off"), "error"))
+@println("ok")
+"#;
+
+    let stdout = support::build_and_run(source, "log_format.tea", &[])?;
+    assert_eq!(stdout, "ok\n");
+    Ok(())
+}
+
+#[test]
+fn log_filters_by_tea_log_and_rotates_files() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use env from "std.env"
+use fs from "std.fs"
+use log from "std.log"
+use path from "std.path"
+use string from "std.string"
+
+env.set("TEA_LOG", "warn")
+const dir = fs.create_temp_dir("tea-log")
+const file_path = path.join([dir, "app.log"])
+const logger = log.to_file(log.from_env(), file_path, max_bytes: 120, max_files: 2)
+
+log.write(logger, "info", "dropped")
+assert.ok(! fs.exists(file_path))
+
+var index = 0
+while index < 6
+  log.write(logger, "warn", "disk almost full", {"attempt": `${index}`})
+  index = index + 1
+end
+
+assert.ok(fs.exists(`${file_path}.1`))
+assert.ok(fs.exists(`${file_path}.2`))
+assert.ok(! fs.exists(`${file_path}.3`))
+for file in [file_path, `${file_path}.1`, `${file_path}.2`]
+  assert.ok(fs.metadata(file).size <= 120)
+end
+assert.ok(string.contains(fs.read_file(file_path), "attempt=5"))
+@println("ok")
+"#;
+
+    let stdout = support::build_and_run(source, "log_rotate.tea", &[])?;
+    assert_eq!(stdout, "ok\n");
+    Ok(())
+}
+
+#[test]
+fn log_falls_back_to_defaults_for_unknown_env_values() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use env from "std.env"
+use log from "std.log"
+
+env.set("TEA_LOG", "loud")
+env.set("TEA_LOG_FORMAT", "xml")
+const logger = log.from_env()
+assert.eq(logger.level, "info")
+assert.eq(logger.format, "human")
+
+log.info("still logged")
+log.debug("still dropped")
+@println("ok")
+"#;
+
+    let stdout = support::build_and_run(source, "log_env_fallback.tea", &[])?;
+    assert_eq!(stdout, "ok\n");
+    Ok(())
+}
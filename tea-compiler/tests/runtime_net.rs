@@ -0,0 +1,150 @@
+mod support;
+
+#[test]
+fn tcp_connections_exchange_lines_and_time_out() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use net from "std.net"
+use string from "std.string"
+use task from "std.task"
+
+def echo_upper(conn: net.Conn) -> Int ! net.NetError
+  var count = 0
+  while var line = net.read_line(conn, timeout_ms: 5000)
+    net.write(conn, string.to_upper(line) + "\n")
+    count = count + 1
+  end
+  net.close(conn)
+  count
+end
+
+def serve_one(listener: net.Listener) -> Int
+  const served = try echo_upper(net.accept(listener, timeout_ms: 5000)) catch err
+    case _
+      -1
+  end
+  served
+end
+
+def run() -> Void ! net.NetError
+  const listener = net.listen("127.0.0.1:0")
+  const address = net.listener_address(listener)
+  const server = task.spawn(|| => serve_one(listener))
+
+  const conn = net.connect(address, timeout_ms: 1000)
+  assert.eq(net.peer_address(conn), address)
+  net.write(conn, "hello\r\nworld\n")
+  assert.eq(net.read_line(conn) ?? "", "HELLO")
+  assert.eq(net.read_line(conn) ?? "", "WORLD")
+  net.shutdown_write(conn)
+  assert.eq(net.read_line(conn, timeout_ms: 1000), nil)
+  net.close(conn)
+  assert.eq(task.join(server), 2)
+
+  const timed_out = try net.accept(listener, timeout_ms: 50) catch err
+    case is net.NetError.Timeout
+      nil
+    case _
+      @panic("expected accept to time out")
+  end
+  assert.eq(timed_out, nil)
+
+  net.close_listener(listener)
+  assert.eq(net.probe(address, timeout_ms: 200), false)
+end
+
+try run() catch err
+  case _
+    @panic("tcp exchange failed")
+end
+@println("ok")
+"#;
+
+    let stdout = support::build_and_run(source, "net_tcp.tea", &[])?;
+    assert_eq!(stdout, "ok\n");
+    Ok(())
+}
+
+#[test]
+fn udp_sockets_send_and_receive_datagrams() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use bytes from "std.bytes"
+use net from "std.net"
+
+def run() -> Void ! net.NetError
+  const left = net.bind_udp("127.0.0.1:0")
+  const right = net.bind_udp("127.0.0.1:0")
+  net.send_to(left, net.udp_address(right), "ping")
+  const datagram = net.receive(right, timeout_ms: 1000)
+  assert.eq(bytes.to_string(datagram.data), "ping")
+  assert.eq(datagram.sender, net.udp_address(left))
+  net.close_udp(left)
+  net.close_udp(right)
+end
+
+try run() catch err
+  case _
+    @panic("udp exchange failed")
+end
+@println("ok")
+"#;
+
+    let stdout = support::build_and_run(source, "net_udp.tea", &[])?;
+    assert_eq!(stdout, "ok\n");
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_sockets_round_trip_and_clean_up() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use bytes from "std.bytes"
+use fs from "std.fs"
+use net from "std.net"
+use task from "std.task"
+
+def reply_once(listener: net.Listener) -> Int ! net.NetError
+  const conn = net.accept(listener, timeout_ms: 5000)
+  const request = net.read_all(conn, timeout_ms: 5000)
+  net.write(conn, `got ${request}`)
+  net.close(conn)
+  @len(request)
+end
+
+def reply(listener: net.Listener) -> Int
+  const sent = try reply_once(listener) catch err
+    case _
+      -1
+  end
+  sent
+end
+
+def run() -> Void ! net.NetError
+  const socket_path = fs.create_temp_dir("net") + "/echo.sock"
+  const listener = net.listen_unix(socket_path)
+  const server = task.spawn(|| => reply(listener))
+
+  const conn = net.connect_unix(socket_path)
+  net.write_bytes(conn, bytes.from_string("tea"))
+  net.shutdown_write(conn)
+  assert.eq(net.read_all(conn, timeout_ms: 1000), "got tea")
+  net.close(conn)
+  assert.eq(task.join(server), 3)
+
+  net.close_listener(listener)
+  assert.eq(fs.exists(socket_path), false)
+end
+
+try run() catch err
+  case _
+    @panic("unix socket exchange failed")
+end
+@println("ok")
+"#;
+
+    let stdout = support::build_and_run(source, "net_unix.tea", &[])?;
+    assert_eq!(stdout, "ok\n");
+    Ok(())
+}
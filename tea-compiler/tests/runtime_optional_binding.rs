@@ -0,0 +1,54 @@
+mod support;
+
+#[test]
+fn optional_bindings_unwrap_values_in_if_while_and_guard() -> anyhow::Result<()> {
+    let source = r#"
+def lookup(items: List[Int], position: Int) -> Int?
+  if position < @len(items)
+    return items[position]
+  end
+  return nil
+end
+
+def describe(items: List[Int], position: Int) -> String
+  guard var item = lookup(items, position) else
+    return "missing"
+  end
+  return `item ${item}`
+end
+
+const values = [4, 0, 7]
+
+if var first = lookup(values, 0)
+  @println(first + 1)
+else
+  @println("empty")
+end
+
+if var missing = lookup(values, 10)
+  @println(missing)
+else
+  @println("empty")
+end
+
+var position = 0
+while var item = lookup(values, position)
+  @println(item)
+  position = position + 1
+end
+
+@println(describe(values, 2))
+@println(describe(values, 3))
+
+for value in values
+  guard value != 0 else
+    continue
+  end
+  @println(value)
+end
+"#;
+
+    let stdout = support::build_and_run(source, "optional-binding.tea", &[])?;
+    assert_eq!(stdout, "5\nempty\n4\n0\n7\nitem 7\nmissing\n4\n7\n");
+    Ok(())
+}
@@ -10,6 +10,9 @@ var result = process.run_checked("echo", ["hello"])
 assert.ok(result.success)
 assert.eq(result.stdout, "hello\n")
 
+var piped = process.run("cat", stdin: "piped\n")
+assert.eq(piped.stdout, "piped\n")
+
 var streaming = process.spawn("cat", [])
 process.write_stdin(streaming, "bye\n")
 process.close_stdin(streaming)
@@ -31,9 +34,10 @@ fn process_byte_helpers_execute_through_runtime() -> anyhow::Result<()> {
     let source = r#"
 use process from "std.process"
 use assert from "std.assert"
+use bytes from "std.bytes"
 
 var stdout_handle = process.spawn("cat", [])
-process.write_stdin_bytes(stdout_handle, [0, 255, 10, 65])
+process.write_stdin_bytes(stdout_handle, bytes.from_list([0, 255, 10, 65]))
 process.close_stdin(stdout_handle)
 var stdout_bytes = process.read_stdout_bytes(stdout_handle, 4)
 assert.eq(@len(stdout_bytes), 4)
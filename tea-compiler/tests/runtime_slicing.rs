@@ -0,0 +1,49 @@
+mod support;
+
+#[test]
+fn slices_support_negative_open_and_stepped_ranges() -> anyhow::Result<()> {
+    let source = r#"
+var numbers = [1, 2, 3, 4, 5]
+@println(numbers[-1])
+@println(numbers[-3..])
+@println(numbers[..2])
+@println(numbers[..])
+@println(numbers[3..100])
+@println(numbers[0..5 by 2])
+@println(numbers[.. by -1])
+@println(numbers[3..1])
+
+const word = "teapot"
+@println(word[-3..])
+@println(word[1...-2])
+@println(word[.. by -1])
+"#;
+
+    let stdout = support::build_and_run(source, "slicing.tea", &[])?;
+    assert_eq!(
+        stdout,
+        "5\n[3, 4, 5]\n[1, 2]\n[1, 2, 3, 4, 5]\n[4, 5]\n[1, 3, 5]\n[5, 4, 3, 2, 1]\n[]\npot\neapo\ntopaet\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn slice_assignment_replaces_a_contiguous_range() -> anyhow::Result<()> {
+    let source = r#"
+var numbers = [1, 2, 3, 4, 5]
+numbers[1..3] = [9]
+@println(numbers)
+numbers[-1] = 0
+@println(numbers)
+numbers[1..] = [7, 8, 9, 10, 11, 12, 13, 14]
+@println(numbers)
+@println(@len(numbers))
+"#;
+
+    let stdout = support::build_and_run(source, "slice-assign.tea", &[])?;
+    assert_eq!(
+        stdout,
+        "[1, 9, 4, 5]\n[1, 9, 4, 0]\n[1, 7, 8, 9, 10, 11, 12, 13, 14]\n9\n"
+    );
+    Ok(())
+}
@@ -155,8 +155,8 @@ def verify() -> Void
   assert.eq(stripped_suffix!, "report")
   assert.eq(string.pad_start("7", 3), "  7")
   assert.eq(string.pad_end("7", 3), "7  ")
-  assert.eq(string.pad_start_with("tea", 6, "0"), "000tea")
-  assert.eq(string.pad_end_with("tea", 6, "."), "tea...")
+  assert.eq(string.pad_start("tea", 6, "0"), "000tea")
+  assert.eq(string.pad_end("tea", 6, fill: "."), "tea...")
   assert.eq(string.join(["a", "b", "c"], "-"), "a-b-c")
   assert.eq(string.repeat("ha", 3), "hahaha")
 end
@@ -0,0 +1,43 @@
+mod support;
+
+#[test]
+fn struct_defaults_and_update_syntax() -> anyhow::Result<()> {
+    let source = r#"
+const DEFAULT_RETRIES = 3
+
+struct Options {
+  name: String
+  retries: Int = DEFAULT_RETRIES
+  tags: List[String] = []
+  verbose: Bool = false
+}
+
+def describe(options: Options) -> String
+  `${options.name} retries=${options.retries} tags=${@len(options.tags)} verbose=${options.verbose}`
+end
+
+const base = Options(name: "base")
+@println(describe(base))
+
+const positional = Options("positional", 5)
+@println(describe(positional))
+
+const updated = Options(..base, verbose: true, tags: ["a", "b"])
+@println(describe(updated))
+@println(describe(base))
+
+const renamed = Options(..updated, name: "renamed")
+@println(describe(renamed))
+"#;
+
+    let stdout = support::build_and_run(source, "struct_update.tea", &[])?;
+    assert_eq!(
+        stdout,
+        "base retries=3 tags=0 verbose=false\n\
+         positional retries=5 tags=0 verbose=false\n\
+         base retries=3 tags=2 verbose=true\n\
+         base retries=3 tags=0 verbose=false\n\
+         renamed retries=3 tags=2 verbose=true\n"
+    );
+    Ok(())
+}
@@ -0,0 +1,61 @@
+mod support;
+
+#[test]
+fn tasks_return_typed_results() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use task from "std.task"
+
+def square_later(value: Int) -> Int
+  const job = task.spawn(|| => value * value)
+  task.join(job)
+end
+
+const greeting = task.spawn(|| => "hello")
+assert.eq(task.join(greeting), "hello")
+assert.eq(square_later(7), 49)
+
+const lengths = task.parallel_map(["a", "bb", "ccc"], |word: String| => @len(word))
+@println(lengths)
+"#;
+
+    let stdout = support::build_and_run(source, "task-results.tea", &[])?;
+    assert_eq!(stdout, "[1, 2, 3]\n");
+    Ok(())
+}
+
+#[test]
+fn channels_pass_values_between_tasks() -> anyhow::Result<()> {
+    let source = r#"
+use task from "std.task"
+
+const numbers = task.channel[Int](2)
+
+def send_all(count: Int) -> Bool
+  var value = 1
+  while value <= count
+    task.send(numbers, value)
+    value = value + 1
+  end
+  task.close(numbers)
+  task.send(numbers, 99)
+end
+
+def receive_all() -> String
+  const producer = task.spawn(|| => send_all(5))
+  var total = 0
+  var next = task.recv(numbers)
+  while next != nil
+    total = total + (next ?? 0)
+    next = task.recv(numbers)
+  end
+  `${total} ${task.join(producer)}`
+end
+
+@println(receive_all())
+"#;
+
+    let stdout = support::build_and_run(source, "task-channels.tea", &[])?;
+    assert_eq!(stdout, "15 false\n");
+    Ok(())
+}
@@ -0,0 +1,55 @@
+mod support;
+
+#[test]
+fn term_styles_progress_and_tables_render_plain_text() -> anyhow::Result<()> {
+    let source = r#"
+use assert from "std.assert"
+use env from "std.env"
+use term from "std.term"
+
+env.unset("FORCE_COLOR")
+assert.eq(term.color_enabled(), false)
+assert.eq(term.red("plain"), "plain")
+assert.eq(term.strip(term.paint("forced", ["red"])), "forced")
+assert.ne(term.paint("forced", ["red"]), "forced")
+
+env.set("FORCE_COLOR", "1")
+const styled = term.style("ok", ["bold", "bg_blue"])
+assert.ne(styled, "ok")
+assert.eq(term.strip(styled), "ok")
+
+env.set("COLUMNS", "132")
+assert.eq(term.size().columns, 132)
+
+assert.eq(term.render_progress(term.progress(4, width: 8), 1), "[=>      ]  25% 1/4")
+assert.eq(term.render_progress(term.progress(4, label: "copy", width: 4), 9), "copy [====] 100% 4/4")
+assert.eq(term.render_spinner(term.spinner("wait"), 11), "⠙ wait")
+
+const table = term.table(["NAME", "QTY"], [["tea", "3"], ["お茶", "12"]], align: ["left", "right"])
+assert.eq(table, "NAME  QTY\n----  ---\ntea     3\nお茶   12")
+@println("ok")
+"#;
+
+    let stdout = support::build_and_run(source, "term_render.tea", &[])?;
+    assert_eq!(stdout, "ok\n");
+    Ok(())
+}
+
+#[test]
+fn term_prompts_read_answers_from_stdin() -> anyhow::Result<()> {
+    let source = r#"
+use term from "std.term"
+
+@println(term.prompt("Name"))
+@println(term.prompt("City", default: "Oslo"))
+@println(term.confirm("Continue?"))
+@println(term.select("Pick", ["red", "green", "blue"]))
+@println(term.password("Token"))
+@println(term.confirm("Again?", default: true))
+"#;
+
+    let input = "ada\n\nmaybe\nyes\n9\n2\nsecret\n";
+    let stdout = support::build_and_run_with_input(source, "term_prompt.tea", &[], input)?;
+    assert_eq!(stdout, "ada\nOslo\ntrue\n1\nsecret\ntrue\n");
+    Ok(())
+}
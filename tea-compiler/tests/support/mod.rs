@@ -1,6 +1,7 @@
 use std::fs;
+use std::io::Write;
 use std::path::PathBuf;
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 use tempfile::tempdir;
 
@@ -19,6 +20,17 @@ fn cargo_bin() -> PathBuf {
 
 #[allow(dead_code)]
 pub fn build_and_run(source: &str, file_name: &str, args: &[&str]) -> anyhow::Result<String> {
+    build_and_run_with_input(source, file_name, args, "")
+}
+
+/// Like `build_and_run`, but feeds `input` to the binary's stdin.
+#[allow(dead_code)]
+pub fn build_and_run_with_input(
+    source: &str,
+    file_name: &str,
+    args: &[&str],
+    input: &str,
+) -> anyhow::Result<String> {
     let tmp = tempdir()?;
     let script_path = tmp.path().join(file_name);
     let binary_path = tmp.path().join(file_name.trim_end_matches(".tea"));
@@ -39,10 +51,21 @@ pub fn build_and_run(source: &str, file_name: &str, args: &[&str]) -> anyhow::Re
         String::from_utf8_lossy(&build_output.stderr)
     );
 
-    let output = Command::new(&binary_path)
+    let mut child = Command::new(&binary_path)
         .args(args)
-        .output()
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
         .expect("run compiled tea binary");
+    // Reap the child even when writing its input fails.
+    let written = child
+        .stdin
+        .take()
+        .expect("binary stdin")
+        .write_all(input.as_bytes());
+    let output = child.wait_with_output()?;
+    written?;
     assert!(
         output.status.success(),
         "binary should succeed: {}",
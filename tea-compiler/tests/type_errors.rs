@@ -857,3 +857,1183 @@ enum Color {
         messages
     );
 }
+
+#[test]
+fn rejects_list_where_bytes_expected() {
+    let source = r#"
+use fs from "std.fs"
+
+fs.write_bytes("out.bin", [1, 2, 3])
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("bytes_arg.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(
+        result.is_err(),
+        "expected List[Int] to be rejected where Bytes is expected"
+    );
+}
+
+#[test]
+fn rejects_code_points_as_string_list() {
+    let source = r#"
+use string from "std.string"
+
+const letters: List[String] = string.chars("tea")
+const points: List[String] = string.code_points("tea")
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("code_points.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(
+        result.is_err(),
+        "expected List[Int] code points to be rejected where List[String] is expected"
+    );
+    assert_eq!(
+        compiler.diagnostics().entries().len(),
+        1,
+        "only the code_points binding should fail: {:?}",
+        compiler.diagnostics()
+    );
+}
+
+#[test]
+fn rejects_stepped_slice_assignment() {
+    let source = r#"
+var numbers = [1, 2, 3, 4]
+numbers[.. by 2] = [0, 0]
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("stepped_slice.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(result.is_err(), "expected stepped slice assignment to fail");
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|d| d.message.clone())
+        .collect();
+    assert!(
+        messages
+            .iter()
+            .any(|msg| msg.contains("slice assignment does not support a step")),
+        "expected step diagnostic, found {:?}",
+        messages
+    );
+}
+
+#[test]
+fn rejects_struct_default_of_wrong_type() {
+    let source = r#"
+struct Options {
+  retries: Int = "three"
+}
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("struct_default.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(result.is_err(), "expected mistyped default to fail");
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|d| d.message.clone())
+        .collect();
+    assert!(
+        messages
+            .iter()
+            .any(|msg| msg.contains("default value for field 'retries' in struct 'Options'")),
+        "expected default diagnostic, found {:?}",
+        messages
+    );
+}
+
+#[test]
+fn rejects_missing_field_without_default() {
+    let source = r#"
+struct Options {
+  name: String
+  retries: Int = 3
+}
+
+var options = Options(retries: 1)
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("struct_missing.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(result.is_err(), "expected missing field to fail");
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|d| d.message.clone())
+        .collect();
+    assert!(
+        messages
+            .iter()
+            .any(|msg| msg.contains("missing value for field 'name' in struct 'Options'")),
+        "expected missing field diagnostic, found {:?}",
+        messages
+    );
+}
+
+#[test]
+fn rejects_struct_update_with_mismatched_base() {
+    let source = r#"
+struct Point {
+  x: Int
+  y: Int
+}
+
+struct Size {
+  x: Int
+  y: Int
+}
+
+var size = Size(x: 1, y: 2)
+var point = Point(..size, x: 3)
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("struct_update_base.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(result.is_err(), "expected mismatched base to fail");
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|d| d.message.clone())
+        .collect();
+    assert!(
+        messages
+            .iter()
+            .any(|msg| msg.contains("'..' base for struct 'Point' must be a Point value")),
+        "expected base diagnostic, found {:?}",
+        messages
+    );
+}
+
+#[test]
+fn rejects_struct_update_field_type_mismatch() {
+    let source = r#"
+struct Point {
+  x: Int
+  y: Int
+}
+
+var origin = Point(x: 0, y: 0)
+var moved = Point(..origin, y: "up")
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("struct_update_field.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(result.is_err(), "expected mistyped update field to fail");
+}
+
+#[test]
+fn rejects_struct_update_base_with_other_type_arguments() {
+    let source = r#"
+struct Box[T] {
+  value: T
+  count: Int
+}
+
+var words = Box[String](value: "tea", count: 1)
+var numbers = Box[Int](..words, count: 2)
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("struct_update_type_arguments.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(
+        result.is_err(),
+        "expected mismatched base type argument to fail"
+    );
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|d| d.message.clone())
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "'..' base for struct 'Box' has String for type parameter 'T' but Int was given"
+                .to_string()
+        ]
+    );
+}
+
+#[test]
+fn rejects_spread_argument_in_function_call() {
+    let source = r#"
+struct Point {
+  x: Int
+  y: Int
+}
+
+def norm(point: Point) -> Int
+  point.x + point.y
+end
+
+var origin = Point(x: 0, y: 0)
+var total = norm(..origin)
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("spread_call.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(result.is_err(), "expected spread in function call to fail");
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|d| d.message.clone())
+        .collect();
+    assert!(
+        messages
+            .iter()
+            .any(|msg| msg.contains("'..' base values are only supported in struct constructors")),
+        "expected spread diagnostic, found {:?}",
+        messages
+    );
+}
+
+#[test]
+fn rejects_call_missing_required_argument() {
+    let source = r#"
+def greet(name: String, greeting: String = "Hello") -> String
+  `${greeting}, ${name}`
+end
+
+var message = greet(greeting: "Hi")
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("missing_argument.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(result.is_err(), "expected missing argument to fail");
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|d| d.message.clone())
+        .collect();
+    assert!(
+        messages
+            .iter()
+            .any(|msg| msg.contains("missing argument for parameter 'name' in call to 'greet'")),
+        "expected missing argument diagnostic, found {:?}",
+        messages
+    );
+}
+
+#[test]
+fn rejects_unknown_named_argument() {
+    let source = r#"
+def greet(name: String, greeting: String = "Hello") -> String
+  `${greeting}, ${name}`
+end
+
+var message = greet("Tea", salutation: "Hi")
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("unknown_named_argument.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(result.is_err(), "expected unknown named argument to fail");
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|d| d.message.clone())
+        .collect();
+    assert!(
+        messages
+            .iter()
+            .any(|msg| msg.contains("function 'greet' has no parameter named 'salutation'")),
+        "expected unknown parameter diagnostic, found {:?}",
+        messages
+    );
+}
+
+#[test]
+fn rejects_too_many_arguments_with_defaults() {
+    let source = r#"
+def greet(name: String, greeting: String = "Hello") -> String
+  `${greeting}, ${name}`
+end
+
+var message = greet("Tea", "Hi", "extra")
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("too_many_arguments.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(result.is_err(), "expected extra argument to fail");
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|d| d.message.clone())
+        .collect();
+    assert!(
+        messages
+            .iter()
+            .any(|msg| msg.contains("expected between 1 and 2 arguments but got 3")),
+        "expected arity diagnostic, found {:?}",
+        messages
+    );
+}
+
+#[test]
+fn rejects_required_parameter_after_default() {
+    let source = r#"
+def greet(greeting: String = "Hello", name: String) -> String
+  `${greeting}, ${name}`
+end
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("default_order.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(
+        result.is_err(),
+        "expected required parameter after default to fail"
+    );
+}
+
+#[test]
+fn rejects_variadic_argument_type_mismatch() {
+    let source = r#"
+def total(...values: Int) -> Int
+  var sum = 0
+  for value in values
+    sum = sum + value
+  end
+  sum
+end
+
+var result = total(1, 2, "three")
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("variadic_mismatch.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(
+        result.is_err(),
+        "expected mistyped variadic argument to fail"
+    );
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|d| d.message.clone())
+        .collect();
+    assert!(
+        messages
+            .iter()
+            .any(|msg| msg.contains("argument 3 to 'total'")),
+        "expected variadic argument diagnostic, found {:?}",
+        messages
+    );
+}
+
+#[test]
+fn rejects_spawned_closure_capturing_list() {
+    let source = r#"
+use task from "std.task"
+
+def total(limit: Int) -> Int
+  var items = [1, 2, 3]
+  const job = task.spawn(|| => @len(items) + limit)
+  task.join(job)
+end
+
+@println(total(2))
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("spawn_capture.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(result.is_err(), "expected list capture to be rejected");
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|d| d.message.clone())
+        .collect();
+    assert!(
+        messages.iter().any(|msg| msg.contains(
+            "closure passed to 'task.spawn' captures 'items' of type List[Int], which is not safe to share between tasks"
+        )),
+        "expected capture diagnostic, found {:?}",
+        messages
+    );
+    assert!(
+        !messages.iter().any(|msg| msg.contains("'limit'")),
+        "Int captures should be allowed, found {:?}",
+        messages
+    );
+}
+
+#[test]
+fn rejects_parallel_map_closure_capturing_dict() {
+    let source = r#"
+use { parallel_map } from "std.task"
+
+def scores(names: List[String]) -> List[Int]
+  const bonus = { "ada": 2 }
+  parallel_map(names, |name: String| => @len(name) + @len(bonus))
+end
+
+@println(scores(["ada"]))
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("parallel_map_capture.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(result.is_err(), "expected dict capture to be rejected");
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|d| d.message.clone())
+        .collect();
+    assert!(
+        messages.iter().any(|msg| msg.contains(
+            "closure passed to 'parallel_map' captures 'bonus' of type Dict[String, Int]"
+        )),
+        "expected capture diagnostic, found {:?}",
+        messages
+    );
+}
+
+#[test]
+fn rejects_spawned_const_closure_capturing_list() {
+    let source = r#"
+use task from "std.task"
+
+def total(limit: Int) -> Int
+  var items = [1, 2, 3]
+  const work = || => @len(items) + limit
+  const job = task.spawn(work)
+  task.join(job)
+end
+
+@println(total(2))
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("spawn_named_closure.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(result.is_err(), "expected list capture to be rejected");
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|d| d.message.clone())
+        .collect();
+    assert!(
+        messages.iter().any(|msg| msg.contains(
+            "closure passed to 'task.spawn' captures 'items' of type List[Int], which is not safe to share between tasks"
+        )),
+        "expected capture diagnostic, found {:?}",
+        messages
+    );
+}
+
+#[test]
+fn rejects_spawned_closure_using_module_variable() {
+    let source = r#"
+use task from "std.task"
+
+var items: List[Int] = []
+
+def record(value: Int) -> Void
+  @append(items, value)
+end
+
+const writer = task.spawn(|| => @append(items, 1))
+const helper = task.spawn(|| => record(2))
+@append(items, 3)
+task.join(writer)
+task.join(helper)
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("spawn_module_variable.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(
+        result.is_err(),
+        "expected module variable use to be rejected"
+    );
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|d| d.message.clone())
+        .collect();
+    let module_variable_errors = messages
+        .iter()
+        .filter(|msg| msg.contains("closure passed to 'task.spawn' uses module variable 'items'"))
+        .count();
+    assert_eq!(
+        module_variable_errors, 2,
+        "expected one diagnostic per spawn, found {:?}",
+        messages
+    );
+}
+
+#[test]
+fn rejects_sending_lists_through_channels() {
+    let source = r#"
+use task from "std.task"
+
+def forward[T](target: task.Channel[T], value: T) -> Bool
+  task.send(target, value)
+end
+
+const lists = task.channel[List[Int]](2)
+var items = [1, 2]
+task.send(lists, items)
+forward(lists, items)
+@append(items, 3)
+
+const numbers = task.channel[Int](2)
+task.send(numbers, 4)
+"#;
+    let messages = rejection_diagnostics("channel_send_list.tea", source);
+    let sent: Vec<_> = messages
+        .iter()
+        .filter(|msg| msg.contains("has type List[Int], which is not safe to share between tasks"))
+        .collect();
+    assert_eq!(
+        sent.len(),
+        2,
+        "expected both sends of the list to be rejected, found {:?}",
+        messages
+    );
+    assert!(
+        messages
+            .iter()
+            .any(|msg| msg.starts_with("value sent through 'task.send'")),
+        "expected the diagnostic to name task.send, found {:?}",
+        messages
+    );
+}
+
+#[test]
+fn accepts_iterator_pipelines_and_generators() {
+    let source = r#"
+use iter from "std.iter"
+use fs from "std.fs"
+use process from "std.process"
+
+def countdown(start: Int) -> Iterator[Int]
+  var current = start
+  while current > 0
+    yield current
+    current = current - 1
+  end
+end
+
+def labels(limit: Int) -> List[String]
+  const evens = iter.filter(countdown(limit), |n: Int| => n % 2 == 0)
+  const named = iter.map(evens, |n: Int| => `#${n}`)
+  iter.collect(iter.take(named, 3))
+end
+
+def first_pair() -> String
+  const pairs = iter.zip(countdown(3), iter.from_list(["a", "b"]))
+  var result = ""
+  for pair in pairs
+    result = `${result}${pair.second}${pair.first}`
+  end
+  for entry in iter.enumerate(iter.chain(countdown(1), countdown(2)))
+    result = `${result}${entry.index}`
+  end
+  const rest = iter.take_while(countdown(5), |n: Int| => n > 3)
+  const head = iter.next(rest) ?? 0
+  `${result}${head}`
+end
+
+def line_count(path: String) -> Int
+  var count = 0
+  for line in fs.stream_lines(path)
+    count = count + @len(line)
+  end
+  for entry in fs.stream_walk(path)
+    count = count + 1
+  end
+  for line in process.stream_stdout(process.spawn("ls"))
+    count = count + 1
+  end
+  count
+end
+
+@println(labels(10))
+@println(first_pair())
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("iterators.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|d| d.message.clone())
+        .collect();
+    assert!(
+        result.is_ok(),
+        "expected iterator code to type check, found {:?}",
+        messages
+    );
+}
+
+fn rejection_diagnostics(name: &str, source: &str) -> Vec<String> {
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(SourceId(0), PathBuf::from(name), source.to_string());
+    let result = compiler.compile(&source_file);
+    assert!(result.is_err(), "expected {name} to be rejected");
+    compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|d| d.message.clone())
+        .collect()
+}
+
+#[test]
+fn rejects_generator_rule_violations() {
+    let cases = [
+        (
+            "generator_return_type.tea",
+            "def numbers() -> List[Int]\n  yield 1\nend\n",
+            "generator function 'numbers' must return Iterator[T], found List[Int]",
+        ),
+        (
+            "generator_yield_type.tea",
+            "def numbers() -> Iterator[Int]\n  yield \"one\"\nend\n",
+            "yield expression",
+        ),
+        (
+            "generator_return_value.tea",
+            "def numbers() -> Iterator[Int]\n  yield 1\n  return 2\nend\n",
+            "generator functions cannot return a value; use yield to produce items",
+        ),
+        (
+            "generator_errors.tea",
+            "error Oops {\n  Bad\n}\n\ndef numbers() -> Iterator[Int] ! Oops\n  yield 1\nend\n",
+            "generator function 'numbers' cannot declare errors; handle them inside the generator",
+        ),
+        (
+            "lambda_yield.tea",
+            "def numbers() -> Int\n  const f = |x: Int| => {\n    yield x\n    x\n  }\n  f(1)\nend\n",
+            "yield is only allowed inside generator functions",
+        ),
+        (
+            "top_level_yield.tea",
+            "yield 1\n",
+            "yield is only allowed inside generator functions",
+        ),
+        (
+            "iterate_int.tea",
+            "for item in 3\n  @println(item)\nend\n",
+            "cannot iterate over type Int, expected List[T] or Iterator[T]",
+        ),
+    ];
+    for (name, source, expected) in cases {
+        let messages = rejection_diagnostics(name, source);
+        assert!(
+            messages.iter().any(|msg| msg.contains(expected)),
+            "expected '{expected}' for {name}, found {messages:?}"
+        );
+    }
+}
+
+#[test]
+fn accepts_extended_list_methods() {
+    let source = r#"
+var numbers = [3, 1, 2]
+numbers.sort()
+numbers.sort_by(|a, b| => b - a)
+numbers.reverse()
+numbers.insert(0, 7)
+const removed: Int = numbers.remove_at(0)
+const last: Int? = numbers.pop()
+const total: Int = numbers.sum()
+const smallest: Int? = numbers.min()
+const sorted: List[Int] = numbers.sorted_by(|a, b| => a - b)
+const groups: Dict[String, List[Int]] = numbers.group_by(|n| => `${n % 2}`)
+const halves: List[List[Int]] = numbers.partition(|n| => n > 1)
+const labels: List[String] = numbers.zip(["a", "b"], |n, label| => `${label}${n}`)
+const spread: List[Int] = numbers.flat_map(|n| => [n, n])
+const chunks: List[List[Int]] = numbers.chunk(2)
+const text: String = ["a", "b"].join(", ")
+const mean: Float = [1.5, 2.5].sum() / 2.0
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("list_methods.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(
+        result.is_ok(),
+        "expected list methods to type check, found {:?}",
+        compiler.diagnostics()
+    );
+}
+
+#[test]
+fn rejects_list_methods_on_unsupported_elements() {
+    let cases = [
+        (
+            "list_sum_strings.tea",
+            "const total = [\"a\"].sum()\n",
+            "List.sum requires a List of Int or Float, found List[String]",
+        ),
+        (
+            "list_join_ints.tea",
+            "const text = [1, 2].join(\",\")\n",
+            "List.join requires a List of String, found List[Int]",
+        ),
+        (
+            "list_sort_bools.tea",
+            "var flags = [true, false]\nflags.sort()\n",
+            "List.sort requires a List of Int, Float, or String, found List[Bool]",
+        ),
+        (
+            "list_sort_by_return.tea",
+            "var names = [\"b\", \"a\"]\nnames.sort_by(|a, b| => a < b)\n",
+            "List.sort_by comparator return type",
+        ),
+        (
+            "list_flat_map_return.tea",
+            "const values = [1].flat_map(|n| => n)\n",
+            "List.flat_map callback must return a List, found Int",
+        ),
+        (
+            "list_unknown_method.tea",
+            "const values = [1].shuffle()\n",
+            "List has no method named 'shuffle'",
+        ),
+    ];
+
+    for (name, source, expected) in cases {
+        let messages = rejection_diagnostics(name, source);
+        assert!(
+            messages.iter().any(|message| message.contains(expected)),
+            "expected '{expected}' for {name}, found {messages:?}"
+        );
+    }
+}
+
+#[test]
+fn rejects_format_spec_for_wrong_value_type() {
+    let source = r#"
+const count = 255
+const name = "tea"
+@println(`${count:08x} ${name:x}`)
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("format_spec_type.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(result.is_err(), "expected ':x' on a String to be rejected");
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|diagnostic| diagnostic.message.clone())
+        .collect();
+    assert_eq!(messages.len(), 1, "{messages:?}");
+    assert!(
+        messages[0].contains("invalid format specifier 'x' for String"),
+        "{messages:?}"
+    );
+}
+
+#[test]
+fn rejects_malformed_format_spec() {
+    let source = r#"
+const price = 1.5
+@println(`${price:.f}`)
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("format_spec_syntax.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(
+        result.is_err(),
+        "expected a malformed specifier to be rejected"
+    );
+    assert!(
+        compiler
+            .diagnostics()
+            .entries()
+            .iter()
+            .any(|diagnostic| diagnostic.message.contains("format precision")),
+        "{:?}",
+        compiler.diagnostics()
+    );
+}
+
+#[test]
+fn rejects_public_const_computed_at_runtime() {
+    let source = r#"
+pub const WIDTH: Int = 80
+pub const HALF: Int = WIDTH / 2
+pub const ARGUMENTS: Int = @len(@args())
+const LOCAL_ARGUMENTS: Int = @len(@args())
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("runtime_const.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(
+        result.is_err(),
+        "expected a runtime pub const to be rejected"
+    );
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|diagnostic| diagnostic.message.clone())
+        .collect();
+    assert_eq!(messages.len(), 1, "{messages:?}");
+    assert!(
+        messages[0].starts_with("public const 'ARGUMENTS' must be a compile-time constant"),
+        "{messages:?}"
+    );
+}
+
+#[test]
+fn rejects_public_const_that_overflows() {
+    let source = r#"
+pub const BIG: Int = 9223372036854775807
+pub const BIGGER: Int = BIG + 1
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("overflow_const.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(
+        result.is_err(),
+        "expected an overflowing pub const to be rejected"
+    );
+    assert!(
+        compiler
+            .diagnostics()
+            .entries()
+            .iter()
+            .any(|diagnostic| diagnostic.message.contains("arithmetic overflows Int")),
+        "{:?}",
+        compiler.diagnostics()
+    );
+}
+
+#[test]
+fn match_patterns_fold_constants_and_compare_variables_at_runtime() {
+    let source = r#"
+const LIMIT = 10
+
+def classify(value: Int, threshold: Int) -> String
+  match value
+    case LIMIT => "limit"
+    case LIMIT * 2 => "double"
+    case -1 => "negative one"
+    case threshold => "threshold"
+    case _ => "other"
+  end
+end
+
+print(classify(3, 3))
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("constant_patterns.tea"),
+        source.to_string(),
+    );
+    compiler
+        .compile(&source_file)
+        .unwrap_or_else(|_| panic!("{:?}", compiler.diagnostics()));
+}
+
+#[test]
+fn rejects_match_pattern_that_overflows() {
+    let source = r#"
+const BIG = 9223372036854775807
+
+def classify(value: Int) -> String
+  match value
+    case BIG + 1 => "overflow"
+    case _ => "other"
+  end
+end
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("overflow_pattern.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(
+        result.is_err(),
+        "expected an overflowing pattern to be rejected"
+    );
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|diagnostic| diagnostic.message.clone())
+        .collect();
+    assert_eq!(
+        messages,
+        vec!["invalid match pattern: arithmetic overflows Int".to_string()]
+    );
+}
+
+#[test]
+fn rejects_const_built_from_variables() {
+    let source = r#"
+var width = 80
+const ARGUMENTS: Int = @len(@args())
+const HALF = width / 2
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(
+        SourceId(0),
+        PathBuf::from("variable_const.tea"),
+        source.to_string(),
+    );
+    let result = compiler.compile(&source_file);
+    assert!(
+        result.is_err(),
+        "expected a const over a variable to be rejected"
+    );
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|diagnostic| diagnostic.message.clone())
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "const 'HALF' must be a compile-time constant: 'width' is not a compile-time constant"
+                .to_string()
+        ]
+    );
+}
+
+#[test]
+fn extern_functions_bind_c_compatible_signatures() {
+    let source = r#"
+extern def strlen(s: CString) -> Int from "c"
+extern def sqlite3_libversion() -> CString from "sqlite3"
+extern def free(pointer: CPointer) from "c"
+extern def isatty(fd: Int) -> Bool from "c"
+
+var length: Int = strlen("tea")
+var version: String = sqlite3_libversion()
+free(0)
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(SourceId(0), PathBuf::from("ffi.tea"), source.to_string());
+    let compilation = compiler
+        .compile(&source_file)
+        .unwrap_or_else(|err| panic!("{err}: {:?}", compiler.diagnostics().entries()));
+    assert_eq!(compilation.extern_libraries(), vec!["sqlite3".to_string()]);
+}
+
+#[test]
+fn rejects_extern_types_without_a_c_representation() {
+    let source = r#"
+extern def join(parts: List[String]) -> Int from "c"
+extern def getenv(name: String) -> CString from "c"
+extern def maybe() -> Int? from "c"
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(SourceId(0), PathBuf::from("ffi.tea"), source.to_string());
+    assert!(compiler.compile(&source_file).is_err());
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|diagnostic| diagnostic.message.clone())
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "parameter 'parts' of extern function 'join' cannot cross the C boundary as List[String]; only Int, Float, Bool, CString and CPointer are allowed".to_string(),
+            "parameter 'name' of extern function 'getenv' cannot cross the C boundary as String; only Int, Float, Bool, CString and CPointer are allowed; use CString to pass text".to_string(),
+            "return type of extern function 'maybe' cannot cross the C boundary as Int?; only Int, Float, Bool, CString and CPointer are allowed".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn rejects_extern_parameter_defaults() {
+    let source = "extern def abs(value: Int = 0) -> Int from \"c\"\n";
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(SourceId(0), PathBuf::from("ffi.tea"), source.to_string());
+    assert!(compiler.compile(&source_file).is_err());
+    assert!(compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .any(|diagnostic| diagnostic.message
+            == "parameter 'value' of extern function 'abs' cannot have a default value"));
+}
+
+#[test]
+fn builtin_min_and_max_keep_int_arguments_as_int() {
+    let source = r#"
+var smallest: Int = @min(3, 7)
+var largest: Float = @max(1.5, 2.5)
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(SourceId(0), PathBuf::from("math.tea"), source.to_string());
+    compiler
+        .compile(&source_file)
+        .unwrap_or_else(|err| panic!("{err}: {:?}", compiler.diagnostics().entries()));
+}
+
+#[test]
+fn rejects_builtin_max_with_mixed_int_and_float() {
+    let source = "var value = @max(1, 2.5)\n";
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(SourceId(0), PathBuf::from("math.tea"), source.to_string());
+    assert!(compiler.compile(&source_file).is_err());
+    assert!(compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .any(|diagnostic| diagnostic.message
+            == "function 'max' expects two Int or two Float arguments, found Int and Float"));
+}
+
+#[test]
+fn typed_json_decode_returns_the_requested_type() {
+    let source = r#"
+use json from "std.json"
+
+struct Server {
+  host: String
+  port: Int
+}
+
+var port: Int = json.decode[Server]("{}").port
+var host: Int = json.decode[Server]("{}").host
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(SourceId(0), PathBuf::from("json.tea"), source.to_string());
+    assert!(compiler.compile(&source_file).is_err());
+    let messages: Vec<_> = compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .map(|diagnostic| diagnostic.message.clone())
+        .collect();
+    assert_eq!(messages.len(), 1, "unexpected diagnostics: {messages:?}");
+    assert_eq!(messages[0], "variable 'host': expected Int, found String");
+}
+
+#[test]
+fn rejects_typed_json_intrinsic_without_type_argument() {
+    let source = r#"
+use intrinsics from "std.intrinsics"
+
+var value = intrinsics.json_decode_as("{}", {})
+"#;
+    let mut compiler = Compiler::new(CompileOptions::default());
+    let source_file = SourceFile::new(SourceId(0), PathBuf::from("json.tea"), source.to_string());
+    assert!(compiler.compile(&source_file).is_err());
+    assert!(compiler
+        .diagnostics()
+        .entries()
+        .iter()
+        .any(|diagnostic| diagnostic.message
+            == "module function 'intrinsics.json_decode_as' requires a type argument"));
+}
@@ -10,15 +10,23 @@ use tea_compiler::{
     InterpolatedStringPart, LambdaBody, LambdaExpression, Literal, LoopHeader, MemberExpression,
     Module, SourceSpan, Statement, TypeExpression, UnaryOperator,
 };
+use tea_support::{resolve_index, slice_positions, slice_range, FormatArg, FormatSpec};
 
 #[derive(Debug, Clone)]
 pub struct EvalOptions {
     pub fuel: usize,
+    /// Seed for the shared random generator. wasm32 has no OS entropy and fixed
+    /// hash keys, so the browser host passes one from `crypto.getRandomValues`;
+    /// `None` seeds it from the process's hash randomness.
+    pub random_seed: Option<i64>,
 }
 
 impl Default for EvalOptions {
     fn default() -> Self {
-        Self { fuel: 50_000 }
+        Self {
+            fuel: 50_000,
+            random_seed: None,
+        }
     }
 }
 
@@ -124,11 +132,13 @@ impl Value {
             Self::Function(_) => "<function>".into(),
             Self::Module(_) => "<module>".into(),
             Self::Range(range) => {
-                if range.inclusive {
-                    format!("{}...{}", range.start, range.end)
-                } else {
-                    format!("{}..{}", range.start, range.end)
+                let bound = |value: Option<i64>| value.map(|v| v.to_string()).unwrap_or_default();
+                let operator = if range.inclusive { "..." } else { ".." };
+                let mut rendered = format!("{}{operator}{}", bound(range.start), bound(range.end));
+                if range.step != 1 {
+                    rendered.push_str(&format!(" by {}", range.step));
                 }
+                rendered
             }
         }
     }
@@ -136,9 +146,10 @@ impl Value {
 
 #[derive(Clone)]
 struct RangeValue {
-    start: i64,
-    end: i64,
+    start: Option<i64>,
+    end: Option<i64>,
     inclusive: bool,
+    step: i64,
 }
 
 #[derive(Clone)]
@@ -225,6 +236,7 @@ impl Environment {
 struct StructDefinition {
     name: String,
     fields: Vec<String>,
+    defaults: HashMap<String, Expression>,
 }
 
 enum Flow {
@@ -240,6 +252,9 @@ struct Interpreter {
     stdout: Vec<String>,
     exit_code: Option<i32>,
     fuel_remaining: usize,
+    // Handle 0 is the shared generator; seeded handles count up from 1.
+    random_generators: HashMap<i64, tea_intrinsics::random::Generator>,
+    random_seed: Option<i64>,
 }
 
 impl Interpreter {
@@ -251,6 +266,8 @@ impl Interpreter {
             stdout: Vec::new(),
             exit_code: None,
             fuel_remaining: options.fuel,
+            random_generators: HashMap::new(),
+            random_seed: options.random_seed,
         };
         interpreter.install_builtins();
         interpreter
@@ -323,10 +340,24 @@ impl Interpreter {
                                 .iter()
                                 .map(|field| field.name.clone())
                                 .collect(),
+                            defaults: struct_stmt
+                                .fields
+                                .iter()
+                                .filter_map(|field| {
+                                    let default = field.default_value.clone()?;
+                                    Some((field.name.clone(), default))
+                                })
+                                .collect(),
                         },
                     );
                 }
                 Statement::Function(function) => {
+                    if let Some(extern_function) = &function.extern_function {
+                        bail!(
+                            "extern function '{}' requires a native build",
+                            extern_function.symbol
+                        );
+                    }
                     let cell = env.define_placeholder(function.name.clone());
                     *cell.borrow_mut() =
                         Value::Function(Rc::new(FunctionValue::User(UserFunction {
@@ -370,8 +401,15 @@ impl Interpreter {
             }
             Statement::Conditional(conditional) => {
                 let condition = self.evaluate_expression(env, &conditional.condition)?;
-                if self.is_truthy(&condition) {
+                let matched = match &conditional.binding {
+                    Some(_) => !matches!(condition, Value::Nil),
+                    None => self.is_truthy(&condition),
+                };
+                if matched {
                     let scope = Environment::new(Some(env.clone()));
+                    if let Some(binding) = &conditional.binding {
+                        scope.define(binding.name.clone(), condition);
+                    }
                     self.register_declarations(&scope, &conditional.consequent.statements)?;
                     self.execute_block(&scope, &conditional.consequent)
                 } else if let Some(alternative) = &conditional.alternative {
@@ -404,6 +442,29 @@ impl Interpreter {
                     }
                     Ok(Flow::Next(last_value))
                 }
+                LoopHeader::Binding { binding, value } => {
+                    let mut last_value = None;
+                    loop {
+                        let bound = self.evaluate_expression(env, value)?;
+                        if matches!(bound, Value::Nil) {
+                            break;
+                        }
+                        let scope = Environment::new(Some(env.clone()));
+                        scope.define(binding.name.clone(), bound);
+                        self.register_declarations(&scope, &loop_stmt.body.statements)?;
+                        match self.execute_block(&scope, &loop_stmt.body)? {
+                            Flow::Next(result) => {
+                                if result.is_some() {
+                                    last_value = result;
+                                }
+                            }
+                            Flow::Return(value) => return Ok(Flow::Return(value)),
+                            Flow::Break => break,
+                            Flow::Continue => continue,
+                        }
+                    }
+                    Ok(Flow::Next(last_value))
+                }
                 LoopHeader::For { pattern, iterator } => {
                     let iterable = self.evaluate_expression(env, iterator)?;
                     let mut last_value = None;
@@ -426,9 +487,29 @@ impl Interpreter {
                     Ok(Flow::Next(last_value))
                 }
             },
+            Statement::Guard(guard_stmt) => {
+                let condition = self.evaluate_expression(env, &guard_stmt.condition)?;
+                let passed = match &guard_stmt.binding {
+                    Some(_) => !matches!(condition, Value::Nil),
+                    None => self.is_truthy(&condition),
+                };
+                if !passed {
+                    let scope = Environment::new(Some(env.clone()));
+                    self.register_declarations(&scope, &guard_stmt.alternative.statements)?;
+                    return match self.execute_block(&scope, &guard_stmt.alternative)? {
+                        Flow::Next(_) => bail!("guard else block must exit the enclosing scope"),
+                        flow => Ok(flow),
+                    };
+                }
+                if let Some(binding) = &guard_stmt.binding {
+                    env.define(binding.name.clone(), condition);
+                }
+                Ok(Flow::Next(None))
+            }
             Statement::Break(_) => Ok(Flow::Break),
             Statement::Continue(_) => Ok(Flow::Continue),
             Statement::Throw(_) => bail!("throw is not supported in the browser runner"),
+            Statement::Yield(_) => bail!("yield is not supported in the browser runner"),
             Statement::Return(return_stmt) => {
                 let value = match &return_stmt.expression {
                     Some(expression) => self.evaluate_expression(env, expression)?,
@@ -482,6 +563,20 @@ impl Interpreter {
                         InterpolatedStringPart::Expression(expression) => {
                             result.push_str(&self.evaluate_expression(env, expression)?.render());
                         }
+                        InterpolatedStringPart::Formatted(formatted) => {
+                            let value = self.evaluate_expression(env, &formatted.expression)?;
+                            let rendered = value.render();
+                            let argument = match &value {
+                                Value::Int(number) => FormatArg::Int(*number),
+                                Value::Float(number) => FormatArg::Float(*number),
+                                Value::String(text) => FormatArg::String(text),
+                                _ => FormatArg::Other(&rendered),
+                            };
+                            let text = FormatSpec::parse(&formatted.spec)
+                                .and_then(|spec| spec.apply(argument))
+                                .map_err(|message| anyhow!(message))?;
+                            result.push_str(&text);
+                        }
                     }
                 }
                 Ok(Value::String(result))
@@ -536,14 +631,14 @@ impl Interpreter {
             ExpressionKind::Member(member) => self.evaluate_member(env, member),
             ExpressionKind::Index(index) => self.evaluate_index(env, index),
             ExpressionKind::Range(range) => {
-                let start_value = self.evaluate_expression(env, &range.start)?;
-                let end_value = self.evaluate_expression(env, &range.end)?;
-                let start = self.expect_int(start_value)?;
-                let end = self.expect_int(end_value)?;
+                let start = self.evaluate_optional_int(env, range.start.as_deref())?;
+                let end = self.evaluate_optional_int(env, range.end.as_deref())?;
+                let step = self.evaluate_optional_int(env, range.step.as_deref())?;
                 Ok(Value::Range(RangeValue {
                     start,
                     end,
                     inclusive: range.inclusive,
+                    step: step.unwrap_or(1),
                 }))
             }
             ExpressionKind::Lambda(lambda) => Ok(Value::Function(Rc::new(FunctionValue::User(
@@ -662,7 +757,17 @@ impl Interpreter {
             }
         }
 
-        let callee_value = self.evaluate_expression(env, callee)?;
+        let callee_value = match &callee.kind {
+            ExpressionKind::Member(member) => {
+                let object = self.evaluate_expression(env, &member.object)?;
+                if let Value::List(items) = object {
+                    let (positional, _) = self.evaluate_arguments(env, arguments)?;
+                    return self.call_list_method(&items, &member.property, positional);
+                }
+                self.member_value(object, member)?
+            }
+            _ => self.evaluate_expression(env, callee)?,
+        };
 
         match callee_value {
             Value::Function(function) => match function.as_ref() {
@@ -679,6 +784,10 @@ impl Interpreter {
         member: &MemberExpression,
     ) -> Result<Value> {
         let object = self.evaluate_expression(env, &member.object)?;
+        self.member_value(object, member)
+    }
+
+    fn member_value(&self, object: Value, member: &MemberExpression) -> Result<Value> {
         match object {
             Value::Module(module) => module
                 .get(&member.property)
@@ -705,6 +814,20 @@ impl Interpreter {
         self.index_value(object, index_value)
     }
 
+    fn evaluate_optional_int(
+        &mut self,
+        env: &Rc<Environment>,
+        expression: Option<&Expression>,
+    ) -> Result<Option<i64>> {
+        match expression {
+            Some(expression) => {
+                let value = self.evaluate_expression(env, expression)?;
+                Ok(Some(self.expect_int(value)?))
+            }
+            None => Ok(None),
+        }
+    }
+
     fn evaluate_assignment(
         &mut self,
         env: &Rc<Environment>,
@@ -749,6 +872,20 @@ impl Interpreter {
                         items.borrow_mut()[index] = value;
                         Ok(())
                     }
+                    (Value::List(items), Value::Range(range)) => {
+                        if range.step != 1 {
+                            bail!("slice assignment does not support a step");
+                        }
+                        let Value::List(replacement) = value else {
+                            bail!("slice assignment expects a List value");
+                        };
+                        let replacement = replacement.borrow().clone();
+                        let mut items = items.borrow_mut();
+                        let (start, end) =
+                            slice_range(items.len(), range.start, range.end, range.inclusive);
+                        items.splice(start..end, replacement);
+                        Ok(())
+                    }
                     (Value::Dict(entries), Value::String(key)) => {
                         entries.borrow_mut().insert(key, value);
                         Ok(())
@@ -765,9 +902,18 @@ impl Interpreter {
         function: &UserFunction,
         env: &Rc<Environment>,
         arguments: &[CallArgument],
+    ) -> Result<Value> {
+        let (positional, named) = self.evaluate_arguments(env, arguments)?;
+        self.invoke_user(function, positional, named)
+    }
+
+    fn invoke_user(
+        &mut self,
+        function: &UserFunction,
+        mut positional: VecDeque<Value>,
+        named: HashMap<String, Value>,
     ) -> Result<Value> {
         let call_env = Environment::new(Some(function.closure.clone()));
-        let (mut positional, named) = self.evaluate_arguments(env, arguments)?;
         self.bind_parameters(&call_env, &function.parameters, &mut positional, &named)?;
 
         match &function.body {
@@ -790,11 +936,31 @@ impl Interpreter {
         function: &NativeFunction,
         arguments: &[CallArgument],
     ) -> Result<Value> {
-        let (mut positional, named) = self.evaluate_arguments(env, arguments)?;
+        let (positional, named) = self.evaluate_arguments(env, arguments)?;
         if !named.is_empty() {
             bail!("named arguments are not supported for '{}'", function.name);
         }
+        self.invoke_native(function, positional)
+    }
+
+    /// Call a function value with already evaluated positional arguments.
+    fn call_value(&mut self, callee: &Value, arguments: Vec<Value>) -> Result<Value> {
+        let Value::Function(function) = callee else {
+            bail!("attempted to call a non-function value");
+        };
+        match function.as_ref() {
+            FunctionValue::Native(function) => self.invoke_native(function, arguments.into()),
+            FunctionValue::User(function) => {
+                self.invoke_user(function, arguments.into(), HashMap::new())
+            }
+        }
+    }
 
+    fn invoke_native(
+        &mut self,
+        function: &NativeFunction,
+        mut positional: VecDeque<Value>,
+    ) -> Result<Value> {
         match function.name.as_str() {
             "print" => {
                 let value = self.take_arg(&mut positional, "print")?;
@@ -852,16 +1018,24 @@ impl Interpreter {
                 self.expect_number(self.take_arg(&mut positional, "sqrt")?)?
                     .sqrt(),
             )),
-            "min" => {
-                let left = self.expect_number(self.take_arg(&mut positional, "min")?)?;
-                let right = self.expect_number(self.take_arg(&mut positional, "min")?)?;
-                Ok(Value::Float(left.min(right)))
-            }
-            "max" => {
-                let left = self.expect_number(self.take_arg(&mut positional, "max")?)?;
-                let right = self.expect_number(self.take_arg(&mut positional, "max")?)?;
-                Ok(Value::Float(left.max(right)))
-            }
+            "min" => match (
+                self.take_arg(&mut positional, "min")?,
+                self.take_arg(&mut positional, "min")?,
+            ) {
+                (Value::Int(left), Value::Int(right)) => Ok(Value::Int(left.min(right))),
+                (left, right) => Ok(Value::Float(
+                    self.expect_number(left)?.min(self.expect_number(right)?),
+                )),
+            },
+            "max" => match (
+                self.take_arg(&mut positional, "max")?,
+                self.take_arg(&mut positional, "max")?,
+            ) {
+                (Value::Int(left), Value::Int(right)) => Ok(Value::Int(left.max(right))),
+                (left, right) => Ok(Value::Float(
+                    self.expect_number(left)?.max(self.expect_number(right)?),
+                )),
+            },
             "std.assert.ok" => {
                 let value = self.take_arg(&mut positional, "std.assert.ok")?;
                 if self.is_truthy(&value) {
@@ -950,6 +1124,145 @@ impl Interpreter {
                     self.expect_string(self.take_arg(&mut positional, "string_to_upper")?)?;
                 Ok(Value::String(text.to_uppercase()))
             }
+            "std.intrinsics.string_chars" => {
+                let text = self.expect_string(self.take_arg(&mut positional, "string_chars")?)?;
+                let parts = tea_intrinsics::string::chars(&text)
+                    .into_iter()
+                    .map(Value::String)
+                    .collect();
+                Ok(Value::List(Rc::new(RefCell::new(parts))))
+            }
+            "std.intrinsics.string_graphemes" => {
+                let text =
+                    self.expect_string(self.take_arg(&mut positional, "string_graphemes")?)?;
+                let parts = tea_intrinsics::string::graphemes(&text)
+                    .into_iter()
+                    .map(Value::String)
+                    .collect();
+                Ok(Value::List(Rc::new(RefCell::new(parts))))
+            }
+            "std.intrinsics.string_code_points" => {
+                let text =
+                    self.expect_string(self.take_arg(&mut positional, "string_code_points")?)?;
+                let points = tea_intrinsics::string::code_points(&text)
+                    .into_iter()
+                    .map(Value::Int)
+                    .collect();
+                Ok(Value::List(Rc::new(RefCell::new(points))))
+            }
+            "std.intrinsics.string_byte_len" => {
+                let text =
+                    self.expect_string(self.take_arg(&mut positional, "string_byte_len")?)?;
+                Ok(Value::Int(text.len() as i64))
+            }
+            "std.intrinsics.string_display_width" => {
+                let text =
+                    self.expect_string(self.take_arg(&mut positional, "string_display_width")?)?;
+                Ok(Value::Int(tea_intrinsics::string::display_width(&text)))
+            }
+            "std.intrinsics.string_fold_case" => {
+                let text =
+                    self.expect_string(self.take_arg(&mut positional, "string_fold_case")?)?;
+                Ok(Value::String(tea_intrinsics::string::fold_case(&text)))
+            }
+            "std.intrinsics.string_normalize_nfc" => {
+                let text =
+                    self.expect_string(self.take_arg(&mut positional, "string_normalize_nfc")?)?;
+                Ok(Value::String(tea_intrinsics::string::normalize_nfc(&text)))
+            }
+            "std.intrinsics.string_normalize_nfd" => {
+                let text =
+                    self.expect_string(self.take_arg(&mut positional, "string_normalize_nfd")?)?;
+                Ok(Value::String(tea_intrinsics::string::normalize_nfd(&text)))
+            }
+            "std.intrinsics.string_is_alpha" => {
+                let text =
+                    self.expect_string(self.take_arg(&mut positional, "string_is_alpha")?)?;
+                Ok(Value::Bool(tea_intrinsics::string::is_alpha(&text)))
+            }
+            "std.intrinsics.string_is_digit" => {
+                let text =
+                    self.expect_string(self.take_arg(&mut positional, "string_is_digit")?)?;
+                Ok(Value::Bool(tea_intrinsics::string::is_digit(&text)))
+            }
+            "std.intrinsics.string_is_whitespace" => {
+                let text =
+                    self.expect_string(self.take_arg(&mut positional, "string_is_whitespace")?)?;
+                Ok(Value::Bool(tea_intrinsics::string::is_whitespace(&text)))
+            }
+            "std.intrinsics.math_sin" => {
+                let value = self.expect_number(self.take_arg(&mut positional, "math_sin")?)?;
+                Ok(Value::Float(tea_intrinsics::math::sin(value)))
+            }
+            "std.intrinsics.math_cos" => {
+                let value = self.expect_number(self.take_arg(&mut positional, "math_cos")?)?;
+                Ok(Value::Float(tea_intrinsics::math::cos(value)))
+            }
+            "std.intrinsics.math_tan" => {
+                let value = self.expect_number(self.take_arg(&mut positional, "math_tan")?)?;
+                Ok(Value::Float(tea_intrinsics::math::tan(value)))
+            }
+            "std.intrinsics.math_exp" => {
+                let value = self.expect_number(self.take_arg(&mut positional, "math_exp")?)?;
+                Ok(Value::Float(tea_intrinsics::math::exp(value)))
+            }
+            "std.intrinsics.math_ln" => {
+                let value = self.expect_number(self.take_arg(&mut positional, "math_ln")?)?;
+                Ok(Value::Float(tea_intrinsics::math::ln(value)))
+            }
+            "std.intrinsics.math_log10" => {
+                let value = self.expect_number(self.take_arg(&mut positional, "math_log10")?)?;
+                Ok(Value::Float(tea_intrinsics::math::log10(value)))
+            }
+            "std.intrinsics.math_log2" => {
+                let value = self.expect_number(self.take_arg(&mut positional, "math_log2")?)?;
+                Ok(Value::Float(tea_intrinsics::math::log2(value)))
+            }
+            "std.intrinsics.math_atan2" => {
+                let left = self.expect_number(self.take_arg(&mut positional, "math_atan2")?)?;
+                let right = self.expect_number(self.take_arg(&mut positional, "math_atan2")?)?;
+                Ok(Value::Float(tea_intrinsics::math::atan2(left, right)))
+            }
+            "std.intrinsics.math_pow" => {
+                let left = self.expect_number(self.take_arg(&mut positional, "math_pow")?)?;
+                let right = self.expect_number(self.take_arg(&mut positional, "math_pow")?)?;
+                Ok(Value::Float(tea_intrinsics::math::pow(left, right)))
+            }
+            "std.intrinsics.math_hypot" => {
+                let left = self.expect_number(self.take_arg(&mut positional, "math_hypot")?)?;
+                let right = self.expect_number(self.take_arg(&mut positional, "math_hypot")?)?;
+                Ok(Value::Float(tea_intrinsics::math::hypot(left, right)))
+            }
+            "std.intrinsics.math_is_nan" => {
+                let value = self.expect_number(self.take_arg(&mut positional, "math_is_nan")?)?;
+                Ok(Value::Bool(tea_intrinsics::math::is_nan(value)))
+            }
+            "std.intrinsics.math_is_finite" => {
+                let value =
+                    self.expect_number(self.take_arg(&mut positional, "math_is_finite")?)?;
+                Ok(Value::Bool(tea_intrinsics::math::is_finite(value)))
+            }
+            "std.intrinsics.random_seeded" => {
+                let seed = self.expect_int(self.take_arg(&mut positional, "random_seeded")?)?;
+                let handle = self.random_generators.keys().max().copied().unwrap_or(0) + 1;
+                self.random_generators
+                    .insert(handle, tea_intrinsics::random::Generator::from_seed(seed));
+                Ok(Value::Int(handle))
+            }
+            "std.intrinsics.random_int" => {
+                let handle = self.expect_int(self.take_arg(&mut positional, "random_int")?)?;
+                let low = self.expect_int(self.take_arg(&mut positional, "random_int")?)?;
+                let high = self.expect_int(self.take_arg(&mut positional, "random_int")?)?;
+                let value = self
+                    .random_generator(handle)?
+                    .int_in(low, high)
+                    .map_err(|message| anyhow!(message))?;
+                Ok(Value::Int(value))
+            }
+            "std.intrinsics.random_float" => {
+                let handle = self.expect_int(self.take_arg(&mut positional, "random_float")?)?;
+                Ok(Value::Float(self.random_generator(handle)?.float()))
+            }
             "std.intrinsics.json_encode" => {
                 let value = self.take_arg(&mut positional, "json_encode")?;
                 Ok(Value::String(self.value_to_json(&value)?.to_string()))
@@ -962,6 +1275,294 @@ impl Interpreter {
         }
     }
 
+    fn call_list_method(
+        &mut self,
+        items: &Rc<RefCell<Vec<Value>>>,
+        method: &str,
+        arguments: VecDeque<Value>,
+    ) -> Result<Value> {
+        let mut arguments = arguments;
+        let values = items.borrow().clone();
+        let new_list = |values: Vec<Value>| Value::List(Rc::new(RefCell::new(values)));
+        match method {
+            "map" => {
+                let transform = self.take_arg(&mut arguments, "List.map")?;
+                let mut mapped = Vec::with_capacity(values.len());
+                for value in values {
+                    mapped.push(self.call_value(&transform, vec![value])?);
+                }
+                Ok(new_list(mapped))
+            }
+            "filter" => {
+                let keep = self.take_arg(&mut arguments, "List.filter")?;
+                let mut kept = Vec::new();
+                for value in values {
+                    if self.call_predicate(&keep, &value)? {
+                        kept.push(value);
+                    }
+                }
+                Ok(new_list(kept))
+            }
+            "reduce" => {
+                let mut accumulator = self.take_arg(&mut arguments, "List.reduce")?;
+                let combine = self.take_arg(&mut arguments, "List.reduce")?;
+                for value in values {
+                    accumulator = self.call_value(&combine, vec![accumulator, value])?;
+                }
+                Ok(accumulator)
+            }
+            "find" | "find_index" | "any" | "all" => {
+                let predicate = self.take_arg(&mut arguments, method)?;
+                let mut found = None;
+                for (index, value) in values.into_iter().enumerate() {
+                    if self.call_predicate(&predicate, &value)? != (method == "all") {
+                        found = Some((index, value));
+                        break;
+                    }
+                }
+                Ok(match method {
+                    "find" => found.map(|(_, value)| value).unwrap_or(Value::Nil),
+                    "find_index" => Value::Int(found.map(|(index, _)| index as i64).unwrap_or(-1)),
+                    "any" => Value::Bool(found.is_some()),
+                    _ => Value::Bool(found.is_none()),
+                })
+            }
+            "contains" | "index_of" => {
+                let needle = self.take_arg(&mut arguments, method)?;
+                let position = values
+                    .iter()
+                    .position(|value| self.values_equal(value, &needle));
+                if method == "contains" {
+                    Ok(Value::Bool(position.is_some()))
+                } else {
+                    Ok(Value::Int(position.map(|index| index as i64).unwrap_or(-1)))
+                }
+            }
+            "take" | "skip" => {
+                let count = self.expect_int(self.take_arg(&mut arguments, method)?)?;
+                let count = count.clamp(0, values.len() as i64) as usize;
+                Ok(new_list(if method == "take" {
+                    values[..count].to_vec()
+                } else {
+                    values[count..].to_vec()
+                }))
+            }
+            "sort" | "sorted" => {
+                let mut sorted = values;
+                let mut failure = None;
+                sorted.sort_by(|left, right| {
+                    self.natural_order(left, right).unwrap_or_else(|error| {
+                        failure.get_or_insert(error);
+                        std::cmp::Ordering::Equal
+                    })
+                });
+                if let Some(error) = failure {
+                    return Err(error);
+                }
+                self.reordered_list(items, method == "sort", sorted)
+            }
+            "sort_by" | "sorted_by" => {
+                let compare = self.take_arg(&mut arguments, method)?;
+                let mut sorted = values;
+                let mut failure = None;
+                sorted.sort_by(|left, right| {
+                    if failure.is_some() {
+                        return std::cmp::Ordering::Equal;
+                    }
+                    match self
+                        .call_value(&compare, vec![left.clone(), right.clone()])
+                        .and_then(|ordering| self.expect_int(ordering))
+                    {
+                        Ok(ordering) => ordering.cmp(&0),
+                        Err(error) => {
+                            failure = Some(error);
+                            std::cmp::Ordering::Equal
+                        }
+                    }
+                });
+                if let Some(error) = failure {
+                    return Err(error);
+                }
+                self.reordered_list(items, method == "sort_by", sorted)
+            }
+            "reverse" | "reversed" => {
+                let mut reversed = values;
+                reversed.reverse();
+                self.reordered_list(items, method == "reverse", reversed)
+            }
+            "unique" => {
+                let mut unique: Vec<Value> = Vec::new();
+                for value in values {
+                    if !unique.iter().any(|seen| self.values_equal(seen, &value)) {
+                        unique.push(value);
+                    }
+                }
+                Ok(new_list(unique))
+            }
+            "group_by" => {
+                let key_of = self.take_arg(&mut arguments, "List.group_by")?;
+                let mut groups: BTreeMap<String, Value> = BTreeMap::new();
+                for value in values {
+                    let key = self.call_value(&key_of, vec![value.clone()])?;
+                    let key = self.expect_string(key)?;
+                    if let Value::List(group) =
+                        groups.entry(key).or_insert_with(|| new_list(vec![]))
+                    {
+                        group.borrow_mut().push(value);
+                    }
+                }
+                Ok(Value::Dict(Rc::new(RefCell::new(groups))))
+            }
+            "partition" => {
+                let keep = self.take_arg(&mut arguments, "List.partition")?;
+                let mut matching = Vec::new();
+                let mut rest = Vec::new();
+                for value in values {
+                    if self.call_predicate(&keep, &value)? {
+                        matching.push(value);
+                    } else {
+                        rest.push(value);
+                    }
+                }
+                Ok(new_list(vec![new_list(matching), new_list(rest)]))
+            }
+            "zip" => {
+                let other = match self.take_arg(&mut arguments, "List.zip")? {
+                    Value::List(other) => other.borrow().clone(),
+                    _ => bail!("List.zip expects a List as its first argument"),
+                };
+                let combine = self.take_arg(&mut arguments, "List.zip")?;
+                let mut combined = Vec::new();
+                for (left, right) in values.into_iter().zip(other) {
+                    combined.push(self.call_value(&combine, vec![left, right])?);
+                }
+                Ok(new_list(combined))
+            }
+            "flat_map" => {
+                let transform = self.take_arg(&mut arguments, "List.flat_map")?;
+                let mut flattened = Vec::new();
+                for value in values {
+                    match self.call_value(&transform, vec![value])? {
+                        Value::List(mapped) => flattened.extend(mapped.borrow().iter().cloned()),
+                        _ => bail!("List.flat_map callback must return a List"),
+                    }
+                }
+                Ok(new_list(flattened))
+            }
+            "chunk" | "window" => {
+                let size = self.expect_int(self.take_arg(&mut arguments, method)?)?;
+                if size <= 0 {
+                    bail!("List.{method} size must be positive");
+                }
+                let groups: Vec<Value> = if method == "chunk" {
+                    values
+                        .chunks(size as usize)
+                        .map(|chunk| new_list(chunk.to_vec()))
+                        .collect()
+                } else {
+                    values
+                        .windows(size as usize)
+                        .map(|window| new_list(window.to_vec()))
+                        .collect()
+                };
+                Ok(new_list(groups))
+            }
+            "join" => {
+                let separator = self.expect_string(self.take_arg(&mut arguments, "List.join")?)?;
+                let parts = values
+                    .into_iter()
+                    .map(|value| self.expect_string(value))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(Value::String(parts.join(&separator)))
+            }
+            "sum" => {
+                let mut total = Value::Int(0);
+                for value in values {
+                    total = match (total, value) {
+                        (Value::Int(total), Value::Int(value)) => {
+                            Value::Int(total.wrapping_add(value))
+                        }
+                        (total, Value::Float(value)) => {
+                            Value::Float(self.expect_number(total)? + value)
+                        }
+                        _ => bail!("List.sum expects a List of Int or Float"),
+                    };
+                }
+                Ok(total)
+            }
+            "min" | "max" => {
+                let mut best: Option<Value> = None;
+                for value in values {
+                    best = Some(match best {
+                        None => value,
+                        Some(current) => {
+                            let ordering = self.natural_order(&value, &current)?;
+                            let replace = if method == "max" {
+                                ordering.is_ge()
+                            } else {
+                                ordering.is_lt()
+                            };
+                            if replace {
+                                value
+                            } else {
+                                current
+                            }
+                        }
+                    });
+                }
+                Ok(best.unwrap_or(Value::Nil))
+            }
+            "insert" => {
+                let index = self.expect_int(self.take_arg(&mut arguments, "List.insert")?)?;
+                let value = self.take_arg(&mut arguments, "List.insert")?;
+                let mut items = items.borrow_mut();
+                if index < 0 || index as usize > items.len() {
+                    bail!("index out of bounds");
+                }
+                items.insert(index as usize, value);
+                Ok(Value::Nil)
+            }
+            "remove_at" => {
+                let index = self.expect_int(self.take_arg(&mut arguments, "List.remove_at")?)?;
+                let index = self.normalize_index(index, values.len())?;
+                Ok(items.borrow_mut().remove(index))
+            }
+            "pop" => Ok(items.borrow_mut().pop().unwrap_or(Value::Nil)),
+            _ => bail!("List has no method named '{method}'"),
+        }
+    }
+
+    fn call_predicate(&mut self, predicate: &Value, value: &Value) -> Result<bool> {
+        let result = self.call_value(predicate, vec![value.clone()])?;
+        Ok(self.is_truthy(&result))
+    }
+
+    /// Store a reordered list back in place, or return it as a new list for the copying
+    /// variants.
+    fn reordered_list(
+        &self,
+        items: &Rc<RefCell<Vec<Value>>>,
+        in_place: bool,
+        values: Vec<Value>,
+    ) -> Result<Value> {
+        if in_place {
+            *items.borrow_mut() = values;
+            Ok(Value::Nil)
+        } else {
+            Ok(Value::List(Rc::new(RefCell::new(values))))
+        }
+    }
+
+    /// Natural ordering used by `List.sort`, `List.min` and `List.max`.
+    fn natural_order(&self, left: &Value, right: &Value) -> Result<std::cmp::Ordering> {
+        match (left, right) {
+            (Value::Int(left), Value::Int(right)) => Ok(left.cmp(right)),
+            (Value::Float(left), Value::Float(right)) => Ok(left.total_cmp(right)),
+            (Value::String(left), Value::String(right)) => Ok(left.cmp(right)),
+            _ => bail!("list items are not comparable"),
+        }
+    }
+
     fn bind_parameters(
         &mut self,
         env: &Rc<Environment>,
@@ -970,7 +1571,10 @@ impl Interpreter {
         named: &HashMap<String, Value>,
     ) -> Result<()> {
         for parameter in parameters {
-            let value = if let Some(value) = named.get(&parameter.name) {
+            let value = if parameter.variadic {
+                let rest = positional.drain(..).collect();
+                Value::List(Rc::new(RefCell::new(rest)))
+            } else if let Some(value) = named.get(&parameter.name) {
                 value.clone()
             } else if let Some(value) = positional.pop_front() {
                 value
@@ -1003,6 +1607,10 @@ impl Interpreter {
         let mut positional = VecDeque::new();
         let mut named = HashMap::new();
         for argument in arguments {
+            if argument.spread {
+                // `..base` values are consumed by `construct_struct`.
+                continue;
+            }
             let value = self.evaluate_expression(env, &argument.expression)?;
             if let Some(name) = &argument.name {
                 named.insert(name.clone(), value);
@@ -1025,6 +1633,18 @@ impl Interpreter {
             .get(name)
             .cloned()
             .ok_or_else(|| anyhow!("unknown struct '{name}'"))?;
+        let base = match arguments.iter().find(|argument| argument.spread) {
+            Some(argument) => match self.evaluate_expression(env, &argument.expression)? {
+                Value::Struct(base) if base.borrow().name == definition.name => {
+                    Some(base.borrow().fields.clone())
+                }
+                other => bail!(
+                    "'..' base for struct '{name}' must be a {name} value, found {}",
+                    other.type_name()
+                ),
+            },
+            None => None,
+        };
         let (mut positional, named) = self.evaluate_arguments(env, arguments)?;
         let mut fields = BTreeMap::new();
 
@@ -1033,6 +1653,11 @@ impl Interpreter {
                 value.clone()
             } else if let Some(value) = positional.pop_front() {
                 value
+            } else if let Some(value) = base.as_ref().and_then(|base| base.get(field)) {
+                value.clone()
+            } else if let Some(default) = definition.defaults.get(field) {
+                let globals = self.globals.clone();
+                self.evaluate_expression(&globals, default)?
             } else {
                 bail!("missing field '{field}' for struct '{name}'")
             };
@@ -1146,6 +1771,110 @@ impl Interpreter {
                     "string_to_upper".into(),
                     self.native_function_value("std.intrinsics.string_to_upper"),
                 ),
+                (
+                    "string_chars".into(),
+                    self.native_function_value("std.intrinsics.string_chars"),
+                ),
+                (
+                    "string_graphemes".into(),
+                    self.native_function_value("std.intrinsics.string_graphemes"),
+                ),
+                (
+                    "string_code_points".into(),
+                    self.native_function_value("std.intrinsics.string_code_points"),
+                ),
+                (
+                    "string_byte_len".into(),
+                    self.native_function_value("std.intrinsics.string_byte_len"),
+                ),
+                (
+                    "string_display_width".into(),
+                    self.native_function_value("std.intrinsics.string_display_width"),
+                ),
+                (
+                    "string_fold_case".into(),
+                    self.native_function_value("std.intrinsics.string_fold_case"),
+                ),
+                (
+                    "string_normalize_nfc".into(),
+                    self.native_function_value("std.intrinsics.string_normalize_nfc"),
+                ),
+                (
+                    "string_normalize_nfd".into(),
+                    self.native_function_value("std.intrinsics.string_normalize_nfd"),
+                ),
+                (
+                    "string_is_alpha".into(),
+                    self.native_function_value("std.intrinsics.string_is_alpha"),
+                ),
+                (
+                    "string_is_digit".into(),
+                    self.native_function_value("std.intrinsics.string_is_digit"),
+                ),
+                (
+                    "string_is_whitespace".into(),
+                    self.native_function_value("std.intrinsics.string_is_whitespace"),
+                ),
+                (
+                    "math_sin".into(),
+                    self.native_function_value("std.intrinsics.math_sin"),
+                ),
+                (
+                    "math_cos".into(),
+                    self.native_function_value("std.intrinsics.math_cos"),
+                ),
+                (
+                    "math_tan".into(),
+                    self.native_function_value("std.intrinsics.math_tan"),
+                ),
+                (
+                    "math_exp".into(),
+                    self.native_function_value("std.intrinsics.math_exp"),
+                ),
+                (
+                    "math_ln".into(),
+                    self.native_function_value("std.intrinsics.math_ln"),
+                ),
+                (
+                    "math_log10".into(),
+                    self.native_function_value("std.intrinsics.math_log10"),
+                ),
+                (
+                    "math_log2".into(),
+                    self.native_function_value("std.intrinsics.math_log2"),
+                ),
+                (
+                    "math_atan2".into(),
+                    self.native_function_value("std.intrinsics.math_atan2"),
+                ),
+                (
+                    "math_pow".into(),
+                    self.native_function_value("std.intrinsics.math_pow"),
+                ),
+                (
+                    "math_hypot".into(),
+                    self.native_function_value("std.intrinsics.math_hypot"),
+                ),
+                (
+                    "math_is_nan".into(),
+                    self.native_function_value("std.intrinsics.math_is_nan"),
+                ),
+                (
+                    "math_is_finite".into(),
+                    self.native_function_value("std.intrinsics.math_is_finite"),
+                ),
+                (
+                    "random_seeded".into(),
+                    self.native_function_value("std.intrinsics.random_seeded"),
+                ),
+                (
+                    "random_int".into(),
+                    self.native_function_value("std.intrinsics.random_int"),
+                ),
+                (
+                    "random_float".into(),
+                    self.native_function_value("std.intrinsics.random_float"),
+                ),
                 (
                     "json_encode".into(),
                     self.native_function_value("std.intrinsics.json_encode"),
@@ -1159,6 +1888,21 @@ impl Interpreter {
         }
     }
 
+    fn random_generator(&mut self, handle: i64) -> Result<&mut tea_intrinsics::random::Generator> {
+        if handle == 0 {
+            let seed = self.random_seed;
+            return Ok(self.random_generators.entry(0).or_insert_with(|| {
+                seed.map_or_else(
+                    tea_intrinsics::random::Generator::from_os,
+                    tea_intrinsics::random::Generator::from_seed,
+                )
+            }));
+        }
+        self.random_generators
+            .get_mut(&handle)
+            .ok_or_else(|| anyhow!("invalid random generator handle {handle}"))
+    }
+
     fn native_function_value(&self, name: &str) -> Value {
         Value::Function(Rc::new(FunctionValue::Native(NativeFunction {
             name: name.to_string(),
@@ -1184,14 +1928,25 @@ impl Interpreter {
                 .map(|(key, value)| (Some(Value::String(key.clone())), value.clone()))
                 .collect()),
             Value::Range(range) => {
-                let end = if range.inclusive {
-                    range.end + 1
-                } else {
-                    range.end
+                let (Some(start), Some(end)) = (range.start, range.end) else {
+                    bail!("ranges used outside of slices need both a start and an end");
+                };
+                if range.step == 0 {
+                    bail!("range step cannot be zero");
+                }
+                let mut values = Vec::new();
+                let mut value = start;
+                let in_range = |value: i64| match (range.step > 0, range.inclusive) {
+                    (true, true) => value <= end,
+                    (true, false) => value < end,
+                    (false, true) => value >= end,
+                    (false, false) => value > end,
                 };
-                Ok((range.start..end)
-                    .map(|value| (None, Value::Int(value)))
-                    .collect())
+                while in_range(value) {
+                    values.push((None, Value::Int(value)));
+                    value += range.step;
+                }
+                Ok(values)
             }
             _ => bail!("value is not iterable"),
         }
@@ -1227,9 +1982,12 @@ impl Interpreter {
             }
             (Value::List(items), Value::Range(range)) => {
                 let list = items.borrow();
-                let (start, end) = self.slice_bounds(range, list.len())?;
+                let positions = self.slice_positions(&range, list.len())?;
                 Ok(Value::List(Rc::new(RefCell::new(
-                    list[start..end].to_vec(),
+                    positions
+                        .into_iter()
+                        .map(|index| list[index].clone())
+                        .collect(),
                 ))))
             }
             (Value::String(text), Value::Int(index)) => {
@@ -1239,8 +1997,10 @@ impl Interpreter {
             }
             (Value::String(text), Value::Range(range)) => {
                 let chars = text.chars().collect::<Vec<_>>();
-                let (start, end) = self.slice_bounds(range, chars.len())?;
-                Ok(Value::String(chars[start..end].iter().collect()))
+                let positions = self.slice_positions(&range, chars.len())?;
+                Ok(Value::String(
+                    positions.into_iter().map(|index| chars[index]).collect(),
+                ))
             }
             (Value::Dict(entries), Value::String(key)) => {
                 Ok(entries.borrow().get(&key).cloned().unwrap_or(Value::Nil))
@@ -1249,42 +2009,13 @@ impl Interpreter {
         }
     }
 
-    fn slice_bounds(&self, range: RangeValue, len: usize) -> Result<(usize, usize)> {
-        let start = self.normalize_index_allow_end(range.start, len)?;
-        let end = self.normalize_index_allow_end(
-            if range.inclusive {
-                range.end + 1
-            } else {
-                range.end
-            },
-            len,
-        )?;
-        if start > end {
-            return Ok((start, start));
-        }
-        Ok((start, end))
+    fn slice_positions(&self, range: &RangeValue, len: usize) -> Result<Vec<usize>> {
+        slice_positions(len, range.start, range.end, range.inclusive, range.step)
+            .map_err(|message| anyhow!(message))
     }
 
     fn normalize_index(&self, index: i64, len: usize) -> Result<usize> {
-        if index < 0 {
-            bail!("negative indexes are not supported")
-        }
-        let index = index as usize;
-        if index >= len {
-            bail!("index out of bounds")
-        }
-        Ok(index)
-    }
-
-    fn normalize_index_allow_end(&self, index: i64, len: usize) -> Result<usize> {
-        if index < 0 {
-            bail!("negative indexes are not supported")
-        }
-        let index = index as usize;
-        if index > len {
-            bail!("slice bound out of range")
-        }
-        Ok(index)
+        resolve_index(index, len).ok_or_else(|| anyhow!("index out of bounds"))
     }
 
     fn add_values(&self, left: Value, right: Value) -> Result<Value> {
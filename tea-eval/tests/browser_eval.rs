@@ -62,12 +62,23 @@ fn browser_eval_handles_json_decode_and_fuel_limits() {
 use json from "std.json"
 
 @println(json.encode([1, 2, 3]))
+const values = json.decode[List[Int]]("[4, 5]")
+@println(values[1] + 1)
 "#,
     );
 
-    let output = evaluate(&compilation, EvalOptions { fuel: 10_000 });
+    let output = evaluate(
+        &compilation,
+        EvalOptions {
+            fuel: 10_000,
+            ..EvalOptions::default()
+        },
+    );
     assert_eq!(output.runtime_error, None);
-    assert_eq!(output.stdout, vec!["[1,2,3]\n".to_string()]);
+    assert_eq!(
+        output.stdout,
+        vec!["[1,2,3]\n".to_string(), "6\n".to_string()]
+    );
 
     let loop_compilation = compile_browser_source(
         r#"
@@ -75,9 +86,201 @@ while true
 end
 "#,
     );
-    let loop_output = evaluate(&loop_compilation, EvalOptions { fuel: 32 });
+    let loop_output = evaluate(
+        &loop_compilation,
+        EvalOptions {
+            fuel: 32,
+            ..EvalOptions::default()
+        },
+    );
     assert_eq!(
         loop_output.runtime_error,
         Some("execution limit reached".to_string())
     );
 }
+
+#[test]
+fn browser_eval_supports_negative_open_and_stepped_slices() {
+    let compilation = compile_browser_source(
+        r#"
+var numbers = [1, 2, 3, 4, 5]
+@println(numbers[-1])
+@println(numbers[-3..])
+@println(numbers[..2])
+@println(numbers[.. by -2])
+@println("teapot"[1...-2])
+numbers[1..3] = [9]
+@println(numbers)
+"#,
+    );
+
+    let output = evaluate(&compilation, EvalOptions::default());
+    assert_eq!(output.runtime_error, None);
+    assert_eq!(
+        output.stdout,
+        vec![
+            "5\n".to_string(),
+            "[3, 4, 5]\n".to_string(),
+            "[1, 2]\n".to_string(),
+            "[5, 3, 1]\n".to_string(),
+            "eapo\n".to_string(),
+            "[1, 9, 4, 5]\n".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn browser_eval_supports_optional_bindings_and_guards() {
+    let compilation = compile_browser_source(
+        r#"
+def lookup(items: List[Int], position: Int) -> Int?
+  if position < @len(items)
+    return items[position]
+  end
+  return nil
+end
+
+def describe(items: List[Int], position: Int) -> String
+  guard var item = lookup(items, position) else
+    return "missing"
+  end
+  return `item ${item}`
+end
+
+var position = 0
+while var item = lookup([1, 2], position)
+  @println(item)
+  position = position + 1
+end
+
+if var found = lookup([9], 0)
+  @println(found)
+end
+
+@println(describe([5], 0))
+@println(describe([5], 1))
+"#,
+    );
+
+    let output = evaluate(&compilation, EvalOptions::default());
+    assert_eq!(output.runtime_error, None);
+    assert_eq!(
+        output.stdout,
+        vec![
+            "1\n".to_string(),
+            "2\n".to_string(),
+            "9\n".to_string(),
+            "item 5\n".to_string(),
+            "missing\n".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn browser_eval_supports_struct_defaults_and_updates() {
+    let compilation = compile_browser_source(
+        r#"
+struct Options {
+  name: String
+  retries: Int = 3
+  verbose: Bool = false
+}
+
+const base = Options(name: "base")
+const updated = Options(..base, verbose: true)
+@println(`${base.name} ${base.retries} ${base.verbose}`)
+@println(`${updated.name} ${updated.retries} ${updated.verbose}`)
+@println(Options("positional").retries)
+"#,
+    );
+
+    let output = evaluate(&compilation, EvalOptions::default());
+    assert_eq!(output.runtime_error, None);
+    assert_eq!(
+        output.stdout,
+        vec![
+            "base 3 false\n".to_string(),
+            "base 3 true\n".to_string(),
+            "3\n".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn browser_eval_supports_default_and_variadic_parameters() {
+    let compilation = compile_browser_source(
+        r#"
+def greet(name: String, greeting: String = "Hello") -> String
+  `${greeting}, ${name}`
+end
+
+def count(label: String, ...items: Int) -> String
+  `${label} ${@len(items)}`
+end
+
+@println(greet("Tea"))
+@println(greet(greeting: "Hi", name: "Tea"))
+@println(count("none"))
+@println(count("some", 1, 2, 3))
+"#,
+    );
+
+    let output = evaluate(&compilation, EvalOptions::default());
+    assert_eq!(output.runtime_error, None);
+    assert_eq!(
+        output.stdout,
+        vec![
+            "Hello, Tea\n".to_string(),
+            "Hi, Tea\n".to_string(),
+            "none 0\n".to_string(),
+            "some 3\n".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn browser_eval_seeds_shared_random_generator_from_host() {
+    let compilation = compile_browser_source(
+        r#"
+use random from "std.random"
+
+@println(random.int_in(1, 1000000))
+"#,
+    );
+    let run = |seed: i64| {
+        evaluate(
+            &compilation,
+            EvalOptions {
+                random_seed: Some(seed),
+                ..EvalOptions::default()
+            },
+        )
+        .stdout
+    };
+    assert_eq!(run(7), run(7));
+    assert_ne!(run(7), run(8));
+}
+
+/// Programs under `tea-compiler/tests/conformance` must print the same output in the
+/// browser runner as in native builds.
+#[test]
+fn browser_eval_matches_conformance_programs() {
+    let directory =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../tea-compiler/tests/conformance");
+    let mut programs = std::fs::read_dir(&directory)
+        .expect("read conformance directory")
+        .map(|entry| entry.expect("conformance entry").path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "tea"))
+        .collect::<Vec<_>>();
+    programs.sort();
+    assert!(!programs.is_empty(), "expected conformance programs");
+
+    for program in programs {
+        let source = std::fs::read_to_string(&program).expect("read conformance program");
+        let expected = std::fs::read_to_string(program.with_extension("stdout"))
+            .expect("read expected stdout");
+        let output = evaluate(&compile_browser_source(&source), EvalOptions::default());
+        assert_eq!(output.runtime_error, None, "{}", program.display());
+        assert_eq!(output.stdout.concat(), expected, "{}", program.display());
+    }
+}
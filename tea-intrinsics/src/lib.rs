@@ -2,4 +2,5 @@ pub mod env;
 pub mod fs;
 pub mod math;
 pub mod path;
+pub mod random;
 pub mod string;
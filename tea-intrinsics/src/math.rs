@@ -32,3 +32,63 @@ pub fn min(a: f64, b: f64) -> f64 {
 pub fn max(a: f64, b: f64) -> f64 {
     a.max(b)
 }
+
+/// Returns the sine of an angle in radians
+pub fn sin(angle: f64) -> f64 {
+    angle.sin()
+}
+
+/// Returns the cosine of an angle in radians
+pub fn cos(angle: f64) -> f64 {
+    angle.cos()
+}
+
+/// Returns the tangent of an angle in radians
+pub fn tan(angle: f64) -> f64 {
+    angle.tan()
+}
+
+/// Returns the angle of the point (x, y) from the positive x axis
+pub fn atan2(y: f64, x: f64) -> f64 {
+    y.atan2(x)
+}
+
+/// Returns e raised to the given power
+pub fn exp(value: f64) -> f64 {
+    value.exp()
+}
+
+/// Returns the natural logarithm of a float
+pub fn ln(value: f64) -> f64 {
+    value.ln()
+}
+
+/// Returns the base-10 logarithm of a float
+pub fn log10(value: f64) -> f64 {
+    value.log10()
+}
+
+/// Returns the base-2 logarithm of a float
+pub fn log2(value: f64) -> f64 {
+    value.log2()
+}
+
+/// Raises a float to a floating-point power
+pub fn pow(base: f64, exponent: f64) -> f64 {
+    base.powf(exponent)
+}
+
+/// Returns the Euclidean distance from the origin to (x, y)
+pub fn hypot(x: f64, y: f64) -> f64 {
+    x.hypot(y)
+}
+
+/// Returns true when a float is NaN
+pub fn is_nan(value: f64) -> bool {
+    value.is_nan()
+}
+
+/// Returns true when a float is neither infinite nor NaN
+pub fn is_finite(value: f64) -> bool {
+    value.is_finite()
+}
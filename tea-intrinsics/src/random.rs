@@ -0,0 +1,80 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// A xoshiro256** pseudo-random generator.
+///
+/// The same seed produces the same sequence on every platform, so native builds and the
+/// browser evaluator agree on seeded output.
+#[derive(Debug, Clone)]
+pub struct Generator {
+    state: [u64; 4],
+}
+
+impl Generator {
+    /// Creates a generator whose sequence is fully determined by `seed`
+    pub fn from_seed(seed: i64) -> Self {
+        let mut mix = seed as u64;
+        let mut state = [0; 4];
+        for word in &mut state {
+            *word = splitmix64(&mut mix);
+        }
+        Self { state }
+    }
+
+    /// Creates a generator seeded from the process's hash randomness. On
+    /// wasm32-unknown-unknown the hash keys are fixed, so browser hosts seed the
+    /// shared generator with `from_seed` and entropy from `crypto.getRandomValues`.
+    pub fn from_os() -> Self {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(0x7465_615f_7261_6e64);
+        Self::from_seed(hasher.finish() as i64)
+    }
+
+    /// Returns the next 64 random bits
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let shifted = self.state[1] << 17;
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= shifted;
+        self.state[3] = self.state[3].rotate_left(45);
+        result
+    }
+
+    /// Returns a uniformly distributed integer in `low..=high`
+    pub fn int_in(&mut self, low: i64, high: i64) -> Result<i64, String> {
+        if low > high {
+            return Err(format!(
+                "random range is empty: {low} is greater than {high}"
+            ));
+        }
+        let span = (high as i128 - low as i128 + 1) as u128;
+        if span > u64::MAX as u128 {
+            return Ok(self.next_u64() as i64);
+        }
+        let span = span as u64;
+        // Reject the few lowest outputs so every value in the span is equally likely.
+        let threshold = span.wrapping_neg() % span;
+        loop {
+            let value = self.next_u64();
+            if value >= threshold {
+                return Ok((low as i128 + (value % span) as i128) as i64);
+            }
+        }
+    }
+
+    /// Returns a uniformly distributed float in `[0, 1)`
+    pub fn float(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -1,7 +1,14 @@
-/// Finds the index of the first occurrence of a substring
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Finds the character index of the first occurrence of a substring
 /// Returns -1 if not found
 pub fn index_of(haystack: &str, needle: &str) -> i64 {
-    haystack.find(needle).map(|i| i as i64).unwrap_or(-1)
+    haystack
+        .find(needle)
+        .map(|offset| haystack[..offset].chars().count() as i64)
+        .unwrap_or(-1)
 }
 
 /// Splits a string by a delimiter
@@ -28,3 +35,60 @@ pub fn to_lower(text: &str) -> String {
 pub fn to_upper(text: &str) -> String {
     text.to_uppercase()
 }
+
+/// Splits a string into its Unicode scalar values
+pub fn chars(text: &str) -> Vec<String> {
+    text.chars().map(|ch| ch.to_string()).collect()
+}
+
+/// Splits a string into extended grapheme clusters
+pub fn graphemes(text: &str) -> Vec<String> {
+    text.graphemes(true)
+        .map(|cluster| cluster.to_string())
+        .collect()
+}
+
+/// Returns the code point of every character in a string
+pub fn code_points(text: &str) -> Vec<i64> {
+    text.chars().map(|ch| ch as i64).collect()
+}
+
+/// Counts the Unicode scalar values in a string
+pub fn char_count(text: &str) -> i64 {
+    text.chars().count() as i64
+}
+
+/// Returns the number of terminal columns a string occupies
+pub fn display_width(text: &str) -> i64 {
+    text.width() as i64
+}
+
+/// Applies Unicode default case folding
+pub fn fold_case(text: &str) -> String {
+    caseless::default_case_fold_str(text)
+}
+
+/// Converts a string to Normalization Form C (composed)
+pub fn normalize_nfc(text: &str) -> String {
+    text.nfc().collect()
+}
+
+/// Converts a string to Normalization Form D (decomposed)
+pub fn normalize_nfd(text: &str) -> String {
+    text.nfd().collect()
+}
+
+/// Checks that a string is non-empty and every character is alphabetic
+pub fn is_alpha(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(char::is_alphabetic)
+}
+
+/// Checks that a string is non-empty and every character is numeric
+pub fn is_digit(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(char::is_numeric)
+}
+
+/// Checks that a string is non-empty and every character is whitespace
+pub fn is_whitespace(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(char::is_whitespace)
+}
@@ -5,8 +5,8 @@ use std::sync::Arc;
 use anyhow::{anyhow, Result};
 use tea_compiler::{
     CatchKind, CompileOptions, Compiler, Diagnostic as CompilerDiagnostic, DiagnosticLevel,
-    InterpolatedStringPart, Keyword, Lexer, MatchPattern, Module, ModuleAliasBinding,
-    ModuleExportKind, SourceFile, SourceId, Statement, TokenKind,
+    FormattedInterpolation, InterpolatedStringPart, Keyword, Lexer, MatchPattern, Module,
+    ModuleAliasBinding, ModuleExportKind, SourceFile, SourceId, Statement, TokenKind,
 };
 use tokio::{
     sync::Mutex,
@@ -895,8 +895,41 @@ fn collect_symbols(module: &Module, analysis: &tea_compiler::SemanticAnalysis) -
             }
         }
 
+        fn visit_optional_binding(&mut self, binding: &tea_compiler::OptionalBinding) {
+            let range = range_from_span!(&binding.span);
+            let type_desc = self.binding_types.get(&binding.span).cloned();
+            self.symbols.push(SymbolInfo {
+                name: binding.name.clone(),
+                range,
+                kind: SymbolKind::Variable,
+                type_desc,
+                docstring: None,
+            });
+        }
+
         fn visit_statement(&mut self, statement: &Statement) {
             match statement {
+                Statement::Use(use_stmt) if !use_stmt.items.is_empty() => {
+                    let binding = self.module_aliases.get(&use_stmt.alias.name);
+                    for item in &use_stmt.items {
+                        let range = range_from_span!(&item.span);
+                        let (kind, type_desc, docstring) = match binding {
+                            Some(binding) => (
+                                module_export_symbol_kind(binding, &item.name),
+                                binding.export_types.get(&item.name).cloned(),
+                                binding.export_docs.get(&item.name).cloned(),
+                            ),
+                            None => (SymbolKind::Variable, None, None),
+                        };
+                        self.symbols.push(SymbolInfo {
+                            name: item.alias.clone().unwrap_or_else(|| item.name.clone()),
+                            range,
+                            kind,
+                            type_desc,
+                            docstring,
+                        });
+                    }
+                }
                 Statement::Use(use_stmt) => {
                     let range = range_from_span!(&use_stmt.alias.span);
                     let docstring = self
@@ -991,6 +1024,9 @@ fn collect_symbols(module: &Module, analysis: &tea_compiler::SemanticAnalysis) -
                             type_desc: field_type,
                             docstring: field.docstring.clone(),
                         });
+                        if let Some(default_value) = &field.default_value {
+                            self.visit_expression(default_value);
+                        }
                     }
                 }
                 Statement::Union(union_stmt) => {
@@ -1035,6 +1071,9 @@ fn collect_symbols(module: &Module, analysis: &tea_compiler::SemanticAnalysis) -
                 }
                 Statement::Conditional(cond_stmt) => {
                     self.visit_expression(&cond_stmt.condition);
+                    if let Some(binding) = &cond_stmt.binding {
+                        self.visit_optional_binding(binding);
+                    }
                     self.visit_statements(&cond_stmt.consequent.statements);
                     if let Some(alternative) = &cond_stmt.alternative {
                         self.visit_statements(&alternative.statements);
@@ -1088,9 +1127,20 @@ fn collect_symbols(module: &Module, analysis: &tea_compiler::SemanticAnalysis) -
                         tea_compiler::LoopHeader::Condition(expr) => {
                             self.visit_expression(expr);
                         }
+                        tea_compiler::LoopHeader::Binding { binding, value } => {
+                            self.visit_expression(value);
+                            self.visit_optional_binding(binding);
+                        }
                     }
                     self.visit_statements(&loop_stmt.body.statements);
                 }
+                Statement::Guard(guard_stmt) => {
+                    self.visit_expression(&guard_stmt.condition);
+                    self.visit_statements(&guard_stmt.alternative.statements);
+                    if let Some(binding) = &guard_stmt.binding {
+                        self.visit_optional_binding(binding);
+                    }
+                }
                 Statement::Return(ret_stmt) => {
                     if let Some(expr) = &ret_stmt.expression {
                         self.visit_expression(expr);
@@ -1102,6 +1152,9 @@ fn collect_symbols(module: &Module, analysis: &tea_compiler::SemanticAnalysis) -
                 Statement::Throw(throw_stmt) => {
                     self.visit_expression(&throw_stmt.expression);
                 }
+                Statement::Yield(yield_stmt) => {
+                    self.visit_expression(&yield_stmt.expression);
+                }
                 Statement::Match(match_stmt) => {
                     self.visit_expression(&match_stmt.scrutinee);
                     for arm in &match_stmt.arms {
@@ -1126,7 +1179,12 @@ fn collect_symbols(module: &Module, analysis: &tea_compiler::SemanticAnalysis) -
                 ExpressionKind::Identifier(_) | ExpressionKind::Literal(_) => {}
                 ExpressionKind::InterpolatedString(template) => {
                     for part in &template.parts {
-                        if let InterpolatedStringPart::Expression(expr) = part {
+                        if let InterpolatedStringPart::Expression(expr)
+                        | InterpolatedStringPart::Formatted(FormattedInterpolation {
+                            expression: expr,
+                            ..
+                        }) = part
+                        {
                             self.visit_expression(expr);
                         }
                     }
@@ -1179,8 +1237,9 @@ fn collect_symbols(module: &Module, analysis: &tea_compiler::SemanticAnalysis) -
                     self.visit_expression(&expr.index);
                 }
                 ExpressionKind::Range(expr) => {
-                    self.visit_expression(&expr.start);
-                    self.visit_expression(&expr.end);
+                    for bound in [&expr.start, &expr.end, &expr.step].into_iter().flatten() {
+                        self.visit_expression(bound);
+                    }
                 }
                 ExpressionKind::Lambda(expr) => {
                     for parameter in &expr.parameters {
@@ -2346,6 +2405,7 @@ fn format_std_type(ty: &tea_compiler::StdType) -> &'static str {
         StdType::Int => "Int",
         StdType::Float => "Float",
         StdType::String => "String",
+        StdType::Bytes => "Bytes",
         StdType::List => "List",
         StdType::Dict => "Dict",
         StdType::Struct => "Struct",
@@ -2,30 +2,39 @@ mod cli;
 
 use crate::cli::{CliParseOutcome, CliScopeOutcome, RuntimeValue};
 use anyhow::{anyhow, Result};
+use base64::prelude::{Engine as _, BASE64_STANDARD};
 use dirs_next::{config_dir, home_dir};
 use glob::glob;
+use hmac::{Hmac, Mac};
+use md5::Md5;
 use percent_encoding::{percent_decode_str, utf8_percent_encode, NON_ALPHANUMERIC};
 use reqwest::blocking::Client;
 use reqwest::Method;
 use serde_json::Value as JsonValue;
 use serde_yaml::Value as YamlValue;
-use std::cell::Cell;
-use std::collections::HashMap;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
 use std::env;
-use std::ffi::{c_void, CStr};
+use std::ffi::{c_void, CStr, CString};
 use std::fs::{self, File};
 use std::hash::{BuildHasherDefault, Hasher};
-use std::io::{BufReader, Read, Write};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
 use std::os::raw::{c_char, c_double, c_int, c_longlong};
 use std::path::{Component, Path, PathBuf};
 use std::process::{Child, ChildStderr, ChildStdin, ChildStdout, Command, Stdio};
 use std::ptr;
-use std::sync::atomic::{AtomicI64, Ordering};
-use std::sync::{Mutex, OnceLock};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, OnceLock, PoisonError};
 use std::thread;
-use std::time::{Duration, UNIX_EPOCH};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use subtle::ConstantTimeEq;
 use tea_support::{
-    cli_error, env_error, fs_error, http_error, io_error, process_error, time_error, url_error,
+    bytes_error, cli_error, decode_slice_bound, env_error, fs_error, http_error, io_error,
+    iter_error, process_error, resolve_index, slice_positions, slice_range, task_error, time_error,
+    url_error, FormatArg, FormatSpec,
 };
 use tempfile::{Builder as TempBuilder, NamedTempFile};
 use time::format_description::well_known::Rfc3339;
@@ -34,6 +43,8 @@ use url::{form_urlencoded, Url};
 
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
 #[cfg(windows)]
 use std::os::windows::fs::MetadataExt;
 
@@ -102,6 +113,10 @@ pub struct TeaDict {
     entries: TeaDictMap,
 }
 
+pub struct TeaBytes {
+    data: Vec<u8>,
+}
+
 type TeaDictMap = HashMap<String, TeaValue, BuildHasherDefault<TeaDictHasher>>;
 
 #[derive(Default)]
@@ -141,17 +156,30 @@ impl Hasher for TeaDictHasher {
     }
 }
 
+// Handle tables are shared by every task thread. Each entry carries its own lock so
+// a blocking read or wait on one handle never holds up operations on the others.
+type HandleTable<T> = Mutex<HashMap<i64, Arc<Mutex<T>>>>;
+
+/// Lock a runtime table, recovering it if another thread panicked while holding it.
+fn lock_table<T>(table: &Mutex<T>) -> MutexGuard<'_, T> {
+    table.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
 struct FsHandle {
     reader: BufReader<File>,
 }
 
-static FS_HANDLES: OnceLock<Mutex<HashMap<i64, FsHandle>>> = OnceLock::new();
+static FS_HANDLES: OnceLock<HandleTable<FsHandle>> = OnceLock::new();
 static NEXT_FS_HANDLE: AtomicI64 = AtomicI64::new(1);
 
-fn fs_handles() -> &'static Mutex<HashMap<i64, FsHandle>> {
+fn fs_handles() -> &'static HandleTable<FsHandle> {
     FS_HANDLES.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
+fn fs_handle(handle: c_longlong) -> Option<Arc<Mutex<FsHandle>>> {
+    lock_table(fs_handles()).get(&handle).cloned()
+}
+
 struct ProcessHandleEntry {
     child: Child,
     stdout: Option<BufReader<ChildStdout>>,
@@ -160,13 +188,17 @@ struct ProcessHandleEntry {
     command: String,
 }
 
-static PROCESS_HANDLES: OnceLock<Mutex<HashMap<i64, ProcessHandleEntry>>> = OnceLock::new();
+static PROCESS_HANDLES: OnceLock<HandleTable<ProcessHandleEntry>> = OnceLock::new();
 static NEXT_PROCESS_HANDLE: AtomicI64 = AtomicI64::new(1);
 
-fn process_handles() -> &'static Mutex<HashMap<i64, ProcessHandleEntry>> {
+fn process_handles() -> &'static HandleTable<ProcessHandleEntry> {
     PROCESS_HANDLES.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
+fn process_handle(handle: c_longlong) -> Option<Arc<Mutex<ProcessHandleEntry>>> {
+    lock_table(process_handles()).get(&handle).cloned()
+}
+
 // Regex handles for compiled regex patterns
 struct RegexHandle {
     regex: regex::Regex,
@@ -179,6 +211,31 @@ fn regex_handles() -> &'static Mutex<HashMap<i64, RegexHandle>> {
     REGEX_HANDLES.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
+// Random generators; handle 0 is the shared generator, seeded from the OS on first use.
+static RANDOM_GENERATORS: OnceLock<Mutex<HashMap<i64, tea_intrinsics::random::Generator>>> =
+    OnceLock::new();
+static NEXT_RANDOM_HANDLE: AtomicI64 = AtomicI64::new(1);
+
+fn random_generators() -> &'static Mutex<HashMap<i64, tea_intrinsics::random::Generator>> {
+    RANDOM_GENERATORS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn with_random_generator<R>(
+    handle: c_longlong,
+    f: impl FnOnce(&mut tea_intrinsics::random::Generator) -> R,
+) -> R {
+    let mut table = lock_table(random_generators());
+    if handle == 0 {
+        return f(table
+            .entry(0)
+            .or_insert_with(tea_intrinsics::random::Generator::from_os));
+    }
+    match table.get_mut(&handle) {
+        Some(generator) => f(generator),
+        None => panic!("invalid random generator handle {handle}"),
+    }
+}
+
 thread_local! {
     static CURRENT_ERROR: Cell<*mut TeaErrorInstance> = Cell::new(ptr::null_mut());
 }
@@ -395,6 +452,68 @@ fn tea_list_to_bytes(list: *const TeaList, function_name: &str) -> Vec<u8> {
     buffer
 }
 
+fn alloc_tea_bytes(data: Vec<u8>) -> *mut TeaBytes {
+    Box::into_raw(Box::new(TeaBytes { data }))
+}
+
+fn expect_bytes<'a>(bytes: *const TeaBytes, function_name: &str) -> &'a [u8] {
+    if bytes.is_null() {
+        panic!("{function_name} expects a Bytes argument");
+    }
+    unsafe { &(*bytes).data }
+}
+
+fn format_bytes(data: &[u8]) -> String {
+    let mut result = String::with_capacity(data.len() * 2 + 8);
+    result.push_str("Bytes(");
+    result.push_str(&encode_hex(data));
+    result.push(')');
+    result
+}
+
+fn encode_hex(data: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut result = String::with_capacity(data.len() * 2);
+    for byte in data {
+        result.push(DIGITS[(byte >> 4) as usize] as char);
+        result.push(DIGITS[(byte & 0x0f) as usize] as char);
+    }
+    result
+}
+
+fn decode_hex(text: &str) -> Result<Vec<u8>, String> {
+    fn digit(ch: u8) -> Option<u8> {
+        match ch {
+            b'0'..=b'9' => Some(ch - b'0'),
+            b'a'..=b'f' => Some(ch - b'a' + 10),
+            b'A'..=b'F' => Some(ch - b'A' + 10),
+            _ => None,
+        }
+    }
+
+    let input = text.as_bytes();
+    if !input.len().is_multiple_of(2) {
+        return Err("hex input must have an even number of digits".to_string());
+    }
+    let mut result = Vec::with_capacity(input.len() / 2);
+    for (index, pair) in input.chunks(2).enumerate() {
+        match (digit(pair[0]), digit(pair[1])) {
+            (Some(high), Some(low)) => result.push((high << 4) | low),
+            _ => {
+                return Err(format!(
+                    "invalid hex digit at offset {}",
+                    if digit(pair[0]).is_none() {
+                        index * 2
+                    } else {
+                        index * 2 + 1
+                    }
+                ))
+            }
+        }
+    }
+    Ok(result)
+}
+
 fn write_atomic_bytes(path: &Path, data: &[u8]) -> std::io::Result<()> {
     let parent = path
         .parent()
@@ -435,6 +554,7 @@ pub enum TeaValueTag {
     Error,
     Closure,
     Nil,
+    Bytes,
 }
 
 #[repr(C)]
@@ -449,6 +569,7 @@ pub union TeaValuePayload {
     pub struct_value: *const TeaStructInstance,
     pub error_value: *const TeaErrorInstance,
     pub closure_value: *const TeaClosure,
+    pub bytes_value: *const TeaBytes,
 }
 
 #[repr(C)]
@@ -991,6 +1112,7 @@ fn print_value(value: TeaValue) {
             TeaValueTag::Struct => tea_print_struct(value.payload.struct_value),
             TeaValueTag::Error => tea_print_error(value.payload.error_value),
             TeaValueTag::Closure => tea_print_closure(value.payload.closure_value),
+            TeaValueTag::Bytes => tea_print_bytes(value.payload.bytes_value),
         }
     }
 }
@@ -1017,6 +1139,13 @@ fn eprint_value(value: TeaValue) {
             TeaValueTag::Struct => tea_eprint_struct(value.payload.struct_value),
             TeaValueTag::Error => tea_eprint_error(value.payload.error_value),
             TeaValueTag::Closure => tea_eprint_closure(value.payload.closure_value),
+            TeaValueTag::Bytes => {
+                let _ = write!(
+                    std::io::stderr(),
+                    "{}",
+                    format_bytes(expect_bytes(value.payload.bytes_value, "eprint"))
+                );
+            }
         }
     }
 }
@@ -1611,6 +1740,9 @@ unsafe fn tea_value_equals(left: TeaValue, right: TeaValue) -> bool {
         (TeaValueTag::Closure, TeaValueTag::Closure) => {
             left.payload.closure_value == right.payload.closure_value
         }
+        (TeaValueTag::Bytes, TeaValueTag::Bytes) => {
+            tea_bytes_equal(left.payload.bytes_value, right.payload.bytes_value) != 0
+        }
         _ => false,
     }
 }
@@ -1748,10 +1880,27 @@ unsafe fn tea_value_to_string(value: TeaValue) -> String {
             result
         }
         TeaValueTag::Closure => "<closure>".to_string(),
+        TeaValueTag::Bytes => format_bytes(expect_bytes(value.payload.bytes_value, "to_string")),
     }
 }
 
 fn tea_value_to_json(value: TeaValue) -> Result<JsonValue, String> {
+    tea_value_to_json_with(value, &JsonEncodeOptions::default())
+}
+
+/// Struct field handling for `json.encode`.
+#[derive(Default)]
+struct JsonEncodeOptions {
+    /// JSON keys to use in place of struct field names.
+    rename: HashMap<String, String>,
+    /// Leave out struct fields whose value is nil.
+    omit_nil: bool,
+}
+
+fn tea_value_to_json_with(
+    value: TeaValue,
+    options: &JsonEncodeOptions,
+) -> Result<JsonValue, String> {
     unsafe {
         match value.tag {
             TeaValueTag::Nil => Ok(JsonValue::Null),
@@ -1778,7 +1927,7 @@ fn tea_value_to_json(value: TeaValue) -> Result<JsonValue, String> {
                 let mut items = Vec::with_capacity(len.max(0) as usize);
                 for index in 0..len.max(0) {
                     let element = *items_ptr.add(index as usize);
-                    items.push(tea_value_to_json(element)?);
+                    items.push(tea_value_to_json_with(element, options)?);
                 }
                 Ok(JsonValue::Array(items))
             }
@@ -1790,7 +1939,7 @@ fn tea_value_to_json(value: TeaValue) -> Result<JsonValue, String> {
                 let dict_ref = &*dict_ptr;
                 let mut object = serde_json::Map::with_capacity(dict_ref.entries.len());
                 for (key, entry_value) in dict_ref.entries.iter() {
-                    object.insert(key.clone(), tea_value_to_json(*entry_value)?);
+                    object.insert(key.clone(), tea_value_to_json_with(*entry_value, options)?);
                 }
                 Ok(JsonValue::Object(object))
             }
@@ -1815,12 +1964,23 @@ fn tea_value_to_json(value: TeaValue) -> Result<JsonValue, String> {
                     let field_name =
                         tea_cstr_to_rust(field_name_ptr).unwrap_or_else(|| format!("field{index}"));
                     let field_value = *instance.fields.add(index as usize);
-                    object.insert(field_name, tea_value_to_json(field_value)?);
+                    if options.omit_nil && matches!(field_value.tag, TeaValueTag::Nil) {
+                        continue;
+                    }
+                    let key = options
+                        .rename
+                        .get(&field_name)
+                        .cloned()
+                        .unwrap_or(field_name);
+                    object.insert(key, tea_value_to_json_with(field_value, options)?);
                 }
                 Ok(JsonValue::Object(object))
             }
             TeaValueTag::Error => Ok(JsonValue::String(tea_value_to_string(value))),
             TeaValueTag::Closure => Err("cannot encode closures as JSON".to_string()),
+            TeaValueTag::Bytes => Ok(JsonValue::String(
+                BASE64_STANDARD.encode(expect_bytes(value.payload.bytes_value, "json.encode")),
+            )),
         }
     }
 }
@@ -2366,6 +2526,7 @@ pub extern "C" fn tea_type_of(value: TeaValue) -> *mut TeaString {
         TeaValueTag::Error => "Error",
         TeaValueTag::Closure => "Closure",
         TeaValueTag::Nil => "Nil",
+        TeaValueTag::Bytes => "Bytes",
     };
     alloc_tea_string(type_name)
 }
@@ -2499,6 +2660,7 @@ pub extern "C" fn tea_panic(message: *const TeaString) {
 
 #[no_mangle]
 pub extern "C" fn tea_exit(code: c_longlong) {
+    restore_terminal_echo();
     std::process::exit(code as i32);
 }
 
@@ -2517,7 +2679,30 @@ pub extern "C" fn tea_util_len(value: TeaValue) -> c_longlong {
                     tea_list_len(&*list_ptr)
                 }
             }
-            _ => panic!("len builtin expects a String or List"),
+            TeaValueTag::Bytes => tea_bytes_len(value.payload.bytes_value),
+            _ => panic!("len builtin expects a String, List, or Bytes"),
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn tea_format_value(
+    value_ptr: *const TeaValue,
+    spec: *const TeaString,
+) -> *mut TeaString {
+    let spec = expect_string(spec, "format specifier must be a String");
+    unsafe {
+        let value = *value_ptr;
+        let rendered = tea_value_to_string(value);
+        let argument = match value.tag {
+            TeaValueTag::Int => FormatArg::Int(value.payload.int_value),
+            TeaValueTag::Float => FormatArg::Float(value.payload.float_value),
+            TeaValueTag::String => FormatArg::String(&rendered),
+            _ => FormatArg::Other(&rendered),
+        };
+        match FormatSpec::parse(&spec).and_then(|spec| spec.apply(argument)) {
+            Ok(text) => alloc_tea_string(&text),
+            Err(message) => panic!("{message}"),
         }
     }
 }
@@ -2668,33 +2853,27 @@ pub extern "C" fn tea_fs_write_text_atomic(path: *const TeaString, contents: *co
 }
 
 #[no_mangle]
-pub extern "C" fn tea_fs_read_bytes(path: *const TeaString) -> *mut TeaList {
+pub extern "C" fn tea_fs_read_bytes(path: *const TeaString) -> *mut TeaBytes {
     let path_str = expect_path(path);
     let bytes = fs::read(&path_str)
         .unwrap_or_else(|error| panic!("{}", fs_error("read_bytes", &path_str, &error)));
-    bytes_to_list(&bytes)
+    alloc_tea_bytes(bytes)
 }
 
 #[no_mangle]
-pub extern "C" fn tea_fs_write_bytes(path: *const TeaString, data: *const TeaList) {
-    if data.is_null() {
-        panic!("write_bytes expects a List argument");
-    }
+pub extern "C" fn tea_fs_write_bytes(path: *const TeaString, data: *const TeaBytes) {
+    let buffer = expect_bytes(data, "write_bytes");
     let path_str = expect_path(path);
-    let buffer = tea_list_to_bytes(data, "write_bytes");
     fs::write(&path_str, buffer)
         .unwrap_or_else(|error| panic!("{}", fs_error("write_bytes", &path_str, &error)));
 }
 
 #[no_mangle]
-pub extern "C" fn tea_fs_write_bytes_atomic(path: *const TeaString, data: *const TeaList) {
-    if data.is_null() {
-        panic!("write_bytes_atomic expects a List argument");
-    }
+pub extern "C" fn tea_fs_write_bytes_atomic(path: *const TeaString, data: *const TeaBytes) {
+    let buffer = expect_bytes(data, "write_bytes_atomic");
     let path_str = expect_path(path);
-    let buffer = tea_list_to_bytes(data, "write_bytes_atomic");
     let fs_path = Path::new(&path_str);
-    write_atomic_bytes(fs_path, &buffer).unwrap_or_else(|error| {
+    write_atomic_bytes(fs_path, buffer).unwrap_or_else(|error| {
         panic!("{}", fs_error("write_bytes_atomic", &path_str, &error));
     });
 }
@@ -2716,15 +2895,15 @@ pub extern "C" fn tea_fs_append_text(path: *const TeaString, contents: *const Te
 }
 
 #[no_mangle]
-pub extern "C" fn tea_fs_append_bytes(path: *const TeaString, data: *const TeaList) {
+pub extern "C" fn tea_fs_append_bytes(path: *const TeaString, data: *const TeaBytes) {
+    let buffer = expect_bytes(data, "append_bytes");
     let path_str = expect_path(path);
-    let buffer = tea_list_to_bytes(data, "append_bytes");
     let mut file = fs::OpenOptions::new()
         .create(true)
         .append(true)
         .open(&path_str)
         .unwrap_or_else(|error| panic!("{}", fs_error("append_bytes", &path_str, &error)));
-    file.write_all(&buffer)
+    file.write_all(buffer)
         .unwrap_or_else(|error| panic!("{}", fs_error("append_bytes", &path_str, &error)));
 }
 
@@ -2956,50 +3135,75 @@ pub extern "C" fn tea_fs_open_read(path: *const TeaString) -> c_longlong {
     let path_str = expect_path(path);
     let file = File::open(&path_str)
         .unwrap_or_else(|error| panic!("{}", fs_error("open_read", &path_str, &error)));
-    let mut table = fs_handles().lock().unwrap();
     let id = NEXT_FS_HANDLE.fetch_add(1, Ordering::SeqCst);
-    table.insert(
+    lock_table(fs_handles()).insert(
         id,
-        FsHandle {
+        Arc::new(Mutex::new(FsHandle {
             reader: BufReader::new(file),
-        },
+        })),
     );
+    track_generator_file(id, true);
     id
 }
 
 #[no_mangle]
-pub extern "C" fn tea_fs_read_chunk(handle: c_longlong, size: c_longlong) -> *mut TeaList {
+pub extern "C" fn tea_fs_read_chunk(handle: c_longlong, size: c_longlong) -> *mut TeaBytes {
     if size <= 0 {
         panic!("read_chunk expects a positive chunk size");
     }
-    let mut table = fs_handles().lock().unwrap();
-    let entry = table
-        .get_mut(&(handle as i64))
-        .unwrap_or_else(|| panic!("invalid file handle {handle}"));
+    let entry = fs_handle(handle).unwrap_or_else(|| panic!("invalid file handle {handle}"));
+    let mut entry = lock_table(&entry);
     let mut buffer = vec![0u8; size as usize];
     let bytes_read = entry.reader.read(&mut buffer).unwrap_or_else(|error| {
         let target = format!("handle {handle}");
         panic!("{}", fs_error("read_chunk", &target, &error));
     });
     buffer.truncate(bytes_read);
-    drop(table);
-    let list = tea_alloc_list(bytes_read as c_longlong);
-    for (index, byte) in buffer.into_iter().enumerate() {
-        tea_list_set(
-            list,
-            index as c_longlong,
-            tea_value_from_int(byte as c_longlong),
-        );
+    drop(entry);
+    alloc_tea_bytes(buffer)
+}
+
+/// Read the next line from an open file into `out`, or Nil at end of file.
+#[no_mangle]
+pub extern "C" fn tea_fs_read_line(out: *mut TeaValue, handle: c_longlong) {
+    let entry = fs_handle(handle).unwrap_or_else(|| panic!("invalid file handle {handle}"));
+    let line = read_text_line(&mut lock_table(&entry).reader).unwrap_or_else(|error| {
+        let target = format!("handle {handle}");
+        panic!("{}", fs_error("read_line", &target, &error));
+    });
+    unsafe {
+        *out = line_to_value(line);
+    }
+}
+
+/// Read one line without its line ending; `None` marks end of input.
+fn read_text_line(reader: &mut impl std::io::BufRead) -> std::io::Result<Option<String>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    Ok(Some(line))
+}
+
+fn line_to_value(line: Option<String>) -> TeaValue {
+    match line {
+        Some(line) => tea_value_from_string(alloc_tea_string(&line)),
+        None => tea_value_nil(),
     }
-    list
 }
 
 #[no_mangle]
 pub extern "C" fn tea_fs_close(handle: c_longlong) {
-    let mut table = fs_handles().lock().unwrap();
-    if table.remove(&(handle as i64)).is_none() {
+    if lock_table(fs_handles()).remove(&handle).is_none() {
         panic!("invalid file handle {handle}");
     }
+    track_generator_file(handle, false);
 }
 
 #[no_mangle]
@@ -3131,11 +3335,285 @@ pub extern "C" fn tea_list_set(list: *mut TeaList, index: c_longlong, value: Tea
         }
         let list_ref = &mut *list;
         let (items, len, _capacity) = tea_list_items_mut(list_ref);
-        if index < 0 || index >= len {
-            panic!("index out of bounds");
+        let index =
+            resolve_index(index, len as usize).unwrap_or_else(|| panic!("index out of bounds"));
+        *items.add(index) = value;
+    }
+}
+
+/// Replace the elements selected by a contiguous slice with the contents of `replacement`,
+/// growing or shrinking the list in place.
+#[no_mangle]
+pub extern "C" fn tea_list_splice(
+    list: *mut TeaList,
+    start: c_longlong,
+    end: c_longlong,
+    inclusive: c_int,
+    replacement: *const TeaList,
+) {
+    unsafe {
+        if list.is_null() || replacement.is_null() {
+            panic!("null list in slice assignment");
+        }
+        let replacement_values = tea_list_values(replacement);
+        let mut values = tea_list_values(list);
+        let (start, end) = slice_range(
+            values.len(),
+            decode_slice_bound(start),
+            decode_slice_bound(end),
+            inclusive != 0,
+        );
+        values.splice(start..end, replacement_values);
+        tea_list_replace_values(list, values);
+    }
+}
+
+/// Copy the items of a list into a Vec.
+unsafe fn tea_list_values(list: *const TeaList) -> Vec<TeaValue> {
+    let (items, len) = tea_list_items(&*list);
+    std::slice::from_raw_parts(items, len as usize).to_vec()
+}
+
+/// Replace the contents of a list in place, moving it to heap storage.
+unsafe fn tea_list_replace_values(list: *mut TeaList, mut values: Vec<TeaValue>) {
+    let list_ref = &mut *list;
+    let (items, _len, capacity) = tea_list_items_mut(list_ref);
+    if list_ref.tag == 0 {
+        drop(Vec::from_raw_parts(
+            items,
+            capacity as usize,
+            capacity as usize,
+        ));
+    }
+    let new_len = values.len();
+    let new_capacity = new_len.max(4);
+    values.resize(new_capacity, tea_value_nil());
+    let items_ptr = values.as_mut_ptr();
+    std::mem::forget(values);
+
+    list_ref.tag = 0;
+    list_ref.len = 0;
+    list_ref.padding = [0; 6];
+    tea_list_store_heap_metadata(list_ref, new_len as i64, new_capacity as i64, items_ptr);
+}
+
+fn tea_list_from_values(values: &[TeaValue]) -> *mut TeaList {
+    let list = tea_alloc_list(values.len() as c_longlong);
+    for (index, value) in values.iter().enumerate() {
+        tea_list_set(list, index as c_longlong, *value);
+    }
+    list
+}
+
+fn expect_list_values(list: *const TeaList, context: &str) -> Vec<TeaValue> {
+    if list.is_null() {
+        panic!("null list in {context}");
+    }
+    unsafe { tea_list_values(list) }
+}
+
+/// Natural ordering used by `List.sort`, `List.min` and `List.max`.
+unsafe fn tea_value_compare(left: &TeaValue, right: &TeaValue) -> std::cmp::Ordering {
+    match (left.tag, right.tag) {
+        (TeaValueTag::Int, TeaValueTag::Int) => {
+            left.payload.int_value.cmp(&right.payload.int_value)
+        }
+        (TeaValueTag::Float, TeaValueTag::Float) => left
+            .payload
+            .float_value
+            .total_cmp(&right.payload.float_value),
+        (TeaValueTag::String, TeaValueTag::String) => tea_string_to_rust(left.payload.string_value)
+            .cmp(&tea_string_to_rust(right.payload.string_value)),
+        _ => panic!("list items are not comparable"),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn tea_list_sort(list: *mut TeaList) {
+    let mut values = expect_list_values(list, "List.sort");
+    unsafe {
+        values.sort_by(|left, right| tea_value_compare(left, right));
+        tea_list_replace_values(list, values);
+    }
+}
+
+/// Stable sort ordered by the sign of `compare(closure, left, right)`.
+#[no_mangle]
+pub extern "C" fn tea_list_sort_by(
+    list: *mut TeaList,
+    compare: extern "C" fn(*const TeaClosure, *const TeaValue, *const TeaValue) -> c_longlong,
+    closure: *const TeaClosure,
+) {
+    let mut values = expect_list_values(list, "List.sort_by");
+    values.sort_by(|left, right| compare(closure, left, right).cmp(&0));
+    unsafe { tea_list_replace_values(list, values) }
+}
+
+#[no_mangle]
+pub extern "C" fn tea_list_reverse(list: *mut TeaList) {
+    let mut values = expect_list_values(list, "List.reverse");
+    values.reverse();
+    unsafe { tea_list_replace_values(list, values) }
+}
+
+#[no_mangle]
+pub extern "C" fn tea_list_unique(list: *const TeaList) -> *mut TeaList {
+    let mut unique: Vec<TeaValue> = Vec::new();
+    for value in expect_list_values(list, "List.unique") {
+        if !unique
+            .iter()
+            .any(|seen| unsafe { tea_value_equals(*seen, value) })
+        {
+            unique.push(value);
+        }
+    }
+    tea_list_from_values(&unique)
+}
+
+fn expect_group_size(size: c_longlong, context: &str) -> usize {
+    if size <= 0 {
+        panic!("{context} size must be positive");
+    }
+    size as usize
+}
+
+#[no_mangle]
+pub extern "C" fn tea_list_chunk(list: *const TeaList, size: c_longlong) -> *mut TeaList {
+    let size = expect_group_size(size, "List.chunk");
+    let groups: Vec<TeaValue> = expect_list_values(list, "List.chunk")
+        .chunks(size)
+        .map(|chunk| tea_value_from_list(tea_list_from_values(chunk)))
+        .collect();
+    tea_list_from_values(&groups)
+}
+
+#[no_mangle]
+pub extern "C" fn tea_list_window(list: *const TeaList, size: c_longlong) -> *mut TeaList {
+    let size = expect_group_size(size, "List.window");
+    let groups: Vec<TeaValue> = expect_list_values(list, "List.window")
+        .windows(size)
+        .map(|window| tea_value_from_list(tea_list_from_values(window)))
+        .collect();
+    tea_list_from_values(&groups)
+}
+
+#[no_mangle]
+pub extern "C" fn tea_list_join(
+    list: *const TeaList,
+    separator: *const TeaString,
+) -> *mut TeaString {
+    let separator = expect_string(separator, "List.join expects a String separator");
+    let parts: Vec<String> = expect_list_values(list, "List.join")
+        .into_iter()
+        .map(|value| unsafe {
+            match value.tag {
+                TeaValueTag::String => expect_string(
+                    value.payload.string_value,
+                    "List.join expects a List of String",
+                ),
+                _ => panic!("List.join expects a List of String"),
+            }
+        })
+        .collect();
+    alloc_tea_string(&parts.join(&separator))
+}
+
+#[no_mangle]
+pub extern "C" fn tea_list_sum_int(list: *const TeaList) -> c_longlong {
+    expect_list_values(list, "List.sum")
+        .iter()
+        .map(|value| unsafe { value.payload.int_value })
+        .fold(0, c_longlong::wrapping_add)
+}
+
+#[no_mangle]
+pub extern "C" fn tea_list_sum_float(list: *const TeaList) -> c_double {
+    expect_list_values(list, "List.sum")
+        .iter()
+        .map(|value| unsafe { value.payload.float_value })
+        .sum()
+}
+
+#[no_mangle]
+pub extern "C" fn tea_list_min(list: *const TeaList) -> TeaValue {
+    expect_list_values(list, "List.min")
+        .into_iter()
+        .min_by(|left, right| unsafe { tea_value_compare(left, right) })
+        .unwrap_or_else(|| tea_value_nil())
+}
+
+#[no_mangle]
+pub extern "C" fn tea_list_max(list: *const TeaList) -> TeaValue {
+    expect_list_values(list, "List.max")
+        .into_iter()
+        .max_by(|left, right| unsafe { tea_value_compare(left, right) })
+        .unwrap_or_else(|| tea_value_nil())
+}
+
+/// Insert before `index`; an index equal to the length appends.
+#[no_mangle]
+pub extern "C" fn tea_list_insert(list: *mut TeaList, index: c_longlong, value: *const TeaValue) {
+    if value.is_null() {
+        panic!("null list insert value");
+    }
+    let mut values = expect_list_values(list, "List.insert");
+    if index < 0 || index as usize > values.len() {
+        panic!("index out of bounds");
+    }
+    unsafe {
+        values.insert(index as usize, *value);
+        tea_list_replace_values(list, values);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn tea_list_remove_at(list: *mut TeaList, index: c_longlong) -> TeaValue {
+    let mut values = expect_list_values(list, "List.remove_at");
+    let index = resolve_index(index, values.len()).unwrap_or_else(|| panic!("index out of bounds"));
+    let removed = values.remove(index);
+    unsafe { tea_list_replace_values(list, values) };
+    removed
+}
+
+#[no_mangle]
+pub extern "C" fn tea_list_pop(list: *mut TeaList) -> TeaValue {
+    let mut values = expect_list_values(list, "List.pop");
+    match values.pop() {
+        Some(value) => {
+            unsafe { tea_list_replace_values(list, values) };
+            value
         }
-        *items.add(index as usize) = value;
+        None => tea_value_nil(),
+    }
+}
+
+/// Append every item of `other` to `list`; used by `List.flat_map`.
+#[no_mangle]
+pub extern "C" fn tea_list_extend(list: *mut TeaList, other: *const TeaList) {
+    let mut values = expect_list_values(list, "List.flat_map");
+    values.extend(expect_list_values(other, "List.flat_map"));
+    unsafe { tea_list_replace_values(list, values) }
+}
+
+/// Append `value` to the list stored under `key`, creating it on first use; used by
+/// `List.group_by`.
+#[no_mangle]
+pub extern "C" fn tea_dict_group_append(
+    dict: *mut TeaDict,
+    key: *const TeaString,
+    value: *const TeaValue,
+) {
+    if dict.is_null() {
+        panic!("null dict");
     }
+    let group = if tea_dict_has(dict, key) == 0 {
+        let group = tea_alloc_list(0);
+        dict_set_internal(dict, key, tea_value_from_list(group));
+        group
+    } else {
+        unsafe { tea_dict_get(dict, key).payload.list_value as *mut TeaList }
+    };
+    tea_list_append(group, value);
 }
 
 #[no_mangle]
@@ -3199,10 +3677,9 @@ pub extern "C" fn tea_list_get(list: *const TeaList, index: c_longlong) -> TeaVa
         }
         let list_ref = &*list;
         let (items, len) = tea_list_items(list_ref);
-        if index < 0 || index >= len {
-            panic!("index out of bounds");
-        }
-        *items.add(index as usize)
+        let index =
+            resolve_index(index, len as usize).unwrap_or_else(|| panic!("index out of bounds"));
+        *items.add(index)
     }
 }
 
@@ -3211,18 +3688,13 @@ pub extern "C" fn tea_string_index(string: *const TeaString, index: c_longlong)
     if string.is_null() {
         panic!("null string");
     }
-    if index < 0 {
-        panic!("negative index");
-    }
     unsafe {
         let string_ref = &*string;
         let bytes = tea_string_as_bytes(string_ref);
         let text = std::str::from_utf8(bytes).unwrap_or_else(|_| panic!("invalid UTF-8 in string"));
         let chars: Vec<char> = text.chars().collect();
-        let idx = index as usize;
-        if idx >= chars.len() {
-            panic!("index out of bounds");
-        }
+        let idx =
+            resolve_index(index, chars.len()).unwrap_or_else(|| panic!("index out of bounds"));
         let ch = chars[idx];
         alloc_tea_string(&ch.to_string())
     }
@@ -3235,10 +3707,7 @@ pub extern "C" fn tea_string_index_of(
 ) -> c_longlong {
     let text_str = expect_string(text, "string.index_of expects a valid text string");
     let pattern_str = expect_string(pattern, "string.index_of expects a valid pattern string");
-    text_str
-        .find(&pattern_str)
-        .map(|index| index as c_longlong)
-        .unwrap_or(-1)
+    tea_intrinsics::string::index_of(&text_str, &pattern_str)
 }
 
 #[no_mangle]
@@ -3302,78 +3771,62 @@ pub extern "C" fn tea_string_slice(
     string: *const TeaString,
     start: c_longlong,
     end: c_longlong,
+    step: c_longlong,
     inclusive: c_int,
 ) -> *mut TeaString {
     if string.is_null() {
         panic!("null string in slice");
     }
-    if start < 0 || end < 0 {
-        panic!("slice indices cannot be negative");
-    }
     unsafe {
         let string_ref = &*string;
         let bytes = tea_string_as_bytes(string_ref);
         let text = std::str::from_utf8(bytes).unwrap_or_else(|_| panic!("invalid UTF-8 in string"));
         let chars: Vec<char> = text.chars().collect();
-
-        let start_idx = start as usize;
-        let mut end_idx = end as usize;
-
-        if inclusive != 0 {
-            end_idx = end_idx.saturating_add(1);
-        }
-
-        if start_idx > chars.len() {
-            panic!("slice start index out of bounds");
-        }
-        let end_idx = end_idx.min(chars.len());
-        if start_idx > end_idx {
-            panic!("slice start must be <= end");
-        }
-
-        let slice: String = chars[start_idx..end_idx].iter().collect();
+        let positions = runtime_slice_positions(chars.len(), start, end, step, inclusive);
+        let slice: String = positions.into_iter().map(|index| chars[index]).collect();
         alloc_tea_string(&slice)
     }
 }
 
+fn runtime_slice_positions(
+    len: usize,
+    start: c_longlong,
+    end: c_longlong,
+    step: c_longlong,
+    inclusive: c_int,
+) -> Vec<usize> {
+    slice_positions(
+        len,
+        decode_slice_bound(start),
+        decode_slice_bound(end),
+        inclusive != 0,
+        step,
+    )
+    .unwrap_or_else(|message| panic!("{message}"))
+}
+
 #[no_mangle]
 pub extern "C" fn tea_list_slice(
     list: *const TeaList,
     start: c_longlong,
     end: c_longlong,
+    step: c_longlong,
     inclusive: c_int,
 ) -> *mut TeaList {
     if list.is_null() {
         panic!("null list in slice");
     }
-    if start < 0 || end < 0 {
-        panic!("slice indices cannot be negative");
-    }
     unsafe {
         let list_ref = &*list;
         let (items, len) = tea_list_items(list_ref);
-        let start_idx = start as usize;
-        let mut end_idx = end as usize;
-
-        if inclusive != 0 {
-            end_idx = end_idx.saturating_add(1);
-        }
+        let positions = runtime_slice_positions(len as usize, start, end, step, inclusive);
 
-        if start_idx > len as usize {
-            panic!("slice start index out of bounds");
-        }
-        let end_idx = end_idx.min(len as usize);
-        if start_idx > end_idx {
-            panic!("slice start must be <= end");
-        }
-
-        let slice_len = (end_idx - start_idx) as c_longlong;
-        let result = tea_alloc_list(slice_len);
+        let result = tea_alloc_list(positions.len() as c_longlong);
         let result_ref = &mut *result;
         let (result_items, _, _) = tea_list_items_mut(result_ref);
 
-        for i in 0..slice_len {
-            *result_items.add(i as usize) = *items.add((start_idx + i as usize) as usize);
+        for (offset, index) in positions.into_iter().enumerate() {
+            *result_items.add(offset) = *items.add(index);
         }
 
         result
@@ -3381,42 +3834,254 @@ pub extern "C" fn tea_list_slice(
 }
 
 #[no_mangle]
-pub extern "C" fn tea_dict_new() -> *mut TeaDict {
-    Box::into_raw(Box::new(TeaDict {
-        entries: TeaDictMap::default(),
-    }))
-}
-
-/// Internal helper for setting dict values (safe to call from Rust)
-fn dict_set_internal(dict: *mut TeaDict, key: *const TeaString, value: TeaValue) {
-    if dict.is_null() {
-        panic!("null dict");
-    }
-    unsafe {
-        if key.is_null() {
-            panic!("dict key must be a valid string");
-        }
-        let key_str = expect_string_ref(&*key, "dict key must be a valid string");
-        let dict_ref = &mut *dict;
-        if let Some(existing) = dict_ref.entries.get_mut(key_str) {
-            *existing = value;
-        } else {
-            dict_ref.entries.insert(key_str.to_owned(), value);
-        }
-    }
+pub extern "C" fn tea_bytes_len(bytes: *const TeaBytes) -> c_longlong {
+    expect_bytes(bytes, "bytes.len").len() as c_longlong
 }
 
 #[no_mangle]
-pub extern "C" fn tea_dict_set(
-    dict: *mut TeaDict,
-    key: *const TeaString,
-    value_ptr: *const TeaValue,
-) {
-    // Take value by pointer to avoid ARM64 ABI struct passing issues
-    // Internal callers should use dict_set_internal directly
-    unsafe {
-        dict_set_internal(dict, key, *value_ptr);
-    }
+pub extern "C" fn tea_bytes_get(bytes: *const TeaBytes, index: c_longlong) -> c_longlong {
+    let data = expect_bytes(bytes, "bytes index");
+    let index = resolve_index(index, data.len()).unwrap_or_else(|| panic!("index out of bounds"));
+    data[index] as c_longlong
+}
+
+#[no_mangle]
+pub extern "C" fn tea_bytes_slice(
+    bytes: *const TeaBytes,
+    start: c_longlong,
+    end: c_longlong,
+    step: c_longlong,
+    inclusive: c_int,
+) -> *mut TeaBytes {
+    let data = expect_bytes(bytes, "bytes slice");
+    let positions = runtime_slice_positions(data.len(), start, end, step, inclusive);
+    alloc_tea_bytes(positions.into_iter().map(|index| data[index]).collect())
+}
+
+#[no_mangle]
+pub extern "C" fn tea_bytes_equal(left: *const TeaBytes, right: *const TeaBytes) -> c_int {
+    let left = expect_bytes(left, "bytes equality");
+    let right = expect_bytes(right, "bytes equality");
+    if left == right {
+        1
+    } else {
+        0
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn tea_bytes_concat(left: *const TeaBytes, right: *const TeaBytes) -> *mut TeaBytes {
+    let left = expect_bytes(left, "bytes.concat");
+    let right = expect_bytes(right, "bytes.concat");
+    let mut data = Vec::with_capacity(left.len() + right.len());
+    data.extend_from_slice(left);
+    data.extend_from_slice(right);
+    alloc_tea_bytes(data)
+}
+
+#[no_mangle]
+pub extern "C" fn tea_bytes_from_list(list: *const TeaList) -> *mut TeaBytes {
+    alloc_tea_bytes(tea_list_to_bytes(list, "bytes.from_list"))
+}
+
+#[no_mangle]
+pub extern "C" fn tea_bytes_to_list(bytes: *const TeaBytes) -> *mut TeaList {
+    bytes_to_list(expect_bytes(bytes, "bytes.to_list"))
+}
+
+#[no_mangle]
+pub extern "C" fn tea_bytes_from_string(text: *const TeaString) -> *mut TeaBytes {
+    if text.is_null() {
+        panic!("bytes.from_string expects a String argument");
+    }
+    let data = unsafe { tea_string_as_bytes(&*text) };
+    alloc_tea_bytes(data.to_vec())
+}
+
+#[no_mangle]
+pub extern "C" fn tea_bytes_to_string(bytes: *const TeaBytes) -> *mut TeaString {
+    let data = expect_bytes(bytes, "bytes.to_string");
+    match std::str::from_utf8(data) {
+        Ok(text) => alloc_tea_string(text),
+        Err(error) => panic!(
+            "{}",
+            bytes_error(
+                "to_string",
+                format!("invalid UTF-8 at byte offset {}", error.valid_up_to())
+            )
+        ),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn tea_bytes_to_string_lossy(bytes: *const TeaBytes) -> *mut TeaString {
+    let data = expect_bytes(bytes, "bytes.to_string_lossy");
+    alloc_tea_string(&String::from_utf8_lossy(data))
+}
+
+#[no_mangle]
+pub extern "C" fn tea_bytes_is_utf8(bytes: *const TeaBytes) -> c_int {
+    let data = expect_bytes(bytes, "bytes.is_utf8");
+    if std::str::from_utf8(data).is_ok() {
+        1
+    } else {
+        0
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn tea_crypto_digest(
+    algorithm: *const TeaString,
+    bytes: *const TeaBytes,
+) -> *mut TeaBytes {
+    let algorithm = expect_string(algorithm, "crypto digest expects an algorithm name");
+    let data = expect_bytes(bytes, "crypto digest");
+    let digest = match algorithm.as_str() {
+        "sha256" => Sha256::digest(data).to_vec(),
+        "sha1" => Sha1::digest(data).to_vec(),
+        "md5" => Md5::digest(data).to_vec(),
+        "blake3" => blake3::hash(data).as_bytes().to_vec(),
+        other => panic!("unsupported digest algorithm '{other}'"),
+    };
+    alloc_tea_bytes(digest)
+}
+
+#[no_mangle]
+pub extern "C" fn tea_crypto_hmac_sha256(
+    key: *const TeaBytes,
+    message: *const TeaBytes,
+) -> *mut TeaBytes {
+    let key = expect_bytes(key, "crypto.hmac_sha256");
+    let message = expect_bytes(message, "crypto.hmac_sha256");
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    alloc_tea_bytes(mac.finalize().into_bytes().to_vec())
+}
+
+#[no_mangle]
+pub extern "C" fn tea_crypto_constant_time_eq(
+    left: *const TeaBytes,
+    right: *const TeaBytes,
+) -> c_int {
+    let left = expect_bytes(left, "crypto.constant_time_eq");
+    let right = expect_bytes(right, "crypto.constant_time_eq");
+    // Only the lengths are compared early; equal-length inputs take the same
+    // time whatever their contents.
+    c_int::from(left.ct_eq(right).unwrap_u8())
+}
+
+#[no_mangle]
+pub extern "C" fn tea_bytes_to_hex(bytes: *const TeaBytes) -> *mut TeaString {
+    alloc_tea_string(&encode_hex(expect_bytes(bytes, "bytes.to_hex")))
+}
+
+#[no_mangle]
+pub extern "C" fn tea_bytes_from_hex(text: *const TeaString) -> *mut TeaBytes {
+    let text = expect_string(text, "bytes.from_hex expects a valid string");
+    let data =
+        decode_hex(&text).unwrap_or_else(|error| panic!("{}", bytes_error("from_hex", error)));
+    alloc_tea_bytes(data)
+}
+
+#[no_mangle]
+pub extern "C" fn tea_bytes_to_base64(bytes: *const TeaBytes) -> *mut TeaString {
+    let data = expect_bytes(bytes, "bytes.to_base64");
+    alloc_tea_string(&BASE64_STANDARD.encode(data))
+}
+
+#[no_mangle]
+pub extern "C" fn tea_bytes_from_base64(text: *const TeaString) -> *mut TeaBytes {
+    let text = expect_string(text, "bytes.from_base64 expects a valid string");
+    let data = BASE64_STANDARD
+        .decode(text.as_bytes())
+        .unwrap_or_else(|error| panic!("{}", bytes_error("from_base64", error)));
+    alloc_tea_bytes(data)
+}
+
+thread_local! {
+    static BYTES_DECODE_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Decode `"hex"` or `"base64"` text. A failure is recorded for
+/// `tea_bytes_decode_error` and yields empty bytes, so `std.encoding` can throw it.
+#[no_mangle]
+pub extern "C" fn tea_bytes_decode(
+    text: *const TeaString,
+    format: *const TeaString,
+) -> *mut TeaBytes {
+    let text = expect_string(text, "bytes_decode expects a valid string");
+    let format = expect_string(format, "bytes_decode expects a valid format");
+    let result = match format.as_str() {
+        "hex" => decode_hex(&text),
+        "base64" => BASE64_STANDARD
+            .decode(text.as_bytes())
+            .map_err(|error| error.to_string()),
+        other => panic!("bytes_decode: unknown format '{other}'"),
+    };
+    BYTES_DECODE_ERROR.with(|slot| match result {
+        Ok(data) => {
+            *slot.borrow_mut() = None;
+            alloc_tea_bytes(data)
+        }
+        Err(message) => {
+            *slot.borrow_mut() = Some(message);
+            alloc_tea_bytes(Vec::new())
+        }
+    })
+}
+
+/// The message of the last failed `tea_bytes_decode` on this thread, or "".
+#[no_mangle]
+pub extern "C" fn tea_bytes_decode_error() -> *mut TeaString {
+    BYTES_DECODE_ERROR.with(|slot| alloc_tea_string(slot.borrow().as_deref().unwrap_or("")))
+}
+
+#[no_mangle]
+pub extern "C" fn tea_print_bytes(bytes: *const TeaBytes) {
+    if bytes.is_null() {
+        print!("Bytes()");
+        return;
+    }
+    print!("{}", format_bytes(expect_bytes(bytes, "print")));
+}
+
+#[no_mangle]
+pub extern "C" fn tea_dict_new() -> *mut TeaDict {
+    Box::into_raw(Box::new(TeaDict {
+        entries: TeaDictMap::default(),
+    }))
+}
+
+/// Internal helper for setting dict values (safe to call from Rust)
+fn dict_set_internal(dict: *mut TeaDict, key: *const TeaString, value: TeaValue) {
+    if dict.is_null() {
+        panic!("null dict");
+    }
+    unsafe {
+        if key.is_null() {
+            panic!("dict key must be a valid string");
+        }
+        let key_str = expect_string_ref(&*key, "dict key must be a valid string");
+        let dict_ref = &mut *dict;
+        if let Some(existing) = dict_ref.entries.get_mut(key_str) {
+            *existing = value;
+        } else {
+            dict_ref.entries.insert(key_str.to_owned(), value);
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn tea_dict_set(
+    dict: *mut TeaDict,
+    key: *const TeaString,
+    value_ptr: *const TeaValue,
+) {
+    // Take value by pointer to avoid ARM64 ABI struct passing issues
+    // Internal callers should use dict_set_internal directly
+    unsafe {
+        dict_set_internal(dict, key, *value_ptr);
+    }
 }
 
 #[no_mangle]
@@ -3669,6 +4334,40 @@ pub extern "C" fn tea_string_set_len_ffi(string: *mut TeaString, len: c_longlong
     }
 }
 
+/// Copy a string into a NUL-terminated buffer for a `CString` argument of an
+/// `extern def`. The caller releases it with `tea_cstring_free` after the call.
+/// Returns NULL when the string contains a NUL byte; the wrapper throws then.
+#[no_mangle]
+pub extern "C" fn tea_string_to_cstring(string: *const TeaString) -> *mut c_char {
+    let bytes = if string.is_null() {
+        &[][..]
+    } else {
+        unsafe { tea_string_as_bytes(&*string) }
+    };
+    match CString::new(bytes) {
+        Ok(cstring) => cstring.into_raw(),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn tea_cstring_free(cstring: *mut c_char) {
+    if !cstring.is_null() {
+        unsafe { drop(CString::from_raw(cstring)) };
+    }
+}
+
+/// Copy a `CString` returned by a C function into a Tea string. The C side keeps
+/// ownership of its buffer; NULL becomes the empty string.
+#[no_mangle]
+pub extern "C" fn tea_string_from_cstring(cstring: *const c_char) -> *mut TeaString {
+    if cstring.is_null() {
+        return alloc_tea_string_bytes(&[]);
+    }
+    let bytes = unsafe { CStr::from_ptr(cstring) }.to_bytes();
+    alloc_tea_string_bytes(bytes)
+}
+
 #[no_mangle]
 pub extern "C" fn tea_io_read_line() -> TeaValue {
     let mut buffer = String::new();
@@ -3698,20 +4397,12 @@ pub extern "C" fn tea_io_read_all() -> *mut TeaString {
 }
 
 #[no_mangle]
-pub extern "C" fn tea_io_read_bytes() -> *mut TeaList {
+pub extern "C" fn tea_io_read_bytes() -> *mut TeaBytes {
     let mut buffer = Vec::new();
     std::io::stdin()
         .read_to_end(&mut buffer)
         .unwrap_or_else(|error| panic!("{}", io_error("read_bytes", &error)));
-    let list = tea_alloc_list(buffer.len() as c_longlong);
-    for (index, byte) in buffer.into_iter().enumerate() {
-        tea_list_set(
-            list,
-            index as c_longlong,
-            tea_value_from_int(byte as c_longlong),
-        );
-    }
-    list
+    alloc_tea_bytes(buffer)
 }
 
 #[no_mangle]
@@ -3764,110 +4455,614 @@ pub extern "C" fn tea_json_decode(out: *mut TeaValue, text: *const TeaString) {
 }
 
 #[no_mangle]
-pub extern "C" fn tea_yaml_encode(value: TeaValue) -> *mut TeaString {
-    let json_value =
-        tea_value_to_json(value).unwrap_or_else(|error| panic!("failed to encode YAML: {error}"));
-    let yaml_value = serde_yaml::to_string(&json_value)
-        .unwrap_or_else(|error| panic!("failed to encode YAML: {error}"));
-    let bytes = yaml_value.as_bytes();
-    tea_alloc_string(bytes.as_ptr() as *const c_char, bytes.len() as c_longlong)
+pub extern "C" fn tea_json_encode_with(
+    value_ptr: *const TeaValue,
+    rename: *const TeaDict,
+    omit_nil: c_int,
+) -> *mut TeaString {
+    // Take by pointer to avoid ARM64 ABI struct passing issues
+    let value = unsafe { *value_ptr };
+    let options = JsonEncodeOptions {
+        rename: json_rename_map(rename),
+        omit_nil: omit_nil != 0,
+    };
+    let json_value = tea_value_to_json_with(value, &options)
+        .unwrap_or_else(|error| panic!("failed to encode JSON: {error}"));
+    let encoded = serde_json::to_string(&json_value)
+        .unwrap_or_else(|error| panic!("failed to encode JSON: {error}"));
+    alloc_tea_string(&encoded)
+}
+
+thread_local! {
+    // The mismatch found by the last typed JSON decode on this thread, if any.
+    static JSON_DECODE_ERROR: RefCell<Option<JsonDecodeError>> = const { RefCell::new(None) };
 }
 
 #[no_mangle]
-pub extern "C" fn tea_yaml_decode(text: *const TeaString) -> TeaValue {
-    let input = expect_string(text, "yaml.decode expects a String argument");
-    let parsed: YamlValue = serde_yaml::from_str(&input)
-        .unwrap_or_else(|error| panic!("failed to decode YAML: {error}"));
-    let json_value = serde_json::to_value(parsed)
-        .unwrap_or_else(|error| panic!("failed to normalise YAML: {error}"));
-    json_to_tea_value(&json_value)
+pub extern "C" fn tea_json_decode_typed(
+    out: *mut TeaValue,
+    text: *const TeaString,
+    schema: *const c_char,
+    rename: *const TeaDict,
+) {
+    // Write to out-pointer to avoid ARM64 ABI struct return issues. A document
+    // that does not match records its error for `json_decode_error` and
+    // produces nil, which the caller never uses.
+    let input = expect_string(text, "json.decode expects a String argument");
+    let result = decode_json_with_schema(&input, schema, rename);
+    let value = JSON_DECODE_ERROR.with(|slot| match result {
+        Ok(value) => {
+            *slot.borrow_mut() = None;
+            value
+        }
+        Err(error) => {
+            *slot.borrow_mut() = Some(error);
+            tea_value_nil()
+        }
+    });
+    unsafe {
+        *out = value;
+    }
 }
 
-fn collect_cli_args() -> Vec<String> {
-    env::args_os()
-        .skip(1)
-        .map(|arg| arg.to_string_lossy().into_owned())
-        .collect()
+#[no_mangle]
+pub extern "C" fn tea_json_decode_error() -> *mut TeaString {
+    JSON_DECODE_ERROR
+        .with(|slot| alloc_tea_string(slot.borrow().as_ref().map_or("", |error| &error.message)))
 }
 
-fn detect_program_name() -> Option<String> {
-    env::args_os().next().map(|arg| {
-        let path = Path::new(&arg);
-        path.file_stem()
-            .map(|stem| stem.to_string_lossy().into_owned())
-            .unwrap_or_else(|| arg.to_string_lossy().into_owned())
-    })
+#[no_mangle]
+pub extern "C" fn tea_json_decode_error_path() -> *mut TeaString {
+    JSON_DECODE_ERROR
+        .with(|slot| alloc_tea_string(slot.borrow().as_ref().map_or("", |error| &error.path)))
 }
 
-fn tea_list_to_runtime(list: *const TeaList) -> Result<Vec<RuntimeValue>> {
-    if list.is_null() {
-        return Ok(Vec::new());
-    }
-    unsafe {
-        let list_ref = &*list;
-        let (items, len) = tea_list_items(list_ref);
-        let mut values = Vec::with_capacity(len as usize);
-        for index in 0..len {
-            let value = *items.add(index as usize);
-            values.push(runtime_value_from_tea(value)?);
-        }
-        Ok(values)
+fn json_rename_map(rename: *const TeaDict) -> HashMap<String, String> {
+    if rename.is_null() {
+        return HashMap::new();
     }
+    let entries = unsafe { &(*rename).entries };
+    entries
+        .iter()
+        .map(|(field, key)| {
+            let key = match key.tag {
+                TeaValueTag::String => tea_string_to_rust(unsafe { key.payload.string_value }),
+                _ => None,
+            };
+            let key = key.unwrap_or_else(|| panic!("json rename values must be Strings"));
+            (field.clone(), key)
+        })
+        .collect()
 }
 
-fn tea_dict_to_runtime(dict: *const TeaDict) -> Result<HashMap<String, RuntimeValue>> {
-    if dict.is_null() {
-        return Ok(HashMap::new());
-    }
-    unsafe {
-        let dict_ref = &*dict;
-        let mut map = HashMap::with_capacity(dict_ref.entries.len());
-        for (key, value) in dict_ref.entries.iter() {
-            map.insert(key.clone(), runtime_value_from_tea(*value)?);
+// The shape of a Tea type as described by the schema the compiler emits for
+// `json.decode[T]`.
+enum JsonShape {
+    Int,
+    Float,
+    Bool,
+    String,
+    Bytes,
+    Any,
+    List(Box<JsonShape>),
+    Dict(Box<JsonShape>),
+    Optional(Box<JsonShape>),
+    Struct(String),
+    // An enum, decoded from the name of one of its variants.
+    Enum { name: String, variants: Vec<String> },
+}
+
+impl JsonShape {
+    fn parse(value: &JsonValue) -> Option<JsonShape> {
+        if let Some(name) = value.as_str() {
+            return match name {
+                "Int" => Some(JsonShape::Int),
+                "Float" => Some(JsonShape::Float),
+                "Bool" => Some(JsonShape::Bool),
+                "String" => Some(JsonShape::String),
+                "Bytes" => Some(JsonShape::Bytes),
+                "Any" => Some(JsonShape::Any),
+                _ => None,
+            };
+        }
+        let (kind, inner) = value.as_object()?.iter().next()?;
+        match kind.as_str() {
+            "List" => Some(JsonShape::List(Box::new(JsonShape::parse(inner)?))),
+            "Dict" => Some(JsonShape::Dict(Box::new(JsonShape::parse(inner)?))),
+            "Optional" => Some(JsonShape::Optional(Box::new(JsonShape::parse(inner)?))),
+            "Struct" => Some(JsonShape::Struct(inner.as_str()?.to_string())),
+            "Enum" => Some(JsonShape::Enum {
+                name: inner.get("name")?.as_str()?.to_string(),
+                variants: inner
+                    .get("variants")?
+                    .as_array()?
+                    .iter()
+                    .map(|variant| variant.as_str().map(str::to_string))
+                    .collect::<Option<Vec<_>>>()?,
+            }),
+            _ => None,
         }
-        Ok(map)
     }
 }
 
-fn runtime_value_from_tea(value: TeaValue) -> Result<RuntimeValue> {
-    unsafe {
-        match value.tag {
-            TeaValueTag::Nil => Ok(RuntimeValue::Nil),
-            TeaValueTag::Int => Ok(RuntimeValue::Int(value.payload.int_value)),
-            TeaValueTag::Float => Ok(RuntimeValue::Float(value.payload.float_value)),
-            TeaValueTag::Bool => Ok(RuntimeValue::Bool(value.payload.bool_value != 0)),
-            TeaValueTag::String => Ok(RuntimeValue::String(expect_string(
-                value.payload.string_value,
-                "cli value expects a valid string",
-            ))),
-            TeaValueTag::List => {
-                let items = tea_list_to_runtime(value.payload.list_value)?;
-                Ok(RuntimeValue::List(items))
-            }
-            TeaValueTag::Dict => {
-                let map = tea_dict_to_runtime(value.payload.dict_value)?;
-                Ok(RuntimeValue::Dict(map))
-            }
-            TeaValueTag::Struct | TeaValueTag::Error | TeaValueTag::Closure => {
-                Err(anyhow!("cli spec does not support struct values"))
-            }
-        }
-    }
+struct JsonStructSchema {
+    template: *const TeaStructTemplate,
+    fields: Vec<(String, JsonShape)>,
 }
 
-fn runtime_list_to_tea(items: &[RuntimeValue]) -> Result<TeaValue> {
-    let list = tea_alloc_list(items.len() as c_longlong);
-    for (index, item) in items.iter().enumerate() {
-        let value = runtime_value_to_tea(item)?;
-        tea_list_set(list, index as c_longlong, value);
-    }
-    Ok(tea_value_from_list(list))
+struct JsonDecodeError {
+    // Where the mismatch happened, such as `$.items[2].id`; empty for syntax errors.
+    path: String,
+    message: String,
 }
 
-fn runtime_dict_to_tea(map: &HashMap<String, RuntimeValue>) -> Result<TeaValue> {
-    let dict = tea_dict_new();
-    for (key, value) in map {
-        let value_tea = runtime_value_to_tea(value)?;
+// Struct templates created for decoded values, keyed by struct name and shared
+// across calls so repeated decodes do not leak a template each time.
+static JSON_STRUCT_TEMPLATES: OnceLock<Mutex<HashMap<String, usize>>> = OnceLock::new();
+
+fn json_struct_template(name: &str, field_names: &[&str]) -> *const TeaStructTemplate {
+    let templates = JSON_STRUCT_TEMPLATES.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut table = lock_table(templates);
+    let address = *table.entry(name.to_string()).or_insert_with(|| {
+        let name_ptr = CString::new(name)
+            .expect("struct names do not contain NUL bytes")
+            .into_raw();
+        let names: Vec<*const c_char> = field_names
+            .iter()
+            .map(|field| {
+                CString::new(*field)
+                    .expect("field names do not contain NUL bytes")
+                    .into_raw() as *const c_char
+            })
+            .collect();
+        tea_struct_template_new(name_ptr, names.len() as c_longlong, names.as_ptr()) as usize
+    });
+    address as *const TeaStructTemplate
+}
+
+fn json_kind_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "nil",
+        JsonValue::Bool(_) => "Bool",
+        JsonValue::Number(number) if number.is_f64() => "Float",
+        JsonValue::Number(_) => "Int",
+        JsonValue::String(_) => "String",
+        JsonValue::Array(_) => "List",
+        JsonValue::Object(_) => "Dict",
+    }
+}
+
+struct JsonSchemaDecoder {
+    structs: HashMap<String, JsonStructSchema>,
+    rename: HashMap<String, String>,
+}
+
+impl JsonSchemaDecoder {
+    fn new(schema: &JsonValue, rename: HashMap<String, String>) -> Option<(Self, JsonShape)> {
+        let root = JsonShape::parse(schema.get("type")?)?;
+        let mut structs = HashMap::new();
+        for (name, fields) in schema.get("structs")?.as_object()? {
+            let mut parsed = Vec::new();
+            for field in fields.as_array()? {
+                let field_name = field.get(0)?.as_str()?.to_string();
+                parsed.push((field_name, JsonShape::parse(field.get(1)?)?));
+            }
+            let field_names: Vec<&str> = parsed.iter().map(|(field, _)| field.as_str()).collect();
+            let template = json_struct_template(name, &field_names);
+            structs.insert(
+                name.clone(),
+                JsonStructSchema {
+                    template,
+                    fields: parsed,
+                },
+            );
+        }
+        Some((Self { structs, rename }, root))
+    }
+
+    fn mismatch(path: &str, expected: &str, value: &JsonValue) -> JsonDecodeError {
+        JsonDecodeError {
+            path: path.to_string(),
+            message: format!("expected {expected}, found {}", json_kind_name(value)),
+        }
+    }
+
+    fn decode(
+        &self,
+        value: &JsonValue,
+        shape: &JsonShape,
+        path: &mut String,
+    ) -> Result<TeaValue, JsonDecodeError> {
+        match shape {
+            JsonShape::Any => Ok(json_to_tea_value(value)),
+            JsonShape::Optional(inner) => match value {
+                JsonValue::Null => Ok(tea_value_nil()),
+                _ => self.decode(value, inner, path),
+            },
+            JsonShape::Int => value
+                .as_i64()
+                .map(|number| tea_value_from_int(number))
+                .ok_or_else(|| Self::mismatch(path, "Int", value)),
+            JsonShape::Float => value
+                .as_f64()
+                .map(|number| tea_value_from_float(number))
+                .ok_or_else(|| Self::mismatch(path, "Float", value)),
+            JsonShape::Bool => value
+                .as_bool()
+                .map(|flag| tea_value_from_bool(if flag { 1 } else { 0 }))
+                .ok_or_else(|| Self::mismatch(path, "Bool", value)),
+            JsonShape::String => value
+                .as_str()
+                .map(|text| tea_value_from_string(alloc_tea_string(text)))
+                .ok_or_else(|| Self::mismatch(path, "String", value)),
+            JsonShape::Enum { name, variants } => {
+                let text = value
+                    .as_str()
+                    .ok_or_else(|| Self::mismatch(path, name, value))?;
+                if !variants.iter().any(|variant| variant == text) {
+                    return Err(JsonDecodeError {
+                        path: path.clone(),
+                        message: format!(
+                            "expected one of {} for {name}, found \"{text}\"",
+                            variants.join(", ")
+                        ),
+                    });
+                }
+                Ok(tea_value_from_string(alloc_tea_string(text)))
+            }
+            JsonShape::Bytes => {
+                let text = value
+                    .as_str()
+                    .ok_or_else(|| Self::mismatch(path, "base64 String", value))?;
+                let data = BASE64_STANDARD
+                    .decode(text)
+                    .map_err(|error| JsonDecodeError {
+                        path: path.clone(),
+                        message: format!("invalid base64: {error}"),
+                    })?;
+                Ok(tea_value_from_bytes(alloc_tea_bytes(data)))
+            }
+            JsonShape::List(element) => {
+                let items = value
+                    .as_array()
+                    .ok_or_else(|| Self::mismatch(path, "List", value))?;
+                let list = tea_alloc_list(items.len() as c_longlong);
+                for (index, item) in items.iter().enumerate() {
+                    let length = path.len();
+                    path.push_str(&format!("[{index}]"));
+                    let decoded = self.decode(item, element, path)?;
+                    path.truncate(length);
+                    tea_list_set(list, index as c_longlong, decoded);
+                }
+                Ok(tea_value_from_list(list))
+            }
+            JsonShape::Dict(entry) => {
+                let object = value
+                    .as_object()
+                    .ok_or_else(|| Self::mismatch(path, "Dict", value))?;
+                let dict = tea_dict_new();
+                for (key, item) in object {
+                    let length = path.len();
+                    path.push('.');
+                    path.push_str(key);
+                    let decoded = self.decode(item, entry, path)?;
+                    path.truncate(length);
+                    dict_set_value(dict, key, decoded);
+                }
+                Ok(tea_value_from_dict(dict))
+            }
+            JsonShape::Struct(name) => {
+                let schema = self.structs.get(name).ok_or_else(|| JsonDecodeError {
+                    path: path.clone(),
+                    message: format!("unknown struct '{name}' in decode schema"),
+                })?;
+                let object = value
+                    .as_object()
+                    .ok_or_else(|| Self::mismatch(path, name, value))?;
+                let instance = tea_alloc_struct(schema.template);
+                for (index, (field, field_shape)) in schema.fields.iter().enumerate() {
+                    let key = self.rename.get(field).unwrap_or(field);
+                    let decoded = match object.get(key) {
+                        Some(item) => {
+                            let length = path.len();
+                            path.push('.');
+                            path.push_str(key);
+                            let decoded = self.decode(item, field_shape, path)?;
+                            path.truncate(length);
+                            decoded
+                        }
+                        None if matches!(field_shape, JsonShape::Optional(_)) => tea_value_nil(),
+                        None => {
+                            return Err(JsonDecodeError {
+                                path: path.clone(),
+                                message: format!("missing field '{key}'"),
+                            })
+                        }
+                    };
+                    tea_struct_set_field(instance, index as c_longlong, &decoded);
+                }
+                Ok(tea_value_from_struct(instance))
+            }
+        }
+    }
+}
+
+fn decode_json_with_schema(
+    input: &str,
+    schema: *const c_char,
+    rename: *const TeaDict,
+) -> Result<TeaValue, JsonDecodeError> {
+    let schema_text = tea_cstr_to_rust(schema).expect("json.decode schema must be valid UTF-8");
+    let schema_value: JsonValue =
+        serde_json::from_str(&schema_text).expect("json.decode schema must be valid JSON");
+    let (decoder, root) = JsonSchemaDecoder::new(&schema_value, json_rename_map(rename))
+        .expect("json.decode schema is malformed");
+    let parsed: JsonValue = serde_json::from_str(input).map_err(|error| JsonDecodeError {
+        path: String::new(),
+        message: error.to_string(),
+    })?;
+    let mut path = "$".to_string();
+    decoder.decode(&parsed, &root, &mut path)
+}
+
+#[no_mangle]
+pub extern "C" fn tea_csv_decode_record(
+    out: *mut TeaValue,
+    record: *const TeaDict,
+    schema: *const c_char,
+    rename: *const TeaDict,
+    row: c_longlong,
+) {
+    // Write to out-pointer to avoid ARM64 ABI struct return issues
+    let result = decode_csv_record(record, schema, rename)
+        .unwrap_or_else(|error| panic!("csv record {row}: {error}"));
+    unsafe {
+        *out = result;
+    }
+}
+
+// CSV fields are all text, so each struct field is first parsed from its column
+// into the JSON value its type expects and then built by the JSON schema decoder.
+fn decode_csv_record(
+    record: *const TeaDict,
+    schema: *const c_char,
+    rename: *const TeaDict,
+) -> Result<TeaValue, String> {
+    let schema_text = tea_cstr_to_rust(schema).expect("csv.decode schema must be valid UTF-8");
+    let schema_value: JsonValue =
+        serde_json::from_str(&schema_text).expect("csv.decode schema must be valid JSON");
+    let (decoder, root) = JsonSchemaDecoder::new(&schema_value, json_rename_map(rename))
+        .expect("csv.decode schema is malformed");
+    let JsonShape::Struct(name) = &root else {
+        return Err("csv.decode needs a struct type to decode records into".to_string());
+    };
+    let fields = &decoder
+        .structs
+        .get(name)
+        .expect("csv.decode schema describes its struct")
+        .fields;
+    let entries = unsafe { &(*record).entries };
+    let mut object = serde_json::Map::new();
+    for (field, shape) in fields {
+        let column = decoder.rename.get(field).unwrap_or(field);
+        let scalar = match shape {
+            JsonShape::Optional(inner) => inner,
+            _ => shape,
+        };
+        if matches!(
+            scalar,
+            JsonShape::List(_) | JsonShape::Dict(_) | JsonShape::Struct(_) | JsonShape::Optional(_)
+        ) {
+            return Err(format!(
+                "field '{field}' cannot be read from a CSV column; use String, Int, Float, Bool, Bytes or an enum"
+            ));
+        }
+        let text = match entries.get(column) {
+            Some(value) if matches!(value.tag, TeaValueTag::String) => {
+                tea_string_to_rust(unsafe { value.payload.string_value }).unwrap_or_default()
+            }
+            Some(_) => return Err(format!("column '{column}' is not a String")),
+            None if matches!(shape, JsonShape::Optional(_)) => continue,
+            None => return Err(format!("missing column '{column}'")),
+        };
+        let value = csv_field_to_json(&text, shape).map_err(|expected| {
+            format!("column '{column}': expected {expected}, found {text:?}")
+        })?;
+        object.insert(column.clone(), value);
+    }
+    let mut path = String::new();
+    decoder
+        .decode(&JsonValue::Object(object), &root, &mut path)
+        .map_err(|error| error.message)
+}
+
+// Parse one CSV field as `shape`, or name the type it should have held.
+fn csv_field_to_json(text: &str, shape: &JsonShape) -> Result<JsonValue, &'static str> {
+    match shape {
+        JsonShape::String | JsonShape::Any => Ok(JsonValue::String(text.to_string())),
+        JsonShape::Int => text
+            .trim()
+            .parse::<i64>()
+            .map(JsonValue::from)
+            .map_err(|_| "Int"),
+        JsonShape::Float => text
+            .trim()
+            .parse::<f64>()
+            .map(JsonValue::from)
+            .map_err(|_| "Float"),
+        JsonShape::Bool => match text.trim() {
+            "true" => Ok(JsonValue::Bool(true)),
+            "false" => Ok(JsonValue::Bool(false)),
+            _ => Err("Bool"),
+        },
+        JsonShape::Optional(_) if text.is_empty() => Ok(JsonValue::Null),
+        JsonShape::Optional(inner) => csv_field_to_json(text, inner),
+        // Bytes columns hold base64 text and enum columns a variant name, both
+        // of which the schema decoder checks.
+        JsonShape::Bytes | JsonShape::Enum { .. } => Ok(JsonValue::String(text.to_string())),
+        JsonShape::List(_) | JsonShape::Dict(_) | JsonShape::Struct(_) => Err("a scalar"),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn tea_yaml_encode(value_ptr: *const TeaValue) -> *mut TeaString {
+    // Take by pointer to avoid ARM64 ABI struct passing issues
+    let value = unsafe { *value_ptr };
+    let json_value =
+        tea_value_to_json(value).unwrap_or_else(|error| panic!("failed to encode YAML: {error}"));
+    let yaml_value = serde_yaml::to_string(&json_value)
+        .unwrap_or_else(|error| panic!("failed to encode YAML: {error}"));
+    let bytes = yaml_value.as_bytes();
+    tea_alloc_string(bytes.as_ptr() as *const c_char, bytes.len() as c_longlong)
+}
+
+#[no_mangle]
+pub extern "C" fn tea_yaml_decode(out: *mut TeaValue, text: *const TeaString) {
+    // Write to out-pointer to avoid ARM64 ABI struct return issues
+    let input = expect_string(text, "yaml.decode expects a String argument");
+    let parsed: YamlValue = serde_yaml::from_str(&input)
+        .unwrap_or_else(|error| panic!("failed to decode YAML: {error}"));
+    let json_value = serde_json::to_value(parsed)
+        .unwrap_or_else(|error| panic!("failed to normalise YAML: {error}"));
+    let result = json_to_tea_value(&json_value);
+    unsafe {
+        *out = result;
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn tea_toml_encode(value_ptr: *const TeaValue) -> *mut TeaString {
+    // Take by pointer to avoid ARM64 ABI struct passing issues
+    let value = unsafe { *value_ptr };
+    let json_value =
+        tea_value_to_json(value).unwrap_or_else(|error| panic!("failed to encode TOML: {error}"));
+    if !json_value.is_object() {
+        panic!("failed to encode TOML: a TOML document must be a Dict or struct");
+    }
+    let encoded = toml::to_string(&json_value)
+        .unwrap_or_else(|error| panic!("failed to encode TOML: {error}"));
+    let bytes = encoded.as_bytes();
+    tea_alloc_string(bytes.as_ptr() as *const c_char, bytes.len() as c_longlong)
+}
+
+#[no_mangle]
+pub extern "C" fn tea_toml_decode(out: *mut TeaValue, text: *const TeaString) {
+    // Write to out-pointer to avoid ARM64 ABI struct return issues
+    let input = expect_string(text, "toml.decode expects a String argument");
+    let parsed: toml::Table =
+        toml::from_str(&input).unwrap_or_else(|error| panic!("failed to decode TOML: {error}"));
+    let result = json_to_tea_value(&toml_to_json(toml::Value::Table(parsed)));
+    unsafe {
+        *out = result;
+    }
+}
+
+// Dates and times have no Tea equivalent, so they decode to their TOML text.
+fn toml_to_json(value: toml::Value) -> JsonValue {
+    match value {
+        toml::Value::String(text) => JsonValue::String(text),
+        toml::Value::Integer(number) => JsonValue::from(number),
+        toml::Value::Float(number) => JsonValue::from(number),
+        toml::Value::Boolean(flag) => JsonValue::Bool(flag),
+        toml::Value::Datetime(datetime) => JsonValue::String(datetime.to_string()),
+        toml::Value::Array(items) => {
+            JsonValue::Array(items.into_iter().map(toml_to_json).collect())
+        }
+        toml::Value::Table(table) => JsonValue::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+fn collect_cli_args() -> Vec<String> {
+    env::args_os()
+        .skip(1)
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect()
+}
+
+fn detect_program_name() -> Option<String> {
+    env::args_os().next().map(|arg| {
+        let path = Path::new(&arg);
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| arg.to_string_lossy().into_owned())
+    })
+}
+
+fn tea_list_to_runtime(list: *const TeaList) -> Result<Vec<RuntimeValue>> {
+    if list.is_null() {
+        return Ok(Vec::new());
+    }
+    unsafe {
+        let list_ref = &*list;
+        let (items, len) = tea_list_items(list_ref);
+        let mut values = Vec::with_capacity(len as usize);
+        for index in 0..len {
+            let value = *items.add(index as usize);
+            values.push(runtime_value_from_tea(value)?);
+        }
+        Ok(values)
+    }
+}
+
+fn tea_dict_to_runtime(dict: *const TeaDict) -> Result<HashMap<String, RuntimeValue>> {
+    if dict.is_null() {
+        return Ok(HashMap::new());
+    }
+    unsafe {
+        let dict_ref = &*dict;
+        let mut map = HashMap::with_capacity(dict_ref.entries.len());
+        for (key, value) in dict_ref.entries.iter() {
+            map.insert(key.clone(), runtime_value_from_tea(*value)?);
+        }
+        Ok(map)
+    }
+}
+
+fn runtime_value_from_tea(value: TeaValue) -> Result<RuntimeValue> {
+    unsafe {
+        match value.tag {
+            TeaValueTag::Nil => Ok(RuntimeValue::Nil),
+            TeaValueTag::Int => Ok(RuntimeValue::Int(value.payload.int_value)),
+            TeaValueTag::Float => Ok(RuntimeValue::Float(value.payload.float_value)),
+            TeaValueTag::Bool => Ok(RuntimeValue::Bool(value.payload.bool_value != 0)),
+            TeaValueTag::String => Ok(RuntimeValue::String(expect_string(
+                value.payload.string_value,
+                "cli value expects a valid string",
+            ))),
+            TeaValueTag::List => {
+                let items = tea_list_to_runtime(value.payload.list_value)?;
+                Ok(RuntimeValue::List(items))
+            }
+            TeaValueTag::Dict => {
+                let map = tea_dict_to_runtime(value.payload.dict_value)?;
+                Ok(RuntimeValue::Dict(map))
+            }
+            TeaValueTag::Struct | TeaValueTag::Error | TeaValueTag::Closure => {
+                Err(anyhow!("cli spec does not support struct values"))
+            }
+            TeaValueTag::Bytes => Err(anyhow!("cli spec does not support Bytes values")),
+        }
+    }
+}
+
+fn runtime_list_to_tea(items: &[RuntimeValue]) -> Result<TeaValue> {
+    let list = tea_alloc_list(items.len() as c_longlong);
+    for (index, item) in items.iter().enumerate() {
+        let value = runtime_value_to_tea(item)?;
+        tea_list_set(list, index as c_longlong, value);
+    }
+    Ok(tea_value_from_list(list))
+}
+
+fn runtime_dict_to_tea(map: &HashMap<String, RuntimeValue>) -> Result<TeaValue> {
+    let dict = tea_dict_new();
+    for (key, value) in map {
+        let value_tea = runtime_value_to_tea(value)?;
         let bytes = key.as_bytes();
         let key_ptr = tea_alloc_string(bytes.as_ptr() as *const c_char, bytes.len() as c_longlong);
         dict_set_internal(dict, key_ptr, value_tea);
@@ -4073,15 +5268,6 @@ fn build_process_result_struct(
     Ok(instance)
 }
 
-fn runtime_bytes_value(bytes: &[u8]) -> RuntimeValue {
-    RuntimeValue::List(
-        bytes
-            .iter()
-            .map(|byte| RuntimeValue::Int(i64::from(*byte)))
-            .collect(),
-    )
-}
-
 fn runtime_http_headers(headers: &reqwest::header::HeaderMap) -> RuntimeValue {
     let mut map = HashMap::new();
     for (name, value) in headers {
@@ -4115,8 +5301,11 @@ fn runtime_http_response(
     map.insert("url".to_string(), RuntimeValue::String(url));
     map.insert("headers".to_string(), runtime_http_headers(headers));
     map.insert("body".to_string(), RuntimeValue::String(body));
-    map.insert("body_bytes".to_string(), runtime_bytes_value(&body_bytes));
-    runtime_dict_to_tea(&map)
+    let response = runtime_dict_to_tea(&map)?;
+    let dict = unsafe { response.payload.dict_value } as *mut TeaDict;
+    let body_value = tea_value_from_bytes(alloc_tea_bytes(body_bytes));
+    dict_set_value(dict, "body_bytes", body_value);
+    Ok(response)
 }
 
 #[no_mangle]
@@ -4183,88 +5372,1206 @@ pub extern "C" fn tea_http_send(
     .unwrap_or_else(|error| panic!("{}", http_error("send", &url_text, error)))
 }
 
-#[no_mangle]
-pub extern "C" fn tea_cli_args() -> *mut TeaList {
-    let args = collect_cli_args();
+// ============================================================================
+// HTTP server
+// ============================================================================
 
-    // Use inline storage for up to 8 args (the inline data array size)
-    if args.len() <= 8 {
-        let mut tea_list = TeaList {
-            tag: 1, // inline list
-            len: args.len() as u8,
-            padding: [0; 6],
-            data: [tea_value_nil(); 8],
-        };
-        for (index, arg) in args.iter().enumerate() {
-            let bytes = arg.as_bytes();
-            let string_ptr =
-                tea_alloc_string(bytes.as_ptr() as *const c_char, bytes.len() as c_longlong);
-            tea_list.data[index] = tea_value_from_string(string_ptr);
-        }
-        Box::into_raw(Box::new(tea_list))
-    } else {
-        // Use heap storage for larger lists
-        let list = tea_alloc_list(args.len() as c_longlong);
-        for (index, arg) in args.iter().enumerate() {
-            let bytes = arg.as_bytes();
-            let string_ptr =
-                tea_alloc_string(bytes.as_ptr() as *const c_char, bytes.len() as c_longlong);
-            tea_list_set(list, index as c_longlong, tea_value_from_string(string_ptr));
-        }
-        list
-    }
+// Listeners are non-blocking and polled, so `shutdown`, SIGINT or SIGTERM stop a
+// server between requests instead of cutting off the one being handled.
+struct HttpServer {
+    listener: TcpListener,
+    address: String,
+    stopped: AtomicBool,
 }
 
-/// Get the program name (argv[0] with path stripped).
-#[no_mangle]
-pub extern "C" fn tea_args_program() -> *mut TeaString {
-    let name = detect_program_name().unwrap_or_default();
-    let bytes = name.as_bytes();
-    tea_alloc_string(bytes.as_ptr() as *const c_char, bytes.len() as c_longlong)
+struct HttpConnection {
+    stream: TcpStream,
+    method: String,
+    target: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
 }
 
-// ============================================================================
-// Regex functions
-// ============================================================================
+static HTTP_SERVERS: OnceLock<Mutex<HashMap<i64, Arc<HttpServer>>>> = OnceLock::new();
+static NEXT_HTTP_SERVER: AtomicI64 = AtomicI64::new(1);
+static HTTP_CONNECTIONS: OnceLock<HandleTable<HttpConnection>> = OnceLock::new();
+static NEXT_HTTP_CONNECTION: AtomicI64 = AtomicI64::new(1);
+static HTTP_SHUTDOWN_SIGNALLED: AtomicBool = AtomicBool::new(false);
+static HTTP_SIGNAL_HANDLERS: AtomicBool = AtomicBool::new(false);
 
-/// Compile a regex pattern and return a handle.
-/// Returns -1 if the pattern is invalid.
-#[no_mangle]
-pub extern "C" fn tea_regex_compile(pattern: *const TeaString) -> c_longlong {
-    let pattern_str = expect_string(pattern, "regex.compile expects a valid pattern string");
-    match regex::Regex::new(&pattern_str) {
-        Ok(regex) => {
-            let mut table = regex_handles().lock().unwrap();
-            let handle_id = NEXT_REGEX_HANDLE.fetch_add(1, Ordering::SeqCst);
-            table.insert(handle_id, RegexHandle { regex });
-            handle_id as c_longlong
-        }
-        Err(e) => {
-            panic!("regex error: invalid pattern '{}': {}", pattern_str, e);
-        }
+const HTTP_POLL_INTERVAL: Duration = Duration::from_millis(10);
+// A client has this long to send the request line and headers.
+const HTTP_HEADER_TIMEOUT: Duration = Duration::from_secs(10);
+// Once the headers are in, the body may pause this long between reads.
+const HTTP_READ_TIMEOUT: Duration = Duration::from_secs(30);
+const HTTP_MAX_HEADER_BYTES: usize = 64 * 1024;
+const HTTP_MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+/// Why a request could not be read.
+enum HttpReadError {
+    /// Answered with 400.
+    Malformed(anyhow::Error),
+    /// The body is over `HTTP_MAX_BODY_BYTES`; answered with 413.
+    TooLarge,
+}
+
+impl From<anyhow::Error> for HttpReadError {
+    fn from(error: anyhow::Error) -> Self {
+        HttpReadError::Malformed(error)
     }
 }
 
-/// Test if a regex matches anywhere in the text.
-#[no_mangle]
-pub extern "C" fn tea_regex_is_match(handle: c_longlong, text: *const TeaString) -> c_int {
-    let text_str = expect_string(text, "regex.is_match expects a valid text string");
-    let table = regex_handles().lock().unwrap();
-    let entry = table
-        .get(&(handle as i64))
-        .unwrap_or_else(|| panic!("regex error: invalid regex handle {}", handle));
-    if entry.regex.is_match(&text_str) {
-        1
-    } else {
-        0
+impl From<std::io::Error> for HttpReadError {
+    fn from(error: std::io::Error) -> Self {
+        HttpReadError::Malformed(error.into())
     }
 }
 
-/// Find all matches in the text and return as a list of strings.
-#[no_mangle]
-pub extern "C" fn tea_regex_find_all(handle: c_longlong, text: *const TeaString) -> *mut TeaList {
-    let text_str = expect_string(text, "regex.find_all expects a valid text string");
-    let table = regex_handles().lock().unwrap();
+/// Reads an accepted connection in short polls, so a stopped server or a client
+/// that goes quiet cannot hold `accept` for longer than the current deadline.
+struct HttpRequestReader<'a> {
+    stream: &'a TcpStream,
+    server: &'a HttpServer,
+    deadline: Instant,
+    /// Set once the headers are read: each read that makes progress pushes the
+    /// deadline back by this much.
+    idle_timeout: Option<Duration>,
+}
+
+impl Read for HttpRequestReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            match (&mut &*self.stream).read(buf) {
+                Ok(read) => {
+                    if let Some(timeout) = self.idle_timeout {
+                        self.deadline = Instant::now() + timeout;
+                    }
+                    return Ok(read);
+                }
+                Err(error)
+                    if matches!(
+                        error.kind(),
+                        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    if self.server.stopped.load(Ordering::SeqCst)
+                        || HTTP_SHUTDOWN_SIGNALLED.load(Ordering::SeqCst)
+                    {
+                        return Err(std::io::Error::other("the server is shutting down"));
+                    }
+                    if Instant::now() >= self.deadline {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::TimedOut,
+                            "timed out waiting for the request",
+                        ));
+                    }
+                }
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+fn http_servers() -> &'static Mutex<HashMap<i64, Arc<HttpServer>>> {
+    HTTP_SERVERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn http_connections() -> &'static HandleTable<HttpConnection> {
+    HTTP_CONNECTIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn http_server(handle: c_longlong) -> Arc<HttpServer> {
+    lock_table(http_servers())
+        .get(&handle)
+        .cloned()
+        .unwrap_or_else(|| panic!("invalid http server handle {handle}"))
+}
+
+#[cfg(unix)]
+extern "C" fn http_shutdown_signal(_signal: c_int) {
+    if HTTP_SHUTDOWN_SIGNALLED.swap(true, Ordering::SeqCst) {
+        // A second signal means the user does not want to wait for the request.
+        unsafe { libc::_exit(130) };
+    }
+}
+
+/// While any server is running, SIGINT and SIGTERM ask it to stop after the
+/// current request rather than killing the process.
+fn set_http_signal_handlers(installed: bool) {
+    if HTTP_SIGNAL_HANDLERS.swap(installed, Ordering::SeqCst) == installed {
+        return;
+    }
+    HTTP_SHUTDOWN_SIGNALLED.store(false, Ordering::SeqCst);
+    #[cfg(unix)]
+    unsafe {
+        let handler = if installed {
+            http_shutdown_signal as extern "C" fn(c_int) as libc::sighandler_t
+        } else {
+            libc::SIG_DFL
+        };
+        libc::signal(libc::SIGINT, handler);
+        libc::signal(libc::SIGTERM, handler);
+    }
+}
+
+fn close_http_server(handle: c_longlong) {
+    let mut servers = lock_table(http_servers());
+    servers.remove(&handle);
+    if servers.is_empty() {
+        set_http_signal_handlers(false);
+    }
+}
+
+fn write_http_response(
+    stream: &mut TcpStream,
+    status: i64,
+    headers: &[(String, String)],
+    body: &[u8],
+    include_body: bool,
+) -> std::io::Result<()> {
+    let reason = u16::try_from(status)
+        .ok()
+        .and_then(|code| reqwest::StatusCode::from_u16(code).ok())
+        .and_then(|code| code.canonical_reason())
+        .unwrap_or("");
+    let mut head = format!("HTTP/1.1 {status} {reason}\r\n");
+    for (name, value) in headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str(&format!(
+        "content-length: {}\r\nconnection: close\r\n\r\n",
+        body.len()
+    ));
+    stream.write_all(head.as_bytes())?;
+    if include_body {
+        stream.write_all(body)?;
+    }
+    stream.flush()
+}
+
+fn read_http_line(reader: &mut impl std::io::BufRead, limit: usize) -> Result<String> {
+    let mut line = Vec::new();
+    reader.take(limit as u64 + 1).read_until(b'\n', &mut line)?;
+    if line.len() > limit {
+        return Err(anyhow!("request header is too large"));
+    }
+    if line.last() != Some(&b'\n') {
+        return Err(anyhow!("connection closed before the request was complete"));
+    }
+    line.pop();
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    Ok(String::from_utf8(line)?)
+}
+
+/// Append exactly `length` bytes to `body` as they arrive, rather than allocating
+/// whatever size the client claims up front.
+fn read_http_body_part(
+    reader: &mut impl std::io::BufRead,
+    body: &mut Vec<u8>,
+    length: usize,
+) -> Result<(), HttpReadError> {
+    if length > HTTP_MAX_BODY_BYTES - body.len() {
+        return Err(HttpReadError::TooLarge);
+    }
+    let read = reader.take(length as u64).read_to_end(body)?;
+    if read < length {
+        return Err(anyhow!("connection closed before the request body was complete").into());
+    }
+    Ok(())
+}
+
+fn read_chunked_body(reader: &mut impl std::io::BufRead) -> Result<Vec<u8>, HttpReadError> {
+    let mut body = Vec::new();
+    loop {
+        let size_line = read_http_line(reader, HTTP_MAX_HEADER_BYTES)?;
+        let size_text = size_line.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size_text, 16)
+            .map_err(|_| anyhow!("invalid chunk size '{size_text}'"))?;
+        if size == 0 {
+            // Skip any trailer fields up to the blank line that ends the body.
+            while !read_http_line(reader, HTTP_MAX_HEADER_BYTES)?.is_empty() {}
+            return Ok(body);
+        }
+        read_http_body_part(reader, &mut body, size)?;
+        read_http_line(reader, 2)?;
+    }
+}
+
+fn read_http_request(
+    stream: &TcpStream,
+    server: &HttpServer,
+) -> Result<HttpConnection, HttpReadError> {
+    let mut reader = BufReader::new(HttpRequestReader {
+        stream,
+        server,
+        deadline: Instant::now() + HTTP_HEADER_TIMEOUT,
+        idle_timeout: None,
+    });
+    let request_line = read_http_line(&mut reader, HTTP_MAX_HEADER_BYTES)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(version), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(anyhow!("malformed request line '{request_line}'").into());
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(anyhow!("unsupported protocol '{version}'").into());
+    }
+
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut header_bytes = request_line.len();
+    loop {
+        let line = read_http_line(&mut reader, HTTP_MAX_HEADER_BYTES)?;
+        if line.is_empty() {
+            break;
+        }
+        header_bytes += line.len();
+        if header_bytes > HTTP_MAX_HEADER_BYTES {
+            return Err(anyhow!("request header is too large").into());
+        }
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| anyhow!("malformed header line '{line}'"))?;
+        let name = name.trim().to_ascii_lowercase();
+        let value = value.trim().to_string();
+        match headers.iter_mut().find(|(existing, _)| *existing == name) {
+            Some((_, existing)) => {
+                existing.push_str(", ");
+                existing.push_str(&value);
+            }
+            None => headers.push((name, value)),
+        }
+    }
+
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, value)| value.as_str())
+    };
+    reader.get_mut().deadline = Instant::now() + HTTP_READ_TIMEOUT;
+    reader.get_mut().idle_timeout = Some(HTTP_READ_TIMEOUT);
+    let body = if header("transfer-encoding")
+        .is_some_and(|value| value.to_ascii_lowercase().contains("chunked"))
+    {
+        read_chunked_body(&mut reader)?
+    } else {
+        let length = match header("content-length") {
+            Some(value) => value
+                .parse::<usize>()
+                .map_err(|_| anyhow!("invalid content-length '{value}'"))?,
+            None => 0,
+        };
+        let mut body = Vec::new();
+        read_http_body_part(&mut reader, &mut body, length)?;
+        body
+    };
+
+    Ok(HttpConnection {
+        stream: stream.try_clone()?,
+        method: method.to_string(),
+        target: target.to_string(),
+        headers,
+        body,
+    })
+}
+
+/// Start listening on `address`; port 0 picks a free port.
+#[no_mangle]
+pub extern "C" fn tea_http_server_bind(address: *const TeaString) -> c_longlong {
+    let address_text = expect_string(address, "http.listen expects the address to be a String");
+    let listener = TcpListener::bind(&address_text)
+        .unwrap_or_else(|error| panic!("{}", http_error("listen", &address_text, error)));
+    listener
+        .set_nonblocking(true)
+        .unwrap_or_else(|error| panic!("{}", http_error("listen", &address_text, error)));
+    let bound = listener
+        .local_addr()
+        .map(|address| address.to_string())
+        .unwrap_or(address_text);
+
+    let id = NEXT_HTTP_SERVER.fetch_add(1, Ordering::SeqCst);
+    let mut servers = lock_table(http_servers());
+    servers.insert(
+        id,
+        Arc::new(HttpServer {
+            listener,
+            address: bound,
+            stopped: AtomicBool::new(false),
+        }),
+    );
+    set_http_signal_handlers(true);
+    id
+}
+
+/// The address a server is listening on, with the port it was given.
+#[no_mangle]
+pub extern "C" fn tea_http_server_address(server: c_longlong) -> *mut TeaString {
+    alloc_tea_string(&http_server(server).address)
+}
+
+/// Wait for the next request and return its connection handle, or 0 once the
+/// server has been shut down. Malformed requests are answered with 400 and
+/// oversized bodies with 413 here; a request still being read when the server
+/// stops is dropped.
+#[no_mangle]
+pub extern "C" fn tea_http_server_accept(server: c_longlong) -> c_longlong {
+    let entry = http_server(server);
+    loop {
+        if entry.stopped.load(Ordering::SeqCst) || HTTP_SHUTDOWN_SIGNALLED.load(Ordering::SeqCst) {
+            close_http_server(server);
+            return 0;
+        }
+        let mut stream = match entry.listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {
+                thread::sleep(HTTP_POLL_INTERVAL);
+                continue;
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(error) => panic!("{}", http_error("serve", &entry.address, error)),
+        };
+        if stream.set_nonblocking(false).is_err()
+            || stream.set_read_timeout(Some(HTTP_POLL_INTERVAL)).is_err()
+        {
+            continue;
+        }
+
+        let (status, message) = match read_http_request(&stream, &entry) {
+            Ok(request) => {
+                let id = NEXT_HTTP_CONNECTION.fetch_add(1, Ordering::SeqCst);
+                lock_table(http_connections()).insert(id, Arc::new(Mutex::new(request)));
+                return id;
+            }
+            Err(_)
+                if entry.stopped.load(Ordering::SeqCst)
+                    || HTTP_SHUTDOWN_SIGNALLED.load(Ordering::SeqCst) =>
+            {
+                continue;
+            }
+            Err(HttpReadError::Malformed(error)) => (400, format!("bad request: {error}\n")),
+            Err(HttpReadError::TooLarge) => (
+                413,
+                format!("request body is larger than {HTTP_MAX_BODY_BYTES} bytes\n"),
+            ),
+        };
+        let headers = [(
+            "content-type".to_string(),
+            "text/plain; charset=utf-8".to_string(),
+        )];
+        let _ = write_http_response(&mut stream, status, &headers, message.as_bytes(), true);
+    }
+}
+
+/// The method, target, headers and body of an accepted request.
+#[no_mangle]
+pub extern "C" fn tea_http_server_request(connection: c_longlong) -> TeaValue {
+    let entry = lock_table(http_connections())
+        .get(&connection)
+        .cloned()
+        .unwrap_or_else(|| panic!("invalid http connection handle {connection}"));
+    let entry = lock_table(&entry);
+    let headers = entry
+        .headers
+        .iter()
+        .map(|(name, value)| (name.clone(), RuntimeValue::String(value.clone())))
+        .collect();
+    let mut map = HashMap::new();
+    map.insert(
+        "method".to_string(),
+        RuntimeValue::String(entry.method.clone()),
+    );
+    map.insert(
+        "url".to_string(),
+        RuntimeValue::String(entry.target.clone()),
+    );
+    map.insert("headers".to_string(), RuntimeValue::Dict(headers));
+    map.insert(
+        "body".to_string(),
+        RuntimeValue::String(String::from_utf8_lossy(&entry.body).into_owned()),
+    );
+    runtime_dict_to_tea(&map)
+        .unwrap_or_else(|error| panic!("{}", http_error("serve", &entry.target, error)))
+}
+
+/// Send the response for a request and close its connection.
+#[no_mangle]
+pub extern "C" fn tea_http_server_respond(
+    connection: c_longlong,
+    status: c_longlong,
+    headers: *const TeaDict,
+    body: *const TeaBytes,
+) {
+    let entry = lock_table(http_connections())
+        .remove(&connection)
+        .unwrap_or_else(|| panic!("invalid http connection handle {connection}"));
+    let mut entry = lock_table(&entry);
+    if !(100..=999).contains(&status) {
+        panic!(
+            "{}",
+            http_error("respond", &entry.target, format!("invalid status {status}"))
+        );
+    }
+    let headers_map = tea_value_dict_to_string_map(tea_value_from_dict(headers))
+        .unwrap_or_else(|error| panic!("{}", http_error("respond", &entry.target, error)));
+    let body = expect_bytes(body, "http.respond");
+
+    // A line break would let a header name or value start a header of its own.
+    if let Some((name, _)) = headers_map
+        .iter()
+        .find(|(name, value)| name.contains(['\r', '\n']) || value.contains(['\r', '\n']))
+    {
+        panic!(
+            "{}",
+            http_error(
+                "respond",
+                &entry.target,
+                format!("header {name:?} contains a line break")
+            )
+        );
+    }
+    let mut response_headers: Vec<(String, String)> = headers_map
+        .into_iter()
+        .map(|(name, value)| (name.to_ascii_lowercase(), value))
+        .filter(|(name, _)| name != "content-length" && name != "connection")
+        .collect();
+    response_headers.sort();
+    if !body.is_empty()
+        && !response_headers
+            .iter()
+            .any(|(name, _)| name == "content-type")
+    {
+        response_headers.push((
+            "content-type".to_string(),
+            "text/plain; charset=utf-8".to_string(),
+        ));
+    }
+    // HEAD responses describe the body without sending it.
+    let include_body = entry.method != "HEAD";
+    // The client may already have gone away; that only affects this request.
+    let _ = write_http_response(
+        &mut entry.stream,
+        status,
+        &response_headers,
+        body,
+        include_body,
+    );
+    let _ = entry.stream.shutdown(std::net::Shutdown::Write);
+}
+
+/// Stop a server once the request it is handling, if any, has been answered.
+#[no_mangle]
+pub extern "C" fn tea_http_server_shutdown(server: c_longlong) {
+    if let Some(entry) = lock_table(http_servers()).get(&server) {
+        entry.stopped.store(true, Ordering::SeqCst);
+    }
+}
+
+// ============================================================================
+// Networking
+// ============================================================================
+
+// Every socket lives in one handle table. Failures do not panic: they are recorded
+// in a per-thread slot that `std.net` reads back and throws as a `NetError`.
+enum NetStream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+enum NetListener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener, PathBuf),
+}
+
+enum NetSocket {
+    Stream {
+        stream: NetStream,
+        pending: Vec<u8>,
+    },
+    Listener(NetListener),
+    Udp {
+        socket: UdpSocket,
+        last_sender: String,
+    },
+}
+
+struct NetFailure {
+    kind: &'static str,
+    message: String,
+}
+
+static NET_SOCKETS: OnceLock<HandleTable<NetSocket>> = OnceLock::new();
+static NEXT_NET_SOCKET: AtomicI64 = AtomicI64::new(1);
+
+const NET_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+thread_local! {
+    static NET_ERROR: RefCell<Option<NetFailure>> = const { RefCell::new(None) };
+}
+
+fn net_sockets() -> &'static HandleTable<NetSocket> {
+    NET_SOCKETS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn net_socket(handle: c_longlong) -> Arc<Mutex<NetSocket>> {
+    lock_table(net_sockets())
+        .get(&handle)
+        .cloned()
+        .unwrap_or_else(|| panic!("invalid socket handle {handle}"))
+}
+
+fn net_register(socket: NetSocket) -> c_longlong {
+    let id = NEXT_NET_SOCKET.fetch_add(1, Ordering::SeqCst);
+    lock_table(net_sockets()).insert(id, Arc::new(Mutex::new(socket)));
+    id
+}
+
+fn net_timeout(timeout_ms: c_longlong) -> Option<Duration> {
+    (timeout_ms > 0).then(|| Duration::from_millis(timeout_ms as u64))
+}
+
+fn net_io_failure(action: &str, error: std::io::Error) -> NetFailure {
+    match error.kind() {
+        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => NetFailure {
+            kind: "timeout",
+            message: format!("{action} timed out"),
+        },
+        _ => NetFailure {
+            kind: "io",
+            message: format!("{action} failed: {error}"),
+        },
+    }
+}
+
+/// Run a socket operation, recording its failure for `tea_net_error_kind` and
+/// returning `fallback` in its place.
+fn net_attempt<T>(fallback: T, operation: impl FnOnce() -> Result<T, NetFailure>) -> T {
+    let result = operation();
+    NET_ERROR.with(|slot| match result {
+        Ok(value) => {
+            *slot.borrow_mut() = None;
+            value
+        }
+        Err(failure) => {
+            *slot.borrow_mut() = Some(failure);
+            fallback
+        }
+    })
+}
+
+impl NetStream {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()> {
+        match self {
+            NetStream::Tcp(stream) => stream.set_read_timeout(timeout),
+            #[cfg(unix)]
+            NetStream::Unix(stream) => stream.set_read_timeout(timeout),
+        }
+    }
+
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()> {
+        match self {
+            NetStream::Tcp(stream) => stream.set_write_timeout(timeout),
+            #[cfg(unix)]
+            NetStream::Unix(stream) => stream.set_write_timeout(timeout),
+        }
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            NetStream::Tcp(stream) => stream.read(buffer),
+            #[cfg(unix)]
+            NetStream::Unix(stream) => stream.read(buffer),
+        }
+    }
+
+    fn write_all(&mut self, data: &[u8]) -> std::io::Result<()> {
+        match self {
+            NetStream::Tcp(stream) => stream.write_all(data),
+            #[cfg(unix)]
+            NetStream::Unix(stream) => stream.write_all(data),
+        }
+    }
+
+    fn shutdown_write(&self) -> std::io::Result<()> {
+        match self {
+            NetStream::Tcp(stream) => stream.shutdown(std::net::Shutdown::Write),
+            #[cfg(unix)]
+            NetStream::Unix(stream) => stream.shutdown(std::net::Shutdown::Write),
+        }
+    }
+
+    fn local_address(&self) -> String {
+        match self {
+            NetStream::Tcp(stream) => stream
+                .local_addr()
+                .map(|address| address.to_string())
+                .unwrap_or_default(),
+            #[cfg(unix)]
+            NetStream::Unix(stream) => stream
+                .local_addr()
+                .ok()
+                .and_then(|address| address.as_pathname().map(|path| path.display().to_string()))
+                .unwrap_or_default(),
+        }
+    }
+
+    fn peer_address(&self) -> String {
+        match self {
+            NetStream::Tcp(stream) => stream
+                .peer_addr()
+                .map(|address| address.to_string())
+                .unwrap_or_default(),
+            #[cfg(unix)]
+            NetStream::Unix(stream) => stream
+                .peer_addr()
+                .ok()
+                .and_then(|address| address.as_pathname().map(|path| path.display().to_string()))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+fn net_connect_tcp(address: &str, timeout: Option<Duration>) -> Result<TcpStream, NetFailure> {
+    let action = format!("connect to {address}");
+    let Some(timeout) = timeout else {
+        return TcpStream::connect(address).map_err(|error| net_io_failure(&action, error));
+    };
+    let candidates = address
+        .to_socket_addrs()
+        .map_err(|error| net_io_failure(&action, error))?;
+    let mut last_error = None;
+    for candidate in candidates {
+        match TcpStream::connect_timeout(&candidate, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(error) => last_error = Some(error),
+        }
+    }
+    Err(net_io_failure(
+        &action,
+        last_error.unwrap_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "no addresses found")
+        }),
+    ))
+}
+
+#[cfg(not(unix))]
+fn net_unix_unsupported() -> NetFailure {
+    NetFailure {
+        kind: "io",
+        message: "unix domain sockets are not supported on this platform".to_string(),
+    }
+}
+
+/// Connect to a TCP address such as "127.0.0.1:6379"; returns 0 on failure.
+#[no_mangle]
+pub extern "C" fn tea_net_tcp_connect(
+    address: *const TeaString,
+    timeout_ms: c_longlong,
+) -> c_longlong {
+    let address = expect_string(address, "net.connect expects the address to be a String");
+    net_attempt(0, || {
+        let stream = net_connect_tcp(&address, net_timeout(timeout_ms))?;
+        Ok(net_register(NetSocket::Stream {
+            stream: NetStream::Tcp(stream),
+            pending: Vec::new(),
+        }))
+    })
+}
+
+/// Listen for TCP connections; port 0 picks a free port.
+#[no_mangle]
+pub extern "C" fn tea_net_tcp_listen(address: *const TeaString) -> c_longlong {
+    let address = expect_string(address, "net.listen expects the address to be a String");
+    net_attempt(0, || {
+        let listener = TcpListener::bind(&address)
+            .map_err(|error| net_io_failure(&format!("listen on {address}"), error))?;
+        Ok(net_register(NetSocket::Listener(NetListener::Tcp(
+            listener,
+        ))))
+    })
+}
+
+/// Connect to a Unix domain socket at `path`; returns 0 on failure.
+#[no_mangle]
+pub extern "C" fn tea_net_unix_connect(path: *const TeaString) -> c_longlong {
+    let path = expect_string(path, "net.connect_unix expects the path to be a String");
+    net_attempt(0, || {
+        #[cfg(unix)]
+        {
+            let stream = UnixStream::connect(&path)
+                .map_err(|error| net_io_failure(&format!("connect to {path}"), error))?;
+            Ok(net_register(NetSocket::Stream {
+                stream: NetStream::Unix(stream),
+                pending: Vec::new(),
+            }))
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = path;
+            Err(net_unix_unsupported())
+        }
+    })
+}
+
+/// Listen on a Unix domain socket at `path`. The socket file is removed on close.
+#[no_mangle]
+pub extern "C" fn tea_net_unix_listen(path: *const TeaString) -> c_longlong {
+    let path = expect_string(path, "net.listen_unix expects the path to be a String");
+    net_attempt(0, || {
+        #[cfg(unix)]
+        {
+            let listener = UnixListener::bind(&path)
+                .map_err(|error| net_io_failure(&format!("listen on {path}"), error))?;
+            Ok(net_register(NetSocket::Listener(NetListener::Unix(
+                listener,
+                PathBuf::from(&path),
+            ))))
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = path;
+            Err(net_unix_unsupported())
+        }
+    })
+}
+
+/// Bind a UDP socket; port 0 picks a free port.
+#[no_mangle]
+pub extern "C" fn tea_net_udp_bind(address: *const TeaString) -> c_longlong {
+    let address = expect_string(address, "net.bind_udp expects the address to be a String");
+    net_attempt(0, || {
+        let socket = UdpSocket::bind(&address)
+            .map_err(|error| net_io_failure(&format!("bind {address}"), error))?;
+        Ok(net_register(NetSocket::Udp {
+            socket,
+            last_sender: String::new(),
+        }))
+    })
+}
+
+fn net_accept_polling<T>(
+    timeout: Option<Duration>,
+    mut accept: impl FnMut() -> std::io::Result<T>,
+) -> std::io::Result<T> {
+    let deadline = timeout.map(|timeout| std::time::Instant::now() + timeout);
+    loop {
+        match accept() {
+            Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {
+                if deadline.is_some_and(|deadline| std::time::Instant::now() >= deadline) {
+                    return Err(error);
+                }
+                thread::sleep(NET_POLL_INTERVAL);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Wait for the next connection on a listener; returns 0 on failure.
+#[no_mangle]
+pub extern "C" fn tea_net_accept(listener: c_longlong, timeout_ms: c_longlong) -> c_longlong {
+    let entry = net_socket(listener);
+    let entry = lock_table(&entry);
+    let NetSocket::Listener(listener) = &*entry else {
+        panic!("net.accept expects a listener");
+    };
+    let timeout = net_timeout(timeout_ms);
+    net_attempt(0, || {
+        let stream = match listener {
+            NetListener::Tcp(listener) => {
+                let action = format!(
+                    "accept on {}",
+                    listener
+                        .local_addr()
+                        .map(|address| address.to_string())
+                        .unwrap_or_default()
+                );
+                listener
+                    .set_nonblocking(timeout.is_some())
+                    .map_err(|error| net_io_failure(&action, error))?;
+                let (stream, _) = net_accept_polling(timeout, || listener.accept())
+                    .map_err(|error| net_io_failure(&action, error))?;
+                stream
+                    .set_nonblocking(false)
+                    .map_err(|error| net_io_failure(&action, error))?;
+                NetStream::Tcp(stream)
+            }
+            #[cfg(unix)]
+            NetListener::Unix(listener, path) => {
+                let action = format!("accept on {}", path.display());
+                listener
+                    .set_nonblocking(timeout.is_some())
+                    .map_err(|error| net_io_failure(&action, error))?;
+                let (stream, _) = net_accept_polling(timeout, || listener.accept())
+                    .map_err(|error| net_io_failure(&action, error))?;
+                stream
+                    .set_nonblocking(false)
+                    .map_err(|error| net_io_failure(&action, error))?;
+                NetStream::Unix(stream)
+            }
+        };
+        Ok(net_register(NetSocket::Stream {
+            stream,
+            pending: Vec::new(),
+        }))
+    })
+}
+
+/// Read up to `max` bytes as soon as any arrive; empty at end of stream.
+#[no_mangle]
+pub extern "C" fn tea_net_read(
+    connection: c_longlong,
+    max: c_longlong,
+    timeout_ms: c_longlong,
+) -> *mut TeaBytes {
+    let entry = net_socket(connection);
+    let mut entry = lock_table(&entry);
+    let NetSocket::Stream { stream, pending } = &mut *entry else {
+        panic!("net.read expects a connection");
+    };
+    let max = max.max(1) as usize;
+    let data = net_attempt(Vec::new(), || {
+        if !pending.is_empty() {
+            let count = max.min(pending.len());
+            return Ok(pending.drain(..count).collect());
+        }
+        let action = format!("read from {}", stream.peer_address());
+        stream
+            .set_read_timeout(net_timeout(timeout_ms))
+            .map_err(|error| net_io_failure(&action, error))?;
+        let mut buffer = vec![0; max];
+        let count = stream
+            .read(&mut buffer)
+            .map_err(|error| net_io_failure(&action, error))?;
+        buffer.truncate(count);
+        Ok(buffer)
+    });
+    alloc_tea_bytes(data)
+}
+
+/// Read through the next "\n", keeping it; empty at end of stream. A final line
+/// without a line break is returned as is.
+#[no_mangle]
+pub extern "C" fn tea_net_read_line(
+    connection: c_longlong,
+    timeout_ms: c_longlong,
+) -> *mut TeaString {
+    let entry = net_socket(connection);
+    let mut entry = lock_table(&entry);
+    let NetSocket::Stream { stream, pending } = &mut *entry else {
+        panic!("net.read_line expects a connection");
+    };
+    let line = net_attempt(Vec::new(), || {
+        let action = format!("read from {}", stream.peer_address());
+        stream
+            .set_read_timeout(net_timeout(timeout_ms))
+            .map_err(|error| net_io_failure(&action, error))?;
+        let mut buffer = [0; 4096];
+        loop {
+            if let Some(end) = pending.iter().position(|byte| *byte == b'\n') {
+                return Ok(pending.drain(..=end).collect());
+            }
+            let count = stream
+                .read(&mut buffer)
+                .map_err(|error| net_io_failure(&action, error))?;
+            if count == 0 {
+                return Ok(std::mem::take(pending));
+            }
+            pending.extend_from_slice(&buffer[..count]);
+        }
+    });
+    alloc_tea_string(&String::from_utf8_lossy(&line))
+}
+
+/// Write all of `data` to a connection.
+#[no_mangle]
+pub extern "C" fn tea_net_write(
+    connection: c_longlong,
+    data: *const TeaBytes,
+    timeout_ms: c_longlong,
+) {
+    let data = expect_bytes(data, "net.write");
+    let entry = net_socket(connection);
+    let mut entry = lock_table(&entry);
+    let NetSocket::Stream { stream, .. } = &mut *entry else {
+        panic!("net.write expects a connection");
+    };
+    net_attempt((), || {
+        let action = format!("write to {}", stream.peer_address());
+        stream
+            .set_write_timeout(net_timeout(timeout_ms))
+            .map_err(|error| net_io_failure(&action, error))?;
+        stream
+            .write_all(data)
+            .map_err(|error| net_io_failure(&action, error))
+    })
+}
+
+/// Signal the end of the data sent on a connection while still reading replies.
+#[no_mangle]
+pub extern "C" fn tea_net_shutdown_write(connection: c_longlong) {
+    let entry = net_socket(connection);
+    let entry = lock_table(&entry);
+    let NetSocket::Stream { stream, .. } = &*entry else {
+        panic!("net.shutdown_write expects a connection");
+    };
+    net_attempt((), || {
+        stream
+            .shutdown_write()
+            .map_err(|error| net_io_failure(&format!("shut down {}", stream.peer_address()), error))
+    })
+}
+
+/// The local address of a socket, or the path of a Unix socket.
+#[no_mangle]
+pub extern "C" fn tea_net_local_address(handle: c_longlong) -> *mut TeaString {
+    let entry = net_socket(handle);
+    let entry = lock_table(&entry);
+    let address = match &*entry {
+        NetSocket::Stream { stream, .. } => stream.local_address(),
+        NetSocket::Listener(NetListener::Tcp(listener)) => listener
+            .local_addr()
+            .map(|address| address.to_string())
+            .unwrap_or_default(),
+        #[cfg(unix)]
+        NetSocket::Listener(NetListener::Unix(_, path)) => path.display().to_string(),
+        NetSocket::Udp { socket, .. } => socket
+            .local_addr()
+            .map(|address| address.to_string())
+            .unwrap_or_default(),
+    };
+    alloc_tea_string(&address)
+}
+
+/// The address at the other end of a connection.
+#[no_mangle]
+pub extern "C" fn tea_net_peer_address(connection: c_longlong) -> *mut TeaString {
+    let entry = net_socket(connection);
+    let entry = lock_table(&entry);
+    let NetSocket::Stream { stream, .. } = &*entry else {
+        panic!("net.peer_address expects a connection");
+    };
+    alloc_tea_string(&stream.peer_address())
+}
+
+/// Send one datagram and return the number of bytes sent.
+#[no_mangle]
+pub extern "C" fn tea_net_udp_send(
+    socket: c_longlong,
+    address: *const TeaString,
+    data: *const TeaBytes,
+) -> c_longlong {
+    let address = expect_string(address, "net.send_to expects the address to be a String");
+    let data = expect_bytes(data, "net.send_to");
+    let entry = net_socket(socket);
+    let entry = lock_table(&entry);
+    let NetSocket::Udp { socket, .. } = &*entry else {
+        panic!("net.send_to expects a UDP socket");
+    };
+    net_attempt(0, || {
+        socket
+            .send_to(data, &address)
+            .map(|count| count as c_longlong)
+            .map_err(|error| net_io_failure(&format!("send to {address}"), error))
+    })
+}
+
+/// Wait for one datagram of up to `max` bytes; `tea_net_udp_sender` names its sender.
+#[no_mangle]
+pub extern "C" fn tea_net_udp_receive(
+    socket: c_longlong,
+    max: c_longlong,
+    timeout_ms: c_longlong,
+) -> *mut TeaBytes {
+    let entry = net_socket(socket);
+    let mut entry = lock_table(&entry);
+    let NetSocket::Udp {
+        socket,
+        last_sender,
+    } = &mut *entry
+    else {
+        panic!("net.receive expects a UDP socket");
+    };
+    let data = net_attempt(Vec::new(), || {
+        let action = format!(
+            "receive on {}",
+            socket
+                .local_addr()
+                .map(|address| address.to_string())
+                .unwrap_or_default()
+        );
+        socket
+            .set_read_timeout(net_timeout(timeout_ms))
+            .map_err(|error| net_io_failure(&action, error))?;
+        let mut buffer = vec![0; max.max(1) as usize];
+        let (count, sender) = socket
+            .recv_from(&mut buffer)
+            .map_err(|error| net_io_failure(&action, error))?;
+        buffer.truncate(count);
+        *last_sender = sender.to_string();
+        Ok(buffer)
+    });
+    alloc_tea_bytes(data)
+}
+
+/// The sender of the last datagram received on a UDP socket.
+#[no_mangle]
+pub extern "C" fn tea_net_udp_sender(socket: c_longlong) -> *mut TeaString {
+    let entry = net_socket(socket);
+    let entry = lock_table(&entry);
+    let NetSocket::Udp { last_sender, .. } = &*entry else {
+        panic!("net.receive expects a UDP socket");
+    };
+    alloc_tea_string(last_sender)
+}
+
+/// Close a socket. Closing a Unix listener removes its socket file.
+#[no_mangle]
+pub extern "C" fn tea_net_close(handle: c_longlong) {
+    let Some(entry) = lock_table(net_sockets()).remove(&handle) else {
+        return;
+    };
+    let socket = lock_table(&entry);
+    #[cfg(unix)]
+    if let NetSocket::Listener(NetListener::Unix(_, path)) = &*socket {
+        let _ = fs::remove_file(path);
+    }
+    drop(socket);
+}
+
+/// "timeout" or "io" when the last socket operation on this thread failed, else "".
+#[no_mangle]
+pub extern "C" fn tea_net_error_kind() -> *mut TeaString {
+    NET_ERROR
+        .with(|slot| alloc_tea_string(slot.borrow().as_ref().map_or("", |failure| failure.kind)))
+}
+
+/// The message of the last failed socket operation on this thread.
+#[no_mangle]
+pub extern "C" fn tea_net_error_message() -> *mut TeaString {
+    NET_ERROR.with(|slot| {
+        alloc_tea_string(
+            slot.borrow()
+                .as_ref()
+                .map_or("", |failure| failure.message.as_str()),
+        )
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tea_cli_args() -> *mut TeaList {
+    let args = collect_cli_args();
+
+    // Use inline storage for up to 8 args (the inline data array size)
+    if args.len() <= 8 {
+        let mut tea_list = TeaList {
+            tag: 1, // inline list
+            len: args.len() as u8,
+            padding: [0; 6],
+            data: [tea_value_nil(); 8],
+        };
+        for (index, arg) in args.iter().enumerate() {
+            let bytes = arg.as_bytes();
+            let string_ptr =
+                tea_alloc_string(bytes.as_ptr() as *const c_char, bytes.len() as c_longlong);
+            tea_list.data[index] = tea_value_from_string(string_ptr);
+        }
+        Box::into_raw(Box::new(tea_list))
+    } else {
+        // Use heap storage for larger lists
+        let list = tea_alloc_list(args.len() as c_longlong);
+        for (index, arg) in args.iter().enumerate() {
+            let bytes = arg.as_bytes();
+            let string_ptr =
+                tea_alloc_string(bytes.as_ptr() as *const c_char, bytes.len() as c_longlong);
+            tea_list_set(list, index as c_longlong, tea_value_from_string(string_ptr));
+        }
+        list
+    }
+}
+
+/// Get the program name (argv[0] with path stripped).
+#[no_mangle]
+pub extern "C" fn tea_args_program() -> *mut TeaString {
+    let name = detect_program_name().unwrap_or_default();
+    let bytes = name.as_bytes();
+    tea_alloc_string(bytes.as_ptr() as *const c_char, bytes.len() as c_longlong)
+}
+
+// ============================================================================
+// Random numbers
+// ============================================================================
+
+#[no_mangle]
+pub extern "C" fn tea_random_seeded(seed: c_longlong) -> c_longlong {
+    let handle = NEXT_RANDOM_HANDLE.fetch_add(1, Ordering::SeqCst);
+    lock_table(random_generators())
+        .insert(handle, tea_intrinsics::random::Generator::from_seed(seed));
+    handle
+}
+
+#[no_mangle]
+pub extern "C" fn tea_random_int(
+    handle: c_longlong,
+    low: c_longlong,
+    high: c_longlong,
+) -> c_longlong {
+    with_random_generator(handle, |generator| generator.int_in(low, high))
+        .unwrap_or_else(|message| panic!("{message}"))
+}
+
+#[no_mangle]
+pub extern "C" fn tea_random_float(handle: c_longlong) -> c_double {
+    with_random_generator(handle, |generator| generator.float())
+}
+
+// ============================================================================
+// Regex functions
+// ============================================================================
+
+/// Compile a regex pattern and return a handle.
+/// Returns -1 if the pattern is invalid.
+#[no_mangle]
+pub extern "C" fn tea_regex_compile(pattern: *const TeaString) -> c_longlong {
+    let pattern_str = expect_string(pattern, "regex.compile expects a valid pattern string");
+    match regex::Regex::new(&pattern_str) {
+        Ok(regex) => {
+            let mut table = lock_table(regex_handles());
+            let handle_id = NEXT_REGEX_HANDLE.fetch_add(1, Ordering::SeqCst);
+            table.insert(handle_id, RegexHandle { regex });
+            handle_id as c_longlong
+        }
+        Err(e) => {
+            panic!("regex error: invalid pattern '{}': {}", pattern_str, e);
+        }
+    }
+}
+
+/// Test if a regex matches anywhere in the text.
+#[no_mangle]
+pub extern "C" fn tea_regex_is_match(handle: c_longlong, text: *const TeaString) -> c_int {
+    let text_str = expect_string(text, "regex.is_match expects a valid text string");
+    let table = lock_table(regex_handles());
+    let entry = table
+        .get(&(handle as i64))
+        .unwrap_or_else(|| panic!("regex error: invalid regex handle {}", handle));
+    if entry.regex.is_match(&text_str) {
+        1
+    } else {
+        0
+    }
+}
+
+/// Find all matches in the text and return as a list of strings.
+#[no_mangle]
+pub extern "C" fn tea_regex_find_all(handle: c_longlong, text: *const TeaString) -> *mut TeaList {
+    let text_str = expect_string(text, "regex.find_all expects a valid text string");
+    let table = lock_table(regex_handles());
     let entry = table
         .get(&(handle as i64))
         .unwrap_or_else(|| panic!("regex error: invalid regex handle {}", handle));
@@ -4288,7 +6595,7 @@ pub extern "C" fn tea_regex_find_all(handle: c_longlong, text: *const TeaString)
 #[no_mangle]
 pub extern "C" fn tea_regex_captures(handle: c_longlong, text: *const TeaString) -> *mut TeaList {
     let text_str = expect_string(text, "regex.captures expects a valid text string");
-    let table = regex_handles().lock().unwrap();
+    let table = lock_table(regex_handles());
     let entry = table
         .get(&(handle as i64))
         .unwrap_or_else(|| panic!("regex error: invalid regex handle {}", handle));
@@ -4326,7 +6633,7 @@ pub extern "C" fn tea_regex_replace(
         replacement,
         "regex.replace expects a valid replacement string",
     );
-    let table = regex_handles().lock().unwrap();
+    let table = lock_table(regex_handles());
     let entry = table
         .get(&(handle as i64))
         .unwrap_or_else(|| panic!("regex error: invalid regex handle {}", handle));
@@ -4347,7 +6654,7 @@ pub extern "C" fn tea_regex_replace_all(
         replacement,
         "regex.replace_all expects a valid replacement string",
     );
-    let table = regex_handles().lock().unwrap();
+    let table = lock_table(regex_handles());
     let entry = table
         .get(&(handle as i64))
         .unwrap_or_else(|| panic!("regex error: invalid regex handle {}", handle));
@@ -4360,7 +6667,7 @@ pub extern "C" fn tea_regex_replace_all(
 #[no_mangle]
 pub extern "C" fn tea_regex_split(handle: c_longlong, text: *const TeaString) -> *mut TeaList {
     let text_str = expect_string(text, "regex.split expects a valid text string");
-    let table = regex_handles().lock().unwrap();
+    let table = lock_table(regex_handles());
     let entry = table
         .get(&(handle as i64))
         .unwrap_or_else(|| panic!("regex error: invalid regex handle {}", handle));
@@ -4457,7 +6764,8 @@ pub extern "C" fn tea_process_run(
     for (key, value) in &env_map {
         command_proc.env(key, value);
     }
-    if let Some(dir) = &cwd_str {
+    // An empty cwd keeps the current working directory.
+    if let Some(dir) = cwd_str.as_deref().filter(|dir| !dir.is_empty()) {
         command_proc.current_dir(dir);
     }
     if stdin_text.is_some() {
@@ -4535,7 +6843,8 @@ pub extern "C" fn tea_process_spawn(
     for (key, value) in &env_map {
         command_proc.env(key, value);
     }
-    if let Some(dir) = &cwd_str {
+    // An empty cwd keeps the current working directory.
+    if let Some(dir) = cwd_str.as_deref().filter(|dir| !dir.is_empty()) {
         command_proc.current_dir(dir);
     }
     command_proc.stdin(Stdio::piped());
@@ -4549,31 +6858,30 @@ pub extern "C" fn tea_process_spawn(
     let stderr = child.stderr.take().map(BufReader::new);
     let stdin = child.stdin.take();
 
-    let mut table = process_handles().lock().unwrap();
     let handle_id = NEXT_PROCESS_HANDLE.fetch_add(1, Ordering::SeqCst);
-    table.insert(
+    lock_table(process_handles()).insert(
         handle_id,
-        ProcessHandleEntry {
+        Arc::new(Mutex::new(ProcessHandleEntry {
             child,
             stdout,
             stderr,
             stdin,
             command: command_str,
-        },
+        })),
     );
     handle_id as c_longlong
 }
 
 #[no_mangle]
 pub extern "C" fn tea_process_read_stdout(handle: c_longlong, size: c_longlong) -> *mut TeaString {
-    let mut table = process_handles().lock().unwrap();
     let target = format!("handle {}", handle);
-    let entry = table.get_mut(&(handle as i64)).unwrap_or_else(|| {
+    let entry = process_handle(handle).unwrap_or_else(|| {
         panic!(
             "{}",
             process_error("read_stdout", &target, "invalid process handle")
         )
     });
+    let mut entry = lock_table(&entry);
     let command = entry.command.clone();
     let limit = if size <= 0 { None } else { Some(size as usize) };
     let output = read_process_pipe(&mut entry.stdout, limit)
@@ -4585,32 +6893,56 @@ pub extern "C" fn tea_process_read_stdout(handle: c_longlong, size: c_longlong)
 pub extern "C" fn tea_process_read_stdout_bytes(
     handle: c_longlong,
     size: c_longlong,
-) -> *mut TeaList {
-    let mut table = process_handles().lock().unwrap();
+) -> *mut TeaBytes {
     let target = format!("handle {}", handle);
-    let entry = table.get_mut(&(handle as i64)).unwrap_or_else(|| {
+    let entry = process_handle(handle).unwrap_or_else(|| {
         panic!(
             "{}",
             process_error("read_stdout_bytes", &target, "invalid process handle")
         )
     });
+    let mut entry = lock_table(&entry);
     let command = entry.command.clone();
     let limit = if size <= 0 { None } else { Some(size as usize) };
     let output = read_process_pipe_bytes(&mut entry.stdout, limit)
         .unwrap_or_else(|error| panic!("{}", process_error("read_stdout_bytes", &command, error)));
-    bytes_to_list(&output)
+    alloc_tea_bytes(output)
+}
+
+/// Read the next stdout line into `out`, or Nil once the stream is exhausted.
+#[no_mangle]
+pub extern "C" fn tea_process_read_stdout_line(out: *mut TeaValue, handle: c_longlong) {
+    let target = format!("handle {}", handle);
+    let entry = process_handle(handle).unwrap_or_else(|| {
+        panic!(
+            "{}",
+            process_error("read_stdout_line", &target, "invalid process handle")
+        )
+    });
+    let mut entry = lock_table(&entry);
+    let command = entry.command.clone();
+    let line = match entry.stdout.as_mut() {
+        Some(reader) => read_text_line(reader).unwrap_or_else(|error| {
+            panic!("{}", process_error("read_stdout_line", &command, error))
+        }),
+        None => None,
+    };
+    drop(entry);
+    unsafe {
+        *out = line_to_value(line);
+    }
 }
 
 #[no_mangle]
 pub extern "C" fn tea_process_read_stderr(handle: c_longlong, size: c_longlong) -> *mut TeaString {
-    let mut table = process_handles().lock().unwrap();
     let target = format!("handle {}", handle);
-    let entry = table.get_mut(&(handle as i64)).unwrap_or_else(|| {
+    let entry = process_handle(handle).unwrap_or_else(|| {
         panic!(
             "{}",
             process_error("read_stderr", &target, "invalid process handle")
         )
     });
+    let mut entry = lock_table(&entry);
     let command = entry.command.clone();
     let limit = if size <= 0 { None } else { Some(size as usize) };
     let output = read_process_pipe(&mut entry.stderr, limit)
@@ -4622,32 +6954,32 @@ pub extern "C" fn tea_process_read_stderr(handle: c_longlong, size: c_longlong)
 pub extern "C" fn tea_process_read_stderr_bytes(
     handle: c_longlong,
     size: c_longlong,
-) -> *mut TeaList {
-    let mut table = process_handles().lock().unwrap();
+) -> *mut TeaBytes {
     let target = format!("handle {}", handle);
-    let entry = table.get_mut(&(handle as i64)).unwrap_or_else(|| {
+    let entry = process_handle(handle).unwrap_or_else(|| {
         panic!(
             "{}",
             process_error("read_stderr_bytes", &target, "invalid process handle")
         )
     });
+    let mut entry = lock_table(&entry);
     let command = entry.command.clone();
     let limit = if size <= 0 { None } else { Some(size as usize) };
     let output = read_process_pipe_bytes(&mut entry.stderr, limit)
         .unwrap_or_else(|error| panic!("{}", process_error("read_stderr_bytes", &command, error)));
-    bytes_to_list(&output)
+    alloc_tea_bytes(output)
 }
 
 #[no_mangle]
 pub extern "C" fn tea_process_write_stdin(handle: c_longlong, data: TeaValue) {
-    let mut table = process_handles().lock().unwrap();
     let target = format!("handle {}", handle);
-    let entry = table.get_mut(&(handle as i64)).unwrap_or_else(|| {
+    let entry = process_handle(handle).unwrap_or_else(|| {
         panic!(
             "{}",
             process_error("write_stdin", &target, "invalid process handle")
         )
     });
+    let mut entry = lock_table(&entry);
     let command = entry.command.clone();
     let input = match data.tag {
         TeaValueTag::String => unsafe {
@@ -4674,19 +7006,19 @@ pub extern "C" fn tea_process_write_stdin(handle: c_longlong, data: TeaValue) {
 }
 
 #[no_mangle]
-pub extern "C" fn tea_process_write_stdin_bytes(handle: c_longlong, data: *const TeaList) {
-    let mut table = process_handles().lock().unwrap();
+pub extern "C" fn tea_process_write_stdin_bytes(handle: c_longlong, data: *const TeaBytes) {
     let target = format!("handle {}", handle);
-    let entry = table.get_mut(&(handle as i64)).unwrap_or_else(|| {
+    let entry = process_handle(handle).unwrap_or_else(|| {
         panic!(
             "{}",
             process_error("write_stdin_bytes", &target, "invalid process handle")
         )
     });
+    let mut entry = lock_table(&entry);
     let command = entry.command.clone();
-    let input = tea_list_to_bytes(data, "write_stdin_bytes");
+    let input = expect_bytes(data, "write_stdin_bytes");
     if let Some(stdin) = entry.stdin.as_mut() {
-        stdin.write_all(&input).unwrap_or_else(|error| {
+        stdin.write_all(input).unwrap_or_else(|error| {
             panic!("{}", process_error("write_stdin_bytes", &command, error))
         });
     } else {
@@ -4699,14 +7031,14 @@ pub extern "C" fn tea_process_write_stdin_bytes(handle: c_longlong, data: *const
 
 #[no_mangle]
 pub extern "C" fn tea_process_close_stdin(handle: c_longlong) {
-    let mut table = process_handles().lock().unwrap();
     let target = format!("handle {}", handle);
-    let entry = table.get_mut(&(handle as i64)).unwrap_or_else(|| {
+    let entry = process_handle(handle).unwrap_or_else(|| {
         panic!(
             "{}",
             process_error("close_stdin", &target, "invalid process handle")
         )
     });
+    let mut entry = lock_table(&entry);
     entry.stdin.take();
 }
 
@@ -4715,14 +7047,16 @@ pub extern "C" fn tea_process_wait(
     template: *const TeaStructTemplate,
     handle: c_longlong,
 ) -> *mut TeaStructInstance {
-    let mut table = process_handles().lock().unwrap();
     let target = format!("handle {}", handle);
-    let mut entry = table.remove(&(handle as i64)).unwrap_or_else(|| {
-        panic!(
-            "{}",
-            process_error("wait", &target, "invalid process handle")
-        )
-    });
+    let entry = lock_table(process_handles())
+        .remove(&handle)
+        .unwrap_or_else(|| {
+            panic!(
+                "{}",
+                process_error("wait", &target, "invalid process handle")
+            )
+        });
+    let mut entry = lock_table(&entry);
     let command = entry.command.clone();
     let status = entry
         .child
@@ -4746,14 +7080,14 @@ pub extern "C" fn tea_process_wait(
 
 #[no_mangle]
 pub extern "C" fn tea_process_kill(handle: c_longlong) -> c_int {
-    let mut table = process_handles().lock().unwrap();
     let target = format!("handle {}", handle);
-    let entry = table.get_mut(&(handle as i64)).unwrap_or_else(|| {
+    let entry = process_handle(handle).unwrap_or_else(|| {
         panic!(
             "{}",
             process_error("kill", &target, "invalid process handle")
         )
     });
+    let mut entry = lock_table(&entry);
     let command = entry.command.clone();
     entry
         .child
@@ -4764,9 +7098,9 @@ pub extern "C" fn tea_process_kill(handle: c_longlong) -> c_int {
 
 #[no_mangle]
 pub extern "C" fn tea_process_close(handle: c_longlong) {
-    let mut table = process_handles().lock().unwrap();
-    if let Some(mut entry) = table.remove(&(handle as i64)) {
-        let _ = entry.child.kill();
+    let entry = lock_table(process_handles()).remove(&handle);
+    if let Some(entry) = entry {
+        let _ = lock_table(&entry).child.kill();
     }
 }
 
@@ -4785,47 +7119,125 @@ pub extern "C" fn tea_string_equal(left: *const TeaString, right: *const TeaStri
             return 0;
         }
 
-        let left_bytes = tea_string_as_bytes(left_ref);
-        let right_bytes = tea_string_as_bytes(right_ref);
-        if left_bytes == right_bytes {
-            1
-        } else {
-            0
-        }
-    }
+        let left_bytes = tea_string_as_bytes(left_ref);
+        let right_bytes = tea_string_as_bytes(right_ref);
+        if left_bytes == right_bytes {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn tea_string_replace(
+    text: *const TeaString,
+    search: *const TeaString,
+    replacement: *const TeaString,
+) -> *mut TeaString {
+    let text = expect_string(text, "string_replace expects the text to be a String");
+    let search = expect_string(search, "string_replace expects the pattern to be a String");
+    let replacement = expect_string(
+        replacement,
+        "string_replace expects the replacement to be a String",
+    );
+    let replaced = tea_intrinsics::string::replace(&text, &search, &replacement);
+    let bytes = replaced.as_bytes();
+    tea_alloc_string(bytes.as_ptr() as *const c_char, bytes.len() as c_longlong)
+}
+
+#[no_mangle]
+pub extern "C" fn tea_string_to_lower(text: *const TeaString) -> *mut TeaString {
+    let text = expect_string(text, "string_to_lower expects the text to be a String");
+    let lowered = tea_intrinsics::string::to_lower(&text);
+    let bytes = lowered.as_bytes();
+    tea_alloc_string(bytes.as_ptr() as *const c_char, bytes.len() as c_longlong)
+}
+
+#[no_mangle]
+pub extern "C" fn tea_string_to_upper(text: *const TeaString) -> *mut TeaString {
+    let text = expect_string(text, "string_to_upper expects the text to be a String");
+    let upper = tea_intrinsics::string::to_upper(&text);
+    let bytes = upper.as_bytes();
+    tea_alloc_string(bytes.as_ptr() as *const c_char, bytes.len() as c_longlong)
+}
+
+fn tea_string_list_from_parts(parts: &[String]) -> *mut TeaList {
+    let values: Vec<TeaValue> = parts
+        .iter()
+        .map(|part| tea_value_from_string(alloc_tea_string(part)))
+        .collect();
+    tea_list_from_values(&values)
+}
+
+#[no_mangle]
+pub extern "C" fn tea_string_chars(text: *const TeaString) -> *mut TeaList {
+    let text = expect_string(text, "string_chars expects the text to be a String");
+    tea_string_list_from_parts(&tea_intrinsics::string::chars(&text))
+}
+
+#[no_mangle]
+pub extern "C" fn tea_string_graphemes(text: *const TeaString) -> *mut TeaList {
+    let text = expect_string(text, "string_graphemes expects the text to be a String");
+    tea_string_list_from_parts(&tea_intrinsics::string::graphemes(&text))
+}
+
+#[no_mangle]
+pub extern "C" fn tea_string_code_points(text: *const TeaString) -> *mut TeaList {
+    let text = expect_string(text, "string_code_points expects the text to be a String");
+    let values: Vec<TeaValue> = tea_intrinsics::string::code_points(&text)
+        .into_iter()
+        .map(|point| tea_value_from_int(point))
+        .collect();
+    tea_list_from_values(&values)
+}
+
+#[no_mangle]
+pub extern "C" fn tea_string_char_count(text: *const TeaString) -> c_longlong {
+    let text = expect_string(text, "string length expects the text to be a String");
+    tea_intrinsics::string::char_count(&text)
+}
+
+#[no_mangle]
+pub extern "C" fn tea_string_display_width(text: *const TeaString) -> c_longlong {
+    let text = expect_string(text, "string_display_width expects the text to be a String");
+    tea_intrinsics::string::display_width(&text)
+}
+
+#[no_mangle]
+pub extern "C" fn tea_string_fold_case(text: *const TeaString) -> *mut TeaString {
+    let text = expect_string(text, "string_fold_case expects the text to be a String");
+    alloc_tea_string(&tea_intrinsics::string::fold_case(&text))
+}
+
+#[no_mangle]
+pub extern "C" fn tea_string_normalize_nfc(text: *const TeaString) -> *mut TeaString {
+    let text = expect_string(text, "string_normalize_nfc expects the text to be a String");
+    alloc_tea_string(&tea_intrinsics::string::normalize_nfc(&text))
 }
 
 #[no_mangle]
-pub extern "C" fn tea_string_replace(
-    text: *const TeaString,
-    search: *const TeaString,
-    replacement: *const TeaString,
-) -> *mut TeaString {
-    let text = expect_string(text, "string_replace expects the text to be a String");
-    let search = expect_string(search, "string_replace expects the pattern to be a String");
-    let replacement = expect_string(
-        replacement,
-        "string_replace expects the replacement to be a String",
-    );
-    let replaced = tea_intrinsics::string::replace(&text, &search, &replacement);
-    let bytes = replaced.as_bytes();
-    tea_alloc_string(bytes.as_ptr() as *const c_char, bytes.len() as c_longlong)
+pub extern "C" fn tea_string_normalize_nfd(text: *const TeaString) -> *mut TeaString {
+    let text = expect_string(text, "string_normalize_nfd expects the text to be a String");
+    alloc_tea_string(&tea_intrinsics::string::normalize_nfd(&text))
 }
 
 #[no_mangle]
-pub extern "C" fn tea_string_to_lower(text: *const TeaString) -> *mut TeaString {
-    let text = expect_string(text, "string_to_lower expects the text to be a String");
-    let lowered = tea_intrinsics::string::to_lower(&text);
-    let bytes = lowered.as_bytes();
-    tea_alloc_string(bytes.as_ptr() as *const c_char, bytes.len() as c_longlong)
+pub extern "C" fn tea_string_is_alpha(text: *const TeaString) -> c_int {
+    let text = expect_string(text, "string_is_alpha expects the text to be a String");
+    tea_intrinsics::string::is_alpha(&text) as c_int
 }
 
 #[no_mangle]
-pub extern "C" fn tea_string_to_upper(text: *const TeaString) -> *mut TeaString {
-    let text = expect_string(text, "string_to_upper expects the text to be a String");
-    let upper = tea_intrinsics::string::to_upper(&text);
-    let bytes = upper.as_bytes();
-    tea_alloc_string(bytes.as_ptr() as *const c_char, bytes.len() as c_longlong)
+pub extern "C" fn tea_string_is_digit(text: *const TeaString) -> c_int {
+    let text = expect_string(text, "string_is_digit expects the text to be a String");
+    tea_intrinsics::string::is_digit(&text) as c_int
+}
+
+#[no_mangle]
+pub extern "C" fn tea_string_is_whitespace(text: *const TeaString) -> c_int {
+    let text = expect_string(text, "string_is_whitespace expects the text to be a String");
+    tea_intrinsics::string::is_whitespace(&text) as c_int
 }
 
 #[no_mangle]
@@ -4906,6 +7318,17 @@ pub extern "C" fn tea_value_as_dict(value_ptr: *const TeaValue) -> *const TeaDic
     }
 }
 
+#[no_mangle]
+pub extern "C" fn tea_value_as_bytes(value_ptr: *const TeaValue) -> *const TeaBytes {
+    unsafe {
+        let value = *value_ptr;
+        match value.tag {
+            TeaValueTag::Bytes => value.payload.bytes_value,
+            _ => panic!("tea_value_as_bytes: value is not Bytes"),
+        }
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn tea_value_as_struct(value_ptr: *const TeaValue) -> *const TeaStructInstance {
     unsafe {
@@ -4989,6 +7412,14 @@ pub extern "C" fn tea_value_from_dict(value: *const TeaDict) -> TeaValue {
     }
 }
 
+#[no_mangle]
+pub extern "C" fn tea_value_from_bytes(value: *const TeaBytes) -> TeaValue {
+    TeaValue {
+        tag: TeaValueTag::Bytes,
+        payload: TeaValuePayload { bytes_value: value },
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn tea_value_from_struct(value: *const TeaStructInstance) -> TeaValue {
     TeaValue {
@@ -5025,6 +7456,417 @@ pub extern "C" fn tea_value_nil() -> TeaValue {
     }
 }
 
+// ============================================================================
+// Tasks and channels
+// ============================================================================
+
+/// Moves Tea values and closures onto task threads.
+///
+/// The typechecker only lets a spawned closure capture values that are safe to
+/// share, and channel values change owner when they are received.
+struct ThreadValue<T>(T);
+
+unsafe impl<T> Send for ThreadValue<T> {}
+
+impl<T> ThreadValue<T> {
+    fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Entry point generated for each spawned closure's return type; it stores the
+/// closure's result in the out-pointer.
+type TaskEntry = extern "C" fn(*const TeaClosure, *mut TeaValue);
+
+static TASK_HANDLES: OnceLock<Mutex<HashMap<i64, thread::JoinHandle<ThreadValue<TeaValue>>>>> =
+    OnceLock::new();
+static NEXT_TASK_HANDLE: AtomicI64 = AtomicI64::new(1);
+
+fn task_handles() -> &'static Mutex<HashMap<i64, thread::JoinHandle<ThreadValue<TeaValue>>>> {
+    TASK_HANDLES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+struct ChannelState {
+    values: VecDeque<ThreadValue<TeaValue>>,
+    closed: bool,
+}
+
+struct Channel {
+    capacity: usize,
+    state: Mutex<ChannelState>,
+    readable: Condvar,
+    writable: Condvar,
+}
+
+static CHANNELS: OnceLock<Mutex<HashMap<i64, Arc<Channel>>>> = OnceLock::new();
+static NEXT_CHANNEL: AtomicI64 = AtomicI64::new(1);
+
+fn channels() -> &'static Mutex<HashMap<i64, Arc<Channel>>> {
+    CHANNELS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn channel(operation: &str, handle: c_longlong) -> Arc<Channel> {
+    lock_table(channels())
+        .get(&handle)
+        .cloned()
+        .unwrap_or_else(|| {
+            let target = format!("handle {handle}");
+            panic!(
+                "{}",
+                task_error(operation, &target, "invalid channel handle")
+            )
+        })
+}
+
+#[no_mangle]
+pub extern "C" fn tea_task_spawn(closure: *const TeaClosure, entry: TaskEntry) -> c_longlong {
+    if closure.is_null() {
+        panic!("{}", task_error("spawn", "closure", "closure is null"));
+    }
+    let handle_id = NEXT_TASK_HANDLE.fetch_add(1, Ordering::SeqCst);
+    let closure = ThreadValue(closure);
+    let thread = thread::Builder::new()
+        .name(format!("tea-task-{handle_id}"))
+        .spawn(move || {
+            let mut result = tea_value_nil();
+            entry(closure.into_inner(), &mut result);
+            ThreadValue(result)
+        })
+        .unwrap_or_else(|error| {
+            let target = format!("handle {handle_id}");
+            panic!("{}", task_error("spawn", &target, error))
+        });
+    lock_table(task_handles()).insert(handle_id, thread);
+    handle_id as c_longlong
+}
+
+/// Wait for a task and write its result to `out`. Returns via out-pointer for ARM64 ABI compatibility.
+#[no_mangle]
+pub extern "C" fn tea_task_join(out: *mut TeaValue, handle: c_longlong) {
+    let target = format!("handle {handle}");
+    let thread = lock_table(task_handles())
+        .remove(&handle)
+        .unwrap_or_else(|| {
+            panic!(
+                "{}",
+                task_error("join", &target, "invalid or already joined task handle")
+            )
+        });
+    let result = thread
+        .join()
+        .unwrap_or_else(|_| panic!("{}", task_error("join", &target, "task panicked")));
+    unsafe {
+        *out = result.into_inner();
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn tea_channel_new(capacity: c_longlong) -> c_longlong {
+    if capacity <= 0 {
+        let target = capacity.to_string();
+        panic!(
+            "{}",
+            task_error("channel", &target, "capacity must be positive")
+        );
+    }
+    let handle_id = NEXT_CHANNEL.fetch_add(1, Ordering::SeqCst);
+    lock_table(channels()).insert(
+        handle_id,
+        Arc::new(Channel {
+            capacity: capacity as usize,
+            state: Mutex::new(ChannelState {
+                values: VecDeque::new(),
+                closed: false,
+            }),
+            readable: Condvar::new(),
+            writable: Condvar::new(),
+        }),
+    );
+    handle_id as c_longlong
+}
+
+/// Send a value, blocking while the channel is full. Returns 0 once the channel is closed.
+#[no_mangle]
+pub extern "C" fn tea_channel_send(handle: c_longlong, value: *const TeaValue) -> c_int {
+    let channel = channel("send", handle);
+    let value = unsafe { *value };
+    let mut state = lock_table(&channel.state);
+    while !state.closed && state.values.len() >= channel.capacity {
+        state = channel
+            .writable
+            .wait(state)
+            .unwrap_or_else(PoisonError::into_inner);
+    }
+    if state.closed {
+        return 0;
+    }
+    state.values.push_back(ThreadValue(value));
+    channel.readable.notify_one();
+    1
+}
+
+/// Receive the next value into `out`, or Nil once the channel is closed and drained.
+#[no_mangle]
+pub extern "C" fn tea_channel_recv(out: *mut TeaValue, handle: c_longlong) {
+    let channel = channel("recv", handle);
+    let mut state = lock_table(&channel.state);
+    while state.values.is_empty() && !state.closed {
+        state = channel
+            .readable
+            .wait(state)
+            .unwrap_or_else(PoisonError::into_inner);
+    }
+    let value = match state.values.pop_front() {
+        Some(value) => {
+            channel.writable.notify_one();
+            value.into_inner()
+        }
+        None => tea_value_nil(),
+    };
+    unsafe {
+        *out = value;
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn tea_channel_close(handle: c_longlong) {
+    let channel = channel("close", handle);
+    lock_table(&channel.state).closed = true;
+    channel.readable.notify_all();
+    channel.writable.notify_all();
+}
+
+// ============================================================================
+// Generators
+// ============================================================================
+
+// A generator body runs on its own thread, but the handoff below only ever lets
+// one side run: `next` wakes the body and sleeps until it yields or finishes, and
+// `yield` wakes the consumer and sleeps until the next `next`.
+//
+// Closing a suspended generator wakes its body with the `cancelled` flag set, so
+// the pending `yield` returns 0 and the compiled body returns straight away,
+// closing the iterators it owns on the way out. Files the body opened and did
+// not close are closed once it has returned.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum GeneratorTurn {
+    Consumer,
+    Producer,
+}
+
+struct GeneratorState {
+    turn: GeneratorTurn,
+    start: Option<(ThreadValue<*const TeaClosure>, TaskEntry)>,
+    value: Option<ThreadValue<TeaValue>>,
+    finished: bool,
+    cancelled: bool,
+    panic: Option<Box<dyn std::any::Any + Send>>,
+    open_files: Vec<i64>,
+}
+
+struct Generator {
+    state: Mutex<GeneratorState>,
+    resumed: Condvar,
+}
+
+impl Generator {
+    /// Hand the turn to the body and wait until it yields or returns.
+    fn resume<'a>(
+        &self,
+        mut state: MutexGuard<'a, GeneratorState>,
+    ) -> MutexGuard<'a, GeneratorState> {
+        state.turn = GeneratorTurn::Producer;
+        self.resumed.notify_all();
+        while state.turn == GeneratorTurn::Producer {
+            state = self
+                .resumed
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        state
+    }
+}
+
+static GENERATORS: OnceLock<Mutex<HashMap<i64, Arc<Generator>>>> = OnceLock::new();
+static NEXT_GENERATOR: AtomicI64 = AtomicI64::new(1);
+
+fn generators() -> &'static Mutex<HashMap<i64, Arc<Generator>>> {
+    GENERATORS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+thread_local! {
+    static CURRENT_GENERATOR: RefCell<Option<Arc<Generator>>> = const { RefCell::new(None) };
+}
+
+/// Remember a file opened by the running generator body, if any.
+fn track_generator_file(handle: i64, open: bool) {
+    CURRENT_GENERATOR.with(|current| {
+        if let Some(generator) = current.borrow().as_ref() {
+            let mut state = lock_table(&generator.state);
+            if open {
+                state.open_files.push(handle);
+            } else {
+                state.open_files.retain(|file| *file != handle);
+            }
+        }
+    });
+}
+
+/// Create a generator whose body is `closure`; it starts on the first `tea_iter_next`.
+#[no_mangle]
+pub extern "C" fn tea_generator_new(closure: *const TeaClosure, entry: TaskEntry) -> c_longlong {
+    if closure.is_null() {
+        panic!("{}", iter_error("generator", "closure", "closure is null"));
+    }
+    let handle_id = NEXT_GENERATOR.fetch_add(1, Ordering::SeqCst);
+    lock_table(generators()).insert(
+        handle_id,
+        Arc::new(Generator {
+            state: Mutex::new(GeneratorState {
+                turn: GeneratorTurn::Consumer,
+                start: Some((ThreadValue(closure), entry)),
+                value: None,
+                finished: false,
+                cancelled: false,
+                panic: None,
+                open_files: Vec::new(),
+            }),
+            resumed: Condvar::new(),
+        }),
+    );
+    handle_id as c_longlong
+}
+
+/// Resume a generator and write its next value to `out`. Returns 0 once it has finished.
+///
+/// Finished and closed generators are dropped from the table, and advancing
+/// them again keeps returning 0.
+#[no_mangle]
+pub extern "C" fn tea_iter_next(out: *mut TeaValue, handle: c_longlong) -> c_int {
+    let target = format!("handle {handle}");
+    let Some(generator) = lock_table(generators()).get(&handle).cloned() else {
+        if handle <= 0 || handle >= NEXT_GENERATOR.load(Ordering::SeqCst) {
+            panic!("{}", iter_error("next", &target, "invalid iterator handle"));
+        }
+        unsafe {
+            *out = tea_value_nil();
+        }
+        return 0;
+    };
+    let mut state = lock_table(&generator.state);
+    if !state.finished {
+        if let Some((closure, entry)) = state.start.take() {
+            let body = Arc::clone(&generator);
+            thread::Builder::new()
+                .name(format!("tea-generator-{handle}"))
+                .spawn(move || run_generator(body, closure, entry))
+                .unwrap_or_else(|error| panic!("{}", iter_error("next", &target, error)));
+        }
+        state = generator.resume(state);
+    }
+    let (value, produced) = match state.value.take() {
+        Some(value) => (value.into_inner(), 1),
+        None => (tea_value_nil(), 0),
+    };
+    if state.finished {
+        let panic = state.panic.take();
+        drop(state);
+        lock_table(generators()).remove(&handle);
+        if let Some(payload) = panic {
+            std::panic::resume_unwind(payload);
+        }
+    }
+    unsafe {
+        *out = value;
+    }
+    produced
+}
+
+/// Stop a generator early. A suspended body is resumed with its pending
+/// `yield` returning 0 and runs to its end before this returns.
+#[no_mangle]
+pub extern "C" fn tea_iter_close(handle: c_longlong) {
+    let Some(generator) = lock_table(generators()).remove(&handle) else {
+        return;
+    };
+    let mut state = lock_table(&generator.state);
+    state.cancelled = true;
+    // A body that never started has nothing to unwind, and one that is running
+    // (it is further up this thread's or another consumer's stack) stops at
+    // its next `yield`.
+    if state.finished || state.start.take().is_some() || state.turn == GeneratorTurn::Producer {
+        return;
+    }
+    state = generator.resume(state);
+    if let Some(payload) = state.panic.take() {
+        drop(state);
+        std::panic::resume_unwind(payload);
+    }
+}
+
+fn run_generator(
+    generator: Arc<Generator>,
+    closure: ThreadValue<*const TeaClosure>,
+    entry: TaskEntry,
+) {
+    CURRENT_GENERATOR.with(|current| *current.borrow_mut() = Some(Arc::clone(&generator)));
+    {
+        let mut state = lock_table(&generator.state);
+        while state.turn == GeneratorTurn::Consumer {
+            state = generator
+                .resumed
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+    let closure = closure.into_inner();
+    let outcome = std::panic::catch_unwind(|| {
+        let mut result = tea_value_nil();
+        entry(closure, &mut result);
+    });
+    let mut state = lock_table(&generator.state);
+    if state.cancelled {
+        let mut files = lock_table(fs_handles());
+        for file in state.open_files.drain(..) {
+            files.remove(&file);
+        }
+    }
+    state.finished = true;
+    state.panic = outcome.err();
+    state.turn = GeneratorTurn::Consumer;
+    generator.resumed.notify_all();
+}
+
+/// Hand `value` to the consumer of the running generator and wait to be resumed.
+///
+/// Returns 0 when the generator has been closed and the body should return.
+#[no_mangle]
+pub extern "C" fn tea_generator_yield(value: *const TeaValue) -> c_int {
+    let generator = CURRENT_GENERATOR
+        .with(|current| current.borrow().clone())
+        .unwrap_or_else(|| {
+            panic!(
+                "{}",
+                iter_error("yield", "generator", "yield called outside a generator")
+            )
+        });
+    let value = unsafe { *value };
+    let mut state = lock_table(&generator.state);
+    if state.cancelled {
+        return 0;
+    }
+    state.value = Some(ThreadValue(value));
+    state.turn = GeneratorTurn::Consumer;
+    generator.resumed.notify_all();
+    while state.turn == GeneratorTurn::Consumer {
+        state = generator
+            .resumed
+            .wait(state)
+            .unwrap_or_else(PoisonError::into_inner);
+    }
+    c_int::from(!state.cancelled)
+}
+
 // ============================================================================
 // Standard I/O intrinsics
 // ============================================================================
@@ -5080,6 +7922,162 @@ pub extern "C" fn tea_is_tty() -> c_int {
     }
 }
 
+/// Check if a standard stream (0 = stdin, 1 = stdout, 2 = stderr) is a terminal
+#[no_mangle]
+pub extern "C" fn tea_term_is_terminal(stream: c_longlong) -> c_int {
+    use std::io::IsTerminal;
+    let terminal = match stream {
+        0 => std::io::stdin().is_terminal(),
+        1 => std::io::stdout().is_terminal(),
+        2 => std::io::stderr().is_terminal(),
+        _ => false,
+    };
+    terminal as c_int
+}
+
+// Ask each standard stream in turn, since any of them may be redirected.
+#[cfg(unix)]
+fn terminal_window_size() -> Option<(u16, u16)> {
+    for fd in [libc::STDOUT_FILENO, libc::STDERR_FILENO, libc::STDIN_FILENO] {
+        let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+        let status = unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, &mut size) };
+        if status == 0 && size.ws_col > 0 && size.ws_row > 0 {
+            return Some((size.ws_col, size.ws_row));
+        }
+    }
+    None
+}
+
+#[cfg(not(unix))]
+fn terminal_window_size() -> Option<(u16, u16)> {
+    None
+}
+
+/// Width of the terminal in columns, or 0 when no stream is a terminal
+#[no_mangle]
+pub extern "C" fn tea_term_columns() -> c_longlong {
+    terminal_window_size().map_or(0, |(columns, _)| columns as c_longlong)
+}
+
+/// Height of the terminal in rows, or 0 when no stream is a terminal
+#[no_mangle]
+pub extern "C" fn tea_term_rows() -> c_longlong {
+    terminal_window_size().map_or(0, |(_, rows)| rows as c_longlong)
+}
+
+/// Terminal settings from before echo was turned off. Written only while
+/// `ECHO_HIDDEN` is clear and read only while it is set, so the signal handler
+/// can restore them without taking a lock.
+#[cfg(unix)]
+struct SavedTermios(std::cell::UnsafeCell<std::mem::MaybeUninit<libc::termios>>);
+
+#[cfg(unix)]
+unsafe impl Sync for SavedTermios {}
+
+#[cfg(unix)]
+static SAVED_TERMIOS: SavedTermios =
+    SavedTermios(std::cell::UnsafeCell::new(std::mem::MaybeUninit::uninit()));
+#[cfg(unix)]
+static ECHO_HIDDEN: AtomicBool = AtomicBool::new(false);
+#[cfg(unix)]
+static PREVIOUS_SIGINT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+#[cfg(unix)]
+static PREVIOUS_SIGTERM: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+#[cfg(unix)]
+static ECHO_PANIC_HOOK: std::sync::Once = std::sync::Once::new();
+
+/// Put back the settings saved by `tea_term_set_echo(0)`, if echo is still off.
+/// Only calls async-signal-safe functions.
+#[cfg(unix)]
+fn restore_terminal_echo() -> bool {
+    if !ECHO_HIDDEN.swap(false, Ordering::SeqCst) {
+        return false;
+    }
+    unsafe {
+        let saved = (*SAVED_TERMIOS.0.get()).as_ptr();
+        libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, saved) == 0
+    }
+}
+
+#[cfg(not(unix))]
+fn restore_terminal_echo() -> bool {
+    false
+}
+
+/// Restore echo before SIGINT or SIGTERM reaches whatever handled it before,
+/// so interrupting a password prompt does not leave the shell silent.
+#[cfg(unix)]
+extern "C" fn echo_restore_signal(signal: c_int) {
+    restore_terminal_echo();
+    let previous = if signal == libc::SIGINT {
+        &PREVIOUS_SIGINT
+    } else {
+        &PREVIOUS_SIGTERM
+    };
+    unsafe {
+        libc::signal(signal, previous.load(Ordering::SeqCst));
+        libc::raise(signal);
+    }
+}
+
+#[cfg(unix)]
+fn set_echo_signal_handlers(installed: bool) {
+    unsafe {
+        if installed {
+            let handler = echo_restore_signal as extern "C" fn(c_int) as libc::sighandler_t;
+            PREVIOUS_SIGINT.store(libc::signal(libc::SIGINT, handler), Ordering::SeqCst);
+            PREVIOUS_SIGTERM.store(libc::signal(libc::SIGTERM, handler), Ordering::SeqCst);
+        } else {
+            libc::signal(libc::SIGINT, PREVIOUS_SIGINT.load(Ordering::SeqCst));
+            libc::signal(libc::SIGTERM, PREVIOUS_SIGTERM.load(Ordering::SeqCst));
+        }
+    }
+}
+
+/// Turn echoing of typed characters on stdin on or off.
+/// Returns 0 when stdin is not a terminal and nothing was changed.
+///
+/// While echo is off, SIGINT, SIGTERM, a panic or `exit` put the original
+/// terminal settings back first.
+#[no_mangle]
+pub extern "C" fn tea_term_set_echo(enabled: c_int) -> c_int {
+    #[cfg(unix)]
+    {
+        if enabled != 0 && ECHO_HIDDEN.load(Ordering::SeqCst) {
+            let restored = restore_terminal_echo();
+            set_echo_signal_handlers(false);
+            return restored as c_int;
+        }
+        let mut settings: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut settings) } != 0 {
+            return 0;
+        }
+        if enabled != 0 {
+            settings.c_lflag |= libc::ECHO;
+        } else {
+            if !ECHO_HIDDEN.load(Ordering::SeqCst) {
+                unsafe { (*SAVED_TERMIOS.0.get()).write(settings) };
+                ECHO_HIDDEN.store(true, Ordering::SeqCst);
+                set_echo_signal_handlers(true);
+                ECHO_PANIC_HOOK.call_once(|| {
+                    let previous = std::panic::take_hook();
+                    std::panic::set_hook(Box::new(move |info| {
+                        restore_terminal_echo();
+                        previous(info);
+                    }));
+                });
+            }
+            settings.c_lflag &= !libc::ECHO;
+        }
+        (unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &settings) } == 0) as c_int
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = enabled;
+        0
+    }
+}
+
 /// Print string to stderr without newline
 #[no_mangle]
 pub extern "C" fn tea_eprint_string(value: *const TeaString) {
@@ -0,0 +1,379 @@
+//! Format specifiers for template string interpolations (`${value:spec}`), shared by the
+//! typechecker, the native runtime and the browser interpreter.
+//!
+//! The grammar follows Python's format mini-language:
+//! `[[fill]align][sign][#][0][width][grouping][.precision][type]`. Widths are measured
+//! in terminal columns so that padded columns line up with wide characters.
+
+use unicode_width::UnicodeWidthStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormatAlign {
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormatKind {
+    /// No type character: the value's usual string form.
+    Display,
+    /// `d`
+    Decimal,
+    /// `f`
+    Fixed,
+    /// `e`
+    Exponent,
+    /// `%`
+    Percent,
+    /// `x`
+    Hex,
+    /// `X`
+    UpperHex,
+    /// `b`
+    Binary,
+    /// `o`
+    Octal,
+}
+
+/// The kind of value a specifier is applied to, used to validate the specifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormatClass {
+    Int,
+    Float,
+    String,
+    Other,
+}
+
+/// A value handed to [`FormatSpec::apply`]. Values other than numbers are passed as
+/// their display text.
+#[derive(Clone, Copy, Debug)]
+pub enum FormatArg<'a> {
+    Int(i64),
+    Float(f64),
+    String(&'a str),
+    Other(&'a str),
+}
+
+impl FormatArg<'_> {
+    fn class(&self) -> FormatClass {
+        match self {
+            FormatArg::Int(_) => FormatClass::Int,
+            FormatArg::Float(_) => FormatClass::Float,
+            FormatArg::String(_) => FormatClass::String,
+            FormatArg::Other(_) => FormatClass::Other,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormatSpec {
+    pub fill: char,
+    pub align: Option<FormatAlign>,
+    pub plus_sign: bool,
+    pub alternate: bool,
+    pub zero_pad: bool,
+    pub width: Option<usize>,
+    pub grouping: Option<char>,
+    pub precision: Option<usize>,
+    pub kind: FormatKind,
+}
+
+impl Default for FormatSpec {
+    fn default() -> Self {
+        Self {
+            fill: ' ',
+            align: None,
+            plus_sign: false,
+            alternate: false,
+            zero_pad: false,
+            width: None,
+            grouping: None,
+            precision: None,
+            kind: FormatKind::Display,
+        }
+    }
+}
+
+fn parse_align(ch: char) -> Option<FormatAlign> {
+    match ch {
+        '<' => Some(FormatAlign::Left),
+        '>' => Some(FormatAlign::Right),
+        '^' => Some(FormatAlign::Center),
+        _ => None,
+    }
+}
+
+fn parse_number(chars: &[char], pos: &mut usize) -> Option<usize> {
+    let start = *pos;
+    while chars.get(*pos).is_some_and(|ch| ch.is_ascii_digit()) {
+        *pos += 1;
+    }
+    if *pos == start {
+        return None;
+    }
+    chars[start..*pos].iter().collect::<String>().parse().ok()
+}
+
+impl FormatSpec {
+    /// Parse the text after the `:` of an interpolation.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let chars: Vec<char> = spec.chars().collect();
+        let mut result = FormatSpec::default();
+        let mut pos = 0;
+
+        if let Some(align) = chars.get(1).and_then(|ch| parse_align(*ch)) {
+            result.fill = chars[0];
+            result.align = Some(align);
+            pos = 2;
+        } else if let Some(align) = chars.first().and_then(|ch| parse_align(*ch)) {
+            result.align = Some(align);
+            pos = 1;
+        }
+
+        if let Some(sign @ ('+' | '-')) = chars.get(pos) {
+            result.plus_sign = *sign == '+';
+            pos += 1;
+        }
+        if chars.get(pos) == Some(&'#') {
+            result.alternate = true;
+            pos += 1;
+        }
+        if chars.get(pos) == Some(&'0') {
+            result.zero_pad = true;
+            pos += 1;
+        }
+        result.width = parse_number(&chars, &mut pos);
+        if let Some(separator @ (',' | '_')) = chars.get(pos) {
+            result.grouping = Some(*separator);
+            pos += 1;
+        }
+        if chars.get(pos) == Some(&'.') {
+            pos += 1;
+            result.precision = Some(
+                parse_number(&chars, &mut pos)
+                    .ok_or_else(|| format!("format precision in '{spec}' must be a number"))?,
+            );
+        }
+        if let Some(kind) = chars.get(pos) {
+            result.kind = match kind {
+                'd' => FormatKind::Decimal,
+                'f' => FormatKind::Fixed,
+                'e' => FormatKind::Exponent,
+                '%' => FormatKind::Percent,
+                'x' => FormatKind::Hex,
+                'X' => FormatKind::UpperHex,
+                'b' => FormatKind::Binary,
+                'o' => FormatKind::Octal,
+                other => return Err(format!("unknown format type '{other}' in '{spec}'")),
+            };
+            pos += 1;
+        }
+        if pos != chars.len() {
+            return Err(format!("invalid format specifier '{spec}'"));
+        }
+        if result.grouping == Some(',') && result.is_radix() {
+            return Err(format!(
+                "',' grouping cannot be combined with a hex, binary or octal type in '{spec}'"
+            ));
+        }
+        if result.alternate && !result.is_radix() {
+            return Err(format!(
+                "'#' requires a hex, binary or octal type in '{spec}'"
+            ));
+        }
+        Ok(result)
+    }
+
+    fn is_radix(&self) -> bool {
+        matches!(
+            self.kind,
+            FormatKind::Hex | FormatKind::UpperHex | FormatKind::Binary | FormatKind::Octal
+        )
+    }
+
+    fn is_numeric_only(&self) -> bool {
+        self.plus_sign || self.zero_pad || self.grouping.is_some()
+    }
+
+    /// Check that the specifier can format a value of the given class, returning a
+    /// description of the problem when it cannot.
+    pub fn check(&self, class: FormatClass) -> Result<(), String> {
+        let numeric = matches!(class, FormatClass::Int | FormatClass::Float);
+        match self.kind {
+            FormatKind::Decimal
+            | FormatKind::Hex
+            | FormatKind::UpperHex
+            | FormatKind::Binary
+            | FormatKind::Octal => {
+                if class != FormatClass::Int {
+                    return Err(format!(
+                        "format type '{}' requires an Int value",
+                        self.kind_char()
+                    ));
+                }
+                if self.precision.is_some() {
+                    return Err("precision is not allowed for integer format types".to_string());
+                }
+            }
+            FormatKind::Fixed | FormatKind::Exponent | FormatKind::Percent => {
+                if !numeric {
+                    return Err(format!(
+                        "format type '{}' requires an Int or Float value",
+                        self.kind_char()
+                    ));
+                }
+            }
+            FormatKind::Display => {
+                if self.is_numeric_only() && !numeric {
+                    return Err(
+                        "sign, '0' and grouping options require an Int or Float value".to_string(),
+                    );
+                }
+                if self.precision.is_some()
+                    && !matches!(class, FormatClass::Float | FormatClass::String)
+                {
+                    return Err("precision requires a Float or String value".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn kind_char(&self) -> char {
+        match self.kind {
+            FormatKind::Display => 's',
+            FormatKind::Decimal => 'd',
+            FormatKind::Fixed => 'f',
+            FormatKind::Exponent => 'e',
+            FormatKind::Percent => '%',
+            FormatKind::Hex => 'x',
+            FormatKind::UpperHex => 'X',
+            FormatKind::Binary => 'b',
+            FormatKind::Octal => 'o',
+        }
+    }
+
+    /// Format a value according to the specifier.
+    pub fn apply(&self, value: FormatArg<'_>) -> Result<String, String> {
+        self.check(value.class())?;
+        let (negative, body) = match value {
+            FormatArg::String(text) | FormatArg::Other(text) => {
+                let text = match self.precision {
+                    Some(limit) => text.chars().take(limit).collect(),
+                    None => text.to_string(),
+                };
+                return Ok(self.pad("", text, FormatAlign::Left));
+            }
+            FormatArg::Int(number) => match self.kind {
+                FormatKind::Fixed | FormatKind::Exponent | FormatKind::Percent => {
+                    (number < 0, self.float_body(number.unsigned_abs() as f64))
+                }
+                _ => (number < 0, self.int_body(number.unsigned_abs())),
+            },
+            FormatArg::Float(number) => {
+                let negative = number.is_sign_negative() && !number.is_nan();
+                (negative, self.float_body(number.abs()))
+            }
+        };
+        let sign = if negative {
+            "-"
+        } else if self.plus_sign {
+            "+"
+        } else {
+            ""
+        };
+        let prefix = match (self.alternate, self.kind) {
+            (true, FormatKind::Hex | FormatKind::UpperHex) => "0x",
+            (true, FormatKind::Binary) => "0b",
+            (true, FormatKind::Octal) => "0o",
+            _ => "",
+        };
+        Ok(self.pad(&format!("{sign}{prefix}"), body, FormatAlign::Right))
+    }
+
+    fn int_body(&self, magnitude: u64) -> String {
+        let (digits, group_size) = match self.kind {
+            FormatKind::Hex => (format!("{magnitude:x}"), 4),
+            FormatKind::UpperHex => (format!("{magnitude:X}"), 4),
+            FormatKind::Binary => (format!("{magnitude:b}"), 4),
+            FormatKind::Octal => (format!("{magnitude:o}"), 4),
+            _ => (magnitude.to_string(), 3),
+        };
+        match self.grouping {
+            Some(separator) => group_digits(&digits, separator, group_size),
+            None => digits,
+        }
+    }
+
+    fn float_body(&self, magnitude: f64) -> String {
+        if !magnitude.is_finite() {
+            let text = if magnitude.is_nan() { "NaN" } else { "inf" };
+            return text.to_string();
+        }
+        let precision = self.precision.unwrap_or(6);
+        let (text, suffix) = match self.kind {
+            FormatKind::Fixed => (format!("{magnitude:.precision$}"), ""),
+            FormatKind::Percent => (format!("{:.precision$}", magnitude * 100.0), "%"),
+            FormatKind::Exponent => (exponent_text(magnitude, precision), ""),
+            _ => match self.precision {
+                Some(precision) => (format!("{magnitude:.precision$}"), ""),
+                None => (format!("{magnitude}"), ""),
+            },
+        };
+        let text = match self.grouping {
+            Some(separator) => {
+                let (whole, rest) = text.split_at(
+                    text.find(|ch: char| !ch.is_ascii_digit())
+                        .unwrap_or(text.len()),
+                );
+                format!("{}{rest}", group_digits(whole, separator, 3))
+            }
+            None => text,
+        };
+        format!("{text}{suffix}")
+    }
+
+    fn pad(&self, prefix: &str, body: String, default_align: FormatAlign) -> String {
+        let width = self.width.unwrap_or(0);
+        let used = prefix.width() + body.width();
+        if used >= width {
+            return format!("{prefix}{body}");
+        }
+        let missing = width - used;
+        if self.zero_pad && self.align.is_none() {
+            return format!("{prefix}{}{body}", "0".repeat(missing));
+        }
+        let fill = |count: usize| self.fill.to_string().repeat(count);
+        match self.align.unwrap_or(default_align) {
+            FormatAlign::Left => format!("{prefix}{body}{}", fill(missing)),
+            FormatAlign::Right => format!("{}{prefix}{body}", fill(missing)),
+            FormatAlign::Center => {
+                let before = missing / 2;
+                format!("{}{prefix}{body}{}", fill(before), fill(missing - before))
+            }
+        }
+    }
+}
+
+fn group_digits(digits: &str, separator: char, group_size: usize) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / group_size);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(group_size) {
+            grouped.push(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Scientific notation with a signed, two-digit exponent (`1.50e+03`).
+fn exponent_text(magnitude: f64, precision: usize) -> String {
+    let text = format!("{magnitude:.precision$e}");
+    let (mantissa, exponent) = text.split_once('e').unwrap_or((&text, "0"));
+    let (sign, digits) = match exponent.strip_prefix('-') {
+        Some(digits) => ('-', digits),
+        None => ('+', exponent),
+    };
+    format!("{mantissa}e{sign}{digits:0>2}")
+}
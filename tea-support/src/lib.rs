@@ -1,6 +1,14 @@
 use std::borrow::Cow;
 use std::fmt;
 
+mod format;
+mod slice;
+
+pub use format::{FormatAlign, FormatArg, FormatClass, FormatKind, FormatSpec};
+pub use slice::{
+    decode_slice_bound, resolve_index, slice_positions, slice_range, SLICE_OPEN_BOUND,
+};
+
 fn escape_single_quotes(input: &str) -> Cow<'_, str> {
     if input.contains('\'') {
         Cow::Owned(input.replace('\'', "\\'"))
@@ -59,3 +67,15 @@ pub fn http_error(operation: &str, target: &str, error: impl fmt::Display) -> St
 pub fn time_error(operation: &str, target: &str, error: impl fmt::Display) -> String {
     format_operation_error("std.time", operation, Some(target), error)
 }
+
+pub fn bytes_error(operation: &str, error: impl fmt::Display) -> String {
+    format_operation_error("std.bytes", operation, None, error)
+}
+
+pub fn task_error(operation: &str, target: &str, error: impl fmt::Display) -> String {
+    format_operation_error("std.task", operation, Some(target), error)
+}
+
+pub fn iter_error(operation: &str, target: &str, error: impl fmt::Display) -> String {
+    format_operation_error("std.iter", operation, Some(target), error)
+}
@@ -0,0 +1,99 @@
+//! Index and slice resolution shared by the native runtime and the browser interpreter.
+//!
+//! Indexes may be negative and count back from the end (`xs[-1]` is the last element).
+//! Slices clamp out-of-range bounds to the collection instead of failing, and a start
+//! that lies past the end yields an empty slice, matching the behaviour of Python.
+
+/// Sentinel used across the runtime ABI for an omitted slice bound (`xs[..n]`, `xs[n..]`).
+pub const SLICE_OPEN_BOUND: i64 = i64::MIN;
+
+/// Resolve an index against a collection of `len` elements, counting negative indexes
+/// from the end. Returns `None` when the index falls outside the collection.
+pub fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let len = len as i64;
+    let resolved = if index < 0 { index + len } else { index };
+    if (0..len).contains(&resolved) {
+        Some(resolved as usize)
+    } else {
+        None
+    }
+}
+
+/// Resolve a contiguous slice (step 1) into a `start..end` range of positions with
+/// `start <= end <= len`.
+pub fn slice_range(
+    len: usize,
+    start: Option<i64>,
+    end: Option<i64>,
+    inclusive: bool,
+) -> (usize, usize) {
+    let len_i = len as i64;
+    let start = start.map_or(0, |value| clamp_bound(value, len_i, 0, len_i));
+    let end = end.map_or(len_i, |value| {
+        let end = clamp_bound(value, len_i, 0, len_i);
+        if inclusive && value.saturating_add(len_i) >= 0 {
+            (end + 1).min(len_i)
+        } else {
+            end
+        }
+    });
+    let end = end.max(start);
+    (start as usize, end as usize)
+}
+
+/// Resolve a slice with an arbitrary step into the positions it selects, in order.
+///
+/// Positive steps walk forward from `start` (default: the first element) towards `end`
+/// (default: past the last element); negative steps walk backward from `start`
+/// (default: the last element) towards `end` (default: before the first element).
+pub fn slice_positions(
+    len: usize,
+    start: Option<i64>,
+    end: Option<i64>,
+    inclusive: bool,
+    step: i64,
+) -> Result<Vec<usize>, String> {
+    if step == 0 {
+        return Err("slice step cannot be zero".to_string());
+    }
+    if step > 0 {
+        let (start, end) = slice_range(len, start, end, inclusive);
+        return Ok((start..end).step_by(step as usize).collect());
+    }
+
+    let len_i = len as i64;
+    let start = start.map_or(len_i - 1, |value| clamp_bound(value, len_i, -1, len_i - 1));
+    let end = match end {
+        None => -1,
+        Some(value) => {
+            let end = clamp_bound(value, len_i, -1, len_i - 1);
+            if inclusive && end >= 0 && value.saturating_add(len_i) >= 0 {
+                end - 1
+            } else {
+                end
+            }
+        }
+    };
+
+    let mut positions = Vec::new();
+    let mut position = start;
+    while position > end {
+        positions.push(position as usize);
+        position += step;
+    }
+    Ok(positions)
+}
+
+/// Decode a bound passed across the runtime ABI, mapping the open-bound sentinel to `None`.
+pub fn decode_slice_bound(value: i64) -> Option<i64> {
+    (value != SLICE_OPEN_BOUND).then_some(value)
+}
+
+fn clamp_bound(value: i64, len: i64, min: i64, max: i64) -> i64 {
+    let resolved = if value < 0 {
+        value.saturating_add(len)
+    } else {
+        value
+    };
+    resolved.clamp(min, max)
+}
@@ -15,6 +15,9 @@ struct RunTeaRequest {
     entry_path: String,
     files: HashMap<String, String>,
     fuel: Option<usize>,
+    /// Two 32-bit words from `crypto.getRandomValues`, seeding the shared random
+    /// generator; wasm has no other entropy source.
+    random_seed: Option<[u32; 2]>,
 }
 
 #[derive(Debug, Serialize)]
@@ -85,6 +88,9 @@ pub fn run_tea(value: JsValue) -> Result<JsValue, JsValue> {
                 &compilation,
                 EvalOptions {
                     fuel: request.fuel.unwrap_or(EvalOptions::default().fuel),
+                    random_seed: request
+                        .random_seed
+                        .map(|[high, low]| ((u64::from(high) << 32) | u64::from(low)) as i64),
                 },
             );
             RunTeaResponse {
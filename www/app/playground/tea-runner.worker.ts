@@ -2,6 +2,7 @@ type RunTeaRequest = {
   entryPath: string;
   files: Record<string, string>;
   fuel?: number;
+  randomSeed?: [number, number];
 };
 
 type RunTeaResponse = {
@@ -91,7 +92,12 @@ self.onmessage = async (event: MessageEvent<WorkerRequest>) => {
 
   try {
     const module = await loadModule();
-    const payload = module.run_tea(event.data.payload);
+    // wasm has no entropy source of its own, so seed `std.random` per run.
+    const [high, low] = crypto.getRandomValues(new Uint32Array(2));
+    const payload = module.run_tea({
+      ...event.data.payload,
+      randomSeed: [high, low],
+    });
     self.postMessage({
       type: "result",
       id: event.data.id,